@@ -0,0 +1,192 @@
+//! Compiler for V8-Rust JavaScript engine
+//!
+//! This module turns an AST into bytecode the VM can execute: the
+//! bytecode generator emits instructions, which are then lowered to the
+//! VM's instruction set and constant pool.
+
+use v8_ast::Node;
+use v8_bytecode::generator::BytecodeGenerator;
+use v8_bytecode::instructions::{Capture as OpCapture, Constant, FunctionInfo as OpFunctionInfo, Instruction as Op};
+use v8_vm::bytecode::{Capture, FunctionInfo};
+use v8_vm::collection;
+use v8_vm::value::Value;
+use v8_vm::{Bytecode, Instruction};
+
+/// AST to VM bytecode compiler
+#[derive(Debug)]
+pub struct Compiler {
+    globals: Vec<String>, // Global names, indexed by their VM slot
+}
+
+impl Default for Compiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Compiler {
+    /// Create a new compiler
+    ///
+    /// The first global slots are the VM's built-in constructors, such as
+    /// `Set` and `Map`, which the executor creates on first use.
+    pub fn new() -> Self {
+        let globals = collection::CONSTRUCTORS.iter().map(|(name, _)| name.to_string()).collect();
+        Compiler { globals }
+    }
+
+    /// Compile an AST into VM bytecode and its constant pool
+    ///
+    /// The generator panics on constructs it does not support yet; those
+    /// can still be evaluated with the `Interpreter`.
+    pub fn compile(&mut self, ast: &Node) -> Result<(Bytecode, Vec<Value>), String> {
+        let mut generator = BytecodeGenerator::new();
+        generator.generate(ast);
+
+        let constants = generator
+            .constants
+            .values
+            .iter()
+            .map(lower_constant)
+            .collect::<Result<Vec<_>, _>>()?;
+        let bytecode = self.lower_code(generator.instructions, generator.functions);
+        Ok((bytecode, constants))
+    }
+
+    /// VM global slot assigned to `name`, if it has been referenced
+    pub fn global_slot(&self, name: &str) -> Option<usize> {
+        self.globals.iter().position(|global| global == name)
+    }
+
+    fn global_slot_or_insert(&mut self, name: String) -> usize {
+        match self.global_slot(&name) {
+            Some(slot) => slot,
+            None => {
+                self.globals.push(name);
+                self.globals.len() - 1
+            }
+        }
+    }
+
+    fn lower_code(&mut self, instructions: Vec<Op>, functions: Vec<OpFunctionInfo>) -> Bytecode {
+        let instructions = instructions.into_iter().map(|op| self.lower_instruction(op)).collect();
+        let functions = functions.into_iter().map(|function| self.lower_function(function)).collect();
+        Bytecode::with_functions(instructions, functions)
+    }
+
+    fn lower_function(&mut self, function: OpFunctionInfo) -> FunctionInfo {
+        let captures = function
+            .captures
+            .into_iter()
+            .map(|(name, capture)| match capture {
+                OpCapture::Local(slot) => (name, Capture::Local(slot)),
+                OpCapture::Closure => (name, Capture::Closure),
+            })
+            .collect();
+        FunctionInfo {
+            name: function.name,
+            bytecode: self.lower_code(function.instructions, function.functions),
+            arg_count: function.param_count,
+            local_count: function.local_count,
+            captures,
+            is_async: function.is_async,
+        }
+    }
+
+    fn lower_instruction(&mut self, op: Op) -> Instruction {
+        match op {
+            Op::PushConst(idx) => Instruction::PushConst(idx),
+            Op::Pop => Instruction::Pop,
+            Op::Dup => Instruction::Dup,
+            Op::Add => Instruction::Add,
+            Op::Sub => Instruction::Sub,
+            Op::Mul => Instruction::Mul,
+            Op::Div => Instruction::Div,
+            Op::Mod => Instruction::Mod,
+            Op::Inc => Instruction::Inc,
+            Op::Dec => Instruction::Dec,
+            Op::And => Instruction::And,
+            Op::Or => Instruction::Or,
+            Op::Not => Instruction::Not,
+            Op::Xor => Instruction::Xor,
+            Op::Eq => Instruction::Eq,
+            Op::Ne => Instruction::Ne,
+            Op::Lt => Instruction::Lt,
+            Op::Gt => Instruction::Gt,
+            Op::Le => Instruction::Le,
+            Op::Ge => Instruction::Ge,
+            Op::StrictEq => Instruction::StrictEq,
+            Op::StrictNe => Instruction::StrictNe,
+            Op::LoadGlobal(name) => Instruction::LoadGlobal(self.global_slot_or_insert(name)),
+            Op::StoreGlobal(name) => Instruction::StoreGlobal(self.global_slot_or_insert(name)),
+            Op::LoadLocal(idx) => Instruction::LoadLocal(idx),
+            Op::StoreLocal(idx) => Instruction::StoreLocal(idx),
+            Op::NewCell(idx) => Instruction::NewCell(idx),
+            Op::LoadCell(idx) => Instruction::LoadCell(idx),
+            Op::StoreCell(idx) => Instruction::StoreCell(idx),
+            Op::Jump(target) => Instruction::Jump(target),
+            Op::JumpIfTrue(target) => Instruction::JumpIfTrue(target),
+            Op::JumpIfFalse(target) => Instruction::JumpIfFalse(target),
+            Op::JumpIfNotNullish(target) => Instruction::JumpIfNotNullish(target),
+            Op::JumpIfNullish(target) => Instruction::JumpIfNullish(target),
+            Op::Call(argc) => Instruction::Call(argc),
+            Op::CallWithSpread => Instruction::CallWithSpread,
+            Op::TailCall(argc) => Instruction::TailCall(argc),
+            Op::CallMethod(argc) => Instruction::CallMethod(argc),
+            Op::LoadArg(idx) => Instruction::LoadArg(idx),
+            Op::LoadRestArgs(start) => Instruction::LoadRestArgs(start),
+            Op::LoadClosureVar(name) => Instruction::LoadClosureVar(name),
+            Op::StoreClosureVar(name) => Instruction::StoreClosureVar(name),
+            Op::LoadThisFunction => Instruction::LoadThisFunction,
+            Op::LoadThis => Instruction::LoadThis,
+            Op::CreateFunction(idx) => Instruction::CreateFunction(idx),
+            Op::Return => Instruction::Return,
+            Op::NewObject => Instruction::NewObject,
+            Op::NewArray(size) => Instruction::NewArray(size),
+            Op::SetProperty => Instruction::SetProperty,
+            Op::GetProperty => Instruction::GetProperty,
+            Op::DeleteProperty => Instruction::DeleteProperty,
+            Op::RestElements(start) => Instruction::RestElements(start),
+            Op::RestProperties(count) => Instruction::RestProperties(count),
+            Op::GetIterator => Instruction::GetIterator,
+            Op::IteratorNext => Instruction::IteratorNext,
+            Op::CloseIterator => Instruction::CloseIterator,
+            Op::EnumerateKeys => Instruction::EnumerateKeys,
+            Op::TypeOf => Instruction::TypeOf,
+            Op::InstanceOf => Instruction::InstanceOf,
+            Op::In => Instruction::In,
+            Op::Delete => Instruction::Delete,
+            Op::New(argc) => Instruction::New(argc),
+            Op::NewClass => Instruction::NewClass,
+            Op::CreateClass(method_count) => Instruction::CreateClass(method_count),
+            Op::GetPrototype => Instruction::GetPrototype,
+            Op::SetPrototype => Instruction::SetPrototype,
+            Op::Await => Instruction::Await,
+            Op::Yield => Instruction::Yield,
+            Op::Throw => Instruction::Throw,
+            Op::Try(catch) => Instruction::Try(catch),
+            Op::EndTry => Instruction::EndTry,
+            Op::Catch => Instruction::Catch,
+            Op::Finally => Instruction::Finally,
+            Op::Spread => Instruction::Spread,
+            Op::Destructure => Instruction::Destructure,
+            Op::OptionalChain => Instruction::OptionalChain,
+            Op::NullishCoalesce => Instruction::NullishCoalesce,
+            Op::PushNull => Instruction::PushNull,
+            Op::PushUndefined => Instruction::PushUndefined,
+            Op::PushTrue => Instruction::PushTrue,
+            Op::PushFalse => Instruction::PushFalse,
+            Op::PushSymbol(idx) => Instruction::PushSymbol(idx),
+            Op::PushBigInt(idx) => Instruction::PushBigInt(idx),
+        }
+    }
+}
+
+fn lower_constant(constant: &Constant) -> Result<Value, String> {
+    match constant {
+        Constant::Number(n) => Ok(Value::Number(*n)),
+        Constant::String(s) => Ok(Value::String(s.clone())),
+        Constant::Boolean(b) => Ok(Value::Boolean(*b)),
+        Constant::Symbol(_) => Err("Symbol values are not supported by the VM".to_string()),
+        Constant::BigInt(_) => Err("BigInt values are not supported by the VM".to_string()),
+    }
+}
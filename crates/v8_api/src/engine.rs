@@ -0,0 +1,171 @@
+//! Engine for V8-Rust JavaScript engine
+//!
+//! This module ties the parser and the interpreter together behind a
+//! single `eval` entry point. Microtasks queued by the script (promise
+//! reactions and `FinalizationRegistry` cleanups) run after it finishes,
+//! before `eval` returns. Globals
+//! persist from one `eval` to the next until `reset` is called, as a REPL
+//! expects.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use v8_ast::Node;
+use v8_parser::Parser;
+use v8_runtime::function::NativeFunction;
+use v8_runtime::{array, collections, global, iterator, number, object_constructor, promise, reflect, regexp, weak, wrapper, Context, Function, Object, Runtime, Value};
+use crate::error::EngineError;
+use crate::interpreter::Interpreter;
+
+/// JavaScript engine
+#[derive(Debug)]
+pub struct Engine {
+    runtime: Runtime,
+    interpreter: Interpreter,
+    context: Context, // Global scope shared by every `eval`
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine {
+    /// Call depth limit of a new engine
+    pub const DEFAULT_MAX_CALL_DEPTH: usize = 1000;
+
+    /// Native stack limit of a new engine, in bytes
+    ///
+    /// This leaves room within the 2 MiB stack of a thread spawned with the
+    /// default size. Each call level takes about 3 KiB when optimized (over
+    /// 12 KiB in a debug build), so on such a stack the depth limit is only
+    /// reached in optimized builds. Hosts running scripts on a larger stack
+    /// can raise it with `set_max_stack_size`.
+    pub const DEFAULT_MAX_STACK_SIZE: usize = 1536 * 1024;
+
+    /// Create a new engine
+    pub fn new() -> Self {
+        let runtime = Runtime::new();
+        let mut interpreter = Interpreter::with_microtask_queue(runtime.microtask_queue());
+        interpreter.set_finalization_registries(runtime.finalization_registries());
+        interpreter.set_max_call_depth(Some(Self::DEFAULT_MAX_CALL_DEPTH));
+        interpreter.set_max_stack_size(Some(Self::DEFAULT_MAX_STACK_SIZE));
+        let context = Context::new(Self::create_global_object());
+        Self { runtime, interpreter, context }
+    }
+
+    /// Discard every global declared by earlier scripts
+    pub fn reset(&mut self) {
+        self.context = Context::new(Self::create_global_object());
+    }
+
+    /// Limit how deeply scripts may nest function calls
+    /// (`DEFAULT_MAX_CALL_DEPTH` by default)
+    ///
+    /// Recursion beyond the limit throws a catchable `RangeError` instead of
+    /// overflowing the host's stack. `None` removes the limit, which is only
+    /// safe for trusted scripts.
+    pub fn set_max_call_depth(&mut self, depth: Option<usize>) {
+        self.interpreter.set_max_call_depth(depth);
+    }
+
+    /// Limit how many bytes of native stack nested function calls may take
+    /// (`DEFAULT_MAX_STACK_SIZE` by default)
+    ///
+    /// Like the depth limit, this throws a catchable `RangeError`. It must
+    /// stay below the stack of the thread calling `eval`; `None` removes the
+    /// limit.
+    pub fn set_max_stack_size(&mut self, size: Option<usize>) {
+        self.interpreter.set_max_stack_size(size);
+    }
+
+    /// Limit how many objects, arrays and functions scripts may keep alive
+    /// at once (no limit by default)
+    ///
+    /// An allocation beyond the limit throws a catchable `RangeError`.
+    /// Unreachable values are freed right away and do not count.
+    pub fn set_max_heap_objects(&mut self, max: Option<usize>) {
+        self.interpreter.set_max_heap_objects(max);
+    }
+
+    /// Parse and evaluate a script, returning its completion value
+    pub fn eval(&mut self, source: &str) -> Result<Value, EngineError> {
+        let ast = Self::parse(source)?;
+        let ctx = &mut self.context;
+        let result = self.interpreter.eval(&ast, ctx).map_err(|value| EngineError::Runtime { value });
+        // Promise reactions run even if the script threw, after the cleanup
+        // callbacks for what the script left unreachable are queued
+        self.runtime.collect_garbage();
+        let drained = self.runtime.run_microtasks(&mut self.interpreter.host(&mut self.context));
+        let value = result?;
+        drained.map_err(|value| EngineError::Runtime { value })?;
+        Ok(value)
+    }
+
+    /// Parse a script and run semantic analysis on it, without running it
+    pub fn check(&self, source: &str) -> Result<(), EngineError> {
+        let ast = Self::parse(source)?;
+        v8_semantic::analyze(&ast)?;
+        Ok(())
+    }
+
+    /// Parse a script, reporting a lexer error as such
+    fn parse(source: &str) -> Result<Node, EngineError> {
+        let mut parser = Parser::new(source);
+        let result = parser.parse_with_recovery();
+        if let Some(error) = parser.lexer_error() {
+            return Err(error.clone().into());
+        }
+        match result {
+            (Some(ast), errors) if errors.is_empty() => Ok(ast),
+            (_, errors) => Err(EngineError::Parse(errors)),
+        }
+    }
+
+    /// Create a global object with the standard global properties
+    fn create_global_object() -> Rc<RefCell<Object>> {
+        let mut global = Object::new();
+        global.set_property("undefined".to_string(), Value::Undefined);
+        global.set_property("NaN".to_string(), Value::Number(f64::NAN));
+        global.set_property("Infinity".to_string(), Value::Number(f64::INFINITY));
+
+        let functions: [(&str, NativeFunction); 10] = [
+            ("isNaN", global::is_nan),
+            ("isFinite", global::is_finite),
+            ("parseInt", global::parse_int),
+            ("parseFloat", global::parse_float),
+            ("encodeURI", global::encode_uri),
+            ("encodeURIComponent", global::encode_uri_component),
+            ("decodeURI", global::decode_uri),
+            ("decodeURIComponent", global::decode_uri_component),
+            ("escape", global::escape),
+            ("unescape", global::unescape),
+        ];
+        for (name, function) in functions {
+            let function = Function::native(name, function);
+            global.set_property(name.to_string(), Value::Function(Rc::new(RefCell::new(function))));
+        }
+        global.set_property(
+            "Object".to_string(),
+            Value::Object(object_constructor::create_object_constructor()),
+        );
+        global.set_property("Array".to_string(), Value::Function(array::create_array_constructor()));
+        global.set_property("Number".to_string(), Value::Function(number::create_number_constructor()));
+        global.set_property("String".to_string(), Value::Function(wrapper::create_string_constructor()));
+        global.set_property("Boolean".to_string(), Value::Function(wrapper::create_boolean_constructor()));
+        global.set_property("Symbol".to_string(), Value::Object(iterator::create_symbol_object()));
+        global.set_property("Reflect".to_string(), Value::Object(reflect::create_reflect_object()));
+        global.set_property("Set".to_string(), Value::Function(collections::create_set_constructor()));
+        global.set_property("Map".to_string(), Value::Function(collections::create_map_constructor()));
+        let regexp = Function::native("RegExp", regexp::regexp_constructor);
+        global.set_property("RegExp".to_string(), Value::Function(Rc::new(RefCell::new(regexp))));
+        global.set_property("Promise".to_string(), Value::Function(promise::create_promise_constructor()));
+        global.set_property("WeakRef".to_string(), Value::Function(weak::create_weak_ref_constructor()));
+        global.set_property(
+            "FinalizationRegistry".to_string(),
+            Value::Function(weak::create_finalization_registry_constructor()),
+        );
+
+        Rc::new(RefCell::new(global))
+    }
+}
@@ -0,0 +1,50 @@
+//! Errors reported by the V8-Rust JavaScript engine
+//!
+//! `EngineError` says at which stage a script failed. A script that
+//! throws keeps the thrown JavaScript value, so callers can inspect an
+//! error object rather than its message.
+
+use thiserror::Error;
+use v8_lexer::LexerError;
+use v8_parser::ParseError;
+use v8_runtime::Value;
+use v8_semantic::SemanticError;
+
+/// Error from lexing, parsing, checking or running a script
+#[derive(Error, Debug, Clone)]
+pub enum EngineError {
+    #[error("SyntaxError: {0}")]
+    Lex(LexerError),
+
+    #[error("SyntaxError: {}", first_parse_error(.0))]
+    Parse(Vec<ParseError>),
+
+    #[error("SyntaxError: {0}")]
+    Semantic(SemanticError),
+
+    /// The script threw `value` and nothing caught it
+    #[error("{value}")]
+    Runtime { value: Value },
+
+    #[error("Execution interrupted")]
+    Interrupted,
+}
+
+fn first_parse_error(errors: &[ParseError]) -> String {
+    match errors.first() {
+        Some(error) => error.to_string(),
+        None => "Invalid program".to_string(),
+    }
+}
+
+impl From<LexerError> for EngineError {
+    fn from(error: LexerError) -> Self {
+        EngineError::Lex(error)
+    }
+}
+
+impl From<SemanticError> for EngineError {
+    fn from(error: SemanticError) -> Self {
+        EngineError::Semantic(error)
+    }
+}
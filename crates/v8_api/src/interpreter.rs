@@ -0,0 +1,1139 @@
+//! Tree-walking interpreter for V8-Rust JavaScript engine
+//!
+//! This module evaluates the AST directly, without generating bytecode.
+//! It is slower than the VM but supports constructs the bytecode generator
+//! does not handle yet, and serves as a reference to cross-check the VM.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::{Rc, Weak};
+use v8_ast::Node;
+use v8_runtime::function::{FunctionType, Host, UserFunction};
+use v8_runtime::iterator::{builtin_iterator_method, iterator_key};
+use v8_runtime::elements::Elements;
+use v8_runtime::object::{array_index, PropertyDescriptor};
+use v8_runtime::reflect;
+use v8_runtime::regexp::{regexp_property, string_method, RegExp};
+use v8_runtime::runtime::{Microtask, MicrotaskQueue};
+use v8_runtime::value::PreferredType;
+use v8_runtime::weak::FinalizationRegistries;
+use v8_runtime::{Context, Environment, Function, Object, Value};
+
+/// How a statement finished executing
+enum Completion {
+    Normal(Value),
+    Return(Value),
+    Break,
+    Continue,
+}
+
+/// Tree-walking interpreter
+#[derive(Debug, Default)]
+pub struct Interpreter {
+    microtasks: MicrotaskQueue, // Jobs queued by promises, run by the owner
+    finalization_registries: FinalizationRegistries, // Registries created by scripts, swept by the owner
+    depth: usize,               // User functions being called right now
+    stack_base: usize,          // Native stack address of the outermost call
+    max_call_depth: Option<usize>,
+    max_stack_size: Option<usize>,
+    max_heap_objects: Option<usize>,
+    allocations: Vec<Allocation>, // What the script allocated, while a heap limit is set
+    next_sweep: usize,            // Length of `allocations` that triggers dropping the dead ones
+}
+
+/// An object, array or function the script allocated
+#[derive(Debug)]
+enum Allocation {
+    Object(Weak<RefCell<Object>>),
+    Array(Weak<RefCell<Elements<Value>>>),
+    Function(Weak<RefCell<Function>>),
+}
+
+impl Allocation {
+    fn is_live(&self) -> bool {
+        match self {
+            Allocation::Object(object) => object.strong_count() > 0,
+            Allocation::Array(array) => array.strong_count() > 0,
+            Allocation::Function(function) => function.strong_count() > 0,
+        }
+    }
+}
+
+/// The interpreter seen by host methods, bound to the context being run
+pub struct InterpreterHost<'a> {
+    interpreter: &'a mut Interpreter,
+    ctx: &'a mut Context,
+}
+
+impl Host for InterpreterHost<'_> {
+    fn call(&mut self, function: &Value, this: Value, args: &[Value]) -> Result<Value, Value> {
+        self.interpreter.call(function, this, args, self.ctx)
+    }
+
+    fn construct(&mut self, function: &Value, args: &[Value]) -> Result<Value, Value> {
+        self.interpreter.construct(function, args, self.ctx)
+    }
+
+    fn enqueue_microtask(&mut self, task: Microtask) {
+        self.interpreter.microtasks.borrow_mut().push_back(task);
+    }
+
+    fn track_finalization_registry(&mut self, registry: &Rc<RefCell<Object>>) {
+        self.interpreter.finalization_registries.borrow_mut().push(Rc::downgrade(registry));
+    }
+}
+
+impl Interpreter {
+    /// Create a new interpreter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an interpreter that queues microtasks on `queue`
+    ///
+    /// Pass `Runtime::microtask_queue()` so `Runtime::run_microtasks` can
+    /// run the jobs the script queued.
+    pub fn with_microtask_queue(queue: MicrotaskQueue) -> Self {
+        Self { microtasks: queue, ..Self::default() }
+    }
+
+    /// Record the `FinalizationRegistry` objects scripts create in `registries`
+    ///
+    /// Pass `Runtime::finalization_registries()` so `Runtime::collect_garbage`
+    /// can queue their cleanup callbacks.
+    pub fn set_finalization_registries(&mut self, registries: FinalizationRegistries) {
+        self.finalization_registries = registries;
+    }
+
+    /// Limit how many user function calls may be nested (no limit by default)
+    ///
+    /// A call beyond the limit throws `RangeError: Maximum call stack size
+    /// exceeded`. Every level also nests native calls, so the limit must fit
+    /// the stack of the thread running the interpreter.
+    pub fn set_max_call_depth(&mut self, depth: Option<usize>) {
+        self.max_call_depth = depth;
+    }
+
+    /// Limit how many bytes of native stack nested user function calls may
+    /// take (no limit by default)
+    ///
+    /// A call beyond the limit throws the same `RangeError` as the depth
+    /// limit. The size is measured from the outermost call, so it must stay
+    /// below the stack of the thread running the interpreter.
+    pub fn set_max_stack_size(&mut self, size: Option<usize>) {
+        self.max_stack_size = size;
+    }
+
+    /// Limit how many objects, arrays and functions the script may keep
+    /// alive at once (no limit by default)
+    ///
+    /// An allocation beyond the limit throws `RangeError: JavaScript heap
+    /// out of memory`. Only what the interpreter allocates for literals,
+    /// closures and `new` is counted, not the results of native methods.
+    pub fn set_max_heap_objects(&mut self, max: Option<usize>) {
+        self.max_heap_objects = max;
+        self.allocations.clear();
+        self.next_sweep = 0;
+    }
+
+    /// Count a new allocation against the heap limit, passing it through
+    ///
+    /// Allocations that are no longer reachable are dropped from the count
+    /// whenever the list doubles, so checking stays linear overall.
+    fn track(&mut self, value: Value) -> Result<Value, Value> {
+        let Some(max) = self.max_heap_objects else { return Ok(value) };
+        let allocation = match &value {
+            Value::Object(object) => Allocation::Object(Rc::downgrade(object)),
+            Value::Array(array) => Allocation::Array(Rc::downgrade(array)),
+            Value::Function(function) => Allocation::Function(Rc::downgrade(function)),
+            _ => return Ok(value),
+        };
+        self.allocations.push(allocation);
+        if self.allocations.len() > self.next_sweep.max(max) {
+            self.allocations.retain(Allocation::is_live);
+            self.next_sweep = self.allocations.len() * 2;
+            if self.allocations.len() > max {
+                return Err(format!("RangeError: JavaScript heap out of memory (limit of {} objects)", max).into());
+            }
+        }
+        Ok(value)
+    }
+
+    /// Host handle for running microtasks in `ctx`
+    pub fn host<'a>(&'a mut self, ctx: &'a mut Context) -> InterpreterHost<'a> {
+        InterpreterHost { interpreter: self, ctx }
+    }
+
+    /// Evaluate a program, statement or expression in the given context
+    ///
+    /// Returns the completion value: the value of the last expression
+    /// statement executed, or the value of an expression node. The error is
+    /// the value the script threw; built-in errors throw their message.
+    pub fn eval(&mut self, ast: &Node, ctx: &mut Context) -> Result<Value, Value> {
+        match self.execute(ast, ctx)? {
+            Completion::Normal(value) | Completion::Return(value) => Ok(value),
+            Completion::Break => Err("SyntaxError: Illegal break statement".to_string().into()),
+            Completion::Continue => Err("SyntaxError: Illegal continue statement".to_string().into()),
+        }
+    }
+
+    fn execute(&mut self, node: &Node, ctx: &mut Context) -> Result<Completion, Value> {
+        match node {
+            Node::Program(program) => self.execute_program(program, ctx),
+            Node::BlockStatement(block) => {
+                ctx.push_scope();
+                let result = self.execute_statements(&block.body, ctx);
+                ctx.pop_scope();
+                result
+            }
+            Node::ExpressionStatement(stmt) => Ok(Completion::Normal(self.evaluate(&stmt.expression, ctx)?)),
+            Node::VariableDeclaration(decl) => self.execute_variable_declaration(decl, ctx),
+            // Function declarations are hoisted by execute_statements
+            Node::FunctionDeclaration(_) => Ok(Completion::Normal(Value::Undefined)),
+            Node::IfStatement(stmt) => {
+                if self.evaluate(&stmt.test, ctx)?.to_boolean() {
+                    self.execute(&stmt.consequent, ctx)
+                } else if let Some(alternate) = &stmt.alternate {
+                    self.execute(alternate, ctx)
+                } else {
+                    Ok(Completion::Normal(Value::Undefined))
+                }
+            }
+            Node::WhileStatement(stmt) => self.execute_while(stmt, ctx),
+            Node::DoWhileStatement(stmt) => self.execute_do_while(stmt, ctx),
+            Node::ForStatement(stmt) => {
+                // The loop head gets its own scope so `let i` does not leak
+                ctx.push_scope();
+                let result = self.execute_for(stmt, ctx);
+                ctx.pop_scope();
+                result
+            }
+            Node::ForOfStatement(stmt) => self.execute_for_of(stmt, ctx),
+            Node::ForInStatement(stmt) => self.execute_for_in(stmt, ctx),
+            Node::ReturnStatement(stmt) => {
+                let value = match &stmt.argument {
+                    Some(argument) => self.evaluate(argument, ctx)?,
+                    None => Value::Undefined,
+                };
+                Ok(Completion::Return(value))
+            }
+            Node::BreakStatement(_) => Ok(Completion::Break),
+            Node::ContinueStatement(_) => Ok(Completion::Continue),
+            Node::ThrowStatement(stmt) => {
+                Err(self.evaluate(&stmt.argument, ctx)?)
+            }
+            Node::DebuggerStatement(_) => Ok(Completion::Normal(Value::Undefined)),
+            _ => Ok(Completion::Normal(self.evaluate(node, ctx)?)),
+        }
+    }
+
+    fn execute_program(&mut self, program: &v8_ast::Program, ctx: &mut Context) -> Result<Completion, Value> {
+        // Top-level bindings live in the context's script scope, above the
+        // global object, so later scripts in the same context see them
+        let outer = std::mem::replace(&mut ctx.environment, ctx.script_environment.clone());
+        // A later script may redeclare a top-level `let`/`const`, as in a REPL
+        for name in lexical_names(&program.body) {
+            ctx.environment.borrow_mut().remove_binding(name);
+        }
+        let result = self.execute_statements(&program.body, ctx);
+        ctx.environment = outer;
+        result
+    }
+
+    fn execute_variable_declaration(&mut self, decl: &v8_ast::VariableDeclaration, ctx: &mut Context) -> Result<Completion, Value> {
+        for declarator in &decl.declarations {
+            let value = match &declarator.init {
+                Some(init) => self.evaluate(init, ctx)?,
+                None => Value::Undefined,
+            };
+            let name = binding_name(&declarator.id)?;
+            if decl.kind == "var" {
+                let scope = ctx.var_environment();
+                let declared = scope.borrow().has_own_binding(name);
+                if !declared {
+                    scope.borrow_mut().declare(name.to_string(), value, true)?;
+                } else if declarator.init.is_some() {
+                    // `var` may be redeclared; only assign when there is an initializer
+                    ctx.set_variable(name.to_string(), value)?;
+                }
+            } else {
+                ctx.declare_variable(name.to_string(), value, decl.kind != "const")?;
+            }
+        }
+        Ok(Completion::Normal(Value::Undefined))
+    }
+
+    fn execute_while(&mut self, stmt: &v8_ast::WhileStatement, ctx: &mut Context) -> Result<Completion, Value> {
+        let mut last = Value::Undefined;
+        while self.evaluate(&stmt.test, ctx)?.to_boolean() {
+            match self.execute(&stmt.body, ctx)? {
+                Completion::Normal(value) => last = value,
+                Completion::Return(value) => return Ok(Completion::Return(value)),
+                Completion::Break => break,
+                Completion::Continue => {}
+            }
+        }
+        Ok(Completion::Normal(last))
+    }
+
+    fn execute_do_while(&mut self, stmt: &v8_ast::DoWhileStatement, ctx: &mut Context) -> Result<Completion, Value> {
+        let mut last = Value::Undefined;
+        loop {
+            match self.execute(&stmt.body, ctx)? {
+                Completion::Normal(value) => last = value,
+                Completion::Return(value) => return Ok(Completion::Return(value)),
+                Completion::Break => break,
+                Completion::Continue => {}
+            }
+            if !self.evaluate(&stmt.test, ctx)?.to_boolean() {
+                break;
+            }
+        }
+        Ok(Completion::Normal(last))
+    }
+
+    fn execute_for(&mut self, stmt: &v8_ast::ForStatement, ctx: &mut Context) -> Result<Completion, Value> {
+        if let Some(init) = &stmt.init {
+            self.execute(init, ctx)?;
+        }
+        let mut last = Value::Undefined;
+        loop {
+            if let Some(test) = &stmt.test {
+                if !self.evaluate(test, ctx)?.to_boolean() {
+                    break;
+                }
+            }
+            match self.execute(&stmt.body, ctx)? {
+                Completion::Normal(value) => last = value,
+                Completion::Return(value) => return Ok(Completion::Return(value)),
+                Completion::Break => break,
+                Completion::Continue => {}
+            }
+            if let Some(update) = &stmt.update {
+                self.evaluate(update, ctx)?;
+            }
+        }
+        Ok(Completion::Normal(last))
+    }
+
+    fn execute_for_of(&mut self, stmt: &v8_ast::ForOfStatement, ctx: &mut Context) -> Result<Completion, Value> {
+        let iterable = self.evaluate(&stmt.right, ctx)?;
+        let iterator = self.get_iterator(&iterable, ctx)?;
+        let mut last = Value::Undefined;
+        while let Some(value) = self.iterator_step(&iterator, ctx)? {
+            // Each iteration gets a fresh binding for `let` and `const`
+            ctx.push_scope();
+            let result = self
+                .bind_for_of(&stmt.left, value, ctx)
+                .and_then(|_| self.execute(&stmt.body, ctx));
+            ctx.pop_scope();
+            match result {
+                Ok(Completion::Normal(value)) => last = value,
+                Ok(Completion::Continue) => {}
+                Ok(Completion::Break) => {
+                    self.close_iterator(&iterator, ctx)?;
+                    break;
+                }
+                Ok(Completion::Return(value)) => {
+                    self.close_iterator(&iterator, ctx)?;
+                    return Ok(Completion::Return(value));
+                }
+                Err(err) => {
+                    // The original error wins over one thrown while closing
+                    let _ = self.close_iterator(&iterator, ctx);
+                    return Err(err);
+                }
+            }
+        }
+        Ok(Completion::Normal(last))
+    }
+
+    fn execute_for_in(&mut self, stmt: &v8_ast::ForInStatement, ctx: &mut Context) -> Result<Completion, Value> {
+        // The keys are listed up front; null and undefined have none
+        let keys = match self.evaluate(&stmt.right, ctx)? {
+            Value::Object(object) => object.borrow().for_in_keys(),
+            Value::Array(elements) => elements.borrow().indices().map(|index| index.to_string()).collect(),
+            Value::String(s) => (0..s.chars().count()).map(|index| index.to_string()).collect(),
+            _ => Vec::new(),
+        };
+        let mut last = Value::Undefined;
+        for key in keys {
+            ctx.push_scope();
+            let result = self
+                .bind_for_of(&stmt.left, Value::String(key), ctx)
+                .and_then(|_| self.execute(&stmt.body, ctx));
+            ctx.pop_scope();
+            match result? {
+                Completion::Normal(value) => last = value,
+                Completion::Continue => {}
+                Completion::Break => break,
+                Completion::Return(value) => return Ok(Completion::Return(value)),
+            }
+        }
+        Ok(Completion::Normal(last))
+    }
+
+    fn bind_for_of(&mut self, left: &Node, value: Value, ctx: &mut Context) -> Result<(), Value> {
+        match left {
+            Node::VariableDeclaration(decl) => match decl.declarations.as_slice() {
+                [declarator] if declarator.init.is_none() => {
+                    let name = binding_name(&declarator.id)?;
+                    ctx.declare_variable(name.to_string(), value, decl.kind != "const").map_err(Value::from)
+                }
+                _ => Err("SyntaxError: Invalid left-hand side in for-of loop".to_string().into()),
+            },
+            target => self.assign(target, value, ctx),
+        }
+    }
+
+    /// Execute a statement list, hoisting its function declarations first
+    fn execute_statements(&mut self, statements: &[Node], ctx: &mut Context) -> Result<Completion, Value> {
+        self.hoist_functions(statements, ctx)?;
+
+        let mut last = Value::Undefined;
+        for statement in statements {
+            match self.execute(statement, ctx)? {
+                Completion::Normal(value) => {
+                    // Declarations do not change the completion value
+                    if !matches!(statement, Node::VariableDeclaration(_) | Node::FunctionDeclaration(_)) {
+                        last = value;
+                    }
+                }
+                other => return Ok(other),
+            }
+        }
+        Ok(Completion::Normal(last))
+    }
+
+    /// Declare the function declarations among `statements` up front
+    fn hoist_functions(&mut self, statements: &[Node], ctx: &mut Context) -> Result<(), Value> {
+        for statement in statements {
+            if let Node::FunctionDeclaration(decl) = statement {
+                let name = match &decl.id {
+                    Some(id) => binding_name(id)?,
+                    None => continue,
+                };
+                let function = make_function(name, &decl.params, &decl.body, ctx)?;
+                let function = self.track(function)?;
+                let scope = ctx.var_environment();
+                if scope.borrow().has_own_binding(name) {
+                    ctx.set_variable(name.to_string(), function)?;
+                } else {
+                    scope.borrow_mut().declare(name.to_string(), function, true)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self, node: &Node, ctx: &mut Context) -> Result<Value, Value> {
+        match node {
+            Node::Number(n) => Ok(Value::Number(*n)),
+            Node::NumericLiteral(lit) => Ok(Value::Number(lit.value)),
+            Node::ParenthesizedExpression(expr) => self.evaluate(&expr.expression, ctx),
+            Node::SequenceExpression(expr) => {
+                let mut value = Value::Undefined;
+                for expression in &expr.expressions {
+                    value = self.evaluate(expression, ctx)?;
+                }
+                Ok(value)
+            }
+            Node::String(s) => Ok(Value::String(s.clone())),
+            Node::TemplateLiteral(template) => self.evaluate_template(template, ctx),
+            Node::Boolean(b) => Ok(Value::Boolean(*b)),
+            Node::Null => Ok(Value::Null),
+            Node::Undefined => Ok(Value::Undefined),
+            // Each evaluation of a literal creates a new RegExp with its own lastIndex
+            Node::RegExp(regexp) => Ok(Value::new_regexp(RegExp::new(&regexp.pattern, &regexp.flags)?)),
+            Node::This(_) => Ok(ctx.get_this()),
+            Node::Identifier(name) => ctx
+                .get_variable(name)
+                .ok_or_else(|| format!("ReferenceError: {} is not defined", name).into()),
+            Node::ArrayLiteral(literal) => self.evaluate_array(literal, ctx),
+            Node::ObjectLiteral(literal) => self.evaluate_object(literal, ctx),
+            Node::BinaryExpression(expr) => self.evaluate_binary(expr, ctx),
+            Node::LogicalExpression(expr) => self.evaluate_logical(expr, ctx),
+            Node::ConditionalExpression(expr) => {
+                if self.evaluate(&expr.test, ctx)?.to_boolean() {
+                    self.evaluate(&expr.consequent, ctx)
+                } else {
+                    self.evaluate(&expr.alternate, ctx)
+                }
+            }
+            Node::UnaryExpression(expr) => self.evaluate_unary(expr, ctx),
+            Node::AssignmentExpression(expr) => self.evaluate_assignment(expr, ctx),
+            Node::UpdateExpression(expr) => self.evaluate_update(expr, ctx),
+            Node::MemberExpression(_) | Node::CallExpression(_) => {
+                Ok(self.evaluate_chain(node, ctx)?.unwrap_or(Value::Undefined))
+            }
+            Node::NewExpression(expr) => self.evaluate_new(expr, ctx),
+            Node::FunctionExpression(expr) => {
+                let name = match &expr.id {
+                    Some(id) => binding_name(id)?,
+                    None => "",
+                };
+                make_function(name, &expr.params, &expr.body, ctx).and_then(|function| self.track(function))
+            }
+            Node::ArrowFunctionExpression(expr) => {
+                make_function("", &expr.params, &expr.body, ctx).and_then(|function| self.track(function))
+            }
+            other => Err(unsupported(other).into()),
+        }
+    }
+
+    fn evaluate_template(&mut self, template: &v8_ast::TemplateLiteral, ctx: &mut Context) -> Result<Value, Value> {
+        let mut text = String::new();
+        for (index, quasi) in template.quasis.iter().enumerate() {
+            text.push_str(&quasi.value);
+            if let Some(expression) = template.expressions.get(index) {
+                let value = self.evaluate(expression, ctx)?;
+                text.push_str(&value.to_primitive(PreferredType::String, &mut self.host(ctx))?.to_string());
+            }
+        }
+        Ok(Value::String(text))
+    }
+
+    fn evaluate_array(&mut self, literal: &v8_ast::ArrayLiteral, ctx: &mut Context) -> Result<Value, Value> {
+        let mut elements = Vec::new();
+        for element in &literal.elements {
+            match element {
+                Some(Node::SpreadElement(spread)) => {
+                    let value = self.evaluate(&spread.argument, ctx)?;
+                    elements.extend(self.iterate(&value, ctx)?);
+                }
+                Some(element) => elements.push(self.evaluate(element, ctx)?),
+                None => elements.push(Value::Undefined),
+            }
+        }
+        self.track(Value::new_array(elements))
+    }
+
+    fn evaluate_object(&mut self, literal: &v8_ast::ObjectLiteral, ctx: &mut Context) -> Result<Value, Value> {
+        let mut object = Object::new();
+        for property in &literal.properties {
+            match property {
+                Node::Property(property) => {
+                    let key = if property.computed {
+                        self.evaluate(&property.key, ctx)?.to_property_key()
+                    } else {
+                        property_key(&property.key)?
+                    };
+                    let value = self.evaluate(&property.value, ctx)?;
+                    match property.kind.as_str() {
+                        "get" => object.define_accessor(key, Some(value), None),
+                        "set" => object.define_accessor(key, None, Some(value)),
+                        _ => object.define_property(key, PropertyDescriptor::data_descriptor(value, true, true, true)),
+                    };
+                }
+                Node::SpreadElement(spread) => {
+                    // Later properties override earlier ones
+                    match self.evaluate(&spread.argument, ctx)? {
+                        Value::Object(source) => {
+                            for (key, value) in source.borrow().own_enumerable_properties() {
+                                object.set_property(key, value);
+                            }
+                        }
+                        value @ (Value::Array(_) | Value::String(_)) => {
+                            for (index, element) in indexed_elements(&value) {
+                                object.set_property(index.to_string(), element);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                other => return Err(unsupported(other).into()),
+            }
+        }
+        self.track(Value::Object(Rc::new(RefCell::new(object))))
+    }
+
+    fn evaluate_binary(&mut self, expr: &v8_ast::BinaryExpression, ctx: &mut Context) -> Result<Value, Value> {
+        let left = self.evaluate(&expr.left, ctx)?;
+        let right = self.evaluate(&expr.right, ctx)?;
+        if expr.operator == "instanceof" {
+            return Ok(Value::Boolean(left.instance_of(&right, &mut self.host(ctx))?));
+        }
+        self.binary(&expr.operator, left, right, ctx)
+    }
+
+    fn evaluate_logical(&mut self, expr: &v8_ast::LogicalExpression, ctx: &mut Context) -> Result<Value, Value> {
+        let left = self.evaluate(&expr.left, ctx)?;
+        let short_circuit = match expr.operator.as_str() {
+            "&&" => !left.to_boolean(),
+            "||" => left.to_boolean(),
+            "??" => !matches!(left, Value::Null | Value::Undefined),
+            operator => return Err(format!("SyntaxError: Unknown logical operator '{}'", operator).into()),
+        };
+        if short_circuit {
+            Ok(left)
+        } else {
+            self.evaluate(&expr.right, ctx)
+        }
+    }
+
+    fn evaluate_unary(&mut self, expr: &v8_ast::UnaryExpression, ctx: &mut Context) -> Result<Value, Value> {
+        // typeof on an undeclared identifier is not an error
+        if let ("typeof", Node::Identifier(name)) = (expr.operator.as_str(), expr.argument.as_ref()) {
+            let value = ctx.get_variable(name).unwrap_or(Value::Undefined);
+            return Ok(Value::String(value.typeof_()));
+        }
+        if expr.operator == "delete" {
+            return self.delete(&expr.argument, ctx);
+        }
+        let argument = self.evaluate(&expr.argument, ctx)?;
+        match expr.operator.as_str() {
+            "!" => Ok(argument.logical_not()),
+            "-" => Ok(Value::Number(-argument.to_number())),
+            "+" => Ok(Value::Number(argument.to_number())),
+            "~" => Ok(Value::Number(!to_int32(&argument) as f64)),
+            "typeof" => Ok(Value::String(argument.typeof_())),
+            "void" => Ok(Value::Undefined),
+            operator => Err(format!("SyntaxError: Unsupported unary operator '{}'", operator).into()),
+        }
+    }
+
+    fn evaluate_assignment(&mut self, expr: &v8_ast::AssignmentExpression, ctx: &mut Context) -> Result<Value, Value> {
+        let value = match expr.operator.as_str() {
+            "=" => self.evaluate(&expr.right, ctx)?,
+            operator => {
+                let current = self.evaluate(&expr.left, ctx)?;
+                let right = self.evaluate(&expr.right, ctx)?;
+                self.binary(operator.trim_end_matches('='), current, right, ctx)?
+            }
+        };
+        self.assign(&expr.left, value.clone(), ctx)?;
+        Ok(value)
+    }
+
+    fn evaluate_update(&mut self, expr: &v8_ast::UpdateExpression, ctx: &mut Context) -> Result<Value, Value> {
+        let old = Value::Number(self.evaluate(&expr.argument, ctx)?.to_number());
+        let delta = if expr.operator == "++" { 1.0 } else { -1.0 };
+        let new = Value::Number(old.to_number() + delta);
+        self.assign(&expr.argument, new.clone(), ctx)?;
+        Ok(if expr.prefix { new } else { old })
+    }
+
+    fn evaluate_new(&mut self, expr: &v8_ast::NewExpression, ctx: &mut Context) -> Result<Value, Value> {
+        let callee = self.evaluate(&expr.callee, ctx)?;
+        let args = self.evaluate_arguments(&expr.arguments, ctx)?;
+        self.construct(&callee, &args, ctx)
+    }
+
+    /// Apply a binary operator, first converting object operands to primitives
+    ///
+    /// `+` and `==` convert with the default hint (`==` only when comparing
+    /// an object with a primitive); arithmetic, bitwise and relational
+    /// operators prefer numbers.
+    fn binary(&mut self, operator: &str, left: Value, right: Value, ctx: &mut Context) -> Result<Value, Value> {
+        let hint = match operator {
+            "===" | "!==" | "in" => None,
+            "==" | "!=" if left.is_primitive() == right.is_primitive() => None,
+            "==" | "!=" if matches!(left, Value::Null | Value::Undefined) || matches!(right, Value::Null | Value::Undefined) => None,
+            "+" | "==" | "!=" => Some(PreferredType::Default),
+            _ => Some(PreferredType::Number),
+        };
+        let (left, right) = match hint {
+            Some(hint) => {
+                let mut host = self.host(ctx);
+                (left.to_primitive(hint, &mut host)?, right.to_primitive(hint, &mut host)?)
+            }
+            None => (left, right),
+        };
+        binary_operation(operator, &left, &right)
+    }
+
+    /// Call a function value with the given `this` and arguments
+    pub fn call(&mut self, callee: &Value, this: Value, args: &[Value], ctx: &mut Context) -> Result<Value, Value> {
+        let function = match callee {
+            Value::Function(function) => function.borrow().clone(),
+            other => return Err(format!("TypeError: {} is not a function", other).into()),
+        };
+        let user = match function.function_type {
+            FunctionType::Native(_) | FunctionType::NativeMethod(_) => return function.call(this, args).map_err(Value::from),
+            FunctionType::HostMethod(method) => return method(&mut self.host(ctx), this, args),
+            FunctionType::User(user) => user,
+            FunctionType::Bound(bound) => {
+                // The bound `this` wins over whatever the caller supplied
+                let mut bound_args = bound.args;
+                bound_args.extend_from_slice(args);
+                return self.call(&Value::Function(bound.target), bound.this, &bound_args, ctx);
+            }
+        };
+
+        self.call_user(&user, this, args, ctx)
+    }
+
+    /// Run the body of a script function with `this` and `args` bound
+    fn call_user(&mut self, user: &UserFunction, this: Value, args: &[Value], ctx: &mut Context) -> Result<Value, Value> {
+        let here = stack_address();
+        if self.depth == 0 {
+            self.stack_base = here;
+        }
+        if self.max_call_depth.is_some_and(|max| self.depth >= max)
+            || self.max_stack_size.is_some_and(|max| self.stack_base.abs_diff(here) > max)
+        {
+            return Err("RangeError: Maximum call stack size exceeded".to_string().into());
+        }
+
+        // The body runs in a fresh scope whose outer scope is the closure's
+        let outer = user.scope.clone().unwrap_or_else(|| ctx.global_environment.clone());
+        let saved_environment = std::mem::replace(
+            &mut ctx.environment,
+            Rc::new(RefCell::new(Environment::new_declarative(Some(outer)))),
+        );
+        let saved_this = std::mem::replace(&mut ctx.this_value, this);
+
+        self.depth += 1;
+        let mut result = Ok(Value::Undefined);
+        for (index, param) in user.params.iter().enumerate() {
+            let value = args.get(index).cloned().unwrap_or(Value::Undefined);
+            if let Err(err) = ctx.declare_variable(param.clone(), value, true) {
+                result = Err(err.into());
+                break;
+            }
+        }
+        if result.is_ok() {
+            result = match user.body.as_ref() {
+                Node::BlockStatement(block) => match self.execute_statements(&block.body, ctx) {
+                    Ok(Completion::Return(value)) => Ok(value),
+                    Ok(_) => Ok(Value::Undefined),
+                    Err(err) => Err(err),
+                },
+                // Arrow function with an expression body
+                body => self.evaluate(body, ctx),
+            };
+        }
+
+        self.depth -= 1;
+        ctx.environment = saved_environment;
+        ctx.this_value = saved_this;
+        result
+    }
+
+    /// Call a function as a constructor (`new`)
+    ///
+    /// `this` is a fresh object inheriting from the function's prototype;
+    /// it is the result unless the function returns an object of its own.
+    fn construct(&mut self, callee: &Value, args: &[Value], ctx: &mut Context) -> Result<Value, Value> {
+        let prototype = match callee {
+            Value::Function(function) => function.borrow().prototype.clone(),
+            other => return Err(format!("TypeError: {} is not a constructor", other).into()),
+        };
+        let this = self.track(Value::Object(Rc::new(RefCell::new(Object::with_prototype(prototype)))))?;
+        match self.call(callee, this.clone(), args, ctx)? {
+            result @ (Value::Object(_) | Value::Array(_) | Value::Function(_) | Value::RegExp(_)) => Ok(result),
+            _ => Ok(this),
+        }
+    }
+
+    /// Get an iterator from `value` through its `Symbol.iterator` method
+    fn get_iterator(&mut self, value: &Value, ctx: &mut Context) -> Result<Value, Value> {
+        let method = match value {
+            Value::Null | Value::Undefined => Value::Undefined,
+            value => get_property(value, &iterator_key())?,
+        };
+        if !matches!(method, Value::Function(_)) {
+            return Err(format!("TypeError: {} is not iterable", value).into());
+        }
+        match self.call(&method, value.clone(), &[], ctx)? {
+            iterator @ Value::Object(_) => Ok(iterator),
+            other => Err(format!("TypeError: Result of the Symbol.iterator method is not an object: {}", other).into()),
+        }
+    }
+
+    /// Call the iterator's `next` method; `None` once it reports `done`
+    fn iterator_step(&mut self, iterator: &Value, ctx: &mut Context) -> Result<Option<Value>, Value> {
+        let next = get_property(iterator, "next")?;
+        let result = self.call(&next, iterator.clone(), &[], ctx)?;
+        if !matches!(result, Value::Object(_)) {
+            return Err(format!("TypeError: Iterator result {} is not an object", result).into());
+        }
+        if get_property(&result, "done")?.to_boolean() {
+            Ok(None)
+        } else {
+            Ok(Some(get_property(&result, "value")?))
+        }
+    }
+
+    /// Call the iterator's `return` method, if any, when a loop exits early
+    fn close_iterator(&mut self, iterator: &Value, ctx: &mut Context) -> Result<(), Value> {
+        let method = get_property(iterator, "return")?;
+        if matches!(method, Value::Function(_)) {
+            self.call(&method, iterator.clone(), &[], ctx)?;
+        }
+        Ok(())
+    }
+
+    /// Elements produced by spreading an iterable value
+    fn iterate(&mut self, value: &Value, ctx: &mut Context) -> Result<Vec<Value>, Value> {
+        let iterator = self.get_iterator(value, ctx)?;
+        let mut elements = Vec::new();
+        while let Some(element) = self.iterator_step(&iterator, ctx)? {
+            elements.push(element);
+        }
+        Ok(elements)
+    }
+
+    /// `Function.prototype.call`, `apply` and `bind`
+    fn call_function_method(
+        &mut self,
+        function: &Rc<RefCell<Function>>,
+        method: &str,
+        args: Vec<Value>,
+        ctx: &mut Context,
+    ) -> Result<Value, Value> {
+        let callee = Value::Function(function.clone());
+        let mut args = args.into_iter();
+        let this = args.next().unwrap_or(Value::Undefined);
+        match method {
+            "call" => self.call(&callee, this, &args.collect::<Vec<_>>(), ctx),
+            "apply" => {
+                let list = match args.next() {
+                    None | Some(Value::Undefined) | Some(Value::Null) => Vec::new(),
+                    Some(Value::Array(elements)) => elements.borrow().to_vec(),
+                    Some(_) => return Err("TypeError: CreateListFromArrayLike called on non-object".to_string().into()),
+                };
+                self.call(&callee, this, &list, ctx)
+            }
+            _ => {
+                let bound = Function::bound(function.clone(), this, args.collect());
+                self.track(Value::Function(Rc::new(RefCell::new(bound))))
+            }
+        }
+    }
+
+    /// Evaluate call arguments, expanding spread elements
+    fn evaluate_arguments(&mut self, arguments: &[Node], ctx: &mut Context) -> Result<Vec<Value>, Value> {
+        let mut args = Vec::with_capacity(arguments.len());
+        for argument in arguments {
+            match argument {
+                Node::SpreadElement(spread) => {
+                    let value = self.evaluate(&spread.argument, ctx)?;
+                    args.extend(self.iterate(&value, ctx)?);
+                }
+                argument => args.push(self.evaluate(argument, ctx)?),
+            }
+        }
+        Ok(args)
+    }
+
+    /// Evaluate a chain of member accesses and calls
+    ///
+    /// Returns `None` when a `?.` met null or undefined, which skips the
+    /// rest of the chain.
+    fn evaluate_chain(&mut self, node: &Node, ctx: &mut Context) -> Result<Option<Value>, Value> {
+        match node {
+            Node::MemberExpression(expr) => {
+                let Some(object) = self.evaluate_chain(&expr.object, ctx)? else {
+                    return Ok(None);
+                };
+                if expr.optional && matches!(object, Value::Null | Value::Undefined) {
+                    return Ok(None);
+                }
+                let key = self.member_key(expr, ctx)?;
+                self.get_property(&object, &key, ctx).map(Some)
+            }
+            Node::CallExpression(expr) => self.evaluate_call(expr, ctx),
+            _ => self.evaluate(node, ctx).map(Some),
+        }
+    }
+
+    /// Evaluate a call; `None` when an optional link short-circuits
+    fn evaluate_call(&mut self, expr: &v8_ast::CallExpression, ctx: &mut Context) -> Result<Option<Value>, Value> {
+        let (callee, this) = match expr.callee.as_ref() {
+            Node::MemberExpression(member) => {
+                let Some(object) = self.evaluate_chain(&member.object, ctx)? else {
+                    return Ok(None);
+                };
+                if member.optional && matches!(object, Value::Null | Value::Undefined) {
+                    return Ok(None);
+                }
+                let key = self.member_key(member, ctx)?;
+                if let Value::Function(function) = &object {
+                    if matches!(key.as_str(), "call" | "apply" | "bind") {
+                        let args = self.evaluate_arguments(&expr.arguments, ctx)?;
+                        return self.call_function_method(function, &key, args, ctx).map(Some);
+                    }
+                }
+                (self.get_property(&object, &key, ctx)?, object)
+            }
+            callee => match self.evaluate_chain(callee, ctx)? {
+                Some(callee) => (callee, Value::Undefined),
+                None => return Ok(None),
+            },
+        };
+        let args = self.evaluate_arguments(&expr.arguments, ctx)?;
+        self.call(&callee, this, &args, ctx).map(Some)
+    }
+
+    /// Read a property, running a getter if it has one
+    fn get_property(&mut self, object: &Value, key: &str, ctx: &mut Context) -> Result<Value, Value> {
+        match object {
+            Value::Object(target) if key != iterator_key() => {
+                let value = Object::get(target, key, object.clone(), &mut self.host(ctx))?;
+                Ok(value.unwrap_or(Value::Undefined))
+            }
+            _ => get_property(object, key),
+        }
+    }
+
+    fn member_key(&mut self, expr: &v8_ast::MemberExpression, ctx: &mut Context) -> Result<String, Value> {
+        if expr.computed {
+            Ok(self.evaluate(&expr.property, ctx)?.to_property_key())
+        } else {
+            property_key(&expr.property)
+        }
+    }
+
+    /// The `delete` operator: only own properties are removed
+    fn delete(&mut self, target: &Node, ctx: &mut Context) -> Result<Value, Value> {
+        match target {
+            Node::MemberExpression(expr) => {
+                let object = self.evaluate(&expr.object, ctx)?;
+                let key = self.member_key(expr, ctx)?;
+                match object {
+                    Value::Null | Value::Undefined => Err(format!(
+                        "TypeError: Cannot convert undefined or null to object (deleting '{}')",
+                        key
+                    ).into()),
+                    // Deletes rejected by non-configurable properties fail silently (sloppy mode)
+                    Value::Object(_) | Value::Array(_) | Value::Function(_) | Value::RegExp(_) => {
+                        reflect::delete_property(&[object, Value::String(key)]).map_err(Value::from)
+                    }
+                    _ => Ok(Value::Boolean(true)),
+                }
+            }
+            // Declared bindings cannot be deleted
+            Node::Identifier(_) => Ok(Value::Boolean(false)),
+            other => {
+                self.evaluate(other, ctx)?;
+                Ok(Value::Boolean(true))
+            }
+        }
+    }
+
+    fn assign(&mut self, target: &Node, value: Value, ctx: &mut Context) -> Result<(), Value> {
+        match target {
+            Node::Identifier(name) => ctx.set_variable(name.clone(), value).map_err(Value::from),
+            Node::MemberExpression(expr) => {
+                let object = self.evaluate(&expr.object, ctx)?;
+                let key = self.member_key(expr, ctx)?;
+                match object {
+                    Value::Object(target) => {
+                        // Writes rejected by frozen objects fail silently (sloppy mode)
+                        Object::set(&target, key, value, Value::Object(target.clone()), &mut self.host(ctx))?;
+                        Ok(())
+                    }
+                    Value::Array(elements) => {
+                        reflect::set_array_property(&elements, &key, value)?;
+                        Ok(())
+                    }
+                    Value::Function(function) => {
+                        let mut function = function.borrow_mut();
+                        match value {
+                            Value::Object(prototype) if key == "prototype" => function.prototype = prototype,
+                            value => {
+                                function.properties.borrow_mut().set_property(key, value);
+                            }
+                        }
+                        Ok(())
+                    }
+                    Value::RegExp(regexp) => {
+                        // lastIndex is the only writable property of a RegExp
+                        if key == "lastIndex" {
+                            let index = value.to_number();
+                            regexp.borrow_mut().last_index = if index > 0.0 { index as usize } else { 0 };
+                        }
+                        Ok(())
+                    }
+                    Value::Null | Value::Undefined => Err(format!(
+                        "TypeError: Cannot set properties of {} (setting '{}')",
+                        object, key
+                    ).into()),
+                    // Properties assigned to primitives are discarded
+                    _ => Ok(()),
+                }
+            }
+            other => Err(format!("SyntaxError: Invalid assignment target: {}", node_kind(other)).into()),
+        }
+    }
+}
+
+/// Create a closure over the current environment
+fn make_function(name: &str, params: &[Node], body: &Node, ctx: &Context) -> Result<Value, Value> {
+    let params = params
+        .iter()
+        .map(|param| binding_name(param).map(str::to_string))
+        .collect::<Result<Vec<_>, _>>()?;
+    let function = Function::user(name, params, Rc::new(body.clone()), Some(ctx.environment.clone()));
+    Ok(Value::Function(Rc::new(RefCell::new(function))))
+}
+
+/// Names bound by the `let`/`const` declarations directly in `statements`
+fn lexical_names(statements: &[Node]) -> Vec<&str> {
+    statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Node::VariableDeclaration(decl) if decl.kind != "var" => Some(&decl.declarations),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|declarator| binding_name(&declarator.id).ok())
+        .collect()
+}
+
+fn binding_name(node: &Node) -> Result<&str, Value> {
+    match node {
+        Node::Identifier(name) => Ok(name),
+        other => Err(unsupported(other).into()),
+    }
+}
+
+fn property_key(node: &Node) -> Result<String, Value> {
+    match node {
+        Node::Identifier(name) | Node::String(name) => Ok(name.clone()),
+        Node::Number(n) => Ok(Value::Number(*n).to_string()),
+        Node::NumericLiteral(lit) => Ok(Value::Number(lit.value).to_string()),
+        other => Err(unsupported(other).into()),
+    }
+}
+
+/// Address of the current native stack frame, to measure how much stack
+/// nested calls take
+#[inline(never)]
+fn stack_address() -> usize {
+    let marker = 0u8;
+    std::ptr::addr_of!(marker) as usize
+}
+
+/// Indexed elements of an array or string with their indices, as copied by
+/// object spread; array holes are skipped
+fn indexed_elements(value: &Value) -> Vec<(usize, Value)> {
+    match value {
+        Value::Array(values) => {
+            let values = values.borrow();
+            values.indices().map(|index| (index, values.get(index).cloned().unwrap_or_default())).collect()
+        }
+        Value::String(s) => s.chars().map(|c| Value::String(c.to_string())).enumerate().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn get_property(object: &Value, key: &str) -> Result<Value, Value> {
+    if key == iterator_key() {
+        if let Some(method) = builtin_iterator_method(object) {
+            return Ok(method);
+        }
+    }
+    let value = match object {
+        Value::Object(object) => object.borrow().get_property(key),
+        Value::Array(elements) => match key {
+            "length" => Some(Value::Number(elements.borrow().len() as f64)),
+            _ => array_index(key).and_then(|index| elements.borrow().get(index as usize).cloned()),
+        },
+        Value::String(s) => match key {
+            "length" => Some(Value::Number(s.encode_utf16().count() as f64)),
+            _ => key
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| s.chars().nth(index))
+                .map(|c| Value::String(c.to_string()))
+                .or_else(|| string_method(key)),
+        },
+        Value::RegExp(regexp) => regexp_property(regexp, key),
+        Value::Function(function) => match key {
+            "name" => Some(Value::String(function.borrow().get_name().to_string())),
+            "length" => Some(Value::Number(function.borrow().get_length() as f64)),
+            "prototype" => Some(Value::Object(function.borrow().prototype.clone())),
+            _ => function.borrow().properties.borrow().get_property(key),
+        },
+        Value::Null | Value::Undefined => {
+            return Err(format!(
+                "TypeError: Cannot read properties of {} (reading '{}')",
+                object, key
+            ).into())
+        }
+        _ => None,
+    };
+    Ok(value.unwrap_or(Value::Undefined))
+}
+
+fn binary_operation(operator: &str, left: &Value, right: &Value) -> Result<Value, Value> {
+    let value = match operator {
+        "+" => left.add(right),
+        "-" => Value::Number(left.to_number() - right.to_number()),
+        "*" => Value::Number(left.to_number() * right.to_number()),
+        "/" => Value::Number(left.to_number() / right.to_number()),
+        "%" => Value::Number(left.to_number() % right.to_number()),
+        "**" => Value::Number(left.to_number().powf(right.to_number())),
+        "==" => Value::Boolean(loose_equals(left, right)),
+        "!=" => Value::Boolean(!loose_equals(left, right)),
+        "===" => Value::Boolean(left.strict_equals(right)),
+        "!==" => Value::Boolean(!left.strict_equals(right)),
+        "<" => Value::Boolean(matches!(compare(left, right), Some(Ordering::Less))),
+        ">" => Value::Boolean(matches!(compare(left, right), Some(Ordering::Greater))),
+        "<=" => Value::Boolean(matches!(compare(left, right), Some(Ordering::Less | Ordering::Equal))),
+        ">=" => Value::Boolean(matches!(compare(left, right), Some(Ordering::Greater | Ordering::Equal))),
+        "in" => reflect::has(&[right.clone(), left.clone()]).map_err(|_| {
+            format!("TypeError: Cannot use 'in' operator to search for '{}' in {}", left.to_property_key(), right)
+        })?,
+        "&" => Value::Number((to_int32(left) & to_int32(right)) as f64),
+        "|" => Value::Number((to_int32(left) | to_int32(right)) as f64),
+        "^" => Value::Number((to_int32(left) ^ to_int32(right)) as f64),
+        "<<" => Value::Number(to_int32(left).wrapping_shl(to_uint32(right) & 31) as f64),
+        ">>" => Value::Number(to_int32(left).wrapping_shr(to_uint32(right) & 31) as f64),
+        ">>>" => Value::Number(to_uint32(left).wrapping_shr(to_uint32(right) & 31) as f64),
+        operator => return Err(format!("SyntaxError: Unsupported binary operator '{}'", operator).into()),
+    };
+    Ok(value)
+}
+
+/// Abstract equality (==) for primitives; objects compare by identity
+fn loose_equals(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Null | Value::Undefined, Value::Null | Value::Undefined) => true,
+        (Value::Null | Value::Undefined, _) | (_, Value::Null | Value::Undefined) => false,
+        (Value::Number(_) | Value::String(_) | Value::Boolean(_), Value::Number(_) | Value::String(_) | Value::Boolean(_)) => {
+            if let (Value::String(a), Value::String(b)) = (left, right) {
+                a == b
+            } else {
+                left.to_number() == right.to_number()
+            }
+        }
+        _ => left.strict_equals(right),
+    }
+}
+
+/// Abstract relational comparison: strings by UTF-16 code units, the rest via ToNumber
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::String(a), Value::String(b)) => Some(a.encode_utf16().cmp(b.encode_utf16())),
+        _ => left.to_number().partial_cmp(&right.to_number()),
+    }
+}
+
+fn to_uint32(value: &Value) -> u32 {
+    let n = value.to_number();
+    if !n.is_finite() {
+        return 0;
+    }
+    n.trunc().rem_euclid(4294967296.0) as u32
+}
+
+fn to_int32(value: &Value) -> i32 {
+    to_uint32(value) as i32
+}
+
+fn unsupported(node: &Node) -> String {
+    format!("SyntaxError: {} is not supported by the interpreter", node_kind(node))
+}
+
+/// Name of the node's variant, for error messages
+fn node_kind(node: &Node) -> String {
+    let debug = format!("{:?}", node);
+    debug
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
@@ -4,9 +4,11 @@
 //! all components of the JavaScript engine.
 
 pub mod engine;
+pub mod error;
 pub mod compiler;
 pub mod interpreter;
 
 pub use engine::Engine;
+pub use error::EngineError;
 pub use compiler::Compiler;
 pub use interpreter::Interpreter; 
\ No newline at end of file
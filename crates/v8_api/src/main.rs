@@ -0,0 +1,34 @@
+//! Command-line runner for V8-Rust JavaScript engine
+//!
+//! Evaluates the script given as the first argument, or standard input
+//! when no file is given, and prints its completion value.
+
+use std::io::Read;
+use std::process;
+use v8_api::Engine;
+
+fn main() {
+    let source = match std::env::args().nth(1) {
+        Some(path) => std::fs::read_to_string(&path).unwrap_or_else(|err| {
+            eprintln!("Cannot read {}: {}", path, err);
+            process::exit(1);
+        }),
+        None => {
+            let mut source = String::new();
+            if let Err(err) = std::io::stdin().read_to_string(&mut source) {
+                eprintln!("Cannot read standard input: {}", err);
+                process::exit(1);
+            }
+            source
+        }
+    };
+
+    let mut engine = Engine::new();
+    match engine.eval(&source) {
+        Ok(value) => println!("{}", value),
+        Err(err) => {
+            eprintln!("Uncaught {}", err);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use v8_api::{Engine, EngineError};
+use v8_lexer::LexerError;
+use v8_runtime::Value;
+use v8_semantic::SemanticError;
+
+fn property(value: &Value, key: &str) -> Option<Value> {
+    match value {
+        Value::Object(object) => object.borrow().get_property(key),
+        other => panic!("Expected object, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_lex_error() {
+    let err = Engine::new().eval("let s = 'abc").unwrap_err();
+    assert!(matches!(err, EngineError::Lex(LexerError::UnterminatedString)), "{:?}", err);
+    assert!(err.to_string().starts_with("SyntaxError"));
+    // The statements before the bad token do not hide it
+    let err = Engine::new().eval("let a = 1;\nf(a); 'abc").unwrap_err();
+    assert!(matches!(err, EngineError::Lex(LexerError::UnterminatedString)), "{:?}", err);
+}
+
+#[test]
+fn test_parse_error() {
+    match Engine::new().eval("let = ;").unwrap_err() {
+        EngineError::Parse(errors) => assert!(!errors.is_empty()),
+        other => panic!("Expected parse error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_semantic_error() {
+    let engine = Engine::new();
+    assert!(engine.check("let x = 1; x = 2;").is_ok());
+    match engine.check("const x = 1; x = 2;").unwrap_err() {
+        EngineError::Semantic(SemanticError::ConstReassignment { name, .. }) => assert_eq!(name, "x"),
+        other => panic!("Expected semantic error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_runtime_error_keeps_thrown_value() {
+    let mut engine = Engine::new();
+    match engine.eval("throw {code: 42};").unwrap_err() {
+        EngineError::Runtime { value } => assert_eq!(property(&value, "code"), Some(Value::Number(42.0))),
+        other => panic!("Expected runtime error, got {:?}", other),
+    }
+    match engine.eval("[...5]").unwrap_err() {
+        EngineError::Runtime { value } => {
+            assert_eq!(value, Value::String("TypeError: 5 is not iterable".to_string()))
+        }
+        other => panic!("Expected runtime error, got {:?}", other),
+    }
+
+    // So do values thrown through a built-in that called back into the script
+    match engine.eval("Array.from([1], function () { throw {code: 3}; })").unwrap_err() {
+        EngineError::Runtime { value } => assert_eq!(property(&value, "code"), Some(Value::Number(3.0))),
+        other => panic!("Expected runtime error, got {:?}", other),
+    }
+
+    // Promise rejections carry the thrown value too
+    let result = engine
+        .eval("let r = {}; new Promise(function () { throw {code: 7}; }).catch(function (e) { r.code = e.code; }); r")
+        .unwrap();
+    assert_eq!(property(&result, "code"), Some(Value::Number(7.0)));
+}
+
+#[test]
+fn test_interrupted_error() {
+    let err: Box<dyn std::error::Error> = Box::new(EngineError::Interrupted);
+    assert_eq!(err.to_string(), "Execution interrupted");
+}
+
+#[test]
+fn test_globals_persist_between_evals() {
+    let mut engine = Engine::new();
+    engine.eval("let x = 1; var y = 2; function add(a) { return a + x + y; }").unwrap();
+    assert_eq!(engine.eval("x = x + 10; add(100)").unwrap(), Value::Number(113.0));
+    // A top-level `let` may be declared again by a later script
+    assert_eq!(engine.eval("let x = 5; add(0)").unwrap(), Value::Number(7.0));
+    assert!(engine.eval("let z = 1; let z = 2;").is_err());
+}
+
+#[test]
+fn test_reset_clears_globals() {
+    let mut engine = Engine::new();
+    engine.eval("let x = 1; function f() { return 2; }").unwrap();
+    assert_eq!(engine.eval("x + f()").unwrap(), Value::Number(3.0));
+    engine.reset();
+    assert!(engine.eval("x").is_err());
+    assert!(engine.eval("f()").is_err());
+    assert_eq!(engine.eval("typeof Object").unwrap(), Value::String("object".to_string()));
+}
+
+#[test]
+fn test_global_this_and_top_level_bindings() {
+    let mut engine = Engine::new();
+    assert_eq!(engine.eval("globalThis === globalThis").unwrap(), Value::Boolean(true));
+    assert_eq!(engine.eval("globalThis.globalThis === globalThis").unwrap(), Value::Boolean(true));
+    assert_eq!(engine.eval("typeof globalThis.Object").unwrap(), Value::String("object".to_string()));
+
+    // Top-level `var` and function declarations are properties of the global object
+    engine.eval("var x = 1; function f() { return 2; }").unwrap();
+    assert_eq!(engine.eval("globalThis.x === 1").unwrap(), Value::Boolean(true));
+    assert_eq!(engine.eval("globalThis.f()").unwrap(), Value::Number(2.0));
+    assert_eq!(engine.eval("globalThis.x = 5; x").unwrap(), Value::Number(5.0));
+    // `let` and `const` are not, nor is a `var` inside a function
+    engine.eval("let y = 1; const z = 2; function g() { var w = 3; return w; } g();").unwrap();
+    assert_eq!(engine.eval("globalThis.y").unwrap(), Value::Undefined);
+    assert_eq!(engine.eval("'z' in globalThis").unwrap(), Value::Boolean(false));
+    assert_eq!(engine.eval("globalThis.w").unwrap(), Value::Undefined);
+    assert_eq!(engine.eval("y + z").unwrap(), Value::Number(3.0));
+}
@@ -0,0 +1,1119 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use v8_api::{Compiler, Engine, Interpreter};
+use v8_parser::Parser;
+use v8_runtime::{Context, Object, Value};
+use v8_vm::executor::Executor;
+use v8_vm::optimizer::Optimizer;
+use v8_vm::promise::PromiseState;
+use v8_vm::value::Value as VmValue;
+
+fn interpret(source: &str) -> Result<Value, String> {
+    let ast = Parser::new(source).parse().expect("parse error");
+    let mut ctx = Context::new(Rc::new(RefCell::new(Object::new())));
+    Interpreter::new().eval(&ast, &mut ctx).map_err(|value| value.to_string())
+}
+
+fn run_vm(source: &str) -> Value {
+    let ast = Parser::new(source).parse().expect("parse error");
+    let (bytecode, constants) = Compiler::new().compile(&ast).expect("compile error");
+    let mut executor = Executor::new();
+    executor.execute(&bytecode, &constants);
+    match executor.stack.values.last().cloned().expect("empty stack") {
+        VmValue::Number(n) => Value::Number(n),
+        VmValue::String(s) => Value::String(s),
+        VmValue::Boolean(b) => Value::Boolean(b),
+        VmValue::Null => Value::Null,
+        VmValue::Undefined => Value::Undefined,
+        other => panic!("Unexpected VM value {:?}", other),
+    }
+}
+
+#[test]
+fn test_interpreter_matches_vm() {
+    let sources = [
+        "1 + 2 * 3",
+        "(10 - 4) / 3",
+        "7 % 4",
+        "2 < 3",
+        "3 <= 2",
+        "'apple' < 'banana'",
+        "4 >= 4",
+        "1 === 1",
+        "1 !== 2",
+        "0 && 5",
+        "0 || 5",
+        "null ?? 7",
+        "'x' ?? 7",
+        "!0",
+        "1 < 2 && (3 > 4 || 10)",
+        "null",
+        "undefined",
+        "1 > 2 ? 'a' : 0 ? 'b' : 'c'",
+    ];
+    for source in sources {
+        assert_eq!(interpret(source).unwrap(), run_vm(source), "source: {}", source);
+    }
+}
+
+#[test]
+fn test_interpret_variables_and_loops() {
+    let source = "
+        let total = 0;
+        for (let i = 1; i <= 10; i++) {
+            if (i % 2 === 0) { continue; }
+            total = total + i;
+        }
+        let n = 0;
+        while (true) { n = n + 1; if (n === 5) break; }
+        total * 100 + n;
+    ";
+    assert_eq!(interpret(source).unwrap(), Value::Number(2505.0));
+}
+
+#[test]
+fn test_interpret_function_calls_and_closures() {
+    let source = "
+        function factorial(n) {
+            if (n <= 1) { return 1; }
+            return n * factorial(n - 1);
+        }
+        function counter() {
+            let count = 0;
+            return function () { count = count + 1; return count; };
+        }
+        const next = counter();
+        next();
+        next();
+        factorial(5) + next();
+    ";
+    assert_eq!(interpret(source).unwrap(), Value::Number(123.0));
+}
+
+#[test]
+fn test_interpret_block_scoping() {
+    assert_eq!(interpret("let x = 1; { let x = 2; } x;").unwrap(), Value::Number(1.0));
+    assert_eq!(interpret("{ let y = 1; } y;").unwrap_err(), "ReferenceError: y is not defined");
+}
+
+#[test]
+fn test_interpret_errors() {
+    assert_eq!(
+        interpret("const a = 1; a = 2;").unwrap_err(),
+        "TypeError: Assignment to constant variable."
+    );
+    assert_eq!(interpret("let f = 1; f();").unwrap_err(), "TypeError: 1 is not a function");
+}
+
+#[test]
+fn test_engine_eval() {
+    let mut engine = Engine::new();
+    assert_eq!(engine.eval("parseInt('42px') + 1").unwrap(), Value::Number(43.0));
+    assert!(engine.eval("let = ;").unwrap_err().to_string().starts_with("SyntaxError"));
+}
+
+#[test]
+fn test_engine_max_call_depth() {
+    let mut engine = Engine::new();
+    engine.set_max_call_depth(Some(50));
+    let err = engine.eval("function f(n) { return f(n + 1); } f(0)").unwrap_err().to_string();
+    assert!(err.contains("RangeError: Maximum call stack size exceeded"), "{}", err);
+    // The depth is back to zero once the error unwinds
+    assert_eq!(engine.eval("function g(n) { return n == 0 ? 0 : g(n - 1); } g(49)").unwrap(), Value::Number(0.0));
+}
+
+#[test]
+fn test_engine_default_call_depth() {
+    let mut engine = Engine::new();
+    let err = engine.eval("function f(n) { return f(n + 1); } f(0)").unwrap_err().to_string();
+    assert!(err.contains("RangeError: Maximum call stack size exceeded"), "{}", err);
+    assert_eq!(engine.eval("function g(n) { return n == 0 ? 0 : g(n - 1); } g(100)").unwrap(), Value::Number(0.0));
+    // Without a depth limit the stack limit still stops the recursion
+    engine.set_max_call_depth(None);
+    let err = engine.eval("f(0)").unwrap_err().to_string();
+    assert!(err.contains("RangeError: Maximum call stack size exceeded"), "{}", err);
+}
+
+#[test]
+fn test_engine_max_stack_size() {
+    // A host with a larger stack can allow deeper recursion
+    let depth = std::thread::Builder::new()
+        .stack_size(64 << 20)
+        .spawn(|| {
+            let mut engine = Engine::new();
+            engine.set_max_stack_size(Some(48 << 20));
+            engine.eval("function g(n) { return n == 0 ? 0 : 1 + g(n - 1); } g(999)").unwrap().to_number()
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    assert_eq!(depth, 999.0);
+}
+
+#[test]
+fn test_engine_max_heap_objects() {
+    let mut engine = Engine::new();
+    engine.set_max_heap_objects(Some(1000));
+    let err = engine.eval("let list = null; while (true) { list = {next: list}; }").unwrap_err().to_string();
+    assert!(err.contains("RangeError: JavaScript heap out of memory"), "{}", err);
+    // Garbage does not count against the limit
+    let mut engine = Engine::new();
+    engine.set_max_heap_objects(Some(1000));
+    let source = "let n = 0; for (let i = 0; i < 5000; i++) { let o = {x: [i]}; n = n + o.x[0]; } n";
+    assert_eq!(engine.eval(source).unwrap(), Value::Number(12497500.0));
+}
+
+#[test]
+fn test_interpret_spread() {
+    let mut engine = Engine::new();
+    assert_eq!(engine.eval("let a = [1, 2]; [...a, 3, ...'ab'].length").unwrap(), Value::Number(5.0));
+    assert_eq!(engine.eval("let args = ['ff', 16]; parseInt(...args)").unwrap(), Value::Number(255.0));
+    assert_eq!(engine.eval("let o = {a: 1, b: 2}; let p = {...o, b: 3}; p.b").unwrap(), Value::Number(3.0));
+    assert_eq!(engine.eval("[...5]").unwrap_err().to_string(), "TypeError: 5 is not iterable");
+}
+
+#[test]
+fn test_vm_block_locals() {
+    let source = "
+        let x = 1;
+        { let a = 2; { let b = 10; x = x + b; } x = x + a; }
+        { let c = 3; x = x + c; }
+        x;
+    ";
+    assert_eq!(run_vm(source), Value::Number(16.0));
+    assert_eq!(interpret(source).unwrap(), Value::Number(16.0));
+}
+
+#[test]
+fn test_object_statics() {
+    let mut engine = Engine::new();
+    assert_eq!(engine.eval("Object.keys({a: 1, b: 2}).length").unwrap(), Value::Number(2.0));
+    assert_eq!(
+        engine.eval("let k = Object.keys({b: 1, '2': 2, a: 3, '1': 4}); k[0] + k[1] + k[2] + k[3]").unwrap(),
+        Value::String("12ba".to_string())
+    );
+    let source = "let d = {}; Object.defineProperties(d, {x: {value: 1}, y: {'get': function() { return 2; }}}); \
+                  let n = Object.getOwnPropertyNames(d); n[0] + n[1] + Object.keys(d).length + (d.x + d.y)";
+    assert_eq!(engine.eval(source).unwrap(), Value::String("xy03".to_string()));
+    assert_eq!(
+        engine.eval("let t = {a: 1}; Object.assign(t, {b: 2}, {a: 3}); t.a + t.b").unwrap(),
+        Value::Number(5.0)
+    );
+    assert_eq!(
+        engine.eval("let o = Object.freeze({a: 1}); o.b = 2; o.a = 5; o.a + (o.b === undefined)").unwrap(),
+        Value::Number(2.0)
+    );
+}
+
+#[test]
+fn test_interpret_reference_semantics() {
+    let mut engine = Engine::new();
+    assert_eq!(
+        engine.eval("let a = [1]; let b = a; b[1] = 2; a.length * 10 + a[1]").unwrap(),
+        Value::Number(22.0)
+    );
+    assert_eq!(engine.eval("let o = {}; let p = o; p.x = 5; o.x").unwrap(), Value::Number(5.0));
+    assert_eq!(engine.eval("let c = [1]; c === c && [1] !== [1]").unwrap(), Value::Boolean(true));
+}
+
+#[test]
+fn test_array_length_tracks_and_truncates() {
+    let mut engine = Engine::new();
+    assert_eq!(engine.eval("let a = [1, 2, 3]; a.length").unwrap(), Value::Number(3.0));
+    assert_eq!(engine.eval("a.length = 0; a.length === 0 && a[0] === undefined").unwrap(), Value::Boolean(true));
+    assert_eq!(engine.eval("a[5] = 1; a.length").unwrap(), Value::Number(6.0));
+    assert_eq!(engine.eval("a[4] === undefined && !(4 in a)").unwrap(), Value::Boolean(true));
+    assert_eq!(engine.eval("a.length = 2; a[5]").unwrap(), Value::Undefined);
+    assert_eq!(engine.eval("a.length = '3'; a.length").unwrap(), Value::Number(3.0));
+    assert_eq!(engine.eval("Reflect.set(a, 'length', 1) && a.length").unwrap(), Value::Number(1.0));
+    for invalid in ["-1", "1.5", "4294967296", "'x'"] {
+        let error = engine.eval(&format!("a.length = {}", invalid)).unwrap_err();
+        assert!(error.to_string().starts_with("RangeError"), "{}: {}", invalid, error);
+    }
+    assert_eq!(engine.eval("a.length").unwrap(), Value::Number(1.0));
+}
+
+#[test]
+fn test_array_large_indices_and_holes() {
+    let mut engine = Engine::new();
+    // Far indices and lengths are stored without allocating the gap
+    assert_eq!(engine.eval("let a = []; a.length = 4294967295; a.length").unwrap(), Value::Number(4294967295.0));
+    assert_eq!(engine.eval("let b = []; b[4294967294] = 1; b.length").unwrap(), Value::Number(4294967295.0));
+    assert_eq!(engine.eval("let c = [1]; c[100000000] = 2; c.length + c[100000000]").unwrap(), Value::Number(100000003.0));
+    assert_eq!(engine.eval("c.length = 1; c[100000000]").unwrap(), Value::Undefined);
+    // Grown slots and deleted elements are holes, not undefined properties
+    assert_eq!(engine.eval("let d = []; d.length = 3; 0 in d").unwrap(), Value::Boolean(false));
+    assert_eq!(engine.eval("d[1] = 1; delete d[1]; 1 in d || Object.keys(d).length").unwrap(), Value::Number(0.0));
+    assert_eq!(engine.eval("let e = []; e[2] = 'x'; Object.keys(e).length").unwrap(), Value::Number(1.0));
+    assert_eq!(engine.eval("0 in new Array(5)").unwrap(), Value::Boolean(false));
+    // Only canonical index strings are indices
+    assert_eq!(engine.eval("let f = []; f['01'] = 5; f['+1'] = 6; f[4294967295] = 7; f.length").unwrap(), Value::Number(0.0));
+    assert_eq!(engine.eval("f['01'] === undefined").unwrap(), Value::Boolean(true));
+}
+
+#[test]
+fn test_function_call_apply_bind() {
+    let mut engine = Engine::new();
+    let prelude = "function f(a, b) { return this.x * 100 + a * 10 + b; } let o = {x: 1}; ";
+    assert_eq!(engine.eval(&format!("{}f.call(o, 2, 3)", prelude)).unwrap(), Value::Number(123.0));
+    assert_eq!(engine.eval(&format!("{}f.apply(o, [4, 5])", prelude)).unwrap(), Value::Number(145.0));
+    assert_eq!(
+        engine.eval(&format!("{}let g = f.bind(o, 7); g(8)", prelude)).unwrap(),
+        Value::Number(178.0)
+    );
+    // A bound `this` cannot be replaced by call
+    assert_eq!(
+        engine.eval(&format!("{}let h = f.bind(o); h.call({{x: 9}}, 1, 2)", prelude)).unwrap(),
+        Value::Number(112.0)
+    );
+    assert_eq!(engine.eval(&format!("{}f.bind(o, 1).length", prelude)).unwrap(), Value::Number(1.0));
+}
+
+#[test]
+fn test_vm_delete() {
+    assert_eq!(
+        run_vm("let o = {a: 1, b: 2}; delete o.a && o.a === undefined && o.b === 2"),
+        Value::Boolean(true)
+    );
+    assert_eq!(run_vm("let k = 'b'; let o = {b: 1}; delete o[k] && o.b"), Value::Undefined);
+    assert_eq!(run_vm("let a = [1, 2]; delete a[0]; a.length"), Value::Number(2.0));
+    assert_eq!(run_vm("delete 5"), Value::Boolean(true));
+}
+
+#[test]
+fn test_iterator_protocol() {
+    let mut engine = Engine::new();
+    assert_eq!(
+        engine
+            .eval("let s = new Set([1, 2, 2, 3]); let t = 0; for (const x of s) { t = t + x; } t * 10 + s.size")
+            .unwrap(),
+        Value::Number(63.0)
+    );
+    match engine.eval("[...\"ab\"]").unwrap() {
+        Value::Array(elements) => assert_eq!(
+            *elements.borrow(),
+            vec![Value::String("a".to_string()), Value::String("b".to_string())]
+        ),
+        other => panic!("Expected array, got {:?}", other),
+    }
+    let range = "
+        let closed = false;
+        let range = {};
+        range[Symbol.iterator] = function () {
+            let i = 0;
+            let iterator = { next: function () { i = i + 1; return {value: i, done: i > 3}; } };
+            iterator['return'] = function () { closed = true; return {}; };
+            return iterator;
+        };
+    ";
+    assert_eq!(engine.eval(&format!("{}[...range].length", range)).unwrap(), Value::Number(3.0));
+    assert_eq!(
+        engine
+            .eval(&format!("{}let n = 0; for (const v of range) {{ if (v === 2) break; n = n + v; }} n * 10 + closed", range))
+            .unwrap(),
+        Value::Number(11.0)
+    );
+    assert_eq!(engine.eval("for (const c of 5) {}").unwrap_err().to_string(), "TypeError: 5 is not iterable");
+}
+
+#[test]
+fn test_for_in() {
+    let mut engine = Engine::new();
+    let order = "let r = ''; for (const k in {b: 1, '2': 2, a: 3, '1': 4}) { r = r + k; } r";
+    assert_eq!(engine.eval(order).unwrap(), Value::String("12ba".to_string()));
+    // Inherited keys follow the own ones; shadowed and non-enumerable keys are skipped
+    let inherited = "let p = {z: 1, a: 2}; Object.defineProperties(p, {h: {value: 3}}); let o = Object.create(p); o.a = 4; \
+                     let r = ''; for (let k in o) { if (k === 'q') continue; r = r + k; } r";
+    assert_eq!(engine.eval(inherited).unwrap(), Value::String("az".to_string()));
+    assert_eq!(engine.eval("let k; for (k in [5, 6]) {} k").unwrap(), Value::String("1".to_string()));
+    assert_eq!(engine.eval("let n = 0; for (var k in null) { n = n + 1; } n").unwrap(), Value::Number(0.0));
+    assert_eq!(engine.eval("let r = ''; for (const k in 'ab') { r = r + k; } r").unwrap(), Value::String("01".to_string()));
+}
+
+#[test]
+fn test_array_statics() {
+    let mut engine = Engine::new();
+    assert_eq!(engine.eval("Array.isArray([]) + ',' + Array.isArray({})").unwrap(), Value::String("true,false".to_string()));
+    match engine.eval("Array.from(\"ab\")").unwrap() {
+        Value::Array(elements) => assert_eq!(
+            *elements.borrow(),
+            vec![Value::String("a".to_string()), Value::String("b".to_string())]
+        ),
+        other => panic!("Expected array, got {:?}", other),
+    }
+    assert_eq!(engine.eval("Array.of(7).length * 10 + Array.of(7)[0]").unwrap(), Value::Number(17.0));
+    assert_eq!(engine.eval("new Array(3).length === 3 && Array(1, 2, 3)[2] === 3").unwrap(), Value::Boolean(true));
+    // Any iterable, with the map function receiving each index
+    let source = "
+        let n = 0;
+        let counter = {};
+        counter[Symbol.iterator] = function () {
+            return { next: function () { n = n + 1; return { value: n, done: n > 3 }; } };
+        };
+        let a = Array.from(counter, function (v, i) { return v * 10 + i; });
+        a.length * 1000 + a[0] + a[1] + a[2]";
+    assert_eq!(engine.eval(source).unwrap(), Value::Number(3063.0));
+    assert_eq!(engine.eval("Array.from({ length: 2, '0': 'x' }).length").unwrap(), Value::Number(2.0));
+    assert_eq!(engine.eval("new Array(-1)").unwrap_err().to_string(), "RangeError: Invalid array length");
+}
+
+#[test]
+fn test_regexp() {
+    let mut engine = Engine::new();
+    assert_eq!(engine.eval(r#"/\d+/.test("a1")"#).unwrap(), Value::Boolean(true));
+    assert_eq!(engine.eval(r#"/abc/i.test("xABCx")"#).unwrap(), Value::Boolean(true));
+    assert_eq!(
+        engine.eval(r#"let m = /(\w+)-(\d+)/.exec("id: item-42"); m[1] + m[2] + m.index"#).unwrap(),
+        Value::String("item424".to_string())
+    );
+    assert_eq!(engine.eval(r#""a1b22c333".match(/\d+/g).length"#).unwrap(), Value::Number(3.0));
+    assert_eq!(
+        engine.eval(r#""2024-01-05".replace(/(\d+)-(\d+)-(\d+)/, "$3/$2/$1")"#).unwrap(),
+        Value::String("05/01/2024".to_string())
+    );
+    assert_eq!(engine.eval(r#""a1b2c".split(/\d/).length"#).unwrap(), Value::Number(3.0));
+    assert_eq!(
+        engine.eval(r#"let r = new RegExp("o", "g"); r.test("foo"); r.lastIndex"#).unwrap(),
+        Value::Number(2.0)
+    );
+    assert_eq!(engine.eval("/a/g.source + typeof /a/").unwrap(), Value::String("aobject".to_string()));
+}
+
+#[test]
+fn test_promise() {
+    let mut engine = Engine::new();
+    let property = |object: Value, key: &str| match object {
+        Value::Object(object) => object.borrow().get_property(key).unwrap_or(Value::Undefined),
+        other => panic!("Expected object, got {:?}", other),
+    };
+
+    // then callbacks run after the script, once microtasks are drained
+    let r = engine
+        .eval("let r = {}; Promise.resolve(1).then(function (v) { r.v = v; }); r.sync = r.v === undefined; r")
+        .unwrap();
+    assert_eq!(property(r.clone(), "sync"), Value::Boolean(true));
+    assert_eq!(property(r, "v"), Value::Number(1.0));
+
+    let r = engine
+        .eval(
+            "let r = {};
+             Promise.all([Promise.resolve(1), 2, new Promise(function (resolve) { resolve(3); })])
+                 .then(function (v) { r.sum = v[0] + v[1] + v[2]; });
+             r",
+        )
+        .unwrap();
+    assert_eq!(property(r, "sum"), Value::Number(6.0));
+
+    let r = engine
+        .eval(
+            "let r = {};
+             new Promise(function () { throw 'boom'; })
+                 .then(function () { r.then = true; })
+                 .catch(function (e) { r.error = e; return 5; })
+                 .finally(function () { r.finally = true; })
+                 .then(function (v) { r.v = v; });
+             Promise.race([new Promise(function () {}), Promise.reject('lost')])
+                 .catch(function (e) { r.race = e; });
+             r",
+        )
+        .unwrap();
+    assert_eq!(property(r.clone(), "then"), Value::Undefined);
+    assert_eq!(property(r.clone(), "error"), Value::String("boom".to_string()));
+    assert_eq!(property(r.clone(), "finally"), Value::Boolean(true));
+    assert_eq!(property(r.clone(), "v"), Value::Number(5.0));
+    assert_eq!(property(r, "race"), Value::String("lost".to_string()));
+
+    // A resolved promise adopts the state of a thenable
+    let r = engine
+        .eval("let r = {}; Promise.resolve(1).then(function () { return Promise.resolve(2); }).then(function (v) { r.v = v; }); r")
+        .unwrap();
+    assert_eq!(property(r, "v"), Value::Number(2.0));
+
+    assert!(engine.eval("Promise(function () {})").unwrap_err().to_string().starts_with("TypeError"));
+}
+
+#[test]
+fn test_in_has_own_and_delete_follow_the_prototype_chain() {
+    let mut engine = Engine::new();
+    let setup = "let proto = {toString: 1}; let o = Object.create(proto); o.a = 2;";
+    let eval = |engine: &mut Engine, source: &str| engine.eval(&format!("{} {}", setup, source)).unwrap();
+    assert_eq!(eval(&mut engine, r#""toString" in o"#), Value::Boolean(true));
+    assert_eq!(eval(&mut engine, r#"Object.hasOwn(o, "toString")"#), Value::Boolean(false));
+    assert_eq!(eval(&mut engine, r#"Object.hasOwn(o, "a")"#), Value::Boolean(true));
+    assert_eq!(eval(&mut engine, r#"delete o.toString && "toString" in o"#), Value::Boolean(true));
+    assert_eq!(eval(&mut engine, r#"delete o.a && !("a" in o)"#), Value::Boolean(true));
+    assert_eq!(
+        eval(&mut engine, r#"let f = Object.freeze({b: 1}); delete f.b || f.b"#),
+        Value::Number(1.0)
+    );
+    assert!(engine.eval(r#""a" in 1"#).unwrap_err().to_string().contains("TypeError"));
+}
+
+#[test]
+fn test_object_prototype_access() {
+    let mut engine = Engine::new();
+    assert_eq!(
+        engine.eval("let p = {a: 1}; Object.getPrototypeOf(Object.create(p)) === p").unwrap(),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        engine.eval("let o = {}; Object.setPrototypeOf(o, {a: 2}); o.a").unwrap(),
+        Value::Number(2.0)
+    );
+    assert_eq!(
+        engine.eval("let q = {b: 3}; let r = {}; r.__proto__ = q; r.b + (r.__proto__ === q)").unwrap(),
+        Value::Number(4.0)
+    );
+    assert_eq!(
+        engine.eval("let n = Object.create({c: 1}); Object.setPrototypeOf(n, null); Object.getPrototypeOf(n)").unwrap(),
+        Value::Null
+    );
+    let cycle = engine.eval("let x = {}; let y = Object.create(x); Object.setPrototypeOf(x, y)");
+    assert!(cycle.unwrap_err().to_string().contains("Cyclic __proto__ value"));
+}
+
+#[test]
+fn test_instanceof() {
+    let mut engine = Engine::new();
+    assert_eq!(
+        engine.eval("function Point() {} let p = new Point(); p instanceof Point").unwrap(),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        engine.eval("function Other() {} p instanceof Other").unwrap(),
+        Value::Boolean(false)
+    );
+    assert_eq!(
+        engine.eval("let child = Object.create(p); (child instanceof Point) + (1 instanceof Point)").unwrap(),
+        Value::Number(1.0)
+    );
+    // Symbol.hasInstance overrides the prototype chain; its result is coerced to a boolean
+    assert_eq!(
+        engine.eval("function Even() {} Even[Symbol.hasInstance] = function (x) { return x % 2 === 0; }; 4 instanceof Even").unwrap(),
+        Value::Boolean(true)
+    );
+    assert_eq!(engine.eval("new Even() instanceof Even").unwrap(), Value::Boolean(false));
+    assert_eq!(
+        engine.eval("let Any = {}; Any[Symbol.hasInstance] = function () { return 'yes'; }; null instanceof Any").unwrap(),
+        Value::Boolean(true)
+    );
+    let not_callable = engine.eval("p instanceof {}");
+    assert!(not_callable.unwrap_err().to_string().contains("is not callable"));
+}
+
+#[test]
+fn test_reflect() {
+    let mut engine = Engine::new();
+    assert_eq!(engine.eval(r#"Reflect.has({a: 1}, "a")"#).unwrap(), Value::Boolean(true));
+    assert_eq!(
+        engine.eval("let o = {x: 1, y: 2}; Reflect.ownKeys(o).length").unwrap(),
+        Value::Number(2.0)
+    );
+    assert_eq!(
+        engine
+            .eval("function f(a, b) { return this.base + a * b; } Reflect.apply(f, {base: 1}, [2, 3])")
+            .unwrap(),
+        Value::Number(7.0)
+    );
+    assert_eq!(
+        engine.eval("function P(x) { this.x = x; } Reflect.construct(P, [4]).x").unwrap(),
+        Value::Number(4.0)
+    );
+    assert!(engine.eval("Reflect.apply(1, null, [])").unwrap_err().to_string().starts_with("TypeError"));
+}
+
+#[test]
+fn test_number_statics() {
+    let mut engine = Engine::new();
+    assert_eq!(engine.eval(r#"Number.isNaN("x")"#).unwrap(), Value::Boolean(false));
+    assert_eq!(engine.eval(r#"isNaN("x")"#).unwrap(), Value::Boolean(true));
+    assert_eq!(engine.eval("Number.isInteger(5) && !Number.isInteger(5.5)").unwrap(), Value::Boolean(true));
+    assert_eq!(engine.eval("Number.MAX_SAFE_INTEGER + 2").unwrap(), Value::Number(9007199254740992.0));
+    assert_eq!(engine.eval(r#"Number.parseFloat("1.5kg") + Number("2")"#).unwrap(), Value::Number(3.5));
+}
+
+#[test]
+fn test_primitive_wrapper_constructors() {
+    let mut engine = Engine::new();
+    assert_eq!(engine.eval(r#"String(123) === "123""#).unwrap(), Value::Boolean(true));
+    assert_eq!(engine.eval(r#"Number("42") === 42"#).unwrap(), Value::Boolean(true));
+    assert_eq!(engine.eval("Boolean(0) === false && Boolean('a') === true").unwrap(), Value::Boolean(true));
+    assert_eq!(engine.eval(r#"String() === "" && Number() === 0 && Boolean() === false"#).unwrap(), Value::Boolean(true));
+
+    assert_eq!(engine.eval(r#"typeof new String("a")"#).unwrap(), Value::String("object".to_string()));
+    assert_eq!(engine.eval(r#"new String("a").valueOf()"#).unwrap(), Value::String("a".to_string()));
+    assert_eq!(engine.eval("new Number('7').valueOf() === 7").unwrap(), Value::Boolean(true));
+    assert_eq!(engine.eval("new Number(7) === 7").unwrap(), Value::Boolean(false));
+    assert_eq!(engine.eval("new Number(7) + 1").unwrap(), Value::Number(8.0));
+    // A wrapper is an object, so even a wrapped `false` is truthy
+    assert_eq!(engine.eval("new Boolean(false) ? 'yes' : 'no'").unwrap(), Value::String("yes".to_string()));
+    assert_eq!(engine.eval("new Boolean(false).toString()").unwrap(), Value::String("false".to_string()));
+    assert!(engine.eval("Number.prototype.valueOf.call('x')").unwrap_err().to_string().starts_with("TypeError"));
+}
+
+#[test]
+fn test_leading_zero_number_literals() {
+    let mut engine = Engine::new();
+    assert_eq!(engine.eval("010 === 8").unwrap(), Value::Boolean(true));
+    assert_eq!(engine.eval("08 === 8").unwrap(), Value::Boolean(true));
+}
+
+#[test]
+fn test_numeric_literal_forms() {
+    let mut engine = Engine::new();
+    assert_eq!(engine.eval("0xFF + 1_000 + 1e1").unwrap(), Value::Number(1265.0));
+    assert_eq!(run_vm("0xFF + 1_000"), Value::Number(1255.0));
+}
+
+#[test]
+fn test_vm_function_and_class_values() {
+    assert_eq!(run_vm("(function () { return 7; })()"), Value::Number(7.0));
+    assert_eq!(run_vm("let x = 3; let twice = function () { return x * 2; }; twice()"), Value::Number(6.0));
+    assert_eq!(run_vm("class C { m() { return 5; } } new C().m()"), Value::Number(5.0));
+}
+
+#[test]
+fn test_vm_closures_share_captured_variables() {
+    // The closure sees writes made after it was created
+    assert_eq!(run_vm("let x = 5; let f = () => x; x = 6; f()"), Value::Number(6.0));
+    // and its own writes reach the enclosing function, not a global
+    let counter = "function mk() { let c = 0; return function () { c = c + 1; return c; }; }";
+    assert_eq!(run_vm(&format!("{} mk()()", counter)), Value::Number(1.0));
+    assert_eq!(run_vm(&format!("{} let next = mk(); next(); next()", counter)), Value::Number(2.0));
+    assert_eq!(run_vm(&format!("{} mk()(); typeof c", counter)), Value::String("undefined".to_string()));
+    // Two closures over the same variable, one of them declared before it
+    let pair = "function f() { function read() { return n; } let n = 1; let write = (v) => { n = v; }; write(4); return read(); } f()";
+    assert_eq!(run_vm(pair), Value::Number(4.0));
+    // A closure two levels down writes through the middle function
+    assert_eq!(run_vm("let t = 1; function a() { return () => { t = t * 10; }; } a()(); a()(); t"), Value::Number(100.0));
+    // `let` in a for head is a new variable per iteration; `var` is one for the whole function
+    let per_iteration = "let fs = []; for (let i = 0; i < 3; i = i + 1) { fs[i] = () => i; } fs[0]() * 100 + fs[1]() * 10 + fs[2]()";
+    assert_eq!(run_vm(per_iteration), Value::Number(12.0));
+    let shared = "let fs = []; for (var i = 0; i < 3; i = i + 1) { fs[i] = () => i; } fs[0]() * 100 + fs[1]() * 10 + fs[2]()";
+    assert_eq!(run_vm(shared), Value::Number(333.0));
+    // Parameters and destructured bindings are captured the same way
+    assert_eq!(run_vm("function f(p, {q}) { let g = () => p + q; p = 10; return g(); } f(1, {q: 2})"), Value::Number(12.0));
+}
+
+#[test]
+fn test_vm_update_and_compound_assignment_to_captured_variables() {
+    assert_eq!(run_vm("let i = 0; let bump = function () { i++; }; bump(); bump(); i"), Value::Number(2.0));
+    assert_eq!(run_vm("let i = 5; let f = () => --i; let r = f(); r * 10 + i"), Value::Number(44.0));
+    let counter = "function mk() { let c = 0; return function () { c += 1; return c; }; }";
+    assert_eq!(run_vm(&format!("{} let next = mk(); next(); let a = next(); let b = mk()(); a * 10 + b", counter)), Value::Number(21.0));
+    assert_eq!(run_vm("let s = 'a'; let add = (x) => { s += x; }; add('b'); add('c'); s"), Value::String("abc".to_string()));
+}
+
+#[test]
+fn test_vm_rest_default_and_destructuring_parameters() {
+    let f = "function f(a, b = a + 1, ...rest) { return a * 100 + b * 10 + rest.length; }";
+    // b defaults to a + 1 and rest is empty
+    assert_eq!(run_vm(&format!("{} f(1)", f)), Value::Number(120.0));
+    // rest collects the arguments after b
+    assert_eq!(run_vm(&format!("{} f(1, 5, 7, 8)", f)), Value::Number(152.0));
+    assert_eq!(run_vm("function f(a, ...rest) { return rest[1]; } f(1, 2, 3)"), Value::Number(3.0));
+    // Only undefined triggers the default
+    assert_eq!(run_vm("function k(a = 1) { return a; } k(null)"), Value::Null);
+    assert_eq!(run_vm("function g({x}) { return x; } g({x: 9})"), Value::Number(9.0));
+    assert_eq!(run_vm("function p([x, , y = 3], {z: w = 4}) { return x + y + w; } p([1, 2], {})"), Value::Number(8.0));
+}
+
+#[test]
+fn test_optional_chaining_short_circuits() {
+    let mut engine = Engine::new();
+    assert_eq!(engine.eval("let a = null; a?.x.y").unwrap(), Value::Undefined);
+    assert_eq!(engine.eval("let b; b?.f().g").unwrap(), Value::Undefined);
+    assert_eq!(engine.eval("({x: {y: 5}})?.x.y").unwrap(), Value::Number(5.0));
+    assert!(engine.eval("({})?.x.y").is_err());
+
+    assert_eq!(run_vm("let a = null; a?.x.y"), Value::Undefined);
+    assert_eq!(run_vm("let a = [[5]]; a?.[0][0]"), Value::Number(5.0));
+}
+
+#[test]
+fn test_object_literal_accessors() {
+    let mut engine = Engine::new();
+    let source = "
+        let rect = {
+            width: 3,
+            height: 4,
+            get area() { return this.width * this.height; },
+            set size(value) { this.width = value; this.height = value; }
+        };
+        rect.size = 5;
+        rect.area
+    ";
+    assert_eq!(engine.eval(source).unwrap(), Value::Number(25.0));
+
+    let source = "
+        let written = [];
+        let o = { get x() { return 1; }, set x(v) { written[written.length] = v; } };
+        o.x = 7;
+        o.x = o.x + 1;
+        written[0] + ',' + written[1] + ':' + o.x
+    ";
+    assert_eq!(engine.eval(source).unwrap(), Value::String("7,2:1".to_string()));
+}
+
+#[test]
+fn test_keyword_and_numeric_property_keys() {
+    let mut engine = Engine::new();
+    let source = "let o = { get: 1, set: 2, new: 3, 4: 5, 'x y': 6 }; o.get + o.set + o.new + o[4] + o['x y']";
+    assert_eq!(engine.eval(source).unwrap(), Value::Number(17.0));
+    assert_eq!(run_vm("let o = { get: 1, if: 2, 3: 4 }; o.get + o.if + o[3];"), Value::Number(7.0));
+}
+
+#[test]
+fn test_typeof_matches_in_interpreter_and_vm() {
+    let cases = [
+        ("typeof undefined", "undefined"),
+        ("typeof null", "object"),
+        ("typeof true", "boolean"),
+        ("typeof 1.5", "number"),
+        ("typeof 'a'", "string"),
+        ("typeof {}", "object"),
+        ("typeof [1]", "object"),
+        ("typeof function () {}", "function"),
+    ];
+    for (source, expected) in cases {
+        let expected = Value::String(expected.to_string());
+        assert_eq!(interpret(source).unwrap(), expected, "source: {}", source);
+        assert_eq!(run_vm(source), expected, "source: {}", source);
+    }
+    assert_eq!(interpret("typeof null === 'object'").unwrap(), Value::Boolean(true));
+}
+
+#[test]
+fn test_update_expressions_write_back() {
+    let cases = [
+        ("let i = 0; let j = i++; i * 10 + j", 10.0),
+        ("let i = 1; ++i", 2.0),
+        ("let i = 1; let j = ++i; i * 10 + j", 22.0),
+        ("let i = 5; i--; i", 4.0),
+        ("let o = {n: 1}; let old = o.n++; o.n * 10 + old", 21.0),
+        ("let a = [5]; let k = 0; let now = --a[k]; a[0] * 10 + now", 44.0),
+    ];
+    for (source, expected) in cases {
+        assert_eq!(interpret(source).unwrap(), Value::Number(expected), "source: {}", source);
+        assert_eq!(run_vm(source), Value::Number(expected), "source: {}", source);
+    }
+}
+
+#[test]
+fn test_sequence_expression_keeps_last_value() {
+    let cases = [
+        ("let a = 0; (a = 1, a = 2, a + 3)", 5.0),
+        // Earlier expressions still run: `a` ends up as 2
+        ("let a = 0; let r = (a = 1, a = 2, a + 3); r * 10 + a", 52.0),
+    ];
+    for (source, expected) in cases {
+        assert_eq!(interpret(source).unwrap(), Value::Number(expected), "source: {}", source);
+        assert_eq!(run_vm(source), Value::Number(expected), "source: {}", source);
+    }
+}
+
+#[test]
+fn test_vm_array_and_object_literals_keep_source_order() {
+    assert_eq!(run_vm("let a = [1, 2, 3]; a[0] * 100 + a[1] * 10 + a[2]"), Value::Number(123.0));
+    assert_eq!(run_vm("let o = {a: 1, b: 2}; o.a * 10 + o.b"), Value::Number(12.0));
+
+    let elements: Vec<String> = (0..100).map(|i| i.to_string()).collect();
+    let source = format!("let big = [{}]; big[0] + big[57] * 1000 + big.length * 1000000", elements.join(", "));
+    assert_eq!(run_vm(&source), Value::Number(100_057_000.0));
+}
+
+#[test]
+fn test_vm_non_tail_recursion() {
+    // Values pushed before a call stay on the operand stack
+    assert_eq!(run_vm("function f(n) { return n ? 1 + f(n - 1) : 0 } f(50)"), Value::Number(50.0));
+    let unbounded = "function f(n) { return 1 + f(n + 1); } let r; try { f(0); } catch (e) { r = e; } r";
+    assert_eq!(run_vm(unbounded), Value::String("RangeError: Maximum call stack size exceeded".to_string()));
+}
+
+#[test]
+fn test_vm_array_large_indices_and_holes() {
+    assert_eq!(run_vm("let a = []; a.length = 4294967295; a.length"), Value::Number(4294967295.0));
+    assert_eq!(run_vm("let b = []; b[4294967294] = 1; b.length"), Value::Number(4294967295.0));
+    assert_eq!(run_vm("let c = [1]; c[100000000] = 2; c.length + c[100000000]"), Value::Number(100000003.0));
+    // Grown slots are holes: for-in and forEach skip them
+    let keys = "let d = [1]; d.length = 3; d[5] = 2; let r = ''; for (const k in d) { r = r + k; } r";
+    assert_eq!(run_vm(keys), Value::String("05".to_string()));
+    assert_eq!(run_vm("let n = 0; let e = [1]; e[9] = 2; e.forEach(() => { n = n + 1; }); n"), Value::Number(2.0));
+    assert_eq!(run_vm("let f = []; f['01'] = 5; f.length"), Value::Number(0.0));
+}
+
+#[test]
+fn test_vm_in_operator() {
+    assert_eq!(run_vm("let o = {a: 1}; ('a' in o) && !('b' in o)"), Value::Boolean(true));
+    // Inherited keys are found through the prototype chain
+    assert_eq!(run_vm("class A { m() {} } 'm' in new A()"), Value::Boolean(true));
+    // Array indices are keys unless they are holes
+    assert_eq!(run_vm("let a = [1]; a[3] = 4; (0 in a) && !(1 in a) && ('3' in a) && ('length' in a)"), Value::Boolean(true));
+    assert_eq!(run_vm("let a = [1, 2]; delete a[0]; 0 in a"), Value::Boolean(false));
+    assert_eq!(
+        run_vm("try { 'a' in 1; } catch (e) { e }"),
+        Value::String("TypeError: Cannot use 'in' operator to search for 'a' in 1".to_string())
+    );
+}
+
+#[test]
+fn test_vm_catch_receives_thrown_value() {
+    assert_eq!(run_vm("try { throw {code: 42}; } catch (e) { e.code }"), Value::Number(42.0));
+    let error = "class MyError { message() { return 'bad'; } }";
+    assert_eq!(
+        run_vm(&format!("{} try {{ throw new MyError(); }} catch (e) {{ e.message() }}", error)),
+        Value::String("bad".to_string())
+    );
+    // Thrown from a called function, past its frame
+    assert_eq!(
+        run_vm("function f() { throw [1, 2]; } try { f(); } catch (e) { e.length }"),
+        Value::Number(2.0)
+    );
+    // Errors raised by the VM itself are caught too
+    assert_eq!(
+        run_vm("try { [...5]; } catch (e) { e }"),
+        Value::String("TypeError: 5 is not iterable".to_string())
+    );
+    assert_eq!(run_vm("let x = 1; try { x = 2; } catch (e) { x = 3; } x"), Value::Number(2.0));
+}
+
+#[test]
+fn test_vm_finally_runs_and_rethrows() {
+    let source = "let ran = 0; try { try { throw 7; } finally { ran = 1; } } catch (e) { ran * 10 + e }";
+    assert_eq!(run_vm(source), Value::Number(17.0));
+    assert_eq!(run_vm("function f() { try { throw 7; } finally { 1; } } try { f(); } catch (e) { e }"), Value::Number(7.0));
+    assert_eq!(run_vm("let ran = 0; try { ran = 1; } finally { ran = ran + 1; } ran"), Value::Number(2.0));
+}
+
+/// Runs `source` on the VM, whose last expression is a promise, and
+/// returns the promise state before and after draining microtasks
+fn run_vm_promise(source: &str) -> (PromiseState, PromiseState) {
+    let ast = Parser::new(source).parse().expect("parse error");
+    let (bytecode, constants) = Compiler::new().compile(&ast).expect("compile error");
+    let mut executor = Executor::new();
+    executor.execute(&bytecode, &constants);
+    let promise = executor.stack.values.last().cloned().expect("empty stack");
+    let before = executor.promise_state(&promise).cloned().expect("not a promise");
+    executor.run_microtasks(&constants);
+    let after = executor.promise_state(&promise).cloned().expect("not a promise");
+    (before, after)
+}
+
+#[test]
+fn test_vm_async_function_fulfills_after_microtasks() {
+    let source = "async function g() { return 41; } async function f() { let v = await g(); return v + 1; } f()";
+    assert_eq!(run_vm_promise(source), (PromiseState::Pending, PromiseState::Fulfilled(VmValue::Number(42.0))));
+    // Without an await the promise is already fulfilled when the call returns
+    let (before, _) = run_vm_promise("async function f() { return 1; } f()");
+    assert_eq!(before, PromiseState::Fulfilled(VmValue::Number(1.0)));
+}
+
+#[test]
+fn test_vm_await_rejected_promise_throws() {
+    let source = "async function g() { throw 1; } \
+                  async function f() { try { await g(); } catch (e) { return e + 1; } } f()";
+    assert_eq!(run_vm_promise(source).1, PromiseState::Fulfilled(VmValue::Number(2.0)));
+    let uncaught = "async function g() { throw 1; } async function f() { let v = await g(); return v; } f()";
+    assert_eq!(run_vm_promise(uncaught).1, PromiseState::Rejected(VmValue::Number(1.0)));
+}
+
+#[test]
+fn test_vm_chained_member_assignment() {
+    let source = "let o = {}; o.a = o.b = 5; [o.a, o.b]";
+    let ast = Parser::new(source).parse().expect("parse error");
+    let (bytecode, constants) = Compiler::new().compile(&ast).expect("compile error");
+    let mut executor = Executor::new();
+    executor.execute(&bytecode, &constants);
+    // The statement-level assignment leaves nothing behind: only the result remains
+    let [VmValue::Array(handle)] = executor.stack.values.as_slice() else {
+        panic!("Expected only the result array, got {:?}", executor.stack.values);
+    };
+    let elements: Vec<VmValue> = (0..2).filter_map(|idx| executor.heap.get_array_element(*handle, idx).cloned()).collect();
+    assert_eq!(elements, vec![VmValue::Number(5.0), VmValue::Number(5.0)]);
+    assert_eq!(run_vm("let o = {}; let x = o.y = 3; x + o.y"), Value::Number(6.0));
+    assert_eq!(run_vm("let o = {}; let k = 'z'; o[k] = 2; o.z"), Value::Number(2.0));
+}
+
+#[test]
+fn test_vm_new_and_instanceof() {
+    assert_eq!(run_vm("class A {} let a = new A(); a instanceof A"), Value::Boolean(true));
+    assert_eq!(run_vm("class A {} class B {} new A() instanceof B"), Value::Boolean(false));
+    assert_eq!(run_vm("class A {} 1 instanceof A"), Value::Boolean(false));
+    assert_eq!(run_vm("function P(x) { this.x = x; } new P(5).x"), Value::Number(5.0));
+    assert_eq!(run_vm("function P(x) { this.x = x; } new P(5) instanceof P"), Value::Boolean(true));
+    assert_eq!(run_vm("function Q() { this.x = 1; return {y: 2}; } new Q().y"), Value::Number(2.0));
+}
+
+#[test]
+fn test_vm_rest_in_destructuring() {
+    let array_declaration = "let [a, ...rest] = [1, 2, 3]; a === 1 && rest.length === 2 && rest[0] === 2 && rest[1] === 3";
+    assert_eq!(run_vm(array_declaration), Value::Boolean(true));
+    let array_assignment = "let a, rest; [a, ...rest] = [1, 2, 3]; a * 100 + rest[0] * 10 + rest[1]";
+    assert_eq!(run_vm(array_assignment), Value::Number(123.0));
+    let object_declaration = "let {a, ...rest} = {a: 1, b: 2, c: 3}; \
+                              a === 1 && rest.b === 2 && rest.c === 3 && typeof rest.a === 'undefined'";
+    assert_eq!(run_vm(object_declaration), Value::Boolean(true));
+    let object_assignment = "let a, rest; ({a, ...rest} = {a: 1, b: 2, c: 3}); a * 100 + rest.b * 10 + rest.c";
+    assert_eq!(run_vm(object_assignment), Value::Number(123.0));
+    let member_targets = "let o = {}; [o.x, ...o.y] = [1, 2]; o.x + o.y[0]";
+    assert_eq!(run_vm(member_targets), Value::Number(3.0));
+}
+
+#[test]
+fn test_vm_loop_break_and_continue() {
+    let early_break = "let sum = 0; for (let i = 0; i < 10; i++) { if (i === 3) break; sum = sum + i; } sum";
+    assert_eq!(run_vm(early_break), Value::Number(3.0));
+    let skip_even = "let sum = 0; for (let i = 0; i < 10; i++) { if (i % 2 === 0) continue; sum = sum + i; } sum";
+    assert_eq!(run_vm(skip_even), Value::Number(25.0));
+    let while_loop = "let n = 0; while (true) { n = n + 1; if (n < 5) continue; break; } n";
+    assert_eq!(run_vm(while_loop), Value::Number(5.0));
+    let labeled = "let count = 0; \
+                   outer: for (let i = 0; i < 3; i++) { for (let j = 0; j < 3; j++) { if (j === 1) continue outer; count = count + 1; } } \
+                   count";
+    assert_eq!(run_vm(labeled), Value::Number(3.0));
+    let do_while = "let k = 0; do { k = k + 1; if (k === 2) continue; } while (k < 4); k";
+    assert_eq!(run_vm(do_while), Value::Number(4.0));
+}
+
+#[test]
+fn test_vm_switch_break_and_continue() {
+    // `break` leaves the switch and the loop goes on; without it a case falls through
+    let in_loop = "let log = 0; for (let i = 0; i < 4; i++) { \
+                   switch (i) { case 1: log = log + 10; break; case 2: log = log + 100; default: log = log + 1; } } \
+                   log";
+    assert_eq!(run_vm(in_loop), Value::Number(113.0));
+    // `continue` skips the rest of the enclosing loop's body
+    let skip = "let n = 0; for (let i = 0; i < 5; i++) { switch (i % 2) { case 0: continue; } n = n + 1; } n";
+    assert_eq!(run_vm(skip), Value::Number(2.0));
+    let labeled = "let c = 0; outer: for (let i = 0; i < 5; i++) { switch (i) { case 2: break outer; } c = c + 1; } c";
+    assert_eq!(run_vm(labeled), Value::Number(2.0));
+
+    // The default is only taken when no case matches, wherever it is
+    let with_default = |value: &str| {
+        format!("let r = 5; switch ({}) {{ case 1: r = 1; break; default: r = 2; case 4: r = r * 10; }} r", value)
+    };
+    assert_eq!(run_vm(&with_default("3")), Value::Number(20.0));
+    assert_eq!(run_vm(&with_default("4")), Value::Number(50.0));
+    assert_eq!(run_vm(&with_default("1")), Value::Number(1.0));
+    assert_eq!(run_vm("let r = 1; switch (9) { case 1: r = 2; } r"), Value::Number(1.0));
+    // Cases are compared with === and tested in order until one matches
+    let tests = "let calls = {n: 0}; function t(v) { calls.n += 1; return v; } \
+                 switch ('1') { case 1: calls.n = 100; break; case t('1'): break; case t(2): break; } calls.n";
+    assert_eq!(run_vm(tests), Value::Number(1.0));
+}
+
+#[test]
+fn test_vm_array_higher_order_methods() {
+    assert_eq!(run_vm("let a = [1, 2, 3].map((x, i) => x * 10 + i); a[0] + a[1] + a[2]"), Value::Number(63.0));
+    assert_eq!(run_vm("let a = [1, 2, 3, 4, 5].filter(x => x % 2 === 1); a.length * 10 + a[2]"), Value::Number(35.0));
+    assert_eq!(run_vm("[1, 2, 3, 4].reduce((sum, x) => sum + x, 10)"), Value::Number(20.0));
+    assert_eq!(run_vm("[1, 2, 3].reduce((acc, x) => acc * x)"), Value::Number(6.0));
+    assert_eq!(run_vm("[1, 5, 3].some(x => x > 4)"), Value::Boolean(true));
+    assert_eq!(run_vm("[1, 5, 3].every(x => x > 4)"), Value::Boolean(false));
+    assert_eq!(run_vm("[4, 7, 9].find(x => x > 5) * 10 + [4, 7, 9].findIndex(x => x > 5)"), Value::Number(71.0));
+    // Chained calls and `this` inside script methods
+    let source = "let o = {k: 3}; o.scale = function(x) { return x * this.k; }; \
+                  [1, 2].map(x => o.scale(x)).reduce((a, b) => a + b, 0)";
+    assert_eq!(run_vm(source), Value::Number(9.0));
+    // An exception thrown by a callback leaves the method
+    let source = "let r = {n: 0}; try { [1, 2].forEach(x => { if (x === 2) throw x * 7; r.n += x; }); } catch (e) { r.n += e; } r.n";
+    assert_eq!(run_vm(source), Value::Number(15.0));
+}
+
+#[test]
+fn test_vm_array_sort() {
+    let digits = |source: &str| format!("let a = {}; a[0] * 1000 + a[1] * 100 + a[2] * 10 + a[3]", source);
+    assert_eq!(run_vm(&digits("[3, 1, 4, 2].sort((x, y) => x - y)")), Value::Number(1234.0));
+    assert_eq!(run_vm(&digits("[3, 1, 4, 2].sort((x, y) => y - x)")), Value::Number(4321.0));
+    // Without a comparator elements are compared as strings
+    assert_eq!(run_vm("let a = [10, 9, 1]; a.sort(); a[0] + a[1] * 100 + a[2] * 10000"), Value::Number(91001.0));
+    // Elements that compare equal keep their order
+    let stable = "let a = [{k: 1, id: 1}, {k: 0, id: 2}, {k: 1, id: 3}, {k: 0, id: 4}]; \
+                  a.sort((x, y) => x.k - y.k); a[0].id * 1000 + a[1].id * 100 + a[2].id * 10 + a[3].id";
+    assert_eq!(run_vm(stable), Value::Number(2413.0));
+}
+
+#[test]
+fn test_vm_spread_iterator_object() {
+    let counter = "let it = { i: 0, next() { this.i = this.i + 1; return { done: this.i > 3, value: this.i }; } };";
+    assert_eq!(run_vm(&format!("{} let a = [0, ...it]; a[1] * 100 + a[2] * 10 + a[3] + a.length * 1000", counter)), Value::Number(4123.0));
+    assert_eq!(run_vm(&format!("{} function f(a, b, c) {{ return a * 100 + b * 10 + c; }} f(...it)", counter)), Value::Number(123.0));
+}
+
+#[test]
+fn test_vm_for_of() {
+    let set = "let s = new Set([1, 2, 2, 3]); let t = 0; for (const x of s) { t = t + x; } t * 10 + s.size";
+    assert_eq!(run_vm(set), Value::Number(63.0));
+    let pairs = "let t = 0; for (const [k, v] of [[1, 2], [3, 4]]) { t = t * 100 + k * 10 + v; } t";
+    assert_eq!(run_vm(pairs), Value::Number(1234.0));
+    assert_eq!(run_vm("let r = ''; for (let c of 'abc') { if (c === 'b') continue; r = r + c; } r"), Value::String("ac".to_string()));
+    assert_eq!(run_vm("let x; for (x of [1, 2]) {} x"), Value::Number(2.0));
+    // Elements pushed by the body are visited
+    assert_eq!(run_vm("let a = [1]; let n = 0; for (const v of a) { if (v < 3) { a[a.length] = v + 1; } n = n + 1; } n"), Value::Number(3.0));
+    // Each iteration has its own binding
+    let closures = "let fs = []; for (const x of [1, 2, 3]) { fs[x - 1] = () => x; } fs[0]() * 100 + fs[1]() * 10 + fs[2]()";
+    assert_eq!(run_vm(closures), Value::Number(123.0));
+    // An iterator object is stepped lazily and closed by `break`
+    let iterator = "let closed = false; let it = { i: 0, next() { this.i = this.i + 1; return { done: this.i > 3, value: this.i }; }, \
+                    return() { closed = true; return {}; } };";
+    let early = format!("{} let n = 0; for (const v of it) {{ if (v === 2) break; n = n + v; }} n * 10 + closed", iterator);
+    assert_eq!(run_vm(&early), Value::Number(11.0));
+    let complete = format!("{} let n = 0; for (const v of it) {{ n = n + v; }} n * 10 + closed", iterator);
+    assert_eq!(run_vm(&complete), Value::Number(60.0));
+    assert_eq!(run_vm("try { for (const c of 5) {} } catch (e) { e }"), Value::String("TypeError: 5 is not iterable".to_string()));
+}
+
+#[test]
+fn test_vm_for_in() {
+    let order = "let r = ''; for (const k in {b: 1, '2': 2, a: 3, '1': 4}) { r = r + k; } r";
+    assert_eq!(run_vm(order), Value::String("12ba".to_string()));
+    // Keys of the prototype follow the own ones, once each
+    let inherited = "function P() { this.a = 1; } P.prototype.z = 2; P.prototype.a = 3; let o = new P(); \
+                     let r = ''; for (let k in o) { r = r + k; } r";
+    assert_eq!(run_vm(inherited), Value::String("az".to_string()));
+    assert_eq!(run_vm("let r = ''; for (var k in [5, 6]) { if (k === '0') continue; r = r + k; } r"), Value::String("1".to_string()));
+    assert_eq!(run_vm("let n = 0; for (const k in undefined) { n = n + 1; } n"), Value::Number(0.0));
+    let closures = "let fs = []; for (const k in {a: 1, b: 2}) { fs[fs.length] = () => k; } fs[0]() + fs[1]()";
+    assert_eq!(run_vm(closures), Value::String("ab".to_string()));
+}
+
+#[test]
+fn test_vm_set_and_map() {
+    assert_eq!(run_vm("typeof Set + typeof Map"), Value::String("functionfunction".to_string()));
+    // Keys are compared with SameValueZero
+    let set = "let s = new Set(); s.add(NaN).add(NaN).add(0).add(-0).add('0'); let had = s.delete(0); had + s.size";
+    assert_eq!(run_vm(set), Value::Number(3.0));
+    assert_eq!(run_vm("let o = {}; let s = new Set([o, {}]); s.has(o) && !s.has({}) && s.size === 2"), Value::Boolean(true));
+    let map = "let m = new Map([['a', 1], ['b', 2]]); m.set('a', 5).set('c', 3); let r = ''; \
+               for (const [k, v] of m) { r = r + k + v; } r + m.size";
+    assert_eq!(run_vm(map), Value::String("a5b2c33".to_string()));
+    assert_eq!(run_vm("let m = new Map(); m.set(1, 'x'); m.get(1)"), Value::String("x".to_string()));
+    assert_eq!(run_vm("new Map().get('missing')"), Value::Undefined);
+    let for_each = "let r = 0; new Map([[1, 2], [3, 4]]).forEach((v, k) => { r = r * 100 + k * 10 + v; }); r";
+    assert_eq!(run_vm(for_each), Value::Number(1234.0));
+    assert_eq!(run_vm("let s = new Set([1, 2]); s.clear(); s.size"), Value::Number(0.0));
+    assert_eq!(run_vm("[...new Set('aab')].length"), Value::Number(2.0));
+    assert_eq!(
+        run_vm("try { new Map([1]); } catch (e) { e.message }"),
+        Value::String("Iterator value 1 is not an entry object".to_string())
+    );
+    assert_eq!(
+        run_vm("let o = { has: new Set().has }; try { o.has(1); } catch (e) { e.message }"),
+        Value::String("Method Set.prototype.has called on incompatible receiver [object Object]".to_string())
+    );
+}
+
+#[test]
+fn test_arrow_object_literal_body() {
+    assert_eq!(run_vm("const f = () => ({a: 1}); f().a"), Value::Number(1.0));
+    // Braces are a block: `a:` labels the expression statement `1`
+    assert_eq!(run_vm("const g = () => {a: 1}; g()"), Value::Undefined);
+}
+
+#[test]
+fn test_vm_compound_assignment() {
+    assert_eq!(run_vm("let a = [0]; a[0] += 5; a[0]"), Value::Number(5.0));
+    assert_eq!(run_vm("let a = [1, 2]; let i = 1; a[i] = 7; a[0] + a[1]"), Value::Number(8.0));
+    assert_eq!(run_vm("let o = {n: 10}; o.n -= 3; o.n *= 2"), Value::Number(14.0));
+    assert_eq!(run_vm("let x = 2; x += 3; x *= x; x"), Value::Number(25.0));
+    assert_eq!(run_vm("let s = 'a'; s += 'b'; s"), Value::String("ab".to_string()));
+    // The object and the index are evaluated once, not again for the store
+    let source = "let calls = {n: 0}; function next() { calls.n += 1; return 0; } \
+                  let a = [1]; a[next()] += 5; calls.n * 10 + a[0]";
+    assert_eq!(run_vm(source), Value::Number(16.0));
+    let source = "let calls = {n: 0}; let a = [1]; function arr() { calls.n += 1; return a; } \
+                  arr()[0] *= 4; calls.n * 10 + a[0]";
+    assert_eq!(run_vm(source), Value::Number(14.0));
+}
+
+#[test]
+fn test_vm_optimizer_preserves_results() {
+    let sources = [
+        "function f(n) { let x = n * 2; return x + 1; } f(4)",
+        "function sum(n) { let total = 0; for (let i = 0; i < n; i++) { total += i; } return total; } sum(5)",
+        "let o = {n: 1}; o.n = 5; o.n += 2; o.n",
+        "!true || !false",
+        "function f(flag) { if (!false) { return flag; } return 0; } f(3)",
+    ];
+    let mut saved = 0;
+    for source in sources {
+        let ast = Parser::new(source).parse().expect("parse error");
+        let (bytecode, constants) = Compiler::new().compile(&ast).expect("compile error");
+        let mut optimized = bytecode.clone();
+        Optimizer::new().optimize(&mut optimized, &constants);
+        let size = |bytecode: &v8_vm::Bytecode| {
+            bytecode.instructions.len()
+                + bytecode.functions.iter().map(|function| function.bytecode.instructions.len()).sum::<usize>()
+        };
+        saved += size(&bytecode) - size(&optimized);
+
+        let mut expected = Executor::new();
+        expected.execute(&bytecode, &constants);
+        let mut actual = Executor::new();
+        actual.execute(&optimized, &constants);
+        assert_eq!(actual.stack.values.last(), expected.stack.values.last(), "{}", source);
+    }
+    assert!(saved > 0);
+}
+
+#[test]
+fn test_template_literal_substitutions() {
+    let source = "let name = 'x'; let n = 2; `${name} = ${n + 1}!`";
+    assert_eq!(interpret(source).unwrap(), Value::String("x = 3!".to_string()));
+    assert_eq!(run_vm(source), Value::String("x = 3!".to_string()));
+    assert_eq!(interpret("`${1}${2}`").unwrap(), Value::String("12".to_string()));
+    assert_eq!(run_vm("`${1}${2}`"), Value::String("12".to_string()));
+    let source = "let o = {a: '}'}; `${o.a}${`[${o['a']}]`}`";
+    assert_eq!(interpret(source).unwrap(), Value::String("}[}]".to_string()));
+}
+
+#[test]
+fn test_to_primitive_dispatch() {
+    let mut engine = Engine::new();
+    assert_eq!(
+        engine.eval("let n = { valueOf: function () { return 42; } }; n + 0 === 42").unwrap(),
+        Value::Boolean(true)
+    );
+    assert_eq!(engine.eval("n * 2 + (n == 42) + (n > 41)").unwrap(), Value::Number(86.0));
+    // A string hint tries toString first
+    assert_eq!(
+        engine.eval("let s = { toString: function () { return 'str'; }, valueOf: function () { return 1; } }; `${s}` + String(s) + (s + '')").unwrap(),
+        Value::String("strstr1".to_string())
+    );
+
+    // Symbol.toPrimitive overrides both and receives the hint
+    engine
+        .eval("let p = { valueOf: function () { return 1; }, toString: function () { return 'x'; } }; \
+               p[Symbol.toPrimitive] = function (hint) { return hint; };")
+        .unwrap();
+    assert_eq!(engine.eval("`${p}|` + (p + '|') + String(p)").unwrap(), Value::String("string|default|string".to_string()));
+    assert!(engine.eval("p * 1").unwrap().to_number().is_nan()); // "number" is not numeric
+
+    // Conversions that yield no primitive are TypeErrors
+    let err = engine.eval("let bad = { valueOf: function () { return {}; }, toString: function () { return {}; } }; bad + 1").unwrap_err();
+    assert!(err.to_string().contains("Cannot convert object to primitive value"), "{}", err);
+    // Objects without these methods convert as before
+    assert_eq!(engine.eval("({}) + ''").unwrap(), Value::String("[object Object]".to_string()));
+    assert_eq!(engine.eval("new Number(5) + 1").unwrap(), Value::Number(6.0));
+}
+
+#[test]
+fn test_weak_ref_and_finalization_registry() {
+    let mut engine = Engine::new();
+    engine
+        .eval(
+            "let target = {}; let weak = new WeakRef(target); let log = []; \
+             let registry = new FinalizationRegistry(function (held) { log[log.length] = held; }); \
+             registry.register(target, 'target'); \
+             let kept = {}; registry.register(kept, 'kept', kept); registry.unregister(kept);",
+        )
+        .unwrap();
+    assert_eq!(engine.eval("weak.deref() === target").unwrap(), Value::Boolean(true));
+    // The cleanup callback runs with the microtasks of the script that dropped the target
+    assert_eq!(engine.eval("target = null; log.length").unwrap(), Value::Number(0.0));
+    assert_eq!(engine.eval("weak.deref()").unwrap(), Value::Undefined);
+    assert_eq!(engine.eval("log[0]").unwrap(), Value::String("target".to_string()));
+    // An unregistered target has no callback
+    assert_eq!(engine.eval("kept = null; log.length").unwrap(), Value::Number(1.0));
+    assert_eq!(engine.eval("log.length").unwrap(), Value::Number(1.0));
+}
@@ -0,0 +1,328 @@
+//! Builder functions for constructing AST nodes in code
+//!
+//! Each function returns a ready `Node`, hiding the `Box::new` and span
+//! boilerplate of the node structs. Nodes get no span of their own, except
+//! that binary-like nodes cover their first through last child when both
+//! carry one (see `Node::span_through`):
+//!
+//! ```
+//! use v8_ast::builder as ast;
+//!
+//! // let x = f(1 + 2);
+//! let program = ast::program(vec![ast::let_decl(
+//!     "x",
+//!     Some(ast::call(ast::ident("f"), vec![ast::bin("+", ast::num(1.0), ast::num(2.0))])),
+//! )]);
+//! assert_eq!(program.children().len(), 1);
+//! ```
+
+use crate::node::*;
+
+impl Node {
+    /// Compare two trees ignoring source locations
+    ///
+    /// Useful to check parser output, which carries spans, against a tree
+    /// built with this module.
+    pub fn structurally_eq(&self, other: &Node) -> bool {
+        match (serde_json::to_value(self), serde_json::to_value(other)) {
+            (Ok(mut a), Ok(mut b)) => {
+                strip_spans(&mut a);
+                strip_spans(&mut b);
+                a == b
+            }
+            _ => false,
+        }
+    }
+}
+
+fn strip_spans(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.remove("span");
+            map.values_mut().for_each(strip_spans);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_spans),
+        _ => {}
+    }
+}
+
+fn boxed(node: Node) -> Box<Node> {
+    Box::new(node)
+}
+
+fn identifiers(names: &[&str]) -> Vec<Node> {
+    names.iter().map(|name| ident(name)).collect()
+}
+
+// Literals and identifiers
+
+pub fn ident(name: &str) -> Node {
+    Node::Identifier(name.to_string())
+}
+
+pub fn num(value: f64) -> Node {
+    Node::Number(value)
+}
+
+pub fn string(value: &str) -> Node {
+    Node::String(value.to_string())
+}
+
+pub fn boolean(value: bool) -> Node {
+    Node::Boolean(value)
+}
+
+pub fn null() -> Node {
+    Node::Null
+}
+
+pub fn undefined() -> Node {
+    Node::Undefined
+}
+
+pub fn this() -> Node {
+    Node::This(ThisExpression { span: None })
+}
+
+/// Array literal without holes
+pub fn array(elements: Vec<Node>) -> Node {
+    Node::ArrayLiteral(ArrayLiteral {
+        elements: elements.into_iter().map(Some).collect(),
+        span: None,
+    })
+}
+
+/// Object literal with plain `key: value` properties
+pub fn object(properties: Vec<(&str, Node)>) -> Node {
+    Node::ObjectLiteral(ObjectLiteral {
+        properties: properties
+            .into_iter()
+            .map(|(key, value)| {
+                Node::Property(Property {
+                    key: boxed(ident(key)),
+                    value: boxed(value),
+                    kind: "init".to_string(),
+                    computed: false,
+                    method: false,
+                    shorthand: false,
+                    span: None,
+                })
+            })
+            .collect(),
+        span: None,
+    })
+}
+
+pub fn spread(argument: Node) -> Node {
+    Node::SpreadElement(SpreadElement { argument: boxed(argument), span: None })
+}
+
+// Expressions
+
+pub fn bin(operator: &str, left: Node, right: Node) -> Node {
+    let span = Node::span_through(&left, &right);
+    Node::BinaryExpression(BinaryExpression {
+        left: boxed(left),
+        operator: operator.to_string(),
+        right: boxed(right),
+        span,
+    })
+}
+
+pub fn logical(operator: &str, left: Node, right: Node) -> Node {
+    let span = Node::span_through(&left, &right);
+    Node::LogicalExpression(LogicalExpression {
+        left: boxed(left),
+        operator: operator.to_string(),
+        right: boxed(right),
+        span,
+    })
+}
+
+/// Prefix unary operation such as `!x` or `typeof x`
+pub fn unary(operator: &str, argument: Node) -> Node {
+    Node::UnaryExpression(UnaryExpression {
+        operator: operator.to_string(),
+        argument: boxed(argument),
+        prefix: true,
+        span: None,
+    })
+}
+
+pub fn update(operator: &str, argument: Node, prefix: bool) -> Node {
+    Node::UpdateExpression(UpdateExpression {
+        operator: operator.to_string(),
+        argument: boxed(argument),
+        prefix,
+        span: None,
+    })
+}
+
+pub fn assign(operator: &str, left: Node, right: Node) -> Node {
+    let span = Node::span_through(&left, &right);
+    Node::AssignmentExpression(AssignmentExpression {
+        left: boxed(left),
+        operator: operator.to_string(),
+        right: boxed(right),
+        span,
+    })
+}
+
+pub fn cond(test: Node, consequent: Node, alternate: Node) -> Node {
+    Node::ConditionalExpression(ConditionalExpression {
+        test: boxed(test),
+        consequent: boxed(consequent),
+        alternate: boxed(alternate),
+        span: None,
+    })
+}
+
+pub fn seq(expressions: Vec<Node>) -> Node {
+    let span = match (expressions.first(), expressions.last()) {
+        (Some(first), Some(last)) => Node::span_through(first, last),
+        _ => None,
+    };
+    Node::SequenceExpression(SequenceExpression { expressions, span })
+}
+
+pub fn paren(expression: Node) -> Node {
+    Node::ParenthesizedExpression(ParenthesizedExpression { expression: boxed(expression), span: None })
+}
+
+pub fn call(callee: Node, arguments: Vec<Node>) -> Node {
+    Node::CallExpression(CallExpression { callee: boxed(callee), arguments, span: None })
+}
+
+pub fn new(callee: Node, arguments: Vec<Node>) -> Node {
+    Node::NewExpression(NewExpression { callee: boxed(callee), arguments, span: None })
+}
+
+/// Static member access, `object.property`
+pub fn member(object: Node, property: &str) -> Node {
+    Node::MemberExpression(MemberExpression {
+        object: boxed(object),
+        property: boxed(ident(property)),
+        computed: false,
+        optional: false,
+        span: None,
+    })
+}
+
+/// Computed member access, `object[property]`
+pub fn index(object: Node, property: Node) -> Node {
+    Node::MemberExpression(MemberExpression {
+        object: boxed(object),
+        property: boxed(property),
+        computed: true,
+        optional: false,
+        span: None,
+    })
+}
+
+/// Anonymous function expression, or a named one when `name` is given
+pub fn function(name: Option<&str>, params: &[&str], body: Vec<Node>) -> Node {
+    Node::FunctionExpression(FunctionExpression {
+        id: name.map(|name| boxed(ident(name))),
+        params: identifiers(params),
+        body: boxed(block(body)),
+        generator: false,
+        r#async: false,
+        span: None,
+    })
+}
+
+/// Arrow function with an expression body, `(params) => body`
+pub fn arrow(params: &[&str], body: Node) -> Node {
+    Node::ArrowFunctionExpression(ArrowFunctionExpression {
+        params: identifiers(params),
+        body: boxed(body),
+        expression: true,
+        r#async: false,
+        span: None,
+    })
+}
+
+// Statements and declarations
+
+pub fn program(body: Vec<Node>) -> Node {
+    Node::Program(Program { body, source_type: "script".to_string(), span: None })
+}
+
+pub fn expr_stmt(expression: Node) -> Node {
+    Node::ExpressionStatement(ExpressionStatement { expression: boxed(expression), span: None })
+}
+
+/// Single-binding declaration of the given kind (`var`, `let` or `const`)
+pub fn var_decl(kind: &str, name: &str, init: Option<Node>) -> Node {
+    Node::VariableDeclaration(VariableDeclaration {
+        kind: kind.to_string(),
+        declarations: vec![VariableDeclarator {
+            id: boxed(ident(name)),
+            init: init.map(boxed),
+            span: None,
+        }],
+        span: None,
+    })
+}
+
+pub fn let_decl(name: &str, init: Option<Node>) -> Node {
+    var_decl("let", name, init)
+}
+
+pub fn const_decl(name: &str, init: Node) -> Node {
+    var_decl("const", name, Some(init))
+}
+
+pub fn function_decl(name: &str, params: &[&str], body: Vec<Node>) -> Node {
+    Node::FunctionDeclaration(FunctionDeclaration {
+        id: Some(boxed(ident(name))),
+        params: identifiers(params),
+        body: boxed(block(body)),
+        generator: false,
+        r#async: false,
+        span: None,
+    })
+}
+
+pub fn block(body: Vec<Node>) -> Node {
+    Node::BlockStatement(BlockStatement { body, span: None })
+}
+
+pub fn if_stmt(test: Node, consequent: Node, alternate: Option<Node>) -> Node {
+    Node::IfStatement(IfStatement {
+        test: boxed(test),
+        consequent: boxed(consequent),
+        alternate: alternate.map(boxed),
+        span: None,
+    })
+}
+
+pub fn while_stmt(test: Node, body: Node) -> Node {
+    Node::WhileStatement(WhileStatement { test: boxed(test), body: boxed(body), span: None })
+}
+
+pub fn for_stmt(init: Option<Node>, test: Option<Node>, update: Option<Node>, body: Node) -> Node {
+    Node::ForStatement(ForStatement {
+        init: init.map(boxed),
+        test: test.map(boxed),
+        update: update.map(boxed),
+        body: boxed(body),
+        span: None,
+    })
+}
+
+pub fn return_stmt(argument: Option<Node>) -> Node {
+    Node::ReturnStatement(ReturnStatement { argument: argument.map(boxed), span: None })
+}
+
+pub fn throw_stmt(argument: Node) -> Node {
+    Node::ThrowStatement(ThrowStatement { argument: boxed(argument), span: None })
+}
+
+pub fn break_stmt() -> Node {
+    Node::BreakStatement(BreakStatement { label: None, span: None })
+}
+
+pub fn continue_stmt() -> Node {
+    Node::ContinueStatement(ContinueStatement { label: None, span: None })
+}
@@ -0,0 +1,777 @@
+//! Code generation for V8-Rust JavaScript engine
+//!
+//! Pretty-prints an AST back to JavaScript source. While emitting, the
+//! generator records a mapping segment for every statement that carries a
+//! span, linking its position in the output to its original position.
+
+use crate::*;
+
+/// Links a position in generated source to a position in the original source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MappingSegment {
+    pub generated: Position,
+    pub original: Position,
+}
+
+/// Generated source together with its mapping segments
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedCode {
+    pub code: String,
+    pub mappings: Vec<MappingSegment>,
+}
+
+/// Generate JavaScript source for `node`
+pub fn generate(node: &Node) -> String {
+    generate_with_source_map(node).code
+}
+
+/// Generate JavaScript source for `node` along with per-statement mappings
+pub fn generate_with_source_map(node: &Node) -> GeneratedCode {
+    let mut generator = CodeGenerator::new();
+    generator.emit_node(node);
+    generator.finish()
+}
+
+/// Pretty-printer that tracks the current output position
+#[derive(Debug)]
+pub struct CodeGenerator {
+    output: String,
+    mappings: Vec<MappingSegment>,
+    indent: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Default for CodeGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CodeGenerator {
+    /// Create a new code generator
+    pub fn new() -> Self {
+        Self {
+            output: String::new(),
+            mappings: Vec::new(),
+            indent: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    /// Consume the generator, returning the emitted code and mappings
+    pub fn finish(self) -> GeneratedCode {
+        GeneratedCode {
+            code: self.output,
+            mappings: self.mappings,
+        }
+    }
+
+    /// Emit a node: statements in statement position, anything else as an expression
+    pub fn emit_node(&mut self, node: &Node) {
+        match node {
+            Node::Program(_)
+            | Node::VariableDeclaration(_)
+            | Node::FunctionDeclaration(_)
+            | Node::ClassDeclaration(_)
+            | Node::ImportDeclaration(_)
+            | Node::ExportDeclaration(_)
+            | Node::BlockStatement(_)
+            | Node::IfStatement(_)
+            | Node::ForStatement(_)
+            | Node::ForOfStatement(_)
+            | Node::ForInStatement(_)
+            | Node::WhileStatement(_)
+            | Node::DoWhileStatement(_)
+            | Node::SwitchStatement(_)
+            | Node::TryStatement(_)
+            | Node::ThrowStatement(_)
+            | Node::ReturnStatement(_)
+            | Node::BreakStatement(_)
+            | Node::ContinueStatement(_)
+            | Node::LabeledStatement(_)
+            | Node::WithStatement(_)
+            | Node::DebuggerStatement(_)
+            | Node::ExpressionStatement(_) => self.statement(node),
+            _ => self.expression(node, 0),
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        for c in text.chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.output.push_str(text);
+    }
+
+    fn newline(&mut self) {
+        self.write("\n");
+        let indent = "    ".repeat(self.indent);
+        self.write(&indent);
+    }
+
+    fn add_mapping(&mut self, node: &Node) {
+        if let Some(span) = node.span() {
+            self.mappings.push(MappingSegment {
+                generated: Position::new(self.line, self.column),
+                original: span.start,
+            });
+        }
+    }
+
+    fn statement(&mut self, node: &Node) {
+        if !matches!(node, Node::Program(_)) {
+            self.add_mapping(node);
+        }
+        match node {
+            Node::Program(program) => {
+                for statement in &program.body {
+                    self.statement(statement);
+                    self.write("\n");
+                }
+            }
+            Node::VariableDeclaration(decl) => {
+                self.variable_declaration(decl);
+                self.write(";");
+            }
+            Node::FunctionDeclaration(decl) => {
+                self.function(decl.r#async, decl.generator, decl.id.as_deref(), &decl.params, &decl.body);
+            }
+            Node::ClassDeclaration(decl) => {
+                self.class(decl.id.as_deref(), decl.super_class.as_deref(), &decl.body);
+            }
+            Node::ImportDeclaration(decl) => {
+                self.write("import ");
+                if !decl.specifiers.is_empty() {
+                    self.write("{ ");
+                    self.comma_separated(&decl.specifiers);
+                    self.write(" } from ");
+                }
+                self.expression(&decl.source, 0);
+                self.write(";");
+            }
+            Node::ExportDeclaration(decl) => {
+                self.write("export ");
+                if decl.default {
+                    self.write("default ");
+                }
+                match &decl.declaration {
+                    Some(declaration) => self.emit_node(declaration),
+                    None => {
+                        self.write("{ ");
+                        self.comma_separated(&decl.specifiers);
+                        self.write(" }");
+                        if let Some(source) = &decl.source {
+                            self.write(" from ");
+                            self.expression(source, 0);
+                        }
+                        self.write(";");
+                    }
+                }
+            }
+            Node::BlockStatement(block) => self.block(&block.body),
+            Node::IfStatement(stmt) => {
+                self.write("if (");
+                self.expression(&stmt.test, 0);
+                self.write(") ");
+                self.statement(&stmt.consequent);
+                if let Some(alternate) = &stmt.alternate {
+                    self.write(" else ");
+                    self.statement(alternate);
+                }
+            }
+            Node::ForStatement(stmt) => {
+                self.write("for (");
+                match stmt.init.as_deref() {
+                    Some(Node::VariableDeclaration(decl)) => self.variable_declaration(decl),
+                    Some(init) => self.expression(init, 0),
+                    None => {}
+                }
+                self.write(";");
+                if let Some(test) = &stmt.test {
+                    self.write(" ");
+                    self.expression(test, 0);
+                }
+                self.write(";");
+                if let Some(update) = &stmt.update {
+                    self.write(" ");
+                    self.expression(update, 0);
+                }
+                self.write(") ");
+                self.statement(&stmt.body);
+            }
+            Node::ForOfStatement(stmt) => {
+                self.write(if stmt.r#await { "for await (" } else { "for (" });
+                match stmt.left.as_ref() {
+                    Node::VariableDeclaration(decl) => self.variable_declaration(decl),
+                    left => self.expression(left, 0),
+                }
+                self.write(" of ");
+                self.expression(&stmt.right, ASSIGNMENT);
+                self.write(") ");
+                self.statement(&stmt.body);
+            }
+            Node::ForInStatement(stmt) => {
+                self.write("for (");
+                match stmt.left.as_ref() {
+                    Node::VariableDeclaration(decl) => self.variable_declaration(decl),
+                    left => self.expression(left, 0),
+                }
+                self.write(" in ");
+                self.expression(&stmt.right, 0);
+                self.write(") ");
+                self.statement(&stmt.body);
+            }
+            Node::WhileStatement(stmt) => {
+                self.write("while (");
+                self.expression(&stmt.test, 0);
+                self.write(") ");
+                self.statement(&stmt.body);
+            }
+            Node::DoWhileStatement(stmt) => {
+                self.write("do ");
+                self.statement(&stmt.body);
+                self.write(" while (");
+                self.expression(&stmt.test, 0);
+                self.write(");");
+            }
+            Node::SwitchStatement(stmt) => {
+                self.write("switch (");
+                self.expression(&stmt.discriminant, 0);
+                self.write(") {");
+                self.indent += 1;
+                for case in &stmt.cases {
+                    self.newline();
+                    match &case.test {
+                        Some(test) => {
+                            self.write("case ");
+                            self.expression(test, 0);
+                            self.write(":");
+                        }
+                        None => self.write("default:"),
+                    }
+                    self.indent += 1;
+                    for statement in &case.consequent {
+                        self.newline();
+                        self.statement(statement);
+                    }
+                    self.indent -= 1;
+                }
+                self.indent -= 1;
+                self.newline();
+                self.write("}");
+            }
+            Node::TryStatement(stmt) => {
+                self.write("try ");
+                self.statement(&stmt.block);
+                if let Some(handler) = &stmt.handler {
+                    self.write(" ");
+                    self.expression(handler, 0);
+                }
+                if let Some(finalizer) = &stmt.finalizer {
+                    self.write(" finally ");
+                    self.statement(finalizer);
+                }
+            }
+            Node::ThrowStatement(stmt) => {
+                self.write("throw ");
+                self.expression(&stmt.argument, 0);
+                self.write(";");
+            }
+            Node::ReturnStatement(stmt) => {
+                self.write("return");
+                if let Some(argument) = &stmt.argument {
+                    self.write(" ");
+                    self.expression(argument, 0);
+                }
+                self.write(";");
+            }
+            Node::BreakStatement(stmt) => self.jump("break", stmt.label.as_deref()),
+            Node::ContinueStatement(stmt) => self.jump("continue", stmt.label.as_deref()),
+            Node::LabeledStatement(stmt) => {
+                self.expression(&stmt.label, 0);
+                self.write(": ");
+                self.statement(&stmt.body);
+            }
+            Node::WithStatement(stmt) => {
+                self.write("with (");
+                self.expression(&stmt.object, 0);
+                self.write(") ");
+                self.statement(&stmt.body);
+            }
+            Node::DebuggerStatement(_) => self.write("debugger;"),
+            Node::ExpressionStatement(stmt) => {
+                // `{`, `function` and `class` would start a declaration instead
+                let ambiguous = matches!(
+                    stmt.expression.as_ref(),
+                    Node::ObjectLiteral(_) | Node::FunctionExpression(_) | Node::ClassExpression(_)
+                );
+                if ambiguous {
+                    self.write("(");
+                    self.expression(&stmt.expression, 0);
+                    self.write(")");
+                } else {
+                    self.expression(&stmt.expression, 0);
+                }
+                self.write(";");
+            }
+            expression => {
+                self.expression(expression, 0);
+                self.write(";");
+            }
+        }
+    }
+
+    fn block(&mut self, body: &[Node]) {
+        self.write("{");
+        if body.is_empty() {
+            self.write("}");
+            return;
+        }
+        self.indent += 1;
+        for statement in body {
+            self.newline();
+            self.statement(statement);
+        }
+        self.indent -= 1;
+        self.newline();
+        self.write("}");
+    }
+
+    fn jump(&mut self, keyword: &str, label: Option<&Node>) {
+        self.write(keyword);
+        if let Some(label) = label {
+            self.write(" ");
+            self.expression(label, 0);
+        }
+        self.write(";");
+    }
+
+    fn variable_declaration(&mut self, decl: &VariableDeclaration) {
+        self.write(&decl.kind);
+        self.write(" ");
+        for (i, declarator) in decl.declarations.iter().enumerate() {
+            if i > 0 {
+                self.write(", ");
+            }
+            self.expression(&declarator.id, 0);
+            if let Some(init) = &declarator.init {
+                self.write(" = ");
+                self.expression(init, ASSIGNMENT);
+            }
+        }
+    }
+
+    fn function(&mut self, is_async: bool, generator: bool, id: Option<&Node>, params: &[Node], body: &Node) {
+        if is_async {
+            self.write("async ");
+        }
+        self.write(if generator { "function* " } else { "function " });
+        if let Some(id) = id {
+            self.expression(id, 0);
+        }
+        self.parameters(params);
+        self.write(" ");
+        self.statement(body);
+    }
+
+    fn class(&mut self, id: Option<&Node>, super_class: Option<&Node>, body: &Node) {
+        self.write("class ");
+        if let Some(id) = id {
+            self.expression(id, 0);
+            self.write(" ");
+        }
+        if let Some(super_class) = super_class {
+            self.write("extends ");
+            self.expression(super_class, CALL);
+            self.write(" ");
+        }
+        self.statement(body);
+    }
+
+    fn parameters(&mut self, params: &[Node]) {
+        self.write("(");
+        self.comma_separated(params);
+        self.write(")");
+    }
+
+    fn comma_separated(&mut self, nodes: &[Node]) {
+        for (i, node) in nodes.iter().enumerate() {
+            if i > 0 {
+                self.write(", ");
+            }
+            self.expression(node, ASSIGNMENT);
+        }
+    }
+
+    fn elements(&mut self, elements: &[Option<Node>]) {
+        self.write("[");
+        for (i, element) in elements.iter().enumerate() {
+            if i > 0 {
+                self.write(", ");
+            }
+            match element {
+                Some(element) => self.expression(element, ASSIGNMENT),
+                // A trailing hole needs its own comma to survive
+                None if i + 1 == elements.len() => self.write(","),
+                None => {}
+            }
+        }
+        self.write("]");
+    }
+
+    fn properties(&mut self, properties: &[Node]) {
+        if properties.is_empty() {
+            self.write("{}");
+            return;
+        }
+        self.write("{ ");
+        self.comma_separated(properties);
+        self.write(" }");
+    }
+
+    fn property(&mut self, prop: &Property) {
+        if prop.shorthand {
+            // `{ a }` or, in patterns, `{ a = 1 }`
+            self.expression(&prop.value, ASSIGNMENT);
+            return;
+        }
+        let method = match prop.value.as_ref() {
+            Node::FunctionExpression(func) if prop.method || prop.kind != "init" => Some(func),
+            _ => None,
+        };
+        if let Some(func) = method {
+            match prop.kind.as_str() {
+                "get" => self.write("get "),
+                "set" => self.write("set "),
+                _ => {
+                    if func.r#async {
+                        self.write("async ");
+                    }
+                    if func.generator {
+                        self.write("*");
+                    }
+                }
+            }
+        }
+        self.property_key(&prop.key, prop.computed);
+        match method {
+            Some(func) => {
+                self.parameters(&func.params);
+                self.write(" ");
+                self.statement(&func.body);
+            }
+            None => {
+                self.write(": ");
+                self.expression(&prop.value, ASSIGNMENT);
+            }
+        }
+    }
+
+    fn property_key(&mut self, key: &Node, computed: bool) {
+        if computed {
+            self.write("[");
+            self.expression(key, ASSIGNMENT);
+            self.write("]");
+        } else {
+            self.expression(key, 0);
+        }
+    }
+
+    fn template(&mut self, literal: &TemplateLiteral) {
+        self.write("`");
+        for (i, quasi) in literal.quasis.iter().enumerate() {
+            self.write(&quasi.raw);
+            if let Some(expression) = literal.expressions.get(i) {
+                self.write("${");
+                self.expression(expression, 0);
+                self.write("}");
+            }
+        }
+        self.write("`");
+    }
+
+    /// Emit an expression, parenthesizing it when it binds looser than `min`
+    fn expression(&mut self, node: &Node, min: u8) {
+        let wrap = precedence(node) < min;
+        if wrap {
+            self.write("(");
+        }
+        match node {
+            Node::BinaryExpression(expr) => {
+                self.binary(&expr.left, &expr.operator, &expr.right);
+            }
+            Node::LogicalExpression(expr) => {
+                self.binary(&expr.left, &expr.operator, &expr.right);
+            }
+            Node::UnaryExpression(expr) => {
+                self.write(&expr.operator);
+                let needs_space = expr.operator.chars().all(char::is_alphabetic)
+                    || matches!(
+                        expr.argument.as_ref(),
+                        Node::UnaryExpression(UnaryExpression { operator, .. })
+                        | Node::UpdateExpression(UpdateExpression { operator, prefix: true, .. })
+                            if operator.starts_with(expr.operator.as_str())
+                    );
+                if needs_space {
+                    self.write(" ");
+                }
+                self.expression(&expr.argument, UNARY);
+            }
+            Node::UpdateExpression(expr) => {
+                if expr.prefix {
+                    self.write(&expr.operator);
+                    self.expression(&expr.argument, POSTFIX);
+                } else {
+                    self.expression(&expr.argument, POSTFIX);
+                    self.write(&expr.operator);
+                }
+            }
+            Node::AssignmentExpression(expr) => {
+                self.expression(&expr.left, CALL);
+                self.write(" ");
+                self.write(&expr.operator);
+                self.write(" ");
+                self.expression(&expr.right, ASSIGNMENT);
+            }
+            Node::ConditionalExpression(expr) => {
+                self.expression(&expr.test, CONDITIONAL + 1);
+                self.write(" ? ");
+                self.expression(&expr.consequent, ASSIGNMENT);
+                self.write(" : ");
+                self.expression(&expr.alternate, ASSIGNMENT);
+            }
+            Node::CallExpression(expr) => {
+                self.expression(&expr.callee, CALL);
+                self.write("(");
+                self.comma_separated(&expr.arguments);
+                self.write(")");
+            }
+            Node::NewExpression(expr) => {
+                self.write("new ");
+                // A call inside the callee would otherwise take the arguments
+                if matches!(expr.callee.as_ref(), Node::CallExpression(_)) {
+                    self.write("(");
+                    self.expression(&expr.callee, 0);
+                    self.write(")");
+                } else {
+                    self.expression(&expr.callee, CALL);
+                }
+                self.write("(");
+                self.comma_separated(&expr.arguments);
+                self.write(")");
+            }
+            Node::MemberExpression(expr) => {
+                if matches!(expr.object.as_ref(), Node::Number(_) | Node::NumericLiteral(_)) {
+                    self.write("(");
+                    self.expression(&expr.object, 0);
+                    self.write(")");
+                } else {
+                    self.expression(&expr.object, CALL);
+                }
+                if expr.optional {
+                    self.write("?.");
+                } else if !expr.computed {
+                    self.write(".");
+                }
+                if expr.computed {
+                    self.write("[");
+                    self.expression(&expr.property, 0);
+                    self.write("]");
+                } else {
+                    self.expression(&expr.property, 0);
+                }
+            }
+            Node::ArrowFunctionExpression(expr) => {
+                if expr.r#async {
+                    self.write("async ");
+                }
+                self.parameters(&expr.params);
+                self.write(" => ");
+                match expr.body.as_ref() {
+                    Node::BlockStatement(_) => self.statement(&expr.body),
+                    Node::ObjectLiteral(_) => {
+                        self.write("(");
+                        self.expression(&expr.body, 0);
+                        self.write(")");
+                    }
+                    body => self.expression(body, ASSIGNMENT),
+                }
+            }
+            Node::FunctionExpression(expr) => {
+                self.function(expr.r#async, expr.generator, expr.id.as_deref(), &expr.params, &expr.body);
+            }
+            Node::ClassExpression(expr) => {
+                self.class(expr.id.as_deref(), expr.super_class.as_deref(), &expr.body);
+            }
+            Node::YieldExpression(expr) => {
+                self.write(if expr.delegate { "yield*" } else { "yield" });
+                if let Some(argument) = &expr.argument {
+                    self.write(" ");
+                    self.expression(argument, ASSIGNMENT);
+                }
+            }
+            Node::AwaitExpression(expr) => {
+                self.write("await ");
+                self.expression(&expr.argument, UNARY);
+            }
+            Node::SequenceExpression(expr) => self.comma_separated(&expr.expressions),
+            Node::ParenthesizedExpression(expr) => {
+                self.write("(");
+                self.expression(&expr.expression, 0);
+                self.write(")");
+            }
+            Node::CatchClause(clause) => {
+                self.write("catch (");
+                self.expression(&clause.param, 0);
+                self.write(") ");
+                self.statement(&clause.body);
+            }
+            Node::ArrayLiteral(lit) => self.elements(&lit.elements),
+            Node::ArrayPattern(pattern) => self.elements(&pattern.elements),
+            Node::ObjectLiteral(lit) => self.properties(&lit.properties),
+            Node::ObjectPattern(pattern) => self.properties(&pattern.properties),
+            Node::Property(prop) => self.property(prop),
+            Node::TemplateLiteral(lit) => self.template(lit),
+            Node::TaggedTemplateExpression(expr) => {
+                self.expression(&expr.tag, CALL);
+                self.expression(&expr.quasi, 0);
+            }
+            Node::SpreadElement(elem) => {
+                self.write("...");
+                self.expression(&elem.argument, ASSIGNMENT);
+            }
+            Node::RestElement(elem) => {
+                self.write("...");
+                self.expression(&elem.argument, ASSIGNMENT);
+            }
+            Node::AssignmentPattern(pattern) => {
+                self.expression(&pattern.left, CALL);
+                self.write(" = ");
+                self.expression(&pattern.right, ASSIGNMENT);
+            }
+            Node::Super(_) => self.write("super"),
+            Node::MetaProperty(prop) => {
+                self.expression(&prop.meta, 0);
+                self.write(".");
+                self.expression(&prop.property, 0);
+            }
+            Node::Identifier(name) => self.write(name),
+            Node::Number(n) => self.write(&format_number(*n)),
+            Node::NumericLiteral(lit) => self.write(&lit.raw),
+            Node::String(s) => {
+                let quoted = quote(s);
+                self.write(&quoted);
+            }
+            Node::Boolean(b) => self.write(if *b { "true" } else { "false" }),
+            Node::Null => self.write("null"),
+            Node::Undefined => self.write("undefined"),
+            Node::This(_) => self.write("this"),
+            Node::RegExp(re) => {
+                let literal = format!("/{}/{}", re.pattern, re.flags);
+                self.write(&literal);
+            }
+            Node::BigInt(digits) => {
+                let literal = format!("{}n", digits.trim_end_matches('n'));
+                self.write(&literal);
+            }
+            statement => self.statement(statement),
+        }
+        if wrap {
+            self.write(")");
+        }
+    }
+
+    fn binary(&mut self, left: &Node, operator: &str, right: &Node) {
+        let prec = binary_precedence(operator);
+        // `**` is right-associative; everything else associates to the left
+        let (left_min, right_min) = if operator == "**" { (prec + 1, prec) } else { (prec, prec + 1) };
+        self.expression(left, left_min);
+        self.write(" ");
+        self.write(operator);
+        self.write(" ");
+        self.expression(right, right_min);
+    }
+}
+
+// Binding strength of expressions, loosest first
+const SEQUENCE: u8 = 1;
+const ASSIGNMENT: u8 = 2;
+const CONDITIONAL: u8 = 3;
+const UNARY: u8 = 15;
+const POSTFIX: u8 = 16;
+const CALL: u8 = 17;
+const PRIMARY: u8 = 18;
+
+fn binary_precedence(operator: &str) -> u8 {
+    match operator {
+        "??" | "||" => 4,
+        "&&" => 5,
+        "|" => 6,
+        "^" => 7,
+        "&" => 8,
+        "==" | "!=" | "===" | "!==" => 9,
+        "<" | ">" | "<=" | ">=" | "instanceof" | "in" => 10,
+        "<<" | ">>" | ">>>" => 11,
+        "+" | "-" => 12,
+        "*" | "/" | "%" => 13,
+        "**" => 14,
+        _ => 4,
+    }
+}
+
+fn precedence(node: &Node) -> u8 {
+    match node {
+        Node::SequenceExpression(_) => SEQUENCE,
+        Node::AssignmentExpression(_)
+        | Node::ArrowFunctionExpression(_)
+        | Node::YieldExpression(_) => ASSIGNMENT,
+        Node::ConditionalExpression(_) => CONDITIONAL,
+        Node::BinaryExpression(expr) => binary_precedence(&expr.operator),
+        Node::LogicalExpression(expr) => binary_precedence(&expr.operator),
+        Node::UnaryExpression(_) | Node::AwaitExpression(_) => UNARY,
+        Node::UpdateExpression(expr) if expr.prefix => UNARY,
+        Node::UpdateExpression(_) => POSTFIX,
+        Node::CallExpression(_)
+        | Node::NewExpression(_)
+        | Node::MemberExpression(_)
+        | Node::TaggedTemplateExpression(_) => CALL,
+        _ => PRIMARY,
+    }
+}
+
+/// Canonical source text of a number, as `Node::Number` is printed
+pub fn format_number(n: f64) -> String {
+    if n.is_nan() {
+        "NaN".to_string()
+    } else if n.is_infinite() {
+        if n > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else {
+        n.to_string()
+    }
+}
+
+/// Quote a string as a double-quoted JavaScript string literal
+fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
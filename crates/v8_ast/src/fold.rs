@@ -0,0 +1,210 @@
+//! Fold (reduce) utility for AST traversal
+//! 
+//! Complements the push-based `Visitor` with a pull-based helper for
+//! computing aggregate values over a tree.
+
+use crate::Node;
+
+impl Node {
+    /// Direct child nodes, in source order
+    pub fn children(&self) -> Vec<&Node> {
+        let mut children: Vec<&Node> = Vec::new();
+        match self {
+            Node::Program(program) => children.extend(&program.body),
+            Node::VariableDeclaration(decl) => {
+                for var_decl in &decl.declarations {
+                    children.push(&var_decl.id);
+                    children.extend(var_decl.init.as_deref());
+                }
+            }
+            Node::FunctionDeclaration(decl) => {
+                children.extend(decl.id.as_deref());
+                children.extend(&decl.params);
+                children.push(&decl.body);
+            }
+            Node::ClassDeclaration(decl) => {
+                children.extend(decl.id.as_deref());
+                children.extend(decl.super_class.as_deref());
+                children.push(&decl.body);
+            }
+            Node::BinaryExpression(expr) => {
+                children.push(&expr.left);
+                children.push(&expr.right);
+            }
+            Node::UnaryExpression(expr) => children.push(&expr.argument),
+            Node::CallExpression(expr) => {
+                children.push(&expr.callee);
+                children.extend(&expr.arguments);
+            }
+            Node::NewExpression(expr) => {
+                children.push(&expr.callee);
+                children.extend(&expr.arguments);
+            }
+            Node::MemberExpression(expr) => {
+                children.push(&expr.object);
+                children.push(&expr.property);
+            }
+            Node::AssignmentExpression(expr) => {
+                children.push(&expr.left);
+                children.push(&expr.right);
+            }
+            Node::ConditionalExpression(expr) => {
+                children.push(&expr.test);
+                children.push(&expr.consequent);
+                children.push(&expr.alternate);
+            }
+            Node::LogicalExpression(expr) => {
+                children.push(&expr.left);
+                children.push(&expr.right);
+            }
+            Node::UpdateExpression(expr) => children.push(&expr.argument),
+            Node::ArrowFunctionExpression(expr) => {
+                children.extend(&expr.params);
+                children.push(&expr.body);
+            }
+            Node::FunctionExpression(expr) => {
+                children.extend(expr.id.as_deref());
+                children.extend(&expr.params);
+                children.push(&expr.body);
+            }
+            Node::ClassExpression(expr) => {
+                children.extend(expr.id.as_deref());
+                children.extend(expr.super_class.as_deref());
+                children.push(&expr.body);
+            }
+            Node::YieldExpression(expr) => children.extend(expr.argument.as_deref()),
+            Node::AwaitExpression(expr) => children.push(&expr.argument),
+            Node::SequenceExpression(expr) => children.extend(&expr.expressions),
+            Node::ParenthesizedExpression(expr) => children.push(&expr.expression),
+            Node::BlockStatement(stmt) => children.extend(&stmt.body),
+            Node::IfStatement(stmt) => {
+                children.push(&stmt.test);
+                children.push(&stmt.consequent);
+                children.extend(stmt.alternate.as_deref());
+            }
+            Node::ForStatement(stmt) => {
+                children.extend(stmt.init.as_deref());
+                children.extend(stmt.test.as_deref());
+                children.extend(stmt.update.as_deref());
+                children.push(&stmt.body);
+            }
+            Node::ForOfStatement(stmt) => {
+                children.push(&stmt.left);
+                children.push(&stmt.right);
+                children.push(&stmt.body);
+            }
+            Node::ForInStatement(stmt) => {
+                children.push(&stmt.left);
+                children.push(&stmt.right);
+                children.push(&stmt.body);
+            }
+            Node::WhileStatement(stmt) => {
+                children.push(&stmt.test);
+                children.push(&stmt.body);
+            }
+            Node::DoWhileStatement(stmt) => {
+                children.push(&stmt.body);
+                children.push(&stmt.test);
+            }
+            Node::SwitchStatement(stmt) => {
+                children.push(&stmt.discriminant);
+                for case in &stmt.cases {
+                    children.extend(case.test.as_deref());
+                    children.extend(&case.consequent);
+                }
+            }
+            Node::TryStatement(stmt) => {
+                children.push(&stmt.block);
+                children.extend(stmt.handler.as_deref());
+                children.extend(stmt.finalizer.as_deref());
+            }
+            Node::CatchClause(clause) => {
+                children.push(&clause.param);
+                children.push(&clause.body);
+            }
+            Node::ThrowStatement(stmt) => children.push(&stmt.argument),
+            Node::ReturnStatement(stmt) => children.extend(stmt.argument.as_deref()),
+            Node::BreakStatement(stmt) => children.extend(stmt.label.as_deref()),
+            Node::ContinueStatement(stmt) => children.extend(stmt.label.as_deref()),
+            Node::LabeledStatement(stmt) => {
+                children.push(&stmt.label);
+                children.push(&stmt.body);
+            }
+            Node::WithStatement(stmt) => {
+                children.push(&stmt.object);
+                children.push(&stmt.body);
+            }
+            Node::ExpressionStatement(stmt) => children.push(&stmt.expression),
+            Node::ArrayLiteral(lit) => children.extend(lit.elements.iter().flatten()),
+            Node::ObjectLiteral(lit) => children.extend(&lit.properties),
+            Node::TemplateLiteral(lit) => children.extend(&lit.expressions),
+            Node::TaggedTemplateExpression(expr) => {
+                children.push(&expr.tag);
+                children.push(&expr.quasi);
+            }
+            Node::Property(prop) => {
+                children.push(&prop.key);
+                children.push(&prop.value);
+            }
+            Node::SpreadElement(elem) => children.push(&elem.argument),
+            Node::RestElement(elem) => children.push(&elem.argument),
+            Node::ObjectPattern(pattern) => children.extend(&pattern.properties),
+            Node::ArrayPattern(pattern) => children.extend(pattern.elements.iter().flatten()),
+            Node::AssignmentPattern(pattern) => {
+                children.push(&pattern.left);
+                children.push(&pattern.right);
+            }
+            Node::MetaProperty(prop) => {
+                children.push(&prop.meta);
+                children.push(&prop.property);
+            }
+            Node::ImportDeclaration(decl) => {
+                children.extend(&decl.specifiers);
+                children.push(&decl.source);
+            }
+            Node::ExportDeclaration(decl) => {
+                children.extend(decl.declaration.as_deref());
+                children.extend(&decl.specifiers);
+                children.extend(decl.source.as_deref());
+            }
+            Node::DebuggerStatement(_)
+            | Node::Super(_)
+            | Node::Identifier(_)
+            | Node::Number(_)
+            | Node::NumericLiteral(_)
+            | Node::String(_)
+            | Node::Boolean(_)
+            | Node::Null
+            | Node::Undefined
+            | Node::This(_)
+            | Node::RegExp(_)
+            | Node::BigInt(_) => {}
+        }
+        children
+    }
+
+    /// Fold over this node and all its descendants in pre-order
+    pub fn fold<T, F>(&self, init: T, mut f: F) -> T
+    where
+        F: FnMut(T, &Node) -> T,
+    {
+        self.fold_with_depth(init, |acc, node, _depth| f(acc, node))
+    }
+
+    /// Fold in pre-order, also passing each node's depth (the root is at depth 1)
+    pub fn fold_with_depth<T, F>(&self, init: T, mut f: F) -> T
+    where
+        F: FnMut(T, &Node, usize) -> T,
+    {
+        let mut acc = init;
+        let mut stack = vec![(self, 1)];
+        while let Some((node, depth)) = stack.pop() {
+            acc = f(acc, node, depth);
+            // Push in reverse so children are visited left to right
+            for child in node.children().into_iter().rev() {
+                stack.push((child, depth + 1));
+            }
+        }
+        acc
+    }
+}
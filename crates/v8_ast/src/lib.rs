@@ -5,7 +5,12 @@
 //! - Serialization support
 //! - Visitor pattern support
 //! - Source location tracking
+//! - Source generation with source-map segments
+//! - Builder functions for constructing trees in code
 
+pub mod builder;
+pub mod codegen;
+pub mod fold;
 pub mod node;
 pub mod visitor;
 
@@ -4,7 +4,9 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Represents a position in the source code
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Positions order by line, then by column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Position {
     pub line: usize,
     pub column: usize,
@@ -46,6 +48,11 @@ impl Span {
             end: Position::new(end_line, end_col),
         }
     }
+    
+    /// Smallest span covering both `a` and `b`
+    pub fn merge(a: &Span, b: &Span) -> Span {
+        Span::new(a.start.min(b.start), a.end.max(b.end))
+    }
 }
 
 /// Main AST node enum containing all possible node types
@@ -76,11 +83,15 @@ pub enum Node {
     ClassExpression(ClassExpression),
     YieldExpression(YieldExpression),
     AwaitExpression(AwaitExpression),
+    SequenceExpression(SequenceExpression),
+    ParenthesizedExpression(ParenthesizedExpression), // Only produced when the parser preserves parentheses
     
     // Statements
     BlockStatement(BlockStatement),
     IfStatement(IfStatement),
     ForStatement(ForStatement),
+    ForOfStatement(ForOfStatement),
+    ForInStatement(ForInStatement),
     WhileStatement(WhileStatement),
     DoWhileStatement(DoWhileStatement),
     SwitchStatement(SwitchStatement),
@@ -105,19 +116,101 @@ pub enum Node {
     Property(Property),
     SpreadElement(SpreadElement),
     RestElement(RestElement),
+    ObjectPattern(ObjectPattern),
+    ArrayPattern(ArrayPattern),
+    AssignmentPattern(AssignmentPattern),
     Super(Super),
     MetaProperty(MetaProperty),
     Identifier(String),
     Number(f64),
+    NumericLiteral(NumericLiteral), // Number whose source text is not its canonical form
     String(String),
     Boolean(bool),
     Null,
     Undefined,
-    This,
+    This(ThisExpression),
     RegExp(RegExp),
     BigInt(String),
 }
 
+impl Node {
+    /// Span of a composite node, from its first child through its last
+    ///
+    /// `None` unless both children have a span; identifiers and plain
+    /// literals never do.
+    pub fn span_through(first: &Node, last: &Node) -> Option<Span> {
+        Some(Span::merge(first.span()?, last.span()?))
+    }
+    
+    /// Source span of this node, if the parser recorded one
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            Node::Program(node) => node.span.as_ref(),
+            Node::VariableDeclaration(node) => node.span.as_ref(),
+            Node::FunctionDeclaration(node) => node.span.as_ref(),
+            Node::ClassDeclaration(node) => node.span.as_ref(),
+            Node::ImportDeclaration(node) => node.span.as_ref(),
+            Node::ExportDeclaration(node) => node.span.as_ref(),
+            Node::BinaryExpression(node) => node.span.as_ref(),
+            Node::UnaryExpression(node) => node.span.as_ref(),
+            Node::CallExpression(node) => node.span.as_ref(),
+            Node::NewExpression(node) => node.span.as_ref(),
+            Node::MemberExpression(node) => node.span.as_ref(),
+            Node::AssignmentExpression(node) => node.span.as_ref(),
+            Node::ConditionalExpression(node) => node.span.as_ref(),
+            Node::LogicalExpression(node) => node.span.as_ref(),
+            Node::UpdateExpression(node) => node.span.as_ref(),
+            Node::ArrowFunctionExpression(node) => node.span.as_ref(),
+            Node::FunctionExpression(node) => node.span.as_ref(),
+            Node::ClassExpression(node) => node.span.as_ref(),
+            Node::YieldExpression(node) => node.span.as_ref(),
+            Node::AwaitExpression(node) => node.span.as_ref(),
+            Node::SequenceExpression(node) => node.span.as_ref(),
+            Node::ParenthesizedExpression(node) => node.span.as_ref(),
+            Node::BlockStatement(node) => node.span.as_ref(),
+            Node::IfStatement(node) => node.span.as_ref(),
+            Node::ForStatement(node) => node.span.as_ref(),
+            Node::ForOfStatement(node) => node.span.as_ref(),
+            Node::ForInStatement(node) => node.span.as_ref(),
+            Node::WhileStatement(node) => node.span.as_ref(),
+            Node::DoWhileStatement(node) => node.span.as_ref(),
+            Node::SwitchStatement(node) => node.span.as_ref(),
+            Node::TryStatement(node) => node.span.as_ref(),
+            Node::CatchClause(node) => node.span.as_ref(),
+            Node::ThrowStatement(node) => node.span.as_ref(),
+            Node::ReturnStatement(node) => node.span.as_ref(),
+            Node::BreakStatement(node) => node.span.as_ref(),
+            Node::ContinueStatement(node) => node.span.as_ref(),
+            Node::LabeledStatement(node) => node.span.as_ref(),
+            Node::WithStatement(node) => node.span.as_ref(),
+            Node::DebuggerStatement(node) => node.span.as_ref(),
+            Node::ExpressionStatement(node) => node.span.as_ref(),
+            Node::ArrayLiteral(node) => node.span.as_ref(),
+            Node::ObjectLiteral(node) => node.span.as_ref(),
+            Node::TemplateLiteral(node) => node.span.as_ref(),
+            Node::TaggedTemplateExpression(node) => node.span.as_ref(),
+            Node::Property(node) => node.span.as_ref(),
+            Node::SpreadElement(node) => node.span.as_ref(),
+            Node::RestElement(node) => node.span.as_ref(),
+            Node::ObjectPattern(node) => node.span.as_ref(),
+            Node::ArrayPattern(node) => node.span.as_ref(),
+            Node::AssignmentPattern(node) => node.span.as_ref(),
+            Node::Super(node) => node.span.as_ref(),
+            Node::MetaProperty(node) => node.span.as_ref(),
+            Node::RegExp(node) => node.span.as_ref(),
+            Node::NumericLiteral(node) => node.span.as_ref(),
+            Node::This(node) => node.span.as_ref(),
+            Node::Identifier(_)
+            | Node::Number(_)
+            | Node::String(_)
+            | Node::Boolean(_)
+            | Node::Null
+            | Node::Undefined
+            | Node::BigInt(_) => None,
+        }
+    }
+}
+
 // Program structure
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Program {
@@ -255,6 +348,23 @@ pub struct ForStatement {
     pub span: Option<Span>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForOfStatement {
+    pub left: Box<Node>, // Variable declaration without initializer, or assignment target
+    pub right: Box<Node>,
+    pub body: Box<Node>,
+    pub r#await: bool, // `for await (... of ...)`
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForInStatement {
+    pub left: Box<Node>, // Variable declaration without initializer, or assignment target
+    pub right: Box<Node>,
+    pub body: Box<Node>,
+    pub span: Option<Span>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WhileStatement {
     pub test: Box<Node>,
@@ -383,6 +493,26 @@ pub struct RestElement {
     pub span: Option<Span>,
 }
 
+// Binding patterns
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObjectPattern {
+    pub properties: Vec<Node>, // Property or RestElement
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArrayPattern {
+    pub elements: Vec<Option<Node>>,
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssignmentPattern {
+    pub left: Box<Node>,
+    pub right: Box<Node>,
+    pub span: Option<Span>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TemplateLiteral {
     pub quasis: Vec<TemplateElement>,
@@ -392,7 +522,8 @@ pub struct TemplateLiteral {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TemplateElement {
-    pub value: String,
+    pub value: String, // Cooked (escape sequences processed)
+    pub raw: String,
     pub tail: bool,
     pub span: Option<Span>,
 }
@@ -432,6 +563,11 @@ pub struct ClassExpression {
     pub span: Option<Span>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThisExpression {
+    pub span: Option<Span>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Super {
     pub span: Option<Span>,
@@ -457,6 +593,28 @@ pub struct AwaitExpression {
     pub span: Option<Span>,
 }
 
+/// Comma-separated expressions evaluated in order, such as `a = 1, a + 2`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SequenceExpression {
+    pub expressions: Vec<Node>,
+    pub span: Option<Span>,
+}
+
+/// Expression written inside parentheses, such as `(a + b)`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParenthesizedExpression {
+    pub expression: Box<Node>,
+    pub span: Option<Span>,
+}
+
+/// Number literal that keeps its source text, such as `0xFF`, `1_000` or `.5`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NumericLiteral {
+    pub value: f64,
+    pub raw: String,
+    pub span: Option<Span>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RegExp {
     pub pattern: String,
@@ -25,6 +25,8 @@ pub trait Visitor {
             Node::BlockStatement(stmt) => self.visit_block_statement(stmt),
             Node::IfStatement(stmt) => self.visit_if_statement(stmt),
             Node::ForStatement(stmt) => self.visit_for_statement(stmt),
+            Node::ForOfStatement(stmt) => self.visit_for_of_statement(stmt),
+            Node::ForInStatement(stmt) => self.visit_for_in_statement(stmt),
             Node::WhileStatement(stmt) => self.visit_while_statement(stmt),
             Node::DoWhileStatement(stmt) => self.visit_do_while_statement(stmt),
             Node::SwitchStatement(stmt) => self.visit_switch_statement(stmt),
@@ -40,20 +42,26 @@ pub trait Visitor {
             Node::Property(prop) => self.visit_property(prop),
             Node::Identifier(id) => self.visit_identifier(id),
             Node::Number(num) => self.visit_number(*num),
+            Node::NumericLiteral(lit) => self.visit_number(lit.value),
             Node::String(s) => self.visit_string(s),
             Node::Boolean(b) => self.visit_boolean(*b),
             Node::Null => self.visit_null(),
             Node::Undefined => self.visit_undefined(),
-            Node::This => self.visit_this(),
+            Node::This(expr) => self.visit_this(expr),
             Node::ArrowFunctionExpression(expr) => self.visit_arrow_function_expression(expr),
             Node::FunctionExpression(expr) => self.visit_function_expression(expr),
             Node::ClassExpression(expr) => self.visit_class_expression(expr),
             Node::YieldExpression(expr) => self.visit_yield_expression(expr),
             Node::AwaitExpression(expr) => self.visit_await_expression(expr),
+            Node::SequenceExpression(expr) => self.visit_sequence_expression(expr),
+            Node::ParenthesizedExpression(expr) => self.visit_parenthesized_expression(expr),
             Node::Super(super_expr) => self.visit_super(super_expr),
             Node::MetaProperty(prop) => self.visit_meta_property(prop),
             Node::SpreadElement(elem) => self.visit_spread_element(elem),
             Node::RestElement(elem) => self.visit_rest_element(elem),
+            Node::ObjectPattern(pattern) => self.visit_object_pattern(pattern),
+            Node::ArrayPattern(pattern) => self.visit_array_pattern(pattern),
+            Node::AssignmentPattern(pattern) => self.visit_assignment_pattern(pattern),
             Node::TemplateLiteral(lit) => self.visit_template_literal(lit),
             Node::TaggedTemplateExpression(expr) => self.visit_tagged_template_expression(expr),
             Node::ImportDeclaration(decl) => self.visit_import_declaration(decl),
@@ -83,6 +91,8 @@ pub trait Visitor {
     fn visit_block_statement(&mut self, _stmt: &crate::BlockStatement) -> Self::Output { unimplemented!() }
     fn visit_if_statement(&mut self, _stmt: &crate::IfStatement) -> Self::Output { unimplemented!() }
     fn visit_for_statement(&mut self, _stmt: &crate::ForStatement) -> Self::Output { unimplemented!() }
+    fn visit_for_of_statement(&mut self, _stmt: &crate::ForOfStatement) -> Self::Output { unimplemented!() }
+    fn visit_for_in_statement(&mut self, _stmt: &crate::ForInStatement) -> Self::Output { unimplemented!() }
     fn visit_while_statement(&mut self, _stmt: &crate::WhileStatement) -> Self::Output { unimplemented!() }
     fn visit_do_while_statement(&mut self, _stmt: &crate::DoWhileStatement) -> Self::Output { unimplemented!() }
     fn visit_switch_statement(&mut self, _stmt: &crate::SwitchStatement) -> Self::Output { unimplemented!() }
@@ -102,16 +112,21 @@ pub trait Visitor {
     fn visit_boolean(&mut self, _b: bool) -> Self::Output { unimplemented!() }
     fn visit_null(&mut self) -> Self::Output { unimplemented!() }
     fn visit_undefined(&mut self) -> Self::Output { unimplemented!() }
-    fn visit_this(&mut self) -> Self::Output { unimplemented!() }
+    fn visit_this(&mut self, _expr: &crate::ThisExpression) -> Self::Output { unimplemented!() }
     fn visit_arrow_function_expression(&mut self, _expr: &crate::ArrowFunctionExpression) -> Self::Output { unimplemented!() }
     fn visit_function_expression(&mut self, _expr: &crate::FunctionExpression) -> Self::Output { unimplemented!() }
     fn visit_class_expression(&mut self, _expr: &crate::ClassExpression) -> Self::Output { unimplemented!() }
     fn visit_yield_expression(&mut self, _expr: &crate::YieldExpression) -> Self::Output { unimplemented!() }
     fn visit_await_expression(&mut self, _expr: &crate::AwaitExpression) -> Self::Output { unimplemented!() }
+    fn visit_sequence_expression(&mut self, _expr: &crate::SequenceExpression) -> Self::Output { unimplemented!() }
+    fn visit_parenthesized_expression(&mut self, expr: &crate::ParenthesizedExpression) -> Self::Output { self.visit_node(&expr.expression) }
     fn visit_super(&mut self, _super_expr: &crate::Super) -> Self::Output { unimplemented!() }
     fn visit_meta_property(&mut self, _prop: &crate::MetaProperty) -> Self::Output { unimplemented!() }
     fn visit_spread_element(&mut self, _elem: &crate::SpreadElement) -> Self::Output { unimplemented!() }
     fn visit_rest_element(&mut self, _elem: &crate::RestElement) -> Self::Output { unimplemented!() }
+    fn visit_object_pattern(&mut self, _pattern: &crate::ObjectPattern) -> Self::Output { unimplemented!() }
+    fn visit_array_pattern(&mut self, _pattern: &crate::ArrayPattern) -> Self::Output { unimplemented!() }
+    fn visit_assignment_pattern(&mut self, _pattern: &crate::AssignmentPattern) -> Self::Output { unimplemented!() }
     fn visit_template_literal(&mut self, _lit: &crate::TemplateLiteral) -> Self::Output { unimplemented!() }
     fn visit_tagged_template_expression(&mut self, _expr: &crate::TaggedTemplateExpression) -> Self::Output { unimplemented!() }
     fn visit_import_declaration(&mut self, _decl: &crate::ImportDeclaration) -> Self::Output { unimplemented!() }
@@ -232,6 +247,23 @@ impl Visitor for NodeCounter {
                 self.visit_node(&prop.key);
                 self.visit_node(&prop.value);
             }
+            Node::ObjectPattern(pattern) => {
+                for prop in &pattern.properties {
+                    self.visit_node(prop);
+                }
+            }
+            Node::ArrayPattern(pattern) => {
+                for elem in pattern.elements.iter().flatten() {
+                    self.visit_node(elem);
+                }
+            }
+            Node::AssignmentPattern(pattern) => {
+                self.visit_node(&pattern.left);
+                self.visit_node(&pattern.right);
+            }
+            Node::RestElement(elem) => {
+                self.visit_node(&elem.argument);
+            }
             _ => {}
         }
     }
@@ -276,13 +308,18 @@ impl Visitor for AstPrinter {
             Node::ArrayLiteral(_) => println!("ArrayLiteral"),
             Node::ObjectLiteral(_) => println!("ObjectLiteral"),
             Node::Property(_) => println!("Property"),
+            Node::ObjectPattern(_) => println!("ObjectPattern"),
+            Node::ArrayPattern(_) => println!("ArrayPattern"),
+            Node::AssignmentPattern(_) => println!("AssignmentPattern"),
+            Node::RestElement(_) => println!("RestElement"),
             Node::Identifier(id) => println!("Identifier: {}", id),
             Node::Number(num) => println!("Number: {}", num),
+            Node::NumericLiteral(lit) => println!("Number: {}", lit.raw),
             Node::String(s) => println!("String: {}", s),
             Node::Boolean(b) => println!("Boolean: {}", b),
             Node::Null => println!("Null"),
             Node::Undefined => println!("Undefined"),
-            Node::This => println!("This"),
+            Node::This(_) => println!("This"),
             _ => println!("Unknown node"),
         }
         
@@ -380,6 +417,23 @@ impl Visitor for AstPrinter {
                 self.visit_node(&prop.key);
                 self.visit_node(&prop.value);
             }
+            Node::ObjectPattern(pattern) => {
+                for prop in &pattern.properties {
+                    self.visit_node(prop);
+                }
+            }
+            Node::ArrayPattern(pattern) => {
+                for elem in pattern.elements.iter().flatten() {
+                    self.visit_node(elem);
+                }
+            }
+            Node::AssignmentPattern(pattern) => {
+                self.visit_node(&pattern.left);
+                self.visit_node(&pattern.right);
+            }
+            Node::RestElement(elem) => {
+                self.visit_node(&elem.argument);
+            }
             _ => {}
         }
         self.indent -= 1;
@@ -0,0 +1,94 @@
+//! Tests for the AST builder functions
+
+use v8_ast::builder as ast;
+use v8_ast::*;
+
+/// `function add(a, b) { return a + b; } let x = add(1, 2);`, built by hand
+fn hand_built_program() -> Node {
+    Node::Program(Program {
+        body: vec![
+            Node::FunctionDeclaration(FunctionDeclaration {
+                id: Some(Box::new(Node::Identifier("add".to_string()))),
+                params: vec![Node::Identifier("a".to_string()), Node::Identifier("b".to_string())],
+                body: Box::new(Node::BlockStatement(BlockStatement {
+                    body: vec![Node::ReturnStatement(ReturnStatement {
+                        argument: Some(Box::new(Node::BinaryExpression(BinaryExpression {
+                            left: Box::new(Node::Identifier("a".to_string())),
+                            operator: "+".to_string(),
+                            right: Box::new(Node::Identifier("b".to_string())),
+                            span: None,
+                        }))),
+                        span: None,
+                    })],
+                    span: None,
+                })),
+                generator: false,
+                r#async: false,
+                span: None,
+            }),
+            Node::VariableDeclaration(VariableDeclaration {
+                kind: "let".to_string(),
+                declarations: vec![VariableDeclarator {
+                    id: Box::new(Node::Identifier("x".to_string())),
+                    init: Some(Box::new(Node::CallExpression(CallExpression {
+                        callee: Box::new(Node::Identifier("add".to_string())),
+                        arguments: vec![Node::Number(1.0), Node::Number(2.0)],
+                        span: None,
+                    }))),
+                    span: None,
+                }],
+                span: None,
+            }),
+        ],
+        source_type: "script".to_string(),
+        span: None,
+    })
+}
+
+#[test]
+fn test_builder_matches_hand_built_tree() {
+    let built = ast::program(vec![
+        ast::function_decl(
+            "add",
+            &["a", "b"],
+            vec![ast::return_stmt(Some(ast::bin("+", ast::ident("a"), ast::ident("b"))))],
+        ),
+        ast::let_decl("x", Some(ast::call(ast::ident("add"), vec![ast::num(1.0), ast::num(2.0)]))),
+    ]);
+    assert!(built.structurally_eq(&hand_built_program()));
+    assert_eq!(built, hand_built_program());
+}
+
+#[test]
+fn test_structurally_eq_ignores_spans() {
+    let with_span = Node::BinaryExpression(BinaryExpression {
+        left: Box::new(Node::Number(1.0)),
+        operator: "-".to_string(),
+        right: Box::new(Node::Number(2.0)),
+        span: Some(Span::from_positions(1, 0, 1, 5)),
+    });
+    let built = ast::bin("-", ast::num(1.0), ast::num(2.0));
+    assert_ne!(built, with_span);
+    assert!(built.structurally_eq(&with_span));
+    assert!(!built.structurally_eq(&ast::bin("+", ast::num(1.0), ast::num(2.0))));
+}
+
+#[test]
+fn test_span_merge_and_builder_spans() {
+    let first = Span::from_positions(1, 10, 1, 14);
+    let second = Span::from_positions(2, 1, 2, 3);
+    let merged = Span::merge(&second, &first);
+    assert_eq!(merged, Span::from_positions(1, 10, 2, 3));
+
+    let spanned = |span: Span| {
+        Node::BinaryExpression(BinaryExpression {
+            left: Box::new(Node::Number(1.0)),
+            operator: "+".to_string(),
+            right: Box::new(Node::Number(2.0)),
+            span: Some(span),
+        })
+    };
+    let built = ast::bin("*", spanned(first.clone()), spanned(second.clone()));
+    assert_eq!(built.span(), Some(&merged));
+    assert_eq!(ast::bin("*", ast::num(1.0), spanned(second)).span(), None);
+}
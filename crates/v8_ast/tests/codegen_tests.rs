@@ -0,0 +1,98 @@
+//! Tests for source generation and mapping segments
+
+use v8_ast::codegen::{generate, generate_with_source_map, MappingSegment};
+use v8_ast::*;
+
+fn ident(name: &str) -> Node {
+    Node::Identifier(name.to_string())
+}
+
+fn binary(left: Node, operator: &str, right: Node) -> Node {
+    Node::BinaryExpression(BinaryExpression {
+        left: Box::new(left),
+        operator: operator.to_string(),
+        right: Box::new(right),
+        span: None,
+    })
+}
+
+/// `let x = 1;` on line 2 and `f(x);` on line 4 of the original source
+fn two_statement_program() -> Node {
+    Node::Program(Program {
+        body: vec![
+            Node::VariableDeclaration(VariableDeclaration {
+                kind: "let".to_string(),
+                declarations: vec![VariableDeclarator {
+                    id: Box::new(ident("x")),
+                    init: Some(Box::new(Node::Number(1.0))),
+                    span: None,
+                }],
+                span: Some(Span::from_positions(2, 5, 2, 15)),
+            }),
+            Node::ExpressionStatement(ExpressionStatement {
+                expression: Box::new(Node::CallExpression(CallExpression {
+                    callee: Box::new(ident("f")),
+                    arguments: vec![ident("x")],
+                    span: None,
+                })),
+                span: Some(Span::from_positions(4, 1, 4, 6)),
+            }),
+        ],
+        source_type: "script".to_string(),
+        span: Some(Span::from_positions(1, 1, 4, 6)),
+    })
+}
+
+#[test]
+fn test_two_statements_produce_two_segments() {
+    let generated = generate_with_source_map(&two_statement_program());
+    assert_eq!(generated.code, "let x = 1;\nf(x);\n");
+    assert_eq!(
+        generated.mappings,
+        vec![
+            MappingSegment { generated: Position::new(1, 1), original: Position::new(2, 5) },
+            MappingSegment { generated: Position::new(2, 1), original: Position::new(4, 1) },
+        ]
+    );
+}
+
+#[test]
+fn test_nested_statements_are_mapped() {
+    let program = Node::Program(Program {
+        body: vec![Node::WhileStatement(WhileStatement {
+            test: Box::new(ident("x")),
+            body: Box::new(Node::BlockStatement(BlockStatement {
+                body: vec![Node::BreakStatement(BreakStatement {
+                    label: None,
+                    span: Some(Span::from_positions(1, 12, 1, 18)),
+                })],
+                span: None,
+            })),
+            span: Some(Span::from_positions(1, 1, 1, 20)),
+        })],
+        source_type: "script".to_string(),
+        span: None,
+    });
+    let generated = generate_with_source_map(&program);
+    assert_eq!(generated.code, "while (x) {\n    break;\n}\n");
+    assert_eq!(generated.mappings[1].generated, Position::new(2, 5));
+    assert_eq!(generated.mappings[1].original, Position::new(1, 12));
+}
+
+#[test]
+fn test_generate_respects_precedence() {
+    // (a + b) * c - (d - e)
+    let expr = binary(
+        binary(binary(ident("a"), "+", ident("b")), "*", ident("c")),
+        "-",
+        binary(ident("d"), "-", ident("e")),
+    );
+    assert_eq!(generate(&expr), "(a + b) * c - (d - e)");
+    assert_eq!(generate(&Node::String("say \"hi\"\n".to_string())), "\"say \\\"hi\\\"\\n\"");
+}
+
+#[test]
+fn test_generate_keeps_explicit_parens() {
+    let expr = binary(builder::paren(binary(ident("a"), "*", ident("b"))), "+", builder::paren(ident("c")));
+    assert_eq!(generate(&expr), "(a * b) + (c)");
+}
@@ -274,6 +274,7 @@ pub fn create_template_literal(quasis: Vec<TemplateElement>, expressions: Vec<No
 pub fn create_template_element(value: &str, tail: bool) -> TemplateElement {
     TemplateElement {
         value: value.to_string(),
+        raw: value.to_string(),
         tail,
         span: None,
     }
@@ -313,7 +314,7 @@ pub fn create_super() -> Node {
 
 /// Helper function to create a this expression
 pub fn create_this() -> Node {
-    Node::This
+    Node::This(ThisExpression { span: None })
 }
 
 /// Helper function to create a null literal
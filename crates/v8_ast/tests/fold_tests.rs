@@ -0,0 +1,86 @@
+//! Tests for the AST fold utility
+
+use v8_ast::*;
+
+fn ident(name: &str) -> Node {
+    Node::Identifier(name.to_string())
+}
+
+/// `let x = a + (b * 2); if (x) { f(x); }`
+fn sample_program() -> Node {
+    Node::Program(Program {
+        body: vec![
+            Node::VariableDeclaration(VariableDeclaration {
+                kind: "let".to_string(),
+                declarations: vec![VariableDeclarator {
+                    id: Box::new(ident("x")),
+                    init: Some(Box::new(Node::BinaryExpression(BinaryExpression {
+                        left: Box::new(ident("a")),
+                        operator: "+".to_string(),
+                        right: Box::new(Node::BinaryExpression(BinaryExpression {
+                            left: Box::new(ident("b")),
+                            operator: "*".to_string(),
+                            right: Box::new(Node::Number(2.0)),
+                            span: None,
+                        })),
+                        span: None,
+                    }))),
+                    span: None,
+                }],
+                span: None,
+            }),
+            Node::IfStatement(IfStatement {
+                test: Box::new(ident("x")),
+                consequent: Box::new(Node::BlockStatement(BlockStatement {
+                    body: vec![Node::ExpressionStatement(ExpressionStatement {
+                        expression: Box::new(Node::CallExpression(CallExpression {
+                            callee: Box::new(ident("f")),
+                            arguments: vec![ident("x")],
+                            span: None,
+                        })),
+                        span: None,
+                    })],
+                    span: None,
+                })),
+                alternate: None,
+                span: None,
+            }),
+        ],
+        source_type: "script".to_string(),
+        span: None,
+    })
+}
+
+#[test]
+fn test_fold_counts_like_node_counter() {
+    let ast = sample_program();
+    
+    let mut counter = NodeCounter::new();
+    counter.visit_node(&ast);
+    
+    let count = ast.fold(0, |count, _| count + 1);
+    assert_eq!(count, counter.count);
+    assert_eq!(count, 15);
+}
+
+#[test]
+fn test_fold_is_pre_order() {
+    let ast = sample_program();
+    let identifiers = ast.fold(Vec::new(), |mut names, node| {
+        if let Node::Identifier(name) = node {
+            names.push(name.clone());
+        }
+        names
+    });
+    assert_eq!(identifiers, vec!["x", "a", "b", "x", "f", "x"]);
+}
+
+#[test]
+fn test_fold_max_depth() {
+    let ast = sample_program();
+    // Program > IfStatement > BlockStatement > ExpressionStatement > CallExpression > Identifier
+    let depth = ast.fold_with_depth(0, |max, _, depth| max.max(depth));
+    assert_eq!(depth, 6);
+    
+    assert_eq!(ident("x").fold_with_depth(0, |max, _, depth| max.max(depth)), 1);
+}
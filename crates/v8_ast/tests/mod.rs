@@ -5,6 +5,9 @@ mod common;
 mod node_tests;
 mod visitor_tests;
 mod serialization_tests;
+mod fold_tests;
+mod codegen_tests;
+mod builder_tests;
 
 // Re-export test utilities
 pub use common::*;
@@ -59,7 +59,7 @@ fn test_literal_nodes() {
     
     // This
     let this_lit = create_this();
-    assert!(matches!(this_lit, Node::This));
+    assert!(matches!(this_lit, Node::This(_)));
     
     // BigInt
     let bigint_lit = create_bigint("42n");
@@ -1,12 +1,57 @@
 //! Bytecode generator: Transforms AST into bytecode instructions
 
 use crate::instructions::*;
-use v8_ast::Node;
+use std::collections::{HashMap, HashSet};
+use v8_ast::{BlockStatement, MemberExpression, Node, UpdateExpression};
 
 /// Main struct for bytecode generation
 pub struct BytecodeGenerator {
     pub constants: ConstantPool,
     pub instructions: Vec<Instruction>,
+    /// Number of local slots the generated code needs
+    pub local_count: usize,
+    /// Functions created by the generated code, indexed by `CreateFunction`
+    pub functions: Vec<FunctionInfo>,
+    scopes: Vec<HashMap<String, usize>>, // Lexical scopes, innermost last
+    free_slots: Vec<usize>,              // Slots released by closed scopes, highest first
+    self_name: Option<String>,           // Name of the function being generated, if any
+    enclosing: HashSet<String>,          // Variables visible from the enclosing code
+    captured: Vec<String>,               // Enclosing variables this function uses
+    boxed: HashSet<String>,              // Names used by nested functions; such locals live in cells
+    cells: HashSet<usize>,               // Slots holding the cell of a boxed local
+    try_depth: usize,                    // Enclosing `try` blocks, which rule out tail calls
+    is_async: bool,                      // Body of an async function, whose frame outlives an `await`
+    handler_depth: usize,                // `Try` handlers active at the current instruction
+    jump_targets: Vec<JumpTarget>,       // Enclosing loops and labeled statements, innermost last
+    pending_labels: Vec<String>,         // Labels waiting for the loop they name
+}
+
+/// Where `break` and `continue` inside a loop, `switch` or labeled
+/// statement jump to
+///
+/// Both targets are only known once the statement has been generated, so
+/// the jumps are recorded and back-patched when it is left.
+struct JumpTarget {
+    labels: Vec<String>,
+    kind: TargetKind,
+    handler_depth: usize,   // Handlers active outside the statement
+    breaks: Vec<usize>,     // Jumps to the end of the statement
+    continues: Vec<usize>,  // Jumps to the update or test of the loop
+}
+
+/// Which jumps a `JumpTarget` takes without a label
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TargetKind {
+    Loop,    // `break` and `continue`
+    Switch,  // Only `break`; `continue` goes on to the enclosing loop
+    Labeled, // Neither: only `break label` leaves a labeled block
+}
+
+/// How `bind_pattern` stores the values it destructures
+#[derive(Clone, Copy)]
+enum Binding {
+    Declare { function_scoped: bool }, // Parameters and declarations create the bindings
+    Assign,                            // Assignments write existing variables or properties
 }
 
 impl BytecodeGenerator {
@@ -15,52 +60,778 @@ impl BytecodeGenerator {
         BytecodeGenerator {
             constants: ConstantPool::default(),
             instructions: Vec::new(),
+            local_count: 0,
+            functions: Vec::new(),
+            scopes: vec![HashMap::new()],
+            free_slots: Vec::new(),
+            self_name: None,
+            enclosing: HashSet::new(),
+            captured: Vec::new(),
+            boxed: HashSet::new(),
+            cells: HashSet::new(),
+            try_depth: 0,
+            is_async: false,
+            handler_depth: 0,
+            jump_targets: Vec::new(),
+            pending_labels: Vec::new(),
         }
     }
 
     /// Generates bytecode from the given AST node
     pub fn generate(&mut self, node: &Node) {
+        collect_nested_names(node, false, &mut self.boxed);
+        if let Node::Program(program) = node {
+            self.declare_function_cells(node);
+            self.declare_block_cells(&program.body);
+        }
         self.visit_node(node);
     }
 
+    /// Opens a lexical scope for block-scoped declarations
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    /// Closes the innermost scope, releasing its slots for reuse
+    fn end_scope(&mut self) {
+        if let Some(scope) = self.scopes.pop() {
+            for slot in scope.values() {
+                self.cells.remove(slot);
+            }
+            self.free_slots.extend(scope.into_values());
+            // Ordem decrescente: `pop` devolve sempre o menor slot livre
+            self.free_slots.sort_unstable_by(|a, b| b.cmp(a));
+        }
+    }
+
+    /// Takes the lowest free slot, growing the frame only when none is free
+    fn allocate_slot(&mut self) -> usize {
+        if let Some(slot) = self.free_slots.pop() {
+            return slot;
+        }
+        self.local_count += 1;
+        self.local_count - 1
+    }
+
+    /// Resolves `name` to the slot of its innermost visible declaration
+    fn resolve_local(&self, name: &str) -> Option<usize> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name).copied())
+    }
+
+    /// Returns the slot bound to `name` in the innermost scope (or the
+    /// outermost one for `var`), allocating it on first declaration
+    pub fn get_or_create_local(&mut self, name: &str, function_scoped: bool) -> usize {
+        let depth = if function_scoped { 0 } else { self.scopes.len() - 1 };
+        if let Some(slot) = self.scopes[depth].get(name) {
+            return *slot;
+        }
+        let slot = self.allocate_slot();
+        self.scopes[depth].insert(name.to_string(), slot);
+        slot
+    }
+
+    /// Declares `name` like `get_or_create_local`; a boxed variable gets a
+    /// new cell in its slot when the binding is created
+    fn declare_local(&mut self, name: &str, function_scoped: bool) -> usize {
+        let depth = if function_scoped { 0 } else { self.scopes.len() - 1 };
+        let fresh = !self.scopes[depth].contains_key(name);
+        let slot = self.get_or_create_local(name, function_scoped);
+        if fresh && self.boxed.contains(name) {
+            self.instructions.push(Instruction::NewCell(slot));
+            self.cells.insert(slot);
+        }
+        slot
+    }
+
+    /// Creates up front the cells of the boxed `var` and function
+    /// declarations of a function body
+    ///
+    /// They belong to the whole function, so a closure created anywhere in
+    /// it, or in an earlier iteration of a loop, shares the same cell.
+    fn declare_function_cells(&mut self, body: &Node) {
+        let mut names = Vec::new();
+        collect_var_names(body, &mut names);
+        names.retain(|name| self.boxed.contains(name));
+        for name in &names {
+            self.declare_local(name, true);
+        }
+    }
+
+    /// Creates on entry to a block the cells of its boxed `let`, `const`
+    /// and class declarations, so functions declared before them share them
+    fn declare_block_cells(&mut self, statements: &[Node]) {
+        let mut names = Vec::new();
+        for statement in statements {
+            match statement {
+                Node::VariableDeclaration(decl) if decl.kind != "var" => {
+                    for var in &decl.declarations {
+                        pattern_names(&var.id, &mut names);
+                    }
+                }
+                Node::ClassDeclaration(decl) => names.extend(decl.id.iter().flat_map(|id| match id.as_ref() {
+                    Node::Identifier(name) => Some(name.clone()),
+                    _ => None,
+                })),
+                _ => {}
+            }
+        }
+        names.retain(|name| self.boxed.contains(name));
+        for name in &names {
+            self.declare_local(name, false);
+        }
+    }
+
+    /// Pushes the value held in a variable slot, reading through its cell if it has one
+    fn load_slot(&mut self, slot: usize) {
+        let instruction = if self.cells.contains(&slot) { Instruction::LoadCell(slot) } else { Instruction::LoadLocal(slot) };
+        self.instructions.push(instruction);
+    }
+
+    /// Pops the top of the stack into a variable slot, writing through its cell if it has one
+    fn store_slot(&mut self, slot: usize) {
+        let instruction = if self.cells.contains(&slot) { Instruction::StoreCell(slot) } else { Instruction::StoreLocal(slot) };
+        self.instructions.push(instruction);
+    }
+
+    /// Pushes the value of the variable `name`
+    ///
+    /// Variables of the enclosing code are reached through the cells
+    /// captured when the function is created, so reads and writes on
+    /// either side are shared; anything else not declared here is a global.
+    fn load_variable(&mut self, name: &str) {
+        if let Some(slot) = self.resolve_local(name) {
+            self.load_slot(slot);
+        } else if self.self_name.as_deref() == Some(name) {
+            self.instructions.push(Instruction::LoadThisFunction);
+        } else if self.enclosing.contains(name) {
+            self.capture(name);
+            self.instructions.push(Instruction::LoadClosureVar(name.to_string()));
+        } else {
+            self.instructions.push(Instruction::LoadGlobal(name.to_string()));
+        }
+    }
+
+    /// Pops the top of the stack into the variable `name`
+    fn store_variable(&mut self, name: &str) {
+        match self.resolve_local(name) {
+            Some(slot) => self.store_slot(slot),
+            None if self.enclosing.contains(name) => {
+                self.capture(name);
+                self.instructions.push(Instruction::StoreClosureVar(name.to_string()));
+            }
+            None => self.instructions.push(Instruction::StoreGlobal(name.to_string())),
+        }
+    }
+
+    /// Releases slots taken with `allocate_slot` for temporaries
+    fn release_slots(&mut self, slots: &[usize]) {
+        self.free_slots.extend(slots);
+        self.free_slots.sort_unstable_by(|a, b| b.cmp(a));
+    }
+
+    /// Records that this function reads the enclosing variable `name`
+    fn capture(&mut self, name: &str) {
+        if !self.captured.iter().any(|captured| captured == name) {
+            self.captured.push(name.to_string());
+        }
+    }
+
+    /// Compiles a function into `functions` and pushes `CreateFunction`
+    fn visit_function(&mut self, name: Option<&str>, params: &[Node], body: &Node, is_async: bool) {
+        let mut inner = BytecodeGenerator::new();
+        inner.is_async = is_async;
+        // Todas as funções compartilham o pool de constantes do programa
+        inner.constants = std::mem::take(&mut self.constants);
+        inner.self_name = name.map(str::to_string);
+        for param in params {
+            collect_nested_names(param, false, &mut inner.boxed);
+        }
+        collect_nested_names(body, false, &mut inner.boxed);
+        inner.enclosing = self
+            .scopes
+            .iter()
+            .flat_map(|scope| scope.keys().cloned())
+            .chain(self.enclosing.iter().cloned())
+            .collect();
+        // Parâmetros são ligados da esquerda para a direita: um valor padrão
+        // enxerga os parâmetros anteriores
+        for (index, param) in params.iter().enumerate() {
+            match param {
+                Node::RestElement(rest) => {
+                    inner.instructions.push(Instruction::LoadRestArgs(index));
+                    inner.bind_pattern(&rest.argument, Binding::Declare { function_scoped: true });
+                }
+                param => {
+                    inner.instructions.push(Instruction::LoadArg(index));
+                    inner.bind_pattern(param, Binding::Declare { function_scoped: true });
+                }
+            }
+        }
+        inner.declare_function_cells(body);
+        match body {
+            Node::BlockStatement(_) => inner.visit_node(body),
+            // Arrow com corpo de expressão
+            expression => {
+                inner.visit_node(expression);
+                inner.instructions.push(Instruction::Return);
+            }
+        }
+        // Sem `return` a função retorna undefined
+        inner.instructions.push(Instruction::PushUndefined);
+        inner.instructions.push(Instruction::Return);
+        self.constants = std::mem::take(&mut inner.constants);
+
+        let captures = inner
+            .captured
+            .iter()
+            .map(|name| match self.resolve_local(name) {
+                Some(slot) => (name.clone(), Capture::Local(slot)),
+                None => {
+                    self.capture(name);
+                    (name.clone(), Capture::Closure)
+                }
+            })
+            .collect();
+        self.functions.push(FunctionInfo {
+            name: name.unwrap_or_default().to_string(),
+            // Como `length`: só os parâmetros antes do primeiro padrão ou rest
+            param_count: params
+                .iter()
+                .take_while(|param| !matches!(param, Node::AssignmentPattern(_) | Node::RestElement(_)))
+                .count(),
+            local_count: inner.local_count,
+            instructions: inner.instructions,
+            functions: inner.functions,
+            captures,
+            is_async,
+        });
+        self.instructions.push(Instruction::CreateFunction(self.functions.len() - 1));
+    }
+
+    /// Pops the value on top of the stack into the targets of a pattern
+    ///
+    /// Object patterns read each key with `GetProperty` and collect the
+    /// rest with `RestProperties`; array patterns read by index and collect
+    /// the rest with `RestElements`.
+    fn bind_pattern(&mut self, pattern: &Node, binding: Binding) {
+        match pattern {
+            Node::Identifier(name) => match binding {
+                Binding::Declare { function_scoped } => {
+                    let slot = self.declare_local(name, function_scoped);
+                    self.store_slot(slot);
+                }
+                Binding::Assign => self.store_variable(name),
+            },
+            Node::MemberExpression(member) if matches!(binding, Binding::Assign) => {
+                // SetProperty espera o valor acima do objeto e da chave
+                let value = self.allocate_slot();
+                self.instructions.push(Instruction::StoreLocal(value));
+                self.visit_node(&member.object);
+                self.visit_member_key(member);
+                self.instructions.push(Instruction::LoadLocal(value));
+                self.instructions.push(Instruction::SetProperty);
+                self.release_slots(&[value]);
+            }
+            Node::AssignmentPattern(assign) => {
+                self.visit_default(&assign.right);
+                self.bind_pattern(&assign.left, binding);
+            }
+            Node::ObjectPattern(object) => {
+                let has_rest = object.properties.iter().any(|property| matches!(property, Node::RestElement(_)));
+                // Com rest, cada chave lida fica num slot para ser excluída da cópia
+                let mut keys = Vec::new();
+                for property in &object.properties {
+                    match property {
+                        Node::Property(prop) => {
+                            self.instructions.push(Instruction::Dup);
+                            match prop.key.as_ref() {
+                                Node::Identifier(name) if !prop.computed => {
+                                    let idx = self.constants.add(Constant::String(name.clone()));
+                                    self.instructions.push(Instruction::PushConst(idx));
+                                }
+                                key => self.visit_node(key),
+                            }
+                            if has_rest {
+                                let slot = self.allocate_slot();
+                                self.instructions.push(Instruction::Dup);
+                                self.instructions.push(Instruction::StoreLocal(slot));
+                                keys.push(slot);
+                            }
+                            self.instructions.push(Instruction::GetProperty);
+                            self.bind_pattern(&prop.value, binding);
+                        }
+                        Node::RestElement(rest) => {
+                            self.instructions.push(Instruction::Dup);
+                            for slot in &keys {
+                                self.instructions.push(Instruction::LoadLocal(*slot));
+                            }
+                            self.instructions.push(Instruction::RestProperties(keys.len()));
+                            self.bind_pattern(&rest.argument, binding);
+                        }
+                        other => unimplemented!("Object pattern property {:?} not implemented", other),
+                    }
+                }
+                self.release_slots(&keys);
+                self.instructions.push(Instruction::Pop);
+            }
+            Node::ArrayPattern(array) => {
+                for (index, element) in array.elements.iter().enumerate() {
+                    match element {
+                        // Buraco no padrão: o elemento é ignorado
+                        None => {}
+                        Some(Node::RestElement(rest)) => {
+                            self.instructions.push(Instruction::Dup);
+                            self.instructions.push(Instruction::RestElements(index));
+                            self.bind_pattern(&rest.argument, binding);
+                        }
+                        Some(element) => {
+                            self.instructions.push(Instruction::Dup);
+                            let idx = self.constants.add(Constant::Number(index as f64));
+                            self.instructions.push(Instruction::PushConst(idx));
+                            self.instructions.push(Instruction::GetProperty);
+                            self.bind_pattern(element, binding);
+                        }
+                    }
+                }
+                self.instructions.push(Instruction::Pop);
+            }
+            other => unimplemented!("Binding pattern {:?} not implemented", other),
+        }
+    }
+
+    /// Replaces the value on top of the stack with `default` when it is `undefined`
+    ///
+    /// The default is only evaluated when it is used; `null` keeps its value.
+    fn visit_default(&mut self, default: &Node) {
+        self.instructions.push(Instruction::Dup);
+        self.instructions.push(Instruction::PushUndefined);
+        self.instructions.push(Instruction::StrictEq);
+        let jump = self.instructions.len();
+        self.instructions.push(Instruction::JumpIfFalse(0));
+        self.instructions.push(Instruction::Pop);
+        self.visit_node(default);
+        let end = self.instructions.len();
+        self.patch_jump(jump, end);
+    }
+
+    /// Compiles a class into its constructor and methods and pushes `CreateClass`
+    fn visit_class(&mut self, id: Option<&Node>, super_class: Option<&Node>, body: &Node) {
+        if super_class.is_some() {
+            unimplemented!("Class inheritance not implemented");
+        }
+        let name = match id {
+            Some(Node::Identifier(name)) => Some(name.as_str()),
+            _ => None,
+        };
+        let members = match body {
+            Node::BlockStatement(block) => block.body.as_slice(),
+            _ => &[],
+        };
+        let methods: Vec<(&str, &Node)> = members
+            .iter()
+            .filter_map(|member| match member {
+                Node::Property(prop) if prop.kind != "init" => unimplemented!("Getters and setters not implemented"),
+                Node::Property(prop) => match prop.key.as_ref() {
+                    Node::Identifier(key) => Some((key.as_str(), prop.value.as_ref())),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+
+        match methods.iter().find(|(key, _)| *key == "constructor") {
+            Some((_, Node::FunctionExpression(constructor))) => {
+                self.visit_function(name, &constructor.params, &constructor.body, false)
+            }
+            // Construtor padrão vazio
+            _ => {
+                let empty = Node::BlockStatement(BlockStatement { body: Vec::new(), span: None });
+                self.visit_function(name, &[], &empty, false);
+            }
+        }
+        let mut method_count = 0;
+        for (key, method) in &methods {
+            let Node::FunctionExpression(method) = method else { continue };
+            if *key == "constructor" {
+                continue;
+            }
+            let idx = self.constants.add(Constant::String(key.to_string()));
+            self.instructions.push(Instruction::PushConst(idx));
+            self.visit_function(Some(key), &method.params, &method.body, method.r#async);
+            method_count += 1;
+        }
+        self.instructions.push(Instruction::CreateClass(method_count));
+    }
+
+    /// Opens a loop, `switch` or labeled statement, taking the labels that name it
+    fn enter_jump_target(&mut self, kind: TargetKind) {
+        self.jump_targets.push(JumpTarget {
+            labels: std::mem::take(&mut self.pending_labels),
+            kind,
+            handler_depth: self.handler_depth,
+            breaks: Vec::new(),
+            continues: Vec::new(),
+        });
+    }
+
+    /// Closes the innermost loop, `switch` or labeled statement, patching its
+    /// `continue` jumps to `continue_target` and its `break` jumps to the
+    /// next instruction
+    fn leave_jump_target(&mut self, continue_target: usize) {
+        let target = self.jump_targets.pop().expect("no enclosing jump target");
+        let end = self.instructions.len();
+        for jump in target.breaks {
+            self.patch_jump(jump, end);
+        }
+        for jump in target.continues {
+            self.patch_jump(jump, continue_target);
+        }
+    }
+
+    /// Emits the jump of a `break` or `continue`, to be patched when its
+    /// target is left
+    ///
+    /// Without a label, `break` targets the innermost loop or `switch` and
+    /// `continue` the innermost loop; with one, the statement it names.
+    /// `Try` handlers opened inside the target are removed first (a
+    /// `finally` does not run yet).
+    fn visit_jump(&mut self, label: Option<&Node>, is_continue: bool) {
+        let label = match label {
+            Some(Node::Identifier(name)) => Some(name.as_str()),
+            Some(other) => panic!("Invalid label {:?}", other),
+            None => None,
+        };
+        let index = self
+            .jump_targets
+            .iter()
+            .rposition(|target| match label {
+                Some(label) => target.labels.iter().any(|name| name == label),
+                None if is_continue => target.kind == TargetKind::Loop,
+                None => target.kind != TargetKind::Labeled,
+            })
+            .unwrap_or_else(|| match label {
+                Some(label) => panic!("Undefined label '{}'", label),
+                None if is_continue => panic!("Illegal continue statement: no surrounding loop"),
+                None => panic!("Illegal break statement"),
+            });
+        if is_continue && self.jump_targets[index].kind != TargetKind::Loop {
+            panic!("Illegal continue statement: '{}' does not denote a loop", label.unwrap_or_default());
+        }
+        for _ in self.jump_targets[index].handler_depth..self.handler_depth {
+            self.instructions.push(Instruction::EndTry);
+        }
+        let jump = self.instructions.len();
+        self.instructions.push(Instruction::Jump(0));
+        let target = &mut self.jump_targets[index];
+        if is_continue {
+            target.continues.push(jump);
+        } else {
+            target.breaks.push(jump);
+        }
+    }
+
+    /// Sets the target of the jump instruction at `index`
+    fn patch_jump(&mut self, index: usize, target: usize) {
+        match &mut self.instructions[index] {
+            Instruction::Jump(t)
+            | Instruction::JumpIfTrue(t)
+            | Instruction::JumpIfFalse(t)
+            | Instruction::JumpIfNotNullish(t)
+            | Instruction::JumpIfNullish(t)
+            | Instruction::Try(t) => *t = target,
+            other => panic!("Instruction {:?} is not a jump", other),
+        }
+    }
+
+    /// Builds an array from `elements`, leaving it on the stack
+    ///
+    /// Consecutive plain elements are collected with `NewArray(n)`; each
+    /// spread element is appended to the array below it with `Spread`.
+    fn visit_array_elements(&mut self, elements: &[Option<&Node>]) {
+        let mut pending = 0;
+        let mut started = false;
+        for element in elements {
+            match element {
+                Some(Node::SpreadElement(elem)) => {
+                    if !started || pending > 0 {
+                        self.instructions.push(Instruction::NewArray(pending));
+                        if started {
+                            self.instructions.push(Instruction::Spread);
+                        }
+                        started = true;
+                        pending = 0;
+                    }
+                    self.visit_node(&elem.argument);
+                    self.instructions.push(Instruction::Spread);
+                }
+                Some(node) => {
+                    self.visit_node(node);
+                    pending += 1;
+                }
+                None => {
+                    // Buraco no array literal
+                    self.instructions.push(Instruction::PushUndefined);
+                    pending += 1;
+                }
+            }
+        }
+        if !started || pending > 0 {
+            self.instructions.push(Instruction::NewArray(pending));
+            if started {
+                self.instructions.push(Instruction::Spread);
+            }
+        }
+    }
+
+    /// Pushes the key of a member access; `obj.prop` pushes the name as a string
+    fn visit_member_key(&mut self, member: &MemberExpression) {
+        match member.property.as_ref() {
+            Node::Identifier(name) if !member.computed => {
+                let idx = self.constants.add(Constant::String(name.clone()));
+                self.instructions.push(Instruction::PushConst(idx));
+            }
+            property => self.visit_node(property),
+        }
+    }
+
+    /// Emits `++`/`--` on a variable or a property
+    ///
+    /// The target is read, stepped and written back; the value left on the
+    /// stack is the old one for postfix and the new one for prefix. For a
+    /// property, the object and key are kept in temporary slots so they are
+    /// evaluated only once.
+    fn visit_update(&mut self, expr: &UpdateExpression) {
+        let step = if expr.operator == "++" { Instruction::Inc } else { Instruction::Dec };
+        match expr.argument.as_ref() {
+            Node::Identifier(name) => {
+                self.load_variable(name);
+                if expr.prefix {
+                    self.instructions.push(step);
+                    self.instructions.push(Instruction::Dup);
+                } else {
+                    self.instructions.push(Instruction::Dup);
+                    self.instructions.push(step);
+                }
+                self.store_variable(name);
+            }
+            Node::MemberExpression(member) => {
+                let temps = [self.allocate_slot(), self.allocate_slot(), self.allocate_slot()];
+                let [object, key, value] = temps;
+                self.visit_node(&member.object);
+                self.instructions.push(Instruction::StoreLocal(object));
+                self.visit_member_key(member);
+                self.instructions.push(Instruction::StoreLocal(key));
+                self.instructions.push(Instruction::LoadLocal(object));
+                self.instructions.push(Instruction::LoadLocal(key));
+                self.instructions.push(Instruction::GetProperty);
+                if !expr.prefix {
+                    // O valor antigo fica na pilha como resultado
+                    self.instructions.push(Instruction::Dup);
+                }
+                self.instructions.push(step);
+                self.instructions.push(Instruction::StoreLocal(value));
+                self.instructions.push(Instruction::LoadLocal(object));
+                self.instructions.push(Instruction::LoadLocal(key));
+                self.instructions.push(Instruction::LoadLocal(value));
+                self.instructions.push(Instruction::SetProperty);
+                if expr.prefix {
+                    self.instructions.push(Instruction::LoadLocal(value));
+                }
+                self.release_slots(&temps);
+            }
+            _ => unimplemented!("Invalid update target"),
+        }
+    }
+
+    /// Emits a chain of member accesses and calls
+    ///
+    /// Each `?.` jumps out of the whole chain when its base is null or
+    /// undefined; the chain then evaluates to `undefined`.
+    fn visit_chain(&mut self, node: &Node) {
+        let mut exits = Vec::new();
+        self.visit_chain_link(node, &mut exits, 0);
+        if exits.is_empty() {
+            return;
+        }
+
+        // Cada saída descarta a base nula e os argumentos já empilhados abaixo dela
+        let mut jumps_to_end = Vec::new();
+        let mut depths: Vec<usize> = exits.iter().map(|&(_, depth)| depth).collect();
+        depths.sort_unstable();
+        depths.dedup();
+        for depth in depths {
+            jumps_to_end.push(self.instructions.len());
+            self.instructions.push(Instruction::Jump(0));
+            let landing = self.instructions.len();
+            for &(jump, _) in exits.iter().filter(|&&(_, d)| d == depth) {
+                self.patch_jump(jump, landing);
+            }
+            for _ in 0..=depth {
+                self.instructions.push(Instruction::Pop);
+            }
+            self.instructions.push(Instruction::PushUndefined);
+        }
+        let end = self.instructions.len();
+        for jump in jumps_to_end {
+            self.patch_jump(jump, end);
+        }
+    }
+
+    /// Emits one link of a chain; `exits` collects the `?.` jumps with the
+    /// number of values pushed since the chain started
+    fn visit_chain_link(&mut self, node: &Node, exits: &mut Vec<(usize, usize)>, depth: usize) {
+        match node {
+            Node::MemberExpression(expr) => {
+                self.visit_chain_link(&expr.object, exits, depth);
+                if expr.optional {
+                    self.instructions.push(Instruction::Dup);
+                    exits.push((self.instructions.len(), depth));
+                    self.instructions.push(Instruction::JumpIfNullish(0));
+                }
+                self.visit_member_key(expr);
+                self.instructions.push(Instruction::GetProperty);
+            }
+            Node::CallExpression(expr) => {
+                if expr.arguments.iter().any(|arg| matches!(arg, Node::SpreadElement(_))) {
+                    // Com spread o número de argumentos só é conhecido em tempo de execução
+                    let arguments: Vec<Option<&Node>> = expr.arguments.iter().map(Some).collect();
+                    self.visit_array_elements(&arguments);
+                    self.visit_chain_link(&expr.callee, exits, depth + 1);
+                    self.instructions.push(Instruction::CallWithSpread);
+                } else if let Some(member) = method_callee(&expr.callee) {
+                    // O objeto fica abaixo dos argumentos e vira o `this` da chamada
+                    self.visit_node(&member.object);
+                    let receiver = self.allocate_slot();
+                    self.instructions.push(Instruction::Dup);
+                    self.instructions.push(Instruction::StoreLocal(receiver));
+                    for arg in &expr.arguments {
+                        self.visit_node(arg);
+                    }
+                    self.instructions.push(Instruction::LoadLocal(receiver));
+                    self.release_slots(&[receiver]);
+                    self.visit_member_key(member);
+                    self.instructions.push(Instruction::GetProperty);
+                    self.instructions.push(Instruction::CallMethod(expr.arguments.len()));
+                } else {
+                    for arg in &expr.arguments {
+                        self.visit_node(arg);
+                    }
+                    self.visit_chain_link(&expr.callee, exits, depth + expr.arguments.len());
+                    self.instructions.push(Instruction::Call(expr.arguments.len()));
+                }
+            }
+            _ => self.visit_node(node),
+        }
+    }
+
+    /// Loop of a for-of or for-in statement over the iterator on the stack
+    fn visit_iteration(&mut self, left: &Node, body: &Node) {
+        // O iterador e o número de passos ficam em temporários; cada
+        // IteratorNext empilha o próximo valor e se a iteração acabou
+        let temps = [self.allocate_slot(), self.allocate_slot()];
+        let [iterator, steps] = temps;
+        self.instructions.push(Instruction::StoreLocal(iterator));
+        let zero = self.constants.add(Constant::Number(0.0));
+        self.instructions.push(Instruction::PushConst(zero));
+        self.instructions.push(Instruction::StoreLocal(steps));
+        let start = self.instructions.len();
+        self.instructions.push(Instruction::LoadLocal(iterator));
+        self.instructions.push(Instruction::LoadLocal(steps));
+        self.instructions.push(Instruction::IteratorNext);
+        let exhausted = self.instructions.len();
+        self.instructions.push(Instruction::JumpIfTrue(0));
+        // A variável do laço é uma ligação nova a cada iteração
+        self.begin_scope();
+        match left {
+            Node::VariableDeclaration(decl) => {
+                let function_scoped = decl.kind == "var";
+                for var in &decl.declarations {
+                    self.bind_pattern(&var.id, Binding::Declare { function_scoped });
+                }
+            }
+            target => self.bind_pattern(target, Binding::Assign),
+        }
+        self.enter_jump_target(TargetKind::Loop);
+        self.visit_node(body);
+        self.end_scope();
+        let next_step = self.instructions.len();
+        self.instructions.push(Instruction::LoadLocal(steps));
+        self.instructions.push(Instruction::Inc);
+        self.instructions.push(Instruction::StoreLocal(steps));
+        self.instructions.push(Instruction::Jump(start));
+        // `break` sai fechando o iterador; o fim da iteração pula o fechamento
+        self.leave_jump_target(next_step);
+        self.instructions.push(Instruction::LoadLocal(iterator));
+        self.instructions.push(Instruction::CloseIterator);
+        let skip_exhausted = self.instructions.len();
+        self.instructions.push(Instruction::Jump(0));
+        self.patch_jump(exhausted, self.instructions.len());
+        // Descarta o valor empilhado junto com o fim da iteração
+        self.instructions.push(Instruction::Pop);
+        self.patch_jump(skip_exhausted, self.instructions.len());
+        self.release_slots(&temps);
+    }
+
     fn visit_node(&mut self, node: &Node) {
         match node {
             // Program structure
             Node::Program(program) => {
-                for stmt in &program.body {
-                    self.visit_node(stmt);
+                for (idx, stmt) in program.body.iter().enumerate() {
+                    match stmt {
+                        // A última instrução deixa seu valor na pilha como resultado do programa
+                        Node::ExpressionStatement(last) if idx + 1 == program.body.len() => {
+                            self.visit_node(&last.expression)
+                        }
+                        stmt => self.visit_node(stmt),
+                    }
                 }
             }
             // Declarations
             Node::VariableDeclaration(decl) => {
+                let function_scoped = decl.kind == "var";
                 for var in &decl.declarations {
-                    self.visit_node(&var.id);
-                    if let Some(init) = &var.init {
-                        self.visit_node(init);
-                        self.instructions.push(Instruction::StoreLocal(0)); // Exemplo
+                    let name = match var.id.as_ref() {
+                        Node::Identifier(name) => name,
+                        pattern => {
+                            // O parser exige um inicializador para padrões fora de for-in/for-of
+                            match &var.init {
+                                Some(init) => self.visit_node(init),
+                                None => self.instructions.push(Instruction::PushUndefined),
+                            }
+                            self.bind_pattern(pattern, Binding::Declare { function_scoped });
+                            continue;
+                        }
+                    };
+                    // O inicializador é avaliado antes de a nova ligação existir
+                    match &var.init {
+                        Some(init) => self.visit_node(init),
+                        // Um slot reaproveitado pode conter o valor de outro bloco
+                        None if !function_scoped => self.instructions.push(Instruction::PushUndefined),
+                        None => {
+                            self.declare_local(name, true);
+                            continue;
+                        }
                     }
+                    let slot = self.declare_local(name, function_scoped);
+                    self.store_slot(slot);
                 }
             }
             Node::FunctionDeclaration(decl) => {
-                if let Some(id) = &decl.id {
-                    self.visit_node(id);
-                }
-                for param in &decl.params {
-                    self.visit_node(param);
-                }
-                self.visit_node(&decl.body);
-                // Instrução de função
+                let name = match decl.id.as_deref() {
+                    Some(Node::Identifier(name)) => name.as_str(),
+                    _ => unimplemented!("Function declarations without a name not implemented"),
+                };
+                self.visit_function(Some(name), &decl.params, &decl.body, decl.r#async);
+                let slot = self.declare_local(name, true);
+                self.store_slot(slot);
             }
             Node::ClassDeclaration(decl) => {
-                // Para simplificação, apenas empilha o nome da classe (ou None)
-                if let Some(id) = &decl.id {
-                    self.visit_node(id);
-                }
-                if let Some(super_class) = &decl.super_class {
-                    self.visit_node(super_class);
+                self.visit_class(decl.id.as_deref(), decl.super_class.as_deref(), &decl.body);
+                // Como `let`, a classe pertence ao escopo do bloco
+                if let Some(Node::Identifier(name)) = decl.id.as_deref() {
+                    let slot = self.declare_local(name, false);
+                    self.store_slot(slot);
                 }
-                self.visit_node(&decl.body);
-                self.instructions.push(Instruction::NewClass);
             }
             Node::ImportDeclaration(_)
             | Node::ExportDeclaration(_) => {
@@ -68,14 +839,7 @@ impl BytecodeGenerator {
                 // Placeholder: nenhuma instrução
             }
             Node::ClassExpression(expr) => {
-                if let Some(id) = &expr.id {
-                    self.visit_node(id);
-                }
-                if let Some(super_class) = &expr.super_class {
-                    self.visit_node(super_class);
-                }
-                self.visit_node(&expr.body);
-                self.instructions.push(Instruction::NewClass);
+                self.visit_class(expr.id.as_deref(), expr.super_class.as_deref(), &expr.body);
             }
             Node::YieldExpression(expr) => {
                 if let Some(arg) = &expr.argument {
@@ -87,53 +851,165 @@ impl BytecodeGenerator {
                 self.visit_node(&expr.argument);
                 self.instructions.push(Instruction::Await);
             }
+            Node::ParenthesizedExpression(expr) => self.visit_node(&expr.expression),
+            Node::SequenceExpression(expr) => {
+                // Só o valor da última expressão fica na pilha
+                for (i, expression) in expr.expressions.iter().enumerate() {
+                    if i > 0 {
+                        self.instructions.push(Instruction::Pop);
+                    }
+                    self.visit_node(expression);
+                }
+            }
             Node::SwitchStatement(stmt) => {
+                // Os testes rodam em ordem até um ser igual (===) ao valor; o
+                // default só é escolhido se nenhum for, mesmo que venha antes
+                let discriminant = self.allocate_slot();
                 self.visit_node(&stmt.discriminant);
+                self.instructions.push(Instruction::StoreLocal(discriminant));
+                let mut case_jumps = Vec::new();
                 for case in &stmt.cases {
                     if let Some(test) = &case.test {
+                        // O teste vem antes do valor: uma chamada no teste
+                        // consumiria o que estivesse abaixo dos argumentos como `this`
                         self.visit_node(test);
+                        self.instructions.push(Instruction::LoadLocal(discriminant));
+                        self.instructions.push(Instruction::StrictEq);
+                        case_jumps.push(Some(self.instructions.len()));
+                        self.instructions.push(Instruction::JumpIfTrue(0));
+                    } else {
+                        case_jumps.push(None);
+                    }
+                }
+                self.release_slots(&[discriminant]);
+                let no_match = self.instructions.len();
+                self.instructions.push(Instruction::Jump(0));
+                // Os corpos ficam em sequência, então sem `break` um caso continua no seguinte
+                self.enter_jump_target(TargetKind::Switch);
+                self.begin_scope();
+                let mut default_start = None;
+                for (case, jump) in stmt.cases.iter().zip(case_jumps) {
+                    let start = self.instructions.len();
+                    match jump {
+                        Some(jump) => self.patch_jump(jump, start),
+                        None => default_start = Some(start),
                     }
                     for cons in &case.consequent {
                         self.visit_node(cons);
                     }
                 }
-                // Placeholder: controle de fluxo real pode ser expandido
+                self.end_scope();
+                let end = self.instructions.len();
+                self.patch_jump(no_match, default_start.unwrap_or(end));
+                self.leave_jump_target(end);
             }
             Node::TryStatement(stmt) => {
-                self.visit_node(&stmt.block);
-                if let Some(handler) = &stmt.handler {
-                    self.visit_node(handler);
+                // O finally protege o bloco e o catch: numa exceção roda e relança
+                // (`return` dentro do try ainda não passa pelo finally)
+                let finally_try = stmt.finalizer.as_ref().map(|_| {
+                    self.instructions.push(Instruction::Try(0));
+                    self.handler_depth += 1;
+                    self.instructions.len() - 1
+                });
+                self.try_depth += 1;
+                match &stmt.handler {
+                    Some(handler) => {
+                        let catch_try = self.instructions.len();
+                        self.instructions.push(Instruction::Try(0));
+                        self.handler_depth += 1;
+                        self.visit_node(&stmt.block);
+                        self.instructions.push(Instruction::EndTry);
+                        // O handler do catch já saiu da pilha quando o catch roda
+                        self.handler_depth -= 1;
+                        let skip_catch = self.instructions.len();
+                        self.instructions.push(Instruction::Jump(0));
+                        self.patch_jump(catch_try, self.instructions.len());
+                        self.visit_node(handler);
+                        self.patch_jump(skip_catch, self.instructions.len());
+                    }
+                    None => self.visit_node(&stmt.block),
                 }
-                if let Some(finalizer) = &stmt.finalizer {
+                self.try_depth -= 1;
+                if let (Some(finally_try), Some(finalizer)) = (finally_try, &stmt.finalizer) {
+                    self.instructions.push(Instruction::EndTry);
+                    self.handler_depth -= 1;
                     self.visit_node(finalizer);
+                    let skip_rethrow = self.instructions.len();
+                    self.instructions.push(Instruction::Jump(0));
+                    // O valor lançado fica num temporário enquanto o finally roda
+                    self.patch_jump(finally_try, self.instructions.len());
+                    let thrown = self.allocate_slot();
+                    self.instructions.push(Instruction::StoreLocal(thrown));
+                    self.visit_node(finalizer);
+                    self.instructions.push(Instruction::LoadLocal(thrown));
+                    self.instructions.push(Instruction::Throw);
+                    self.release_slots(&[thrown]);
+                    self.patch_jump(skip_rethrow, self.instructions.len());
                 }
-                self.instructions.push(Instruction::Try(0, 0)); // Placeholder
             }
             Node::CatchClause(clause) => {
-                self.visit_node(&clause.param);
+                // O valor lançado está no topo da pilha; o parâmetro pertence ao escopo do catch
+                self.begin_scope();
+                match clause.param.as_ref() {
+                    Node::Identifier(name) => {
+                        let slot = self.declare_local(name, false);
+                        self.store_slot(slot);
+                    }
+                    _ => unimplemented!("Destructuring patterns not implemented"),
+                }
                 self.visit_node(&clause.body);
-                self.instructions.push(Instruction::Catch);
+                self.end_scope();
             }
             Node::ThrowStatement(stmt) => {
                 self.visit_node(&stmt.argument);
                 self.instructions.push(Instruction::Throw);
             }
-            Node::ReturnStatement(stmt) => {
-                if let Some(arg) = &stmt.argument {
-                    self.visit_node(arg);
+            Node::ReturnStatement(stmt) => match stmt.argument.as_deref() {
+                // `return f(x)` está em posição de cauda: a chamada substitui o frame atual
+                // (`return a?.f()` não, pois pode resultar em undefined sem chamar nada)
+                // (nem dentro de um try, cujo handler fica no frame substituído,
+                // nem numa função async, cuja promise é resolvida pelo próprio frame)
+                Some(Node::CallExpression(call))
+                    if self.try_depth == 0
+                        && !self.is_async
+                        && !call.arguments.iter().any(|arg| matches!(arg, Node::SpreadElement(_)))
+                        && !has_optional_link(&call.callee)
+                        && method_callee(&call.callee).is_none() =>
+                {
+                    for arg in &call.arguments {
+                        self.visit_node(arg);
+                    }
+                    self.visit_node(&call.callee);
+                    self.instructions.push(Instruction::TailCall(call.arguments.len()));
                 }
-                self.instructions.push(Instruction::Return);
-            }
-            Node::BreakStatement(_) => {
-                self.instructions.push(Instruction::Jump(0)); // Placeholder
-            }
-            Node::ContinueStatement(_) => {
-                self.instructions.push(Instruction::Jump(0)); // Placeholder
-            }
+                argument => {
+                    if let Some(arg) = argument {
+                        self.visit_node(arg);
+                    }
+                    self.instructions.push(Instruction::Return);
+                }
+            },
+            Node::BreakStatement(stmt) => self.visit_jump(stmt.label.as_deref(), false),
+            Node::ContinueStatement(stmt) => self.visit_jump(stmt.label.as_deref(), true),
             Node::LabeledStatement(stmt) => {
-                self.visit_node(&stmt.label);
-                self.visit_node(&stmt.body);
-                // Placeholder: controle de fluxo real pode ser expandido
+                let Node::Identifier(label) = stmt.label.as_ref() else {
+                    panic!("Invalid label {:?}", stmt.label)
+                };
+                self.pending_labels.push(label.clone());
+                match stmt.body.as_ref() {
+                    // O laço (ou o próximo rótulo) assume os rótulos pendentes
+                    Node::ForStatement(_)
+                    | Node::WhileStatement(_)
+                    | Node::DoWhileStatement(_)
+                    | Node::LabeledStatement(_) => self.visit_node(&stmt.body),
+                    // Outros comandos só aceitam `break rótulo`
+                    body => {
+                        self.enter_jump_target(TargetKind::Labeled);
+                        self.visit_node(body);
+                        let end = self.instructions.len();
+                        self.leave_jump_target(end);
+                    }
+                }
             }
             Node::WithStatement(stmt) => {
                 self.visit_node(&stmt.object);
@@ -144,10 +1020,20 @@ impl BytecodeGenerator {
                 // Debugger: sem instrução específica
             }
             Node::TemplateLiteral(lit) => {
-                for expr in &lit.expressions {
+                // Concatena os trechos com os valores: o primeiro trecho é string, então Add concatena
+                let quasi = |index: usize| lit.quasis.get(index).map_or(String::new(), |quasi| quasi.value.clone());
+                let idx = self.constants.add(Constant::String(quasi(0)));
+                self.instructions.push(Instruction::PushConst(idx));
+                for (index, expr) in lit.expressions.iter().enumerate() {
                     self.visit_node(expr);
+                    self.instructions.push(Instruction::Add);
+                    let text = quasi(index + 1);
+                    if !text.is_empty() {
+                        let idx = self.constants.add(Constant::String(text));
+                        self.instructions.push(Instruction::PushConst(idx));
+                        self.instructions.push(Instruction::Add);
+                    }
                 }
-                // Placeholder: empilha strings/quasis
             }
             Node::TaggedTemplateExpression(expr) => {
                 self.visit_node(&expr.tag);
@@ -176,130 +1062,285 @@ impl BytecodeGenerator {
             Node::BinaryExpression(expr) => {
                 self.visit_node(&expr.left);
                 self.visit_node(&expr.right);
-                match expr.operator.as_str() {
-                    "+" => self.instructions.push(Instruction::Add),
-                    "-" => self.instructions.push(Instruction::Sub),
-                    "*" => self.instructions.push(Instruction::Mul),
-                    "/" => self.instructions.push(Instruction::Div),
-                    _ => unimplemented!("Operator {} not implemented", expr.operator),
-                }
+                self.instructions.push(binary_instruction(&expr.operator));
             }
+            Node::UnaryExpression(expr) if expr.operator == "delete" => match expr.argument.as_ref() {
+                Node::MemberExpression(member) => {
+                    self.visit_node(&member.object);
+                    self.visit_member_key(member);
+                    self.instructions.push(Instruction::DeleteProperty);
+                }
+                argument => {
+                    self.visit_node(argument);
+                    self.instructions.push(Instruction::Delete);
+                }
+            },
             Node::UnaryExpression(expr) => {
                 self.visit_node(&expr.argument);
-                // Instrução unária
-            }
-            Node::CallExpression(expr) => {
-                for arg in &expr.arguments {
-                    self.visit_node(arg);
+                match expr.operator.as_str() {
+                    "!" => self.instructions.push(Instruction::Not),
+                    "typeof" => self.instructions.push(Instruction::TypeOf),
+                    _ => {}
                 }
-                self.visit_node(&expr.callee);
-                self.instructions.push(Instruction::Call(expr.arguments.len()));
+                // Demais operadores unários: a implementar
             }
+            Node::CallExpression(_) | Node::MemberExpression(_) => self.visit_chain(node),
             Node::NewExpression(expr) => {
                 for arg in &expr.arguments {
                     self.visit_node(arg);
                 }
                 self.visit_node(&expr.callee);
-                self.instructions.push(Instruction::New);
+                self.instructions.push(Instruction::New(expr.arguments.len()));
             }
-            Node::MemberExpression(expr) => {
-                self.visit_node(&expr.object);
-                self.visit_node(&expr.property);
-                self.instructions.push(Instruction::GetProperty);
+            Node::AssignmentExpression(expr) if expr.operator != "=" => {
+                // `a op= b`: o operador de `a op b`, sem o `=` final
+                let operator = &expr.operator[..expr.operator.len() - 1];
+                match expr.left.as_ref() {
+                    Node::Identifier(name) => {
+                        self.load_variable(name);
+                        self.visit_node(&expr.right);
+                        self.instructions.push(binary_instruction(operator));
+                        self.instructions.push(Instruction::Dup);
+                        self.store_variable(name);
+                    }
+                    Node::MemberExpression(member) => {
+                        // Objeto e chave ficam em temporários: são avaliados uma vez só
+                        let temps = [self.allocate_slot(), self.allocate_slot(), self.allocate_slot()];
+                        let [object, key, value] = temps;
+                        self.visit_node(&member.object);
+                        self.instructions.push(Instruction::StoreLocal(object));
+                        self.visit_member_key(member);
+                        self.instructions.push(Instruction::StoreLocal(key));
+                        self.instructions.push(Instruction::LoadLocal(object));
+                        self.instructions.push(Instruction::LoadLocal(key));
+                        self.instructions.push(Instruction::GetProperty);
+                        self.visit_node(&expr.right);
+                        self.instructions.push(binary_instruction(operator));
+                        self.instructions.push(Instruction::StoreLocal(value));
+                        self.instructions.push(Instruction::LoadLocal(object));
+                        self.instructions.push(Instruction::LoadLocal(key));
+                        self.instructions.push(Instruction::LoadLocal(value));
+                        self.instructions.push(Instruction::SetProperty);
+                        self.instructions.push(Instruction::LoadLocal(value));
+                        self.release_slots(&temps);
+                    }
+                    _ => unimplemented!("Invalid compound assignment target"),
+                }
             }
             Node::AssignmentExpression(expr) => {
-                self.visit_node(&expr.right);
-                self.visit_node(&expr.left);
-                self.instructions.push(Instruction::StoreLocal(0)); // Exemplo
+                match expr.left.as_ref() {
+                    Node::Identifier(name) => {
+                        self.visit_node(&expr.right);
+                        // A atribuição também é uma expressão: mantém o valor na pilha
+                        self.instructions.push(Instruction::Dup);
+                        self.store_variable(name);
+                    }
+                    Node::MemberExpression(member) => {
+                        // SetProperty não deixa nada na pilha: o valor é guardado
+                        // num temporário e recarregado como resultado da atribuição
+                        let value = self.allocate_slot();
+                        self.visit_node(&member.object);
+                        self.visit_member_key(member);
+                        self.visit_node(&expr.right);
+                        self.instructions.push(Instruction::Dup);
+                        self.instructions.push(Instruction::StoreLocal(value));
+                        self.instructions.push(Instruction::SetProperty);
+                        self.instructions.push(Instruction::LoadLocal(value));
+                        self.release_slots(&[value]);
+                    }
+                    // A atribuição vale o lado direito, que fica na pilha
+                    pattern @ (Node::ObjectPattern(_) | Node::ArrayPattern(_)) => {
+                        self.visit_node(&expr.right);
+                        self.instructions.push(Instruction::Dup);
+                        self.bind_pattern(pattern, Binding::Assign);
+                    }
+                    left => {
+                        self.visit_node(&expr.right);
+                        self.visit_node(left);
+                        self.instructions.push(Instruction::StoreLocal(0)); // Exemplo
+                    }
+                }
             }
             Node::ConditionalExpression(expr) => {
                 self.visit_node(&expr.test);
-                // JumpIfFalse, consequent, alternate
+                let jump_to_alternate = self.instructions.len();
+                self.instructions.push(Instruction::JumpIfFalse(0));
                 self.visit_node(&expr.consequent);
+                let jump_to_end = self.instructions.len();
+                self.instructions.push(Instruction::Jump(0));
+                let alternate = self.instructions.len();
+                self.patch_jump(jump_to_alternate, alternate);
                 self.visit_node(&expr.alternate);
+                let end = self.instructions.len();
+                self.patch_jump(jump_to_end, end);
             }
             Node::LogicalExpression(expr) => {
+                // O operando esquerdo fica na pilha como resultado se a avaliação
+                // for curto-circuitada; caso contrário é descartado e o direito é avaliado
                 self.visit_node(&expr.left);
+                self.instructions.push(Instruction::Dup);
+                let jump = self.instructions.len();
+                match expr.operator.as_str() {
+                    "&&" => self.instructions.push(Instruction::JumpIfFalse(0)),
+                    "||" => self.instructions.push(Instruction::JumpIfTrue(0)),
+                    "??" => self.instructions.push(Instruction::JumpIfNotNullish(0)),
+                    _ => unimplemented!("Operator {} not implemented", expr.operator),
+                }
+                self.instructions.push(Instruction::Pop);
                 self.visit_node(&expr.right);
-                // Instrução lógica
-            }
-            Node::UpdateExpression(expr) => {
-                self.visit_node(&expr.argument);
-                // Instrução de update
+                let end = self.instructions.len();
+                self.patch_jump(jump, end);
             }
+            Node::UpdateExpression(expr) => self.visit_update(expr),
             Node::ArrowFunctionExpression(expr) => {
-                for param in &expr.params {
-                    self.visit_node(param);
-                }
-                self.visit_node(&expr.body);
-                // Instrução de função (arrow)
+                self.visit_function(None, &expr.params, &expr.body, expr.r#async);
             }
             Node::FunctionExpression(expr) => {
-                if let Some(id) = &expr.id {
-                    self.visit_node(id);
-                }
-                for param in &expr.params {
-                    self.visit_node(param);
-                }
-                self.visit_node(&expr.body);
-                // Instrução de função (function expression)
-            }
-            Node::ClassExpression(_)
-            | Node::YieldExpression(_)
-            | Node::AwaitExpression(_) => {
-                // TODO: Implementar
-                unimplemented!("Class/Yield/Await not implemented");
+                let name = match expr.id.as_deref() {
+                    Some(Node::Identifier(name)) => Some(name.as_str()),
+                    _ => None,
+                };
+                self.visit_function(name, &expr.params, &expr.body, expr.r#async);
             }
             // Statements
             Node::BlockStatement(stmt) => {
+                self.begin_scope();
+                self.declare_block_cells(&stmt.body);
                 for node in &stmt.body {
                     self.visit_node(node);
                 }
+                self.end_scope();
             }
             Node::IfStatement(stmt) => {
                 self.visit_node(&stmt.test);
+                let skip_consequent = self.instructions.len();
+                self.instructions.push(Instruction::JumpIfFalse(0));
                 self.visit_node(&stmt.consequent);
-                if let Some(alt) = &stmt.alternate {
-                    self.visit_node(alt);
+                match &stmt.alternate {
+                    Some(alt) => {
+                        let skip_alternate = self.instructions.len();
+                        self.instructions.push(Instruction::Jump(0));
+                        self.patch_jump(skip_consequent, self.instructions.len());
+                        self.visit_node(alt);
+                        self.patch_jump(skip_alternate, self.instructions.len());
+                    }
+                    None => self.patch_jump(skip_consequent, self.instructions.len()),
                 }
             }
             Node::ForStatement(stmt) => {
+                // Declarações no init pertencem ao escopo do laço
+                self.begin_scope();
                 if let Some(init) = &stmt.init {
                     self.visit_node(init);
+                    if !matches!(init.as_ref(), Node::VariableDeclaration(_)) {
+                        self.instructions.push(Instruction::Pop);
+                    }
                 }
-                if let Some(test) = &stmt.test {
+                let start = self.instructions.len();
+                let exit = stmt.test.as_ref().map(|test| {
                     self.visit_node(test);
+                    self.instructions.push(Instruction::JumpIfFalse(0));
+                    self.instructions.len() - 1
+                });
+                self.enter_jump_target(TargetKind::Loop);
+                self.visit_node(&stmt.body);
+                // `continue` segue para a atualização
+                let update_start = self.instructions.len();
+                // Cada iteração tem sua própria cópia das variáveis `let` do laço:
+                // closures criadas no corpo ficam com a célula da iteração
+                let scope = self.scopes.last().into_iter().flat_map(|scope| scope.values());
+                let mut loop_cells: Vec<usize> = scope.filter(|slot| self.cells.contains(slot)).copied().collect();
+                loop_cells.sort_unstable();
+                for slot in loop_cells {
+                    self.instructions.push(Instruction::LoadCell(slot));
+                    self.instructions.push(Instruction::NewCell(slot));
+                    self.instructions.push(Instruction::StoreCell(slot));
                 }
                 if let Some(update) = &stmt.update {
                     self.visit_node(update);
+                    self.instructions.push(Instruction::Pop);
                 }
-                self.visit_node(&stmt.body);
+                self.instructions.push(Instruction::Jump(start));
+                if let Some(exit) = exit {
+                    self.patch_jump(exit, self.instructions.len());
+                }
+                self.leave_jump_target(update_start);
+                self.end_scope();
+            }
+            Node::ForOfStatement(stmt) if stmt.r#await => unimplemented!("for await not implemented"),
+            Node::ForOfStatement(stmt) => {
+                self.visit_node(&stmt.right);
+                self.instructions.push(Instruction::GetIterator);
+                self.visit_iteration(&stmt.left, &stmt.body);
+            }
+            Node::ForInStatement(stmt) => {
+                // Percorre um array com as chaves enumeráveis do objeto
+                self.visit_node(&stmt.right);
+                self.instructions.push(Instruction::EnumerateKeys);
+                self.instructions.push(Instruction::GetIterator);
+                self.visit_iteration(&stmt.left, &stmt.body);
             }
             Node::WhileStatement(stmt) => {
+                let start = self.instructions.len();
                 self.visit_node(&stmt.test);
+                let exit = self.instructions.len();
+                self.instructions.push(Instruction::JumpIfFalse(0));
+                self.enter_jump_target(TargetKind::Loop);
                 self.visit_node(&stmt.body);
+                self.instructions.push(Instruction::Jump(start));
+                self.patch_jump(exit, self.instructions.len());
+                self.leave_jump_target(start);
             }
             Node::DoWhileStatement(stmt) => {
+                let start = self.instructions.len();
+                self.enter_jump_target(TargetKind::Loop);
                 self.visit_node(&stmt.body);
+                // `continue` segue para o teste
+                let test_start = self.instructions.len();
                 self.visit_node(&stmt.test);
+                self.instructions.push(Instruction::JumpIfTrue(start));
+                self.leave_jump_target(test_start);
             }
             Node::ExpressionStatement(stmt) => {
                 self.visit_node(&stmt.expression);
+                // O valor de uma atribuição usada como instrução é descartado
+                if matches!(stmt.expression.as_ref(), Node::AssignmentExpression(_)) {
+                    self.instructions.push(Instruction::Pop);
+                }
             }
             // Literals
             Node::ArrayLiteral(lit) => {
-                for elem in &lit.elements {
-                    if let Some(e) = elem {
-                        self.visit_node(e);
-                    }
-                }
-                self.instructions.push(Instruction::NewArray(lit.elements.len()));
+                let elements: Vec<Option<&Node>> = lit.elements.iter().map(Option::as_ref).collect();
+                self.visit_array_elements(&elements);
             }
             Node::ObjectLiteral(lit) => {
+                self.instructions.push(Instruction::NewObject);
                 for prop in &lit.properties {
-                    self.visit_node(prop);
+                    match prop {
+                        Node::Property(prop) if prop.kind != "init" => {
+                            unimplemented!("Getters and setters not implemented")
+                        }
+                        Node::Property(prop) => {
+                            // obj[key] = value, mantendo o objeto na pilha
+                            self.instructions.push(Instruction::Dup);
+                            match prop.key.as_ref() {
+                                Node::Identifier(name) if !prop.computed => {
+                                    let idx = self.constants.add(Constant::String(name.clone()));
+                                    self.instructions.push(Instruction::PushConst(idx));
+                                }
+                                key => self.visit_node(key),
+                            }
+                            self.visit_node(&prop.value);
+                            self.instructions.push(Instruction::SetProperty);
+                        }
+                        Node::SpreadElement(elem) => {
+                            // Copia as propriedades próprias para o objeto no topo
+                            self.visit_node(&elem.argument);
+                            self.instructions.push(Instruction::Spread);
+                        }
+                        other => self.visit_node(other),
+                    }
                 }
-                self.instructions.push(Instruction::NewObject);
             }
             Node::TemplateLiteral(_)
             | Node::TaggedTemplateExpression(_) => {
@@ -316,19 +1357,27 @@ impl BytecodeGenerator {
                 self.visit_node(&elem.argument);
                 // Instrução de rest
             }
+            Node::ObjectPattern(_)
+            | Node::ArrayPattern(_)
+            | Node::AssignmentPattern(_) => {
+                // TODO: Implementar
+                unimplemented!("Destructuring patterns not implemented");
+            }
             Node::Super(_)
             | Node::MetaProperty(_)
             | Node::SpreadElement(_) => {
                 // TODO: Implementar
                 unimplemented!("Super/Meta/Spread not implemented");
             }
-            Node::Identifier(_name) => {
-                self.instructions.push(Instruction::LoadLocal(0));
-            }
+            Node::Identifier(name) => self.load_variable(name),
             Node::Number(n) => {
                 let idx = self.constants.add(Constant::Number(*n));
                 self.instructions.push(Instruction::PushConst(idx));
             }
+            Node::NumericLiteral(lit) => {
+                let idx = self.constants.add(Constant::Number(lit.value));
+                self.instructions.push(Instruction::PushConst(idx));
+            }
             Node::String(s) => {
                 let idx = self.constants.add(Constant::String(s.clone()));
                 self.instructions.push(Instruction::PushConst(idx));
@@ -343,8 +1392,8 @@ impl BytecodeGenerator {
             Node::Undefined => {
                 self.instructions.push(Instruction::PushUndefined);
             }
-            Node::This => {
-                self.instructions.push(Instruction::LoadLocal(0)); // Exemplo
+            Node::This(_) => {
+                self.instructions.push(Instruction::LoadThis);
             }
             Node::RegExp(_)
             | Node::BigInt(_) => {
@@ -356,8 +1405,118 @@ impl BytecodeGenerator {
 }
 
 impl ConstantPool {
+    /// Add a constant, reusing the slot of an identical one
     pub fn add(&mut self, value: Constant) -> usize {
+        if let Some(index) = self.values.iter().position(|existing| existing.is_identical(&value)) {
+            return index;
+        }
         self.values.push(value);
         self.values.len() - 1
     }
-} 
\ No newline at end of file
+}
+
+impl Constant {
+    /// Whether two constants can share a pool slot
+    ///
+    /// Numbers compare by bit pattern rather than `==`, so every `NaN`
+    /// shares one slot while `+0` and `-0` keep their own.
+    pub fn is_identical(&self, other: &Constant) -> bool {
+        match (self, other) {
+            (Constant::Number(a), Constant::Number(b)) => {
+                a.to_bits() == b.to_bits() || (a.is_nan() && b.is_nan())
+            }
+            _ => self == other,
+        }
+    }
+}
+
+/// The instruction for a binary operator, which pops both operands
+fn binary_instruction(operator: &str) -> Instruction {
+    match operator {
+        "+" => Instruction::Add,
+        "-" => Instruction::Sub,
+        "*" => Instruction::Mul,
+        "/" => Instruction::Div,
+        "%" => Instruction::Mod,
+        "==" => Instruction::Eq,
+        "!=" => Instruction::Ne,
+        "===" => Instruction::StrictEq,
+        "!==" => Instruction::StrictNe,
+        "<" => Instruction::Lt,
+        ">" => Instruction::Gt,
+        "<=" => Instruction::Le,
+        ">=" => Instruction::Ge,
+        "instanceof" => Instruction::InstanceOf,
+        "in" => Instruction::In,
+        _ => unimplemented!("Operator {} not implemented", operator),
+    }
+}
+
+/// Whether a chain of member accesses and calls contains `?.`
+fn has_optional_link(node: &Node) -> bool {
+    match node {
+        Node::MemberExpression(member) => member.optional || has_optional_link(&member.object),
+        Node::CallExpression(call) => has_optional_link(&call.callee),
+        _ => false,
+    }
+}
+
+/// The member expression of a method call `a.b()`, whose object is the `this`
+/// of the call
+///
+/// Optional chains are left to `Call`, which passes no `this`.
+fn method_callee(node: &Node) -> Option<&MemberExpression> {
+    match node {
+        Node::MemberExpression(member) if !has_optional_link(node) => Some(member),
+        _ => None,
+    }
+}
+
+/// Collects the names used inside the functions nested in `node`: the
+/// variables a closure may capture
+fn collect_nested_names(node: &Node, nested: bool, names: &mut HashSet<String>) {
+    let nested = nested
+        || matches!(node, Node::FunctionDeclaration(_) | Node::FunctionExpression(_) | Node::ArrowFunctionExpression(_));
+    if let (true, Node::Identifier(name)) = (nested, node) {
+        names.insert(name.clone());
+    }
+    for child in node.children() {
+        collect_nested_names(child, nested, names);
+    }
+}
+
+/// Collects the names declared with `var` or as functions in a function
+/// body, without entering nested functions
+fn collect_var_names(node: &Node, names: &mut Vec<String>) {
+    match node {
+        Node::VariableDeclaration(decl) if decl.kind == "var" => {
+            for var in &decl.declarations {
+                pattern_names(&var.id, names);
+            }
+        }
+        Node::FunctionDeclaration(decl) => {
+            if let Some(Node::Identifier(name)) = decl.id.as_deref() {
+                names.push(name.clone());
+            }
+            return;
+        }
+        Node::FunctionExpression(_) | Node::ArrowFunctionExpression(_) => return,
+        _ => {}
+    }
+    for child in node.children() {
+        collect_var_names(child, names);
+    }
+}
+
+/// Collects the variables bound by a declaration pattern
+fn pattern_names(pattern: &Node, names: &mut Vec<String>) {
+    match pattern {
+        Node::Identifier(name) => names.push(name.clone()),
+        Node::AssignmentPattern(assign) => pattern_names(&assign.left, names),
+        Node::RestElement(rest) => pattern_names(&rest.argument, names),
+        Node::Property(prop) => pattern_names(&prop.value, names),
+        Node::ObjectPattern(object) => object.properties.iter().for_each(|property| pattern_names(property, names)),
+        Node::ArrayPattern(array) => array.elements.iter().flatten().for_each(|element| pattern_names(element, names)),
+        _ => {}
+    }
+}
@@ -16,26 +16,72 @@ pub enum Instruction {
     // Variables
     LoadGlobal(String), StoreGlobal(String),
     LoadLocal(usize), StoreLocal(usize),
+    NewCell(usize), // puts a fresh cell in the slot, for a local captured by closures
+    LoadCell(usize), StoreCell(usize), // read/write the value held by the cell in the slot
     // Control flow
     Jump(usize), JumpIfTrue(usize), JumpIfFalse(usize),
+    JumpIfNotNullish(usize), // pops; jumps unless the value is null/undefined (for `??`)
+    JumpIfNullish(usize), // pops; jumps if the value is null/undefined (for `?.`)
     // Functions
     Call(usize), Return,
+    CallWithSpread, // pops callee and an array of arguments (for `f(...args)`)
+    TailCall(usize), // like Call in tail position; the callee replaces the current frame
+    CallMethod(usize), // like Call, with the receiver below the arguments becoming `this`
+    LoadArg(usize), // pushes an argument of the current call
+    LoadRestArgs(usize), // pushes an array of the arguments from this index on (for `...rest`)
+    LoadClosureVar(String), // pushes a variable captured when the function was created
+    StoreClosureVar(String), // pops into a captured variable; the enclosing frame sees the write
+    LoadThisFunction, // pushes the running function (a function referring to its own name)
+    LoadThis, // pushes the `this` value of the current call (the new object under `New`)
+    CreateFunction(usize), // pushes a new function built from `functions[index]`
     // Objects/Arrays
-    NewObject, NewArray(usize), SetProperty, GetProperty,
+    NewObject, // pushes an empty object; properties follow as Dup, key, value, SetProperty
+    NewArray(usize), // pops n elements (the first pushed becomes index 0); pushes the array
+    SetProperty, GetProperty,
+    DeleteProperty, // pops key and object; removes the own property and pushes the result
+    RestElements(usize), // pops an iterable; pushes an array of its elements from this index on (for `[a, ...rest]`)
+    RestProperties(usize), // pops n keys and an object; pushes an object with its other own properties (for `{a, ...rest}`)
+    GetIterator, // pops an iterable; pushes the iterator stepped by IteratorNext (for `for-of`)
+    IteratorNext, // pops a step count and the iterator; pushes the next value and whether iteration is done
+    CloseIterator, // pops the iterator and calls its `return`, when a for-of is left early
+    EnumerateKeys, // pops a value; pushes an array of the keys a for-in visits
     // Special
-    TypeOf, InstanceOf, In, Delete, New,
+    TypeOf, InstanceOf, In, Delete,
+    New(usize), // pops the constructor and its arguments; pushes the new object
     // Classes/Prototypes
     NewClass, GetPrototype, SetPrototype,
+    CreateClass(usize), // pops a constructor and n (name, method) pairs; pushes the constructor
     // Async/Generators
     Await, Yield,
     // Exception handling
-    Throw, Try(usize, usize), Catch, Finally,
+    Throw, Catch, Finally,
+    Try(usize), // Até o EndTry, uma exceção desvia para o endereço com o valor lançado no topo da pilha
+    EndTry, // Fim do bloco protegido: remove o handler do Try mais recente
     // Modern JS
     Spread, Destructure, OptionalChain, NullishCoalesce,
     // Literals
     PushNull, PushUndefined, PushTrue, PushFalse, PushSymbol(usize), PushBigInt(usize),
 }
 
+/// Where a captured variable's value comes from when the function is created
+#[derive(Debug, Clone, PartialEq)]
+pub enum Capture {
+    Local(usize), // a local slot of the code creating the function
+    Closure,      // a variable of the same name the creating function captured itself
+}
+
+/// A compiled function, instantiated by `CreateFunction`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FunctionInfo {
+    pub name: String,
+    pub param_count: usize,
+    pub local_count: usize,
+    pub instructions: Vec<Instruction>,
+    pub functions: Vec<FunctionInfo>, // functions created by this body, by index
+    pub captures: Vec<(String, Capture)>, // read with LoadClosureVar(name)
+    pub is_async: bool, // calls return a promise; `Await` suspends the frame
+}
+
 /// Pool of constants used by the bytecode
 #[derive(Debug, Default)]
 pub struct ConstantPool {
@@ -271,7 +271,8 @@ fn test_try_statement() {
     });
     let mut gen = BytecodeGenerator::new();
     gen.generate(&try_stmt);
-    assert_eq!(gen.instructions.last(), Some(&Instruction::Try(0, 0)));
+    // Sem catch nem finally não há handler a instalar
+    assert!(gen.instructions.is_empty());
 }
 
 #[test]
@@ -283,7 +284,7 @@ fn test_catch_clause() {
     });
     let mut gen = BytecodeGenerator::new();
     gen.generate(&catch);
-    assert_eq!(gen.instructions.last(), Some(&Instruction::Catch));
+    assert_eq!(gen.instructions, vec![Instruction::StoreLocal(0)]);
 }
 
 #[test]
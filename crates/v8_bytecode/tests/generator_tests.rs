@@ -0,0 +1,484 @@
+use v8_ast::{
+    AssignmentExpression, BinaryExpression, BlockStatement, BreakStatement, CallExpression, CatchClause,
+    ContinueStatement, ExpressionStatement, LabeledStatement, LogicalExpression, MemberExpression, Node,
+    ReturnStatement, ThrowStatement, TryStatement, VariableDeclaration, VariableDeclarator, WhileStatement,
+};
+use v8_bytecode::generator::BytecodeGenerator;
+use v8_bytecode::instructions::{Constant, Instruction};
+
+fn logical(left: Node, operator: &str, right: Node) -> Node {
+    Node::LogicalExpression(LogicalExpression {
+        left: Box::new(left),
+        operator: operator.to_string(),
+        right: Box::new(right),
+        span: None,
+    })
+}
+
+fn let_declaration(name: &str, value: f64) -> Node {
+    Node::VariableDeclaration(VariableDeclaration {
+        kind: "let".to_string(),
+        declarations: vec![VariableDeclarator {
+            id: Box::new(Node::Identifier(name.to_string())),
+            init: Some(Box::new(Node::Number(value))),
+            span: None,
+        }],
+        span: None,
+    })
+}
+
+fn block(body: Vec<Node>) -> Node {
+    Node::BlockStatement(BlockStatement { body, span: None })
+}
+
+#[test]
+fn test_logical_operators_short_circuit() {
+    for (operator, jump) in [
+        ("&&", Instruction::JumpIfFalse(5)),
+        ("||", Instruction::JumpIfTrue(5)),
+        ("??", Instruction::JumpIfNotNullish(5)),
+    ] {
+        let mut generator = BytecodeGenerator::new();
+        generator.generate(&logical(Node::Number(0.0), operator, Node::Number(1.0)));
+        assert_eq!(
+            generator.instructions,
+            vec![
+                Instruction::PushConst(0),
+                Instruction::Dup,
+                jump,
+                Instruction::Pop,
+                Instruction::PushConst(1),
+            ]
+        );
+    }
+}
+
+#[test]
+fn test_nested_logical_jump_targets() {
+    // (a && b) || c: the outer jump skips the whole right operand
+    let mut generator = BytecodeGenerator::new();
+    generator.generate(&logical(
+        logical(Node::Boolean(true), "&&", Node::Boolean(false)),
+        "||",
+        Node::Number(1.0),
+    ));
+    assert_eq!(
+        generator.instructions,
+        vec![
+            Instruction::PushConst(0),
+            Instruction::Dup,
+            Instruction::JumpIfFalse(5),
+            Instruction::Pop,
+            Instruction::PushConst(1),
+            Instruction::Dup,
+            Instruction::JumpIfTrue(9),
+            Instruction::Pop,
+            Instruction::PushConst(2),
+        ]
+    );
+}
+
+#[test]
+fn test_conditional_expression_jumps() {
+    let mut generator = BytecodeGenerator::new();
+    generator.generate(&Node::ConditionalExpression(v8_ast::ConditionalExpression {
+        test: Box::new(Node::Boolean(true)),
+        consequent: Box::new(Node::Number(1.0)),
+        alternate: Box::new(Node::Number(2.0)),
+        span: None,
+    }));
+    assert_eq!(
+        generator.instructions,
+        vec![
+            Instruction::PushConst(0),
+            Instruction::JumpIfFalse(4),
+            Instruction::PushConst(1),
+            Instruction::Jump(5),
+            Instruction::PushConst(2),
+        ]
+    );
+}
+
+#[test]
+fn test_array_spread_groups_plain_elements() {
+    // [...a, 1, 2]
+    let mut generator = BytecodeGenerator::new();
+    generator.generate(&Node::ArrayLiteral(v8_ast::ArrayLiteral {
+        elements: vec![
+            Some(Node::SpreadElement(v8_ast::SpreadElement {
+                argument: Box::new(Node::String("ab".to_string())),
+                span: None,
+            })),
+            Some(Node::Number(1.0)),
+            Some(Node::Number(2.0)),
+        ],
+        span: None,
+    }));
+    assert_eq!(
+        generator.instructions,
+        vec![
+            Instruction::NewArray(0),
+            Instruction::PushConst(0),
+            Instruction::Spread,
+            Instruction::PushConst(1),
+            Instruction::PushConst(2),
+            Instruction::NewArray(2),
+            Instruction::Spread,
+        ]
+    );
+}
+
+#[test]
+fn test_sibling_blocks_reuse_local_slots() {
+    // { let a = 1; } { let b = 2; }
+    let mut generator = BytecodeGenerator::new();
+    generator.generate(&block(vec![
+        block(vec![let_declaration("a", 1.0)]),
+        block(vec![let_declaration("b", 2.0)]),
+    ]));
+    assert_eq!(
+        generator.instructions,
+        vec![
+            Instruction::PushConst(0),
+            Instruction::StoreLocal(0),
+            Instruction::PushConst(1),
+            Instruction::StoreLocal(0),
+        ]
+    );
+    assert_eq!(generator.local_count, 1);
+}
+
+#[test]
+fn test_nested_scopes_keep_live_slots() {
+    // { let a = 1; { let b = 2; b; } let c = 3; a; }
+    let mut generator = BytecodeGenerator::new();
+    generator.generate(&block(vec![
+        let_declaration("a", 1.0),
+        block(vec![let_declaration("b", 2.0), Node::Identifier("b".to_string())]),
+        let_declaration("c", 3.0),
+        Node::Identifier("a".to_string()),
+    ]));
+    assert_eq!(
+        generator.instructions,
+        vec![
+            Instruction::PushConst(0),
+            Instruction::StoreLocal(0),
+            Instruction::PushConst(1),
+            Instruction::StoreLocal(1),
+            Instruction::LoadLocal(1),
+            Instruction::PushConst(2),
+            Instruction::StoreLocal(1),
+            Instruction::LoadLocal(0),
+        ]
+    );
+    assert_eq!(generator.local_count, 2);
+}
+
+#[test]
+fn test_return_call_is_tail_call() {
+    let call = |callee: &str| {
+        Node::CallExpression(CallExpression {
+            callee: Box::new(Node::Identifier(callee.to_string())),
+            arguments: vec![Node::Number(1.0)],
+            span: None,
+        })
+    };
+    // return f(1);
+    let mut generator = BytecodeGenerator::new();
+    generator.generate(&Node::ReturnStatement(ReturnStatement {
+        argument: Some(Box::new(call("f"))),
+        span: None,
+    }));
+    assert_eq!(
+        generator.instructions,
+        vec![
+            Instruction::PushConst(0),
+            Instruction::LoadGlobal("f".to_string()),
+            Instruction::TailCall(1),
+        ]
+    );
+
+    // return f(1) + 1; the call is not the last thing the function does
+    let mut generator = BytecodeGenerator::new();
+    generator.generate(&Node::ReturnStatement(ReturnStatement {
+        argument: Some(Box::new(Node::BinaryExpression(BinaryExpression {
+            left: Box::new(call("f")),
+            operator: "+".to_string(),
+            right: Box::new(Node::Number(1.0)),
+            span: None,
+        }))),
+        span: None,
+    }));
+    assert!(generator.instructions.contains(&Instruction::Call(1)));
+    assert_eq!(generator.instructions.last(), Some(&Instruction::Return));
+}
+
+#[test]
+fn test_try_catch_finally_layout() {
+    // try { throw 1; } catch (e) { return f(1); } finally { 2; }
+    let try_stmt = Node::TryStatement(TryStatement {
+        block: Box::new(block(vec![Node::ThrowStatement(ThrowStatement {
+            argument: Box::new(Node::Number(1.0)),
+            span: None,
+        })])),
+        handler: Some(Box::new(Node::CatchClause(CatchClause {
+            param: Box::new(Node::Identifier("e".to_string())),
+            body: Box::new(block(vec![Node::ReturnStatement(ReturnStatement {
+                argument: Some(Box::new(Node::CallExpression(CallExpression {
+                    callee: Box::new(Node::Identifier("f".to_string())),
+                    arguments: vec![Node::Number(1.0)],
+                    span: None,
+                }))),
+                span: None,
+            })])),
+            span: None,
+        }))),
+        finalizer: Some(Box::new(block(vec![Node::Number(2.0)]))),
+        span: None,
+    });
+    let mut generator = BytecodeGenerator::new();
+    generator.generate(&try_stmt);
+    assert_eq!(
+        generator.instructions,
+        vec![
+            // The finally handler covers both the block and the catch clause
+            Instruction::Try(14),
+            Instruction::Try(6),
+            Instruction::PushConst(0),
+            Instruction::Throw,
+            Instruction::EndTry,
+            Instruction::Jump(11),
+            // catch (e): the thrown value is on top of the stack
+            Instruction::StoreLocal(0),
+            // Not a tail call, which would drop the handler with the frame
+            Instruction::PushConst(0),
+            Instruction::LoadGlobal("f".to_string()),
+            Instruction::Call(1),
+            Instruction::Return,
+            // finally after normal completion
+            Instruction::EndTry,
+            Instruction::PushConst(1),
+            Instruction::Jump(18),
+            // finally after an exception: keep it, run the block and rethrow it
+            Instruction::StoreLocal(0),
+            Instruction::PushConst(1),
+            Instruction::LoadLocal(0),
+            Instruction::Throw,
+        ]
+    );
+}
+
+fn member(object: Node, name: &str, optional: bool) -> Node {
+    Node::MemberExpression(MemberExpression {
+        object: Box::new(object),
+        property: Box::new(Node::Identifier(name.to_string())),
+        computed: false,
+        optional,
+        span: None,
+    })
+}
+
+#[test]
+fn test_optional_chain_skips_rest_of_chain() {
+    // null?.x.y: a null base jumps past both accesses
+    let mut generator = BytecodeGenerator::new();
+    generator.generate(&member(member(Node::Null, "x", true), "y", false));
+    assert_eq!(
+        generator.instructions,
+        vec![
+            Instruction::PushNull,
+            Instruction::Dup,
+            Instruction::JumpIfNullish(8),
+            Instruction::PushConst(0),
+            Instruction::GetProperty,
+            Instruction::PushConst(1),
+            Instruction::GetProperty,
+            Instruction::Jump(10),
+            Instruction::Pop,
+            Instruction::PushUndefined,
+        ]
+    );
+}
+
+#[test]
+fn test_optional_chain_discards_pushed_arguments() {
+    // f(1)?.g(2): the arguments of g are already on the stack when f(1) is checked
+    let call = |callee: Node, arg: f64| {
+        Node::CallExpression(CallExpression {
+            callee: Box::new(callee),
+            arguments: vec![Node::Number(arg)],
+            span: None,
+        })
+    };
+    let mut generator = BytecodeGenerator::new();
+    generator.generate(&call(member(call(Node::Identifier("f".to_string()), 1.0), "g", true), 2.0));
+    assert_eq!(
+        generator.instructions,
+        vec![
+            Instruction::PushConst(0),
+            Instruction::PushConst(1),
+            Instruction::LoadGlobal("f".to_string()),
+            Instruction::Call(1),
+            Instruction::Dup,
+            Instruction::JumpIfNullish(10),
+            Instruction::PushConst(2),
+            Instruction::GetProperty,
+            Instruction::Call(1),
+            Instruction::Jump(13),
+            Instruction::Pop,
+            Instruction::Pop,
+            Instruction::PushUndefined,
+        ]
+    );
+}
+
+#[test]
+fn test_method_call_passes_receiver_below_arguments() {
+    // o.m(1): the object is evaluated once and becomes `this`
+    let mut generator = BytecodeGenerator::new();
+    generator.generate(&Node::CallExpression(CallExpression {
+        callee: Box::new(member(Node::Identifier("o".to_string()), "m", false)),
+        arguments: vec![Node::Number(1.0)],
+        span: None,
+    }));
+    assert_eq!(
+        generator.instructions,
+        vec![
+            Instruction::LoadGlobal("o".to_string()),
+            Instruction::Dup,
+            Instruction::StoreLocal(0),
+            Instruction::PushConst(0),
+            Instruction::LoadLocal(0),
+            Instruction::PushConst(1),
+            Instruction::GetProperty,
+            Instruction::CallMethod(1),
+        ]
+    );
+}
+
+#[test]
+fn test_constant_pool_shares_identical_constants() {
+    let mut generator = BytecodeGenerator::new();
+    let program = Node::Program(v8_ast::Program {
+        body: [f64::NAN, f64::NAN, 0.0, -0.0, 0.0]
+            .into_iter()
+            .map(Node::Number)
+            .chain([Node::String("a".to_string()), Node::String("a".to_string())])
+            .collect(),
+        source_type: "script".to_string(),
+        span: None,
+    });
+    generator.generate(&program);
+
+    let constants = &generator.constants.values;
+    assert_eq!(constants.len(), 4);
+    assert!(matches!(constants[0], Constant::Number(n) if n.is_nan()));
+    assert!(matches!(constants[1], Constant::Number(n) if n == 0.0 && n.is_sign_positive()));
+    assert!(matches!(constants[2], Constant::Number(n) if n == 0.0 && n.is_sign_negative()));
+    assert_eq!(constants[3], Constant::String("a".to_string()));
+    let pushes: Vec<_> = generator
+        .instructions
+        .iter()
+        .filter_map(|instruction| match instruction {
+            Instruction::PushConst(index) => Some(*index),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(pushes, [0, 0, 1, 2, 1, 3, 3]);
+}
+
+#[test]
+fn test_member_assignment_keeps_value_and_statement_pops_it() {
+    let target = Node::MemberExpression(MemberExpression {
+        object: Box::new(Node::Identifier("o".to_string())),
+        property: Box::new(Node::Identifier("a".to_string())),
+        computed: false,
+        optional: false,
+        span: None,
+    });
+    let assignment = Node::AssignmentExpression(AssignmentExpression {
+        left: Box::new(target),
+        operator: "=".to_string(),
+        right: Box::new(Node::Number(5.0)),
+        span: None,
+    });
+    let statement = Node::ExpressionStatement(ExpressionStatement { expression: Box::new(assignment), span: None });
+    let mut generator = BytecodeGenerator::new();
+    generator.generate(&block(vec![statement]));
+
+    assert_eq!(
+        generator.instructions,
+        vec![
+            Instruction::LoadGlobal("o".to_string()),
+            Instruction::PushConst(0),
+            Instruction::PushConst(1),
+            Instruction::Dup,
+            Instruction::StoreLocal(0),
+            Instruction::SetProperty,
+            Instruction::LoadLocal(0),
+            Instruction::Pop,
+        ]
+    );
+}
+
+fn while_loop(test: &str, body: Vec<Node>) -> Node {
+    Node::WhileStatement(WhileStatement {
+        test: Box::new(Node::Identifier(test.to_string())),
+        body: Box::new(block(body)),
+        span: None,
+    })
+}
+
+fn label(name: &str) -> Option<Box<Node>> {
+    Some(Box::new(Node::Identifier(name.to_string())))
+}
+
+#[test]
+fn test_break_and_continue_targets() {
+    // outer: while (a) { while (b) { continue outer; } break; }
+    let inner = while_loop("b", vec![Node::ContinueStatement(ContinueStatement { label: label("outer"), span: None })]);
+    let outer = Node::LabeledStatement(LabeledStatement {
+        label: Box::new(Node::Identifier("outer".to_string())),
+        body: Box::new(while_loop("a", vec![inner, Node::BreakStatement(BreakStatement { label: None, span: None })])),
+        span: None,
+    });
+    let mut generator = BytecodeGenerator::new();
+    generator.generate(&outer);
+
+    assert_eq!(
+        generator.instructions,
+        vec![
+            Instruction::LoadGlobal("a".to_string()),
+            Instruction::JumpIfFalse(8),
+            Instruction::LoadGlobal("b".to_string()),
+            Instruction::JumpIfFalse(6),
+            Instruction::Jump(0), // continue outer
+            Instruction::Jump(2),
+            Instruction::Jump(8), // break
+            Instruction::Jump(0),
+        ]
+    );
+}
+
+#[test]
+fn test_break_out_of_try_removes_handler() {
+    // while (a) { try { break; } catch (e) {} }
+    let try_statement = Node::TryStatement(TryStatement {
+        block: Box::new(block(vec![Node::BreakStatement(BreakStatement { label: None, span: None })])),
+        handler: Some(Box::new(Node::CatchClause(CatchClause {
+            param: Box::new(Node::Identifier("e".to_string())),
+            body: Box::new(block(vec![])),
+            span: None,
+        }))),
+        finalizer: None,
+        span: None,
+    });
+    let mut generator = BytecodeGenerator::new();
+    generator.generate(&while_loop("a", vec![try_statement]));
+
+    let end = generator.instructions.len();
+    assert_eq!(
+        &generator.instructions[2..5],
+        &[Instruction::Try(7), Instruction::EndTry, Instruction::Jump(end)]
+    );
+}
@@ -1,12 +1,18 @@
 //! Error types for the V8-Rust lexer
 
 use thiserror::Error;
+use crate::lexer::EcmaVersion;
 
 /// Errors that can occur during lexing
 #[derive(Debug, Error, Clone, PartialEq)]
 pub enum LexerError {
-    #[error("Unexpected character: {0}")]
-    UnexpectedCharacter(char),
+    #[error("unexpected character '{character}' at line {line}, column {column}{}", suggestion_suffix(.suggestion))]
+    UnexpectedCharacter {
+        character: char,
+        line: usize,
+        column: usize,
+        suggestion: Option<String>,
+    },
     
     #[error("Invalid number: {0}")]
     InvalidNumber(String),
@@ -38,6 +44,9 @@ pub enum LexerError {
     #[error("Invalid octal literal: {0}")]
     InvalidOctalLiteral(String),
     
+    #[error("Number literals with a leading zero are not allowed in strict mode: {0}")]
+    LeadingZeroInStrictMode(String),
+    
     #[error("Invalid hex literal: {0}")]
     InvalidHexLiteral(String),
     
@@ -71,9 +80,34 @@ pub enum LexerError {
     #[error("Invalid token: {0}")]
     InvalidToken(String),
     
+    #[error("{introduced_in} syntax ({feature}) is not allowed when targeting {target}")]
+    UnsupportedSyntax {
+        feature: String,
+        introduced_in: EcmaVersion,
+        target: EcmaVersion,
+    },
+    
     #[error("End of input reached unexpectedly")]
     UnexpectedEndOfInput,
     
     #[error("Internal lexer error: {0}")]
     InternalError(String),
-} 
\ No newline at end of file
+} 
+
+/// Format an optional suggestion as a trailing hint
+fn suggestion_suffix(suggestion: &Option<String>) -> String {
+    match suggestion {
+        Some(hint) => format!(" ({})", hint),
+        None => String::new(),
+    }
+}
+
+/// Suggest what a stray character may have been meant for
+pub(crate) fn unexpected_character_suggestion(c: char) -> Option<String> {
+    match c {
+        '@' => Some("'@' is only used by decorators, which are not supported".to_string()),
+        '#' => Some("'#' is only valid at the start of a private class field name".to_string()),
+        '\\' => Some("'\\' is only valid in escape sequences inside strings".to_string()),
+        _ => None,
+    }
+}
@@ -1,6 +1,51 @@
 //! Lexer implementation for V8-Rust JavaScript engine
 
-use crate::{Token, TokenKind, Position, LexerError};
+use std::collections::VecDeque;
+use std::fmt;
+use crate::{Token, TokenKind, Position, Span, LexerError};
+use crate::error::unexpected_character_suggestion;
+
+/// ECMAScript edition the source is written against
+///
+/// Tokens introduced by a later edition are rejected with
+/// `LexerError::UnsupportedSyntax`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum EcmaVersion {
+    Es5,
+    Es2015,
+    Es2016,
+    Es2017,
+    Es2018,
+    Es2019,
+    Es2020,
+    Es2021,
+    #[default]
+    Latest,
+}
+
+impl fmt::Display for EcmaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            EcmaVersion::Es5 => "ES5",
+            EcmaVersion::Es2015 => "ES2015",
+            EcmaVersion::Es2016 => "ES2016",
+            EcmaVersion::Es2017 => "ES2017",
+            EcmaVersion::Es2018 => "ES2018",
+            EcmaVersion::Es2019 => "ES2019",
+            EcmaVersion::Es2020 => "ES2020",
+            EcmaVersion::Es2021 => "ES2021",
+            EcmaVersion::Latest => "the latest edition",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Options for `Lexer::with_options`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LexerOptions {
+    pub ecma_version: EcmaVersion,
+    pub strict_mode: bool,
+}
 
 /// Lexer for JavaScript/ECMAScript source code
 #[derive(Debug)]
@@ -9,16 +54,38 @@ pub struct Lexer {
     pos: usize,
     line: usize,
     column: usize,
+    offset: usize, // Byte offset of `pos` in the original source
+    regex_allowed: bool, // Whether a '/' here starts a regex rather than a division
+    strict_mode: bool, // Whether leading-zero number literals are rejected
+    ecma_version: EcmaVersion, // Newest edition whose tokens are accepted
+    /// Spans of leading-zero integer literals (`010`, `08`) read so far
+    leading_zero_literals: Vec<Span>,
+    /// Tokens scanned ahead by `peek` but not yet consumed
+    lookahead: VecDeque<Result<Token, LexerError>>,
+    /// Code units of the string just read, when it has a lone surrogate
+    string_code_units: Option<Vec<u16>>,
 }
 
 impl Lexer {
     /// Create a new lexer for the given source code
     pub fn new(source: &str) -> Self {
+        Self::with_options(source, LexerOptions::default())
+    }
+    
+    /// Create a lexer with a target edition and strictness
+    pub fn with_options(source: &str, options: LexerOptions) -> Self {
         Self {
             source: source.chars().collect(),
             pos: 0,
             line: 1,
             column: 1,
+            offset: 0,
+            regex_allowed: true,
+            strict_mode: options.strict_mode,
+            ecma_version: options.ecma_version,
+            leading_zero_literals: Vec::new(),
+            lookahead: VecDeque::new(),
+            string_code_units: None,
         }
     }
     
@@ -26,7 +93,7 @@ impl Lexer {
     pub fn tokenize(&mut self) -> Result<Vec<Token>, LexerError> {
         let mut tokens = Vec::new();
         
-        while self.pos < self.source.len() {
+        while self.pos < self.source.len() || !self.lookahead.is_empty() {
             let start_line = self.line;
             let start_col = self.column;
             
@@ -46,13 +113,7 @@ impl Lexer {
         
         // Add EOF token if we don't have one already
         if tokens.is_empty() || !matches!(tokens.last().unwrap().kind, TokenKind::Eof) {
-            tokens.push(Token::with_positions(
-                TokenKind::Eof,
-                self.line,
-                self.column,
-                self.line,
-                self.column,
-            ));
+            tokens.push(Token::new(TokenKind::Eof, Span::new(self.position(), self.position())));
         }
         
         Ok(tokens)
@@ -60,20 +121,66 @@ impl Lexer {
     
     /// Get the next token from the source
     pub fn next_token(&mut self) -> Result<Token, LexerError> {
+        match self.lookahead.pop_front() {
+            Some(token) => token,
+            None => self.scan_token(),
+        }
+    }
+    
+    /// Look at the token `n` positions ahead without consuming it
+    ///
+    /// `peek(0)` is the token the next call to `next_token` will return.
+    /// Past the end of input, every peek yields the EOF token.
+    pub fn peek(&mut self, n: usize) -> Result<&Token, LexerError> {
+        while self.lookahead.len() <= n {
+            let token = self.scan_token();
+            self.lookahead.push_back(token);
+        }
+        self.lookahead[n].as_ref().map_err(Clone::clone)
+    }
+    
+    /// Reject leading-zero number literals from now on
+    ///
+    /// Tokens already scanned by `peek` are not checked again.
+    pub fn set_strict_mode(&mut self, strict: bool) {
+        self.strict_mode = strict;
+    }
+    
+    /// Reject tokens introduced after `version` from now on
+    pub fn set_ecma_version(&mut self, version: EcmaVersion) {
+        self.ecma_version = version;
+    }
+    
+    /// Spans of the leading-zero integer literals read so far
+    ///
+    /// Both legacy octal (`010`) and decimal (`08`) forms are listed, since
+    /// strict mode code may contain neither. A `"use strict"` directive is
+    /// only known after its tokens are read, so callers check this list.
+    pub fn leading_zero_literals(&self) -> &[Span] {
+        &self.leading_zero_literals
+    }
+    
+    /// Fail unless the target edition includes `feature`, added in `version`
+    fn require(&self, feature: &str, version: EcmaVersion) -> Result<(), LexerError> {
+        if self.ecma_version >= version {
+            return Ok(());
+        }
+        Err(LexerError::UnsupportedSyntax {
+            feature: feature.to_string(),
+            introduced_in: version,
+            target: self.ecma_version,
+        })
+    }
+    
+    /// Scan the next token directly from the source
+    fn scan_token(&mut self) -> Result<Token, LexerError> {
         self.skip_whitespace();
         
         if self.pos >= self.source.len() {
-            return Ok(Token::with_positions(
-                TokenKind::Eof,
-                self.line,
-                self.column,
-                self.line,
-                self.column,
-            ));
+            return Ok(Token::new(TokenKind::Eof, Span::new(self.position(), self.position())));
         }
         
-        let start_line = self.line;
-        let start_col = self.column;
+        let start = self.position();
         let c = self.source[self.pos];
         
         let token_kind = if c.is_ascii_alphabetic() || c == '_' || c == '$' || !c.is_ascii() {
@@ -94,6 +201,20 @@ impl Lexer {
                 self.read_line_comment()?
             } else if self.peek_char(1) == Some('*') {
                 self.read_block_comment()?
+            } else if self.regex_allowed {
+                // Without another '/' on the line this is an operator after all
+                let saved = (self.pos, self.line, self.column, self.offset);
+                let closed = self.source[self.pos + 1..]
+                    .iter()
+                    .take_while(|&&c| c != '\n' && c != '\r')
+                    .any(|&c| c == '/');
+                match self.read_regex() {
+                    Err(LexerError::InvalidRegexLiteral(_)) if !closed => {
+                        (self.pos, self.line, self.column, self.offset) = saved;
+                        self.read_operator()?
+                    }
+                    result => result?,
+                }
             } else {
                 self.read_operator()?
             }
@@ -102,10 +223,16 @@ impl Lexer {
             self.read_operator()?
         };
         
-        let end_line = self.line;
-        let end_col = self.column;
+        if let Some((feature, version)) = introduced_in(&token_kind) {
+            self.require(feature, version)?;
+        }
+        if !matches!(token_kind, TokenKind::Comment(_)) {
+            self.regex_allowed = regex_can_follow(&token_kind);
+        }
         
-        Ok(Token::with_positions(token_kind, start_line, start_col, end_line, end_col))
+        let mut token = Token::new(token_kind, Span::new(start, self.position()));
+        token.code_units = self.string_code_units.take();
+        Ok(token)
     }
     
     /// Read an identifier or keyword
@@ -151,10 +278,13 @@ impl Lexer {
         let mut is_hex = false;
         let mut is_binary = false;
         let mut is_octal = false;
+        let mut leading_zero = false;
+        let start = self.position();
         
         // Check for hex, binary, or octal
         if self.source[self.pos] == '0' && self.pos + 1 < self.source.len() {
             match self.source[self.pos + 1] {
+                '0'..='9' => leading_zero = true,
                 'x' | 'X' => {
                     is_hex = true;
                     number.push('0');
@@ -180,9 +310,42 @@ impl Lexer {
             }
         }
         
+        if leading_zero {
+            let digits: String = self.source[self.pos..]
+                .iter()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            if self.strict_mode {
+                return Err(LexerError::LeadingZeroInStrictMode(digits));
+            }
+            
+            // Only digits 0-7 make a legacy octal literal: 010 is 8, but 08 is decimal
+            if digits.chars().all(|c| c <= '7') {
+                for _ in 0..digits.len() {
+                    self.advance();
+                }
+                self.leading_zero_literals.push(Span::new(start, self.position()));
+                let value = digits.chars().fold(0.0, |acc, c| acc * 8.0 + c.to_digit(8).unwrap() as f64);
+                return Ok(TokenKind::Number(value));
+            }
+        }
+        
+        let radix = if is_hex { 16 } else if is_binary { 2 } else if is_octal { 8 } else { 10 };
         while self.pos < self.source.len() {
             let c = self.source[self.pos];
             
+            // A numeric separator (`1_000`) must sit between two digits; it is dropped
+            if c == '_' {
+                let after_digit = number.chars().last().is_some_and(|prev| prev.is_digit(radix));
+                let before_digit = self.peek_char(1).is_some_and(|next| next.is_digit(radix));
+                if !(after_digit && before_digit) || number == "0" {
+                    return Err(LexerError::InvalidNumber(format!("{}_", number)));
+                }
+                self.require("numeric separators", EcmaVersion::Es2021)?;
+                self.advance();
+                continue;
+            }
+            
             if is_hex {
                 if c.is_ascii_hexdigit() {
                     number.push(c);
@@ -241,6 +404,9 @@ impl Lexer {
                 Err(_) => Err(LexerError::InvalidNumber(number)),
             }
         } else {
+            if leading_zero {
+                self.leading_zero_literals.push(Span::new(start, self.position()));
+            }
             // Parse decimal number
             match number.parse::<f64>() {
                 Ok(n) => Ok(TokenKind::Number(n)),
@@ -250,11 +416,15 @@ impl Lexer {
     }
     
     /// Read a string literal
+    ///
+    /// The value is cooked as UTF-16, like a JavaScript string: escaped
+    /// surrogate pairs (`"\uD83D\uDE00"`) combine into one character, and
+    /// a lone surrogate is kept in the token's `code_units`.
     fn read_string(&mut self) -> Result<TokenKind, LexerError> {
         let quote = self.source[self.pos];
         self.advance(); // Skip opening quote
         
-        let mut string = String::new();
+        let mut units: Vec<u16> = Vec::new();
         
         let mut found_closing_quote = false;
         
@@ -269,19 +439,40 @@ impl Lexer {
                 self.advance(); // Skip backslash
                 if self.pos < self.source.len() {
                     let escaped = self.source[self.pos];
-                    match escaped {
-                        'n' => string.push('\n'),
-                        't' => string.push('\t'),
-                        'r' => string.push('\r'),
-                        '\\' => string.push('\\'),
-                        '"' => string.push('"'),
-                        '\'' => string.push('\''),
-                        _ => string.push(escaped),
-                    }
                     self.advance();
+                    let cooked = match escaped {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'v' => '\u{b}',
+                        '0' if !self.peek_char(0).is_some_and(|c| c.is_ascii_digit()) => '\0',
+                        'x' => {
+                            let unit = self.read_hex_digits(2, 2).ok_or_else(|| {
+                                LexerError::InvalidHexEscape(format!("\\x{}", self.escape_context(2)))
+                            })?;
+                            units.push(unit as u16);
+                            continue;
+                        }
+                        'u' => {
+                            self.read_unicode_escape(&mut units)?;
+                            continue;
+                        }
+                        // A backslash before a line break continues the string
+                        '\n' => continue,
+                        '\r' => {
+                            if self.peek_char(0) == Some('\n') {
+                                self.advance();
+                            }
+                            continue;
+                        }
+                        other => other,
+                    };
+                    units.extend(cooked.encode_utf16(&mut [0; 2]).iter());
                 }
             } else {
-                string.push(c);
+                units.extend(c.encode_utf16(&mut [0; 2]).iter());
                 self.advance();
             }
         }
@@ -291,49 +482,219 @@ impl Lexer {
             return Err(LexerError::UnterminatedString);
         }
         
-        Ok(TokenKind::String(string))
+        match String::from_utf16(&units) {
+            Ok(string) => Ok(TokenKind::String(string)),
+            Err(_) => {
+                let string = String::from_utf16_lossy(&units);
+                self.string_code_units = Some(units);
+                Ok(TokenKind::String(string))
+            }
+        }
+    }
+    
+    /// Read the code units of a `\uXXXX` or `\u{X...}` escape, after the `u`
+    ///
+    /// Any code unit is allowed, including a surrogate; a code point above
+    /// U+10FFFF is an error.
+    fn read_unicode_escape(&mut self, units: &mut Vec<u16>) -> Result<(), LexerError> {
+        if self.peek_char(0) != Some('{') {
+            let unit = self
+                .read_hex_digits(4, 4)
+                .ok_or_else(|| LexerError::InvalidUnicodeEscape(format!("\\u{}", self.escape_context(4))))?;
+            units.push(unit as u16);
+            return Ok(());
+        }
+        self.advance(); // Skip '{'
+        let code_point = self.read_hex_digits(1, usize::MAX);
+        match (code_point, self.peek_char(0)) {
+            (Some(code_point), Some('}')) if code_point <= 0x10FFFF => {
+                self.advance(); // Skip '}'
+                match char::from_u32(code_point) {
+                    Some(c) => units.extend(c.encode_utf16(&mut [0; 2]).iter()),
+                    // Surrogates are not chars, but are valid code units
+                    None => units.push(code_point as u16),
+                }
+                Ok(())
+            }
+            _ => {
+                let text: String = self.source[self.pos..].iter().take_while(|&&c| c != '}' && c != '"' && c != '\'').collect();
+                Err(LexerError::InvalidUnicodeEscape(format!("\\u{{{}}}", text)))
+            }
+        }
+    }
+    
+    /// Read between `min` and `max` hex digits; `None` if there are too few
+    /// or the value overflows
+    fn read_hex_digits(&mut self, min: usize, max: usize) -> Option<u32> {
+        let mut value: u32 = 0;
+        let mut count = 0;
+        while count < max {
+            let Some(digit) = self.peek_char(0).and_then(|c| c.to_digit(16)) else { break };
+            value = value.checked_mul(16)?.checked_add(digit)?;
+            self.advance();
+            count += 1;
+        }
+        (count >= min).then_some(value)
+    }
+    
+    /// Up to `len` characters at the current position, for error messages
+    fn escape_context(&self, len: usize) -> String {
+        self.source[self.pos..].iter().take(len).collect()
     }
     
     /// Read a template string literal
+    ///
+    /// The text is kept raw, substitutions included: each `${` runs to its
+    /// matching `}`, past braces inside strings, comments and nested
+    /// templates, and the parser reads the expressions from it.
     fn read_template_string(&mut self) -> Result<TokenKind, LexerError> {
         self.advance(); // Skip backtick
         
         let mut template = String::new();
+        self.read_template_body(&mut template)?;
         
-        while self.pos < self.source.len() {
-            let c = self.source[self.pos];
-            
-            if c == '`' {
-                self.advance(); // Skip closing backtick
-                break;
-            } else if c == '$' && self.peek_char(1) == Some('{') {
-                // Template expression
-                template.push_str("${");
-                self.advance();
-                self.advance();
-                // TODO: Parse expression inside ${}
-            } else if c == '\\' {
-                self.advance(); // Skip backslash
-                if self.pos < self.source.len() {
-                    let escaped = self.source[self.pos];
-                    match escaped {
-                        'n' => template.push('\n'),
-                        't' => template.push('\t'),
-                        'r' => template.push('\r'),
-                        '\\' => template.push('\\'),
-                        '`' => template.push('`'),
-                        '$' => template.push('$'),
-                        _ => template.push(escaped),
+        Ok(TokenKind::TemplateString(template))
+    }
+    
+    /// Copy template text up to the closing backtick, which is skipped
+    fn read_template_body(&mut self, template: &mut String) -> Result<(), LexerError> {
+        loop {
+            match self.peek_char(0) {
+                None => return Err(LexerError::UnterminatedTemplateString),
+                Some('`') => {
+                    self.advance(); // Skip closing backtick
+                    return Ok(());
+                }
+                Some('$') if self.peek_char(1) == Some('{') => {
+                    self.copy_char(template);
+                    self.copy_char(template);
+                    self.read_substitution(template)?;
+                }
+                Some('\\') => {
+                    // Escapes are kept raw; the parser cooks them (tagged templates need both)
+                    self.copy_char(template);
+                    if self.pos < self.source.len() {
+                        self.copy_char(template);
                     }
-                    self.advance();
                 }
-            } else {
-                template.push(c);
-                self.advance();
+                Some(_) => self.copy_char(template),
+            }
+        }
+    }
+    
+    /// Copy the rest of a `${...}` substitution, through its closing `}`
+    fn read_substitution(&mut self, template: &mut String) -> Result<(), LexerError> {
+        let mut depth = 0;
+        loop {
+            match self.peek_char(0) {
+                None => return Err(LexerError::UnterminatedTemplateString),
+                Some('}') if depth == 0 => {
+                    self.copy_char(template);
+                    return Ok(());
+                }
+                Some('{') => {
+                    depth += 1;
+                    self.copy_char(template);
+                }
+                Some('}') => {
+                    depth -= 1;
+                    self.copy_char(template);
+                }
+                Some('`') => {
+                    self.copy_char(template);
+                    self.read_template_body(template)?;
+                    template.push('`');
+                }
+                Some(quote @ ('"' | '\'')) => {
+                    self.copy_char(template);
+                    while let Some(c) = self.peek_char(0) {
+                        self.copy_char(template);
+                        if c == '\\' && self.pos < self.source.len() {
+                            self.copy_char(template);
+                        } else if c == quote || c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                Some('/') if self.peek_char(1) == Some('/') => {
+                    while self.peek_char(0).is_some_and(|c| c != '\n') {
+                        self.copy_char(template);
+                    }
+                }
+                Some('/') if self.peek_char(1) == Some('*') => {
+                    self.copy_char(template);
+                    self.copy_char(template);
+                    while self.pos < self.source.len() && !(self.peek_char(0) == Some('*') && self.peek_char(1) == Some('/')) {
+                        self.copy_char(template);
+                    }
+                    if self.pos < self.source.len() {
+                        self.copy_char(template);
+                        self.copy_char(template);
+                    }
+                }
+                Some(_) => self.copy_char(template),
             }
         }
+    }
+    
+    /// Copy the current character to `out`, counting the lines it ends
+    fn copy_char(&mut self, out: &mut String) {
+        let c = self.source[self.pos];
+        out.push(c);
+        self.advance();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        }
+    }
+    
+    /// Read a regular expression literal
+    fn read_regex(&mut self) -> Result<TokenKind, LexerError> {
+        self.advance(); // Skip opening '/'
+        
+        let mut pattern = String::new();
+        let mut in_class = false;
+        
+        loop {
+            let c = match self.source.get(self.pos) {
+                Some(&c) if c != '\n' && c != '\r' => c,
+                _ => return Err(LexerError::InvalidRegexLiteral(format!("/{}", pattern))),
+            };
+            self.advance();
+            match c {
+                '/' if !in_class => break,
+                '[' => in_class = true,
+                ']' => in_class = false,
+                '\\' => {
+                    // The escaped character never ends the literal or a class
+                    pattern.push(c);
+                    match self.source.get(self.pos) {
+                        Some(&escaped) if escaped != '\n' && escaped != '\r' => {
+                            pattern.push(escaped);
+                            self.advance();
+                        }
+                        _ => return Err(LexerError::InvalidRegexLiteral(format!("/{}", pattern))),
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            pattern.push(c);
+        }
         
-        Ok(TokenKind::TemplateString(template))
+        let mut flags = String::new();
+        while let Some(&c) = self.source.get(self.pos) {
+            if !(c.is_alphanumeric() || c == '_' || c == '$') {
+                break;
+            }
+            if !"dgimsuvy".contains(c) || flags.contains(c) {
+                return Err(LexerError::InvalidRegexFlags(format!("{}{}", flags, c)));
+            }
+            flags.push(c);
+            self.advance();
+        }
+        
+        Ok(TokenKind::Regex(pattern, flags))
     }
     
     /// Read a line comment
@@ -390,6 +751,14 @@ impl Lexer {
     fn read_operator(&mut self) -> Result<TokenKind, LexerError> {
         let c = self.source[self.pos];
         
+        // `>>>=` is the only four-character operator
+        if self.source[self.pos..].starts_with(&['>', '>', '>', '=']) {
+            for _ in 0..4 {
+                self.advance();
+            }
+            return Ok(TokenKind::UnsignedRightShiftAssign);
+        }
+
         // Check for three-character operators first
         if self.pos + 2 < self.source.len() {
             let next_c = self.source[self.pos + 1];
@@ -403,6 +772,7 @@ impl Lexer {
                 "<<=" => { self.advance(); self.advance(); self.advance(); return Ok(TokenKind::LeftShiftAssign); }
                 ">>=" => { self.advance(); self.advance(); self.advance(); return Ok(TokenKind::RightShiftAssign); }
                 ">>>" => { self.advance(); self.advance(); self.advance(); return Ok(TokenKind::UnsignedRightShift); }
+                "..." => { self.advance(); self.advance(); self.advance(); return Ok(TokenKind::Spread); }
                 _ => {}
             }
         }
@@ -413,16 +783,31 @@ impl Lexer {
             let two_char_op = format!("{}{}", c, next_c);
             
             match two_char_op.as_str() {
+                "**" => { self.advance(); self.advance(); return Ok(TokenKind::StarStar); }
                 "==" => { self.advance(); self.advance(); return Ok(TokenKind::Equal); }
                 "!=" => { self.advance(); self.advance(); return Ok(TokenKind::NotEqual); }
                 "<=" => { self.advance(); self.advance(); return Ok(TokenKind::LessThanEqual); }
                 ">=" => { self.advance(); self.advance(); return Ok(TokenKind::GreaterThanEqual); }
+                "<<" => { self.advance(); self.advance(); return Ok(TokenKind::LeftShift); }
+                ">>" => { self.advance(); self.advance(); return Ok(TokenKind::RightShift); }
                 "++" => { self.advance(); self.advance(); return Ok(TokenKind::Increment); }
                 "--" => { self.advance(); self.advance(); return Ok(TokenKind::Decrement); }
                 "&&" => { self.advance(); self.advance(); return Ok(TokenKind::LogicalAnd); }
                 "||" => { self.advance(); self.advance(); return Ok(TokenKind::LogicalOr); }
                 "=>" => { self.advance(); self.advance(); return Ok(TokenKind::Arrow); }
                 "??" => { self.advance(); self.advance(); return Ok(TokenKind::NullishCoalescing); }
+                "+=" => { self.advance(); self.advance(); return Ok(TokenKind::PlusAssign); }
+                "-=" => { self.advance(); self.advance(); return Ok(TokenKind::MinusAssign); }
+                "*=" => { self.advance(); self.advance(); return Ok(TokenKind::StarAssign); }
+                "/=" => { self.advance(); self.advance(); return Ok(TokenKind::SlashAssign); }
+                "%=" => { self.advance(); self.advance(); return Ok(TokenKind::PercentAssign); }
+                "&=" => { self.advance(); self.advance(); return Ok(TokenKind::BitwiseAndAssign); }
+                "|=" => { self.advance(); self.advance(); return Ok(TokenKind::BitwiseOrAssign); }
+                "^=" => { self.advance(); self.advance(); return Ok(TokenKind::BitwiseXorAssign); }
+                // `a?.5:1` is a conditional with a decimal, not optional chaining
+                "?." if !self.peek_char(2).is_some_and(|c| c.is_ascii_digit()) => {
+                    self.advance(); self.advance(); return Ok(TokenKind::OptionalChaining);
+                }
                 _ => {}
             }
         }
@@ -455,7 +840,12 @@ impl Lexer {
             '&' => { self.advance(); Ok(TokenKind::BitwiseAnd) }
             '|' => { self.advance(); Ok(TokenKind::BitwiseOr) }
             '^' => { self.advance(); Ok(TokenKind::BitwiseXor) }
-            _ => Err(LexerError::UnexpectedCharacter(c)),
+            _ => Err(LexerError::UnexpectedCharacter {
+                character: c,
+                line: self.line,
+                column: self.column,
+                suggestion: unexpected_character_suggestion(c),
+            }),
         }
     }
     
@@ -464,22 +854,26 @@ impl Lexer {
         while self.pos < self.source.len() {
             let c = self.source[self.pos];
             if c.is_whitespace() {
+                self.advance();
                 if c == '\n' {
                     self.line += 1;
                     self.column = 1;
-                } else {
-                    self.column += 1;
                 }
-                self.advance();
             } else {
                 break;
             }
         }
     }
     
+    /// Current line, column and byte offset
+    fn position(&self) -> Position {
+        Position::with_offset(self.line, self.column, self.offset)
+    }
+    
     /// Advance to the next character
     fn advance(&mut self) {
         if self.pos < self.source.len() {
+            self.offset += self.source[self.pos].len_utf8();
             self.pos += 1;
             self.column += 1;
         }
@@ -500,6 +894,46 @@ impl Lexer {
     }
 }
 
+/// Whether a '/' after a token of this kind starts a regex literal
+///
+/// After an operand (identifier, literal or closing bracket) a '/' is
+/// the division operator; anywhere else it can only start a regex.
+fn regex_can_follow(kind: &TokenKind) -> bool {
+    match kind {
+        TokenKind::Identifier(_)
+        | TokenKind::Number(_)
+        | TokenKind::BigInt(_)
+        | TokenKind::String(_)
+        | TokenKind::TemplateString(_)
+        | TokenKind::Boolean(_)
+        | TokenKind::Null
+        | TokenKind::Undefined
+        | TokenKind::Regex(_, _)
+        | TokenKind::RightParen
+        | TokenKind::RightBracket
+        | TokenKind::Increment
+        | TokenKind::Decrement => false,
+        TokenKind::Keyword(kw) => !matches!(kw.as_str(), "this" | "super"),
+        _ => true,
+    }
+}
+
+
+/// The syntax a token belongs to and the edition that introduced it, if
+/// it is newer than ES5
+fn introduced_in(kind: &TokenKind) -> Option<(&'static str, EcmaVersion)> {
+    match kind {
+        TokenKind::Arrow => Some(("arrow functions", EcmaVersion::Es2015)),
+        TokenKind::TemplateString(_) => Some(("template literals", EcmaVersion::Es2015)),
+        TokenKind::Spread => Some(("spread and rest syntax", EcmaVersion::Es2015)),
+        TokenKind::StarStar | TokenKind::StarStarAssign => Some(("the exponentiation operator", EcmaVersion::Es2016)),
+        TokenKind::NullishCoalescing => Some(("nullish coalescing", EcmaVersion::Es2020)),
+        TokenKind::OptionalChaining => Some(("optional chaining", EcmaVersion::Es2020)),
+        TokenKind::BigInt(_) => Some(("BigInt literals", EcmaVersion::Es2020)),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -573,4 +1007,4 @@ mod tests {
         let tokens = lexer.tokenize().unwrap();
         assert_eq!(tokens[0].kind, TokenKind::Equal);
     }
-} 
\ No newline at end of file
+} 
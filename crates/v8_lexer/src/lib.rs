@@ -11,7 +11,7 @@ pub mod lexer;
 pub mod error;
 
 pub use token::{Token, TokenKind, Position, Span};
-pub use lexer::Lexer;
+pub use lexer::{EcmaVersion, Lexer, LexerOptions};
 pub use error::LexerError;
 
 /// Tokenize source code into a vector of tokens
@@ -20,6 +20,12 @@ pub fn tokenize(source: &str) -> Result<Vec<Token>, LexerError> {
     lexer.tokenize()
 }
 
+/// Tokenize source code with a target edition and strictness
+pub fn tokenize_with_options(source: &str, options: LexerOptions) -> Result<Vec<Token>, LexerError> {
+    let mut lexer = Lexer::with_options(source, options);
+    lexer.tokenize()
+}
+
 /// Tokenize source code into a vector of tokens (fallback version)
 pub fn tokenize_fallback(source: &str) -> Vec<Token> {
     match tokenize(source) {
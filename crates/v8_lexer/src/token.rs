@@ -7,11 +7,18 @@ use serde::{Deserialize, Serialize};
 pub struct Position {
     pub line: usize,
     pub column: usize,
+    /// Absolute byte offset into the source (0 when unknown)
+    #[serde(default)]
+    pub offset: usize,
 }
 
 impl Position {
     pub fn new(line: usize, column: usize) -> Self {
-        Self { line, column }
+        Self { line, column, offset: 0 }
+    }
+    
+    pub fn with_offset(line: usize, column: usize, offset: usize) -> Self {
+        Self { line, column, offset }
     }
 }
 
@@ -43,11 +50,11 @@ pub enum TokenKind {
     Number(f64),
     BigInt(String),
     String(String),
-    TemplateString(String),
+    TemplateString(String), // Raw source text between the backticks
     Boolean(bool),
     Null,
     Undefined,
-    Regex(String),
+    Regex(String, String), // Pattern and flags
     
     // Keywords
     Keyword(String),
@@ -133,22 +140,149 @@ pub enum TokenKind {
     PrivateField,
 }
 
+impl TokenKind {
+    /// Binding strength of a binary operator, higher binding tighter
+    ///
+    /// Covers every operator that takes a left and a right operand, from
+    /// `||` and `??` (1) up to `**` (11); `None` for anything else.
+    pub fn precedence(&self) -> Option<u8> {
+        let precedence = match self {
+            TokenKind::LogicalOr | TokenKind::NullishCoalescing => 1,
+            TokenKind::LogicalAnd => 2,
+            TokenKind::BitwiseOr => 3,
+            TokenKind::BitwiseXor => 4,
+            TokenKind::BitwiseAnd => 5,
+            TokenKind::Equal | TokenKind::NotEqual | TokenKind::StrictEqual | TokenKind::StrictNotEqual => 6,
+            TokenKind::LessThan | TokenKind::GreaterThan | TokenKind::LessThanEqual | TokenKind::GreaterThanEqual => 7,
+            TokenKind::Keyword(kw) if kw == "instanceof" || kw == "in" => 7,
+            TokenKind::LeftShift | TokenKind::RightShift | TokenKind::UnsignedRightShift => 8,
+            TokenKind::Plus | TokenKind::Minus => 9,
+            TokenKind::Star | TokenKind::Slash | TokenKind::Percent => 10,
+            TokenKind::StarStar => 11,
+            _ => return None,
+        };
+        Some(precedence)
+    }
+
+    /// Check if the token is a binary operator, logical operators included
+    pub fn is_binary_operator(&self) -> bool {
+        self.precedence().is_some()
+    }
+
+    /// Check if the token is `=` or a compound assignment operator
+    pub fn is_assignment_operator(&self) -> bool {
+        matches!(self,
+            TokenKind::Assign | TokenKind::PlusAssign | TokenKind::MinusAssign |
+            TokenKind::StarAssign | TokenKind::SlashAssign | TokenKind::PercentAssign |
+            TokenKind::StarStarAssign | TokenKind::LeftShiftAssign | TokenKind::RightShiftAssign |
+            TokenKind::UnsignedRightShiftAssign | TokenKind::BitwiseAndAssign |
+            TokenKind::BitwiseOrAssign | TokenKind::BitwiseXorAssign
+        )
+    }
+
+    /// Source text of an operator or punctuator, as used in the AST
+    ///
+    /// Keywords that act as operators (`in`, `instanceof`, `typeof`,
+    /// `void`, `delete`) are included; other tokens have no fixed text.
+    pub fn operator_str(&self) -> Option<&'static str> {
+        let text = match self {
+            TokenKind::Plus => "+",
+            TokenKind::Minus => "-",
+            TokenKind::Star => "*",
+            TokenKind::Slash => "/",
+            TokenKind::Percent => "%",
+            TokenKind::StarStar => "**",
+            TokenKind::Equal => "==",
+            TokenKind::NotEqual => "!=",
+            TokenKind::StrictEqual => "===",
+            TokenKind::StrictNotEqual => "!==",
+            TokenKind::LessThan => "<",
+            TokenKind::GreaterThan => ">",
+            TokenKind::LessThanEqual => "<=",
+            TokenKind::GreaterThanEqual => ">=",
+            TokenKind::LeftShift => "<<",
+            TokenKind::RightShift => ">>",
+            TokenKind::UnsignedRightShift => ">>>",
+            TokenKind::Assign => "=",
+            TokenKind::PlusAssign => "+=",
+            TokenKind::MinusAssign => "-=",
+            TokenKind::StarAssign => "*=",
+            TokenKind::SlashAssign => "/=",
+            TokenKind::PercentAssign => "%=",
+            TokenKind::StarStarAssign => "**=",
+            TokenKind::LeftShiftAssign => "<<=",
+            TokenKind::RightShiftAssign => ">>=",
+            TokenKind::UnsignedRightShiftAssign => ">>>=",
+            TokenKind::BitwiseAndAssign => "&=",
+            TokenKind::BitwiseOrAssign => "|=",
+            TokenKind::BitwiseXorAssign => "^=",
+            TokenKind::LogicalAnd => "&&",
+            TokenKind::LogicalOr => "||",
+            TokenKind::NullishCoalescing => "??",
+            TokenKind::Exclamation => "!",
+            TokenKind::BitwiseAnd => "&",
+            TokenKind::BitwiseOr => "|",
+            TokenKind::BitwiseXor => "^",
+            TokenKind::Tilde => "~",
+            TokenKind::Increment => "++",
+            TokenKind::Decrement => "--",
+            TokenKind::Question => "?",
+            TokenKind::Colon => ":",
+            TokenKind::Comma => ",",
+            TokenKind::Semicolon => ";",
+            TokenKind::Dot => ".",
+            TokenKind::LeftParen => "(",
+            TokenKind::RightParen => ")",
+            TokenKind::LeftBracket => "[",
+            TokenKind::RightBracket => "]",
+            TokenKind::LeftBrace => "{",
+            TokenKind::RightBrace => "}",
+            TokenKind::Arrow => "=>",
+            TokenKind::Spread => "...",
+            TokenKind::OptionalChaining => "?.",
+            TokenKind::Keyword(kw) => match kw.as_str() {
+                "in" => "in",
+                "instanceof" => "instanceof",
+                "typeof" => "typeof",
+                "void" => "void",
+                "delete" => "delete",
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(text)
+    }
+}
+
 /// A token with position information
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Token {
     pub kind: TokenKind,
     pub span: Span,
+    /// UTF-16 code units of a string literal with a lone surrogate
+    /// (`"\uD800"`), which the UTF-8 `TokenKind::String` replaces with U+FFFD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_units: Option<Vec<u16>>,
 }
 
 impl Token {
     pub fn new(kind: TokenKind, span: Span) -> Self {
-        Self { kind, span }
+        Self { kind, span, code_units: None }
     }
     
     pub fn with_positions(kind: TokenKind, start_line: usize, start_col: usize, end_line: usize, end_col: usize) -> Self {
         Self {
             kind,
             span: Span::from_positions(start_line, start_col, end_line, end_col),
+            code_units: None,
+        }
+    }
+    
+    /// The cooked value of a string literal as UTF-16, lone surrogates included
+    pub fn string_code_units(&self) -> Option<Vec<u16>> {
+        match &self.kind {
+            TokenKind::String(value) => Some(self.code_units.clone().unwrap_or_else(|| value.encode_utf16().collect())),
+            _ => None,
         }
     }
     
@@ -179,7 +313,8 @@ impl Token {
             TokenKind::String(_) | 
             TokenKind::Boolean(_) | 
             TokenKind::Null | 
-            TokenKind::Undefined
+            TokenKind::Undefined |
+            TokenKind::Regex(_, _)
         )
     }
     
@@ -266,9 +266,14 @@ fn test_internal_error() {
 
 #[test]
 fn test_error_message_formatting() {
-    let error = LexerError::UnexpectedCharacter('@');
+    let error = LexerError::UnexpectedCharacter {
+        character: '@',
+        line: 1,
+        column: 12,
+        suggestion: None,
+    };
     let message = format!("{}", error);
-    assert!(message.contains("Unexpected character: @"));
+    assert_eq!(message, "unexpected character '@' at line 1, column 12");
     
     let error = LexerError::InvalidNumber("invalid".to_string());
     let message = format!("{}", error);
@@ -277,16 +282,27 @@ fn test_error_message_formatting() {
 
 #[test]
 fn test_error_clone() {
-    let error = LexerError::UnexpectedCharacter('@');
+    let error = LexerError::UnexpectedCharacter {
+        character: '@',
+        line: 1,
+        column: 1,
+        suggestion: None,
+    };
     let cloned = error.clone();
     assert_eq!(error, cloned);
 }
 
 #[test]
 fn test_error_partial_eq() {
-    let error1 = LexerError::UnexpectedCharacter('@');
-    let error2 = LexerError::UnexpectedCharacter('@');
-    let error3 = LexerError::UnexpectedCharacter('#');
+    let unexpected = |character| LexerError::UnexpectedCharacter {
+        character,
+        line: 1,
+        column: 1,
+        suggestion: None,
+    };
+    let error1 = unexpected('@');
+    let error2 = unexpected('@');
+    let error3 = unexpected('#');
     
     assert_eq!(error1, error2);
     assert_ne!(error1, error3);
@@ -323,4 +339,33 @@ fn test_error_positions() {
     assert!(result.is_err());
     // In a more sophisticated implementation, we'd check that the error
     // includes position information about where the error occurred
-} 
\ No newline at end of file
+} 
+#[test]
+fn test_unexpected_character_reports_position() {
+    let error = tokenize("let x = 42 @ 10;").unwrap_err();
+    match &error {
+        LexerError::UnexpectedCharacter { character, line, column, suggestion } => {
+            assert_eq!(*character, '@');
+            assert_eq!((*line, *column), (1, 12));
+            assert!(suggestion.is_some());
+        }
+        _ => panic!("Expected UnexpectedCharacter error"),
+    }
+    let message = error.to_string();
+    assert!(message.starts_with("unexpected character '@' at line 1, column 12"));
+    assert!(message.contains("decorators"));
+}
+
+#[test]
+fn test_unexpected_character_on_later_line() {
+    let message = tokenize("let a = 1;\nlet b = a # 2;").unwrap_err().to_string();
+    assert!(message.contains("'#'"));
+    assert!(message.contains("line 2, column 11"));
+    assert!(message.contains("private class field"));
+}
+
+#[test]
+fn test_unexpected_character_without_suggestion() {
+    let error = tokenize("x \u{7f} y").unwrap_err();
+    assert_eq!(error.to_string(), "unexpected character '\u{7f}' at line 1, column 3");
+}
@@ -2,7 +2,7 @@
 //! 
 //! Tests for lexer functionality, tokenization, and error handling.
 
-use v8_lexer::{Lexer, Token, TokenKind, tokenize, tokenize_fallback};
+use v8_lexer::{EcmaVersion, Lexer, LexerError, LexerOptions, Token, TokenKind, tokenize, tokenize_fallback, tokenize_with_options};
 
 #[test]
 fn test_lexer_creation() {
@@ -45,6 +45,33 @@ fn test_string_tokenization() {
     assert_eq!(tokens[1].kind, TokenKind::Eof);
 }
 
+#[test]
+fn test_string_escapes_cook_as_utf16() {
+    // An escaped surrogate pair combines into one character
+    let tokens = tokenize(r#""\uD83D\uDE00" "\u{1F600}" "\x41\u0042""#).unwrap();
+    assert_eq!(tokens[0].kind, TokenKind::String("\u{1F600}".to_string()));
+    assert_eq!(tokens[0].code_units, None);
+    assert_eq!(tokens[1].kind, tokens[0].kind);
+    assert_eq!(tokens[2].kind, TokenKind::String("AB".to_string()));
+
+    // A lone surrogate is kept as a code unit
+    let tokens = tokenize(r#""a\uD800" "\u{DC00}""#).unwrap();
+    assert_eq!(tokens[0].kind, TokenKind::String("a\u{FFFD}".to_string()));
+    assert_eq!(tokens[0].code_units, Some(vec![0x61, 0xD800]));
+    assert_eq!(tokens[0].string_code_units(), Some(vec![0x61, 0xD800]));
+    assert_eq!(tokens[1].code_units, Some(vec![0xDC00]));
+}
+
+#[test]
+fn test_invalid_unicode_escapes() {
+    for source in [r#""\u{110000}""#, r#""\uD83D\uZZZZ""#, r#""\u{}""#, r#""\u12""#] {
+        assert!(
+            matches!(tokenize(source), Err(LexerError::InvalidUnicodeEscape(_))),
+            "source: {}, result: {:?}", source, tokenize(source)
+        );
+    }
+}
+
 #[test]
 fn test_keyword_tokenization() {
     let keywords = vec![
@@ -89,6 +116,21 @@ fn test_operator_tokenization() {
         ("||", TokenKind::LogicalOr),
         ("++", TokenKind::Increment),
         ("--", TokenKind::Decrement),
+        ("+=", TokenKind::PlusAssign),
+        ("-=", TokenKind::MinusAssign),
+        ("*=", TokenKind::StarAssign),
+        ("/=", TokenKind::SlashAssign),
+        ("%=", TokenKind::PercentAssign),
+        ("**=", TokenKind::StarStarAssign),
+        ("&=", TokenKind::BitwiseAndAssign),
+        ("|=", TokenKind::BitwiseOrAssign),
+        ("^=", TokenKind::BitwiseXorAssign),
+        (">>>=", TokenKind::UnsignedRightShiftAssign),
+        ("<<", TokenKind::LeftShift),
+        (">>", TokenKind::RightShift),
+        (">>>", TokenKind::UnsignedRightShift),
+        ("<<=", TokenKind::LeftShiftAssign),
+        (">>=", TokenKind::RightShiftAssign),
     ];
     
     for (op_str, expected_kind) in operators {
@@ -223,6 +265,60 @@ fn test_octal_number() {
     assert_eq!(tokens[0].kind, TokenKind::Number(493.0));
 }
 
+#[test]
+fn test_leading_zero_numbers() {
+    let mut lexer = Lexer::new("010 08 019.5 0 0.5");
+    let tokens = lexer.tokenize().unwrap();
+
+    assert_eq!(tokens[0].kind, TokenKind::Number(8.0));
+    assert_eq!(tokens[1].kind, TokenKind::Number(8.0));
+    assert_eq!(tokens[2].kind, TokenKind::Number(19.5));
+    assert_eq!(tokens[3].kind, TokenKind::Number(0.0));
+    assert_eq!(tokens[4].kind, TokenKind::Number(0.5));
+
+    // Only the leading-zero forms are marked for strict mode to reject
+    let marked: Vec<_> = lexer.leading_zero_literals().iter().map(|span| span.start.column).collect();
+    assert_eq!(marked, vec![1, 5, 8]);
+}
+
+#[test]
+fn test_leading_zero_numbers_strict_mode() {
+    let mut lexer = Lexer::new("010");
+    lexer.set_strict_mode(true);
+    assert_eq!(
+        lexer.next_token().unwrap_err(),
+        LexerError::LeadingZeroInStrictMode("010".to_string())
+    );
+
+    let mut lexer = Lexer::new("0o10 10");
+    lexer.set_strict_mode(true);
+    let tokens = lexer.tokenize().unwrap();
+    assert_eq!(tokens[0].kind, TokenKind::Number(8.0));
+    assert!(lexer.leading_zero_literals().is_empty());
+}
+
+#[test]
+fn test_numeric_separators() {
+    let tokens = tokenize("1_000 0xFF_FF 1_0.2_5").unwrap();
+    assert_eq!(tokens[0].kind, TokenKind::Number(1000.0));
+    assert_eq!(tokens[1].kind, TokenKind::Number(65535.0));
+    assert_eq!(tokens[2].kind, TokenKind::Number(10.25));
+
+    for source in ["1__0", "1_", "0_1", "0x_1"] {
+        assert!(matches!(tokenize(source), Err(LexerError::InvalidNumber(_))), "{}", source);
+    }
+}
+
+#[test]
+fn test_optional_chaining_operator() {
+    let tokens = tokenize("a?.b").unwrap();
+    assert_eq!(tokens[1].kind, TokenKind::OptionalChaining);
+
+    // `?.` followed by a digit is `?` and a decimal
+    let tokens = tokenize("a?.5:1").unwrap();
+    assert_eq!(tokens[1].kind, TokenKind::Question);
+}
+
 #[test]
 fn test_bigint_number() {
     let source = "42n";
@@ -239,6 +335,23 @@ fn test_template_string() {
     assert_eq!(tokens[0].kind, TokenKind::TemplateString("hello ${name}".to_string()));
 }
 
+#[test]
+fn test_template_string_substitutions() {
+    // A substitution runs to its own `}`, past strings, comments and nested templates
+    for source in ["`a${'}'}b`", "`a${`}${x}`}b`", "`a${/* } */ x}b`", "`a${ {x: 1} }b`"] {
+        let tokens = tokenize(source).unwrap();
+        assert_eq!(tokens.len(), 2, "{}", source);
+        assert_eq!(tokens[0].kind, TokenKind::TemplateString(source[1..source.len() - 1].to_string()));
+    }
+
+    // Lines inside a template are counted
+    let tokens = tokenize("`a\n${b}`\nc").unwrap();
+    assert_eq!(tokens[1].kind, TokenKind::Identifier("c".to_string()));
+    assert_eq!(tokens[1].start().line, 3);
+
+    assert!(tokenize("`a${b").is_err());
+}
+
 #[test]
 fn test_escape_sequences() {
     let source = "\"hello\\nworld\"";
@@ -281,4 +394,68 @@ fn test_whitespace_only() {
     
     assert_eq!(tokens.len(), 1); // Only EOF
     assert_eq!(tokens[0].kind, TokenKind::Eof);
-} 
\ No newline at end of file
+} 
+
+#[test]
+fn test_span_byte_offsets() {
+    // "é" takes two bytes, so byte offsets drift from char counts
+    let source = "let é = 1;\n  foo(\"ü\");\n";
+    let tokens = tokenize(source).unwrap();
+
+    for token in &tokens[..tokens.len() - 1] {
+        let start = token.span.start.offset;
+        let end = token.span.end.offset;
+        assert!(source.is_char_boundary(start) && source.is_char_boundary(end));
+    }
+
+    let foo = tokens
+        .iter()
+        .find(|token| token.kind == TokenKind::Identifier("foo".to_string()))
+        .unwrap();
+    assert_eq!(foo.span.start.line, 2);
+    assert_eq!(foo.span.start.offset, source.find("foo").unwrap());
+    assert_eq!(&source[foo.span.start.offset..foo.span.end.offset], "foo");
+
+    let string = tokens
+        .iter()
+        .find(|token| token.kind == TokenKind::String("ü".to_string()))
+        .unwrap();
+    assert_eq!(&source[string.span.start.offset..string.span.end.offset], "\"ü\"");
+    assert_eq!(tokens.last().unwrap().span.start.offset, source.len());
+}
+
+#[test]
+fn test_regex_literals() {
+    let tokens = tokenize(r"let re = /[a/]+\d/gi; x = a / b / c;").unwrap();
+    assert_eq!(tokens[3].kind, TokenKind::Regex(r"[a/]+\d".to_string(), "gi".to_string()));
+
+    // After an operand, a slash is division
+    let slashes = tokens
+        .iter()
+        .filter(|token| token.kind == TokenKind::Slash)
+        .count();
+    assert_eq!(slashes, 2);
+
+    assert!(tokenize("/abc/gg").is_err());
+}
+
+#[test]
+fn test_ecma_version_gates_newer_tokens() {
+    let target = |ecma_version| LexerOptions { ecma_version, ..LexerOptions::default() };
+
+    for source in ["1_000", "a ?? b", "a?.b", "10n"] {
+        match tokenize_with_options(source, target(EcmaVersion::Es2015)) {
+            Err(LexerError::UnsupportedSyntax { target, .. }) => assert_eq!(target, EcmaVersion::Es2015),
+            other => panic!("Expected UnsupportedSyntax for {:?}, got {:?}", source, other),
+        }
+        assert!(tokenize_with_options(source, target(EcmaVersion::Es2021)).is_ok(), "{}", source);
+        assert!(tokenize(source).is_ok(), "{}", source);
+    }
+
+    let tokens = tokenize_with_options("a ?? b", target(EcmaVersion::Es2021)).unwrap();
+    assert_eq!(tokens[1].kind, TokenKind::NullishCoalescing);
+    let error = tokenize_with_options("1_000", target(EcmaVersion::Es2020)).unwrap_err();
+    assert_eq!(error.to_string(), "ES2021 syntax (numeric separators) is not allowed when targeting ES2020");
+    assert!(tokenize_with_options("x => x", target(EcmaVersion::Es5)).is_err());
+    assert!(tokenize_with_options("x => x", target(EcmaVersion::Es2015)).is_ok());
+}
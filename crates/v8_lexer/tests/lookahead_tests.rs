@@ -0,0 +1,54 @@
+//! Lookahead tests for v8_lexer
+//! 
+//! Tests for peeking at upcoming tokens without consuming them.
+
+use v8_lexer::{Lexer, LexerError, TokenKind};
+
+#[test]
+fn test_peek_returns_upcoming_tokens() {
+    let mut lexer = Lexer::new("(a) => a");
+    
+    assert_eq!(lexer.peek(0).unwrap().kind, TokenKind::LeftParen);
+    assert_eq!(lexer.peek(1).unwrap().kind, TokenKind::Identifier("a".to_string()));
+    assert_eq!(lexer.peek(3).unwrap().kind, TokenKind::Arrow);
+    // Peeking again does not advance
+    assert_eq!(lexer.peek(0).unwrap().kind, TokenKind::LeftParen);
+}
+
+#[test]
+fn test_next_token_after_peek_matches_tokenize() {
+    let source = "let x = foo(1, 2);";
+    let expected = Lexer::new(source).tokenize().unwrap();
+    
+    let mut lexer = Lexer::new(source);
+    assert_eq!(lexer.peek(2).unwrap().kind, TokenKind::Assign);
+    let mut tokens = Vec::new();
+    loop {
+        let token = lexer.next_token().unwrap();
+        let done = token.kind == TokenKind::Eof;
+        tokens.push(token);
+        if done {
+            break;
+        }
+    }
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn test_peek_past_end_yields_eof() {
+    let mut lexer = Lexer::new("x");
+    
+    assert_eq!(lexer.peek(1).unwrap().kind, TokenKind::Eof);
+    assert_eq!(lexer.peek(5).unwrap().kind, TokenKind::Eof);
+    assert_eq!(lexer.next_token().unwrap().kind, TokenKind::Identifier("x".to_string()));
+    assert_eq!(lexer.next_token().unwrap().kind, TokenKind::Eof);
+}
+
+#[test]
+fn test_peek_reports_lexer_errors_in_order() {
+    let mut lexer = Lexer::new("a @");
+    
+    assert!(matches!(lexer.peek(1), Err(LexerError::UnexpectedCharacter { character: '@', .. })));
+    assert_eq!(lexer.next_token().unwrap().kind, TokenKind::Identifier("a".to_string()));
+    assert!(matches!(lexer.next_token(), Err(LexerError::UnexpectedCharacter { character: '@', .. })));
+}
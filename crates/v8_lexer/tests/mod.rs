@@ -7,6 +7,7 @@
 mod common;
 mod lexer_tests;
 mod error_tests;
+mod lookahead_tests;
 
 // TODO: Implement these test modules
 // mod token_tests;
@@ -208,4 +208,36 @@ fn test_token_debug() {
     let debug_str = format!("{:?}", token);
     assert!(debug_str.contains("String"));
     assert!(debug_str.contains("test"));
-} 
\ No newline at end of file
+} 
+#[test]
+fn test_operator_precedence() {
+    assert!(TokenKind::Star.precedence() > TokenKind::Plus.precedence());
+    assert!(TokenKind::StarStar.precedence() > TokenKind::Star.precedence());
+    assert!(TokenKind::LogicalAnd.precedence() > TokenKind::LogicalOr.precedence());
+    assert_eq!(TokenKind::NullishCoalescing.precedence(), TokenKind::LogicalOr.precedence());
+    assert_eq!(TokenKind::Keyword("instanceof".to_string()).precedence(), TokenKind::LessThan.precedence());
+    assert_eq!(TokenKind::Keyword("typeof".to_string()).precedence(), None);
+    assert_eq!(TokenKind::Assign.precedence(), None);
+}
+
+#[test]
+fn test_operator_categories() {
+    assert!(TokenKind::Percent.is_binary_operator());
+    assert!(TokenKind::Keyword("in".to_string()).is_binary_operator());
+    assert!(!TokenKind::Exclamation.is_binary_operator());
+    assert!(!TokenKind::PlusAssign.is_binary_operator());
+
+    assert!(TokenKind::Assign.is_assignment_operator());
+    assert!(TokenKind::UnsignedRightShiftAssign.is_assignment_operator());
+    assert!(!TokenKind::Equal.is_assignment_operator());
+}
+
+#[test]
+fn test_operator_str() {
+    assert_eq!(TokenKind::StrictNotEqual.operator_str(), Some("!=="));
+    assert_eq!(TokenKind::StarStarAssign.operator_str(), Some("**="));
+    assert_eq!(TokenKind::OptionalChaining.operator_str(), Some("?."));
+    assert_eq!(TokenKind::Keyword("typeof".to_string()).operator_str(), Some("typeof"));
+    assert_eq!(TokenKind::Keyword("if".to_string()).operator_str(), None);
+    assert_eq!(TokenKind::Identifier("x".to_string()).operator_str(), None);
+}
@@ -23,6 +23,17 @@ pub enum ParseError {
         expected: Option<String>,
     },
 
+    #[error(
+        "Unterminated {construct} opened at line {}, column {} (end of input at {position})",
+        .opened_at.line,
+        .opened_at.column
+    )]
+    Unterminated {
+        construct: String,
+        opened_at: Position,
+        position: Position,
+    },
+
     #[error("Invalid syntax: {message} at {position}")]
     InvalidSyntax {
         message: String,
@@ -47,6 +58,12 @@ pub enum ParseError {
         position: Position,
     },
 
+    #[error("Missing initializer in {kind} declaration at {position}")]
+    MissingInitializer {
+        kind: String,
+        position: Position,
+    },
+
     #[error("Invalid function: {message} at {position}")]
     InvalidFunction {
         message: String,
@@ -65,6 +82,18 @@ pub enum ParseError {
         position: Position,
     },
 
+    #[error("Unexpected reserved word '{word}' used as a binding name at {}", .span.start)]
+    ReservedWordAsBinding {
+        word: String,
+        span: Span,
+    },
+
+    #[error("Nesting too deep: more than {limit} levels at {position}")]
+    NestingTooDeep {
+        limit: usize,
+        position: Position,
+    },
+
     #[error("Lexer error: {message} at {position}")]
     LexerError {
         message: String,
@@ -99,6 +128,15 @@ impl ParseError {
         }
     }
 
+    /// Create an error for a `{`, `(` or `[` still open at the end of input
+    pub fn unterminated(construct: &str, opened_at: Position, position: Position) -> Self {
+        ParseError::Unterminated {
+            construct: construct.to_string(),
+            opened_at,
+            position,
+        }
+    }
+
     /// Create an invalid syntax error
     pub fn invalid_syntax(message: &str, position: Position) -> Self {
         ParseError::InvalidSyntax {
@@ -131,6 +169,14 @@ impl ParseError {
         }
     }
 
+    /// Create an error for a `const` or destructuring declarator without `=`
+    pub fn missing_initializer(kind: &str, position: Position) -> Self {
+        ParseError::MissingInitializer {
+            kind: kind.to_string(),
+            position,
+        }
+    }
+
     /// Create an invalid function error
     pub fn invalid_function(message: &str, position: Position) -> Self {
         ParseError::InvalidFunction {
@@ -155,6 +201,20 @@ impl ParseError {
         }
     }
 
+    /// Create an error for a reserved word declared as a variable,
+    /// parameter or function name
+    pub fn reserved_word_as_binding(word: &str, span: Span) -> Self {
+        ParseError::ReservedWordAsBinding {
+            word: word.to_string(),
+            span,
+        }
+    }
+
+    /// Create an error for input nested more than `limit` levels deep
+    pub fn nesting_too_deep(limit: usize, position: Position) -> Self {
+        ParseError::NestingTooDeep { limit, position }
+    }
+
     /// Create a lexer error
     pub fn lexer_error(message: &str, position: Position) -> Self {
         ParseError::LexerError {
@@ -175,13 +235,17 @@ impl ParseError {
         match self {
             ParseError::UnexpectedToken { position, .. } => Some(*position),
             ParseError::UnexpectedEndOfInput { position, .. } => Some(*position),
+            ParseError::Unterminated { position, .. } => Some(*position),
             ParseError::InvalidSyntax { position, .. } => Some(*position),
             ParseError::InvalidExpression { position, .. } => Some(*position),
             ParseError::InvalidStatement { position, .. } => Some(*position),
             ParseError::InvalidDeclaration { position, .. } => Some(*position),
+            ParseError::MissingInitializer { position, .. } => Some(*position),
             ParseError::InvalidFunction { position, .. } => Some(*position),
             ParseError::InvalidClass { position, .. } => Some(*position),
             ParseError::InvalidModule { position, .. } => Some(*position),
+            ParseError::ReservedWordAsBinding { span, .. } => Some(span.start),
+            ParseError::NestingTooDeep { position, .. } => Some(*position),
             ParseError::LexerError { position, .. } => Some(*position),
             ParseError::InternalError { .. } => None,
         }
@@ -189,6 +253,9 @@ impl ParseError {
 
     /// Get the span where the error occurred
     pub fn span(&self) -> Option<Span> {
+        if let ParseError::ReservedWordAsBinding { span, .. } = self {
+            return Some(span.clone());
+        }
         self.position().map(|pos| Span::new(pos, pos))
     }
 } 
\ No newline at end of file
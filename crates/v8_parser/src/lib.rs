@@ -7,7 +7,7 @@ pub mod parser;
 pub mod error;
 pub mod recovery;
 
-pub use parser::Parser;
+pub use parser::{Edit, Parser};
 pub use error::{ParseError, ParseResult};
 pub use recovery::{RecoveryStrategy, ParsingContext, RecoveryContext};
 
@@ -5,14 +5,36 @@ use crate::recovery::{ErrorRecovery, RecoveryContext, RecoveryStrategy, ParsingC
 use v8_ast::{
     Node, Position, Span, Program, VariableDeclaration, VariableDeclarator, FunctionDeclaration, 
     FunctionExpression, ClassDeclaration, ClassExpression, IfStatement, WhileStatement, 
-    DoWhileStatement, ForStatement, SwitchStatement, SwitchCase, TryStatement, CatchClause, 
-    WithStatement, DebuggerStatement, ReturnStatement, BreakStatement, ContinueStatement, 
+    DoWhileStatement, ForStatement, ForOfStatement, ForInStatement, SwitchStatement, SwitchCase, TryStatement, CatchClause, 
+    WithStatement, DebuggerStatement, ReturnStatement, BreakStatement, ContinueStatement, LabeledStatement,
     ThrowStatement, ExpressionStatement, BlockStatement, BinaryExpression, LogicalExpression,
+    ConditionalExpression,
     UnaryExpression, UpdateExpression, AssignmentExpression, CallExpression, NewExpression, 
-    MemberExpression, ArrayLiteral, ObjectLiteral, Property, Super, ImportDeclaration, 
-    ExportDeclaration, ArrowFunctionExpression, SpreadElement,
+    MemberExpression, ArrayLiteral, ObjectLiteral, Property, Super, ThisExpression, ImportDeclaration, 
+    ExportDeclaration, ArrowFunctionExpression, SpreadElement, RestElement, ObjectPattern,
+    ArrayPattern, AssignmentPattern, TemplateLiteral, TemplateElement, TaggedTemplateExpression, RegExp,
+    NumericLiteral, ParenthesizedExpression, SequenceExpression, MetaProperty, YieldExpression,
+    AwaitExpression,
 };
-use v8_lexer::{Lexer, Token, TokenKind};
+use v8_ast::codegen::format_number;
+use v8_lexer::{Lexer, LexerError, Token, TokenKind};
+use std::ops::Range;
+
+/// Words that are never a valid binding name
+const RESERVED_WORDS: &[&str] = &[
+    "break", "case", "catch", "class", "const", "continue", "debugger", "default", "delete", "do", "else",
+    "enum", "export", "extends", "false", "finally", "for", "function", "if", "import", "in", "instanceof",
+    "new", "null", "return", "super", "switch", "this", "throw", "true", "try", "typeof", "var", "void",
+    "while", "with",
+];
+
+/// A change to the source: `deleted` bytes at `offset` replaced by `inserted`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub offset: usize,
+    pub deleted: usize,
+    pub inserted: String,
+}
 
 /// Main parser for JavaScript/ECMAScript
 pub struct Parser {
@@ -28,6 +50,9 @@ pub struct Parser {
     /// Previous token
     previous: Option<Token>,
     
+    /// Error the lexer stopped at, after the end of the last token read
+    lexer_error: Option<(LexerError, Position)>,
+    
     /// Error recovery manager
     error_recovery: ErrorRecovery,
     
@@ -36,15 +61,61 @@ pub struct Parser {
     
     /// Whether we're in strict mode
     strict_mode: bool,
+    
+    /// `{`, `(` and `[` tokens not closed yet, innermost last
+    openers: Vec<Opener>,
+    
+    /// Delimiter depth of the for head being parsed, where `in` starts a
+    /// for-in loop instead of being an operator
+    for_head: Option<usize>,
+    
+    /// What the next `{` opens, when it is not a plain block
+    next_brace: Option<&'static str>,
+    
+    /// Whether grouping parentheses produce `ParenthesizedExpression` nodes
+    preserve_parens: bool,
+    
+    /// Whether `await` is an operator: inside an async function body
+    in_async: bool,
+    
+    /// Whether `yield` is an operator: inside a generator body
+    in_generator: bool,
+    
+    /// Statements and expressions being parsed, one inside the other
+    depth: usize,
+    
+    /// Deepest nesting accepted before failing with `NestingTooDeep`
+    max_depth: usize,
+    
+    /// Commas right after a spread element, which a rest element may not have
+    spread_commas: Vec<Position>,
+    
+    /// Byte range of each top-level statement of the last parse, for `reparse`
+    statement_ranges: Vec<Range<usize>>,
+}
+
+/// An opening delimiter, reported if the input ends before it is closed
+#[derive(Debug, Clone)]
+struct Opener {
+    closer: TokenKind,
+    construct: &'static str,
+    position: Position,
 }
 
 impl Parser {
+    /// Nesting limit until `set_max_depth` changes it
+    ///
+    /// Each level of parentheses takes about 8 KiB of native stack in an
+    /// optimized build, so this fits a thread's default 2 MiB; debug builds
+    /// need several times more.
+    pub const DEFAULT_MAX_DEPTH: usize = 256;
+
     /// Create a new parser
     pub fn new(source: &str) -> Self {
         let mut lexer = Lexer::new(source);
-        let current = match lexer.next_token() {
-            Ok(token) => Some(token),
-            Err(_) => None,
+        let (current, lexer_error) = match lexer.next_token() {
+            Ok(token) => (Some(token), None),
+            Err(error) => (None, Some((error, Position::default()))),
         };
         
         Self {
@@ -52,15 +123,55 @@ impl Parser {
             lexer,
             current,
             previous: None,
+            lexer_error,
             error_recovery: ErrorRecovery::default(),
             context: ParsingContext::TopLevel,
             strict_mode: false,
+            openers: Vec::new(),
+            for_head: None,
+            next_brace: None,
+            preserve_parens: false,
+            in_async: false,
+            in_generator: false,
+            depth: 0,
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+            spread_commas: Vec::new(),
+            statement_ranges: Vec::new(),
         }
     }
 
+    /// Keep grouping parentheses as `ParenthesizedExpression` nodes
+    ///
+    /// Off by default: the parentheses only matter to tools that re-emit
+    /// the source, and the evaluators see through them either way.
+    pub fn set_preserve_parens(&mut self, preserve: bool) {
+        self.preserve_parens = preserve;
+    }
+
+    /// Limit how deeply statements and expressions may nest
+    ///
+    /// The parser recurses once per level, so deeper input, such as
+    /// thousands of nested parentheses, fails with `NestingTooDeep`
+    /// instead of overflowing the native stack.
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
     /// Parse the entire source code
+    ///
+    /// A lexer error ends the token stream early, so it fails the parse
+    /// whatever the tokens before it parsed to.
     pub fn parse(&mut self) -> ParseResult<Node> {
-        self.parse_program()
+        let result = self.parse_program();
+        match &self.lexer_error {
+            Some((error, position)) => Err(ParseError::lexer_error(&error.to_string(), *position)),
+            None => result,
+        }
+    }
+
+    /// The error the lexer stopped at during the last parse, if any
+    pub fn lexer_error(&self) -> Option<&LexerError> {
+        self.lexer_error.as_ref().map(|(error, _)| error)
     }
 
     /// Parse with error recovery
@@ -74,8 +185,115 @@ impl Parser {
         }
     }
 
+    /// Parse `new_source`, the source of `old_ast` after `edit`, reusing the
+    /// top-level statements the edit did not touch
+    ///
+    /// `old_ast` must be the result of this parser's last parse. The
+    /// statements overlapping the edit are parsed again, with one neighbour
+    /// on each side in case the edit joined or split statements; the others
+    /// are moved into the new tree as they are. A statement after the edit
+    /// is kept only if its position did not change: the edit added or
+    /// removed no lines, and the statement starts on a later line. Spans only
+    /// record lines and columns, so a reused statement needs no change; the
+    /// byte ranges kept for the next edit are shifted by the edit's length
+    /// difference. When the last parse or the new one has errors, the whole
+    /// source is parsed.
+    pub fn reparse(&mut self, old_ast: Node, edit: &Edit, new_source: &str) -> (Node, Vec<ParseError>) {
+        let old_end = edit.offset + edit.deleted;
+        let expected_len = (self.source.len() + edit.inserted.len()).checked_sub(edit.deleted);
+        let program = match old_ast {
+            Node::Program(program)
+                if self.error_recovery.errors().is_empty()
+                    && program.body.len() == self.statement_ranges.len()
+                    && self.source.get(edit.offset..old_end).is_some()
+                    && expected_len == Some(new_source.len()) =>
+            {
+                program
+            }
+            _ => return self.parse_again(new_source),
+        };
+        let ranges = std::mem::take(&mut self.statement_ranges);
+        let shift = |offset: usize| offset + edit.inserted.len() - edit.deleted;
+
+        // The statements touching the edit, widened by a neighbour on each side
+        let first = ranges.iter().position(|range| range.end >= edit.offset).unwrap_or(ranges.len()).saturating_sub(1);
+        let mut last = ranges.iter().rposition(|range| range.start <= old_end).map_or(1, |index| index + 2).min(ranges.len());
+        let line_of = |offset: usize| self.source[..offset].matches('\n').count();
+        if self.source[edit.offset..old_end].matches('\n').count() != edit.inserted.matches('\n').count() {
+            last = ranges.len();
+        } else {
+            let edit_line = line_of(old_end);
+            while last < ranges.len() && line_of(ranges[last].start) == edit_line {
+                last += 1;
+            }
+        }
+
+        // Blanking the text before the region keeps its lines and columns
+        let start = if first == 0 { 0 } else { ranges[first].start };
+        // A statement's span ends at the token after it, so the statement following the
+        // region is parsed too and then dropped
+        let end = if last == ranges.len() { new_source.len() } else { shift(ranges[last].end) };
+        let mut text: String = new_source[..start].chars().map(|c| if c == '\n' || c == '\r' { c } else { ' ' }).collect();
+        let blanked = text.len();
+        text.push_str(&new_source[start..end]);
+        let mut parser = Parser::new(&text);
+        parser.preserve_parens = self.preserve_parens;
+        parser.max_depth = self.max_depth;
+        let mut region = match parser.parse_with_recovery() {
+            (Some(Node::Program(region)), errors) if errors.is_empty() => region,
+            _ => return self.parse_again(new_source),
+        };
+        let mut region_ranges: Vec<Range<usize>> =
+            parser.statement_ranges.iter().map(|range| range.start - blanked + start..range.end - blanked + start).collect();
+        if last < ranges.len() {
+            let follower = shift(ranges[last].start)..shift(ranges[last].end);
+            if region_ranges.last() != Some(&follower) {
+                return self.parse_again(new_source);
+            }
+            region_ranges.pop();
+            region.body.pop();
+        }
+
+        let mut body = program.body;
+        let tail = body.split_off(last);
+        body.truncate(first);
+        body.extend(region.body);
+        body.extend(tail);
+        let mut statement_ranges = ranges[..first].to_vec();
+        statement_ranges.extend(region_ranges);
+        statement_ranges.extend(ranges[last..].iter().map(|range| shift(range.start)..shift(range.end)));
+        let span = match (program.span, region.span) {
+            (Some(old), Some(new)) => Some(Span::new(
+                if first == 0 { new.start } else { old.start },
+                if last == ranges.len() { new.end } else { old.end },
+            )),
+            (old, new) => new.or(old),
+        };
+
+        self.source = new_source.to_string();
+        self.statement_ranges = statement_ranges;
+        let ast = Node::Program(Program { body, source_type: program.source_type, span });
+        (ast, Vec::new())
+    }
+
+    /// Parse `source` from scratch, keeping this parser's settings
+    fn parse_again(&mut self, source: &str) -> (Node, Vec<ParseError>) {
+        let mut parser = Parser::new(source);
+        parser.preserve_parens = self.preserve_parens;
+        parser.max_depth = self.max_depth;
+        let (ast, errors) = parser.parse_with_recovery();
+        *self = parser;
+        let ast = ast.unwrap_or_else(|| {
+            Node::Program(Program { body: Vec::new(), source_type: "script".to_string(), span: None })
+        });
+        (ast, errors)
+    }
+
     /// Parse a program (top-level)
     fn parse_program(&mut self) -> ParseResult<Node> {
+        self.statement_ranges.clear();
+        self.strict_mode = false;
+        let mut prologue = true;
         let mut body = Vec::new();
         let start_pos = self.current_position();
         
@@ -91,8 +309,14 @@ impl Parser {
         }
         
         while !self.is_eof() {
+            let start = self.current.as_ref().map_or(0, |token| token.span.start.offset);
             match self.parse_statement() {
-                Ok(stmt) => body.push(stmt),
+                Ok(stmt) => {
+                    self.check_directive(&mut prologue, &stmt);
+                    let end = self.previous.as_ref().map_or(start, |token| token.span.end.offset);
+                    self.statement_ranges.push(start..end);
+                    body.push(stmt);
+                }
                 Err(error) => {
                     if !self.try_recover_from_error(error.clone()) {
                         return Err(error);
@@ -117,10 +341,22 @@ impl Parser {
 
     /// Parse a statement
     fn parse_statement(&mut self) -> ParseResult<Node> {
+        self.nested(Self::parse_statement_kind)
+    }
+
+    /// Parse a statement of the kind its first tokens announce
+    fn parse_statement_kind(&mut self) -> ParseResult<Node> {
         let old_context = self.context.clone();
         self.context = ParsingContext::Statement;
         
-        let result = if let Some(token) = &self.current {
+        let result = if self.check_identifier() && self.next_is(TokenKind::Colon) {
+            self.parse_labeled_statement()
+        } else if self.check_keyword("async")
+            && self.next_is(TokenKind::Keyword("function".to_string()))
+            && self.next_on_same_line()
+        {
+            self.parse_function_declaration()
+        } else if let Some(token) = &self.current {
             match &token.kind {
                 TokenKind::Keyword(kw) => match kw.as_str() {
                     "let" | "const" | "var" => self.parse_declaration(),
@@ -142,6 +378,8 @@ impl Parser {
                     "export" => self.parse_export_declaration(),
                     _ => self.parse_expression_statement(),
                 },
+                // A leading `{` always opens a block; an object literal in
+                // statement position needs parentheses, as in `({a: 1})`
                 TokenKind::LeftBrace => self.parse_block_statement(),
                 TokenKind::Semicolon => self.parse_empty_statement(),
                 _ => self.parse_expression_statement(),
@@ -199,13 +437,28 @@ impl Parser {
         let mut declarations = Vec::new();
         
         loop {
-            let id = self.parse_identifier()?;
+            let position = self.current_position().unwrap_or_default();
+            let is_pattern = self.check(TokenKind::LeftBrace) || self.check(TokenKind::LeftBracket);
+            let id = if is_pattern {
+                self.parse_destructuring_pattern()?
+            } else {
+                self.parse_binding_identifier()?
+            };
             let init = if self.check(TokenKind::Assign) {
                 self.advance(); // Consume =
-                Some(Box::new(self.parse_expression()?))
+                Some(Box::new(self.parse_assignment_expression()?))
             } else {
                 None
             };
+            // The head of a for-in/for-of loop supplies the value instead
+            if init.is_none() && !self.check_keyword("of") && !self.check_keyword("in") {
+                if kind == "const" {
+                    return Err(ParseError::missing_initializer("const", position));
+                }
+                if is_pattern {
+                    return Err(ParseError::missing_initializer("destructuring", position));
+                }
+            }
             
             let span = self.create_span_from_tokens();
             declarations.push(VariableDeclarator {
@@ -233,29 +486,37 @@ impl Parser {
         }))
     }
 
-    /// Parse a function declaration
+    /// Parse a function declaration, `[async] function [*] name(params) { body }`
     fn parse_function_declaration(&mut self) -> ParseResult<Node> {
+        let is_async = self.check_keyword("async");
+        if is_async {
+            self.advance(); // Consume 'async'
+        }
         self.advance(); // Consume 'function'
+        let generator = self.check(TokenKind::Star);
+        if generator {
+            self.advance(); // Consume '*'
+        }
         
-        let id = if self.check_identifier() {
-            Some(Box::new(self.parse_identifier()?))
-        } else {
+        let id = if self.check(TokenKind::LeftParen) {
             None
+        } else {
+            Some(Box::new(self.parse_binding_identifier()?))
         };
         
         self.expect(TokenKind::LeftParen)?;
         let params = self.parse_parameters()?;
         self.expect(TokenKind::RightParen)?;
         
-        let body = Box::new(self.parse_function_body()?);
+        let body = Box::new(self.in_function(is_async, generator, Self::parse_function_body)?);
         
         let span = self.create_span_from_tokens();
         Ok(Node::FunctionDeclaration(FunctionDeclaration {
             id,
             params,
             body,
-            generator: false,
-            r#async: false,
+            generator,
+            r#async: is_async,
             span: Some(span),
         }))
     }
@@ -264,10 +525,11 @@ impl Parser {
     fn parse_class_declaration(&mut self) -> ParseResult<Node> {
         self.advance(); // Consume 'class'
         
-        let id = if self.check_identifier() {
-            Some(Box::new(self.parse_identifier()?))
-        } else {
+        // A class is strict mode code, its name included
+        let id = if self.check(TokenKind::LeftBrace) || self.check_keyword("extends") {
             None
+        } else {
+            Some(Box::new(self.in_strict_mode(Self::parse_binding_identifier)?))
         };
         
         let super_class = if let Some(token) = &self.current {
@@ -285,7 +547,7 @@ impl Parser {
             None
         };
         
-        let body = Box::new(self.parse_class_body()?);
+        let body = Box::new(self.in_strict_mode(Self::parse_class_body)?);
         
         let span = self.create_span_from_tokens();
         Ok(Node::ClassDeclaration(ClassDeclaration {
@@ -352,19 +614,50 @@ impl Parser {
     fn parse_for_statement(&mut self) -> ParseResult<Node> {
         self.advance(); // Consume 'for'
         
+        let is_await = self.check_keyword("await");
+        if is_await {
+            if !self.in_async {
+                return Err(ParseError::invalid_statement(
+                    "for await is only valid in async functions",
+                    self.current_position().unwrap_or_default(),
+                ));
+            }
+            self.advance(); // Consume 'await'
+        }
+        
         self.expect(TokenKind::LeftParen)?;
         
+        let outer_head = self.for_head.replace(self.openers.len());
         let init = if !self.check(TokenKind::Semicolon) {
-            Some(Box::new(if self.is_declaration() {
-                self.parse_declaration()?
+            let init = if self.is_declaration() {
+                self.parse_declaration()
             } else {
-                self.parse_expression()?
-            }))
+                self.parse_expression()
+            };
+            self.for_head = outer_head;
+            Some(Box::new(init?))
         } else {
+            self.for_head = outer_head;
             None
         };
         
-        self.expect(TokenKind::Semicolon)?;
+        if self.check_keyword("of") {
+            return self.parse_for_of_rest(init, is_await);
+        }
+        if self.check_keyword("in") && !is_await {
+            return self.parse_for_in_rest(init);
+        }
+        if is_await {
+            return Err(ParseError::invalid_statement(
+                "for await requires an of clause",
+                self.current_position().unwrap_or_default(),
+            ));
+        }
+        
+        // A declaration consumes its own terminating semicolon
+        if !matches!(init.as_deref(), Some(Node::VariableDeclaration(_))) {
+            self.expect(TokenKind::Semicolon)?;
+        }
         
         let test = if !self.check(TokenKind::Semicolon) {
             Some(Box::new(self.parse_expression()?))
@@ -394,6 +687,67 @@ impl Parser {
         }))
     }
 
+    /// Parse the remainder of a for-of statement, from the `of` keyword
+    fn parse_for_of_rest(&mut self, left: Option<Box<Node>>, is_await: bool) -> ParseResult<Node> {
+        let position = self.current_position().unwrap_or_default();
+        let left = match left {
+            Some(left) => left,
+            None => return Err(ParseError::invalid_statement("Missing for-of binding", position)),
+        };
+        if let Node::VariableDeclaration(decl) = left.as_ref() {
+            if decl.declarations.len() != 1 || decl.declarations[0].init.is_some() {
+                return Err(ParseError::invalid_statement(
+                    "Invalid left-hand side in for-of loop",
+                    position,
+                ));
+            }
+        }
+        self.advance(); // Consume 'of'
+        
+        let right = Box::new(self.parse_assignment_expression()?);
+        self.expect(TokenKind::RightParen)?;
+        let body = Box::new(self.parse_statement()?);
+        
+        let span = self.create_span_from_tokens();
+        Ok(Node::ForOfStatement(ForOfStatement {
+            left,
+            right,
+            body,
+            r#await: is_await,
+            span: Some(span),
+        }))
+    }
+
+    /// Parse the remainder of a for-in statement, from the `in` keyword
+    fn parse_for_in_rest(&mut self, left: Option<Box<Node>>) -> ParseResult<Node> {
+        let position = self.current_position().unwrap_or_default();
+        let left = match left {
+            Some(left) => left,
+            None => return Err(ParseError::invalid_statement("Missing for-in binding", position)),
+        };
+        if let Node::VariableDeclaration(decl) = left.as_ref() {
+            if decl.declarations.len() != 1 || decl.declarations[0].init.is_some() {
+                return Err(ParseError::invalid_statement(
+                    "Invalid left-hand side in for-in loop",
+                    position,
+                ));
+            }
+        }
+        self.advance(); // Consume 'in'
+        
+        let right = Box::new(self.parse_expression()?);
+        self.expect(TokenKind::RightParen)?;
+        let body = Box::new(self.parse_statement()?);
+        
+        let span = self.create_span_from_tokens();
+        Ok(Node::ForInStatement(ForInStatement {
+            left,
+            right,
+            body,
+            span: Some(span),
+        }))
+    }
+
     /// Parse a return statement
     fn parse_return_statement(&mut self) -> ParseResult<Node> {
         self.advance(); // Consume 'return'
@@ -404,6 +758,11 @@ impl Parser {
             None
         };
         
+        // Consume semicolon if present
+        if self.check(TokenKind::Semicolon) {
+            self.advance();
+        }
+        
         let span = self.create_span_from_tokens();
         Ok(Node::ReturnStatement(ReturnStatement {
             argument,
@@ -421,6 +780,11 @@ impl Parser {
             None
         };
         
+        // Consume semicolon if present
+        if self.check(TokenKind::Semicolon) {
+            self.advance();
+        }
+        
         let span = self.create_span_from_tokens();
         Ok(Node::BreakStatement(BreakStatement {
             label,
@@ -438,6 +802,11 @@ impl Parser {
             None
         };
         
+        // Consume semicolon if present
+        if self.check(TokenKind::Semicolon) {
+            self.advance();
+        }
+        
         let span = self.create_span_from_tokens();
         Ok(Node::ContinueStatement(ContinueStatement {
             label,
@@ -451,6 +820,11 @@ impl Parser {
         
         let argument = Box::new(self.parse_expression()?);
         
+        // Consume semicolon if present
+        if self.check(TokenKind::Semicolon) {
+            self.advance();
+        }
+        
         let span = self.create_span_from_tokens();
         Ok(Node::ThrowStatement(ThrowStatement {
             argument,
@@ -507,7 +881,7 @@ impl Parser {
         self.advance(); // Consume 'catch'
         
         self.expect(TokenKind::LeftParen)?;
-        let param = Box::new(self.parse_identifier()?);
+        let param = Box::new(self.parse_binding_identifier()?);
         self.expect(TokenKind::RightParen)?;
         
         let body = Box::new(self.parse_block_statement()?);
@@ -567,8 +941,8 @@ impl Parser {
         self.expect(TokenKind::Colon)?;
         
         let mut consequent = Vec::new();
-        while !self.check(TokenKind::Keyword("case".to_string())) && 
-              !self.check(TokenKind::Keyword("default".to_string())) && 
+        while !self.check_keyword("case") && 
+              !self.check_keyword("default") && 
               !self.check(TokenKind::RightBrace) && 
               !self.is_eof() {
             consequent.push(self.parse_statement()?);
@@ -641,7 +1015,13 @@ impl Parser {
     }
 
     /// Parse a block statement
+    ///
+    /// A block cut off by the end of input is still returned, with the
+    /// statements parsed so far, and an `Unterminated` error is recorded.
     fn parse_block_statement(&mut self) -> ParseResult<Node> {
+        let opener = self.openers.len();
+        // Only a function body starts with a directive prologue
+        let mut prologue = self.next_brace == Some("function body");
         self.advance(); // Consume '{'
         
         let old_context = self.context.clone();
@@ -650,7 +1030,10 @@ impl Parser {
         let mut body = Vec::new();
         while !self.check(TokenKind::RightBrace) && !self.is_eof() {
             match self.parse_statement() {
-                Ok(stmt) => body.push(stmt),
+                Ok(stmt) => {
+                    self.check_directive(&mut prologue, &stmt);
+                    body.push(stmt);
+                }
                 Err(error) => {
                     if !self.try_recover_from_error(error.clone()) {
                         self.context = old_context;
@@ -660,7 +1043,21 @@ impl Parser {
             }
         }
         
-        self.expect(TokenKind::RightBrace)?;
+        if self.is_eof() && self.error_recovery.can_recover() {
+            let error = match self.openers.get(opener) {
+                Some(opener) => {
+                    let position = self.current_position().or_else(|| self.previous_position()).unwrap_or_default();
+                    ParseError::unterminated(opener.construct, opener.position, position)
+                }
+                None => self.end_of_input_error("RightBrace"),
+            };
+            self.error_recovery.add_error(error);
+            // Outer constructs report their own delimiters
+            self.openers.truncate(opener);
+        } else if let Err(error) = self.expect(TokenKind::RightBrace) {
+            self.context = old_context;
+            return Err(error);
+        }
         
         self.context = old_context;
         
@@ -683,6 +1080,20 @@ impl Parser {
         }))
     }
 
+    /// Parse a labeled statement, `label: body`
+    fn parse_labeled_statement(&mut self) -> ParseResult<Node> {
+        let label = Box::new(self.parse_identifier()?);
+        self.expect(TokenKind::Colon)?;
+        let body = Box::new(self.parse_statement()?);
+        
+        let span = self.create_span_from_tokens();
+        Ok(Node::LabeledStatement(LabeledStatement {
+            label,
+            body,
+            span: Some(span),
+        }))
+    }
+
     /// Parse an expression statement
     fn parse_expression_statement(&mut self) -> ParseResult<Node> {
         let expression = Box::new(self.parse_expression()?);
@@ -732,199 +1143,285 @@ impl Parser {
     }
 
     /// Parse an expression
+    ///
+    /// Comma-separated expressions form a `SequenceExpression`; contexts
+    /// where a comma separates items parse with `parse_assignment_expression`.
     fn parse_expression(&mut self) -> ParseResult<Node> {
-        self.parse_assignment_expression()
+        let first = self.parse_assignment_expression()?;
+        if !self.check(TokenKind::Comma) {
+            return Ok(first);
+        }
+        let mut expressions = vec![first];
+        while self.check(TokenKind::Comma) {
+            self.advance(); // Consume ','
+            expressions.push(self.parse_assignment_expression()?);
+        }
+        
+        let span = self.create_span_from_tokens();
+        Ok(Node::SequenceExpression(SequenceExpression {
+            expressions,
+            span: Some(span),
+        }))
     }
 
     /// Parse an assignment expression
     fn parse_assignment_expression(&mut self) -> ParseResult<Node> {
-        let left = self.parse_logical_or_expression()?;
+        self.nested(Self::parse_assignment_or_conditional)
+    }
+
+    /// Parse an assignment, or the conditional expression it starts with
+    fn parse_assignment_or_conditional(&mut self) -> ParseResult<Node> {
+        if self.in_generator && self.check_keyword("yield") {
+            return self.parse_yield_expression();
+        }
+        if self.is_arrow_head() {
+            return self.parse_arrow_function_expression(false);
+        }
+        let start = self.current_position();
+        let left = self.parse_conditional_expression()?;
         
-        if self.is_assignment_operator() {
+        if self.current.as_ref().is_some_and(|token| token.kind.is_assignment_operator()) {
             let operator = self.current_token_string();
+            let position = self.current_position().unwrap_or_default();
             self.advance(); // Consume operator
+            // `[a, ...rest] = arr` and `({a, ...rest} = obj)` destructure
+            let left = match left {
+                Node::ArrayLiteral(_) | Node::ObjectLiteral(_) if operator == "=" => to_assignment_pattern(left, position, &self.spread_commas)?,
+                left => left,
+            };
             let right = Box::new(self.parse_assignment_expression()?);
             
-            let span = self.create_span_from_tokens();
+            let span = self.span_through(&left, &right, start);
             Ok(Node::AssignmentExpression(AssignmentExpression {
                 left: Box::new(left),
                 operator,
                 right,
                 span: Some(span),
             }))
-        } else if self.check(TokenKind::Arrow) {
-            // Arrow function expression
-            self.parse_arrow_function_expression(false)
         } else {
             Ok(left)
         }
     }
 
-    /// Parse a logical OR expression (including nullish coalescing)
-    fn parse_logical_or_expression(&mut self) -> ParseResult<Node> {
-        let mut left = self.parse_logical_and_expression()?;
-        
-        while self.is_logical_or_operator() {
-            let operator = self.current_token_string();
-            self.advance(); // Consume operator
-            let right = Box::new(self.parse_logical_and_expression()?);
-            
-            let span = self.create_span_from_tokens();
-            left = Node::LogicalExpression(LogicalExpression {
-                left: Box::new(left),
-                operator,
-                right,
-                span: Some(span),
-            });
+    /// Check whether the current token starts the parameters of an arrow
+    /// function: an identifier or a parenthesized list followed by `=>`
+    fn is_arrow_head(&mut self) -> bool {
+        if self.check_identifier() || self.check_keyword("async") {
+            return self.next_is(TokenKind::Arrow);
         }
-        
-        Ok(left)
-    }
-    
-    /// Check if the current token is a logical OR operator
-    fn is_logical_or_operator(&self) -> bool {
-        if let Some(token) = &self.current {
-            matches!(token.kind,
-                TokenKind::LogicalOr | TokenKind::NullishCoalescing
-            )
-        } else {
-            false
+        if !self.check(TokenKind::LeftParen) {
+            return false;
+        }
+        // Find the matching `)` without consuming anything
+        let mut depth = 1;
+        let mut index = 0;
+        loop {
+            match self.lexer.peek(index).map(|token| token.kind.clone()) {
+                Ok(TokenKind::LeftParen) => depth += 1,
+                Ok(TokenKind::RightParen) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return matches!(self.lexer.peek(index + 1), Ok(token) if token.kind == TokenKind::Arrow);
+                    }
+                }
+                Ok(TokenKind::Eof) | Err(_) => return false,
+                Ok(_) => {}
+            }
+            index += 1;
         }
     }
 
-    /// Parse a logical AND expression
-    fn parse_logical_and_expression(&mut self) -> ParseResult<Node> {
-        let mut left = self.parse_equality_expression()?;
-        
-        while self.check(TokenKind::LogicalAnd) {
-            let operator = self.current_token_string();
-            self.advance(); // Consume operator
-            let right = Box::new(self.parse_equality_expression()?);
-            
-            let span = self.create_span_from_tokens();
-            left = Node::LogicalExpression(LogicalExpression {
-                left: Box::new(left),
-                operator,
-                right,
-                span: Some(span),
-            });
+    /// Parse `yield`, `yield expr` or `yield* expr` in a generator body
+    fn parse_yield_expression(&mut self) -> ParseResult<Node> {
+        let start = self.current_position();
+        self.advance(); // Consume 'yield'
+        let delegate = self.check(TokenKind::Star);
+        if delegate {
+            self.advance(); // Consume '*'
         }
+        // A bare `yield` ends where no operand can start
+        let ends = self.is_eof()
+            || [
+                TokenKind::Semicolon,
+                TokenKind::RightParen,
+                TokenKind::RightBracket,
+                TokenKind::RightBrace,
+                TokenKind::Comma,
+                TokenKind::Colon,
+            ]
+            .into_iter()
+            .any(|kind| self.check(kind));
+        let argument = if delegate || !ends {
+            Some(Box::new(self.parse_assignment_expression()?))
+        } else {
+            None
+        };
         
-        Ok(left)
+        let span = self.create_span(start, self.previous_position());
+        Ok(Node::YieldExpression(YieldExpression {
+            argument,
+            delegate,
+            span: Some(span),
+        }))
     }
 
-    /// Parse an equality expression
-    fn parse_equality_expression(&mut self) -> ParseResult<Node> {
-        let mut left = self.parse_relational_expression()?;
+    /// Parse a conditional (ternary) expression
+    fn parse_conditional_expression(&mut self) -> ParseResult<Node> {
+        let test = self.parse_binary_expression(1)?;
         
-        while self.is_equality_operator() {
-            let operator = self.current_token_string();
-            self.advance(); // Consume operator
-            let right = Box::new(self.parse_relational_expression()?);
-            
-            let span = self.create_span_from_tokens();
-            left = Node::BinaryExpression(BinaryExpression {
-                left: Box::new(left),
-                operator,
-                right,
-                span: Some(span),
-            });
+        if !self.check(TokenKind::Question) {
+            return Ok(test);
         }
+        self.advance(); // Consume '?'
+        let consequent = Box::new(self.parse_assignment_expression()?);
+        self.expect(TokenKind::Colon)?;
+        // The alternate is parsed at assignment level, which makes nested
+        // ternaries right-associative
+        let alternate = Box::new(self.parse_assignment_expression()?);
         
-        Ok(left)
+        let span = self.create_span_from_tokens();
+        Ok(Node::ConditionalExpression(ConditionalExpression {
+            test: Box::new(test),
+            consequent,
+            alternate,
+            span: Some(span),
+        }))
     }
-
-    /// Parse a relational expression
-    fn parse_relational_expression(&mut self) -> ParseResult<Node> {
-        let mut left = self.parse_shift_expression()?;
-        
-        while self.is_relational_operator() {
+    
+    /// Parse a binary expression whose operators bind at least as tightly
+    /// as `min_precedence`
+    ///
+    /// Precedence climbing over `TokenKind::precedence`: the operators below
+    /// `**` are left-associative, so a right operand only takes operators
+    /// that bind more tightly than its own. `&&`, `||` and `??` build
+    /// logical expressions.
+    fn parse_binary_expression(&mut self, min_precedence: u8) -> ParseResult<Node> {
+        self.parse_binary_chain(min_precedence).map(|(node, _)| node)
+    }
+
+    /// `parse_binary_expression`, also returning the logical operator at
+    /// the top of the result when it was not parenthesized
+    ///
+    /// `??` cannot be mixed with `&&` or `||` without parentheses, so
+    /// `a ?? b || c` and `a && b ?? c` are SyntaxErrors.
+    fn parse_binary_chain(&mut self, min_precedence: u8) -> ParseResult<(Node, Option<String>)> {
+        let start = self.current_position();
+        let mut left = self.parse_exponentiation_expression()?;
+        let mut left_logical: Option<String> = None;
+        
+        while let Some(precedence) = self.current_precedence().filter(|&precedence| precedence >= min_precedence) {
+            let logical = self.check(TokenKind::LogicalAnd)
+                || self.check(TokenKind::LogicalOr)
+                || self.check(TokenKind::NullishCoalescing);
+            let position = self.current_position().unwrap_or_default();
             let operator = self.current_token_string();
             self.advance(); // Consume operator
-            let right = Box::new(self.parse_shift_expression()?);
+            let (right, right_logical) = self.parse_binary_chain(precedence + 1)?;
+            let right = Box::new(right);
             
-            let span = self.create_span_from_tokens();
-            left = Node::BinaryExpression(BinaryExpression {
-                left: Box::new(left),
-                operator,
-                right,
-                span: Some(span),
-            });
+            let mixes = |other: &Option<String>| other.as_ref().is_some_and(|other| (other == "??") != (operator == "??"));
+            if logical && (mixes(&left_logical) || mixes(&right_logical)) {
+                return Err(ParseError::invalid_syntax(
+                    "Cannot mix '??' with '&&' or '||' without parentheses",
+                    position,
+                ));
+            }
+            
+            left_logical = logical.then(|| operator.clone());
+            let span = self.span_through(&left, &right, start);
+            left = if logical {
+                Node::LogicalExpression(LogicalExpression {
+                    left: Box::new(left),
+                    operator,
+                    right,
+                    span: Some(span),
+                })
+            } else {
+                Node::BinaryExpression(BinaryExpression {
+                    left: Box::new(left),
+                    operator,
+                    right,
+                    span: Some(span),
+                })
+            };
         }
         
-        Ok(left)
+        Ok((left, left_logical))
     }
 
-    /// Parse a shift expression
-    fn parse_shift_expression(&mut self) -> ParseResult<Node> {
-        let mut left = self.parse_additive_expression()?;
-        
-        while self.is_shift_operator() {
-            let operator = self.current_token_string();
-            self.advance(); // Consume operator
-            let right = Box::new(self.parse_additive_expression()?);
-            
-            let span = self.create_span_from_tokens();
-            left = Node::BinaryExpression(BinaryExpression {
-                left: Box::new(left),
-                operator,
-                right,
-                span: Some(span),
-            });
+    /// Precedence of the current token as a binary operator
+    fn current_precedence(&self) -> Option<u8> {
+        if self.check_keyword("in") && self.for_head == Some(self.openers.len()) {
+            return None;
         }
-        
-        Ok(left)
+        self.current.as_ref().and_then(|token| token.kind.precedence())
     }
 
-    /// Parse an additive expression
-    fn parse_additive_expression(&mut self) -> ParseResult<Node> {
-        let mut left = self.parse_multiplicative_expression()?;
+    /// Parse an exponentiation expression
+    ///
+    /// `**` is right-associative, and its left operand cannot be an
+    /// unparenthesized unary expression: `-2 ** 2` is a SyntaxError because
+    /// it is unclear whether the negation applies before or after.
+    fn parse_exponentiation_expression(&mut self) -> ParseResult<Node> {
+        let start = self.current_position();
+        let parenthesized = self.check(TokenKind::LeftParen);
+        let left = self.parse_unary_expression()?;
         
-        while self.is_additive_operator() {
-            let operator = self.current_token_string();
-            self.advance(); // Consume operator
-            let right = Box::new(self.parse_multiplicative_expression()?);
-            
-            let span = self.create_span_from_tokens();
-            left = Node::BinaryExpression(BinaryExpression {
-                left: Box::new(left),
-                operator,
-                right,
-                span: Some(span),
-            });
+        if !self.check(TokenKind::StarStar) {
+            return Ok(left);
         }
+        if !parenthesized && matches!(left, Node::UnaryExpression(_) | Node::AwaitExpression(_)) {
+            return Err(ParseError::invalid_syntax(
+                "Unary operator used immediately before exponentiation expression; parenthesize the left operand",
+                self.current_position().unwrap_or_default(),
+            ));
+        }
+        
+        let operator = self.current_token_string();
+        self.advance(); // Consume '**'
+        let right = Box::new(self.nested(Self::parse_exponentiation_expression)?);
         
-        Ok(left)
+        let span = self.span_through(&left, &right, start);
+        Ok(Node::BinaryExpression(BinaryExpression {
+            left: Box::new(left),
+            operator,
+            right,
+            span: Some(span),
+        }))
     }
 
-    /// Parse a multiplicative expression
-    fn parse_multiplicative_expression(&mut self) -> ParseResult<Node> {
-        let mut left = self.parse_unary_expression()?;
+    /// Parse a unary expression
+    fn parse_unary_expression(&mut self) -> ParseResult<Node> {
+        if self.in_async && self.check_keyword("await") {
+            let start = self.current_position();
+            self.advance(); // Consume 'await'
+            let argument = Box::new(self.nested(Self::parse_unary_expression)?);
+            let span = self.create_span(start, self.previous_position());
+            return Ok(Node::AwaitExpression(AwaitExpression {
+                argument,
+                span: Some(span),
+            }));
+        }
         
-        while self.is_multiplicative_operator() {
+        if self.check(TokenKind::Increment) || self.check(TokenKind::Decrement) {
             let operator = self.current_token_string();
             self.advance(); // Consume operator
-            let right = Box::new(self.parse_unary_expression()?);
+            let argument = Box::new(self.nested(Self::parse_unary_expression)?);
             
             let span = self.create_span_from_tokens();
-            left = Node::BinaryExpression(BinaryExpression {
-                left: Box::new(left),
+            return Ok(Node::UpdateExpression(UpdateExpression {
                 operator,
-                right,
+                argument,
+                prefix: true,
                 span: Some(span),
-            });
+            }));
         }
         
-        Ok(left)
-    }
-
-    /// Parse a unary expression
-    fn parse_unary_expression(&mut self) -> ParseResult<Node> {
         if self.is_unary_operator() {
             let operator = self.current_token_string();
             let prefix = true;
             self.advance(); // Consume operator
-            let argument = Box::new(self.parse_unary_expression()?);
+            let argument = Box::new(self.nested(Self::parse_unary_expression)?);
             
             let span = self.create_span_from_tokens();
             return Ok(Node::UnaryExpression(UnaryExpression {
@@ -940,69 +1437,58 @@ impl Parser {
 
     /// Parse a postfix expression
     fn parse_postfix_expression(&mut self) -> ParseResult<Node> {
-        let mut expr = self.parse_primary_expression()?;
+        let expr = self.parse_call_expression()?;
+        
+        if self.check(TokenKind::Increment) || self.check(TokenKind::Decrement) {
+            let operator = self.current_token_string();
+            let prefix = false;
+            self.advance(); // Consume operator
+            
+            let span = self.create_span_from_tokens();
+            return Ok(Node::UpdateExpression(UpdateExpression {
+                operator,
+                argument: Box::new(expr),
+                prefix,
+                span: Some(span),
+            }));
+        }
+        
+        Ok(expr)
+    }
+
+    /// Parse a call expression (member accesses and calls in any order)
+    fn parse_call_expression(&mut self) -> ParseResult<Node> {
+        let mut expr = self.parse_primary_expression()?;
         
         loop {
-            if let Some(token) = &self.current {
-                match &token.kind {
-                    TokenKind::LeftBracket => {
-                        self.advance(); // Consume '['
-                        let property = Box::new(self.parse_expression()?);
-                        self.expect(TokenKind::RightBracket)?;
-                        
-                        let span = self.create_span_from_tokens();
-                        expr = Node::MemberExpression(MemberExpression {
-                            object: Box::new(expr),
-                            property,
-                            computed: true,
-                            optional: false,
-                            span: Some(span),
-                        });
-                    }
-                    
-                    TokenKind::Dot => {
-                        self.advance(); // Consume '.'
-                        let property = Box::new(self.parse_identifier()?);
-                        
-                        let span = self.create_span_from_tokens();
-                        expr = Node::MemberExpression(MemberExpression {
-                            object: Box::new(expr),
-                            property,
-                            computed: false,
-                            optional: false,
-                            span: Some(span),
-                        });
-                    }
-                    
-                    TokenKind::LeftParen => {
-                        self.advance(); // Consume '('
-                        let arguments = self.parse_arguments()?;
-                        self.expect(TokenKind::RightParen)?;
-                        
-                        let span = self.create_span_from_tokens();
-                        expr = Node::CallExpression(CallExpression {
-                            callee: Box::new(expr),
-                            arguments,
-                            span: Some(span),
-                        });
-                    }
-                    
-                    TokenKind::Increment | TokenKind::Decrement => {
-                        let operator = self.current_token_string();
-                        let prefix = false;
-                        self.advance(); // Consume operator
-                        
-                        let span = self.create_span_from_tokens();
-                        expr = Node::UpdateExpression(UpdateExpression {
-                            operator,
-                            argument: Box::new(expr),
-                            prefix,
-                            span: Some(span),
-                        });
-                    }
-                    
-                    _ => break,
-                }
+            if self.check(TokenKind::LeftBracket)
+                || self.check(TokenKind::Dot)
+                || self.check(TokenKind::OptionalChaining)
+            {
+                expr = self.parse_member_access(expr)?;
+            } else if self.check(TokenKind::LeftParen) {
+                self.advance(); // Consume '('
+                let arguments = self.parse_arguments()?;
+                self.expect(TokenKind::RightParen)?;
+                
+                let span = self.create_span_from_tokens();
+                expr = Node::CallExpression(CallExpression {
+                    callee: Box::new(expr),
+                    arguments,
+                    span: Some(span),
+                });
+            } else if let Some(TokenKind::TemplateString(raw)) = self.current.as_ref().map(|t| &t.kind) {
+                // Tagged template: tag`...`
+                let raw = raw.clone();
+                self.advance();
+                let quasi = self.parse_template_literal(&raw)?;
+                
+                let span = self.create_span_from_tokens();
+                expr = Node::TaggedTemplateExpression(TaggedTemplateExpression {
+                    tag: Box::new(expr),
+                    quasi: Box::new(quasi),
+                    span: Some(span),
+                });
             } else {
                 break;
             }
@@ -1011,26 +1497,167 @@ impl Parser {
         Ok(expr)
     }
 
+    /// Build a TemplateLiteral from the raw text of the template token just
+    /// consumed
+    ///
+    /// Each substitution ends at the first `}` the lexer reads outside any
+    /// brackets, so braces in strings, comments and nested templates do not
+    /// count. It must hold exactly one expression, and is parsed where it
+    /// stands in the file so positions in errors are the file's.
+    fn parse_template_literal(&mut self, raw: &str) -> ParseResult<Node> {
+        // The text starts right after the opening backtick
+        let (mut line, mut column) = self.previous.as_ref().map_or((1, 1), |token| (token.start().line, token.start().column + 1));
+        let mut quasis = Vec::new();
+        let mut expressions = Vec::new();
+        let mut quasi = String::new();
+        let mut rest = raw;
+        
+        while let Some(c) = rest.chars().next() {
+            if c == '\\' && rest.len() > 1 {
+                let escape: String = rest.chars().take(2).collect();
+                quasi.push_str(&escape);
+                (line, column) = advance_position(line, column, &escape);
+                rest = &rest[escape.len()..];
+            } else if rest.starts_with("${") {
+                (line, column) = advance_position(line, column, "${");
+                let source = &rest[2..];
+                let end = self.substitution_end(source, Position { line, column })?;
+                expressions.push(self.parse_substitution(&source[..end], Position { line, column })?);
+                quasis.push(TemplateElement {
+                    value: cook_template_string(&quasi),
+                    raw: std::mem::take(&mut quasi),
+                    tail: false,
+                    span: None,
+                });
+                (line, column) = advance_position(line, column, &source[..=end]);
+                rest = &source[end + 1..];
+            } else {
+                quasi.push(c);
+                (line, column) = advance_position(line, column, &rest[..c.len_utf8()]);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        
+        quasis.push(TemplateElement {
+            value: cook_template_string(&quasi),
+            raw: quasi,
+            tail: true,
+            span: None,
+        });
+        
+        let span = self.create_span_from_tokens();
+        Ok(Node::TemplateLiteral(TemplateLiteral {
+            quasis,
+            expressions,
+            span: Some(span),
+        }))
+    }
+
+    /// Byte offset of the `}` closing the substitution `source` begins,
+    /// found by reading its tokens
+    fn substitution_end(&self, source: &str, start: Position) -> ParseResult<usize> {
+        let mut lexer = Lexer::new(source);
+        let mut depth = 0;
+        loop {
+            let token = lexer.next_token().map_err(|error| ParseError::lexer_error(&error.to_string(), start))?;
+            match token.kind {
+                TokenKind::LeftBrace => depth += 1,
+                TokenKind::RightBrace if depth == 0 => return Ok(token.start().offset),
+                TokenKind::RightBrace => depth -= 1,
+                TokenKind::Eof => {
+                    return Err(ParseError::invalid_syntax("Unterminated template substitution", start));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Parse the expression of a substitution starting at `start`
+    ///
+    /// Padding the text with the lines and columns before it makes the
+    /// nested parser report positions in the file.
+    fn parse_substitution(&self, source: &str, start: Position) -> ParseResult<Node> {
+        let mut text = "\n".repeat(start.line - 1);
+        text.extend(std::iter::repeat_n(' ', start.column - 1));
+        text.push_str(source);
+        let mut parser = Parser::new(&text);
+        parser.preserve_parens = self.preserve_parens;
+        parser.strict_mode = self.strict_mode;
+        parser.in_async = self.in_async;
+        parser.in_generator = self.in_generator;
+        parser.depth = self.depth;
+        parser.max_depth = self.max_depth;
+        let expression = parser.parse_expression()?;
+        match &parser.current {
+            Some(token) if token.kind != TokenKind::Eof => Err(ParseError::unexpected_token(token, Some("}"))),
+            _ => Ok(expression),
+        }
+    }
+
+    /// Parse a single `.name`, `[expr]`, `?.name` or `?.[expr]` access on the given object
+    fn parse_member_access(&mut self, object: Node) -> ParseResult<Node> {
+        let optional = self.check(TokenKind::OptionalChaining);
+        if optional {
+            self.advance(); // Consume '?.'
+        }
+        let computed = self.check(TokenKind::LeftBracket);
+        if computed || !optional {
+            self.advance(); // Consume '[' or '.'
+        }
+        
+        let property = if computed {
+            let property = self.parse_expression()?;
+            self.expect(TokenKind::RightBracket)?;
+            property
+        } else {
+            self.parse_property_name()?
+        };
+        
+        let span = self.create_span_from_tokens();
+        Ok(Node::MemberExpression(MemberExpression {
+            object: Box::new(object),
+            property: Box::new(property),
+            computed,
+            optional,
+            span: Some(span),
+        }))
+    }
+
     /// Parse a primary expression
     fn parse_primary_expression(&mut self) -> ParseResult<Node> {
         if let Some(token) = &self.current {
             match &token.kind {
                 TokenKind::Number(n) => {
                     let value = *n;
+                    let (start, end) = (token.start().offset, token.end().offset);
                     self.advance();
-                    Ok(Node::Number(value))
+                    // Keep the source text when printing the value would not reproduce it
+                    let raw = self.source.get(start..end).unwrap_or_default();
+                    if raw.is_empty() || raw == format_number(value) {
+                        Ok(Node::Number(value))
+                    } else {
+                        Ok(Node::NumericLiteral(NumericLiteral {
+                            value,
+                            raw: raw.to_string(),
+                            span: Some(self.create_span_from_tokens()),
+                        }))
+                    }
                 }
                 TokenKind::String(s) => {
                     let value = s.clone();
                     self.advance();
                     Ok(Node::String(value))
                 }
-                TokenKind::TemplateString(s) => {
-                    let value = s.clone();
+                TokenKind::TemplateString(raw) => {
+                    let raw = raw.clone();
                     self.advance();
-                    // For now, treat template strings as regular strings
-                    // TODO: Implement proper template literal parsing with expressions
-                    Ok(Node::String(value))
+                    // A template without substitutions is just a string
+                    match self.parse_template_literal(&raw)? {
+                        Node::TemplateLiteral(template) if template.expressions.is_empty() => {
+                            Ok(Node::String(cook_template_string(&raw)))
+                        }
+                        template => Ok(template),
+                    }
                 }
                 TokenKind::Boolean(b) => {
                     let value = *b;
@@ -1045,15 +1672,38 @@ impl Parser {
                     self.advance();
                     Ok(Node::Undefined)
                 }
+                TokenKind::Regex(pattern, flags) => {
+                    let regexp = RegExp {
+                        pattern: pattern.clone(),
+                        flags: flags.clone(),
+                        span: Some(self.create_span_from_tokens()),
+                    };
+                    self.advance();
+                    Ok(Node::RegExp(regexp))
+                }
                 TokenKind::Keyword(kw) if kw == "this" => {
+                    let start = self.current_position();
+                    self.advance();
+                    let span = self.create_span(start, self.previous_position());
+                    Ok(Node::This(ThisExpression { span: Some(span) }))
+                }
+                TokenKind::Keyword(kw) if kw == "super" => {
+                    let start = self.current_position();
                     self.advance();
-                    Ok(Node::This)
+                    let span = self.create_span(start, self.previous_position());
+                    Ok(Node::Super(Super { span: Some(span) }))
                 }
                 TokenKind::LeftParen => {
                     self.advance(); // Consume '('
                     let expr = self.parse_expression()?;
                     self.expect(TokenKind::RightParen)?;
-                    Ok(expr)
+                    if !self.preserve_parens {
+                        return Ok(expr);
+                    }
+                    Ok(Node::ParenthesizedExpression(ParenthesizedExpression {
+                        expression: Box::new(expr),
+                        span: Some(self.create_span_from_tokens()),
+                    }))
                 }
                 TokenKind::LeftBracket => {
                     self.parse_array_literal()
@@ -1062,7 +1712,7 @@ impl Parser {
                     self.parse_object_literal()
                 }
                 TokenKind::Keyword(kw) if kw == "function" => {
-                    self.parse_function_expression()
+                    self.parse_function_expression(false)
                 }
                 TokenKind::Keyword(kw) if kw == "class" => {
                     self.parse_class_expression()
@@ -1071,13 +1721,17 @@ impl Parser {
                     self.parse_new_expression()
                 }
                 TokenKind::Keyword(kw) if kw == "async" => {
-                    // Check if next token is function or =>
+                    // `async` only makes a function or an arrow async when it
+                    // is on the same line; otherwise it is a plain identifier
+                    // and `async\nx => x` is two statements
+                    let same_line = self.next_on_same_line();
                     self.advance(); // Consume 'async'
-                    if self.check(TokenKind::Keyword("function".to_string())) {
-                        self.parse_function_expression()
-                    } else {
-                        // Async arrow function
+                    if same_line && self.check(TokenKind::Keyword("function".to_string())) {
+                        self.parse_function_expression(true)
+                    } else if same_line && self.is_arrow_head() {
                         self.parse_arrow_function_expression(true)
+                    } else {
+                        Ok(Node::Identifier("async".to_string()))
                     }
                 }
                 _ => {
@@ -1107,10 +1761,17 @@ impl Parser {
                 elements.push(None); // Empty slot
                 self.advance(); // Consume comma
             } else {
-                elements.push(Some(self.parse_expression()?));
+                if self.check(TokenKind::Spread) {
+                    elements.push(Some(self.parse_spread_element()?));
+                    self.note_spread_comma();
+                } else {
+                    elements.push(Some(self.parse_assignment_expression()?));
+                }
                 
                 if self.check(TokenKind::Comma) {
                     self.advance(); // Consume comma
+                } else {
+                    break;
                 }
             }
         }
@@ -1124,6 +1785,26 @@ impl Parser {
         }))
     }
 
+    /// Remember the comma after a spread element, in case the literal turns
+    /// out to be an assignment pattern (see `to_assignment_pattern`)
+    fn note_spread_comma(&mut self) {
+        if self.check(TokenKind::Comma) {
+            let position = self.current_position().unwrap_or_default();
+            self.spread_commas.push(position);
+        }
+    }
+
+    /// Parse a spread element (`...expr`) in an array, call or object literal
+    fn parse_spread_element(&mut self) -> ParseResult<Node> {
+        self.advance(); // Consume '...'
+        let argument = Box::new(self.parse_assignment_expression()?);
+        let span = self.create_span_from_tokens();
+        Ok(Node::SpreadElement(SpreadElement {
+            argument,
+            span: Some(span),
+        }))
+    }
+
     /// Parse an object literal
     fn parse_object_literal(&mut self) -> ParseResult<Node> {
         self.advance(); // Consume '{'
@@ -1131,10 +1812,17 @@ impl Parser {
         let mut properties = Vec::new();
         
         while !self.check(TokenKind::RightBrace) && !self.is_eof() {
-            properties.push(self.parse_property()?);
+            if self.check(TokenKind::Spread) {
+                properties.push(self.parse_spread_element()?);
+                self.note_spread_comma();
+            } else {
+                properties.push(self.parse_property()?);
+            }
             
             if self.check(TokenKind::Comma) {
                 self.advance(); // Consume comma
+            } else {
+                break;
             }
         }
         
@@ -1149,24 +1837,41 @@ impl Parser {
 
     /// Parse a property
     fn parse_property(&mut self) -> ParseResult<Node> {
-        let key = if self.check_identifier() {
-            Box::new(self.parse_identifier()?)
-        } else if let Some(token) = &self.current {
-            if let TokenKind::String(_) = &token.kind {
-                Box::new(self.parse_primary_expression()?)
-            } else {
-                return Err(ParseError::invalid_syntax(
-                    "Expected identifier or string literal",
-                    self.current_position().unwrap_or_default(),
-                ));
+        // `get name() {}` and `set name(v) {}` define accessors, and
+        // `async name() {}` an async method
+        if self.check_keyword("get") || self.check_keyword("set") || self.check_keyword("async") {
+            let is_prefix = !(self.next_is(TokenKind::Colon)
+                || self.next_is(TokenKind::LeftParen)
+                || self.next_is(TokenKind::Comma)
+                || self.next_is(TokenKind::RightBrace));
+            if is_prefix {
+                return self.parse_method();
             }
+        }
+        // `*name() {}` and the shorthand `name() {}`
+        if self.check(TokenKind::Star) || self.next_is(TokenKind::LeftParen) {
+            return self.parse_method();
+        }
+        
+        // Any word can be a key, but a shorthand key is also the name read
+        let key_span = self.current_token_span();
+        let key = Box::new(self.parse_property_key()?);
+        
+        // `{a, b}` is short for `{a: a, b: b}`
+        let shorthand = matches!(key.as_ref(), Node::Identifier(_))
+            && (self.check(TokenKind::Comma) || self.check(TokenKind::RightBrace));
+        if let (true, Node::Identifier(word)) = (shorthand, key.as_ref()) {
+            if self.is_reserved_binding(word) {
+                return Err(ParseError::reserved_word_as_binding(word, key_span));
+            }
+        }
+        let value = if shorthand {
+            key.clone()
         } else {
-            return Err(ParseError::unexpected_end_of_input(None));
+            self.expect(TokenKind::Colon)?;
+            Box::new(self.parse_assignment_expression()?)
         };
         
-        self.expect(TokenKind::Colon)?;
-        let value = Box::new(self.parse_expression()?);
-        
         let span = self.create_span_from_tokens();
         Ok(Node::Property(Property {
             key,
@@ -1174,34 +1879,38 @@ impl Parser {
             kind: "init".to_string(),
             computed: false,
             method: false,
-            shorthand: false,
+            shorthand,
             span: Some(span),
         }))
     }
 
-    /// Parse a function expression
-    fn parse_function_expression(&mut self) -> ParseResult<Node> {
+    /// Parse a function expression, after any `async`
+    fn parse_function_expression(&mut self, is_async: bool) -> ParseResult<Node> {
         self.advance(); // Consume 'function'
+        let generator = self.check(TokenKind::Star);
+        if generator {
+            self.advance(); // Consume '*'
+        }
         
-        let id = if self.check_identifier() {
-            Some(Box::new(self.parse_identifier()?))
-        } else {
+        let id = if self.check(TokenKind::LeftParen) {
             None
+        } else {
+            Some(Box::new(self.parse_binding_identifier()?))
         };
         
         self.expect(TokenKind::LeftParen)?;
         let params = self.parse_parameters()?;
         self.expect(TokenKind::RightParen)?;
         
-        let body = Box::new(self.parse_function_body()?);
+        let body = Box::new(self.in_function(is_async, generator, Self::parse_function_body)?);
         
         let span = self.create_span_from_tokens();
         Ok(Node::FunctionExpression(FunctionExpression {
             id,
             params,
             body,
-            generator: false,
-            r#async: false,
+            generator,
+            r#async: is_async,
             span: Some(span),
         }))
     }
@@ -1210,10 +1919,11 @@ impl Parser {
     fn parse_class_expression(&mut self) -> ParseResult<Node> {
         self.advance(); // Consume 'class'
         
-        let id = if self.check_identifier() {
-            Some(Box::new(self.parse_identifier()?))
-        } else {
+        // A class is strict mode code, its name included
+        let id = if self.check(TokenKind::LeftBrace) || self.check_keyword("extends") {
             None
+        } else {
+            Some(Box::new(self.in_strict_mode(Self::parse_binding_identifier)?))
         };
         
         let super_class = if let Some(token) = &self.current {
@@ -1231,7 +1941,7 @@ impl Parser {
             None
         };
         
-        let body = Box::new(self.parse_class_body()?);
+        let body = Box::new(self.in_strict_mode(Self::parse_class_body)?);
         
         let span = self.create_span_from_tokens();
         Ok(Node::ClassExpression(ClassExpression {
@@ -1244,9 +1954,30 @@ impl Parser {
 
     /// Parse a new expression
     fn parse_new_expression(&mut self) -> ParseResult<Node> {
+        let start = self.current_position();
         self.advance(); // Consume 'new'
         
-        let callee = Box::new(self.parse_primary_expression()?);
+        if self.check(TokenKind::Dot) && self.next_is(TokenKind::Identifier("target".to_string())) {
+            self.advance(); // Consume '.'
+            self.advance(); // Consume 'target'
+            return Ok(Node::MetaProperty(MetaProperty {
+                meta: Box::new(Node::Identifier("new".to_string())),
+                property: Box::new(Node::Identifier("target".to_string())),
+                span: Some(self.create_span(start, self.previous_position())),
+            }));
+        }
+        
+        // The callee is a member expression without a trailing call, so
+        // `new a.b.C()` constructs `a.b.C` and `new new X()()` nests.
+        let mut callee = if self.check_keyword("new") {
+            self.parse_new_expression()?
+        } else {
+            self.parse_primary_expression()?
+        };
+        while self.check(TokenKind::LeftBracket) || self.check(TokenKind::Dot) {
+            callee = self.parse_member_access(callee)?;
+        }
+        let callee = Box::new(callee);
         
         let arguments = if self.check(TokenKind::LeftParen) {
             self.advance(); // Consume '('
@@ -1266,29 +1997,60 @@ impl Parser {
     }
 
     /// Parse function parameters
+    ///
+    /// Each parameter is an identifier or a destructuring pattern, with an
+    /// optional default; a final `...rest` collects the remaining arguments.
     fn parse_parameters(&mut self) -> ParseResult<Vec<Node>> {
         let mut params = Vec::new();
         
         while !self.check(TokenKind::RightParen) && !self.is_eof() {
-            params.push(self.parse_identifier()?);
+            if self.check(TokenKind::Spread) {
+                self.advance(); // Consume '...'
+                let argument = Box::new(self.parse_destructuring_pattern()?);
+                let span = self.create_span_from_tokens();
+                params.push(Node::RestElement(RestElement {
+                    argument,
+                    span: Some(span),
+                }));
+                // The rest parameter must be the last one, with no trailing comma
+                self.reject_after_rest("Rest parameter must be last formal parameter")?;
+                break;
+            }
+            params.push(self.parse_binding_element()?);
             
             if self.check(TokenKind::Comma) {
                 self.advance(); // Consume comma
+            } else {
+                break;
             }
         }
         
         Ok(params)
     }
 
+    /// Fail if a comma follows the rest element just parsed
+    fn reject_after_rest(&self, message: &str) -> ParseResult<()> {
+        if self.check(TokenKind::Comma) {
+            return Err(ParseError::invalid_syntax(message, self.current_position().unwrap_or_default()));
+        }
+        Ok(())
+    }
+
     /// Parse function arguments
     fn parse_arguments(&mut self) -> ParseResult<Vec<Node>> {
         let mut arguments = Vec::new();
         
         while !self.check(TokenKind::RightParen) && !self.is_eof() {
-            arguments.push(self.parse_expression()?);
+            if self.check(TokenKind::Spread) {
+                arguments.push(self.parse_spread_element()?);
+            } else {
+                arguments.push(self.parse_assignment_expression()?);
+            }
             
             if self.check(TokenKind::Comma) {
                 self.advance(); // Consume comma
+            } else {
+                break;
             }
         }
         
@@ -1297,7 +2059,70 @@ impl Parser {
 
     /// Parse function body
     fn parse_function_body(&mut self) -> ParseResult<Node> {
-        self.parse_block_statement()
+        self.next_brace = Some("function body");
+        let body = self.parse_block_statement();
+        self.next_brace = None;
+        body
+    }
+
+    /// Run `parse` one nesting level deeper
+    ///
+    /// Fails with `NestingTooDeep` once more than `max_depth` levels are open.
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> ParseResult<T>) -> ParseResult<T> {
+        if self.depth >= self.max_depth {
+            let position = self.current_position().unwrap_or_default();
+            return Err(ParseError::nesting_too_deep(self.max_depth, position));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Run `parse` for the body of a function of the given kind
+    ///
+    /// `await` and `yield` are operators only in the body of the function
+    /// they belong to, not in functions nested inside it. A `"use strict"`
+    /// directive in the body ends with it.
+    fn in_function<T>(
+        &mut self,
+        is_async: bool,
+        generator: bool,
+        parse: impl FnOnce(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<T> {
+        let saved = (self.in_async, self.in_generator, self.strict_mode);
+        self.in_async = is_async;
+        self.in_generator = generator;
+        let result = parse(self);
+        (self.in_async, self.in_generator, self.strict_mode) = saved;
+        result
+    }
+
+    /// Switch to strict mode on a `"use strict"` directive
+    ///
+    /// The directive prologue is the run of string literal statements that
+    /// starts a program or function body; `prologue` turns false at the
+    /// first other statement.
+    fn check_directive(&mut self, prologue: &mut bool, statement: &Node) {
+        if !*prologue {
+            return;
+        }
+        match statement {
+            Node::ExpressionStatement(stmt) => match stmt.expression.as_ref() {
+                Node::String(directive) if directive == "use strict" => self.strict_mode = true,
+                Node::String(_) => {}
+                _ => *prologue = false,
+            },
+            _ => *prologue = false,
+        }
+    }
+
+    /// Run `parse` as strict mode code
+    fn in_strict_mode<T>(&mut self, parse: impl FnOnce(&mut Self) -> ParseResult<T>) -> ParseResult<T> {
+        let saved = std::mem::replace(&mut self.strict_mode, true);
+        let result = parse(self);
+        self.strict_mode = saved;
+        result
     }
 
     /// Parse class body
@@ -1306,8 +2131,11 @@ impl Parser {
         
         let mut body = Vec::new();
         while !self.check(TokenKind::RightBrace) && !self.is_eof() {
-            // Simplified class body parsing
-            body.push(self.parse_statement()?);
+            if self.check(TokenKind::Semicolon) {
+                self.advance();
+                continue;
+            }
+            body.push(self.parse_method()?);
         }
         
         self.expect(TokenKind::RightBrace)?;
@@ -1319,6 +2147,78 @@ impl Parser {
         }))
     }
 
+    /// Parse a method, `name(params) { body }`, as a method property
+    ///
+    /// A leading `get` or `set` makes it a getter or setter instead, and
+    /// `async`, `*` or `async *` an async or generator method, unless the
+    /// word is the method's name (`get() {}`, `async() {}`).
+    fn parse_method(&mut self) -> ParseResult<Node> {
+        let accessor = self.check_keyword("get") || self.check_keyword("set");
+        let kind = if accessor && !self.next_is(TokenKind::LeftParen) {
+            let kind = if self.check_keyword("get") { "get" } else { "set" };
+            self.advance(); // Consume 'get' or 'set'
+            kind.to_string()
+        } else {
+            "init".to_string()
+        };
+        let is_async = kind == "init" && self.check_keyword("async") && !self.next_is(TokenKind::LeftParen);
+        if is_async {
+            self.advance(); // Consume 'async'
+        }
+        let generator = kind == "init" && self.check(TokenKind::Star);
+        if generator {
+            self.advance(); // Consume '*'
+        }
+        let key = Box::new(self.parse_property_key()?);
+        
+        self.expect(TokenKind::LeftParen)?;
+        let params = self.parse_parameters()?;
+        self.expect(TokenKind::RightParen)?;
+        
+        let body = Box::new(self.in_function(is_async, generator, Self::parse_function_body)?);
+        
+        let span = self.create_span_from_tokens();
+        let value = Box::new(Node::FunctionExpression(FunctionExpression {
+            id: None,
+            params,
+            body,
+            generator,
+            r#async: is_async,
+            span: Some(span.clone()),
+        }));
+        Ok(Node::Property(Property {
+            key,
+            value,
+            method: kind == "init",
+            kind,
+            computed: false,
+            shorthand: false,
+            span: Some(span),
+        }))
+    }
+
+    /// Parse the name after `.`, where reserved words are allowed (`p.catch`)
+    fn parse_property_name(&mut self) -> ParseResult<Node> {
+        let name = match self.current.as_ref().map(|token| &token.kind) {
+            Some(TokenKind::Keyword(name)) => name.clone(),
+            Some(TokenKind::Boolean(value)) => value.to_string(),
+            Some(TokenKind::Null) => "null".to_string(),
+            Some(TokenKind::Undefined) => "undefined".to_string(),
+            _ => return self.parse_identifier(),
+        };
+        self.advance();
+        Ok(Node::Identifier(name))
+    }
+
+    /// Parse the key of an object literal property or method: a name, where
+    /// reserved words are allowed (`{get: 1}`), or a string or number literal
+    fn parse_property_key(&mut self) -> ParseResult<Node> {
+        match self.current.as_ref().map(|token| &token.kind) {
+            Some(TokenKind::String(_)) | Some(TokenKind::Number(_)) => self.parse_primary_expression(),
+            _ => self.parse_property_name(),
+        }
+    }
+
     /// Parse an identifier
     fn parse_identifier(&mut self) -> ParseResult<Node> {
         if let Some(token) = &self.current {
@@ -1337,6 +2237,48 @@ impl Parser {
         }
     }
 
+    /// Parse the name a declaration, parameter or binding pattern binds
+    ///
+    /// Reserved words fail with `ReservedWordAsBinding`. The other words the
+    /// lexer reads as keywords (`of`, `get`, `async`...) are names here.
+    fn parse_binding_identifier(&mut self) -> ParseResult<Node> {
+        let word = match self.current.as_ref().map(|token| &token.kind) {
+            Some(TokenKind::Keyword(word)) => word.clone(),
+            Some(TokenKind::Boolean(value)) => value.to_string(),
+            Some(TokenKind::Null) => "null".to_string(),
+            _ => return self.parse_identifier(),
+        };
+        if self.is_reserved_binding(&word) {
+            return Err(ParseError::reserved_word_as_binding(&word, self.current_token_span()));
+        }
+        self.advance();
+        Ok(Node::Identifier(word))
+    }
+
+    /// Whether `word` may not be bound here
+    ///
+    /// Some words are reserved only in strict mode code, and `yield` and
+    /// `await` also inside generators and async functions.
+    fn is_reserved_binding(&self, word: &str) -> bool {
+        match word {
+            "yield" => self.strict_mode || self.in_generator,
+            "await" => self.in_async,
+            "let" | "static" | "implements" | "interface" | "package" | "private" | "protected" | "public" => {
+                self.strict_mode
+            }
+            _ => RESERVED_WORDS.contains(&word),
+        }
+    }
+
+    /// Span of the current token
+    fn current_token_span(&self) -> Span {
+        let end = self.current.as_ref().map(|token| Position {
+            line: token.end().line,
+            column: token.end().column,
+        });
+        self.create_span(self.current_position(), end)
+    }
+
     /// Get the current token
     fn current_token(&self) -> Option<&Token> {
         self.current.as_ref()
@@ -1345,61 +2287,10 @@ impl Parser {
     /// Get the current token as a string
     fn current_token_string(&self) -> String {
         if let Some(token) = &self.current {
+            if let Some(text) = token.kind.operator_str() {
+                return text.to_string();
+            }
             match &token.kind {
-                TokenKind::Plus => "+".to_string(),
-                TokenKind::Minus => "-".to_string(),
-                TokenKind::Star => "*".to_string(),
-                TokenKind::Slash => "/".to_string(),
-                TokenKind::Percent => "%".to_string(),
-                TokenKind::StarStar => "**".to_string(),
-                TokenKind::Equal => "==".to_string(),
-                TokenKind::NotEqual => "!=".to_string(),
-                TokenKind::StrictEqual => "===".to_string(),
-                TokenKind::StrictNotEqual => "!==".to_string(),
-                TokenKind::LessThan => "<".to_string(),
-                TokenKind::GreaterThan => ">".to_string(),
-                TokenKind::LessThanEqual => "<=".to_string(),
-                TokenKind::GreaterThanEqual => ">=".to_string(),
-                TokenKind::LeftShift => "<<".to_string(),
-                TokenKind::RightShift => ">>".to_string(),
-                TokenKind::UnsignedRightShift => ">>>".to_string(),
-                TokenKind::Assign => "=".to_string(),
-                TokenKind::PlusAssign => "+=".to_string(),
-                TokenKind::MinusAssign => "-=".to_string(),
-                TokenKind::StarAssign => "*=".to_string(),
-                TokenKind::SlashAssign => "/=".to_string(),
-                TokenKind::PercentAssign => "%=".to_string(),
-                TokenKind::StarStarAssign => "**=".to_string(),
-                TokenKind::LeftShiftAssign => "<<=".to_string(),
-                TokenKind::RightShiftAssign => ">>=".to_string(),
-                TokenKind::UnsignedRightShiftAssign => ">>>=".to_string(),
-                TokenKind::BitwiseAndAssign => "&=".to_string(),
-                TokenKind::BitwiseOrAssign => "|=".to_string(),
-                TokenKind::BitwiseXorAssign => "^=".to_string(),
-                TokenKind::LogicalAnd => "&&".to_string(),
-                TokenKind::LogicalOr => "||".to_string(),
-                TokenKind::Exclamation => "!".to_string(),
-                TokenKind::BitwiseAnd => "&".to_string(),
-                TokenKind::BitwiseOr => "|".to_string(),
-                TokenKind::BitwiseXor => "^".to_string(),
-                TokenKind::Tilde => "~".to_string(),
-                TokenKind::Increment => "++".to_string(),
-                TokenKind::Decrement => "--".to_string(),
-                TokenKind::Question => "?".to_string(),
-                TokenKind::Colon => ":".to_string(),
-                TokenKind::Comma => ",".to_string(),
-                TokenKind::Semicolon => ";".to_string(),
-                TokenKind::Dot => ".".to_string(),
-                TokenKind::LeftParen => "(".to_string(),
-                TokenKind::RightParen => ")".to_string(),
-                TokenKind::LeftBracket => "[".to_string(),
-                TokenKind::RightBracket => "]".to_string(),
-                TokenKind::LeftBrace => "{".to_string(),
-                TokenKind::RightBrace => "}".to_string(),
-                TokenKind::Arrow => "=>".to_string(),
-                TokenKind::Spread => "...".to_string(),
-                TokenKind::NullishCoalescing => "??".to_string(),
-                TokenKind::OptionalChaining => "?.".to_string(),
                 TokenKind::Identifier(id) => id.clone(),
                 TokenKind::String(s) => s.clone(),
                 TokenKind::Number(n) => n.to_string(),
@@ -1422,6 +2313,14 @@ impl Parser {
         }
     }
 
+    /// Check if the current token is the given keyword
+    fn check_keyword(&self, keyword: &str) -> bool {
+        matches!(
+            self.current.as_ref().map(|t| &t.kind),
+            Some(TokenKind::Keyword(kw)) if kw == keyword
+        )
+    }
+
     /// Check if the current token is an identifier
     fn check_identifier(&self) -> bool {
         self.current_token()
@@ -1429,15 +2328,25 @@ impl Parser {
             .unwrap_or(false)
     }
 
+    /// Check whether the token after the current one is of the given kind
+    fn next_is(&mut self, token_kind: TokenKind) -> bool {
+        matches!(self.lexer.peek(0), Ok(token) if token.kind == token_kind)
+    }
+
+    /// Check whether the token after the current one starts on the line the current one ends on
+    fn next_on_same_line(&mut self) -> bool {
+        let line = self.current.as_ref().map(|token| token.end().line);
+        matches!(self.lexer.peek(0), Ok(token) if Some(token.start().line) == line)
+    }
+
     /// Expect a specific token kind
     fn expect(&mut self, token_kind: TokenKind) -> ParseResult<()> {
         if self.check(token_kind.clone()) {
             self.advance();
             Ok(())
+        } else if self.is_eof() {
+            Err(self.end_of_input_error(&format!("{:?}", token_kind)))
         } else {
-            let current = self.current_token()
-                .map(|t| format!("{:?}", t.kind))
-                .unwrap_or_else(|| "EOF".to_string());
             Err(ParseError::unexpected_token(
                 self.current_token().unwrap_or_else(|| panic!("No current token")),
                 Some(&format!("{:?}", token_kind)),
@@ -1445,12 +2354,58 @@ impl Parser {
         }
     }
 
+    /// Error for input ending while `expected` was required
+    ///
+    /// Reports the innermost unclosed delimiter, if any, since that is
+    /// usually where the mistake is.
+    fn end_of_input_error(&self, expected: &str) -> ParseError {
+        let position = self.current_position()
+            .or_else(|| self.previous_position())
+            .unwrap_or_default();
+        match self.openers.last() {
+            Some(opener) => ParseError::unterminated(opener.construct, opener.position, position),
+            None => ParseError::UnexpectedEndOfInput {
+                position,
+                expected: Some(expected.to_string()),
+            },
+        }
+    }
+
+    /// Record an opening delimiter, or close the one matching a closing delimiter
+    fn track_delimiter(&mut self) {
+        let Some(token) = &self.current else { return };
+        let position = Position {
+            line: token.start().line,
+            column: token.start().column,
+        };
+        let (closer, construct) = match &token.kind {
+            TokenKind::LeftBrace => (TokenKind::RightBrace, self.next_brace.take().unwrap_or("block")),
+            TokenKind::LeftParen => (TokenKind::RightParen, "parenthesis"),
+            TokenKind::LeftBracket => (TokenKind::RightBracket, "bracket"),
+            kind @ (TokenKind::RightBrace | TokenKind::RightParen | TokenKind::RightBracket) => {
+                // Openers skipped by error recovery are dropped with their closer
+                if let Some(index) = self.openers.iter().rposition(|opener| opener.closer == *kind) {
+                    self.openers.truncate(index);
+                }
+                return;
+            }
+            _ => return,
+        };
+        self.openers.push(Opener { closer, construct, position });
+    }
+
     /// Advance to the next token
     fn advance(&mut self) {
+        self.track_delimiter();
         self.previous = self.current.take();
         self.current = match self.lexer.next_token() {
             Ok(token) => Some(token),
-            Err(_) => None,
+            Err(error) => {
+                // The stream ends here: `current` stays `None` from now on
+                let position = self.previous_position().unwrap_or_default();
+                self.lexer_error.get_or_insert((error, position));
+                None
+            }
         };
     }
 
@@ -1479,6 +2434,14 @@ impl Parser {
             })
     }
 
+    /// Span of a composite node, from its first child through its last
+    ///
+    /// `start` is where the first child began; the token range from there
+    /// is used when the children have no spans of their own.
+    fn span_through(&self, first: &Node, last: &Node, start: Option<Position>) -> Span {
+        Node::span_through(first, last).unwrap_or_else(|| self.create_span(start, self.previous_position()))
+    }
+
     /// Create a span from the current tokens
     fn create_span_from_tokens(&self) -> Span {
         let start = self.previous_position().unwrap_or_default();
@@ -1504,73 +2467,6 @@ impl Parser {
         }
     }
 
-    /// Check if the current token is an assignment operator
-    fn is_assignment_operator(&self) -> bool {
-        if let Some(token) = &self.current {
-            matches!(token.kind,
-                TokenKind::Assign | TokenKind::PlusAssign | TokenKind::MinusAssign |
-                TokenKind::StarAssign | TokenKind::SlashAssign | TokenKind::PercentAssign |
-                TokenKind::StarStarAssign | TokenKind::LeftShiftAssign | TokenKind::RightShiftAssign |
-                TokenKind::UnsignedRightShiftAssign | TokenKind::BitwiseAndAssign |
-                TokenKind::BitwiseOrAssign | TokenKind::BitwiseXorAssign
-            )
-        } else {
-            false
-        }
-    }
-
-    /// Check if the current token is an equality operator
-    fn is_equality_operator(&self) -> bool {
-        if let Some(token) = &self.current {
-            matches!(token.kind,
-                TokenKind::Equal | TokenKind::NotEqual | TokenKind::StrictEqual | TokenKind::StrictNotEqual
-            )
-        } else {
-            false
-        }
-    }
-
-    /// Check if the current token is a relational operator
-    fn is_relational_operator(&self) -> bool {
-        if let Some(token) = &self.current {
-            matches!(token.kind,
-                TokenKind::LessThan | TokenKind::GreaterThan | TokenKind::LessThanEqual |
-                TokenKind::GreaterThanEqual
-            ) || matches!(token.kind, TokenKind::Keyword(ref kw) if kw == "instanceof" || kw == "in")
-        } else {
-            false
-        }
-    }
-
-    /// Check if the current token is a shift operator
-    fn is_shift_operator(&self) -> bool {
-        if let Some(token) = &self.current {
-            matches!(token.kind,
-                TokenKind::LeftShift | TokenKind::RightShift | TokenKind::UnsignedRightShift
-            )
-        } else {
-            false
-        }
-    }
-
-    /// Check if the current token is an additive operator
-    fn is_additive_operator(&self) -> bool {
-        if let Some(token) = &self.current {
-            matches!(token.kind, TokenKind::Plus | TokenKind::Minus)
-        } else {
-            false
-        }
-    }
-
-    /// Check if the current token is a multiplicative operator
-    fn is_multiplicative_operator(&self) -> bool {
-        if let Some(token) = &self.current {
-            matches!(token.kind, TokenKind::Star | TokenKind::Slash | TokenKind::Percent)
-        } else {
-            false
-        }
-    }
-
     /// Check if the current token is a unary operator
     fn is_unary_operator(&self) -> bool {
         if let Some(token) = &self.current {
@@ -1714,27 +2610,35 @@ impl Parser {
                 params = self.parse_parameters()?;
             }
             self.expect(TokenKind::RightParen)?;
+        } else if self.check_keyword("async") {
+            // `async => async` has a parameter named async
+            self.advance();
+            params.push(Node::Identifier("async".to_string()));
         } else {
             // Single parameter without parentheses
-            params.push(self.parse_identifier()?);
+            params.push(self.parse_binding_identifier()?);
         }
         
         self.expect(TokenKind::Arrow)?;
         
-        // Parse body
-        let body = if self.check(TokenKind::LeftBrace) {
-            // Block body
-            Box::new(self.parse_function_body()?)
-        } else {
-            // Expression body
-            Box::new(self.parse_expression()?)
-        };
+        // A `{` always opens a block body, so `() => { a: 1 }` is a block
+        // with a labeled statement; an object literal body needs
+        // parentheses: `() => ({ a: 1 })`
+        let expression = !self.check(TokenKind::LeftBrace);
+        let body = self.in_function(is_async, false, |parser| {
+            if expression {
+                parser.parse_assignment_expression()
+            } else {
+                parser.parse_function_body()
+            }
+        })?;
+        let body = Box::new(body);
         
         let span = self.create_span_from_tokens();
         Ok(Node::ArrowFunctionExpression(ArrowFunctionExpression {
             params,
             body,
-            expression: !self.check(TokenKind::LeftBrace),
+            expression,
             r#async: is_async,
             span: Some(span),
         }))
@@ -1748,34 +2652,43 @@ impl Parser {
             let mut properties = Vec::new();
             
             while !self.check(TokenKind::RightBrace) && !self.is_eof() {
-                if self.check_identifier() {
-                    let key = self.parse_identifier()?;
-                    let value = if self.check(TokenKind::Colon) {
-                        self.advance(); // Consume ':'
-                        Some(Box::new(self.parse_expression()?))
+                if self.check(TokenKind::Spread) {
+                    self.advance(); // Consume '...'
+                    let argument = Box::new(self.parse_binding_identifier()?);
+                    let span = self.create_span_from_tokens();
+                    properties.push(Node::RestElement(RestElement {
+                        argument,
+                        span: Some(span),
+                    }));
+                    self.reject_after_rest("Rest element must be last element")?;
+                } else {
+                    // Any word can be a key, but a shorthand key is also the name bound
+                    let key_span = self.current_token_span();
+                    let key = self.parse_property_name()?;
+                    let is_shorthand = !self.check(TokenKind::Colon);
+                    let value = if is_shorthand {
+                        if let Node::Identifier(word) = &key {
+                            if self.is_reserved_binding(word) {
+                                return Err(ParseError::reserved_word_as_binding(word, key_span));
+                            }
+                        }
+                        // `{a}` binds `a`, `{a = 1}` binds `a` with a default
+                        self.parse_binding_default(key.clone())?
                     } else {
-                        None
+                        self.advance(); // Consume ':'
+                        self.parse_binding_element()?
                     };
                     
                     let span = self.create_span_from_tokens();
-                    let is_shorthand = value.is_none();
                     properties.push(Node::Property(Property {
                         key: Box::new(key),
-                        value: value.unwrap_or_else(|| Box::new(Node::Identifier("".to_string()))),
+                        value: Box::new(value),
                         kind: "init".to_string(),
                         computed: false,
                         method: false,
                         shorthand: is_shorthand,
                         span: Some(span),
                     }));
-                } else if self.check(TokenKind::Spread) {
-                    self.advance(); // Consume '...'
-                    let argument = Box::new(self.parse_expression()?);
-                    let span = self.create_span_from_tokens();
-                    properties.push(Node::SpreadElement(SpreadElement {
-                        argument,
-                        span: Some(span),
-                    }));
                 }
                 
                 if self.check(TokenKind::Comma) {
@@ -1788,7 +2701,7 @@ impl Parser {
             self.expect(TokenKind::RightBrace)?;
             
             let span = self.create_span_from_tokens();
-            Ok(Node::ObjectLiteral(ObjectLiteral {
+            Ok(Node::ObjectPattern(ObjectPattern {
                 properties,
                 span: Some(span),
             }))
@@ -1801,16 +2714,18 @@ impl Parser {
                 if self.check(TokenKind::Comma) {
                     elements.push(None);
                     self.advance();
+                    continue;
                 } else if self.check(TokenKind::Spread) {
                     self.advance(); // Consume '...'
-                    let argument = Box::new(self.parse_expression()?);
+                    let argument = Box::new(self.parse_destructuring_pattern()?);
                     let span = self.create_span_from_tokens();
-                    elements.push(Some(Node::SpreadElement(SpreadElement {
+                    elements.push(Some(Node::RestElement(RestElement {
                         argument,
                         span: Some(span),
                     })));
+                    self.reject_after_rest("Rest element must be last element")?;
                 } else {
-                    elements.push(Some(self.parse_expression()?));
+                    elements.push(Some(self.parse_binding_element()?));
                 }
                 
                 if self.check(TokenKind::Comma) {
@@ -1823,13 +2738,151 @@ impl Parser {
             self.expect(TokenKind::RightBracket)?;
             
             let span = self.create_span_from_tokens();
-            Ok(Node::ArrayLiteral(ArrayLiteral {
+            Ok(Node::ArrayPattern(ArrayPattern {
                 elements,
                 span: Some(span),
             }))
         } else {
             // Single identifier
-            self.parse_identifier()
+            self.parse_binding_identifier()
         }
     }
-}
\ No newline at end of file
+
+    /// Parse a binding target (identifier or nested pattern) with an optional default
+    fn parse_binding_element(&mut self) -> ParseResult<Node> {
+        let target = self.parse_destructuring_pattern()?;
+        self.parse_binding_default(target)
+    }
+
+    /// Wrap a binding target in an AssignmentPattern if followed by `= default`
+    fn parse_binding_default(&mut self, target: Node) -> ParseResult<Node> {
+        if !self.check(TokenKind::Assign) {
+            return Ok(target);
+        }
+        self.advance(); // Consume '='
+        let right = Box::new(self.parse_assignment_expression()?);
+        let span = self.create_span_from_tokens();
+        Ok(Node::AssignmentPattern(AssignmentPattern {
+            left: Box::new(target),
+            right,
+            span: Some(span),
+        }))
+    }
+}
+
+/// Reinterpret an array or object literal on the left of `=` as a pattern
+///
+/// Spread elements become rest elements, which must come last with no
+/// trailing comma (`spread_commas`), and `target = default` elements become
+/// assignment patterns. Identifiers and member expressions are assigned as
+/// they are.
+fn to_assignment_pattern(node: Node, position: Position, spread_commas: &[Position]) -> ParseResult<Node> {
+    let rest_last = ParseError::invalid_syntax("Rest element must be last element", position);
+    let has_comma = |span: &Option<Span>| span.as_ref().is_some_and(|span| spread_commas.contains(&span.end));
+    let invalid = || ParseError::invalid_syntax("Invalid destructuring assignment target", position);
+    match node {
+        Node::Identifier(_) | Node::MemberExpression(_) => Ok(node),
+        Node::ParenthesizedExpression(paren)
+            if matches!(*paren.expression, Node::Identifier(_) | Node::MemberExpression(_)) =>
+        {
+            Ok(*paren.expression)
+        }
+        Node::AssignmentExpression(assign) if assign.operator == "=" => Ok(Node::AssignmentPattern(AssignmentPattern {
+            left: Box::new(to_assignment_pattern(*assign.left, position, spread_commas)?),
+            right: assign.right,
+            span: assign.span,
+        })),
+        Node::ArrayLiteral(array) => {
+            let count = array.elements.len();
+            let mut elements = Vec::with_capacity(count);
+            for (index, element) in array.elements.into_iter().enumerate() {
+                let element = match element {
+                    Some(Node::SpreadElement(spread)) if index + 1 == count && !has_comma(&spread.span) => Some(Node::RestElement(RestElement {
+                        argument: Box::new(to_assignment_pattern(*spread.argument, position, spread_commas)?),
+                        span: spread.span,
+                    })),
+                    Some(Node::SpreadElement(_)) => return Err(rest_last),
+                    Some(element) => Some(to_assignment_pattern(element, position, spread_commas)?),
+                    None => None,
+                };
+                elements.push(element);
+            }
+            Ok(Node::ArrayPattern(ArrayPattern { elements, span: array.span }))
+        }
+        Node::ObjectLiteral(object) => {
+            let count = object.properties.len();
+            let mut properties = Vec::with_capacity(count);
+            for (index, property) in object.properties.into_iter().enumerate() {
+                let property = match property {
+                    Node::SpreadElement(spread) if index + 1 == count && !has_comma(&spread.span) => match *spread.argument {
+                        argument @ (Node::Identifier(_) | Node::MemberExpression(_)) => Node::RestElement(RestElement {
+                            argument: Box::new(argument),
+                            span: spread.span,
+                        }),
+                        _ => return Err(invalid()),
+                    },
+                    Node::SpreadElement(_) => return Err(rest_last),
+                    Node::Property(prop) if prop.kind == "init" && !prop.method => Node::Property(Property {
+                        value: Box::new(to_assignment_pattern(*prop.value, position, spread_commas)?),
+                        ..prop
+                    }),
+                    _ => return Err(invalid()),
+                };
+                properties.push(property);
+            }
+            Ok(Node::ObjectPattern(ObjectPattern { properties, span: object.span }))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Line and column after reading `text` from `line` and `column`
+fn advance_position(line: usize, column: usize, text: &str) -> (usize, usize) {
+    text.chars().fold((line, column), |(line, column), c| if c == '\n' { (line + 1, 1) } else { (line, column + 1) })
+}
+
+/// Process the escape sequences in the raw text of a template
+fn cook_template_string(raw: &str) -> String {
+    let mut cooked = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            cooked.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => cooked.push('\n'),
+            Some('t') => cooked.push('\t'),
+            Some('r') => cooked.push('\r'),
+            Some('b') => cooked.push('\u{8}'),
+            Some('f') => cooked.push('\u{c}'),
+            Some('v') => cooked.push('\u{b}'),
+            Some('0') => cooked.push('\0'),
+            Some('\n') => {} // Line continuation
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(ch) => cooked.push(ch),
+                    None => cooked.push_str(&hex),
+                }
+            }
+            Some('u') => {
+                let hex: String = if chars.peek() == Some(&'{') {
+                    chars.next();
+                    chars.by_ref().take_while(|&ch| ch != '}').collect()
+                } else {
+                    chars.by_ref().take(4).collect()
+                };
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(ch) => cooked.push(ch),
+                    None => cooked.push_str(&hex),
+                }
+            }
+            Some(other) => cooked.push(other),
+            None => cooked.push('\\'),
+        }
+    }
+    
+    cooked
+}
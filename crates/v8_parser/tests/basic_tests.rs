@@ -1,5 +1,6 @@
-use v8_parser::Parser;
+use v8_parser::{ParseError, Parser};
 use v8_ast::Node;
+use v8_lexer::LexerError;
 
 #[test]
 fn test_empty_program() {
@@ -37,6 +38,50 @@ fn test_simple_number() {
     }
 }
 
+#[test]
+fn test_number_keeps_source_text() {
+    for source in ["0xFF", "1_000", "1e3", "0b1010"] {
+        let ast = Parser::new(source).parse().unwrap();
+        assert_eq!(v8_ast::codegen::generate(&ast), format!("{};\n", source));
+    }
+
+    // Numbers already in canonical form stay plain `Number` nodes
+    let Ok(Node::Program(program)) = Parser::new("0xFF; 255").parse() else { panic!("Expected Program") };
+    let expressions: Vec<&Node> = program
+        .body
+        .iter()
+        .map(|stmt| match stmt {
+            Node::ExpressionStatement(stmt) => stmt.expression.as_ref(),
+            other => panic!("Expected ExpressionStatement, got {:?}", other),
+        })
+        .collect();
+    assert!(matches!(expressions[0], Node::NumericLiteral(lit) if lit.value == 255.0 && lit.raw == "0xFF"));
+    assert_eq!(*expressions[1], Node::Number(255.0));
+}
+
+#[test]
+fn test_preserve_parens() {
+    let source = "(1 + 2) * (a);";
+    let mut parser = Parser::new(source);
+    parser.set_preserve_parens(true);
+    let Ok(Node::Program(program)) = parser.parse() else { panic!("Expected Program") };
+    let Node::ExpressionStatement(stmt) = &program.body[0] else { panic!("Expected ExpressionStatement") };
+    let Node::BinaryExpression(product) = stmt.expression.as_ref() else { panic!("Expected BinaryExpression") };
+    match product.left.as_ref() {
+        Node::ParenthesizedExpression(paren) => assert!(matches!(*paren.expression, Node::BinaryExpression(_))),
+        other => panic!("Expected ParenthesizedExpression, got {:?}", other),
+    }
+    assert!(matches!(product.right.as_ref(), Node::ParenthesizedExpression(_)));
+    assert_eq!(v8_ast::codegen::generate(&Node::Program(program)), "(1 + 2) * (a);\n");
+
+    // Off by default: parentheses only group
+    let Ok(Node::Program(program)) = Parser::new(source).parse() else { panic!("Expected Program") };
+    let Node::ExpressionStatement(stmt) = &program.body[0] else { panic!("Expected ExpressionStatement") };
+    let Node::BinaryExpression(product) = stmt.expression.as_ref() else { panic!("Expected BinaryExpression") };
+    assert!(matches!(product.left.as_ref(), Node::BinaryExpression(_)));
+    assert_eq!(*product.right, Node::Identifier("a".to_string()));
+}
+
 #[test]
 fn test_simple_string() {
     let mut parser = Parser::new("\"hello\"");
@@ -155,4 +200,110 @@ fn test_binary_expression() {
     } else {
         panic!("Expected Program node");
     }
-} 
\ No newline at end of file
+} 
+#[test]
+fn test_unterminated_block() {
+    let mut parser = Parser::new("let a = 1;\nif (a) {\n  a = 2;\n");
+    let (_, errors) = parser.parse_with_recovery();
+    match errors.first() {
+        Some(ParseError::Unterminated { construct, opened_at, .. }) => {
+            assert_eq!(construct, "block");
+            assert_eq!((opened_at.line, opened_at.column), (2, 8));
+        }
+        other => panic!("Expected unterminated block, got {:?}", other),
+    }
+    assert!(errors[0].to_string().contains("opened at line 2, column 8"));
+}
+
+#[test]
+fn test_unterminated_block_keeps_its_statements() {
+    let mut parser = Parser::new("{ let a = 1; let b = 2");
+    let (ast, errors) = parser.parse_with_recovery();
+    let Some(Node::Program(program)) = ast else { panic!("Expected a partial Program") };
+    let Node::BlockStatement(block) = &program.body[0] else { panic!("Expected BlockStatement") };
+    assert_eq!(block.body.len(), 2);
+    assert!(block.body.iter().all(|stmt| matches!(stmt, Node::VariableDeclaration(_))));
+    assert_eq!(errors.len(), 1);
+    match &errors[0] {
+        ParseError::Unterminated { construct, opened_at, .. } => {
+            assert_eq!(construct, "block");
+            assert_eq!((opened_at.line, opened_at.column), (1, 1));
+        }
+        other => panic!("Expected unterminated block, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_lexer_error_fails_the_parse() {
+    // The statements before the bad token parse fine on their own
+    let mut parser = Parser::new("let a = 1;\nf(a); 'abc");
+    let (_, errors) = parser.parse_with_recovery();
+    assert_eq!(parser.lexer_error(), Some(&LexerError::UnterminatedString));
+    match errors.last() {
+        Some(ParseError::LexerError { message, position }) => {
+            assert_eq!(message, "Unterminated string");
+            assert_eq!((position.line, position.column), (2, 6));
+        }
+        other => panic!("Expected lexer error, got {:?}", other),
+    }
+
+    let mut parser = Parser::new("let a = 1;");
+    assert!(parser.parse().is_ok());
+    assert_eq!(parser.lexer_error(), None);
+}
+
+#[test]
+fn test_unterminated_paren() {
+    let mut parser = Parser::new("f(1, g(2)");
+    let (_, errors) = parser.parse_with_recovery();
+    match errors.first() {
+        Some(ParseError::Unterminated { construct, opened_at, .. }) => {
+            assert_eq!(construct, "parenthesis");
+            assert_eq!((opened_at.line, opened_at.column), (1, 2));
+        }
+        other => panic!("Expected unterminated parenthesis, got {:?}", other),
+    }
+
+    let mut parser = Parser::new("function f() {\n  return 1;");
+    let (_, errors) = parser.parse_with_recovery();
+    assert!(errors[0].to_string().starts_with("Unterminated function body opened at line 1, column 14"));
+}
+
+#[test]
+fn test_nesting_too_deep() {
+    let nested = |open: &str, close: &str, depth: usize| format!("{}1{};", open.repeat(depth), close.repeat(depth));
+
+    // Far deeper than the stack allows without the limit
+    for source in [nested("(", ")", 10_000), nested("[", "]", 10_000), nested("-", "", 10_000), nested("{", "}", 10_000)] {
+        let mut parser = Parser::new(&source);
+        parser.set_max_depth(50);
+        let (_, errors) = parser.parse_with_recovery();
+        match errors.first() {
+            Some(ParseError::NestingTooDeep { limit, .. }) => assert_eq!(*limit, 50),
+            other => panic!("Expected NestingTooDeep, got {:?}", other),
+        }
+    }
+
+    let mut parser = Parser::new(&nested("(", ")", 40));
+    parser.set_max_depth(50);
+    assert!(parser.parse_with_recovery().1.is_empty());
+}
+
+#[test]
+fn test_default_nesting_limit() {
+    // Debug builds need more stack per level than a test thread has
+    let result = std::thread::Builder::new()
+        .stack_size(64 << 20)
+        .spawn(|| {
+            let depth = Parser::DEFAULT_MAX_DEPTH;
+            let source = |depth: usize| format!("{}1{};", "(".repeat(depth), ")".repeat(depth));
+            let shallow = Parser::new(&source(depth / 2)).parse_with_recovery().1;
+            let deep = Parser::new(&source(depth * 10)).parse_with_recovery().1;
+            (shallow, deep)
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    assert!(result.0.is_empty());
+    assert!(matches!(result.1.first(), Some(ParseError::NestingTooDeep { .. })), "{:?}", result.1);
+}
@@ -1,4 +1,4 @@
-use v8_parser::Parser;
+use v8_parser::{ParseError, Parser};
 use v8_ast::Node;
 
 #[test]
@@ -52,6 +52,23 @@ fn test_function_declaration_with_params() {
     }
 }
 
+#[test]
+fn test_function_pattern_default_and_rest_params() {
+    let mut parser = Parser::new("function f(a, b = a + 1, {c}, [d], ...rest) {}");
+    let Ok(Node::Program(program)) = parser.parse() else { panic!("Expected Program") };
+    let Node::FunctionDeclaration(func) = &program.body[0] else { panic!("Expected FunctionDeclaration") };
+    assert_eq!(func.params.len(), 5);
+    assert_eq!(func.params[0], Node::Identifier("a".to_string()));
+    assert!(matches!(&func.params[1], Node::AssignmentPattern(assign) if *assign.left == Node::Identifier("b".to_string())));
+    assert!(matches!(func.params[2], Node::ObjectPattern(_)));
+    assert!(matches!(func.params[3], Node::ArrayPattern(_)));
+    assert!(matches!(&func.params[4], Node::RestElement(rest) if *rest.argument == Node::Identifier("rest".to_string())));
+
+    // Nothing may follow the rest parameter
+    let (_, errors) = Parser::new("function g(...rest, a) {}").parse_with_recovery();
+    assert!(!errors.is_empty());
+}
+
 #[test]
 fn test_class_declaration() {
     let mut parser = Parser::new("class MyClass { constructor() {} }");
@@ -139,4 +156,359 @@ fn test_var_declaration() {
             panic!("Expected VariableDeclaration");
         }
     }
-} 
\ No newline at end of file
+} 
+#[test]
+fn test_object_pattern_declaration() {
+    let mut parser = Parser::new("let {a = 1, b: c, d: {e}} = obj;");
+    let result = parser.parse();
+    assert!(result.is_ok());
+    
+    if let Ok(Node::Program(program)) = result {
+        if let Node::VariableDeclaration(decl) = &program.body[0] {
+            if let Node::ObjectPattern(pattern) = &*decl.declarations[0].id {
+                assert_eq!(pattern.properties.len(), 3);
+                
+                // Shorthand with default
+                if let Node::Property(prop) = &pattern.properties[0] {
+                    assert!(prop.shorthand);
+                    if let Node::AssignmentPattern(assign) = &*prop.value {
+                        assert_eq!(*assign.left, Node::Identifier("a".to_string()));
+                        assert_eq!(*assign.right, Node::Number(1.0));
+                    } else {
+                        panic!("Expected AssignmentPattern");
+                    }
+                } else {
+                    panic!("Expected Property");
+                }
+                
+                // Renaming
+                if let Node::Property(prop) = &pattern.properties[1] {
+                    assert!(!prop.shorthand);
+                    assert_eq!(*prop.key, Node::Identifier("b".to_string()));
+                    assert_eq!(*prop.value, Node::Identifier("c".to_string()));
+                } else {
+                    panic!("Expected Property");
+                }
+                
+                // Nested pattern
+                if let Node::Property(prop) = &pattern.properties[2] {
+                    if let Node::ObjectPattern(nested) = &*prop.value {
+                        assert_eq!(nested.properties.len(), 1);
+                    } else {
+                        panic!("Expected nested ObjectPattern");
+                    }
+                } else {
+                    panic!("Expected Property");
+                }
+            } else {
+                panic!("Expected ObjectPattern");
+            }
+        } else {
+            panic!("Expected VariableDeclaration");
+        }
+    }
+}
+
+#[test]
+fn test_array_pattern_declaration() {
+    let mut parser = Parser::new("const [x, , [y], ...rest] = arr;");
+    let result = parser.parse();
+    assert!(result.is_ok());
+    
+    if let Ok(Node::Program(program)) = result {
+        if let Node::VariableDeclaration(decl) = &program.body[0] {
+            if let Node::ArrayPattern(pattern) = &*decl.declarations[0].id {
+                assert_eq!(pattern.elements.len(), 4);
+                assert_eq!(pattern.elements[0], Some(Node::Identifier("x".to_string())));
+                assert_eq!(pattern.elements[1], None);
+                assert!(matches!(pattern.elements[2], Some(Node::ArrayPattern(_))));
+                if let Some(Node::RestElement(rest)) = &pattern.elements[3] {
+                    assert_eq!(*rest.argument, Node::Identifier("rest".to_string()));
+                } else {
+                    panic!("Expected RestElement");
+                }
+            } else {
+                panic!("Expected ArrayPattern");
+            }
+            assert!(decl.declarations[0].init.is_some());
+        } else {
+            panic!("Expected VariableDeclaration");
+        }
+    }
+}
+
+#[test]
+fn test_mixed_declarator_list() {
+    let mut parser = Parser::new("let a = 1, {b, c = 2} = o, [d, ...e] = arr, f;");
+    let Node::Program(program) = parser.parse().unwrap() else { panic!("Expected Program") };
+    let Node::VariableDeclaration(decl) = &program.body[0] else { panic!("Expected VariableDeclaration") };
+    assert_eq!(decl.declarations.len(), 4);
+    assert_eq!(*decl.declarations[0].id, Node::Identifier("a".to_string()));
+    assert!(matches!(*decl.declarations[1].id, Node::ObjectPattern(_)));
+    assert!(matches!(*decl.declarations[2].id, Node::ArrayPattern(_)));
+    assert_eq!(*decl.declarations[3].id, Node::Identifier("f".to_string()));
+    let inits: Vec<bool> = decl.declarations.iter().map(|d| d.init.is_some()).collect();
+    assert_eq!(inits, [true, true, true, false]);
+}
+
+#[test]
+fn test_missing_initializer() {
+    for (source, kind) in [("const x;", "const"), ("const a = 1, b;", "const"), ("let {a};", "destructuring")] {
+        let (_, errors) = Parser::new(source).parse_with_recovery();
+        match errors.first() {
+            Some(ParseError::MissingInitializer { kind: found, .. }) => assert_eq!(found, kind, "{}", source),
+            other => panic!("Expected MissingInitializer for {:?}, got {:?}", source, other),
+        }
+    }
+    // The loop supplies the value of a for-of or for-in binding
+    for source in ["for (const x of xs) {}", "for (const [k, v] of entries) {}", "for (const k in o) {}"] {
+        let (_, errors) = Parser::new(source).parse_with_recovery();
+        assert!(errors.is_empty(), "{}: {:?}", source, errors);
+    }
+}
+
+#[test]
+fn test_class_methods() {
+    let mut parser = Parser::new("class Point { constructor(x) { this.x = x; } norm() { return 1; } }");
+    let result = parser.parse().unwrap();
+    let Node::Program(program) = result else { panic!("Expected Program") };
+    let Node::ClassDeclaration(class) = &program.body[0] else { panic!("Expected ClassDeclaration") };
+    let Node::BlockStatement(body) = class.body.as_ref() else { panic!("Expected class body") };
+    let names: Vec<&str> = body
+        .body
+        .iter()
+        .map(|member| match member {
+            Node::Property(prop) if prop.method => match prop.key.as_ref() {
+                Node::Identifier(name) => name.as_str(),
+                other => panic!("Expected method name, got {:?}", other),
+            },
+            other => panic!("Expected method, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(names, ["constructor", "norm"]);
+}
+
+/// `(kind, name)` of each property in a class body or object literal
+fn property_kinds(properties: &[Node]) -> Vec<(String, String)> {
+    properties
+        .iter()
+        .map(|member| match member {
+            Node::Property(prop) => match prop.key.as_ref() {
+                Node::Identifier(name) => (prop.kind.clone(), name.clone()),
+                other => panic!("Expected property name, got {:?}", other),
+            },
+            other => panic!("Expected property, got {:?}", other),
+        })
+        .collect()
+}
+
+#[test]
+fn test_getters_and_setters() {
+    let expected = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+        pairs.iter().map(|(kind, name)| (kind.to_string(), name.to_string())).collect()
+    };
+
+    let mut parser = Parser::new("class T { get c() { return 1; } set c(v) {} get() { return 2; } }");
+    let Node::Program(program) = parser.parse().unwrap() else { panic!("Expected Program") };
+    let Node::ClassDeclaration(class) = &program.body[0] else { panic!("Expected ClassDeclaration") };
+    let Node::BlockStatement(body) = class.body.as_ref() else { panic!("Expected class body") };
+    assert_eq!(property_kinds(&body.body), expected(&[("get", "c"), ("set", "c"), ("init", "get")]));
+
+    let mut parser = Parser::new("let o = { get c() { return 1; }, set c(v) {}, a: 1 };");
+    let Node::Program(program) = parser.parse().unwrap() else { panic!("Expected Program") };
+    let Node::VariableDeclaration(decl) = &program.body[0] else { panic!("Expected VariableDeclaration") };
+    let Some(Node::ObjectLiteral(literal)) = decl.declarations[0].init.as_deref() else {
+        panic!("Expected ObjectLiteral")
+    };
+    assert_eq!(property_kinds(&literal.properties), expected(&[("get", "c"), ("set", "c"), ("init", "a")]));
+}
+
+#[test]
+fn test_object_literal_property_keys() {
+    // Reserved words are names after `{` as after `.`, and numbers are keys too
+    let mut parser = Parser::new("let o = { get: 1, set: 2, if: 3, class() {}, null: 4, 1: 5, 'a': 6, get 2() {}, get };");
+    let (program, errors) = parser.parse_with_recovery();
+    assert!(errors.is_empty(), "{:?}", errors);
+    let Some(Node::Program(program)) = program else { panic!("Expected Program") };
+    let Node::VariableDeclaration(decl) = &program.body[0] else { panic!("Expected VariableDeclaration") };
+    let Some(Node::ObjectLiteral(literal)) = decl.declarations[0].init.as_deref() else {
+        panic!("Expected ObjectLiteral")
+    };
+    let keys: Vec<String> = literal
+        .properties
+        .iter()
+        .map(|member| match member {
+            Node::Property(prop) => match prop.key.as_ref() {
+                Node::Identifier(name) => name.clone(),
+                Node::String(value) => format!("'{}'", value),
+                Node::Number(value) => value.to_string(),
+                other => panic!("Expected property key, got {:?}", other),
+            },
+            other => panic!("Expected property, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(keys, ["get", "set", "if", "class", "null", "1", "'a'", "2", "get"]);
+
+    // A shorthand property reads the name, so it cannot be a reserved word
+    let mut parser = Parser::new("let o = { if };");
+    let errors = parser.parse_with_recovery().1;
+    assert!(matches!(&errors[..], [ParseError::ReservedWordAsBinding { word, .. }] if word == "if"), "{:?}", errors);
+}
+
+#[test]
+fn test_async_generator_declaration() {
+    let mut parser = Parser::new("async function* g(p) { yield await p; yield* g(p); yield; }");
+    let (program, errors) = parser.parse_with_recovery();
+    assert!(errors.is_empty(), "{:?}", errors);
+    let Some(Node::Program(program)) = program else { panic!("Expected Program") };
+    let Node::FunctionDeclaration(func) = &program.body[0] else { panic!("Expected FunctionDeclaration") };
+    assert!(func.r#async && func.generator);
+
+    let Node::BlockStatement(body) = func.body.as_ref() else { panic!("Expected function body") };
+    let yields: Vec<_> = body
+        .body
+        .iter()
+        .map(|stmt| match stmt {
+            Node::ExpressionStatement(stmt) => match stmt.expression.as_ref() {
+                Node::YieldExpression(expr) => expr,
+                other => panic!("Expected YieldExpression, got {:?}", other),
+            },
+            other => panic!("Expected ExpressionStatement, got {:?}", other),
+        })
+        .collect();
+    assert!(matches!(yields[0].argument.as_deref(), Some(Node::AwaitExpression(_))));
+    assert!(!yields[0].delegate);
+    assert!(matches!(yields[1].argument.as_deref(), Some(Node::CallExpression(_))));
+    assert!(yields[1].delegate);
+    assert!(yields[2].argument.is_none());
+}
+
+#[test]
+fn test_async_and_generator_methods() {
+    // (async, generator) of each method
+    let flags = |properties: &[Node]| -> Vec<(bool, bool)> {
+        properties
+            .iter()
+            .map(|member| match member {
+                Node::Property(prop) => match prop.value.as_ref() {
+                    Node::FunctionExpression(func) => (func.r#async, func.generator),
+                    other => panic!("Expected method, got {:?}", other),
+                },
+                other => panic!("Expected property, got {:?}", other),
+            })
+            .collect()
+    };
+    let expected = [(true, false), (false, true), (true, true), (false, false)];
+
+    let mut parser = Parser::new("class T { async a() {} *b() {} async *c() {} async() {} }");
+    let Node::Program(program) = parser.parse().unwrap() else { panic!("Expected Program") };
+    let Node::ClassDeclaration(class) = &program.body[0] else { panic!("Expected ClassDeclaration") };
+    let Node::BlockStatement(body) = class.body.as_ref() else { panic!("Expected class body") };
+    assert_eq!(flags(&body.body), expected);
+    assert_eq!(property_kinds(&body.body)[3], ("init".to_string(), "async".to_string()));
+
+    let mut parser = Parser::new("let o = { async a() { await 1; }, *b() { yield 1; }, async *c() {}, async() {} };");
+    let (program, errors) = parser.parse_with_recovery();
+    assert!(errors.is_empty(), "{:?}", errors);
+    let Some(Node::Program(program)) = program else { panic!("Expected Program") };
+    let Node::VariableDeclaration(decl) = &program.body[0] else { panic!("Expected VariableDeclaration") };
+    let Some(Node::ObjectLiteral(literal)) = decl.declarations[0].init.as_deref() else {
+        panic!("Expected ObjectLiteral")
+    };
+    assert_eq!(flags(&literal.properties), expected);
+
+    // `async` followed by `:` is a plain key
+    let mut parser = Parser::new("({ async: 1, async });");
+    let (program, errors) = parser.parse_with_recovery();
+    assert!(errors.is_empty(), "{:?}", errors);
+    let Some(Node::Program(program)) = program else { panic!("Expected Program") };
+    let Node::ExpressionStatement(stmt) = &program.body[0] else { panic!("Expected ExpressionStatement") };
+    let Node::ObjectLiteral(literal) = stmt.expression.as_ref() else { panic!("Expected ObjectLiteral") };
+    assert_eq!(property_kinds(&literal.properties), vec![("init".to_string(), "async".to_string()); 2]);
+}
+
+#[test]
+fn test_trailing_comma_in_parameters() {
+    for source in ["function f(a, b,) {}", "async function f(a,) {}", "({m(a,) {}});", "let [a,] = c;", "let {a,} = c;"] {
+        let (_, errors) = Parser::new(source).parse_with_recovery();
+        assert!(errors.is_empty(), "{}: {:?}", source, errors);
+    }
+    let Ok(Node::Program(program)) = Parser::new("function f(a, b,) {}").parse() else { panic!("Expected Program") };
+    let Node::FunctionDeclaration(func) = &program.body[0] else { panic!("Expected FunctionDeclaration") };
+    assert_eq!(func.params.len(), 2);
+
+    for source in ["function f(a,,) {}", "let a = 1,;", "let {a,,} = c;"] {
+        let (_, errors) = Parser::new(source).parse_with_recovery();
+        assert!(!errors.is_empty(), "{} should not parse", source);
+    }
+}
+
+#[test]
+fn test_rest_must_be_last() {
+    let cases = [
+        ("function f(...a,) {}", "Rest parameter must be last formal parameter"),
+        ("function f(...a, b) {}", "Rest parameter must be last formal parameter"),
+        ("(...a,) => a;", "Rest parameter must be last formal parameter"),
+        ("let [a, ...b,] = c;", "Rest element must be last element"),
+        ("let {a, ...b,} = c;", "Rest element must be last element"),
+    ];
+    for (source, expected) in cases {
+        let (_, errors) = Parser::new(source).parse_with_recovery();
+        match errors.first() {
+            Some(ParseError::InvalidSyntax { message, .. }) => assert_eq!(message, expected, "{}", source),
+            other => panic!("Expected InvalidSyntax for {:?}, got {:?}", source, other),
+        }
+    }
+}
+
+fn reserved_binding(source: &str) -> Option<String> {
+    let (_, errors) = Parser::new(source).parse_with_recovery();
+    match errors.first() {
+        Some(ParseError::ReservedWordAsBinding { word, .. }) => Some(word.clone()),
+        Some(other) => panic!("Expected ReservedWordAsBinding for {:?}, got {:?}", source, other),
+        None => None,
+    }
+}
+
+#[test]
+fn test_reserved_word_as_binding_name() {
+    let cases = [
+        ("let class = 1;", "class"),
+        ("var return;", "return"),
+        ("function f(if) {}", "if"),
+        ("function new() {}", "new"),
+        ("let {a, ...this} = o;", "this"),
+        ("let {a, class} = o;", "class"),
+        ("try {} catch (true) {}", "true"),
+        ("const [x, null] = y;", "null"),
+    ];
+    for (source, word) in cases {
+        assert_eq!(reserved_binding(source).as_deref(), Some(word), "{}", source);
+    }
+    let (_, errors) = Parser::new("let class = 1;").parse_with_recovery();
+    let span = errors[0].span().unwrap();
+    assert_eq!((span.start.column, span.end.column), (5, 10));
+    // A reserved word is still fine as a property key
+    assert_eq!(reserved_binding("let {class: c} = o;"), None);
+}
+
+#[test]
+fn test_strict_mode_reserved_words_as_binding_names() {
+    for word in ["yield", "let", "static", "implements", "private"] {
+        let binding = |source: String| reserved_binding(&source);
+        assert_eq!(binding(format!("var {} = 1;", word)), None);
+        assert_eq!(binding(format!("'use strict'; var {} = 1;", word)).as_deref(), Some(word));
+        assert_eq!(binding(format!("function f() {{ 'use strict'; var {} = 1; }}", word)).as_deref(), Some(word));
+        assert_eq!(binding(format!("class C {{ m({}) {{}} }}", word)).as_deref(), Some(word));
+    }
+    assert_eq!(reserved_binding("'use strict'; let yield = 1;").as_deref(), Some("yield"));
+    // The directive applies to its own function only, and only at the start
+    assert_eq!(reserved_binding("function f() { 'use strict'; } var yield = 1;"), None);
+    assert_eq!(reserved_binding("var a; 'use strict'; var yield = 1;"), None);
+
+    assert_eq!(reserved_binding("function* g() { var yield; }").as_deref(), Some("yield"));
+    assert_eq!(reserved_binding("async function f() { var await; }").as_deref(), Some("await"));
+    assert_eq!(reserved_binding("function f() { var await; }"), None);
+    // Words the lexer reads as keywords but that are not reserved
+    assert_eq!(reserved_binding("let of = 1, get = 2, async = 3;"), None);
+}
@@ -1,5 +1,6 @@
-use v8_parser::Parser;
-use v8_ast::Node;
+use v8_parser::{ParseError, Parser};
+use v8_ast::{Node, Position};
+use v8_lexer::TokenKind;
 
 #[test]
 fn test_unary_expression() {
@@ -24,6 +25,50 @@ fn test_unary_expression() {
     }
 }
 
+#[test]
+fn test_prefix_and_postfix_update() {
+    for (source, operator, prefix) in [("++i", "++", true), ("--i", "--", true), ("i++", "++", false)] {
+        let Ok(Node::Program(program)) = Parser::new(source).parse() else { panic!("Expected Program") };
+        let Node::ExpressionStatement(stmt) = &program.body[0] else { panic!("Expected ExpressionStatement") };
+        let Node::UpdateExpression(expr) = stmt.expression.as_ref() else { panic!("Expected UpdateExpression") };
+        assert_eq!((expr.operator.as_str(), expr.prefix), (operator, prefix), "source: {}", source);
+        assert_eq!(*expr.argument, Node::Identifier("i".to_string()));
+    }
+}
+
+#[test]
+fn test_sequence_expression() {
+    let Ok(Node::Program(program)) = Parser::new("f(a, b), [c, d], x = 1, y").parse() else { panic!("Expected Program") };
+    let Node::ExpressionStatement(stmt) = &program.body[0] else { panic!("Expected ExpressionStatement") };
+    let Node::SequenceExpression(seq) = stmt.expression.as_ref() else { panic!("Expected SequenceExpression") };
+    assert_eq!(seq.expressions.len(), 4);
+    assert!(matches!(&seq.expressions[0], Node::CallExpression(call) if call.arguments.len() == 2));
+    assert!(matches!(&seq.expressions[1], Node::ArrayLiteral(array) if array.elements.len() == 2));
+    assert!(matches!(seq.expressions[2], Node::AssignmentExpression(_)));
+
+    // Commas in declarations separate declarators
+    let Ok(Node::Program(program)) = Parser::new("let a = 1, b = (2, 3);").parse() else { panic!("Expected Program") };
+    let Node::VariableDeclaration(decl) = &program.body[0] else { panic!("Expected VariableDeclaration") };
+    assert_eq!(decl.declarations.len(), 2);
+    assert!(matches!(decl.declarations[1].init.as_deref(), Some(Node::SequenceExpression(_))));
+    assert_eq!(v8_ast::codegen::generate(&program.body[0]), "let a = 1, b = (2, 3);");
+}
+
+#[test]
+fn test_super_and_new_target() {
+    let Ok(Node::Program(program)) = Parser::new("super.m(new.target); new X.y();").parse() else { panic!("Expected Program") };
+    let Node::ExpressionStatement(stmt) = &program.body[0] else { panic!("Expected ExpressionStatement") };
+    let Node::CallExpression(call) = stmt.expression.as_ref() else { panic!("Expected CallExpression") };
+    let Node::MemberExpression(member) = call.callee.as_ref() else { panic!("Expected MemberExpression") };
+    assert!(matches!(member.object.as_ref(), Node::Super(_)));
+    assert!(matches!(&call.arguments[0], Node::MetaProperty(meta) if *meta.property == Node::Identifier("target".to_string())));
+    assert_eq!(v8_ast::codegen::generate(&program.body[0]), "super.m(new.target);");
+
+    // A dot after the callee is still a member access on it
+    let Node::ExpressionStatement(stmt) = &program.body[1] else { panic!("Expected ExpressionStatement") };
+    assert!(matches!(stmt.expression.as_ref(), Node::NewExpression(_)));
+}
+
 #[test]
 fn test_logical_expression() {
     let mut parser = Parser::new("true && false");
@@ -179,6 +224,31 @@ fn test_arrow_function_block_body() {
     }
 }
 
+#[test]
+fn test_arrow_function_braces_are_always_a_block() {
+    let Ok(Node::Program(program)) = Parser::new("const f = () => ({a: 1}); const g = () => {a: 1};").parse() else {
+        panic!("Expected Program")
+    };
+    let arrow = |index: usize| {
+        let Node::VariableDeclaration(decl) = &program.body[index] else { panic!("Expected VariableDeclaration") };
+        match decl.declarations[0].init.as_deref() {
+            Some(Node::ArrowFunctionExpression(arrow)) => arrow.clone(),
+            other => panic!("Expected ArrowFunctionExpression, got {:?}", other),
+        }
+    };
+
+    // Parenthesized object literal: an expression body
+    let f = arrow(0);
+    assert!(f.expression);
+    assert!(matches!(f.body.as_ref(), Node::ObjectLiteral(object) if object.properties.len() == 1));
+
+    // Braces: a block whose `a:` is a label
+    let g = arrow(1);
+    assert!(!g.expression);
+    let Node::BlockStatement(block) = g.body.as_ref() else { panic!("Expected BlockStatement, got {:?}", g.body) };
+    assert!(matches!(&block.body[0], Node::LabeledStatement(labeled) if *labeled.label == Node::Identifier("a".to_string())));
+}
+
 #[test]
 fn test_nullish_coalescing() {
     let mut parser = Parser::new("const value = a ?? b;");
@@ -204,15 +274,51 @@ fn test_destructuring_assignment() {
     
     if let Ok(Node::Program(program)) = result {
         if let Node::VariableDeclaration(decl) = &program.body[0] {
-            if let Node::ObjectLiteral(obj) = &*decl.declarations[0].id {
+            if let Node::ObjectPattern(obj) = &*decl.declarations[0].id {
                 assert_eq!(obj.properties.len(), 2);
             } else {
-                panic!("Expected ObjectLiteral");
+                panic!("Expected ObjectPattern");
             }
         }
     }
 }
 
+#[test]
+fn test_destructuring_assignment_targets() {
+    let parse_expression = |source: &str| -> Node {
+        let Node::Program(program) = Parser::new(source).parse().unwrap() else { panic!("Expected Program") };
+        let Node::ExpressionStatement(stmt) = &program.body[0] else { panic!("Expected ExpressionStatement") };
+        *stmt.expression.clone()
+    };
+
+    let Node::AssignmentExpression(assign) = parse_expression("[a, , ...rest] = arr;") else {
+        panic!("Expected AssignmentExpression")
+    };
+    let Node::ArrayPattern(pattern) = assign.left.as_ref() else { panic!("Expected ArrayPattern") };
+    assert_eq!(pattern.elements[0], Some(Node::Identifier("a".to_string())));
+    assert_eq!(pattern.elements[1], None);
+    assert!(matches!(&pattern.elements[2], Some(Node::RestElement(rest)) if *rest.argument == Node::Identifier("rest".to_string())));
+
+    let Node::AssignmentExpression(assign) = parse_expression("({a, b: o.b, ...rest} = obj);") else {
+        panic!("Expected AssignmentExpression")
+    };
+    let Node::ObjectPattern(pattern) = assign.left.as_ref() else { panic!("Expected ObjectPattern") };
+    assert!(matches!(&pattern.properties[0], Node::Property(prop) if prop.shorthand));
+    assert!(matches!(&pattern.properties[1], Node::Property(prop) if matches!(*prop.value, Node::MemberExpression(_))));
+    assert!(matches!(&pattern.properties[2], Node::RestElement(_)));
+
+    // The rest element must come last, and only references can be assigned
+    for source in ["[...rest, last] = arr;", "({...rest, a} = obj);", "[a + 1] = arr;"] {
+        let (_, errors) = Parser::new(source).parse_with_recovery();
+        assert!(
+            matches!(errors.first(), Some(ParseError::InvalidSyntax { .. })),
+            "{}: {:?}",
+            source,
+            errors
+        );
+    }
+}
+
 #[test]
 fn test_array_destructuring() {
     let mut parser = Parser::new("const [first, second] = arr;");
@@ -221,10 +327,10 @@ fn test_array_destructuring() {
     
     if let Ok(Node::Program(program)) = result {
         if let Node::VariableDeclaration(decl) = &program.body[0] {
-            if let Node::ArrayLiteral(arr) = &*decl.declarations[0].id {
+            if let Node::ArrayPattern(arr) = &*decl.declarations[0].id {
                 assert_eq!(arr.elements.len(), 2);
             } else {
-                panic!("Expected ArrayLiteral");
+                panic!("Expected ArrayPattern");
             }
         }
     }
@@ -250,4 +356,526 @@ fn test_spread_operator() {
             }
         }
     }
-} 
\ No newline at end of file
+} 
+#[test]
+fn test_new_with_member_callee() {
+    let mut parser = Parser::new("new a.b.C()");
+    let result = parser.parse();
+    assert!(result.is_ok());
+    
+    if let Ok(Node::Program(program)) = result {
+        if let Node::ExpressionStatement(stmt) = &program.body[0] {
+            if let Node::NewExpression(new_expr) = &*stmt.expression {
+                assert!(new_expr.arguments.is_empty());
+                if let Node::MemberExpression(outer) = &*new_expr.callee {
+                    assert_eq!(*outer.property, Node::Identifier("C".to_string()));
+                    if let Node::MemberExpression(inner) = &*outer.object {
+                        assert_eq!(*inner.object, Node::Identifier("a".to_string()));
+                        assert_eq!(*inner.property, Node::Identifier("b".to_string()));
+                    } else {
+                        panic!("Expected nested MemberExpression");
+                    }
+                } else {
+                    panic!("Expected MemberExpression callee");
+                }
+            } else {
+                panic!("Expected NewExpression");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_call_of_call_result() {
+    let mut parser = Parser::new("f()()");
+    let result = parser.parse();
+    assert!(result.is_ok());
+    
+    if let Ok(Node::Program(program)) = result {
+        if let Node::ExpressionStatement(stmt) = &program.body[0] {
+            if let Node::CallExpression(outer) = &*stmt.expression {
+                if let Node::CallExpression(inner) = &*outer.callee {
+                    assert_eq!(*inner.callee, Node::Identifier("f".to_string()));
+                } else {
+                    panic!("Expected inner CallExpression");
+                }
+            } else {
+                panic!("Expected CallExpression");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_call_after_index_and_member() {
+    let mut parser = Parser::new("a[b].c()");
+    let result = parser.parse();
+    assert!(result.is_ok());
+    
+    if let Ok(Node::Program(program)) = result {
+        if let Node::ExpressionStatement(stmt) = &program.body[0] {
+            if let Node::CallExpression(call) = &*stmt.expression {
+                if let Node::MemberExpression(member) = &*call.callee {
+                    assert!(!member.computed);
+                    assert_eq!(*member.property, Node::Identifier("c".to_string()));
+                    if let Node::MemberExpression(index) = &*member.object {
+                        assert!(index.computed);
+                        assert_eq!(*index.object, Node::Identifier("a".to_string()));
+                        assert_eq!(*index.property, Node::Identifier("b".to_string()));
+                    } else {
+                        panic!("Expected computed MemberExpression");
+                    }
+                } else {
+                    panic!("Expected MemberExpression callee");
+                }
+            } else {
+                panic!("Expected CallExpression");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_tagged_template() {
+    let mut parser = Parser::new("tag`hello ${name}!`");
+    let result = parser.parse();
+    assert!(result.is_ok());
+    
+    if let Ok(Node::Program(program)) = result {
+        if let Node::ExpressionStatement(stmt) = &program.body[0] {
+            if let Node::TaggedTemplateExpression(tagged) = &*stmt.expression {
+                assert_eq!(*tagged.tag, Node::Identifier("tag".to_string()));
+                if let Node::TemplateLiteral(quasi) = &*tagged.quasi {
+                    assert_eq!(quasi.quasis.len(), 2);
+                    assert_eq!(quasi.quasis[0].value, "hello ");
+                    assert!(!quasi.quasis[0].tail);
+                    assert_eq!(quasi.quasis[1].value, "!");
+                    assert!(quasi.quasis[1].tail);
+                    assert_eq!(quasi.expressions, vec![Node::Identifier("name".to_string())]);
+                } else {
+                    panic!("Expected TemplateLiteral");
+                }
+            } else {
+                panic!("Expected TaggedTemplateExpression");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_tagged_template_keeps_raw_strings() {
+    let mut parser = Parser::new("String.raw`a\\nb`");
+    let result = parser.parse();
+    assert!(result.is_ok());
+    
+    if let Ok(Node::Program(program)) = result {
+        if let Node::ExpressionStatement(stmt) = &program.body[0] {
+            if let Node::TaggedTemplateExpression(tagged) = &*stmt.expression {
+                assert!(matches!(&*tagged.tag, Node::MemberExpression(_)));
+                if let Node::TemplateLiteral(quasi) = &*tagged.quasi {
+                    assert_eq!(quasi.quasis[0].raw, "a\\nb");
+                    assert_eq!(quasi.quasis[0].value, "a\nb");
+                } else {
+                    panic!("Expected TemplateLiteral");
+                }
+            } else {
+                panic!("Expected TaggedTemplateExpression");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_spread_in_call_and_object_literal() {
+    let mut parser = Parser::new("f(1, ...args); ({...a, b: 2});");
+    let result = parser.parse();
+    assert!(result.is_ok());
+    
+    if let Ok(Node::Program(program)) = result {
+        if let Node::ExpressionStatement(stmt) = &program.body[0] {
+            if let Node::CallExpression(call) = &*stmt.expression {
+                assert_eq!(call.arguments.len(), 2);
+                assert!(matches!(call.arguments[1], Node::SpreadElement(_)));
+            } else {
+                panic!("Expected CallExpression");
+            }
+        }
+        if let Node::ExpressionStatement(stmt) = &program.body[1] {
+            if let Node::ObjectLiteral(object) = &*stmt.expression {
+                assert!(matches!(object.properties[0], Node::SpreadElement(_)));
+                assert!(matches!(object.properties[1], Node::Property(_)));
+            } else {
+                panic!("Expected ObjectLiteral");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_conditional_expression() {
+    let mut parser = Parser::new("a ? b : c");
+    let result = parser.parse();
+    assert!(result.is_ok());
+    
+    if let Ok(Node::Program(program)) = result {
+        if let Node::ExpressionStatement(stmt) = &program.body[0] {
+            if let Node::ConditionalExpression(cond) = &*stmt.expression {
+                assert!(matches!(&*cond.test, Node::Identifier(name) if name == "a"));
+                assert!(matches!(&*cond.consequent, Node::Identifier(name) if name == "b"));
+                assert!(matches!(&*cond.alternate, Node::Identifier(name) if name == "c"));
+            } else {
+                panic!("Expected ConditionalExpression");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_nested_conditional_is_right_associative() {
+    let mut parser = Parser::new("a ? b : c ? d : e");
+    let result = parser.parse();
+    assert!(result.is_ok());
+    
+    if let Ok(Node::Program(program)) = result {
+        if let Node::ExpressionStatement(stmt) = &program.body[0] {
+            if let Node::ConditionalExpression(outer) = &*stmt.expression {
+                assert!(matches!(&*outer.test, Node::Identifier(name) if name == "a"));
+                if let Node::ConditionalExpression(inner) = &*outer.alternate {
+                    assert!(matches!(&*inner.test, Node::Identifier(name) if name == "c"));
+                    assert!(matches!(&*inner.alternate, Node::Identifier(name) if name == "e"));
+                } else {
+                    panic!("Expected nested ConditionalExpression");
+                }
+            } else {
+                panic!("Expected ConditionalExpression");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_exponentiation_is_right_associative() {
+    let Ok(Node::Program(program)) = Parser::new("(-2) ** 2 === 4; a ** b ** c; 2 ** -2;").parse() else { panic!("Expected Program") };
+    let Node::ExpressionStatement(stmt) = &program.body[0] else { panic!("Expected ExpressionStatement") };
+    let Node::BinaryExpression(equality) = stmt.expression.as_ref() else { panic!("Expected BinaryExpression") };
+    assert_eq!(equality.operator, "===");
+    let Node::BinaryExpression(power) = equality.left.as_ref() else { panic!("Expected exponentiation") };
+    assert_eq!(power.operator, "**");
+    assert!(matches!(power.left.as_ref(), Node::UnaryExpression(negation) if negation.operator == "-"));
+
+    let Node::ExpressionStatement(stmt) = &program.body[1] else { panic!("Expected ExpressionStatement") };
+    let Node::BinaryExpression(outer) = stmt.expression.as_ref() else { panic!("Expected BinaryExpression") };
+    assert_eq!(*outer.left, Node::Identifier("a".to_string()));
+    assert!(matches!(outer.right.as_ref(), Node::BinaryExpression(inner) if inner.operator == "**"));
+
+    // A unary operator is allowed on the right operand
+    let Node::ExpressionStatement(stmt) = &program.body[2] else { panic!("Expected ExpressionStatement") };
+    let Node::BinaryExpression(power) = stmt.expression.as_ref() else { panic!("Expected BinaryExpression") };
+    assert!(matches!(power.right.as_ref(), Node::UnaryExpression(negation) if negation.operator == "-"));
+}
+
+#[test]
+fn test_unary_left_operand_of_exponentiation_is_an_error() {
+    for source in ["-2 ** 2", "typeof a ** 2", "void 0 ** 2", "delete o.x ** 2"] {
+        let (_, errors) = Parser::new(source).parse_with_recovery();
+        assert!(
+            matches!(errors.first(), Some(ParseError::InvalidSyntax { message, .. }) if message.contains("exponentiation")),
+            "source: {}, errors: {:?}", source, errors
+        );
+    }
+    assert!(Parser::new("(-2) ** 2").parse_with_recovery().1.is_empty());
+}
+
+#[test]
+fn test_regexp_literal() {
+    let mut parser = Parser::new(r"/\d+/g.test(s)");
+    let result = parser.parse();
+    assert!(result.is_ok());
+
+    if let Ok(Node::Program(program)) = result {
+        if let Node::ExpressionStatement(stmt) = &program.body[0] {
+            if let Node::CallExpression(call) = &*stmt.expression {
+                if let Node::MemberExpression(member) = &*call.callee {
+                    if let Node::RegExp(regexp) = &*member.object {
+                        assert_eq!(regexp.pattern, r"\d+");
+                        assert_eq!(regexp.flags, "g");
+                    } else {
+                        panic!("Expected RegExp object");
+                    }
+                } else {
+                    panic!("Expected MemberExpression callee");
+                }
+            } else {
+                panic!("Expected CallExpression");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_keyword_property_name() {
+    let mut parser = Parser::new("p.catch(f).finally(g); o.new; o.null");
+    let result = parser.parse();
+    assert!(result.is_ok());
+
+    if let Ok(Node::Program(program)) = result {
+        if let Node::ExpressionStatement(stmt) = &program.body[1] {
+            if let Node::MemberExpression(member) = &*stmt.expression {
+                assert_eq!(*member.property, Node::Identifier("new".to_string()));
+                assert!(!member.computed);
+            } else {
+                panic!("Expected member expression");
+            }
+        } else {
+            panic!("Expected expression statement");
+        }
+    } else {
+        panic!("Expected program");
+    }
+}
+
+#[test]
+fn test_optional_member_access() {
+    let mut parser = Parser::new("a?.b[c]?.[d]");
+    let result = parser.parse();
+    assert!(result.is_ok());
+
+    if let Ok(Node::Program(program)) = result {
+        if let Node::ExpressionStatement(stmt) = &program.body[0] {
+            if let Node::MemberExpression(outer) = &*stmt.expression {
+                assert!(outer.optional && outer.computed);
+                assert_eq!(*outer.property, Node::Identifier("d".to_string()));
+                if let Node::MemberExpression(index) = &*outer.object {
+                    assert!(!index.optional && index.computed);
+                    if let Node::MemberExpression(inner) = &*index.object {
+                        assert!(inner.optional && !inner.computed);
+                        assert_eq!(*inner.property, Node::Identifier("b".to_string()));
+                    } else {
+                        panic!("Expected optional MemberExpression");
+                    }
+                } else {
+                    panic!("Expected computed MemberExpression");
+                }
+            } else {
+                panic!("Expected MemberExpression");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_binary_expression_span_covers_operands() {
+    let mut parser = Parser::new("a + b * c;\nx = 1 +\n  22;");
+    let program = match parser.parse() {
+        Ok(Node::Program(program)) => program,
+        other => panic!("Expected program, got {:?}", other),
+    };
+
+    let Node::ExpressionStatement(stmt) = &program.body[0] else {
+        panic!("Expected expression statement");
+    };
+    let span = stmt.expression.span().expect("binary expression span");
+    assert_eq!((span.start.line, span.start.column), (1, 1));
+    assert_eq!((span.end.line, span.end.column), (1, 10));
+
+    let Node::ExpressionStatement(stmt) = &program.body[1] else {
+        panic!("Expected expression statement");
+    };
+    let span = stmt.expression.span().expect("assignment span");
+    assert_eq!((span.start.line, span.start.column), (2, 1));
+    assert_eq!(span.end.line, 3);
+}
+
+#[test]
+fn test_reserved_words_as_member_names() {
+    for name in ["class", "if", "delete", "new", "return", "in", "typeof", "true", "null", "undefined", "this"] {
+        let source = format!("a.{};", name);
+        let (ast, errors) = Parser::new(&source).parse_with_recovery();
+        assert!(errors.is_empty(), "{}: {:?}", source, errors);
+        let Some(Node::Program(program)) = ast else { panic!("Expected Program") };
+        let Node::ExpressionStatement(stmt) = &program.body[0] else { panic!("Expected ExpressionStatement") };
+        let Node::MemberExpression(member) = stmt.expression.as_ref() else { panic!("{}: expected MemberExpression", source) };
+        assert!(!member.computed);
+        assert_eq!(*member.property, Node::Identifier(name.to_string()));
+    }
+
+    // Numeric and string keys go through the computed form
+    let Ok(Node::Program(program)) = Parser::new("a[0]['class'];").parse() else { panic!("Expected Program") };
+    let Node::ExpressionStatement(stmt) = &program.body[0] else { panic!("Expected ExpressionStatement") };
+    let Node::MemberExpression(outer) = stmt.expression.as_ref() else { panic!("Expected MemberExpression") };
+    assert!(outer.computed);
+    assert_eq!(*outer.property, Node::String("class".to_string()));
+    let Node::MemberExpression(inner) = outer.object.as_ref() else { panic!("Expected nested MemberExpression") };
+    assert_eq!(*inner.property, Node::Number(0.0));
+}
+
+#[test]
+fn test_trailing_commas() {
+    for source in ["[1, 2,];", "({a: 1, b,});", "({...a,});", "f(1, 2,);", "f(...a,);", "new F(1,);", "(a, b,) => a;", "[a, b,] = c;"] {
+        let (_, errors) = Parser::new(source).parse_with_recovery();
+        assert!(errors.is_empty(), "{}: {:?}", source, errors);
+    }
+
+    // A trailing comma adds no element; a comma before it is an elision
+    let elements = |source: &str| {
+        let Ok(Node::Program(program)) = Parser::new(source).parse() else { panic!("Expected Program") };
+        let Node::ExpressionStatement(stmt) = &program.body[0] else { panic!("Expected ExpressionStatement") };
+        let Node::ArrayLiteral(array) = stmt.expression.as_ref() else { panic!("Expected ArrayLiteral") };
+        array.elements.iter().map(Option::is_some).collect::<Vec<_>>()
+    };
+    assert_eq!(elements("[1,];"), vec![true]);
+    assert_eq!(elements("[1,,3];"), vec![true, false, true]);
+    assert_eq!(elements("[1,,];"), vec![true, false]);
+
+    for source in ["({a: 1,,});", "f(1,,);", "f(,);", "[1 2];", "({a: 1 b: 2});", "f(1 2);", "(a,);"] {
+        let (_, errors) = Parser::new(source).parse_with_recovery();
+        assert!(!errors.is_empty(), "{} should not parse", source);
+    }
+}
+
+#[test]
+fn test_rest_element_rejects_trailing_comma() {
+    for source in ["[a, ...b,] = c;", "({a, ...b,} = c);", "[[...a,]] = c;"] {
+        let (_, errors) = Parser::new(source).parse_with_recovery();
+        match errors.first() {
+            Some(ParseError::InvalidSyntax { message, .. }) => assert_eq!(message, "Rest element must be last element"),
+            other => panic!("Expected InvalidSyntax for {:?}, got {:?}", source, other),
+        }
+    }
+    // The spread of an array used as a value may have one
+    let (_, errors) = Parser::new("[x = [...a,]] = c;").parse_with_recovery();
+    assert!(errors.is_empty(), "{:?}", errors);
+}
+
+#[test]
+fn test_template_literal_substitutions() {
+    let Ok(Node::Program(program)) = Parser::new("`a${b}c${d + 1}`;").parse() else { panic!("Expected Program") };
+    let Node::ExpressionStatement(stmt) = &program.body[0] else { panic!("Expected ExpressionStatement") };
+    let Node::TemplateLiteral(template) = stmt.expression.as_ref() else { panic!("Expected TemplateLiteral") };
+    let quasis: Vec<&str> = template.quasis.iter().map(|quasi| quasi.value.as_str()).collect();
+    assert_eq!(quasis, ["a", "c", ""]);
+    assert_eq!(template.expressions[0], Node::Identifier("b".to_string()));
+    assert!(matches!(template.expressions[1], Node::BinaryExpression(_)));
+
+    // Without substitutions a template is a plain string
+    let Ok(Node::Program(program)) = Parser::new("`a\\tb`;").parse() else { panic!("Expected Program") };
+    let Node::ExpressionStatement(stmt) = &program.body[0] else { panic!("Expected ExpressionStatement") };
+    assert_eq!(*stmt.expression, Node::String("a\tb".to_string()));
+}
+
+#[test]
+fn test_template_substitution_boundaries() {
+    let substitutions = |source: &str| {
+        let (ast, errors) = Parser::new(source).parse_with_recovery();
+        assert!(errors.is_empty(), "{}: {:?}", source, errors);
+        let Some(Node::Program(program)) = ast else { panic!("Expected Program") };
+        let Node::ExpressionStatement(stmt) = &program.body[0] else { panic!("Expected ExpressionStatement") };
+        let Node::TemplateLiteral(template) = stmt.expression.as_ref() else { panic!("Expected TemplateLiteral") };
+        template.expressions.clone()
+    };
+
+    // A `}` in a string or in a nested template does not end the substitution
+    assert_eq!(substitutions("`${\"}\"}`;"), [Node::String("}".to_string())]);
+    assert_eq!(substitutions("`${'`'}`;"), [Node::String("`".to_string())]);
+    let nested = substitutions("`a${`b${c}}`}d`;");
+    let [Node::TemplateLiteral(inner)] = &nested[..] else { panic!("Expected nested TemplateLiteral, got {:?}", nested) };
+    assert_eq!(inner.quasis.iter().map(|quasi| quasi.value.as_str()).collect::<Vec<_>>(), ["b", "}"]);
+    assert!(matches!(&substitutions("`${ {a: 1}.a }`;")[..], [Node::MemberExpression(_)]));
+
+    // A substitution holds exactly one expression
+    let errors = Parser::new("`${1 2}`;").parse_with_recovery().1;
+    assert!(matches!(&errors[..], [ParseError::UnexpectedToken { .. }]), "{:?}", errors);
+
+    // Errors inside a substitution point into the file
+    let errors = Parser::new("x;\n  `ab\n c${ 1 + }`;").parse_with_recovery().1;
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert_eq!(errors[0].position(), Some(Position::new(3, 10)));
+}
+
+#[test]
+fn test_async_as_identifier() {
+    let statements = |source: &str| {
+        let (ast, errors) = Parser::new(source).parse_with_recovery();
+        assert!(errors.is_empty(), "{}: {:?}", source, errors);
+        let Some(Node::Program(program)) = ast else { panic!("Expected Program") };
+        program.body
+    };
+
+    // An arrow with a parameter named async
+    let body = statements("async => async;");
+    let Node::ExpressionStatement(stmt) = &body[0] else { panic!("Expected ExpressionStatement") };
+    let Node::ArrowFunctionExpression(arrow) = stmt.expression.as_ref() else { panic!("Expected ArrowFunctionExpression") };
+    assert!(!arrow.r#async);
+    assert_eq!(arrow.params, vec![Node::Identifier("async".to_string())]);
+    assert_eq!(*arrow.body, Node::Identifier("async".to_string()));
+
+    // A line break after `async` ends the statement: the arrow is not async
+    let body = statements("async\nx => x");
+    assert_eq!(body.len(), 2);
+    let Node::ExpressionStatement(stmt) = &body[0] else { panic!("Expected ExpressionStatement") };
+    assert_eq!(*stmt.expression, Node::Identifier("async".to_string()));
+    let Node::ExpressionStatement(stmt) = &body[1] else { panic!("Expected ExpressionStatement") };
+    let Node::ArrowFunctionExpression(arrow) = stmt.expression.as_ref() else { panic!("Expected ArrowFunctionExpression") };
+    assert!(!arrow.r#async);
+
+    let body = statements("async\nfunction f() {}");
+    assert_eq!(body.len(), 2);
+    let Node::FunctionDeclaration(func) = &body[1] else { panic!("Expected FunctionDeclaration") };
+    assert!(!func.r#async);
+
+    // On the same line it is still a modifier, and otherwise an ordinary name
+    for source in ["async x => x;", "async (a, b) => a;", "(async function () {});"] {
+        let Node::ExpressionStatement(stmt) = &statements(source)[0] else { panic!("Expected ExpressionStatement") };
+        let is_async = match stmt.expression.as_ref() {
+            Node::ArrowFunctionExpression(arrow) => arrow.r#async,
+            Node::FunctionExpression(func) => func.r#async,
+            other => panic!("Expected a function for {:?}, got {:?}", source, other),
+        };
+        assert!(is_async, "{}", source);
+    }
+    let Node::ExpressionStatement(stmt) = &statements("async(1);")[0] else { panic!("Expected ExpressionStatement") };
+    assert!(matches!(stmt.expression.as_ref(), Node::CallExpression(call) if *call.callee == Node::Identifier("async".to_string())));
+}
+
+#[test]
+fn test_binary_operators_follow_token_precedence() {
+    // Fully parenthesized form of a binary tree
+    fn grouped(node: &Node) -> String {
+        match node {
+            Node::BinaryExpression(expr) => format!("({} {} {})", grouped(&expr.left), expr.operator, grouped(&expr.right)),
+            Node::LogicalExpression(expr) => format!("({} {} {})", grouped(&expr.left), expr.operator, grouped(&expr.right)),
+            Node::Identifier(name) => name.clone(),
+            other => panic!("Unexpected operand {:?}", other),
+        }
+    }
+    let parse = |source: &str| {
+        let Ok(Node::Program(program)) = Parser::new(source).parse() else { panic!("Expected Program") };
+        let Node::ExpressionStatement(stmt) = &program.body[0] else { panic!("Expected ExpressionStatement") };
+        grouped(&stmt.expression)
+    };
+    assert_eq!(parse("a || b && c | d ^ e & f == g < h << i + j * k"), "(a || (b && (c | (d ^ (e & (f == (g < (h << (i + (j * k))))))))))");
+    assert_eq!(parse("(a * b + c << d > e != f & g ^ h | i && j) ?? k"), "((((((((((a * b) + c) << d) > e) != f) & g) ^ h) | i) && j) ?? k)");
+    assert_eq!(parse("a ?? (b || c) ?? d"), "((a ?? (b || c)) ?? d)");
+    // `??` does not mix with `&&` or `||` unless one side is parenthesized
+    for source in ["a && b ?? c", "a ?? b && c", "a || b ?? c", "a ?? b || c", "a ?? b | c && d"] {
+        assert!(!Parser::new(source).parse_with_recovery().1.is_empty(), "{} should not parse", source);
+    }
+    assert_eq!(parse("a - b - c"), "((a - b) - c)");
+    assert_eq!(parse("a instanceof b in c"), "((a instanceof b) in c)");
+
+    // Each binary operator token is recorded with its `operator_str`
+    let operators = [
+        TokenKind::LogicalOr, TokenKind::NullishCoalescing, TokenKind::LogicalAnd, TokenKind::BitwiseOr,
+        TokenKind::BitwiseXor, TokenKind::BitwiseAnd, TokenKind::Equal, TokenKind::NotEqual,
+        TokenKind::StrictEqual, TokenKind::StrictNotEqual, TokenKind::LessThan, TokenKind::GreaterThan,
+        TokenKind::LessThanEqual, TokenKind::GreaterThanEqual, TokenKind::Keyword("instanceof".to_string()),
+        TokenKind::Keyword("in".to_string()), TokenKind::LeftShift, TokenKind::RightShift,
+        TokenKind::UnsignedRightShift, TokenKind::Plus, TokenKind::Minus, TokenKind::Star, TokenKind::Slash,
+        TokenKind::Percent, TokenKind::StarStar,
+    ];
+    for kind in operators {
+        assert!(kind.is_binary_operator());
+        let text = kind.operator_str().expect("binary operators have a fixed text");
+        assert_eq!(parse(&format!("a {} b", text)), format!("(a {} b)", text));
+    }
+}
@@ -6,4 +6,5 @@
 mod basic_tests;
 mod expression_tests;
 mod statement_tests;
-mod declaration_tests; 
\ No newline at end of file
+mod declaration_tests;
+mod reparse_tests; 
\ No newline at end of file
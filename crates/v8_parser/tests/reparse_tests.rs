@@ -0,0 +1,115 @@
+use v8_ast::Node;
+use v8_parser::{Edit, Parser};
+
+const SOURCE: &str = "let a = 1;\nlet b = 2;\nfunction f(x) {\n  return x + a;\n}\nfoo(a);\nlet c = b;\n\nbar(c);\n";
+
+fn program(ast: &Node) -> &v8_ast::Program {
+    match ast {
+        Node::Program(program) => program,
+        other => panic!("Expected Program, got {:?}", other),
+    }
+}
+
+/// Address of a statement's first boxed child, which moving the statement keeps
+fn child_address(statement: &Node) -> *const Node {
+    match statement {
+        Node::ExpressionStatement(stmt) => &*stmt.expression,
+        Node::VariableDeclaration(decl) => &*decl.declarations[0].id,
+        Node::FunctionDeclaration(func) => &*func.body,
+        other => panic!("Unexpected statement {:?}", other),
+    }
+}
+
+/// Apply `edit` to `source`
+fn edited(source: &str, edit: &Edit) -> String {
+    let mut text = source.to_string();
+    text.replace_range(edit.offset..edit.offset + edit.deleted, &edit.inserted);
+    text
+}
+
+fn edit(source: &str, target: &str, deleted: usize, inserted: &str) -> Edit {
+    let offset = source.find(target).expect("edit target");
+    Edit { offset, deleted, inserted: inserted.to_string() }
+}
+
+#[test]
+fn test_reparse_reuses_untouched_statements() {
+    let mut parser = Parser::new(SOURCE);
+    let (old, errors) = parser.parse_with_recovery();
+    assert!(errors.is_empty(), "{:?}", errors);
+    let old = old.unwrap();
+    let before: Vec<*const Node> = program(&old).body.iter().map(child_address).collect();
+
+    // `foo(a)` becomes `foo(a, 3)`
+    let change = edit(SOURCE, "a);", 1, "a, 3");
+    let source = edited(SOURCE, &change);
+    let (ast, errors) = parser.reparse(old, &change, &source);
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(ast, Parser::new(&source).parse().unwrap());
+
+    let after: Vec<*const Node> = program(&ast).body.iter().map(child_address).collect();
+    assert_eq!(after.len(), before.len());
+    // The edited statement and its neighbours are new; the others are the same nodes
+    for index in [0, 1, 5] {
+        assert_eq!(after[index], before[index], "statement {} was not reused", index);
+    }
+    assert_ne!(after[3], before[3]);
+}
+
+#[test]
+fn test_reparse_matches_full_parse() {
+    let edits = [
+        edit(SOURCE, "1;", 1, "10"),                 // inside a statement
+        edit(SOURCE, "foo(a);", 0, "\n\n"),          // new lines shift what follows
+        edit(SOURCE, ";\nlet c", 1, ""),             // a missing `;` is inserted again by ASI
+        edit(SOURCE, "\n\nbar", 1, "\nbaz();"),      // a new statement between two others
+        edit(SOURCE, "let a", 0, "let z = 0; "),     // before the first statement
+        Edit { offset: SOURCE.len(), deleted: 0, inserted: "last();".to_string() },
+        edit(SOURCE, "\n\nbar", 1, "   "),            // whitespace only
+        edit(SOURCE, "x + a", 5, "x +"),             // a syntax error
+        edit(SOURCE, "let b = 2;\n", 11, ""),        // a whole statement removed
+    ];
+    for change in edits {
+        let mut parser = Parser::new(SOURCE);
+        let old = parser.parse().unwrap();
+        let source = edited(SOURCE, &change);
+        let (ast, errors) = parser.reparse(old, &change, &source);
+        let (expected, expected_errors) = Parser::new(&source).parse_with_recovery();
+        assert_eq!(errors, expected_errors, "{:?}", change);
+        assert_eq!(Some(ast.clone()), expected, "{:?}", change);
+
+        // The parser is ready for the next edit
+        let next = Edit { offset: 0, deleted: 0, inserted: "x;".to_string() };
+        let next_source = edited(&source, &next);
+        let (ast, _) = parser.reparse(ast, &next, &next_source);
+        assert_eq!(Some(ast), Parser::new(&next_source).parse_with_recovery().0, "after {:?}", change);
+    }
+}
+
+#[test]
+fn test_reparse_after_length_change_shifts_reused_statements() {
+    // The first edit makes the source longer without adding lines, so the
+    // statements after it are reused and only their byte ranges move
+    let mut parser = Parser::new(SOURCE);
+    let old = parser.parse().unwrap();
+    let change = edit(SOURCE, "1;", 1, "1000");
+    let source = edited(SOURCE, &change);
+    let (ast, errors) = parser.reparse(old, &change, &source);
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(ast, Parser::new(&source).parse().unwrap());
+
+    // A second edit inside a reused statement is found at its new offset
+    for (target, deleted, inserted) in [("c);", 1, "c, 1"), ("bar", 3, "quux"), ("b;", 2, "b + 1;\nlet d;")] {
+        let mut parser = Parser::new(SOURCE);
+        let old = parser.parse().unwrap();
+        let (ast, _) = parser.reparse(old, &change, &source);
+        let function = child_address(&program(&ast).body[2]);
+        let next = edit(&source, target, deleted, inserted);
+        let next_source = edited(&source, &next);
+        let (ast, errors) = parser.reparse(ast, &next, &next_source);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(ast, Parser::new(&next_source).parse().unwrap(), "{:?}", next);
+        // Only the statements near the edit were parsed again
+        assert_eq!(child_address(&program(&ast).body[2]), function, "{:?}", next);
+    }
+}
@@ -106,4 +106,183 @@ fn test_block_statement() {
             panic!("Expected BlockStatement");
         }
     }
-} 
\ No newline at end of file
+} 
+#[test]
+fn test_switch_statement() {
+    let mut parser = Parser::new("switch (x) { case 1: y = 1; break; case 2: default: return; }");
+    let result = parser.parse_with_recovery();
+    assert!(result.1.is_empty());
+    
+    if let Some(Node::Program(program)) = result.0 {
+        if let Node::SwitchStatement(stmt) = &program.body[0] {
+            assert_eq!(stmt.cases.len(), 3);
+            assert_eq!(stmt.cases[0].consequent.len(), 2);
+            assert!(matches!(stmt.cases[0].consequent[1], Node::BreakStatement(_)));
+            assert!(stmt.cases[1].consequent.is_empty());
+            assert!(stmt.cases[2].test.is_none());
+        } else {
+            panic!("Expected SwitchStatement");
+        }
+    } else {
+        panic!("Expected Program");
+    }
+}
+
+#[test]
+fn test_for_of_statement() {
+    let mut parser = Parser::new("for (const x of items) { total = total + x; }");
+    let result = parser.parse_with_recovery();
+    assert!(result.1.is_empty());
+    
+    if let Some(Node::Program(program)) = result.0 {
+        if let Node::ForOfStatement(stmt) = &program.body[0] {
+            if let Node::VariableDeclaration(decl) = &*stmt.left {
+                assert_eq!(decl.kind, "const");
+                assert!(decl.declarations[0].init.is_none());
+            } else {
+                panic!("Expected VariableDeclaration binding");
+            }
+            assert_eq!(*stmt.right, Node::Identifier("items".to_string()));
+        } else {
+            panic!("Expected ForOfStatement");
+        }
+    } else {
+        panic!("Expected Program");
+    }
+    
+    let mut parser = Parser::new("for (let x = 1 of items) {}");
+    assert!(!parser.parse_with_recovery().1.is_empty());
+}
+
+#[test]
+fn test_for_in_statement() {
+    let mut parser = Parser::new("for (const k in o) {} for (k in a, b) {} for (let i = ('x' in o) ? 0 : 1; i < 1; i++) {}");
+    let (program, errors) = parser.parse_with_recovery();
+    assert!(errors.is_empty(), "{:?}", errors);
+    let Some(Node::Program(program)) = program else { panic!("Expected Program") };
+    let Node::ForInStatement(stmt) = &program.body[0] else { panic!("Expected ForInStatement") };
+    let Node::VariableDeclaration(decl) = stmt.left.as_ref() else { panic!("Expected VariableDeclaration binding") };
+    assert_eq!(decl.kind, "const");
+    assert_eq!(*stmt.right, Node::Identifier("o".to_string()));
+    let Node::ForInStatement(stmt) = &program.body[1] else { panic!("Expected ForInStatement") };
+    assert_eq!(*stmt.left, Node::Identifier("k".to_string()));
+    assert!(matches!(stmt.right.as_ref(), Node::SequenceExpression(_)));
+    // `in` inside parentheses is an operator again
+    assert!(matches!(program.body[2], Node::ForStatement(_)), "{:?}", program.body[2]);
+
+    for source in ["for (let x = 1 in o) {}", "for (let a, b in o) {}", "for (let i = 'x' in o; ;) {}"] {
+        assert!(!Parser::new(source).parse_with_recovery().1.is_empty(), "{}", source);
+    }
+}
+
+#[test]
+fn test_for_await_of_statement() {
+    let mut parser = Parser::new("async function f() { for await (const x of stream) {} for (const y of list) {} }");
+    let (program, errors) = parser.parse_with_recovery();
+    assert!(errors.is_empty(), "{:?}", errors);
+    let Some(Node::Program(program)) = program else { panic!("Expected Program") };
+    let Node::FunctionDeclaration(func) = &program.body[0] else { panic!("Expected FunctionDeclaration") };
+    let Node::BlockStatement(body) = func.body.as_ref() else { panic!("Expected function body") };
+    let awaits: Vec<bool> = body
+        .body
+        .iter()
+        .map(|stmt| match stmt {
+            Node::ForOfStatement(stmt) => stmt.r#await,
+            other => panic!("Expected ForOfStatement, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(awaits, [true, false]);
+
+    // Only an async function may await, and only a for...of loop
+    for source in [
+        "for await (const x of stream) {}",
+        "function f() { for await (const x of stream) {} }",
+        "async function f() { for await (let i = 0; i < 1; i++) {} }",
+    ] {
+        assert!(!Parser::new(source).parse_with_recovery().1.is_empty(), "{}", source);
+    }
+}
+
+#[test]
+fn test_statement_bodies_without_braces() {
+    let mut parser = Parser::new("if (c) x; else y;");
+    let result = parser.parse_with_recovery();
+    assert!(result.1.is_empty());
+    if let Some(Node::Program(program)) = result.0 {
+        if let Node::IfStatement(stmt) = &program.body[0] {
+            assert!(matches!(*stmt.consequent, Node::ExpressionStatement(_)));
+            assert!(matches!(stmt.alternate.as_deref(), Some(Node::ExpressionStatement(_))));
+        } else {
+            panic!("Expected IfStatement");
+        }
+    }
+    
+    let mut parser = Parser::new("while (c) doIt();");
+    let result = parser.parse_with_recovery();
+    assert!(result.1.is_empty());
+    if let Some(Node::Program(program)) = result.0 {
+        if let Node::WhileStatement(stmt) = &program.body[0] {
+            assert!(matches!(*stmt.body, Node::ExpressionStatement(_)));
+        } else {
+            panic!("Expected WhileStatement");
+        }
+    }
+    
+    let mut parser = Parser::new("for (;;) break;");
+    let result = parser.parse_with_recovery();
+    assert!(result.1.is_empty());
+    if let Some(Node::Program(program)) = result.0 {
+        if let Node::ForStatement(stmt) = &program.body[0] {
+            assert!(stmt.init.is_none() && stmt.test.is_none() && stmt.update.is_none());
+            assert!(matches!(*stmt.body, Node::BreakStatement(_)));
+        } else {
+            panic!("Expected ForStatement");
+        }
+    }
+}
+
+#[test]
+fn test_leading_brace_is_block() {
+    let mut parser = Parser::new("{}");
+    let result = parser.parse_with_recovery();
+    assert!(result.1.is_empty());
+    if let Some(Node::Program(program)) = result.0 {
+        if let Node::BlockStatement(block) = &program.body[0] {
+            assert!(block.body.is_empty());
+        } else {
+            panic!("Expected BlockStatement");
+        }
+    }
+
+    let mut parser = Parser::new("({a: 1})");
+    let result = parser.parse_with_recovery();
+    assert!(result.1.is_empty());
+    if let Some(Node::Program(program)) = result.0 {
+        if let Node::ExpressionStatement(stmt) = &program.body[0] {
+            if let Node::ObjectLiteral(object) = &*stmt.expression {
+                assert_eq!(object.properties.len(), 1);
+            } else {
+                panic!("Expected ObjectLiteral");
+            }
+        } else {
+            panic!("Expected ExpressionStatement");
+        }
+    }
+
+    // Without parentheses, `a:` is a label inside a block
+    let mut parser = Parser::new("{a: 1}");
+    let result = parser.parse_with_recovery();
+    assert!(result.1.is_empty());
+    if let Some(Node::Program(program)) = result.0 {
+        if let Node::BlockStatement(block) = &program.body[0] {
+            if let Node::LabeledStatement(labeled) = &block.body[0] {
+                assert_eq!(*labeled.label, Node::Identifier("a".to_string()));
+                assert!(matches!(*labeled.body, Node::ExpressionStatement(_)));
+            } else {
+                panic!("Expected LabeledStatement");
+            }
+        } else {
+            panic!("Expected BlockStatement");
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Array constructor for V8-Rust JavaScript engine
+//!
+//! This module provides the `Array` global and its static methods.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use super::elements::Elements;
+use super::function::{Function, Host, NativeFunction};
+use super::iterator::{builtin_iterator_method, define_method, iterator_key};
+use super::object::Object;
+use super::value::Value;
+
+/// Create the `Array` global with its static methods
+pub fn create_array_constructor() -> Rc<RefCell<Function>> {
+    let constructor = Function::native_method("Array", array_constructor, 1);
+    {
+        let mut statics = constructor.properties.borrow_mut();
+        let functions: [(&str, NativeFunction); 2] = [("isArray", is_array), ("of", of)];
+        for (name, function) in functions {
+            let mut function = Function::native(name, function);
+            function.length = if name == "isArray" { 1 } else { 0 };
+            define_method(&mut statics, name.to_string(), Value::Function(Rc::new(RefCell::new(function))));
+        }
+        let from = Function::host_method("from", from, 1);
+        define_method(&mut statics, "from".to_string(), Value::Function(Rc::new(RefCell::new(from))));
+    }
+    Rc::new(RefCell::new(constructor))
+}
+
+/// `Array(...items)`, with or without `new`
+///
+/// A single number argument is the length of the new array rather than
+/// its only element, and must be an integer in the 32-bit unsigned range.
+/// The new array holds only holes.
+pub fn array_constructor(_this: Value, args: &[Value]) -> Result<Value, String> {
+    match args {
+        [Value::Number(length)] => {
+            if !(0.0..=u32::MAX as f64).contains(length) || length.fract() != 0.0 {
+                return Err("RangeError: Invalid array length".to_string());
+            }
+            Ok(Value::Array(Rc::new(RefCell::new(Elements::with_length(*length as usize)))))
+        }
+        items => Ok(Value::new_array(items.to_vec())),
+    }
+}
+
+/// `Array.isArray(value)`
+pub fn is_array(args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(args.first().is_some_and(Value::is_array)))
+}
+
+/// `Array.of(...items)`: the arguments as an array, even a single number
+pub fn of(args: &[Value]) -> Result<Value, String> {
+    Ok(Value::new_array(args.to_vec()))
+}
+
+/// `Array.from(items, mapFn?)`
+///
+/// Iterable items are read through their `Symbol.iterator` method; other
+/// objects are read as array-likes, from index 0 up to their `length`.
+/// `mapFn` is called with each element and its index.
+pub fn from(host: &mut dyn Host, _this: Value, args: &[Value]) -> Result<Value, Value> {
+    let items = args.first().cloned().unwrap_or(Value::Undefined);
+    let map = match args.get(1) {
+        None | Some(Value::Undefined) => None,
+        Some(function @ Value::Function(_)) => Some(function.clone()),
+        Some(other) => return Err(format!("TypeError: {} is not a function", other).into()),
+    };
+    let elements = match iterator_method(host, &items)? {
+        Some(method) => {
+            let iterator = host.call(&method, items.clone(), &[])?;
+            let mut elements = Vec::new();
+            while let Some(element) = iterator_step(host, &iterator)? {
+                elements.push(element);
+            }
+            elements
+        }
+        None => array_like_elements(host, &items)?,
+    };
+    let elements = match map {
+        Some(map) => elements
+            .into_iter()
+            .enumerate()
+            .map(|(index, element)| host.call(&map, Value::Undefined, &[element, Value::Number(index as f64)]))
+            .collect::<Result<_, _>>()?,
+        None => elements,
+    };
+    Ok(Value::new_array(elements))
+}
+
+/// The `Symbol.iterator` method of `value`, if it has one
+fn iterator_method(host: &mut dyn Host, value: &Value) -> Result<Option<Value>, Value> {
+    let method = match value {
+        Value::Null | Value::Undefined => return Err(format!("TypeError: {} is not iterable", value).into()),
+        Value::Object(object) => Object::get(object, &iterator_key(), value.clone(), host)?,
+        value => builtin_iterator_method(value),
+    };
+    Ok(method.filter(|method| matches!(method, Value::Function(_))))
+}
+
+/// Call the iterator's `next` method; `None` once it reports `done`
+fn iterator_step(host: &mut dyn Host, iterator: &Value) -> Result<Option<Value>, Value> {
+    let Value::Object(object) = iterator else {
+        return Err(format!("TypeError: Result of the Symbol.iterator method is not an object: {}", iterator).into());
+    };
+    let next = Object::get(object, "next", iterator.clone(), host)?.unwrap_or(Value::Undefined);
+    let result = host.call(&next, iterator.clone(), &[])?;
+    let Value::Object(result_object) = &result else {
+        return Err(format!("TypeError: Iterator result {} is not an object", result).into());
+    };
+    let done = Object::get(result_object, "done", result.clone(), host)?.is_some_and(|done| done.to_boolean());
+    if done {
+        Ok(None)
+    } else {
+        Ok(Some(Object::get(result_object, "value", result.clone(), host)?.unwrap_or(Value::Undefined)))
+    }
+}
+
+/// Elements `0..length` of an array-like object; other values have none
+fn array_like_elements(host: &mut dyn Host, value: &Value) -> Result<Vec<Value>, Value> {
+    let Value::Object(object) = value else { return Ok(Vec::new()) };
+    let length = Object::get(object, "length", value.clone(), host)?.map_or(0.0, |length| length.to_number());
+    let length = if length.is_nan() || length <= 0.0 { 0 } else { length.min(u32::MAX as f64) as usize };
+    (0..length)
+        .map(|index| Ok(Object::get(object, &index.to_string(), value.clone(), host)?.unwrap_or(Value::Undefined)))
+        .collect()
+}
@@ -0,0 +1,274 @@
+//! Keyed collections for V8-Rust JavaScript engine
+//!
+//! This module provides the `Set` and `Map` constructors. Elements are
+//! kept in insertion order and compared with SameValueZero.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use super::function::{argument, Function, NativeMethod};
+use super::iterator::{collect_builtin, create_list_iterator, define_method, iterator_key, method};
+use super::object::{InternalSlot, IterationKind, Object, PropertyDescriptor};
+use super::value::Value;
+
+/// Create the `Set` constructor with its prototype methods
+pub fn create_set_constructor() -> Rc<RefCell<Function>> {
+    let constructor = Function::native_method("Set", set_constructor, 0);
+    {
+        let mut prototype = constructor.prototype.borrow_mut();
+        let methods: [(&str, NativeMethod, usize); 5] = [
+            ("add", set_add, 1),
+            ("has", set_has, 1),
+            ("delete", set_delete, 1),
+            ("clear", clear, 0),
+            ("values", set_values, 0),
+        ];
+        for (name, function, length) in methods {
+            define_method(&mut prototype, name.to_string(), method(name, function, length));
+        }
+        define_method(&mut prototype, "keys".to_string(), method("values", set_values, 0));
+        define_method(&mut prototype, iterator_key(), method("values", set_values, 0));
+    }
+    Rc::new(RefCell::new(constructor))
+}
+
+/// Create the `Map` constructor with its prototype methods
+pub fn create_map_constructor() -> Rc<RefCell<Function>> {
+    let constructor = Function::native_method("Map", map_constructor, 0);
+    {
+        let mut prototype = constructor.prototype.borrow_mut();
+        let methods: [(&str, NativeMethod, usize); 8] = [
+            ("get", map_get, 1),
+            ("set", map_set, 2),
+            ("has", map_has, 1),
+            ("delete", map_delete, 1),
+            ("clear", clear, 0),
+            ("keys", map_keys, 0),
+            ("values", map_values, 0),
+            ("entries", map_entries, 0),
+        ];
+        for (name, function, length) in methods {
+            define_method(&mut prototype, name.to_string(), method(name, function, length));
+        }
+        define_method(&mut prototype, iterator_key(), method("entries", map_entries, 0));
+    }
+    Rc::new(RefCell::new(constructor))
+}
+
+/// SameValueZero: strict equality, except that NaN equals NaN
+fn same_value_zero(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) if a.is_nan() && b.is_nan() => true,
+        _ => a.strict_equals(b),
+    }
+}
+
+/// The receiver as an object, when it has the expected internal slot
+fn receiver(this: &Value, name: &str, is_expected: fn(&InternalSlot) -> bool) -> Result<Rc<RefCell<Object>>, String> {
+    match this {
+        Value::Object(object) if object.borrow().internal.as_ref().is_some_and(is_expected) => Ok(object.clone()),
+        other => Err(format!("TypeError: Method {} called on incompatible receiver {}", name, other)),
+    }
+}
+
+fn is_set(slot: &InternalSlot) -> bool {
+    matches!(slot, InternalSlot::SetData(_))
+}
+
+fn is_map(slot: &InternalSlot) -> bool {
+    matches!(slot, InternalSlot::MapData(_))
+}
+
+fn is_collection(slot: &InternalSlot) -> bool {
+    is_set(slot) || is_map(slot)
+}
+
+/// Keep the `size` property in step with the collection's contents
+///
+/// Objects have no accessor properties yet, so `size` is a read-only data
+/// property rewritten by every mutation.
+fn update_size(object: &mut Object) {
+    let size = match &object.internal {
+        Some(InternalSlot::SetData(elements)) => elements.len(),
+        Some(InternalSlot::MapData(entries)) => entries.len(),
+        _ => return,
+    };
+    object.define_property(
+        "size".to_string(),
+        PropertyDescriptor::data_descriptor(Value::Number(size as f64), false, false, true),
+    );
+}
+
+/// Initialize `this` as a collection filled from an optional iterable
+fn initialize(this: &Value, name: &str, args: &[Value], slot: InternalSlot) -> Result<Value, String> {
+    let object = match this {
+        Value::Object(object) if object.borrow().internal.is_none() => object.clone(),
+        _ => return Err(format!("TypeError: Constructor {} requires 'new'", name)),
+    };
+    object.borrow_mut().internal = Some(slot);
+    update_size(&mut object.borrow_mut());
+    let iterable = argument(args, 0);
+    if matches!(iterable, Value::Undefined | Value::Null) {
+        return Ok(Value::Undefined);
+    }
+    let elements = collect_builtin(&iterable).ok_or_else(|| format!("TypeError: {} is not iterable", iterable))?;
+    for element in elements {
+        if name == "Set" {
+            set_add(this.clone(), &[element])?;
+        } else {
+            let entry = match &element {
+                Value::Array(entry) => entry.borrow().to_vec(),
+                other => return Err(format!("TypeError: Iterator value {} is not an entry object", other)),
+            };
+            map_set(this.clone(), &entry)?;
+        }
+    }
+    Ok(Value::Undefined)
+}
+
+/// `new Set(iterable)`
+pub fn set_constructor(this: Value, args: &[Value]) -> Result<Value, String> {
+    initialize(&this, "Set", args, InternalSlot::SetData(Vec::new()))
+}
+
+/// `Set.prototype.add(value)`
+pub fn set_add(this: Value, args: &[Value]) -> Result<Value, String> {
+    let object = receiver(&this, "Set.prototype.add", is_set)?;
+    let value = argument(args, 0);
+    let mut object = object.borrow_mut();
+    if let Some(InternalSlot::SetData(elements)) = &mut object.internal {
+        if !elements.iter().any(|element| same_value_zero(element, &value)) {
+            elements.push(value);
+        }
+    }
+    update_size(&mut object);
+    Ok(this)
+}
+
+/// `Set.prototype.has(value)`
+pub fn set_has(this: Value, args: &[Value]) -> Result<Value, String> {
+    let object = receiver(&this, "Set.prototype.has", is_set)?;
+    let value = argument(args, 0);
+    let object = object.borrow();
+    let found = match &object.internal {
+        Some(InternalSlot::SetData(elements)) => elements.iter().any(|element| same_value_zero(element, &value)),
+        _ => false,
+    };
+    Ok(Value::Boolean(found))
+}
+
+/// `Set.prototype.delete(value)`
+pub fn set_delete(this: Value, args: &[Value]) -> Result<Value, String> {
+    let object = receiver(&this, "Set.prototype.delete", is_set)?;
+    let value = argument(args, 0);
+    let mut object = object.borrow_mut();
+    let mut deleted = false;
+    if let Some(InternalSlot::SetData(elements)) = &mut object.internal {
+        if let Some(index) = elements.iter().position(|element| same_value_zero(element, &value)) {
+            elements.remove(index);
+            deleted = true;
+        }
+    }
+    update_size(&mut object);
+    Ok(Value::Boolean(deleted))
+}
+
+/// `Set.prototype.values()`, also `keys` and `[Symbol.iterator]`
+pub fn set_values(this: Value, _args: &[Value]) -> Result<Value, String> {
+    receiver(&this, "Set.prototype.values", is_set)?;
+    Ok(create_list_iterator(this, IterationKind::Values))
+}
+
+/// `Set.prototype.clear()` and `Map.prototype.clear()`
+pub fn clear(this: Value, _args: &[Value]) -> Result<Value, String> {
+    let object = receiver(&this, "clear", is_collection)?;
+    let mut object = object.borrow_mut();
+    match &mut object.internal {
+        Some(InternalSlot::SetData(elements)) => elements.clear(),
+        Some(InternalSlot::MapData(entries)) => entries.clear(),
+        _ => {}
+    }
+    update_size(&mut object);
+    Ok(Value::Undefined)
+}
+
+/// `new Map(iterable)`
+pub fn map_constructor(this: Value, args: &[Value]) -> Result<Value, String> {
+    initialize(&this, "Map", args, InternalSlot::MapData(Vec::new()))
+}
+
+/// `Map.prototype.get(key)`
+pub fn map_get(this: Value, args: &[Value]) -> Result<Value, String> {
+    let object = receiver(&this, "Map.prototype.get", is_map)?;
+    let key = argument(args, 0);
+    let object = object.borrow();
+    let value = match &object.internal {
+        Some(InternalSlot::MapData(entries)) => entries
+            .iter()
+            .find(|(existing, _)| same_value_zero(existing, &key))
+            .map(|(_, value)| value.clone()),
+        _ => None,
+    };
+    Ok(value.unwrap_or(Value::Undefined))
+}
+
+/// `Map.prototype.set(key, value)`
+pub fn map_set(this: Value, args: &[Value]) -> Result<Value, String> {
+    let object = receiver(&this, "Map.prototype.set", is_map)?;
+    let key = argument(args, 0);
+    let value = args.get(1).cloned().unwrap_or(Value::Undefined);
+    let mut object = object.borrow_mut();
+    if let Some(InternalSlot::MapData(entries)) = &mut object.internal {
+        match entries.iter_mut().find(|(existing, _)| same_value_zero(existing, &key)) {
+            Some(entry) => entry.1 = value,
+            None => entries.push((key, value)),
+        }
+    }
+    update_size(&mut object);
+    Ok(this)
+}
+
+/// `Map.prototype.has(key)`
+pub fn map_has(this: Value, args: &[Value]) -> Result<Value, String> {
+    let object = receiver(&this, "Map.prototype.has", is_map)?;
+    let key = argument(args, 0);
+    let object = object.borrow();
+    let found = match &object.internal {
+        Some(InternalSlot::MapData(entries)) => entries.iter().any(|(existing, _)| same_value_zero(existing, &key)),
+        _ => false,
+    };
+    Ok(Value::Boolean(found))
+}
+
+/// `Map.prototype.delete(key)`
+pub fn map_delete(this: Value, args: &[Value]) -> Result<Value, String> {
+    let object = receiver(&this, "Map.prototype.delete", is_map)?;
+    let key = argument(args, 0);
+    let mut object = object.borrow_mut();
+    let mut deleted = false;
+    if let Some(InternalSlot::MapData(entries)) = &mut object.internal {
+        if let Some(index) = entries.iter().position(|(existing, _)| same_value_zero(existing, &key)) {
+            entries.remove(index);
+            deleted = true;
+        }
+    }
+    update_size(&mut object);
+    Ok(Value::Boolean(deleted))
+}
+
+/// `Map.prototype.keys()`
+pub fn map_keys(this: Value, _args: &[Value]) -> Result<Value, String> {
+    receiver(&this, "Map.prototype.keys", is_map)?;
+    Ok(create_list_iterator(this, IterationKind::Keys))
+}
+
+/// `Map.prototype.values()`
+pub fn map_values(this: Value, _args: &[Value]) -> Result<Value, String> {
+    receiver(&this, "Map.prototype.values", is_map)?;
+    Ok(create_list_iterator(this, IterationKind::Values))
+}
+
+/// `Map.prototype.entries()`, also `[Symbol.iterator]`
+pub fn map_entries(this: Value, _args: &[Value]) -> Result<Value, String> {
+    receiver(&this, "Map.prototype.entries", is_map)?;
+    Ok(create_list_iterator(this, IterationKind::Entries))
+}
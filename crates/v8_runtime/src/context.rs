@@ -2,43 +2,92 @@
 //! 
 //! This module provides the execution context for JavaScript code.
 
-use std::collections::HashMap;
 use std::rc::Rc;
 use std::cell::RefCell;
 use super::value::Value;
 use super::object::Object;
+use super::environment::Environment;
 
 /// Execution context
 #[derive(Debug, Clone)]
 pub struct Context {
     pub global_object: Rc<RefCell<Object>>,
-    pub variables: HashMap<String, Value>,
+    pub global_environment: Rc<RefCell<Environment>>,
+    pub script_environment: Rc<RefCell<Environment>>, // Top-level bindings, shared by every script
+    pub environment: Rc<RefCell<Environment>>,
     pub this_value: Value,
 }
 
 impl Context {
     /// Create a new execution context
+    ///
+    /// The global object gets a `globalThis` property referring to itself.
     pub fn new(global_object: Rc<RefCell<Object>>) -> Self {
+        let this = Value::Object(global_object.clone());
+        global_object.borrow_mut().set_property("globalThis".to_string(), this);
+        let global_environment = Rc::new(RefCell::new(Environment::new_object(global_object.clone(), None)));
+        let script_environment = Environment::new_declarative(Some(global_environment.clone()));
         Self {
             global_object,
-            variables: HashMap::new(),
+            environment: global_environment.clone(),
+            global_environment,
+            script_environment: Rc::new(RefCell::new(script_environment)),
             this_value: Value::Undefined,
         }
     }
     
+    /// Enter a new declarative scope (function or block)
+    pub fn push_scope(&mut self) {
+        let scope = Environment::new_declarative(Some(self.environment.clone()));
+        self.environment = Rc::new(RefCell::new(scope));
+    }
+    
+    /// Leave the current scope, returning to its outer environment
+    pub fn pop_scope(&mut self) {
+        let outer = self.environment.borrow().outer.clone();
+        if let Some(outer) = outer {
+            self.environment = outer;
+        }
+    }
+    
+    /// Declare a binding in the current scope
+    pub fn declare_variable(&mut self, name: String, value: Value, mutable: bool) -> Result<(), String> {
+        self.environment.borrow_mut().declare(name, value, mutable)
+    }
+    
+    /// The scope that `var` and function declarations of the current scope bind in
+    ///
+    /// At the top level of a script this is the global object, so
+    /// `var x` is also `globalThis.x`; `let` and `const` stay in the
+    /// script scope.
+    pub fn var_environment(&self) -> Rc<RefCell<Environment>> {
+        if Rc::ptr_eq(&self.environment, &self.script_environment) {
+            self.global_environment.clone()
+        } else {
+            self.environment.clone()
+        }
+    }
+    
     /// Set a variable in the context
-    pub fn set_variable(&mut self, name: String, value: Value) {
-        self.variables.insert(name, value);
+    ///
+    /// Assigns to the nearest existing binding; an unresolved name becomes a
+    /// property of the global object.
+    pub fn set_variable(&mut self, name: String, value: Value) -> Result<(), String> {
+        let assigned = self.environment.borrow_mut().set(&name, value.clone())?;
+        if !assigned {
+            self.global_object.borrow_mut().set_property(name, value);
+        }
+        Ok(())
     }
     
     /// Get a variable from the context
     pub fn get_variable(&self, name: &str) -> Option<Value> {
-        self.variables.get(name).cloned()
+        self.environment.borrow().get(name)
     }
     
     /// Check if a variable exists in the context
     pub fn has_variable(&self, name: &str) -> bool {
-        self.variables.contains_key(name)
+        self.environment.borrow().has(name)
     }
     
     /// Set the this value
@@ -55,4 +104,4 @@ impl Context {
     pub fn get_global_object(&self) -> Rc<RefCell<Object>> {
         self.global_object.clone()
     }
-} 
\ No newline at end of file
+}
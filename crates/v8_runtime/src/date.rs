@@ -0,0 +1,131 @@
+//! Date objects for V8-Rust JavaScript engine
+//! 
+//! This module provides the `Date` built-in, backed by a time value in
+//! milliseconds since the Unix epoch (UTC).
+
+use super::value::Value;
+
+const MS_PER_SECOND: f64 = 1000.0;
+const MS_PER_MINUTE: f64 = 60_000.0;
+const MS_PER_HOUR: f64 = 3_600_000.0;
+const MS_PER_DAY: f64 = 86_400_000.0;
+
+/// Largest magnitude of a valid time value (±100,000,000 days)
+const MAX_TIME: f64 = 8.64e15;
+
+/// JavaScript Date
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Date {
+    /// Milliseconds since the epoch, or NaN for an invalid date
+    pub time: f64,
+}
+
+impl Date {
+    /// Create a date from a time value, clipping it as `TimeClip` does
+    pub fn from_time(time: f64) -> Self {
+        let time = if !time.is_finite() || time.abs() > MAX_TIME {
+            f64::NAN
+        } else {
+            time.trunc() + 0.0
+        };
+        Self { time }
+    }
+    
+    /// `new Date(...)` given the current time for the no-argument form
+    pub fn construct(args: &[Value], now: f64) -> Self {
+        match args.first() {
+            None => Self::from_time(now),
+            Some(value) => Self::from_time(value.to_number()),
+        }
+    }
+    
+    /// Check if the date holds a valid time value
+    pub fn is_valid(&self) -> bool {
+        !self.time.is_nan()
+    }
+    
+    /// `Date.prototype.getTime`
+    pub fn get_time(&self) -> f64 {
+        self.time
+    }
+    
+    /// `Date.prototype.getFullYear`
+    pub fn get_full_year(&self) -> f64 {
+        self.civil().map_or(f64::NAN, |(year, _, _)| year as f64)
+    }
+    
+    /// `Date.prototype.getMonth` (0-based)
+    pub fn get_month(&self) -> f64 {
+        self.civil().map_or(f64::NAN, |(_, month, _)| (month - 1) as f64)
+    }
+    
+    /// `Date.prototype.getDate` (day of the month)
+    pub fn get_date(&self) -> f64 {
+        self.civil().map_or(f64::NAN, |(_, _, day)| day as f64)
+    }
+    
+    /// `Date.prototype.getHours`
+    pub fn get_hours(&self) -> f64 {
+        (self.time_in_day() / MS_PER_HOUR).floor()
+    }
+    
+    /// `Date.prototype.getMinutes`
+    pub fn get_minutes(&self) -> f64 {
+        (self.time_in_day() / MS_PER_MINUTE).floor() % 60.0
+    }
+    
+    /// `Date.prototype.getSeconds`
+    pub fn get_seconds(&self) -> f64 {
+        (self.time_in_day() / MS_PER_SECOND).floor() % 60.0
+    }
+    
+    /// `Date.prototype.getMilliseconds`
+    pub fn get_milliseconds(&self) -> f64 {
+        self.time_in_day() % MS_PER_SECOND
+    }
+    
+    /// `Date.prototype.toISOString`
+    pub fn to_iso_string(&self) -> Result<String, String> {
+        let (year, month, day) = self
+            .civil()
+            .ok_or_else(|| "RangeError: Invalid time value".to_string())?;
+        let year = if (0..=9999).contains(&year) {
+            format!("{:04}", year)
+        } else {
+            format!("{}{:06}", if year < 0 { '-' } else { '+' }, year.abs())
+        };
+        Ok(format!(
+            "{}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+            year,
+            month,
+            day,
+            self.get_hours(),
+            self.get_minutes(),
+            self.get_seconds(),
+            self.get_milliseconds()
+        ))
+    }
+    
+    /// Milliseconds elapsed since midnight (NaN for an invalid date)
+    fn time_in_day(&self) -> f64 {
+        self.time.rem_euclid(MS_PER_DAY)
+    }
+    
+    /// Year, month (1-12) and day (1-31) of the date in UTC
+    fn civil(&self) -> Option<(i64, i64, i64)> {
+        if !self.is_valid() {
+            return None;
+        }
+        // Days-to-civil conversion over 400-year eras (proleptic Gregorian)
+        let days = (self.time / MS_PER_DAY).floor() as i64 + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        Some((year, month, day))
+    }
+}
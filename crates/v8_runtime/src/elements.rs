@@ -0,0 +1,134 @@
+//! Array element storage for V8-Rust JavaScript engine
+//!
+//! Elements live in a dense vector while they are written close to its
+//! end; an index far past it goes to a sparse map instead, so a large index
+//! or `length` never allocates storage for the gap. Indices that were never
+//! written, or were deleted, are holes: absent keys that read as
+//! `undefined`.
+
+use std::collections::BTreeMap;
+
+/// Holes the dense storage may grow over before a write goes to the sparse map
+const MAX_DENSE_GAP: usize = 1024;
+
+/// The elements of an array and its `length`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Elements<V> {
+    dense: Vec<Option<V>>,     // Indices `0..dense.len()`; `None` is a hole
+    sparse: BTreeMap<usize, V>, // Indices at or past `dense.len()`
+    length: usize,
+}
+
+impl<V> Default for Elements<V> {
+    fn default() -> Self {
+        Self { dense: Vec::new(), sparse: BTreeMap::new(), length: 0 }
+    }
+}
+
+impl<V> From<Vec<V>> for Elements<V> {
+    fn from(values: Vec<V>) -> Self {
+        let length = values.len();
+        Self { dense: values.into_iter().map(Some).collect(), sparse: BTreeMap::new(), length }
+    }
+}
+
+impl<V> Elements<V> {
+    /// An array of `length` holes
+    pub fn with_length(length: usize) -> Self {
+        Self { length, ..Self::default() }
+    }
+
+    /// The array's `length`: one past the highest index, or as last set
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// The element at `index`, or `None` for a hole
+    pub fn get(&self, index: usize) -> Option<&V> {
+        match self.dense.get(index) {
+            Some(slot) => slot.as_ref(),
+            None => self.sparse.get(&index),
+        }
+    }
+
+    /// Whether `index` holds an element rather than a hole
+    pub fn has(&self, index: usize) -> bool {
+        self.get(index).is_some()
+    }
+
+    /// Write the element at `index`, growing `length` past it if needed
+    pub fn set(&mut self, index: usize, value: V) {
+        if index < self.dense.len() {
+            self.dense[index] = Some(value);
+        } else if self.sparse.is_empty() && index - self.dense.len() <= MAX_DENSE_GAP {
+            self.dense.resize_with(index, || None);
+            self.dense.push(Some(value));
+        } else {
+            self.sparse.insert(index, value);
+        }
+        self.length = self.length.max(index + 1);
+    }
+
+    /// Append an element at `length`
+    pub fn push(&mut self, value: V) {
+        self.set(self.length, value);
+    }
+
+    /// Remove the last element, shortening the array by one
+    pub fn pop(&mut self) -> Option<V> {
+        let last = self.length.checked_sub(1)?;
+        let value = self.remove(last);
+        self.set_len(last);
+        value
+    }
+
+    /// Turn the element at `index` into a hole, keeping `length`
+    pub fn remove(&mut self, index: usize) -> Option<V> {
+        match self.dense.get_mut(index) {
+            Some(slot) => slot.take(),
+            None => self.sparse.remove(&index),
+        }
+    }
+
+    /// Set `length`: shortening drops the elements past it, growing adds holes
+    pub fn set_len(&mut self, length: usize) {
+        if length < self.length {
+            self.dense.truncate(length);
+            self.sparse.split_off(&length);
+        }
+        self.length = length;
+    }
+
+    /// Every index from 0 to `length`, with `None` for holes
+    pub fn iter(&self) -> impl Iterator<Item = Option<&V>> + '_ {
+        (0..self.length).map(|index| self.get(index))
+    }
+
+    /// The indices holding elements, in ascending order
+    pub fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        let dense = self.dense.iter().enumerate().filter(|(_, slot)| slot.is_some()).map(|(index, _)| index);
+        dense.chain(self.sparse.keys().copied())
+    }
+
+    /// The elements, skipping holes, in index order
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.dense.iter().flatten().chain(self.sparse.values())
+    }
+}
+
+impl<V: Clone + Default> Elements<V> {
+    /// The elements in order, with holes read as the default value
+    pub fn to_vec(&self) -> Vec<V> {
+        self.iter().map(|value| value.cloned().unwrap_or_default()).collect()
+    }
+}
+
+impl<V: PartialEq> PartialEq<Vec<V>> for Elements<V> {
+    fn eq(&self, other: &Vec<V>) -> bool {
+        self.length == other.len() && self.iter().zip(other).all(|(value, other)| value == Some(other))
+    }
+}
@@ -0,0 +1,127 @@
+//! Lexical environments for V8-Rust JavaScript engine
+//! 
+//! This module provides environment records and the environment chain
+//! used to resolve identifiers.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::cell::RefCell;
+use super::value::Value;
+use super::object::Object;
+
+/// A binding in a declarative environment record
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub value: Value,
+    pub mutable: bool,
+}
+
+/// Storage for the bindings of an environment
+#[derive(Debug, Clone)]
+pub enum EnvironmentRecord {
+    /// Bindings created by `let`/`const`/`var`, functions and parameters
+    Declarative(HashMap<String, Binding>),
+    /// Bindings backed by the properties of an object (e.g. the global object)
+    Object(Rc<RefCell<Object>>),
+}
+
+/// A lexical environment: a record plus a link to the outer environment
+#[derive(Debug, Clone)]
+pub struct Environment {
+    pub record: EnvironmentRecord,
+    pub outer: Option<Rc<RefCell<Environment>>>,
+}
+
+impl Environment {
+    /// Create a new declarative environment
+    pub fn new_declarative(outer: Option<Rc<RefCell<Environment>>>) -> Self {
+        Self {
+            record: EnvironmentRecord::Declarative(HashMap::new()),
+            outer,
+        }
+    }
+    
+    /// Create a new object environment backed by the given object
+    pub fn new_object(object: Rc<RefCell<Object>>, outer: Option<Rc<RefCell<Environment>>>) -> Self {
+        Self {
+            record: EnvironmentRecord::Object(object),
+            outer,
+        }
+    }
+    
+    /// Check if this environment (not its outer chain) has a binding
+    pub fn has_own_binding(&self, name: &str) -> bool {
+        match &self.record {
+            EnvironmentRecord::Declarative(bindings) => bindings.contains_key(name),
+            EnvironmentRecord::Object(object) => object.borrow().has_property(name),
+        }
+    }
+    
+    /// Remove a binding from this environment (not its outer chain)
+    pub fn remove_binding(&mut self, name: &str) -> bool {
+        match &mut self.record {
+            EnvironmentRecord::Declarative(bindings) => bindings.remove(name).is_some(),
+            EnvironmentRecord::Object(object) => object.borrow_mut().delete_property(name),
+        }
+    }
+    
+    /// Create a binding in this environment
+    pub fn declare(&mut self, name: String, value: Value, mutable: bool) -> Result<(), String> {
+        match &mut self.record {
+            EnvironmentRecord::Declarative(bindings) => {
+                if bindings.contains_key(&name) {
+                    return Err(format!("SyntaxError: Identifier '{}' has already been declared", name));
+                }
+                bindings.insert(name, Binding { value, mutable });
+            }
+            EnvironmentRecord::Object(object) => {
+                object.borrow_mut().set_property(name, value);
+            }
+        }
+        Ok(())
+    }
+    
+    /// Resolve a binding through the environment chain
+    pub fn get(&self, name: &str) -> Option<Value> {
+        let own = match &self.record {
+            EnvironmentRecord::Declarative(bindings) => bindings.get(name).map(|b| b.value.clone()),
+            EnvironmentRecord::Object(object) => object.borrow().get_property(name),
+        };
+        match own {
+            Some(value) => Some(value),
+            None => self.outer.as_ref().and_then(|outer| outer.borrow().get(name)),
+        }
+    }
+    
+    /// Check if a binding is resolvable through the environment chain
+    pub fn has(&self, name: &str) -> bool {
+        self.has_own_binding(name) || self.outer.as_ref().is_some_and(|outer| outer.borrow().has(name))
+    }
+    
+    /// Assign to an existing binding through the environment chain
+    ///
+    /// Returns `Ok(false)` if no environment in the chain has the binding.
+    pub fn set(&mut self, name: &str, value: Value) -> Result<bool, String> {
+        match &mut self.record {
+            EnvironmentRecord::Declarative(bindings) => {
+                if let Some(binding) = bindings.get_mut(name) {
+                    if !binding.mutable {
+                        return Err("TypeError: Assignment to constant variable.".to_string());
+                    }
+                    binding.value = value;
+                    return Ok(true);
+                }
+            }
+            EnvironmentRecord::Object(object) => {
+                if object.borrow().has_property(name) {
+                    object.borrow_mut().set_property(name.to_string(), value);
+                    return Ok(true);
+                }
+            }
+        }
+        match &self.outer {
+            Some(outer) => outer.borrow_mut().set(name, value),
+            None => Ok(false),
+        }
+    }
+}
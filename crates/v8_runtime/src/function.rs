@@ -2,28 +2,76 @@
 //! 
 //! This module provides the core function system for JavaScript functions.
 
-use std::collections::HashMap;
 use std::rc::Rc;
 use std::cell::RefCell;
+use v8_ast::Node;
 use super::value::Value;
 use super::object::Object;
+use super::environment::Environment;
+use super::runtime::Microtask;
 
 /// Function type
 #[derive(Debug, Clone)]
 pub enum FunctionType {
     Native(NativeFunction),
+    NativeMethod(NativeMethod),
+    HostMethod(HostMethod),
     User(UserFunction),
+    Bound(BoundFunction),
 }
 
 /// Native function (built-in)
 pub type NativeFunction = fn(&[Value]) -> Result<Value, String>;
 
+/// Native function that receives `this` (built-in method or constructor)
+pub type NativeMethod = fn(Value, &[Value]) -> Result<Value, String>;
+
+/// Argument `index` of a native call, or `undefined` when it was not passed
+pub(crate) fn argument(args: &[Value], index: usize) -> Value {
+    args.get(index).cloned().unwrap_or(Value::Undefined)
+}
+
+/// Native function that calls back into the engine running it
+///
+/// Its error is the thrown value, so a value thrown by a script function it
+/// called passes through intact.
+pub type HostMethod = fn(&mut dyn Host, Value, &[Value]) -> Result<Value, Value>;
+
+/// Services the engine provides to host methods
+///
+/// Errors are the values the call threw.
+pub trait Host {
+    /// Call any function value, including user-defined functions
+    fn call(&mut self, function: &Value, this: Value, args: &[Value]) -> Result<Value, Value>;
+
+    /// Call a function value as a constructor, like the `new` operator
+    fn construct(&mut self, function: &Value, args: &[Value]) -> Result<Value, Value>;
+
+    /// Queue a job to run after the current script
+    fn enqueue_microtask(&mut self, task: Microtask);
+
+    /// Remember a new `FinalizationRegistry`, so `Runtime::collect_garbage`
+    /// can queue its cleanup callbacks
+    ///
+    /// Hosts that do not track registries never run those callbacks, which
+    /// the language allows.
+    fn track_finalization_registry(&mut self, _registry: &Rc<RefCell<Object>>) {}
+}
+
 /// User-defined function
 #[derive(Debug, Clone)]
 pub struct UserFunction {
     pub params: Vec<String>,
-    pub body: String, // For now, just store as string
-    pub scope: Rc<RefCell<HashMap<String, Value>>>,
+    pub body: Rc<Node>, // Function body (block statement or arrow expression)
+    pub scope: Option<Rc<RefCell<Environment>>>, // Environment captured at creation
+}
+
+/// Function created by `Function.prototype.bind`
+#[derive(Debug, Clone)]
+pub struct BoundFunction {
+    pub target: Rc<RefCell<Function>>,
+    pub this: Value,       // Fixed `this`, which later calls cannot override
+    pub args: Vec<Value>,  // Leading arguments prepended to every call
 }
 
 /// JavaScript function
@@ -32,6 +80,7 @@ pub struct Function {
     pub name: String,
     pub function_type: FunctionType,
     pub prototype: Rc<RefCell<Object>>,
+    pub properties: Rc<RefCell<Object>>, // Own properties, such as static methods
     pub length: usize,
 }
 
@@ -42,33 +91,83 @@ impl Function {
             name: name.to_string(),
             function_type: FunctionType::Native(func),
             prototype: Rc::new(RefCell::new(Object::new())),
+            properties: Rc::new(RefCell::new(Object::new())),
             length: 0, // Will be set based on function signature
         }
     }
     
-    /// Create a new user function
-    pub fn user(name: &str, params: Vec<String>, body: String) -> Self {
+    /// Create a new native function that receives `this`
+    pub fn native_method(name: &str, func: NativeMethod, length: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            function_type: FunctionType::NativeMethod(func),
+            prototype: Rc::new(RefCell::new(Object::new())),
+            properties: Rc::new(RefCell::new(Object::new())),
+            length,
+        }
+    }
+    
+    /// Create a new native function that can call back into the engine
+    pub fn host_method(name: &str, func: HostMethod, length: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            function_type: FunctionType::HostMethod(func),
+            prototype: Rc::new(RefCell::new(Object::new())),
+            properties: Rc::new(RefCell::new(Object::new())),
+            length,
+        }
+    }
+    
+    /// Create a new user function closing over `scope`
+    pub fn user(name: &str, params: Vec<String>, body: Rc<Node>, scope: Option<Rc<RefCell<Environment>>>) -> Self {
         Self {
             name: name.to_string(),
             function_type: FunctionType::User(UserFunction {
                 params: params.clone(),
                 body,
-                scope: Rc::new(RefCell::new(HashMap::new())),
+                scope,
             }),
             prototype: Rc::new(RefCell::new(Object::new())),
+            properties: Rc::new(RefCell::new(Object::new())),
             length: params.len(),
         }
     }
     
+    /// Create a function that calls `target` with a fixed `this` and leading arguments
+    pub fn bound(target: Rc<RefCell<Function>>, this: Value, args: Vec<Value>) -> Self {
+        let (name, length) = {
+            let target = target.borrow();
+            (format!("bound {}", target.name), target.length.saturating_sub(args.len()))
+        };
+        Self {
+            name,
+            function_type: FunctionType::Bound(BoundFunction { target, this, args }),
+            prototype: Rc::new(RefCell::new(Object::new())),
+            properties: Rc::new(RefCell::new(Object::new())),
+            length,
+        }
+    }
+    
     /// Call the function
-    pub fn call(&self, _this: Value, args: &[Value]) -> Result<Value, String> {
+    pub fn call(&self, this: Value, args: &[Value]) -> Result<Value, String> {
         match &self.function_type {
             FunctionType::Native(func) => {
                 func(args)
             }
+            FunctionType::NativeMethod(func) => func(this, args),
+            FunctionType::HostMethod(_) => {
+                // Host methods need an engine; see v8_api::Interpreter
+                Err(format!("TypeError: {} cannot be called without an engine", self.name))
+            }
+            FunctionType::Bound(bound) => {
+                let mut bound_args = bound.args.clone();
+                bound_args.extend_from_slice(args);
+                let target = bound.target.borrow().clone();
+                target.call(bound.this.clone(), &bound_args)
+            }
             FunctionType::User(_user_func) => {
-                // For now, return undefined for user functions
-                // This will be implemented when we have a proper interpreter
+                // User functions need an evaluator for their body; see
+                // v8_api::Interpreter
                 Ok(Value::Undefined)
             }
         }
@@ -0,0 +1,307 @@
+//! Global functions for V8-Rust JavaScript engine
+//! 
+//! This module provides the built-in functions of the global object.
+
+use super::value::Value;
+
+/// Check if a character is JavaScript whitespace or a line terminator
+fn is_js_whitespace(c: char) -> bool {
+    c.is_whitespace() || c == '\u{FEFF}'
+}
+
+/// Convert a number to a 32-bit signed integer (ToInt32)
+fn to_int32(n: f64) -> i32 {
+    if !n.is_finite() {
+        return 0;
+    }
+    let modulo = n.trunc().rem_euclid(4294967296.0);
+    if modulo >= 2147483648.0 {
+        (modulo - 4294967296.0) as i32
+    } else {
+        modulo as i32
+    }
+}
+
+/// `parseInt(string, radix)`
+///
+/// Parses the longest prefix of digits valid in the given radix, after
+/// leading whitespace and an optional sign. A radix of 0 (or missing)
+/// means 10, unless the digits start with `0x`/`0X` in which case 16 is used.
+pub fn parse_int(args: &[Value]) -> Result<Value, String> {
+    let input = args.first().map(|v| v.to_string()).unwrap_or_else(|| "undefined".to_string());
+    let radix = args.get(1).map(|v| to_int32(v.to_number())).unwrap_or(0);
+
+    let mut s = input.trim_start_matches(is_js_whitespace);
+    let mut sign = 1.0;
+    if let Some(rest) = s.strip_prefix('-') {
+        sign = -1.0;
+        s = rest;
+    } else if let Some(rest) = s.strip_prefix('+') {
+        s = rest;
+    }
+
+    let mut radix = radix as u32;
+    let mut strip_prefix = true;
+    if radix != 0 {
+        if !(2..=36).contains(&radix) {
+            return Ok(Value::Number(f64::NAN));
+        }
+        if radix != 16 {
+            strip_prefix = false;
+        }
+    } else {
+        radix = 10;
+    }
+
+    if strip_prefix {
+        if let Some(rest) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            s = rest;
+            radix = 16;
+        }
+    }
+
+    let mut result = 0.0;
+    let mut digits = 0;
+    for c in s.chars() {
+        match c.to_digit(radix) {
+            Some(d) => {
+                result = result * radix as f64 + d as f64;
+                digits += 1;
+            }
+            None => break,
+        }
+    }
+
+    if digits == 0 {
+        return Ok(Value::Number(f64::NAN));
+    }
+    Ok(Value::Number(sign * result))
+}
+
+/// `parseFloat(string)`
+///
+/// Parses the longest prefix that is a valid decimal literal (including
+/// `Infinity`) after leading whitespace, returning `NaN` if there is none.
+pub fn parse_float(args: &[Value]) -> Result<Value, String> {
+    let input = args.first().map(|v| v.to_string()).unwrap_or_else(|| "undefined".to_string());
+    let s = input.trim_start_matches(is_js_whitespace);
+    let bytes = s.as_bytes();
+
+    let mut end = 0;
+    if end < bytes.len() && (bytes[end] == b'+' || bytes[end] == b'-') {
+        end += 1;
+    }
+
+    if s[end..].starts_with("Infinity") {
+        let sign = if s.starts_with('-') { -1.0 } else { 1.0 };
+        return Ok(Value::Number(sign * f64::INFINITY));
+    }
+
+    let mut mantissa_digits = 0;
+    while end < bytes.len() && bytes[end].is_ascii_digit() {
+        end += 1;
+        mantissa_digits += 1;
+    }
+    if end < bytes.len() && bytes[end] == b'.' {
+        end += 1;
+        while end < bytes.len() && bytes[end].is_ascii_digit() {
+            end += 1;
+            mantissa_digits += 1;
+        }
+    }
+    if mantissa_digits == 0 {
+        return Ok(Value::Number(f64::NAN));
+    }
+
+    // The exponent is only part of the literal if it has at least one digit
+    if end < bytes.len() && (bytes[end] == b'e' || bytes[end] == b'E') {
+        let mut exp_end = end + 1;
+        if exp_end < bytes.len() && (bytes[exp_end] == b'+' || bytes[exp_end] == b'-') {
+            exp_end += 1;
+        }
+        let exp_start = exp_end;
+        while exp_end < bytes.len() && bytes[exp_end].is_ascii_digit() {
+            exp_end += 1;
+        }
+        if exp_end > exp_start {
+            end = exp_end;
+        }
+    }
+
+    Ok(Value::Number(s[..end].parse::<f64>().unwrap_or(f64::NAN)))
+}
+
+/// `isNaN(value)`: whether the value converts to `NaN`
+pub fn is_nan(args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(args.first().map_or(f64::NAN, Value::to_number).is_nan()))
+}
+
+/// `isFinite(value)`: whether the value converts to a finite number
+pub fn is_finite(args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(args.first().map_or(f64::NAN, Value::to_number).is_finite()))
+}
+
+/// Characters that `encodeURIComponent` leaves untouched
+const URI_UNRESERVED_MARKS: &str = "-_.!~*'()";
+
+/// Characters with special meaning in a URI, preserved by `encodeURI`/`decodeURI`
+const URI_RESERVED: &str = ";/?:@&=+$,#";
+
+/// Characters that the legacy `escape` leaves untouched
+const ESCAPE_UNESCAPED: &str = "@*_+-./";
+
+const URI_ERROR: &str = "URIError: URI malformed";
+
+/// Percent-encode every UTF-8 byte of characters not in the unescaped set
+fn encode(input: &str, unescaped: &dyn Fn(char) -> bool) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut buf = [0u8; 4];
+    for c in input.chars() {
+        if unescaped(c) {
+            result.push(c);
+        } else {
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                result.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+    result
+}
+
+/// Read the two hex digits of a `%XX` escape starting at `pos`
+fn hex_byte(chars: &[char], pos: usize) -> Option<u8> {
+    if chars.get(pos) != Some(&'%') {
+        return None;
+    }
+    let high = chars.get(pos + 1)?.to_digit(16)?;
+    let low = chars.get(pos + 2)?.to_digit(16)?;
+    Some((high * 16 + low) as u8)
+}
+
+/// Decode `%XX` UTF-8 sequences, keeping escapes of characters in `reserved`
+fn decode(input: &str, reserved: &str) -> Result<String, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut result = String::with_capacity(input.len());
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != '%' {
+            result.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        let lead = hex_byte(&chars, i).ok_or_else(|| URI_ERROR.to_string())?;
+        i += 3;
+
+        if lead < 0x80 {
+            let c = lead as char;
+            if reserved.contains(c) {
+                result.extend(&chars[start..i]);
+            } else {
+                result.push(c);
+            }
+            continue;
+        }
+
+        let len = match lead {
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => return Err(URI_ERROR.to_string()),
+        };
+        let mut bytes = vec![lead];
+        for _ in 1..len {
+            let byte = hex_byte(&chars, i).ok_or_else(|| URI_ERROR.to_string())?;
+            bytes.push(byte);
+            i += 3;
+        }
+        let decoded = std::str::from_utf8(&bytes).map_err(|_| URI_ERROR.to_string())?;
+        result.push_str(decoded);
+    }
+    Ok(result)
+}
+
+/// `encodeURI(uri)`
+pub fn encode_uri(args: &[Value]) -> Result<Value, String> {
+    let input = args.first().map(|v| v.to_string()).unwrap_or_else(|| "undefined".to_string());
+    Ok(Value::String(encode(&input, &|c| {
+        c.is_ascii_alphanumeric() || URI_UNRESERVED_MARKS.contains(c) || URI_RESERVED.contains(c)
+    })))
+}
+
+/// `encodeURIComponent(component)`
+pub fn encode_uri_component(args: &[Value]) -> Result<Value, String> {
+    let input = args.first().map(|v| v.to_string()).unwrap_or_else(|| "undefined".to_string());
+    Ok(Value::String(encode(&input, &|c| {
+        c.is_ascii_alphanumeric() || URI_UNRESERVED_MARKS.contains(c)
+    })))
+}
+
+/// `decodeURI(uri)`
+pub fn decode_uri(args: &[Value]) -> Result<Value, String> {
+    let input = args.first().map(|v| v.to_string()).unwrap_or_else(|| "undefined".to_string());
+    decode(&input, URI_RESERVED).map(Value::String)
+}
+
+/// `decodeURIComponent(component)`
+pub fn decode_uri_component(args: &[Value]) -> Result<Value, String> {
+    let input = args.first().map(|v| v.to_string()).unwrap_or_else(|| "undefined".to_string());
+    decode(&input, "").map(Value::String)
+}
+
+/// `escape(string)`
+///
+/// Legacy encoding: code units below 256 become `%XX`, the rest `%uXXXX`.
+pub fn escape(args: &[Value]) -> Result<Value, String> {
+    let input = args.first().map(|v| v.to_string()).unwrap_or_else(|| "undefined".to_string());
+    let mut result = String::with_capacity(input.len());
+    for unit in input.encode_utf16() {
+        match char::from_u32(unit as u32) {
+            Some(c) if c.is_ascii_alphanumeric() || ESCAPE_UNESCAPED.contains(c) => result.push(c),
+            _ if unit < 256 => result.push_str(&format!("%{:02X}", unit)),
+            _ => result.push_str(&format!("%u{:04X}", unit)),
+        }
+    }
+    Ok(Value::String(result))
+}
+
+/// `unescape(string)`
+///
+/// Reverses `escape`; malformed sequences are left as-is.
+pub fn unescape(args: &[Value]) -> Result<Value, String> {
+    let input = args.first().map(|v| v.to_string()).unwrap_or_else(|| "undefined".to_string());
+    let units: Vec<u16> = input.encode_utf16().collect();
+    let hex = |slice: &[u16]| -> Option<u16> {
+        let s = String::from_utf16(slice).ok()?;
+        if s.chars().all(|c| c.is_ascii_hexdigit()) {
+            u16::from_str_radix(&s, 16).ok()
+        } else {
+            None
+        }
+    };
+
+    let mut result = Vec::with_capacity(units.len());
+    let mut i = 0;
+    while i < units.len() {
+        if units[i] == b'%' as u16 {
+            if units.get(i + 1) == Some(&(b'u' as u16)) && i + 6 <= units.len() {
+                if let Some(unit) = hex(&units[i + 2..i + 6]) {
+                    result.push(unit);
+                    i += 6;
+                    continue;
+                }
+            }
+            if i + 3 <= units.len() {
+                if let Some(unit) = hex(&units[i + 1..i + 3]) {
+                    result.push(unit);
+                    i += 3;
+                    continue;
+                }
+            }
+        }
+        result.push(units[i]);
+        i += 1;
+    }
+    Ok(Value::String(String::from_utf16_lossy(&result)))
+}
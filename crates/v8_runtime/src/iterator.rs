@@ -0,0 +1,197 @@
+//! Iterator protocol for V8-Rust JavaScript engine
+//!
+//! This module provides the `Symbol.iterator`, `Symbol.hasInstance` and
+//! `Symbol.toPrimitive` well-known symbols, the `Symbol` global that exposes them, and the list
+//! iterators returned by the default iterator methods of arrays, strings,
+//! `Set` and `Map`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use super::function::{Function, NativeMethod};
+use super::object::{InternalSlot, IterationKind, Object, PropertyDescriptor};
+use super::value::Value;
+
+/// Description of the `Symbol.iterator` well-known symbol
+pub const ITERATOR_SYMBOL: &str = "Symbol.iterator";
+
+/// The `Symbol.iterator` value
+pub fn iterator_symbol() -> Value {
+    Value::Symbol(ITERATOR_SYMBOL.to_string())
+}
+
+/// Property key under which objects store their `Symbol.iterator` method
+pub fn iterator_key() -> String {
+    iterator_symbol().to_property_key()
+}
+
+/// Description of the `Symbol.hasInstance` well-known symbol
+pub const HAS_INSTANCE_SYMBOL: &str = "Symbol.hasInstance";
+
+/// The `Symbol.hasInstance` value
+pub fn has_instance_symbol() -> Value {
+    Value::Symbol(HAS_INSTANCE_SYMBOL.to_string())
+}
+
+/// Property key under which constructors store their `Symbol.hasInstance` method
+pub fn has_instance_key() -> String {
+    has_instance_symbol().to_property_key()
+}
+
+/// Description of the `Symbol.toPrimitive` well-known symbol
+pub const TO_PRIMITIVE_SYMBOL: &str = "Symbol.toPrimitive";
+
+/// The `Symbol.toPrimitive` value
+pub fn to_primitive_symbol() -> Value {
+    Value::Symbol(TO_PRIMITIVE_SYMBOL.to_string())
+}
+
+/// Property key under which objects store their `Symbol.toPrimitive` method
+pub fn to_primitive_key() -> String {
+    to_primitive_symbol().to_property_key()
+}
+
+/// Create the `Symbol` global with its well-known symbols
+pub fn create_symbol_object() -> Rc<RefCell<Object>> {
+    let mut symbol = Object::new();
+    symbol.set_property("iterator".to_string(), iterator_symbol());
+    symbol.set_property("hasInstance".to_string(), has_instance_symbol());
+    symbol.set_property("toPrimitive".to_string(), to_primitive_symbol());
+    Rc::new(RefCell::new(symbol))
+}
+
+/// Wrap a native method in a function value
+pub fn method(name: &str, function: NativeMethod, length: usize) -> Value {
+    Value::Function(Rc::new(RefCell::new(Function::native_method(name, function, length))))
+}
+
+/// Define a non-enumerable method, as built-in prototypes do
+pub fn define_method(object: &mut Object, key: String, function: Value) {
+    object.define_property(key, PropertyDescriptor::data_descriptor(function, true, false, true));
+}
+
+/// Default `Symbol.iterator` method of a primitive or array value, if any
+///
+/// Arrays and strings have no prototype objects yet, so property lookups
+/// on them ask for their iterator here.
+pub fn builtin_iterator_method(value: &Value) -> Option<Value> {
+    match value {
+        Value::Array(_) | Value::String(_) => Some(method("[Symbol.iterator]", values, 0)),
+        _ => None,
+    }
+}
+
+/// Create an iterator over an array, string, `Set` or `Map`
+///
+/// The iterator reads its source lazily, so elements added while
+/// iterating are visited.
+pub fn create_list_iterator(source: Value, kind: IterationKind) -> Value {
+    let mut iterator = Object::new();
+    iterator.internal = Some(InternalSlot::ListIterator { source, kind, index: 0 });
+    define_method(&mut iterator, "next".to_string(), method("next", next, 0));
+    // Iterators are themselves iterable
+    define_method(&mut iterator, iterator_key(), method("[Symbol.iterator]", return_this, 0));
+    Value::Object(Rc::new(RefCell::new(iterator)))
+}
+
+/// Create an iterator result object `{ value, done }`
+pub fn iterator_result(value: Value, done: bool) -> Value {
+    let mut result = Object::new();
+    result.set_property("value".to_string(), value);
+    result.set_property("done".to_string(), Value::Boolean(done));
+    Value::Object(Rc::new(RefCell::new(result)))
+}
+
+/// Elements of a built-in iterable, read without running any script code
+///
+/// Native constructors such as `new Set(iterable)` cannot call user
+/// functions, so they accept only arrays, strings, `Set` and `Map`.
+pub fn collect_builtin(value: &Value) -> Option<Vec<Value>> {
+    let kind = match value {
+        Value::Object(object) => match object.borrow().internal {
+            Some(InternalSlot::MapData(_)) => IterationKind::Entries,
+            Some(InternalSlot::SetData(_)) | Some(InternalSlot::ListIterator { .. }) => IterationKind::Values,
+            _ => return None,
+        },
+        Value::Array(_) | Value::String(_) => IterationKind::Values,
+        _ => return None,
+    };
+    let iterator = match value {
+        Value::Object(object) if matches!(object.borrow().internal, Some(InternalSlot::ListIterator { .. })) => {
+            value.clone()
+        }
+        _ => create_list_iterator(value.clone(), kind),
+    };
+    let Value::Object(iterator) = iterator else { return None };
+    let mut elements = Vec::new();
+    while let Some(element) = step(&mut iterator.borrow_mut()) {
+        elements.push(element);
+    }
+    Some(elements)
+}
+
+/// `Array.prototype[Symbol.iterator]` and `String.prototype[Symbol.iterator]`
+pub fn values(this: Value, _args: &[Value]) -> Result<Value, String> {
+    match this {
+        Value::Array(_) | Value::String(_) => Ok(create_list_iterator(this, IterationKind::Values)),
+        other => Err(format!("TypeError: {} is not iterable", other)),
+    }
+}
+
+/// `%ListIteratorPrototype%.next`
+fn next(this: Value, _args: &[Value]) -> Result<Value, String> {
+    match &this {
+        Value::Object(object) if matches!(object.borrow().internal, Some(InternalSlot::ListIterator { .. })) => {
+            match step(&mut object.borrow_mut()) {
+                Some(value) => Ok(iterator_result(value, false)),
+                None => Ok(iterator_result(Value::Undefined, true)),
+            }
+        }
+        other => Err(format!("TypeError: next method called on incompatible receiver {}", other)),
+    }
+}
+
+fn return_this(this: Value, _args: &[Value]) -> Result<Value, String> {
+    Ok(this)
+}
+
+/// Advance a list iterator, returning `None` once it is exhausted
+fn step(iterator: &mut Object) -> Option<Value> {
+    let Some(InternalSlot::ListIterator { source, kind, index }) = &mut iterator.internal else {
+        return None;
+    };
+    let entry = match &*source {
+        // Holes read as undefined
+        Value::Array(elements) => {
+            let elements = elements.borrow();
+            (*index < elements.len())
+                .then(|| (Value::Number(*index as f64), elements.get(*index).cloned().unwrap_or_default()))
+        }
+        Value::String(s) => s
+            .chars()
+            .nth(*index)
+            .map(|c| (Value::Number(*index as f64), Value::String(c.to_string()))),
+        Value::Object(object) => match &object.borrow().internal {
+            Some(InternalSlot::SetData(elements)) => {
+                elements.get(*index).map(|element| (element.clone(), element.clone()))
+            }
+            Some(InternalSlot::MapData(entries)) => entries.get(*index).cloned(),
+            _ => None,
+        },
+        _ => None,
+    };
+    match entry {
+        Some((key, value)) => {
+            *index += 1;
+            Some(match kind {
+                IterationKind::Keys => key,
+                IterationKind::Values => value,
+                IterationKind::Entries => Value::new_array(vec![key, value]),
+            })
+        }
+        None => {
+            // An exhausted iterator stays exhausted
+            *source = Value::Undefined;
+            None
+        }
+    }
+}
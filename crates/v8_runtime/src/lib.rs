@@ -3,12 +3,30 @@
 //! This crate provides the runtime environment, execution context,
 //! and value management for the JavaScript engine.
 
+pub mod array;
+pub mod collections;
 pub mod context;
+pub mod date;
+pub mod elements;
+pub mod environment;
 pub mod function;
+pub mod global;
+pub mod iterator;
+pub mod number;
 pub mod object;
+pub mod object_constructor;
+pub mod promise;
+pub mod reflect;
+pub mod regexp;
+pub mod runtime;
 pub mod value;
+pub mod weak;
+pub mod wrapper;
 
 pub use context::Context;
+pub use date::Date;
+pub use environment::Environment;
 pub use function::Function;
 pub use object::Object;
+pub use runtime::Runtime;
 pub use value::Value; 
\ No newline at end of file
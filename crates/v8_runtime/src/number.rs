@@ -0,0 +1,92 @@
+//! Number constructor for V8-Rust JavaScript engine
+//!
+//! This module provides the `Number` global: called as a function it
+//! converts its argument to a number, with `new` it creates a wrapper
+//! object, and it carries the static predicates and numeric constants. Unlike the global `isNaN` and `isFinite`, the
+//! predicates never convert their argument.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use super::function::{Function, NativeFunction};
+use super::global;
+use super::iterator::define_method;
+use super::object::PropertyDescriptor;
+use super::value::Value;
+use super::wrapper::{construct_or_convert, number_value_of, wrapper_constructor};
+
+/// Largest integer n such that n and n + 1 are exactly representable
+pub const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+/// Create the `Number` global with its static methods and constants
+pub fn create_number_constructor() -> Rc<RefCell<Function>> {
+    let constructor = Function::native_method("Number", number_constructor, 1);
+    let constructor = wrapper_constructor(constructor, number_value_of, Value::Number(0.0));
+    {
+        let mut statics = constructor.properties.borrow_mut();
+        let functions: [(&str, NativeFunction); 6] = [
+            ("isInteger", is_integer),
+            ("isFinite", is_finite),
+            ("isNaN", is_nan),
+            ("isSafeInteger", is_safe_integer),
+            ("parseInt", global::parse_int),
+            ("parseFloat", global::parse_float),
+        ];
+        for (name, function) in functions {
+            let mut function = Function::native(name, function);
+            function.length = if name == "parseInt" { 2 } else { 1 };
+            define_method(&mut statics, name.to_string(), Value::Function(Rc::new(RefCell::new(function))));
+        }
+        let constants = [
+            ("MAX_SAFE_INTEGER", MAX_SAFE_INTEGER),
+            ("MIN_SAFE_INTEGER", -MAX_SAFE_INTEGER),
+            ("MAX_VALUE", f64::MAX),
+            ("MIN_VALUE", 5e-324),
+            ("EPSILON", f64::EPSILON),
+            ("POSITIVE_INFINITY", f64::INFINITY),
+            ("NEGATIVE_INFINITY", f64::NEG_INFINITY),
+            ("NaN", f64::NAN),
+        ];
+        for (name, value) in constants {
+            let descriptor = PropertyDescriptor::data_descriptor(Value::Number(value), false, false, false);
+            statics.define_property(name.to_string(), descriptor);
+        }
+    }
+    Rc::new(RefCell::new(constructor))
+}
+
+/// The argument as a number, without conversion
+fn number_argument(args: &[Value]) -> Option<f64> {
+    match args.first() {
+        Some(Value::Number(n)) => Some(*n),
+        _ => None,
+    }
+}
+
+/// `Number(value)`: ToNumber, with no argument meaning 0
+pub fn number_constructor(this: Value, args: &[Value]) -> Result<Value, String> {
+    let number = args.first().map_or(0.0, Value::to_number);
+    Ok(construct_or_convert(&this, Value::Number(number)))
+}
+
+/// `Number.isInteger(value)`
+pub fn is_integer(args: &[Value]) -> Result<Value, String> {
+    let integer = number_argument(args).is_some_and(|n| n.is_finite() && n.trunc() == n);
+    Ok(Value::Boolean(integer))
+}
+
+/// `Number.isFinite(value)`
+pub fn is_finite(args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(number_argument(args).is_some_and(f64::is_finite)))
+}
+
+/// `Number.isNaN(value)`: true only for the number `NaN`
+pub fn is_nan(args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Boolean(number_argument(args).is_some_and(f64::is_nan)))
+}
+
+/// `Number.isSafeInteger(value)`
+pub fn is_safe_integer(args: &[Value]) -> Result<Value, String> {
+    let safe = number_argument(args)
+        .is_some_and(|n| n.is_finite() && n.trunc() == n && n.abs() <= MAX_SAFE_INTEGER);
+    Ok(Value::Boolean(safe))
+}
@@ -2,16 +2,21 @@
 //! 
 //! This module provides the core object system for JavaScript objects.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use std::cell::RefCell;
+use super::function::Host;
+use super::promise::PromiseData;
 use super::value::Value;
+use super::weak::{FinalizationRegistryData, WeakValue};
 
 /// Property descriptor for object properties
 #[derive(Debug, Clone)]
 pub struct PropertyDescriptor {
     pub value: Option<Value>,
     pub writable: Option<bool>,
+    pub get: Option<Value>, // Getter function of an accessor property
+    pub set: Option<Value>, // Setter function of an accessor property
     pub enumerable: Option<bool>,
     pub configurable: Option<bool>,
 }
@@ -22,6 +27,8 @@ impl PropertyDescriptor {
         Self {
             value: None,
             writable: None,
+            get: None,
+            set: None,
             enumerable: None,
             configurable: None,
         }
@@ -32,6 +39,20 @@ impl PropertyDescriptor {
         Self {
             value: Some(value),
             writable: Some(writable),
+            get: None,
+            set: None,
+            enumerable: Some(enumerable),
+            configurable: Some(configurable),
+        }
+    }
+    
+    /// Create an accessor descriptor
+    pub fn accessor_descriptor(get: Option<Value>, set: Option<Value>, enumerable: bool, configurable: bool) -> Self {
+        Self {
+            value: None,
+            writable: None,
+            get,
+            set,
             enumerable: Some(enumerable),
             configurable: Some(configurable),
         }
@@ -41,6 +62,42 @@ impl PropertyDescriptor {
     pub fn is_data_descriptor(&self) -> bool {
         self.value.is_some() || self.writable.is_some()
     }
+    
+    /// Check if this is an accessor descriptor
+    pub fn is_accessor_descriptor(&self) -> bool {
+        self.get.is_some() || self.set.is_some()
+    }
+}
+
+/// Which part of each entry an iterator produces
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IterationKind {
+    Keys,
+    Values,
+    Entries,
+}
+
+/// Internal state of built-in objects, not visible as properties
+#[derive(Debug, Clone)]
+pub enum InternalSlot {
+    /// Elements of a `Set`, in insertion order
+    SetData(Vec<Value>),
+    /// Entries of a `Map`, in insertion order
+    MapData(Vec<(Value, Value)>),
+    /// Iterator over an array, string, `Set` or `Map`
+    ListIterator {
+        source: Value, // Undefined once the iterator is exhausted
+        kind: IterationKind,
+        index: usize,
+    },
+    /// State and pending reactions of a `Promise`
+    Promise(PromiseData),
+    /// Primitive held by a `String`, `Number` or `Boolean` wrapper object
+    PrimitiveValue(Value),
+    /// Target of a `WeakRef`
+    WeakRefTarget(WeakValue),
+    /// Cleanup callback and registered targets of a `FinalizationRegistry`
+    FinalizationRegistry(FinalizationRegistryData),
 }
 
 /// JavaScript object
@@ -49,6 +106,8 @@ pub struct Object {
     pub properties: HashMap<String, PropertyDescriptor>,
     pub prototype: Option<Rc<RefCell<Object>>>,
     pub extensible: bool,
+    pub internal: Option<InternalSlot>,
+    order: Vec<String>, // Own property names in insertion order
 }
 
 impl Object {
@@ -58,6 +117,8 @@ impl Object {
             properties: HashMap::new(),
             prototype: None,
             extensible: true,
+            internal: None,
+            order: Vec::new(),
         }
     }
     
@@ -67,16 +128,31 @@ impl Object {
             properties: HashMap::new(),
             prototype: Some(prototype),
             extensible: true,
+            internal: None,
+            order: Vec::new(),
         }
     }
     
     /// Set a property on the object
-    pub fn set_property(&mut self, name: String, value: Value) {
+    ///
+    /// Returns `false` when the write is rejected: the existing property is
+    /// read-only or an accessor, or the property is new and the object is
+    /// not extensible. Use `Object::set` to run setters.
+    pub fn set_property(&mut self, name: String, value: Value) -> bool {
+        if let Some(descriptor) = self.properties.get_mut(&name) {
+            if !descriptor.writable.unwrap_or(true) || descriptor.is_accessor_descriptor() {
+                return false;
+            }
+            descriptor.value = Some(value);
+            return true;
+        }
         let descriptor = PropertyDescriptor::data_descriptor(value, true, true, true);
-        self.properties.insert(name, descriptor);
+        self.insert_property(name, descriptor)
     }
     
     /// Get a property from the object
+    ///
+    /// Accessor properties have no value here; use `Object::get` to run getters.
     pub fn get_property(&self, name: &str) -> Option<Value> {
         if let Some(descriptor) = self.properties.get(name) {
             descriptor.value.clone()
@@ -87,35 +163,217 @@ impl Object {
         }
     }
     
-    /// Check if the object has a property
+    /// Find an own or inherited property
+    pub fn find_property(&self, name: &str) -> Option<PropertyDescriptor> {
+        match self.properties.get(name) {
+            Some(descriptor) => Some(descriptor.clone()),
+            None => self.prototype.as_ref().and_then(|prototype| prototype.borrow().find_property(name)),
+        }
+    }
+    
+    /// The prototype as a value: an object or `null`
+    pub fn prototype_value(&self) -> Value {
+        self.prototype.clone().map_or(Value::Null, Value::Object)
+    }
+    
+    /// Replace the prototype of `object`
+    ///
+    /// Fails when `prototype` would make the chain loop back to `object`,
+    /// or when a non-extensible object would get a different prototype.
+    pub fn set_prototype(object: &Rc<RefCell<Object>>, prototype: Option<Rc<RefCell<Object>>>) -> Result<(), String> {
+        let current = object.borrow().prototype.clone();
+        let unchanged = match (&current, &prototype) {
+            (Some(current), Some(prototype)) => Rc::ptr_eq(current, prototype),
+            (None, None) => true,
+            _ => false,
+        };
+        if unchanged {
+            return Ok(());
+        }
+        if !object.borrow().extensible {
+            return Err("TypeError: #<Object> is not extensible".to_string());
+        }
+        let mut link = prototype.clone();
+        while let Some(ancestor) = link {
+            if Rc::ptr_eq(&ancestor, object) {
+                return Err("TypeError: Cyclic __proto__ value".to_string());
+            }
+            link = ancestor.borrow().prototype.clone();
+        }
+        object.borrow_mut().prototype = prototype;
+        Ok(())
+    }
+    
+    /// Read a property, calling a getter with `receiver` as `this`
+    ///
+    /// `__proto__` reads the prototype unless the chain defines it.
+    pub fn get(object: &Rc<RefCell<Object>>, name: &str, receiver: Value, host: &mut dyn Host) -> Result<Option<Value>, Value> {
+        // The borrow ends before the getter runs, so it may use the object
+        let descriptor = object.borrow().find_property(name);
+        match descriptor {
+            None if name == "__proto__" => Ok(Some(object.borrow().prototype_value())),
+            Some(descriptor) if descriptor.is_accessor_descriptor() => match descriptor.get {
+                Some(getter) => host.call(&getter, receiver, &[]).map(Some),
+                None => Ok(Some(Value::Undefined)),
+            },
+            Some(descriptor) => Ok(descriptor.value),
+            None => Ok(None),
+        }
+    }
+    
+    /// Write a property, calling a setter with `receiver` as `this`
+    ///
+    /// Setters are found on the prototype chain too. Returns `false` when
+    /// the write is rejected, including an accessor without a setter.
+    /// Writing an object or `null` to `__proto__` replaces the prototype
+    /// unless the chain defines it; other values are ignored.
+    pub fn set(object: &Rc<RefCell<Object>>, name: String, value: Value, receiver: Value, host: &mut dyn Host) -> Result<bool, Value> {
+        let descriptor = object.borrow().find_property(&name);
+        match descriptor {
+            None if name == "__proto__" => match value {
+                Value::Object(prototype) => Object::set_prototype(object, Some(prototype)).map(|_| true).map_err(Value::from),
+                Value::Null => Object::set_prototype(object, None).map(|_| true).map_err(Value::from),
+                _ => Ok(true),
+            },
+            Some(descriptor) if descriptor.is_accessor_descriptor() => match descriptor.set {
+                Some(setter) => host.call(&setter, receiver, &[value]).map(|_| true),
+                None => Ok(false),
+            },
+            _ => Ok(object.borrow_mut().set_property(name, value)),
+        }
+    }
+    
+    /// Check if the object has an own or inherited property (the `in` operator)
     pub fn has_property(&self, name: &str) -> bool {
-        self.properties.contains_key(name) || 
+        self.has_own_property(name) ||
         self.prototype.as_ref().map_or(false, |p| p.borrow().has_property(name))
     }
     
-    /// Delete a property from the object
+    /// Check if the object has an own property, ignoring the prototype chain
+    pub fn has_own_property(&self, name: &str) -> bool {
+        self.properties.contains_key(name)
+    }
+    
+    /// Delete an own property from the object (the `delete` operator)
+    ///
+    /// Inherited and missing properties are left alone and count as
+    /// deleted; only a non-configurable own property returns `false`.
     pub fn delete_property(&mut self, name: &str) -> bool {
-        if let Some(descriptor) = self.properties.get(name) {
-            if descriptor.configurable.unwrap_or(true) {
+        match self.properties.get(name) {
+            Some(descriptor) if !descriptor.configurable.unwrap_or(true) => false,
+            Some(_) => {
                 self.properties.remove(name);
+                self.order.retain(|key| key != name);
                 true
-            } else {
-                false
             }
-        } else {
-            false
+            None => true,
         }
     }
     
     /// Define a property on the object
+    ///
+    /// Non-configurable properties cannot be redefined, and new properties
+    /// cannot be added to a non-extensible object.
     pub fn define_property(&mut self, name: String, descriptor: PropertyDescriptor) -> bool {
+        if let Some(existing) = self.properties.get_mut(&name) {
+            if !existing.configurable.unwrap_or(true) {
+                return false;
+            }
+            *existing = descriptor;
+            return true;
+        }
+        self.insert_property(name, descriptor)
+    }
+    
+    /// Define a getter and/or a setter, keeping the other half of an existing accessor
+    pub fn define_accessor(&mut self, name: String, get: Option<Value>, set: Option<Value>) -> bool {
+        let mut descriptor = match self.properties.get(&name) {
+            Some(existing) if existing.is_accessor_descriptor() => existing.clone(),
+            _ => PropertyDescriptor::accessor_descriptor(None, None, true, true),
+        };
+        if get.is_some() {
+            descriptor.get = get;
+        }
+        if set.is_some() {
+            descriptor.set = set;
+        }
+        self.define_property(name, descriptor)
+    }
+    
+    fn insert_property(&mut self, name: String, descriptor: PropertyDescriptor) -> bool {
+        if !self.extensible {
+            return false;
+        }
+        self.order.push(name.clone());
         self.properties.insert(name, descriptor);
         true
     }
     
-    /// Get all own property names
+    /// Get all own property names, in property enumeration order
+    ///
+    /// Integer index keys come first in ascending order, then the other
+    /// string keys in insertion order, then symbol keys in insertion order.
     pub fn get_own_property_names(&self) -> Vec<String> {
-        self.properties.keys().cloned().collect()
+        let mut indices: Vec<(u32, &String)> = Vec::new();
+        let mut strings = Vec::new();
+        let mut symbols = Vec::new();
+        for key in self.order.iter().filter(|key| self.properties.contains_key(*key)) {
+            match array_index(key) {
+                Some(index) => indices.push((index, key)),
+                None if is_symbol_key(key) => symbols.push(key),
+                None => strings.push(key),
+            }
+        }
+        indices.sort_unstable_by_key(|(index, _)| *index);
+        indices
+            .into_iter()
+            .map(|(_, key)| key)
+            .chain(strings)
+            .chain(symbols)
+            .cloned()
+            .collect()
+    }
+    
+    /// Own enumerable properties with their values, in enumeration order
+    pub fn own_enumerable_properties(&self) -> Vec<(String, Value)> {
+        self.get_own_property_names()
+            .into_iter()
+            .filter_map(|key| {
+                let descriptor = &self.properties[&key];
+                if !descriptor.enumerable.unwrap_or(true) {
+                    return None;
+                }
+                let value = descriptor.value.clone().unwrap_or(Value::Undefined);
+                Some((key, value))
+            })
+            .collect()
+    }
+    
+    /// Keys visited by a for-in loop over the object
+    ///
+    /// Enumerable string keys of the object and then of each prototype, each
+    /// in enumeration order. A key is visited once, and not at all when an
+    /// object closer in the chain shadows it with a non-enumerable property.
+    pub fn for_in_keys(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut keys = Vec::new();
+        let mut visit = |object: &Object| {
+            for key in object.get_own_property_names() {
+                if is_symbol_key(&key) || !seen.insert(key.clone()) {
+                    continue;
+                }
+                if object.properties[&key].enumerable.unwrap_or(true) {
+                    keys.push(key);
+                }
+            }
+        };
+        visit(self);
+        let mut current = self.prototype.clone();
+        while let Some(prototype) = current {
+            visit(&prototype.borrow());
+            current = prototype.borrow().prototype.clone();
+        }
+        keys
     }
     
     /// Prevent extensions on the object
@@ -127,4 +385,38 @@ impl Object {
     pub fn is_extensible(&self) -> bool {
         self.extensible
     }
-} 
\ No newline at end of file
+    
+    /// Make every own property read-only and non-configurable, and prevent extensions
+    pub fn freeze(&mut self) {
+        self.extensible = false;
+        for descriptor in self.properties.values_mut() {
+            if descriptor.is_data_descriptor() {
+                descriptor.writable = Some(false);
+            }
+            descriptor.configurable = Some(false);
+        }
+    }
+    
+    /// Check if the object is frozen
+    pub fn is_frozen(&self) -> bool {
+        !self.extensible
+            && self.properties.values().all(|descriptor| {
+                let read_only = !descriptor.is_data_descriptor() || !descriptor.writable.unwrap_or(true);
+                read_only && !descriptor.configurable.unwrap_or(true)
+            })
+    }
+}
+
+/// The array index a key denotes: the canonical form of an integer below 2^32 - 1
+pub fn array_index(key: &str) -> Option<u32> {
+    key.parse::<u32>()
+        .ok()
+        .filter(|index| *index != u32::MAX && index.to_string() == key)
+}
+
+/// Whether a property key is the key of a symbol
+///
+/// Symbols are keyed as `Symbol(description)` (see `Value::to_property_key`).
+pub(crate) fn is_symbol_key(key: &str) -> bool {
+    key.starts_with("Symbol(") && key.ends_with(')')
+}
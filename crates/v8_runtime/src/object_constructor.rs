@@ -0,0 +1,338 @@
+//! Object constructor for V8-Rust JavaScript engine
+//!
+//! This module provides the static methods of the `Object` constructor.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use super::function::{argument, Function, NativeFunction};
+use super::object::{array_index, is_symbol_key, Object, PropertyDescriptor};
+use super::value::Value;
+
+/// Create the `Object` global with its static methods
+///
+/// Functions cannot carry own properties yet, so the statics live on a
+/// plain object.
+pub fn create_object_constructor() -> Rc<RefCell<Object>> {
+    let mut constructor = Object::new();
+    let statics: [(&str, NativeFunction); 12] = [
+        ("keys", keys),
+        ("values", values),
+        ("entries", entries),
+        ("assign", assign),
+        ("freeze", freeze),
+        ("isFrozen", is_frozen),
+        ("create", create),
+        ("hasOwn", has_own),
+        ("getPrototypeOf", get_prototype_of),
+        ("setPrototypeOf", set_prototype_of),
+        ("getOwnPropertyNames", get_own_property_names),
+        ("defineProperties", define_properties),
+    ];
+    for (name, function) in statics {
+        let function = Function::native(name, function);
+        constructor.set_property(name.to_string(), Value::Function(Rc::new(RefCell::new(function))));
+    }
+    Rc::new(RefCell::new(constructor))
+}
+
+/// Own enumerable string-keyed properties of `value`, in order (ToObject)
+fn own_enumerable_properties(value: &Value) -> Result<Vec<(String, Value)>, String> {
+    match value {
+        Value::Undefined | Value::Null => {
+            Err("TypeError: Cannot convert undefined or null to object".to_string())
+        }
+        Value::Object(object) => Ok(object.borrow().own_enumerable_properties()),
+        Value::Array(elements) => {
+            let elements = elements.borrow();
+            Ok(elements
+                .indices()
+                .map(|index| (index.to_string(), elements.get(index).cloned().unwrap_or_default()))
+                .collect())
+        }
+        Value::String(s) => Ok(s
+            .chars()
+            .enumerate()
+            .map(|(index, c)| (index.to_string(), Value::String(c.to_string())))
+            .collect()),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// `Object.keys(object)`
+pub fn keys(args: &[Value]) -> Result<Value, String> {
+    let properties = own_enumerable_properties(&argument(args, 0))?;
+    Ok(Value::new_array(properties.into_iter().map(|(key, _)| Value::String(key)).collect()))
+}
+
+/// `Object.values(object)`
+pub fn values(args: &[Value]) -> Result<Value, String> {
+    let properties = own_enumerable_properties(&argument(args, 0))?;
+    Ok(Value::new_array(properties.into_iter().map(|(_, value)| value).collect()))
+}
+
+/// `Object.entries(object)`
+pub fn entries(args: &[Value]) -> Result<Value, String> {
+    let properties = own_enumerable_properties(&argument(args, 0))?;
+    Ok(Value::new_array(
+        properties
+            .into_iter()
+            .map(|(key, value)| Value::new_array(vec![Value::String(key), value]))
+            .collect(),
+    ))
+}
+
+/// `Object.assign(target, ...sources)`
+///
+/// Copies own enumerable properties of each source onto the target, in
+/// order. Writes rejected by a frozen or non-extensible target throw.
+pub fn assign(args: &[Value]) -> Result<Value, String> {
+    let target = argument(args, 0);
+    let object = match &target {
+        Value::Object(object) => object.clone(),
+        Value::Undefined | Value::Null => {
+            return Err("TypeError: Cannot convert undefined or null to object".to_string())
+        }
+        // Properties assigned to primitives are discarded
+        _ => return Ok(target),
+    };
+    for source in args.iter().skip(1) {
+        // null and undefined sources are skipped
+        if matches!(source, Value::Undefined | Value::Null) {
+            continue;
+        }
+        for (key, value) in own_enumerable_properties(source)? {
+            let mut object = object.borrow_mut();
+            let exists = object.has_own_property(&key);
+            if !object.set_property(key.clone(), value) {
+                return Err(if exists {
+                    format!("TypeError: Cannot assign to read only property '{}' of object", key)
+                } else {
+                    format!("TypeError: Cannot add property {}, object is not extensible", key)
+                });
+            }
+        }
+    }
+    Ok(target)
+}
+
+/// `Object.freeze(object)`
+pub fn freeze(args: &[Value]) -> Result<Value, String> {
+    let target = argument(args, 0);
+    if let Value::Object(object) = &target {
+        object.borrow_mut().freeze();
+    }
+    Ok(target)
+}
+
+/// `Object.isFrozen(object)`
+pub fn is_frozen(args: &[Value]) -> Result<Value, String> {
+    match argument(args, 0) {
+        Value::Object(object) => Ok(Value::Boolean(object.borrow().is_frozen())),
+        // Arrays have no frozen state yet
+        Value::Array(_) => Ok(Value::Boolean(false)),
+        // Primitives have no mutable properties
+        _ => Ok(Value::Boolean(true)),
+    }
+}
+
+/// `Object.create(proto)`
+pub fn create(args: &[Value]) -> Result<Value, String> {
+    let object = match argument(args, 0) {
+        Value::Object(prototype) => Object::with_prototype(prototype),
+        Value::Null => Object::new(),
+        other => {
+            return Err(format!(
+                "TypeError: Object prototype may only be an Object or null: {}",
+                other
+            ))
+        }
+    };
+    Ok(Value::Object(Rc::new(RefCell::new(object))))
+}
+
+/// `Object.hasOwn(object, key)`: own properties only, unlike the `in` operator
+pub fn has_own(args: &[Value]) -> Result<Value, String> {
+    let key = args.get(1).cloned().unwrap_or(Value::Undefined).to_property_key();
+    let found = match argument(args, 0) {
+        Value::Undefined | Value::Null => {
+            return Err("TypeError: Cannot convert undefined or null to object".to_string())
+        }
+        Value::Object(object) => object.borrow().has_own_property(&key),
+        Value::Array(elements) => {
+            key == "length" || array_index(&key).is_some_and(|index| elements.borrow().has(index as usize))
+        }
+        Value::String(s) => {
+            key == "length" || key.parse::<usize>().is_ok_and(|index| index < s.chars().count())
+        }
+        Value::Function(function) => {
+            matches!(key.as_str(), "name" | "length" | "prototype")
+                || function.borrow().properties.borrow().has_own_property(&key)
+        }
+        _ => false,
+    };
+    Ok(Value::Boolean(found))
+}
+
+/// `Object.getPrototypeOf(object)`
+///
+/// Only plain objects have a prototype chain yet; other values report `null`.
+pub fn get_prototype_of(args: &[Value]) -> Result<Value, String> {
+    match argument(args, 0) {
+        Value::Undefined | Value::Null => {
+            Err("TypeError: Cannot convert undefined or null to object".to_string())
+        }
+        Value::Object(object) => Ok(object.borrow().prototype_value()),
+        _ => Ok(Value::Null),
+    }
+}
+
+/// `Object.setPrototypeOf(object, proto)`
+///
+/// A prototype that would make the chain loop back to the object throws.
+pub fn set_prototype_of(args: &[Value]) -> Result<Value, String> {
+    let target = argument(args, 0);
+    let prototype = match args.get(1).cloned().unwrap_or(Value::Undefined) {
+        Value::Object(prototype) => Some(prototype),
+        Value::Null => None,
+        other => {
+            return Err(format!(
+                "TypeError: Object prototype may only be an Object or null: {}",
+                other
+            ))
+        }
+    };
+    match &target {
+        Value::Undefined | Value::Null => {
+            Err("TypeError: Object.setPrototypeOf called on null or undefined".to_string())
+        }
+        Value::Object(object) => Object::set_prototype(object, prototype).map(|_| target.clone()),
+        // Primitives are returned unchanged
+        _ => Ok(target),
+    }
+}
+
+/// `Object.getOwnPropertyNames(object)`: own string keys, enumerable or not
+pub fn get_own_property_names(args: &[Value]) -> Result<Value, String> {
+    let names = match argument(args, 0) {
+        Value::Undefined | Value::Null => {
+            return Err("TypeError: Cannot convert undefined or null to object".to_string())
+        }
+        Value::Object(object) => object.borrow().get_own_property_names(),
+        Value::Array(elements) => {
+            let mut names: Vec<String> = elements.borrow().indices().map(|index| index.to_string()).collect();
+            names.push("length".to_string());
+            names
+        }
+        Value::String(s) => {
+            let mut names: Vec<String> = (0..s.chars().count()).map(|index| index.to_string()).collect();
+            names.push("length".to_string());
+            names
+        }
+        Value::Function(function) => {
+            let mut names = vec!["length".to_string(), "name".to_string(), "prototype".to_string()];
+            names.extend(function.borrow().properties.borrow().get_own_property_names());
+            names
+        }
+        _ => Vec::new(),
+    };
+    let names = names.into_iter().filter(|name| !is_symbol_key(name)).map(Value::String);
+    Ok(Value::new_array(names.collect()))
+}
+
+/// Fields present on a descriptor object, as in `ToPropertyDescriptor`
+fn to_property_descriptor(key: &str, value: &Value) -> Result<PropertyDescriptor, String> {
+    let Value::Object(object) = value else {
+        return Err(format!("TypeError: Property description must be an object: {}", value));
+    };
+    let object = object.borrow();
+    let field = |name: &str| {
+        object
+            .find_property(name)
+            .map(|_| object.get_property(name).unwrap_or(Value::Undefined))
+    };
+    let mut descriptor = PropertyDescriptor::new();
+    descriptor.value = field("value");
+    descriptor.writable = field("writable").map(|writable| writable.to_boolean());
+    descriptor.enumerable = field("enumerable").map(|enumerable| enumerable.to_boolean());
+    descriptor.configurable = field("configurable").map(|configurable| configurable.to_boolean());
+    for (name, accessor) in [("get", &mut descriptor.get), ("set", &mut descriptor.set)] {
+        *accessor = match field(name) {
+            Some(function @ Value::Function(_)) => Some(function),
+            Some(Value::Undefined) | None => None,
+            Some(other) => return Err(format!("TypeError: {} must be a function: {}", capitalize(name), other)),
+        };
+    }
+    let accessor = field("get").is_some() || field("set").is_some();
+    if accessor && (descriptor.value.is_some() || descriptor.writable.is_some()) {
+        return Err(format!(
+            "TypeError: Invalid property descriptor for '{}'. Cannot both specify accessors and a value or writable attribute",
+            key
+        ));
+    }
+    Ok(descriptor)
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    chars.next().map_or(String::new(), |first| first.to_uppercase().chain(chars).collect())
+}
+
+/// `Object.defineProperties(object, properties)`
+///
+/// Each own enumerable property of `properties` describes a property to
+/// define. All descriptors are read before any property is defined. Fields
+/// a descriptor leaves out keep their current value on an existing
+/// property and default to `false` (or `undefined`) on a new one.
+pub fn define_properties(args: &[Value]) -> Result<Value, String> {
+    let target = argument(args, 0);
+    let Value::Object(object) = &target else {
+        return Err("TypeError: Object.defineProperties called on non-object".to_string());
+    };
+    let properties = args.get(1).cloned().unwrap_or(Value::Undefined);
+    let descriptors = own_enumerable_properties(&properties)?
+        .into_iter()
+        .map(|(key, value)| to_property_descriptor(&key, &value).map(|descriptor| (key, descriptor)))
+        .collect::<Result<Vec<_>, String>>()?;
+    let mut object = object.borrow_mut();
+    for (key, descriptor) in descriptors {
+        let descriptor = complete_descriptor(descriptor, object.properties.get(&key));
+        if !object.define_property(key.clone(), descriptor) {
+            return Err(if object.has_own_property(&key) {
+                format!("TypeError: Cannot redefine property: {}", key)
+            } else {
+                format!("TypeError: Cannot define property {}, object is not extensible", key)
+            });
+        }
+    }
+    drop(object);
+    Ok(target)
+}
+
+/// Fill in the fields a descriptor leaves out
+///
+/// Missing fields come from the current property when the descriptor keeps
+/// its kind (data or accessor); otherwise only `enumerable` and
+/// `configurable` carry over and the rest take their defaults.
+fn complete_descriptor(descriptor: PropertyDescriptor, current: Option<&PropertyDescriptor>) -> PropertyDescriptor {
+    let accessor = descriptor.is_accessor_descriptor();
+    let generic = !accessor && !descriptor.is_data_descriptor();
+    let same_kind = current.filter(|current| generic || accessor == current.is_accessor_descriptor());
+    if let Some(current) = same_kind {
+        return PropertyDescriptor {
+            value: descriptor.value.or_else(|| current.value.clone()),
+            writable: descriptor.writable.or(current.writable),
+            get: descriptor.get.or_else(|| current.get.clone()),
+            set: descriptor.set.or_else(|| current.set.clone()),
+            enumerable: descriptor.enumerable.or(current.enumerable),
+            configurable: descriptor.configurable.or(current.configurable),
+        };
+    }
+    let enumerable = descriptor.enumerable.or(current.and_then(|current| current.enumerable)).unwrap_or(false);
+    let configurable = descriptor.configurable.or(current.and_then(|current| current.configurable)).unwrap_or(false);
+    if accessor {
+        PropertyDescriptor::accessor_descriptor(descriptor.get, descriptor.set, enumerable, configurable)
+    } else {
+        let value = descriptor.value.unwrap_or(Value::Undefined);
+        PropertyDescriptor::data_descriptor(value, descriptor.writable.unwrap_or(false), enumerable, configurable)
+    }
+}
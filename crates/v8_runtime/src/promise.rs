@@ -0,0 +1,430 @@
+//! Promises for V8-Rust JavaScript engine
+//!
+//! This module provides the `Promise` constructor, its prototype methods
+//! and its static methods. Reactions never run synchronously: settling a
+//! promise queues microtasks, which the engine runs once the current
+//! script has finished (see `Runtime::run_microtasks`).
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use super::function::{argument, Function, Host, HostMethod};
+use super::iterator::{collect_builtin, define_method};
+use super::object::{InternalSlot, Object};
+use super::runtime::Microtask;
+use super::value::Value;
+
+/// Settlement state of a promise
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromiseState {
+    Pending,
+    Fulfilled(Value),
+    Rejected(Value),
+}
+
+/// What to do with the outcome of a promise once it settles
+#[derive(Debug, Clone)]
+pub enum ReactionHandler {
+    /// `then(on_fulfilled, on_rejected)`; non-callable handlers pass the outcome through
+    Then { on_fulfilled: Value, on_rejected: Value },
+    /// `finally(on_finally)`: called without arguments, keeps the original outcome
+    Finally(Value),
+    /// Element `index` of a `Promise.all`
+    AllElement { index: usize, state: Rc<RefCell<AllState>> },
+}
+
+/// Progress of a `Promise.all`, shared by the reactions of its elements
+#[derive(Debug)]
+pub struct AllState {
+    values: Vec<Value>,
+    remaining: usize,
+    aggregate: Value,
+}
+
+/// Reaction registered on a promise, run as a microtask once it settles
+#[derive(Debug, Clone)]
+pub struct PromiseReaction {
+    pub handler: ReactionHandler,
+    pub derived: Option<Value>, // Promise settled with the handler's result
+}
+
+/// Internal state of a promise object
+#[derive(Debug, Clone)]
+pub struct PromiseData {
+    pub state: PromiseState,
+    reactions: Vec<PromiseReaction>,
+    // Resolving function pairs are numbered as they are created. A pair
+    // acts only while it is newer than the last pair used, so each pair
+    // resolves at most once and a pair made for a thenable supersedes the
+    // pair that resolved the promise with it.
+    resolving_functions: usize,
+    resolved_by: usize,
+}
+
+impl PromiseData {
+    fn new() -> Self {
+        Self {
+            state: PromiseState::Pending,
+            reactions: Vec::new(),
+            resolving_functions: 0,
+            resolved_by: 0,
+        }
+    }
+}
+
+/// Create the `Promise` constructor with its prototype and static methods
+pub fn create_promise_constructor() -> Rc<RefCell<Function>> {
+    let constructor = Function::host_method("Promise", promise_constructor, 1);
+    {
+        let mut prototype = constructor.prototype.borrow_mut();
+        let methods: [(&str, HostMethod, usize); 3] = [("then", then, 2), ("catch", catch, 1), ("finally", finally, 1)];
+        for (name, function, length) in methods {
+            define_method(&mut prototype, name.to_string(), host_method(name, function, length));
+        }
+    }
+    {
+        let mut statics = constructor.properties.borrow_mut();
+        let methods: [(&str, HostMethod, usize); 4] =
+            [("resolve", resolve, 1), ("reject", reject, 1), ("all", all, 1), ("race", race, 1)];
+        for (name, function, length) in methods {
+            define_method(&mut statics, name.to_string(), host_method(name, function, length));
+        }
+    }
+    Rc::new(RefCell::new(constructor))
+}
+
+/// State of a promise value, or `None` if it is not a promise
+pub fn promise_state(value: &Value) -> Option<PromiseState> {
+    match value {
+        Value::Object(object) => match &object.borrow().internal {
+            Some(InternalSlot::Promise(data)) => Some(data.state.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Run one queued microtask
+pub fn run_microtask(host: &mut dyn Host, task: Microtask) -> Result<(), Value> {
+    match task {
+        Microtask::PromiseReaction { reaction, state } => run_reaction(host, reaction, state),
+        Microtask::ResolveThenable { promise, thenable, then } => {
+            let (resolve, reject) = create_resolving_functions(&promise);
+            if let Err(reason) = host.call(&then, thenable, &[resolve, reject.clone()]) {
+                host.call(&reject, Value::Undefined, &[reason])?;
+            }
+            Ok(())
+        }
+        Microtask::FinalizationCleanup { callback, held } => host.call(&callback, Value::Undefined, &[held]).map(drop),
+    }
+}
+
+fn host_method(name: &str, function: HostMethod, length: usize) -> Value {
+    Value::Function(Rc::new(RefCell::new(Function::host_method(name, function, length))))
+}
+
+fn is_promise(value: &Value) -> bool {
+    promise_state(value).is_some()
+}
+
+/// A new pending promise inheriting from `prototype`
+fn new_promise(prototype: Option<Rc<RefCell<Object>>>) -> Value {
+    let mut object = match prototype {
+        Some(prototype) => Object::with_prototype(prototype),
+        None => Object::new(),
+    };
+    object.internal = Some(InternalSlot::Promise(PromiseData::new()));
+    Value::Object(Rc::new(RefCell::new(object)))
+}
+
+/// Prototype for promises created by a static method called on `this`
+fn constructor_prototype(this: &Value, name: &str) -> Result<Rc<RefCell<Object>>, String> {
+    match this {
+        Value::Function(function) => Ok(function.borrow().prototype.clone()),
+        other => Err(format!("TypeError: Promise.{} called on non-object {}", name, other)),
+    }
+}
+
+/// The receiver of a prototype method, which must be a promise
+fn this_promise(this: &Value, name: &str) -> Result<Rc<RefCell<Object>>, String> {
+    match this {
+        Value::Object(object) if is_promise(this) => Ok(object.clone()),
+        other => Err(format!(
+            "TypeError: Method Promise.prototype.{} called on incompatible receiver {}",
+            name, other
+        )),
+    }
+}
+
+/// Create the `resolve` and `reject` functions passed to an executor
+fn create_resolving_functions(promise: &Value) -> (Value, Value) {
+    let generation = match promise {
+        Value::Object(object) => match &mut object.borrow_mut().internal {
+            Some(InternalSlot::Promise(data)) => {
+                data.resolving_functions += 1;
+                data.resolving_functions
+            }
+            _ => 0,
+        },
+        _ => 0,
+    };
+    let bind = |name: &str, function: HostMethod| {
+        let target = Rc::new(RefCell::new(Function::host_method(name, function, 1)));
+        let bound = Function::bound(target, promise.clone(), vec![Value::Number(generation as f64)]);
+        Value::Function(Rc::new(RefCell::new(bound)))
+    };
+    (bind("resolve", resolve_function), bind("reject", reject_function))
+}
+
+/// Claim the promise for the resolving function pair `generation`
+fn take_resolving_turn(promise: &Value, generation: &Value) -> bool {
+    let generation = generation.to_number() as usize;
+    match promise {
+        Value::Object(object) => match &mut object.borrow_mut().internal {
+            Some(InternalSlot::Promise(data)) if generation > data.resolved_by => {
+                data.resolved_by = generation;
+                true
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Promise resolve function; `this` is the promise, bound with its generation
+fn resolve_function(host: &mut dyn Host, this: Value, args: &[Value]) -> Result<Value, Value> {
+    if take_resolving_turn(&this, &argument(args, 0)) {
+        resolve_promise(host, &this, argument(args, 1));
+    }
+    Ok(Value::Undefined)
+}
+
+/// Promise reject function; `this` is the promise, bound with its generation
+fn reject_function(host: &mut dyn Host, this: Value, args: &[Value]) -> Result<Value, Value> {
+    if take_resolving_turn(&this, &argument(args, 0)) {
+        settle(host, &this, PromiseState::Rejected(argument(args, 1)));
+    }
+    Ok(Value::Undefined)
+}
+
+/// Resolve `promise` with `resolution`, following it if it is a thenable
+fn resolve_promise(host: &mut dyn Host, promise: &Value, resolution: Value) {
+    if resolution.strict_equals(promise) {
+        let error = Value::String("TypeError: Chaining cycle detected for promise #<Promise>".to_string());
+        settle(host, promise, PromiseState::Rejected(error));
+        return;
+    }
+    let then = match &resolution {
+        Value::Object(object) => object.borrow().get_property("then"),
+        _ => None,
+    };
+    match then {
+        Some(then @ Value::Function(_)) => host.enqueue_microtask(Microtask::ResolveThenable {
+            promise: promise.clone(),
+            thenable: resolution,
+            then,
+        }),
+        _ => settle(host, promise, PromiseState::Fulfilled(resolution)),
+    }
+}
+
+/// Fulfill or reject a pending promise, queueing its reactions
+fn settle(host: &mut dyn Host, promise: &Value, state: PromiseState) {
+    let reactions = match promise {
+        Value::Object(object) => match &mut object.borrow_mut().internal {
+            Some(InternalSlot::Promise(data)) if data.state == PromiseState::Pending => {
+                data.state = state.clone();
+                std::mem::take(&mut data.reactions)
+            }
+            _ => return,
+        },
+        _ => return,
+    };
+    for reaction in reactions {
+        host.enqueue_microtask(Microtask::PromiseReaction { reaction, state: state.clone() });
+    }
+}
+
+/// Register a reaction, queueing it right away if the promise has settled
+fn perform_then(host: &mut dyn Host, promise: &Rc<RefCell<Object>>, reaction: PromiseReaction) {
+    let state = match &mut promise.borrow_mut().internal {
+        Some(InternalSlot::Promise(data)) if data.state == PromiseState::Pending => {
+            data.reactions.push(reaction);
+            return;
+        }
+        Some(InternalSlot::Promise(data)) => data.state.clone(),
+        _ => return,
+    };
+    host.enqueue_microtask(Microtask::PromiseReaction { reaction, state });
+}
+
+fn run_reaction(host: &mut dyn Host, reaction: PromiseReaction, state: PromiseState) -> Result<(), Value> {
+    let (argument, rejected) = match state {
+        PromiseState::Fulfilled(value) => (value, false),
+        PromiseState::Rejected(reason) => (reason, true),
+        PromiseState::Pending => return Ok(()),
+    };
+    let passthrough = |argument: Value| if rejected { Err(argument) } else { Ok(argument) };
+    let outcome = match reaction.handler {
+        ReactionHandler::Then { on_fulfilled, on_rejected } => {
+            let handler = if rejected { on_rejected } else { on_fulfilled };
+            if matches!(handler, Value::Function(_)) {
+                host.call(&handler, Value::Undefined, &[argument])
+            } else {
+                passthrough(argument)
+            }
+        }
+        ReactionHandler::Finally(on_finally) => {
+            if matches!(on_finally, Value::Function(_)) {
+                host.call(&on_finally, Value::Undefined, &[]).and_then(|_| passthrough(argument))
+            } else {
+                passthrough(argument)
+            }
+        }
+        ReactionHandler::AllElement { index, state } => {
+            if rejected {
+                let aggregate = state.borrow().aggregate.clone();
+                settle(host, &aggregate, PromiseState::Rejected(argument));
+            } else {
+                let mut all = state.borrow_mut();
+                all.values[index] = argument;
+                all.remaining -= 1;
+                if all.remaining == 0 {
+                    let values = Value::new_array(std::mem::take(&mut all.values));
+                    let aggregate = all.aggregate.clone();
+                    drop(all);
+                    settle(host, &aggregate, PromiseState::Fulfilled(values));
+                }
+            }
+            return Ok(());
+        }
+    };
+    if let Some(derived) = reaction.derived {
+        match outcome {
+            Ok(value) => resolve_promise(host, &derived, value),
+            Err(reason) => settle(host, &derived, PromiseState::Rejected(reason)),
+        }
+    }
+    Ok(())
+}
+
+/// `new Promise(executor)`
+pub fn promise_constructor(host: &mut dyn Host, this: Value, args: &[Value]) -> Result<Value, Value> {
+    match &this {
+        Value::Object(object) if object.borrow().internal.is_none() => {
+            object.borrow_mut().internal = Some(InternalSlot::Promise(PromiseData::new()));
+        }
+        _ => return Err("TypeError: Promise constructor cannot be invoked without 'new'".to_string().into()),
+    }
+    let executor = argument(args, 0);
+    if !matches!(executor, Value::Function(_)) {
+        return Err(format!("TypeError: Promise resolver {} is not a function", executor).into());
+    }
+    let (resolve, reject) = create_resolving_functions(&this);
+    // An exception thrown by the executor rejects the promise
+    if let Err(reason) = host.call(&executor, Value::Undefined, &[resolve, reject.clone()]) {
+        host.call(&reject, Value::Undefined, &[reason])?;
+    }
+    Ok(Value::Undefined)
+}
+
+/// `Promise.prototype.then(onFulfilled, onRejected)`
+pub fn then(host: &mut dyn Host, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handler = ReactionHandler::Then { on_fulfilled: argument(args, 0), on_rejected: argument(args, 1) };
+    chain(host, &this, "then", handler)
+}
+
+/// `Promise.prototype.catch(onRejected)`
+pub fn catch(host: &mut dyn Host, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handler = ReactionHandler::Then { on_fulfilled: Value::Undefined, on_rejected: argument(args, 0) };
+    chain(host, &this, "catch", handler)
+}
+
+/// `Promise.prototype.finally(onFinally)`
+///
+/// A promise returned by `onFinally` is not waited for.
+pub fn finally(host: &mut dyn Host, this: Value, args: &[Value]) -> Result<Value, Value> {
+    chain(host, &this, "finally", ReactionHandler::Finally(argument(args, 0)))
+}
+
+/// Register `handler` on `this` and return the promise it settles
+fn chain(host: &mut dyn Host, this: &Value, name: &str, handler: ReactionHandler) -> Result<Value, Value> {
+    let promise = this_promise(this, name)?;
+    let derived = new_promise(promise.borrow().prototype.clone());
+    perform_then(host, &promise, PromiseReaction { handler, derived: Some(derived.clone()) });
+    Ok(derived)
+}
+
+/// `Promise.resolve(value)`: `value` itself if it is already a promise
+pub fn resolve(host: &mut dyn Host, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let prototype = constructor_prototype(&this, "resolve")?;
+    Ok(promise_resolve(host, prototype, argument(args, 0)))
+}
+
+fn promise_resolve(host: &mut dyn Host, prototype: Rc<RefCell<Object>>, value: Value) -> Value {
+    if is_promise(&value) {
+        return value;
+    }
+    let promise = new_promise(Some(prototype));
+    resolve_promise(host, &promise, value);
+    promise
+}
+
+/// `Promise.reject(reason)`
+pub fn reject(host: &mut dyn Host, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let promise = new_promise(Some(constructor_prototype(&this, "reject")?));
+    settle(host, &promise, PromiseState::Rejected(argument(args, 0)));
+    Ok(promise)
+}
+
+/// Elements of the iterable passed to `Promise.all` or `Promise.race`
+fn elements(value: &Value) -> Result<Vec<Value>, String> {
+    collect_builtin(value).ok_or_else(|| format!("TypeError: {} is not iterable", value))
+}
+
+/// `Promise.all(iterable)`: fulfills with every value, or rejects with the first reason
+pub fn all(host: &mut dyn Host, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let prototype = constructor_prototype(&this, "all")?;
+    let aggregate = new_promise(Some(prototype.clone()));
+    let elements = match elements(&argument(args, 0)) {
+        Ok(elements) => elements,
+        Err(err) => {
+            settle(host, &aggregate, PromiseState::Rejected(Value::String(err)));
+            return Ok(aggregate);
+        }
+    };
+    if elements.is_empty() {
+        settle(host, &aggregate, PromiseState::Fulfilled(Value::new_array(Vec::new())));
+        return Ok(aggregate);
+    }
+    let state = Rc::new(RefCell::new(AllState {
+        values: vec![Value::Undefined; elements.len()],
+        remaining: elements.len(),
+        aggregate: aggregate.clone(),
+    }));
+    for (index, element) in elements.into_iter().enumerate() {
+        let Value::Object(promise) = promise_resolve(host, prototype.clone(), element) else { continue };
+        let handler = ReactionHandler::AllElement { index, state: state.clone() };
+        perform_then(host, &promise, PromiseReaction { handler, derived: None });
+    }
+    Ok(aggregate)
+}
+
+/// `Promise.race(iterable)`: settles like the first element to settle
+pub fn race(host: &mut dyn Host, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let prototype = constructor_prototype(&this, "race")?;
+    let aggregate = new_promise(Some(prototype.clone()));
+    let elements = match elements(&argument(args, 0)) {
+        Ok(elements) => elements,
+        Err(err) => {
+            settle(host, &aggregate, PromiseState::Rejected(Value::String(err)));
+            return Ok(aggregate);
+        }
+    };
+    let (resolve, reject) = create_resolving_functions(&aggregate);
+    for element in elements {
+        let Value::Object(promise) = promise_resolve(host, prototype.clone(), element) else { continue };
+        let handler = ReactionHandler::Then { on_fulfilled: resolve.clone(), on_rejected: reject.clone() };
+        perform_then(host, &promise, PromiseReaction { handler, derived: None });
+    }
+    Ok(aggregate)
+}
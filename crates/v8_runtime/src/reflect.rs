@@ -0,0 +1,224 @@
+//! Reflect for V8-Rust JavaScript engine
+//!
+//! This module provides the `Reflect` namespace object. Its functions do
+//! what the corresponding operators do, but report rejected writes and
+//! deletes as `false` instead of ignoring them.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use super::elements::Elements;
+use super::function::{argument, Function, Host, HostMethod, NativeFunction};
+use super::object::{array_index, Object};
+use super::regexp::regexp_property;
+use super::value::Value;
+
+/// Create the `Reflect` global with its static methods
+pub fn create_reflect_object() -> Rc<RefCell<Object>> {
+    let mut reflect = Object::new();
+    let functions: [(&str, NativeFunction); 6] = [
+        ("get", get),
+        ("set", set),
+        ("has", has),
+        ("deleteProperty", delete_property),
+        ("ownKeys", own_keys),
+        ("getPrototypeOf", get_prototype_of),
+    ];
+    for (name, function) in functions {
+        let function = Function::native(name, function);
+        reflect.set_property(name.to_string(), Value::Function(Rc::new(RefCell::new(function))));
+    }
+    let methods: [(&str, HostMethod, usize); 2] = [("apply", apply, 3), ("construct", construct, 2)];
+    for (name, function, length) in methods {
+        let function = Function::host_method(name, function, length);
+        reflect.set_property(name.to_string(), Value::Function(Rc::new(RefCell::new(function))));
+    }
+    Rc::new(RefCell::new(reflect))
+}
+
+/// The target object and property key of a Reflect call
+fn target_and_key(args: &[Value], name: &str) -> Result<(Value, String), String> {
+    match argument(args, 0) {
+        target @ (Value::Object(_) | Value::Array(_) | Value::Function(_) | Value::RegExp(_)) => {
+            Ok((target, argument(args, 1).to_property_key()))
+        }
+        _ => Err(format!("TypeError: Reflect.{} called on non-object", name)),
+    }
+}
+
+/// The element index `key` denotes, if it is an array index
+fn element_index(key: &str) -> Option<usize> {
+    array_index(key).map(|index| index as usize)
+}
+
+/// Write `key` of an array: whether the array has such a property
+///
+/// Writing past the end leaves holes in the gap. `length` truncates the
+/// array or grows it with holes, and must be an integer in the 32-bit
+/// unsigned range.
+pub fn set_array_property(elements: &RefCell<Elements<Value>>, key: &str, value: Value) -> Result<bool, String> {
+    let mut elements = elements.borrow_mut();
+    if let Some(index) = element_index(key) {
+        elements.set(index, value);
+        Ok(true)
+    } else if key == "length" {
+        let length = value.to_number();
+        if !(0.0..=u32::MAX as f64).contains(&length) || length.fract() != 0.0 {
+            return Err("RangeError: Invalid array length".to_string());
+        }
+        elements.set_len(length as usize);
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// `Reflect.get(target, key)`
+pub fn get(args: &[Value]) -> Result<Value, String> {
+    let (target, key) = target_and_key(args, "get")?;
+    let value = match &target {
+        Value::Object(object) => object.borrow().get_property(&key),
+        Value::Array(elements) => match key.as_str() {
+            "length" => Some(Value::Number(elements.borrow().len() as f64)),
+            _ => element_index(&key).and_then(|index| elements.borrow().get(index).cloned()),
+        },
+        Value::Function(function) => {
+            let function = function.borrow();
+            match key.as_str() {
+                "name" => Some(Value::String(function.name.clone())),
+                "length" => Some(Value::Number(function.length as f64)),
+                "prototype" => Some(Value::Object(function.prototype.clone())),
+                _ => function.properties.borrow().get_property(&key),
+            }
+        }
+        Value::RegExp(regexp) => regexp_property(regexp, &key),
+        _ => None,
+    };
+    Ok(value.unwrap_or(Value::Undefined))
+}
+
+/// `Reflect.set(target, key, value)`: whether the write was accepted
+pub fn set(args: &[Value]) -> Result<Value, String> {
+    let (target, key) = target_and_key(args, "set")?;
+    let value = argument(args, 2);
+    let accepted = match &target {
+        Value::Object(object) => object.borrow_mut().set_property(key, value),
+        Value::Array(elements) => set_array_property(elements, &key, value)?,
+        Value::Function(function) => match (key.as_str(), value) {
+            ("name" | "length", _) => false,
+            ("prototype", Value::Object(prototype)) => {
+                function.borrow_mut().prototype = prototype;
+                true
+            }
+            (_, value) => function.borrow().properties.borrow_mut().set_property(key, value),
+        },
+        Value::RegExp(regexp) if key == "lastIndex" => {
+            let index = value.to_number();
+            regexp.borrow_mut().last_index = if index > 0.0 { index as usize } else { 0 };
+            true
+        }
+        _ => false,
+    };
+    Ok(Value::Boolean(accepted))
+}
+
+/// `Reflect.has(target, key)`: the `in` operator
+pub fn has(args: &[Value]) -> Result<Value, String> {
+    let (target, key) = target_and_key(args, "has")?;
+    let found = match &target {
+        Value::Object(object) => object.borrow().has_property(&key),
+        Value::Array(elements) => {
+            key == "length" || element_index(&key).is_some_and(|index| elements.borrow().has(index))
+        }
+        Value::Function(function) => {
+            matches!(key.as_str(), "name" | "length" | "prototype")
+                || function.borrow().properties.borrow().has_property(&key)
+        }
+        Value::RegExp(regexp) => regexp_property(regexp, &key).is_some(),
+        _ => false,
+    };
+    Ok(Value::Boolean(found))
+}
+
+/// `Reflect.deleteProperty(target, key)`: whether the property is gone
+pub fn delete_property(args: &[Value]) -> Result<Value, String> {
+    let (target, key) = target_and_key(args, "deleteProperty")?;
+    let deleted = match &target {
+        Value::Object(object) => object.borrow_mut().delete_property(&key),
+        Value::Array(elements) => match element_index(&key) {
+            Some(index) => {
+                // The element becomes a hole; the length is kept
+                elements.borrow_mut().remove(index);
+                true
+            }
+            None => key != "length",
+        },
+        Value::Function(function) => match key.as_str() {
+            "prototype" => false,
+            _ => function.borrow().properties.borrow_mut().delete_property(&key),
+        },
+        Value::RegExp(regexp) => regexp_property(regexp, &key).is_none(),
+        _ => true,
+    };
+    Ok(Value::Boolean(deleted))
+}
+
+/// `Reflect.ownKeys(target)`: own property names, enumerable or not
+pub fn own_keys(args: &[Value]) -> Result<Value, String> {
+    let (target, _) = target_and_key(args, "ownKeys")?;
+    let keys = match &target {
+        Value::Object(object) => object.borrow().get_own_property_names(),
+        Value::Array(elements) => {
+            let mut keys: Vec<String> = elements.borrow().indices().map(|index| index.to_string()).collect();
+            keys.push("length".to_string());
+            keys
+        }
+        Value::Function(function) => {
+            let mut keys = vec!["length".to_string(), "name".to_string(), "prototype".to_string()];
+            keys.extend(function.borrow().properties.borrow().get_own_property_names());
+            keys
+        }
+        Value::RegExp(_) => vec!["lastIndex".to_string()],
+        _ => Vec::new(),
+    };
+    Ok(Value::new_array(keys.into_iter().map(Value::String).collect()))
+}
+
+/// `Reflect.getPrototypeOf(target)`
+pub fn get_prototype_of(args: &[Value]) -> Result<Value, String> {
+    let (target, _) = target_and_key(args, "getPrototypeOf")?;
+    Ok(match &target {
+        Value::Object(object) => match &object.borrow().prototype {
+            Some(prototype) => Value::Object(prototype.clone()),
+            None => Value::Null,
+        },
+        _ => Value::Null,
+    })
+}
+
+/// Arguments list from an array, as `Function.prototype.apply` takes it
+fn argument_list(value: &Value) -> Result<Vec<Value>, String> {
+    match value {
+        Value::Array(elements) => Ok(elements.borrow().to_vec()),
+        _ => Err("TypeError: CreateListFromArrayLike called on non-object".to_string()),
+    }
+}
+
+/// `Reflect.apply(function, this, args)`
+pub fn apply(host: &mut dyn Host, _this: Value, args: &[Value]) -> Result<Value, Value> {
+    let function = argument(args, 0);
+    if !matches!(function, Value::Function(_)) {
+        return Err(format!("TypeError: Function.prototype.apply was called on {}, which is not a function", function).into());
+    }
+    let list = argument_list(&argument(args, 2))?;
+    host.call(&function, argument(args, 1), &list)
+}
+
+/// `Reflect.construct(constructor, args)`: the `new` operator
+pub fn construct(host: &mut dyn Host, _this: Value, args: &[Value]) -> Result<Value, Value> {
+    let constructor = argument(args, 0);
+    if !matches!(constructor, Value::Function(_)) {
+        return Err(format!("TypeError: {} is not a constructor", constructor).into());
+    }
+    let list = argument_list(&argument(args, 1))?;
+    host.construct(&constructor, &list)
+}
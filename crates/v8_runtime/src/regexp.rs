@@ -0,0 +1,999 @@
+//! Regular expressions for V8-Rust JavaScript engine
+//!
+//! This module provides `RegExp` values: a pattern compiler, a
+//! backtracking matcher, the `test`/`exec` methods and the string methods
+//! (`match`, `replace`, `split`) that accept a RegExp.
+//!
+//! Patterns are matched over Unicode scalar values; indices exposed to
+//! scripts (`index`, `lastIndex`) are converted to UTF-16 code units.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use super::iterator::method;
+use super::object::Object;
+use super::value::Value;
+
+/// Compiled regular expression with its `lastIndex` state
+#[derive(Debug, Clone)]
+pub struct RegExp {
+    pub source: String,
+    pub flags: String,
+    pub last_index: usize, // In UTF-16 code units, like the script-visible property
+    pattern: Term,
+    group_names: Vec<Option<String>>, // Names of capture groups 1..=n
+}
+
+/// A successful match, in char indices into the input
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match {
+    pub start: usize,
+    pub end: usize,
+    pub captures: Vec<Option<(usize, usize)>>, // Capture groups 1..=n
+}
+
+impl RegExp {
+    /// Compile `source` with the given flags
+    pub fn new(source: &str, flags: &str) -> Result<Self, String> {
+        for (index, flag) in flags.char_indices() {
+            if !"dgimsuvy".contains(flag) || flags[..index].contains(flag) {
+                return Err(format!("SyntaxError: Invalid flags supplied to RegExp constructor '{}'", flags));
+            }
+        }
+        let parser = PatternParser {
+            chars: source.chars().collect(),
+            pos: 0,
+            group_names: Vec::new(),
+        };
+        let (pattern, group_names) = parser
+            .parse()
+            .map_err(|message| format!("SyntaxError: Invalid regular expression: /{}/: {}", source, message))?;
+        Ok(Self {
+            source: source.to_string(),
+            flags: flags.to_string(),
+            last_index: 0,
+            pattern,
+            group_names,
+        })
+    }
+
+    pub fn global(&self) -> bool {
+        self.flags.contains('g')
+    }
+
+    pub fn ignore_case(&self) -> bool {
+        self.flags.contains('i')
+    }
+
+    pub fn multiline(&self) -> bool {
+        self.flags.contains('m')
+    }
+
+    pub fn dot_all(&self) -> bool {
+        self.flags.contains('s')
+    }
+
+    pub fn sticky(&self) -> bool {
+        self.flags.contains('y')
+    }
+
+    /// Match anchored at `pos`
+    pub fn match_at(&self, input: &[char], pos: usize) -> Option<Match> {
+        let matcher = Matcher {
+            input,
+            ignore_case: self.ignore_case(),
+            multiline: self.multiline(),
+            dot_all: self.dot_all(),
+        };
+        let mut captures = vec![None; self.group_names.len() + 1];
+        let mut end = None;
+        let matched = matcher.match_term(&self.pattern, pos, &mut captures, &mut |next, _| {
+            end = Some(next);
+            true
+        });
+        match (matched, end) {
+            (true, Some(end)) => {
+                captures.remove(0);
+                Some(Match { start: pos, end, captures })
+            }
+            _ => None,
+        }
+    }
+
+    /// First match starting at or after `start` (only at `start` when sticky)
+    pub fn find_from(&self, input: &[char], start: usize) -> Option<Match> {
+        if self.sticky() {
+            return self.match_at(input, start);
+        }
+        (start..=input.len()).find_map(|pos| self.match_at(input, pos))
+    }
+
+    /// `RegExp.prototype.exec`: the next match, honouring and updating `lastIndex`
+    pub fn exec(&mut self, input: &str) -> Option<Match> {
+        let chars: Vec<char> = input.chars().collect();
+        let stateful = self.global() || self.sticky();
+        let start = if stateful { char_index(&chars, self.last_index) } else { Some(0) };
+        let found = match start {
+            Some(start) => self.find_from(&chars, start),
+            None => None,
+        };
+        if stateful {
+            self.last_index = match &found {
+                Some(m) => utf16_index(&chars, m.end),
+                None => 0,
+            };
+        }
+        found
+    }
+}
+
+/// Char index of a UTF-16 offset, if it lies within the input
+fn char_index(chars: &[char], utf16: usize) -> Option<usize> {
+    let mut units = 0;
+    for (index, c) in chars.iter().enumerate() {
+        if units >= utf16 {
+            return Some(index);
+        }
+        units += c.len_utf16();
+    }
+    if units >= utf16 {
+        Some(chars.len())
+    } else {
+        None
+    }
+}
+
+/// UTF-16 offset of a char index
+fn utf16_index(chars: &[char], index: usize) -> usize {
+    chars[..index].iter().map(|c| c.len_utf16()).sum()
+}
+
+// ---------------------------------------------------------------------------
+// Pattern syntax
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+enum Term {
+    Char(char),
+    Any,
+    Class { items: Vec<ClassItem>, negated: bool },
+    LineStart,
+    LineEnd,
+    WordBoundary { negated: bool },
+    Group { term: Box<Term>, index: Option<usize> },
+    LookAhead { term: Box<Term>, negated: bool },
+    BackReference(usize),
+    Sequence(Vec<Term>),
+    Alternation(Vec<Term>),
+    Repeat { term: Box<Term>, min: usize, max: Option<usize>, greedy: bool },
+}
+
+#[derive(Debug, Clone)]
+enum ClassItem {
+    Range(char, char),
+    Digit { negated: bool },
+    Word { negated: bool },
+    Space { negated: bool },
+}
+
+impl ClassItem {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            ClassItem::Range(low, high) => (*low..=*high).contains(&c),
+            ClassItem::Digit { negated } => c.is_ascii_digit() != *negated,
+            ClassItem::Word { negated } => is_word_char(c) != *negated,
+            ClassItem::Space { negated } => is_space(c) != *negated,
+        }
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn is_space(c: char) -> bool {
+    c.is_whitespace() || c == '\u{FEFF}'
+}
+
+fn is_line_terminator(c: char) -> bool {
+    matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
+
+/// Recursive-descent parser for pattern source text
+struct PatternParser {
+    chars: Vec<char>,
+    pos: usize,
+    group_names: Vec<Option<String>>,
+}
+
+impl PatternParser {
+    fn parse(mut self) -> Result<(Term, Vec<Option<String>>), String> {
+        let term = self.parse_alternation()?;
+        if self.pos < self.chars.len() {
+            return Err("Unmatched ')'".to_string());
+        }
+        Ok((term, self.group_names))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        let matches = s.chars().enumerate().all(|(offset, c)| self.chars.get(self.pos + offset) == Some(&c));
+        if matches {
+            self.pos += s.chars().count();
+        }
+        matches
+    }
+
+    fn parse_alternation(&mut self) -> Result<Term, String> {
+        let mut alternatives = vec![self.parse_sequence()?];
+        while self.eat('|') {
+            alternatives.push(self.parse_sequence()?);
+        }
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            Term::Alternation(alternatives)
+        })
+    }
+
+    fn parse_sequence(&mut self) -> Result<Term, String> {
+        let mut terms = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.parse_atom()?;
+            terms.push(self.parse_quantifier(atom)?);
+        }
+        Ok(if terms.len() == 1 { terms.pop().unwrap() } else { Term::Sequence(terms) })
+    }
+
+    fn parse_quantifier(&mut self, atom: Term) -> Result<Term, String> {
+        let start = self.pos;
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => match self.parse_braced_quantifier() {
+                Some(bounds) => bounds,
+                None => return Ok(atom), // A '{' that is not a quantifier is a literal
+            },
+            _ => return Ok(atom),
+        };
+        if self.pos == start {
+            self.pos += 1;
+        }
+        if let Some(max) = max {
+            if max < min {
+                return Err("numbers out of order in {} quantifier".to_string());
+            }
+        }
+        let greedy = !self.eat('?');
+        Ok(Term::Repeat { term: Box::new(atom), min, max, greedy })
+    }
+
+    /// `{n}`, `{n,}` or `{n,m}`; leaves the position untouched if malformed
+    fn parse_braced_quantifier(&mut self) -> Option<(usize, Option<usize>)> {
+        let start = self.pos;
+        self.pos += 1; // Skip '{'
+        let bounds = self.parse_decimal().and_then(|min| {
+            if self.eat('}') {
+                Some((min, Some(min)))
+            } else if self.eat(',') {
+                if self.eat('}') {
+                    Some((min, None))
+                } else {
+                    let max = self.parse_decimal()?;
+                    self.eat('}').then_some((min, Some(max)))
+                }
+            } else {
+                None
+            }
+        });
+        if bounds.is_none() {
+            self.pos = start;
+        }
+        bounds
+    }
+
+    fn parse_decimal(&mut self) -> Option<usize> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        digits.parse().ok()
+    }
+
+    fn parse_atom(&mut self) -> Result<Term, String> {
+        let c = self.peek().ok_or("Unexpected end of pattern")?;
+        self.pos += 1;
+        match c {
+            '^' => Ok(Term::LineStart),
+            '$' => Ok(Term::LineEnd),
+            '.' => Ok(Term::Any),
+            '(' => self.parse_group(),
+            '[' => self.parse_class(),
+            '\\' => self.parse_atom_escape(),
+            '*' | '+' | '?' => Err("Nothing to repeat".to_string()),
+            '{' => {
+                self.pos -= 1;
+                if self.parse_braced_quantifier().is_some() {
+                    return Err("Nothing to repeat".to_string());
+                }
+                self.pos += 1;
+                Ok(Term::Char('{'))
+            }
+            c => Ok(Term::Char(c)),
+        }
+    }
+
+    fn parse_group(&mut self) -> Result<Term, String> {
+        let term = if self.eat_str("?:") {
+            Term::Group { term: Box::new(self.parse_alternation()?), index: None }
+        } else if self.eat_str("?=") || self.eat_str("?!") {
+            let negated = self.chars[self.pos - 1] == '!';
+            Term::LookAhead { term: Box::new(self.parse_alternation()?), negated }
+        } else if self.eat_str("?<=") || self.eat_str("?<!") {
+            return Err("Lookbehind assertions are not supported".to_string());
+        } else if self.eat_str("?<") {
+            let mut name = String::new();
+            while let Some(c) = self.peek() {
+                self.pos += 1;
+                if c == '>' {
+                    break;
+                }
+                name.push(c);
+            }
+            if name.is_empty() || !name.chars().all(|c| is_word_char(c) || c == '$') {
+                return Err("Invalid capture group name".to_string());
+            }
+            if self.group_names.contains(&Some(name.clone())) {
+                return Err("Duplicate capture group name".to_string());
+            }
+            self.group_names.push(Some(name));
+            let index = self.group_names.len();
+            Term::Group { term: Box::new(self.parse_alternation()?), index: Some(index) }
+        } else if self.peek() == Some('?') {
+            return Err("Invalid group".to_string());
+        } else {
+            self.group_names.push(None);
+            let index = self.group_names.len();
+            Term::Group { term: Box::new(self.parse_alternation()?), index: Some(index) }
+        };
+        if !self.eat(')') {
+            return Err("Unterminated group".to_string());
+        }
+        Ok(term)
+    }
+
+    fn parse_class(&mut self) -> Result<Term, String> {
+        let negated = self.eat('^');
+        let mut items = Vec::new();
+        loop {
+            let low = match self.peek() {
+                None => return Err("Unterminated character class".to_string()),
+                Some(']') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(_) => self.parse_class_atom()?,
+            };
+            let is_range = self.peek() == Some('-') && !matches!(self.chars.get(self.pos + 1), Some(']') | None);
+            match (low, is_range) {
+                (ClassItem::Range(low, _), true) => {
+                    self.pos += 1; // Skip '-'
+                    match self.parse_class_atom()? {
+                        ClassItem::Range(high, _) if low <= high => items.push(ClassItem::Range(low, high)),
+                        ClassItem::Range(_, _) => return Err("Range out of order in character class".to_string()),
+                        // `[a-\d]` is a literal '-' next to a class escape
+                        set => items.extend([ClassItem::Range(low, low), ClassItem::Range('-', '-'), set]),
+                    }
+                }
+                (item, _) => items.push(item),
+            }
+        }
+        Ok(Term::Class { items, negated })
+    }
+
+    fn parse_class_atom(&mut self) -> Result<ClassItem, String> {
+        let c = self.peek().ok_or("Unterminated character class")?;
+        self.pos += 1;
+        if c != '\\' {
+            return Ok(ClassItem::Range(c, c));
+        }
+        let escaped = self.peek().ok_or("\\ at end of pattern")?;
+        self.pos += 1;
+        if let Some(set) = class_escape(escaped) {
+            return Ok(set);
+        }
+        let c = match escaped {
+            'b' => '\u{8}',
+            '-' => '-',
+            other => self.parse_character_escape(other)?,
+        };
+        Ok(ClassItem::Range(c, c))
+    }
+
+    fn parse_atom_escape(&mut self) -> Result<Term, String> {
+        let escaped = self.peek().ok_or("\\ at end of pattern")?;
+        self.pos += 1;
+        if let Some(set) = class_escape(escaped) {
+            return Ok(Term::Class { items: vec![set], negated: false });
+        }
+        match escaped {
+            'b' => Ok(Term::WordBoundary { negated: false }),
+            'B' => Ok(Term::WordBoundary { negated: true }),
+            '1'..='9' => {
+                self.pos -= 1;
+                let index = self.parse_decimal().unwrap_or(0);
+                Ok(Term::BackReference(index))
+            }
+            other => Ok(Term::Char(self.parse_character_escape(other)?)),
+        }
+    }
+
+    /// Character escapes shared by atoms and classes; `escaped` follows the '\'
+    fn parse_character_escape(&mut self, escaped: char) -> Result<char, String> {
+        let c = match escaped {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\u{B}',
+            'f' => '\u{C}',
+            '0' => '\0',
+            'c' => match self.peek() {
+                Some(letter) if letter.is_ascii_alphabetic() => {
+                    self.pos += 1;
+                    char::from(letter as u8 % 32)
+                }
+                _ => '\\', // Annex B: `\c` not followed by a letter is literal
+            },
+            'x' => self.parse_hex_escape(2).unwrap_or('x'),
+            'u' => {
+                if self.eat('{') {
+                    let start = self.pos;
+                    while self.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+                        self.pos += 1;
+                    }
+                    let digits: String = self.chars[start..self.pos].iter().collect();
+                    let code = u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32);
+                    match (code, self.eat('}')) {
+                        (Some(c), true) => c,
+                        _ => return Err("Invalid Unicode escape".to_string()),
+                    }
+                } else {
+                    self.parse_hex_escape(4).unwrap_or('u')
+                }
+            }
+            other => other, // Identity escape
+        };
+        Ok(c)
+    }
+
+    fn parse_hex_escape(&mut self, digits: usize) -> Option<char> {
+        let hex: String = self.chars.get(self.pos..self.pos + digits)?.iter().collect();
+        let c = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32)?;
+        self.pos += digits;
+        Some(c)
+    }
+}
+
+fn class_escape(escaped: char) -> Option<ClassItem> {
+    match escaped {
+        'd' => Some(ClassItem::Digit { negated: false }),
+        'D' => Some(ClassItem::Digit { negated: true }),
+        'w' => Some(ClassItem::Word { negated: false }),
+        'W' => Some(ClassItem::Word { negated: true }),
+        's' => Some(ClassItem::Space { negated: false }),
+        'S' => Some(ClassItem::Space { negated: true }),
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Matching
+// ---------------------------------------------------------------------------
+
+type Captures = Vec<Option<(usize, usize)>>;
+
+/// Backtracking matcher: each term calls the continuation `k` with the
+/// position after it, and backtracks when the continuation fails
+struct Matcher<'a> {
+    input: &'a [char],
+    ignore_case: bool,
+    multiline: bool,
+    dot_all: bool,
+}
+
+impl Matcher<'_> {
+    fn match_term(
+        &self,
+        term: &Term,
+        pos: usize,
+        captures: &mut Captures,
+        k: &mut dyn FnMut(usize, &mut Captures) -> bool,
+    ) -> bool {
+        match term {
+            Term::Char(expected) => match self.input.get(pos) {
+                Some(&c) if self.chars_equal(c, *expected) => k(pos + 1, captures),
+                _ => false,
+            },
+            Term::Any => match self.input.get(pos) {
+                Some(&c) if self.dot_all || !is_line_terminator(c) => k(pos + 1, captures),
+                _ => false,
+            },
+            Term::Class { items, negated } => match self.input.get(pos) {
+                Some(&c) if self.class_matches(items, c) != *negated => k(pos + 1, captures),
+                _ => false,
+            },
+            Term::LineStart => {
+                let at_start = pos == 0 || (self.multiline && is_line_terminator(self.input[pos - 1]));
+                at_start && k(pos, captures)
+            }
+            Term::LineEnd => {
+                let at_end = pos == self.input.len() || (self.multiline && is_line_terminator(self.input[pos]));
+                at_end && k(pos, captures)
+            }
+            Term::WordBoundary { negated } => {
+                let before = pos > 0 && is_word_char(self.input[pos - 1]);
+                let after = self.input.get(pos).is_some_and(|&c| is_word_char(c));
+                ((before != after) != *negated) && k(pos, captures)
+            }
+            Term::Group { term, index } => self.match_term(term, pos, captures, &mut |end, captures| match index {
+                Some(index) => {
+                    let previous = captures[*index];
+                    captures[*index] = Some((pos, end));
+                    if k(end, captures) {
+                        return true;
+                    }
+                    captures[*index] = previous;
+                    false
+                }
+                None => k(end, captures),
+            }),
+            Term::LookAhead { term, negated } => {
+                let mut inner = captures.clone();
+                let matched = self.match_term(term, pos, &mut inner, &mut |_, _| true);
+                if *negated {
+                    return !matched && k(pos, captures);
+                }
+                if !matched {
+                    return false;
+                }
+                // Captures from a positive lookahead are visible afterwards
+                let saved = std::mem::replace(captures, inner);
+                if k(pos, captures) {
+                    return true;
+                }
+                *captures = saved;
+                false
+            }
+            Term::BackReference(index) => match captures.get(*index).copied().flatten() {
+                Some((start, end)) => {
+                    let length = end - start;
+                    let matches = pos + length <= self.input.len()
+                        && (0..length).all(|offset| self.chars_equal(self.input[pos + offset], self.input[start + offset]));
+                    matches && k(pos + length, captures)
+                }
+                // A group that has not participated matches the empty string
+                None => k(pos, captures),
+            },
+            Term::Sequence(terms) => self.match_sequence(terms, pos, captures, k),
+            Term::Alternation(alternatives) => alternatives
+                .iter()
+                .any(|alternative| self.match_term(alternative, pos, captures, k)),
+            Term::Repeat { term, min, max, greedy } => {
+                self.match_repeat(term, *min, *max, *greedy, 0, pos, captures, k)
+            }
+        }
+    }
+
+    fn match_sequence(
+        &self,
+        terms: &[Term],
+        pos: usize,
+        captures: &mut Captures,
+        k: &mut dyn FnMut(usize, &mut Captures) -> bool,
+    ) -> bool {
+        match terms.split_first() {
+            None => k(pos, captures),
+            Some((first, rest)) => self.match_term(first, pos, captures, &mut |next, captures| {
+                self.match_sequence(rest, next, captures, k)
+            }),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn match_repeat(
+        &self,
+        term: &Term,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+        count: usize,
+        pos: usize,
+        captures: &mut Captures,
+        k: &mut dyn FnMut(usize, &mut Captures) -> bool,
+    ) -> bool {
+        if count >= min && !greedy && k(pos, captures) {
+            return true;
+        }
+        if max.is_none_or(|max| count < max) {
+            let matched = self.match_term(term, pos, captures, &mut |next, captures| {
+                // An iteration that consumes nothing cannot make progress
+                if next == pos && count >= min {
+                    return false;
+                }
+                self.match_repeat(term, min, max, greedy, count + 1, next, captures, k)
+            });
+            if matched {
+                return true;
+            }
+        }
+        count >= min && greedy && k(pos, captures)
+    }
+
+    fn chars_equal(&self, a: char, b: char) -> bool {
+        a == b || (self.ignore_case && fold_case(a) == fold_case(b))
+    }
+
+    fn class_matches(&self, items: &[ClassItem], c: char) -> bool {
+        if items.iter().any(|item| item.matches(c)) {
+            return true;
+        }
+        if !self.ignore_case {
+            return false;
+        }
+        let variants = c.to_lowercase().chain(c.to_uppercase());
+        variants.filter(|&variant| variant != c).any(|variant| items.iter().any(|item| item.matches(variant)))
+    }
+}
+
+/// Simple case folding for case-insensitive comparison
+fn fold_case(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(folded), None) => folded,
+        _ => c,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Script-visible methods
+// ---------------------------------------------------------------------------
+
+/// `RegExp(pattern, flags)`, also used for `new RegExp(...)`
+pub fn regexp_constructor(args: &[Value]) -> Result<Value, String> {
+    let (source, flags) = match (args.first(), args.get(1)) {
+        // Copying a RegExp keeps its source; explicit flags replace its flags
+        (Some(Value::RegExp(regexp)), flags) => {
+            let regexp = regexp.borrow();
+            let flags = match flags {
+                None | Some(Value::Undefined) => regexp.flags.clone(),
+                Some(flags) => flags.to_string(),
+            };
+            (regexp.source.clone(), flags)
+        }
+        (source, flags) => (
+            match source {
+                None | Some(Value::Undefined) => "(?:)".to_string(),
+                Some(source) => source.to_string(),
+            },
+            match flags {
+                None | Some(Value::Undefined) => String::new(),
+                Some(flags) => flags.to_string(),
+            },
+        ),
+    };
+    Ok(Value::new_regexp(RegExp::new(&source, &flags)?))
+}
+
+/// Property of a RegExp value: its flags, `lastIndex`, or a method
+pub fn regexp_property(regexp: &Rc<RefCell<RegExp>>, key: &str) -> Option<Value> {
+    let regexp = regexp.borrow();
+    let value = match key {
+        "source" => Value::String(regexp.source.clone()),
+        "flags" => Value::String(regexp.flags.clone()),
+        "global" => Value::Boolean(regexp.global()),
+        "ignoreCase" => Value::Boolean(regexp.ignore_case()),
+        "multiline" => Value::Boolean(regexp.multiline()),
+        "dotAll" => Value::Boolean(regexp.dot_all()),
+        "unicode" => Value::Boolean(regexp.flags.contains('u')),
+        "sticky" => Value::Boolean(regexp.sticky()),
+        "lastIndex" => Value::Number(regexp.last_index as f64),
+        "test" => method("test", test, 1),
+        "exec" => method("exec", exec, 1),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// String method that works with RegExp arguments, by name
+pub fn string_method(key: &str) -> Option<Value> {
+    match key {
+        "match" => Some(method("match", string_match, 1)),
+        "replace" => Some(method("replace", string_replace, 2)),
+        "split" => Some(method("split", string_split, 2)),
+        _ => None,
+    }
+}
+
+fn this_regexp(this: &Value, name: &str) -> Result<Rc<RefCell<RegExp>>, String> {
+    match this {
+        Value::RegExp(regexp) => Ok(regexp.clone()),
+        other => Err(format!("TypeError: Method RegExp.prototype.{} called on incompatible receiver {}", name, other)),
+    }
+}
+
+fn argument_string(args: &[Value], index: usize) -> String {
+    args.get(index).cloned().unwrap_or(Value::Undefined).to_string()
+}
+
+/// `RegExp.prototype.test(string)`
+pub fn test(this: Value, args: &[Value]) -> Result<Value, String> {
+    let regexp = this_regexp(&this, "test")?;
+    let input = argument_string(args, 0);
+    let found = regexp.borrow_mut().exec(&input).is_some();
+    Ok(Value::Boolean(found))
+}
+
+/// `RegExp.prototype.exec(string)`
+pub fn exec(this: Value, args: &[Value]) -> Result<Value, String> {
+    let regexp = this_regexp(&this, "exec")?;
+    let input = argument_string(args, 0);
+    let found = regexp.borrow_mut().exec(&input);
+    Ok(match found {
+        Some(m) => match_result(&regexp.borrow(), &input, &m),
+        None => Value::Null,
+    })
+}
+
+/// Build the result of `exec`: the match and its groups at indices
+/// `0..=n`, plus `index`, `input` and `groups`
+///
+/// Arrays cannot carry extra properties yet, so the result is an
+/// array-like object with a `length`.
+fn match_result(regexp: &RegExp, input: &str, m: &Match) -> Value {
+    let chars: Vec<char> = input.chars().collect();
+    let text = |(start, end): (usize, usize)| Value::String(chars[start..end].iter().collect());
+    let mut result = Object::new();
+    result.set_property("0".to_string(), text((m.start, m.end)));
+    for (index, capture) in m.captures.iter().enumerate() {
+        result.set_property((index + 1).to_string(), capture.map(text).unwrap_or(Value::Undefined));
+    }
+    result.set_property("length".to_string(), Value::Number((m.captures.len() + 1) as f64));
+    result.set_property("index".to_string(), Value::Number(utf16_index(&chars, m.start) as f64));
+    result.set_property("input".to_string(), Value::String(input.to_string()));
+    let groups = if regexp.group_names.iter().any(Option::is_some) {
+        let mut groups = Object::new();
+        for (name, capture) in regexp.group_names.iter().zip(&m.captures) {
+            if let Some(name) = name {
+                groups.set_property(name.clone(), capture.map(text).unwrap_or(Value::Undefined));
+            }
+        }
+        Value::Object(Rc::new(RefCell::new(groups)))
+    } else {
+        Value::Undefined
+    };
+    result.set_property("groups".to_string(), groups);
+    Value::Object(Rc::new(RefCell::new(result)))
+}
+
+/// The argument as a RegExp, compiling strings as patterns
+fn to_regexp(value: Option<&Value>) -> Result<Rc<RefCell<RegExp>>, String> {
+    match value {
+        Some(Value::RegExp(regexp)) => Ok(regexp.clone()),
+        None | Some(Value::Undefined) => Ok(Rc::new(RefCell::new(RegExp::new("(?:)", "")?))),
+        Some(other) => Ok(Rc::new(RefCell::new(RegExp::new(&other.to_string(), "")?))),
+    }
+}
+
+/// All matches of a global RegExp, advancing past empty matches
+fn all_matches(regexp: &RegExp, chars: &[char]) -> Vec<Match> {
+    let mut matches = Vec::new();
+    let mut start = 0;
+    while start <= chars.len() {
+        let Some(m) = regexp.find_from(chars, start) else { break };
+        start = if m.end == m.start { m.end + 1 } else { m.end };
+        matches.push(m);
+    }
+    matches
+}
+
+/// `String.prototype.match(regexp)`
+pub fn string_match(this: Value, args: &[Value]) -> Result<Value, String> {
+    let input = this.to_string();
+    let regexp = to_regexp(args.first())?;
+    if !regexp.borrow().global() {
+        return exec(Value::RegExp(regexp), &[Value::String(input)]);
+    }
+    let chars: Vec<char> = input.chars().collect();
+    let mut regexp = regexp.borrow_mut();
+    regexp.last_index = 0;
+    let matches: Vec<Value> = all_matches(&regexp, &chars)
+        .into_iter()
+        .map(|m| Value::String(chars[m.start..m.end].iter().collect()))
+        .collect();
+    Ok(if matches.is_empty() { Value::Null } else { Value::new_array(matches) })
+}
+
+/// `String.prototype.replace(pattern, replacement)`
+///
+/// A string pattern replaces its first occurrence; a RegExp replaces its
+/// first match, or every match when global. Replacement functions need
+/// the interpreter to call back into script code and are not supported.
+pub fn string_replace(this: Value, args: &[Value]) -> Result<Value, String> {
+    let input = this.to_string();
+    let chars: Vec<char> = input.chars().collect();
+    let replacement = match args.get(1) {
+        Some(Value::Function(_)) => {
+            return Err("TypeError: Replacement functions are not supported yet".to_string())
+        }
+        other => other.cloned().unwrap_or(Value::Undefined).to_string(),
+    };
+    let (matches, group_names) = match args.first() {
+        Some(Value::RegExp(regexp)) => {
+            let mut regexp = regexp.borrow_mut();
+            let matches = if regexp.global() {
+                regexp.last_index = 0;
+                all_matches(&regexp, &chars)
+            } else {
+                regexp.exec(&input).into_iter().collect()
+            };
+            (matches, regexp.group_names.clone())
+        }
+        pattern => {
+            let pattern: Vec<char> = pattern.cloned().unwrap_or(Value::Undefined).to_string().chars().collect();
+            let position = (0..=chars.len().saturating_sub(pattern.len()))
+                .find(|&start| chars[start..].starts_with(&pattern));
+            let matches = position
+                .map(|start| Match { start, end: start + pattern.len(), captures: Vec::new() })
+                .into_iter()
+                .collect();
+            (matches, Vec::new())
+        }
+    };
+
+    let mut result = String::new();
+    let mut last = 0;
+    for m in &matches {
+        result.extend(&chars[last..m.start]);
+        expand_replacement(&mut result, &replacement, &chars, m, &group_names);
+        last = m.end;
+    }
+    result.extend(&chars[last..]);
+    Ok(Value::String(result))
+}
+
+/// Expand `$$`, `$&`, `` $` ``, `$'`, `$n` and `$<name>` in a replacement string
+fn expand_replacement(result: &mut String, replacement: &str, chars: &[char], m: &Match, group_names: &[Option<String>]) {
+    let capture = |index: usize| -> String {
+        match m.captures.get(index - 1).copied().flatten() {
+            Some((start, end)) => chars[start..end].iter().collect(),
+            None => String::new(),
+        }
+    };
+    let template: Vec<char> = replacement.chars().collect();
+    let mut i = 0;
+    while i < template.len() {
+        let c = template[i];
+        let next = template.get(i + 1).copied();
+        match (c, next) {
+            ('$', Some('$')) => result.push('$'),
+            ('$', Some('&')) => result.extend(&chars[m.start..m.end]),
+            ('$', Some('`')) => result.extend(&chars[..m.start]),
+            ('$', Some('\'')) => result.extend(&chars[m.end..]),
+            ('$', Some(d)) if d.is_ascii_digit() => {
+                // Prefer a two-digit group number when that group exists
+                let one = d.to_digit(10).unwrap() as usize;
+                let two = template
+                    .get(i + 2)
+                    .and_then(|d2| d2.to_digit(10))
+                    .map(|d2| one * 10 + d2 as usize)
+                    .filter(|&n| n >= 1 && n <= m.captures.len());
+                match two {
+                    Some(n) => {
+                        result.push_str(&capture(n));
+                        i += 1;
+                    }
+                    None if one >= 1 && one <= m.captures.len() => result.push_str(&capture(one)),
+                    None => {
+                        result.push('$');
+                        i += 1;
+                        continue;
+                    }
+                }
+            }
+            ('$', Some('<')) if group_names.iter().any(Option::is_some) => {
+                let close = template[i + 2..].iter().position(|&c| c == '>');
+                match close {
+                    Some(length) => {
+                        let name: String = template[i + 2..i + 2 + length].iter().collect();
+                        if let Some(index) = group_names.iter().position(|n| n.as_deref() == Some(name.as_str())) {
+                            result.push_str(&capture(index + 1));
+                        }
+                        i += length + 3;
+                        continue;
+                    }
+                    None => {
+                        result.push('$');
+                        i += 1;
+                        continue;
+                    }
+                }
+            }
+            _ => {
+                result.push(c);
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+}
+
+/// `String.prototype.split(separator, limit)`
+pub fn string_split(this: Value, args: &[Value]) -> Result<Value, String> {
+    let input = this.to_string();
+    let chars: Vec<char> = input.chars().collect();
+    let limit = match args.get(1) {
+        None | Some(Value::Undefined) => usize::MAX,
+        Some(limit) => {
+            let n = limit.to_number();
+            if n.is_finite() { n.trunc().rem_euclid(4294967296.0) as usize } else { 0 }
+        }
+    };
+    let text = |start: usize, end: usize| Value::String(chars[start..end].iter().collect());
+
+    let mut parts = Vec::new();
+    match args.first() {
+        None | Some(Value::Undefined) => parts.push(Value::String(input.clone())),
+        Some(Value::RegExp(regexp)) => {
+            let regexp = regexp.borrow();
+            if chars.is_empty() {
+                if regexp.match_at(&chars, 0).is_none() {
+                    parts.push(Value::String(String::new()));
+                }
+            } else {
+                let mut p = 0;
+                let mut q = 0;
+                while q < chars.len() {
+                    match regexp.match_at(&chars, q) {
+                        Some(m) if m.end != p && m.end <= chars.len() => {
+                            parts.push(text(p, q));
+                            for capture in &m.captures {
+                                parts.push(capture.map(|(start, end)| text(start, end)).unwrap_or(Value::Undefined));
+                            }
+                            p = m.end;
+                            q = if m.end == q { q + 1 } else { m.end };
+                        }
+                        _ => q += 1,
+                    }
+                }
+                parts.push(text(p, chars.len()));
+            }
+        }
+        Some(separator) => {
+            let separator = separator.to_string();
+            if separator.is_empty() {
+                parts.extend(chars.iter().map(|c| Value::String(c.to_string())));
+            } else {
+                parts.extend(input.split(separator.as_str()).map(|part| Value::String(part.to_string())));
+            }
+        }
+    }
+    parts.truncate(limit);
+    Ok(Value::new_array(parts))
+}
@@ -0,0 +1,156 @@
+//! Runtime for V8-Rust JavaScript engine
+//! 
+//! This module provides the runtime that owns the named execution contexts
+//! and host services such as the clock and the microtask queue.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::time::{SystemTime, UNIX_EPOCH};
+use super::context::Context;
+use super::date::Date;
+use super::function::Host;
+use super::object::Object;
+use super::promise::{self, PromiseReaction, PromiseState};
+use super::value::Value;
+use super::weak::{self, FinalizationRegistries};
+
+/// Source of the current time, in milliseconds since the epoch
+pub type Clock = Box<dyn Fn() -> f64>;
+
+/// Job queued to run once the current script has finished
+#[derive(Debug, Clone)]
+pub enum Microtask {
+    /// Run a reaction registered on a promise that has settled
+    PromiseReaction { reaction: PromiseReaction, state: PromiseState },
+    /// Make `promise` follow `thenable` by calling its `then` method
+    ResolveThenable { promise: Value, thenable: Value, then: Value },
+    /// Call a `FinalizationRegistry` cleanup callback for a collected target
+    FinalizationCleanup { callback: Value, held: Value },
+}
+
+/// Microtask queue shared between the runtime and the engine
+pub type MicrotaskQueue = Rc<RefCell<VecDeque<Microtask>>>;
+
+/// JavaScript runtime
+pub struct Runtime {
+    contexts: HashMap<String, Context>,
+    clock: Clock,
+    microtasks: MicrotaskQueue,
+    finalization_registries: FinalizationRegistries,
+}
+
+impl Runtime {
+    /// Create a new runtime with no contexts, using the system clock
+    pub fn new() -> Self {
+        Self {
+            contexts: HashMap::new(),
+            clock: Box::new(system_time),
+            microtasks: Rc::new(RefCell::new(VecDeque::new())),
+            finalization_registries: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+    
+    /// Replace the clock used by `Date.now()` and `new Date()`
+    pub fn set_clock(&mut self, clock: impl Fn() -> f64 + 'static) {
+        self.clock = Box::new(clock);
+    }
+    
+    /// `Date.now()`
+    pub fn now(&self) -> f64 {
+        (self.clock)().trunc()
+    }
+    
+    /// `new Date(...)`
+    pub fn construct_date(&self, args: &[Value]) -> Date {
+        Date::construct(args, self.now())
+    }
+    
+    /// The microtask queue, for engines that queue jobs while running scripts
+    pub fn microtask_queue(&self) -> MicrotaskQueue {
+        self.microtasks.clone()
+    }
+    
+    /// Queue a job to run after the current script
+    pub fn enqueue_microtask(&self, task: Microtask) {
+        self.microtasks.borrow_mut().push_back(task);
+    }
+    
+    /// Run queued microtasks, including those they queue, until none are left
+    pub fn run_microtasks(&self, host: &mut dyn Host) -> Result<(), Value> {
+        loop {
+            // The queue must not stay borrowed while a job queues more jobs
+            let task = self.microtasks.borrow_mut().pop_front();
+            match task {
+                Some(task) => promise::run_microtask(host, task)?,
+                None => return Ok(()),
+            }
+        }
+    }
+    
+    /// The registries scripts created, for engines whose host tracks them
+    pub fn finalization_registries(&self) -> FinalizationRegistries {
+        self.finalization_registries.clone()
+    }
+    
+    /// Queue the cleanup callbacks for `FinalizationRegistry` targets that
+    /// have been collected, returning how many were queued
+    ///
+    /// Values are freed as soon as they become unreachable, so this only
+    /// notices which targets are gone; the callbacks run with the other
+    /// microtasks.
+    pub fn collect_garbage(&self) -> usize {
+        let tasks = weak::collect_finalized(&self.finalization_registries);
+        let count = tasks.len();
+        self.microtasks.borrow_mut().extend(tasks);
+        count
+    }
+    
+    /// Create a named context with its own global object
+    ///
+    /// An existing context with the same name is replaced.
+    pub fn create_context(&mut self, name: &str) -> &mut Context {
+        let global_object = Rc::new(RefCell::new(Object::new()));
+        self.contexts.insert(name.to_string(), Context::new(global_object));
+        self.contexts.get_mut(name).unwrap()
+    }
+    
+    /// Get a named context
+    pub fn get_context(&self, name: &str) -> Option<&Context> {
+        self.contexts.get(name)
+    }
+    
+    /// Get a named context mutably
+    pub fn get_context_mut(&mut self, name: &str) -> Option<&mut Context> {
+        self.contexts.get_mut(name)
+    }
+    
+    /// Remove a named context
+    pub fn remove_context(&mut self, name: &str) -> Option<Context> {
+        self.contexts.remove(name)
+    }
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Runtime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Runtime")
+            .field("contexts", &self.contexts)
+            .field("microtasks", &self.microtasks.borrow().len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Milliseconds since the epoch according to the system clock
+fn system_time() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as f64)
+        .unwrap_or(0.0)
+}
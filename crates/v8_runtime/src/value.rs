@@ -2,12 +2,18 @@
 //! 
 //! This module provides the core value system for JavaScript values.
 
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use std::cell::RefCell;
+use super::elements::Elements;
+use super::function::Host;
+use super::iterator::{has_instance_key, to_primitive_key};
+use super::object::{InternalSlot, PropertyDescriptor};
 
 /// Represents a JavaScript value
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum Value {
+    #[default]
     Undefined,
     Null,
     Boolean(bool),
@@ -17,11 +23,40 @@ pub enum Value {
     BigInt(String),
     Object(Rc<RefCell<Object>>),
     Function(Rc<RefCell<Function>>),
-    Array(Vec<Value>),
-    RegExp(String, String), // pattern, flags
+    Array(Rc<RefCell<Elements<Value>>>), // Shared, so copies alias the same elements
+    RegExp(Rc<RefCell<RegExp>>),
+}
+
+/// The type `to_primitive` should produce, passed to `Symbol.toPrimitive` as its hint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreferredType {
+    Default,
+    Number,
+    String,
+}
+
+impl PreferredType {
+    /// The hint string: `"default"`, `"number"` or `"string"`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PreferredType::Default => "default",
+            PreferredType::Number => "number",
+            PreferredType::String => "string",
+        }
+    }
 }
 
 impl Value {
+    /// Create an array value owning `elements`
+    pub fn new_array(elements: Vec<Value>) -> Value {
+        Value::Array(Rc::new(RefCell::new(elements.into())))
+    }
+
+    /// Create a RegExp value owning `regexp`
+    pub fn new_regexp(regexp: RegExp) -> Value {
+        Value::RegExp(Rc::new(RefCell::new(regexp)))
+    }
+    
     /// Check if the value is undefined
     pub fn is_undefined(&self) -> bool {
         matches!(self, Value::Undefined)
@@ -62,6 +97,11 @@ impl Value {
         matches!(self, Value::Object(_))
     }
     
+    /// Check if the value is a primitive rather than an object of any kind
+    pub fn is_primitive(&self) -> bool {
+        !matches!(self, Value::Object(_) | Value::Function(_) | Value::Array(_) | Value::RegExp(_))
+    }
+    
     /// Check if the value is a function
     pub fn is_function(&self) -> bool {
         matches!(self, Value::Function(_))
@@ -74,7 +114,7 @@ impl Value {
     
     /// Check if the value is a RegExp
     pub fn is_regexp(&self) -> bool {
-        matches!(self, Value::RegExp(_, _))
+        matches!(self, Value::RegExp(_))
     }
     
     /// Convert value to boolean according to ECMAScript rules
@@ -89,7 +129,7 @@ impl Value {
             Value::Object(_) => true,
             Value::Function(_) => true,
             Value::Array(_) => true,
-            Value::RegExp(_, _) => true,
+            Value::RegExp(_) => true,
         }
     }
     
@@ -112,10 +152,13 @@ impl Value {
             }
             Value::Symbol(_) => f64::NAN,
             Value::BigInt(_) => f64::NAN, // BigInt to number conversion is complex
-            Value::Object(_) => f64::NAN, // Object to number conversion is complex
+            Value::Object(object) => match &object.borrow().internal {
+                Some(InternalSlot::PrimitiveValue(value)) => value.to_number(),
+                _ => f64::NAN, // Object to number conversion is complex
+            },
             Value::Function(_) => f64::NAN,
             Value::Array(_) => f64::NAN,
-            Value::RegExp(_, _) => f64::NAN,
+            Value::RegExp(_) => f64::NAN,
         }
     }
     
@@ -141,10 +184,27 @@ impl Value {
             Value::String(s) => s.clone(),
             Value::Symbol(s) => s.clone(),
             Value::BigInt(s) => s.clone(),
-            Value::Object(_) => "[object Object]".to_string(),
+            Value::Object(object) => match &object.borrow().internal {
+                Some(InternalSlot::PrimitiveValue(value)) => value.to_string(),
+                _ => "[object Object]".to_string(),
+            },
             Value::Function(_) => "[object Function]".to_string(),
             Value::Array(_) => "[object Array]".to_string(),
-            Value::RegExp(pattern, flags) => format!("/{}/{}", pattern, flags),
+            Value::RegExp(regexp) => {
+                let regexp = regexp.borrow();
+                format!("/{}/{}", regexp.source, regexp.flags)
+            }
+        }
+    }
+    
+    /// Convert value to a property key (ToPropertyKey)
+    ///
+    /// Symbols are keyed by their description wrapped as `Symbol(...)`, so
+    /// they do not collide with the plain string of the same text.
+    pub fn to_property_key(&self) -> String {
+        match self {
+            Value::Symbol(s) => format!("Symbol({})", s),
+            other => other.to_string(),
         }
     }
     
@@ -174,12 +234,197 @@ impl Value {
             (Value::BigInt(a), Value::BigInt(b)) => a == b,
             (Value::Object(a), Value::Object(b)) => Rc::ptr_eq(a, b),
             (Value::Function(a), Value::Function(b)) => Rc::ptr_eq(a, b),
-            (Value::Array(a), Value::Array(b)) => a == b,
-            (Value::RegExp(a1, a2), Value::RegExp(b1, b2)) => a1 == b1 && a2 == b2,
+            (Value::Array(a), Value::Array(b)) => Rc::ptr_eq(a, b),
+            (Value::RegExp(a), Value::RegExp(b)) => Rc::ptr_eq(a, b),
             _ => false,
         }
     }
     
+    /// Copy objects and arrays recursively, like `structuredClone`
+    ///
+    /// Shared and cyclic references are preserved: a value reached twice
+    /// is cloned once. Functions and regular expressions are shared with
+    /// the original, and prototypes are kept rather than copied.
+    pub fn deep_clone(&self) -> Value {
+        self.deep_clone_with(&mut HashMap::new())
+    }
+    
+    fn deep_clone_with(&self, clones: &mut HashMap<*const (), Value>) -> Value {
+        match self {
+            Value::Object(object) => {
+                let key = Rc::as_ptr(object) as *const ();
+                if let Some(clone) = clones.get(&key) {
+                    return clone.clone();
+                }
+                let copy = Rc::new(RefCell::new(Object::new()));
+                clones.insert(key, Value::Object(copy.clone()));
+                // Registered before the properties are copied, so cycles find it
+                let source = object.borrow();
+                let mut target = Object::new();
+                target.prototype = source.prototype.clone();
+                for name in source.get_own_property_names() {
+                    let mut descriptor: PropertyDescriptor = source.properties[&name].clone();
+                    descriptor.value = descriptor.value.map(|value| value.deep_clone_with(clones));
+                    target.define_property(name, descriptor);
+                }
+                target.extensible = source.extensible;
+                target.internal = source.internal.as_ref().map(|slot| match slot {
+                    InternalSlot::SetData(elements) => {
+                        InternalSlot::SetData(elements.iter().map(|value| value.deep_clone_with(clones)).collect())
+                    }
+                    InternalSlot::MapData(entries) => InternalSlot::MapData(
+                        entries
+                            .iter()
+                            .map(|(key, value)| (key.deep_clone_with(clones), value.deep_clone_with(clones)))
+                            .collect(),
+                    ),
+                    other => other.clone(),
+                });
+                *copy.borrow_mut() = target;
+                Value::Object(copy)
+            }
+            Value::Array(elements) => {
+                let key = Rc::as_ptr(elements) as *const ();
+                if let Some(clone) = clones.get(&key) {
+                    return clone.clone();
+                }
+                let copy = Rc::new(RefCell::new(Elements::default()));
+                clones.insert(key, Value::Array(copy.clone()));
+                let mut cloned = Elements::with_length(elements.borrow().len());
+                for index in elements.borrow().indices() {
+                    let value = elements.borrow().get(index).cloned().unwrap_or_default();
+                    cloned.set(index, value.deep_clone_with(clones));
+                }
+                *copy.borrow_mut() = cloned;
+                Value::Array(copy)
+            }
+            other => other.clone(),
+        }
+    }
+    
+    /// Compare the structure of two values, ignoring object identity
+    ///
+    /// Objects are equal when they have the same own property names with
+    /// deeply equal values; arrays when their elements are. Primitives are
+    /// compared with `===`, except that `NaN` equals `NaN`. Functions and
+    /// regular expressions must be the same value. Cycles are followed once.
+    pub fn deep_equals(&self, other: &Value) -> bool {
+        self.deep_equals_with(other, &mut HashSet::new())
+    }
+    
+    fn deep_equals_with(&self, other: &Value, seen: &mut HashSet<(*const (), *const ())>) -> bool {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => a == b || (a.is_nan() && b.is_nan()),
+            (Value::Object(a), Value::Object(b)) => {
+                // A pair already being compared is assumed equal; any
+                // difference shows up elsewhere in the comparison
+                if Rc::ptr_eq(a, b) || !seen.insert((Rc::as_ptr(a) as *const (), Rc::as_ptr(b) as *const ())) {
+                    return true;
+                }
+                let (a, b) = (a.borrow(), b.borrow());
+                a.properties.len() == b.properties.len()
+                    && a.properties.iter().all(|(name, x)| match b.properties.get(name) {
+                        Some(y) => {
+                            let x = x.value.clone().unwrap_or(Value::Undefined);
+                            let y = y.value.clone().unwrap_or(Value::Undefined);
+                            x.deep_equals_with(&y, seen)
+                        }
+                        None => false,
+                    })
+            }
+            (Value::Array(a), Value::Array(b)) => {
+                if Rc::ptr_eq(a, b) || !seen.insert((Rc::as_ptr(a) as *const (), Rc::as_ptr(b) as *const ())) {
+                    return true;
+                }
+                let (a, b) = (a.borrow(), b.borrow());
+                a.len() == b.len()
+                    && a.iter().zip(b.iter()).all(|(x, y)| match (x, y) {
+                        (Some(x), Some(y)) => x.deep_equals_with(y, seen),
+                        (x, y) => x.is_none() && y.is_none(),
+                    })
+            }
+            _ => self.strict_equals(other),
+        }
+    }
+    
+    /// Convert to a `serde_json::Value`, like `JSON.stringify`
+    ///
+    /// Object properties that are `undefined`, functions or symbols are
+    /// skipped; in arrays they become `null`, as do non-finite numbers and
+    /// such a value at the top level. Accessor properties are skipped, since
+    /// getters cannot run here. Cyclic structures and BigInts are errors.
+    pub fn to_serde_json(&self) -> Result<serde_json::Value, String> {
+        Ok(self.to_serde_json_with(&mut Vec::new())?.unwrap_or(serde_json::Value::Null))
+    }
+    
+    /// `None` for values `JSON.stringify` leaves out; `parents` holds the
+    /// objects and arrays being converted, to detect cycles
+    fn to_serde_json_with(&self, parents: &mut Vec<*const ()>) -> Result<Option<serde_json::Value>, String> {
+        let json = match self {
+            Value::Undefined | Value::Function(_) | Value::Symbol(_) => return Ok(None),
+            Value::Null => serde_json::Value::Null,
+            Value::Boolean(b) => serde_json::Value::Bool(*b),
+            Value::Number(n) if n.fract() == 0.0 && n.abs() < 2f64.powi(53) => serde_json::Value::from(*n as i64),
+            Value::Number(n) => serde_json::Number::from_f64(*n).map_or(serde_json::Value::Null, serde_json::Value::Number),
+            Value::String(s) => serde_json::Value::String(s.clone()),
+            Value::BigInt(_) => return Err("TypeError: Do not know how to serialize a BigInt".to_string()),
+            Value::RegExp(_) => serde_json::Value::Object(serde_json::Map::new()),
+            Value::Object(object) => {
+                // Wrapper objects serialize as their primitive
+                if let Some(InternalSlot::PrimitiveValue(value)) = &object.borrow().internal {
+                    return value.to_serde_json_with(parents);
+                }
+                let key = Rc::as_ptr(object) as *const ();
+                if parents.contains(&key) {
+                    return Err("TypeError: Converting circular structure to JSON".to_string());
+                }
+                parents.push(key);
+                let mut map = serde_json::Map::new();
+                for (name, value) in object.borrow().own_enumerable_properties() {
+                    if let Some(json) = value.to_serde_json_with(parents)? {
+                        map.insert(name, json);
+                    }
+                }
+                parents.pop();
+                serde_json::Value::Object(map)
+            }
+            Value::Array(elements) => {
+                let key = Rc::as_ptr(elements) as *const ();
+                if parents.contains(&key) {
+                    return Err("TypeError: Converting circular structure to JSON".to_string());
+                }
+                parents.push(key);
+                let items = elements
+                    .borrow()
+                    .to_vec()
+                    .iter()
+                    .map(|value| Ok(value.to_serde_json_with(parents)?.unwrap_or(serde_json::Value::Null)))
+                    .collect::<Result<_, String>>()?;
+                parents.pop();
+                serde_json::Value::Array(items)
+            }
+        };
+        Ok(Some(json))
+    }
+    
+    /// Convert host data into a script value, like `JSON.parse`
+    pub fn from_serde_json(json: &serde_json::Value) -> Value {
+        match json {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(b) => Value::Boolean(*b),
+            serde_json::Value::Number(n) => Value::Number(n.as_f64().unwrap_or(f64::NAN)),
+            serde_json::Value::String(s) => Value::String(s.clone()),
+            serde_json::Value::Array(items) => Value::new_array(items.iter().map(Value::from_serde_json).collect()),
+            serde_json::Value::Object(map) => {
+                let mut object = Object::new();
+                for (name, value) in map {
+                    object.set_property(name.clone(), Value::from_serde_json(value));
+                }
+                Value::Object(Rc::new(RefCell::new(object)))
+            }
+        }
+    }
+    
     /// Add two values (+)
     pub fn add(&self, other: &Value) -> Value {
         match (self, other) {
@@ -187,8 +432,6 @@ impl Value {
             (Value::String(a), Value::String(b)) => Value::String(format!("{}{}", a, b)),
             (Value::String(a), b) => Value::String(format!("{}{}", a, b.to_string())),
             (a, Value::String(b)) => Value::String(format!("{}{}", a.to_string(), b)),
-            (Value::Number(a), Value::String(b)) => Value::String(format!("{}{}", a, b)),
-            (Value::String(a), Value::Number(b)) => Value::String(format!("{}{}", a, b)),
             _ => Value::Number(self.to_number() + other.to_number()),
         }
     }
@@ -263,9 +506,93 @@ impl Value {
             Value::Object(_) => "object".to_string(),
             Value::Function(_) => "function".to_string(),
             Value::Array(_) => "object".to_string(),
-            Value::RegExp(_, _) => "object".to_string(),
+            Value::RegExp(_) => "object".to_string(),
         }
     }
+    
+    /// Convert an object to a primitive (ToPrimitive); primitives are returned as they are
+    ///
+    /// A `Symbol.toPrimitive` method decides the result. Otherwise `valueOf`
+    /// and then `toString` are tried, in the opposite order for a string
+    /// hint, until one returns a primitive. Objects with neither method, and
+    /// arrays and regular expressions, convert as `to_number`/`to_string` do.
+    pub fn to_primitive(&self, hint: PreferredType, host: &mut dyn Host) -> Result<Value, Value> {
+        let properties = match self {
+            Value::Object(object) => object.clone(),
+            Value::Function(function) => function.borrow().properties.clone(),
+            _ => return Ok(self.clone()),
+        };
+        let not_primitive = || "TypeError: Cannot convert object to primitive value".to_string();
+        match Object::get(&properties, &to_primitive_key(), self.clone(), host)? {
+            None | Some(Value::Undefined | Value::Null) => {}
+            Some(method @ Value::Function(_)) => {
+                let result = host.call(&method, self.clone(), &[Value::String(hint.as_str().to_string())])?;
+                return if result.is_primitive() { Ok(result) } else { Err(not_primitive().into()) };
+            }
+            Some(other) => return Err(format!("TypeError: {} is not a function", other).into()),
+        }
+
+        let order = match hint {
+            PreferredType::String => ["toString", "valueOf"],
+            PreferredType::Default | PreferredType::Number => ["valueOf", "toString"],
+        };
+        let mut found = false;
+        for name in order {
+            if let Some(method @ Value::Function(_)) = Object::get(&properties, name, self.clone(), host)? {
+                found = true;
+                let result = host.call(&method, self.clone(), &[])?;
+                if result.is_primitive() {
+                    return Ok(result);
+                }
+            }
+        }
+        if found {
+            return Err(not_primitive().into());
+        }
+        Ok(match self {
+            Value::Object(object) => match &object.borrow().internal {
+                Some(InternalSlot::PrimitiveValue(value)) => value.clone(),
+                _ => Value::String(self.to_string()),
+            },
+            _ => Value::String(self.to_string()),
+        })
+    }
+
+    /// `self instanceof target`
+    ///
+    /// A `Symbol.hasInstance` method of `target` decides the result, coerced
+    /// to a boolean. Without one, `target` must be a function whose
+    /// `prototype` is on the prototype chain of `self`.
+    pub fn instance_of(&self, target: &Value, host: &mut dyn Host) -> Result<bool, Value> {
+        let key = has_instance_key();
+        let method = match target {
+            Value::Object(object) => Object::get(object, &key, target.clone(), host)?,
+            Value::Function(function) => {
+                let properties = function.borrow().properties.clone();
+                Object::get(&properties, &key, target.clone(), host)?
+            }
+            Value::Array(_) | Value::RegExp(_) => None,
+            _ => return Err("TypeError: Right-hand side of 'instanceof' is not an object".to_string().into()),
+        };
+        match method {
+            None | Some(Value::Undefined | Value::Null) => {}
+            Some(method @ Value::Function(_)) => return Ok(host.call(&method, target.clone(), std::slice::from_ref(self))?.to_boolean()),
+            Some(other) => return Err(format!("TypeError: {} is not a function", other).into()),
+        }
+        let Value::Function(function) = target else {
+            return Err("TypeError: Right-hand side of 'instanceof' is not callable".to_string().into());
+        };
+        let Value::Object(object) = self else { return Ok(false) };
+        let prototype = function.borrow().prototype.clone();
+        let mut link = object.borrow().prototype.clone();
+        while let Some(ancestor) = link {
+            if Rc::ptr_eq(&ancestor, &prototype) {
+                return Ok(true);
+            }
+            link = ancestor.borrow().prototype.clone();
+        }
+        Ok(false)
+    }
 }
 
 impl PartialEq for Value {
@@ -282,6 +609,14 @@ impl std::fmt::Display for Value {
     }
 }
 
+/// An error message thrown as a string, as built-in errors are
+impl From<String> for Value {
+    fn from(message: String) -> Self {
+        Value::String(message)
+    }
+}
+
 // Re-export the actual types
 pub use super::object::Object;
-pub use super::function::Function; 
\ No newline at end of file
+pub use super::function::Function;
+pub use super::regexp::RegExp; 
\ No newline at end of file
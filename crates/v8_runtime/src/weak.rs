@@ -0,0 +1,207 @@
+//! Weak references for V8-Rust JavaScript engine
+//!
+//! This module provides the `WeakRef` and `FinalizationRegistry`
+//! constructors. Values are reference counted, so an object is collected
+//! as soon as nothing holds it strongly; weak references see that through
+//! `std::rc::Weak`. Registries are swept by `Runtime::collect_garbage`,
+//! which queues their cleanup callbacks as microtasks.
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use super::elements::Elements;
+use super::function::{argument, Function, Host};
+use super::iterator::{define_method, method};
+use super::object::{InternalSlot, Object};
+use super::regexp::RegExp;
+use super::runtime::Microtask;
+use super::value::Value;
+
+/// Registries created by scripts, kept by the runtime so it can sweep them
+pub type FinalizationRegistries = Rc<RefCell<Vec<Weak<RefCell<Object>>>>>;
+
+/// Reference to an object value that does not keep it alive
+#[derive(Debug, Clone)]
+pub enum WeakValue {
+    Object(Weak<RefCell<Object>>),
+    Function(Weak<RefCell<Function>>),
+    Array(Weak<RefCell<Elements<Value>>>),
+    RegExp(Weak<RefCell<RegExp>>),
+}
+
+impl WeakValue {
+    /// A weak reference to `value`, or `None` for primitives
+    pub fn new(value: &Value) -> Option<Self> {
+        match value {
+            Value::Object(object) => Some(Self::Object(Rc::downgrade(object))),
+            Value::Function(function) => Some(Self::Function(Rc::downgrade(function))),
+            Value::Array(array) => Some(Self::Array(Rc::downgrade(array))),
+            Value::RegExp(regexp) => Some(Self::RegExp(Rc::downgrade(regexp))),
+            _ => None,
+        }
+    }
+
+    /// The referenced value, or `None` once it has been collected
+    pub fn upgrade(&self) -> Option<Value> {
+        match self {
+            Self::Object(object) => object.upgrade().map(Value::Object),
+            Self::Function(function) => function.upgrade().map(Value::Function),
+            Self::Array(array) => array.upgrade().map(Value::Array),
+            Self::RegExp(regexp) => regexp.upgrade().map(Value::RegExp),
+        }
+    }
+}
+
+/// A target registered with a `FinalizationRegistry`
+#[derive(Debug, Clone)]
+pub struct RegistryCell {
+    pub target: WeakValue,
+    pub held: Value,                // Passed to the cleanup callback
+    pub token: Option<WeakValue>,   // Unregister token, if one was given
+}
+
+/// Internal state of a `FinalizationRegistry` object
+#[derive(Debug, Clone)]
+pub struct FinalizationRegistryData {
+    pub cleanup: Value,
+    pub cells: Vec<RegistryCell>,
+}
+
+/// Create the `WeakRef` constructor with its prototype methods
+pub fn create_weak_ref_constructor() -> Rc<RefCell<Function>> {
+    let constructor = Function::native_method("WeakRef", weak_ref_constructor, 1);
+    {
+        let mut prototype = constructor.prototype.borrow_mut();
+        define_method(&mut prototype, "deref".to_string(), method("deref", weak_ref_deref, 0));
+    }
+    Rc::new(RefCell::new(constructor))
+}
+
+/// Create the `FinalizationRegistry` constructor with its prototype methods
+pub fn create_finalization_registry_constructor() -> Rc<RefCell<Function>> {
+    let constructor = Function::host_method("FinalizationRegistry", finalization_registry_constructor, 1);
+    {
+        let mut prototype = constructor.prototype.borrow_mut();
+        define_method(&mut prototype, "register".to_string(), method("register", register, 2));
+        define_method(&mut prototype, "unregister".to_string(), method("unregister", unregister, 1));
+    }
+    Rc::new(RefCell::new(constructor))
+}
+
+/// Cleanup jobs for the targets of `registries` that have been collected
+///
+/// Each collected target is removed from its registry, so its callback is
+/// queued once. Registries that have been collected themselves are
+/// forgotten, and their callbacks never run.
+pub fn collect_finalized(registries: &FinalizationRegistries) -> Vec<Microtask> {
+    let mut tasks = Vec::new();
+    registries.borrow_mut().retain(|registry| {
+        let Some(registry) = registry.upgrade() else {
+            return false;
+        };
+        if let Some(InternalSlot::FinalizationRegistry(data)) = &mut registry.borrow_mut().internal {
+            let (collected, live) = std::mem::take(&mut data.cells)
+                .into_iter()
+                .partition(|cell: &RegistryCell| cell.target.upgrade().is_none());
+            data.cells = live;
+            tasks.extend(collected.into_iter().map(|cell| Microtask::FinalizationCleanup {
+                callback: data.cleanup.clone(),
+                held: cell.held,
+            }));
+        }
+        true
+    });
+    tasks
+}
+
+/// `this` as a freshly created object, which a constructor called with `new` receives
+fn new_target(this: &Value, name: &str) -> Result<Rc<RefCell<Object>>, String> {
+    match this {
+        Value::Object(object) if object.borrow().internal.is_none() => Ok(object.clone()),
+        _ => Err(format!("TypeError: Constructor {} requires 'new'", name)),
+    }
+}
+
+/// `new WeakRef(target)`
+pub fn weak_ref_constructor(this: Value, args: &[Value]) -> Result<Value, String> {
+    let object = new_target(&this, "WeakRef")?;
+    let target = WeakValue::new(&argument(args, 0))
+        .ok_or_else(|| "TypeError: WeakRef: target must be an object".to_string())?;
+    object.borrow_mut().internal = Some(InternalSlot::WeakRefTarget(target));
+    Ok(Value::Undefined)
+}
+
+/// `WeakRef.prototype.deref()`: the target, or undefined once it has been collected
+pub fn weak_ref_deref(this: Value, _args: &[Value]) -> Result<Value, String> {
+    match &this {
+        Value::Object(object) => match &object.borrow().internal {
+            Some(InternalSlot::WeakRefTarget(target)) => Ok(target.upgrade().unwrap_or(Value::Undefined)),
+            _ => Err(format!("TypeError: Method WeakRef.prototype.deref called on incompatible receiver {}", this)),
+        },
+        _ => Err(format!("TypeError: Method WeakRef.prototype.deref called on incompatible receiver {}", this)),
+    }
+}
+
+/// `new FinalizationRegistry(cleanup)`
+pub fn finalization_registry_constructor(host: &mut dyn Host, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let object = new_target(&this, "FinalizationRegistry")?;
+    let cleanup = argument(args, 0);
+    if !cleanup.is_function() {
+        return Err(format!("TypeError: FinalizationRegistry: cleanup must be callable, got {}", cleanup).into());
+    }
+    let data = FinalizationRegistryData { cleanup, cells: Vec::new() };
+    object.borrow_mut().internal = Some(InternalSlot::FinalizationRegistry(data));
+    host.track_finalization_registry(&object);
+    Ok(Value::Undefined)
+}
+
+/// Run `f` on the state of the registry `this`
+fn with_registry<T>(
+    this: &Value,
+    name: &str,
+    f: impl FnOnce(&mut FinalizationRegistryData) -> Result<T, String>,
+) -> Result<T, String> {
+    if let Value::Object(object) = this {
+        if let Some(InternalSlot::FinalizationRegistry(data)) = &mut object.borrow_mut().internal {
+            return f(data);
+        }
+    }
+    Err(format!("TypeError: Method FinalizationRegistry.prototype.{} called on incompatible receiver {}", name, this))
+}
+
+/// `FinalizationRegistry.prototype.register(target, held, token)`
+pub fn register(this: Value, args: &[Value]) -> Result<Value, String> {
+    let (target, held, token) = (argument(args, 0), argument(args, 1), argument(args, 2));
+    with_registry(&this, "register", |data| {
+        let weak_target = WeakValue::new(&target)
+            .ok_or_else(|| "TypeError: FinalizationRegistry.prototype.register: invalid target".to_string())?;
+        if target.strict_equals(&held) {
+            return Err("TypeError: FinalizationRegistry.prototype.register: target and holdings must not be same".to_string());
+        }
+        let token = match &token {
+            Value::Undefined => None,
+            token => Some(WeakValue::new(token).ok_or_else(|| {
+                format!("TypeError: FinalizationRegistry.prototype.register: {} is not a valid unregister token", token)
+            })?),
+        };
+        data.cells.push(RegistryCell { target: weak_target, held, token });
+        Ok(Value::Undefined)
+    })
+}
+
+/// `FinalizationRegistry.prototype.unregister(token)`: whether any target was registered with it
+pub fn unregister(this: Value, args: &[Value]) -> Result<Value, String> {
+    let token = argument(args, 0);
+    with_registry(&this, "unregister", |data| {
+        if token.is_primitive() {
+            return Err(format!(
+                "TypeError: FinalizationRegistry.prototype.unregister: {} is not a valid unregister token",
+                token
+            ));
+        }
+        let before = data.cells.len();
+        data.cells.retain(|cell| {
+            !cell.token.as_ref().and_then(WeakValue::upgrade).is_some_and(|existing| existing.strict_equals(&token))
+        });
+        Ok(Value::Boolean(data.cells.len() != before))
+    })
+}
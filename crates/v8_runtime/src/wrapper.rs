@@ -0,0 +1,126 @@
+//! Primitive wrapper constructors for V8-Rust JavaScript engine
+//!
+//! This module provides the `String` and `Boolean` globals and the parts
+//! they share with `Number`. Called as a function, each converts its
+//! argument to the primitive type; with `new` it creates a wrapper object
+//! holding that primitive, which `valueOf` returns.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use super::function::{Function, Host, NativeMethod};
+use super::iterator::{define_method, method};
+use super::object::InternalSlot;
+use super::value::{PreferredType, Value};
+
+/// Create the `String` global
+pub fn create_string_constructor() -> Rc<RefCell<Function>> {
+    let constructor = Function::host_method("String", string_constructor, 1);
+    let constructor = wrapper_constructor(constructor, string_value_of, Value::String(String::new()));
+    {
+        let mut prototype = constructor.prototype.borrow_mut();
+        define_method(&mut prototype, "toString".to_string(), method("toString", string_value_of, 0));
+    }
+    Rc::new(RefCell::new(constructor))
+}
+
+/// Create the `Boolean` global
+pub fn create_boolean_constructor() -> Rc<RefCell<Function>> {
+    let constructor = Function::native_method("Boolean", boolean_constructor, 1);
+    let constructor = wrapper_constructor(constructor, boolean_value_of, Value::Boolean(false));
+    {
+        let mut prototype = constructor.prototype.borrow_mut();
+        define_method(&mut prototype, "toString".to_string(), method("toString", boolean_to_string, 0));
+    }
+    Rc::new(RefCell::new(constructor))
+}
+
+/// Make `constructor` a wrapper: its prototype wraps `default`, with `value_of` as its `valueOf`
+///
+/// As in the specification, `String.prototype` is itself a `String`
+/// object (of `""`), and likewise for `Number` and `Boolean`; this is also
+/// how [`construct_or_convert`] recognises a `new` call.
+pub(crate) fn wrapper_constructor(constructor: Function, value_of: NativeMethod, default: Value) -> Function {
+    {
+        let mut prototype = constructor.prototype.borrow_mut();
+        prototype.internal = Some(InternalSlot::PrimitiveValue(default));
+        define_method(&mut prototype, "valueOf".to_string(), method("valueOf", value_of, 0));
+    }
+    constructor
+}
+
+/// The result of a wrapper constructor called with `this`
+///
+/// Under `new`, `this` is a fresh object inheriting from the prototype;
+/// it is given `primitive` and the call returns `undefined`, so `new`
+/// yields the object. A plain call returns the primitive itself.
+pub(crate) fn construct_or_convert(this: &Value, primitive: Value) -> Value {
+    if let Value::Object(object) = this {
+        let constructing = {
+            let object = object.borrow();
+            object.internal.is_none()
+                && object.prototype.as_ref().is_some_and(|prototype| {
+                    matches!(&prototype.borrow().internal, Some(InternalSlot::PrimitiveValue(default))
+                        if default.typeof_() == primitive.typeof_())
+                })
+        };
+        if constructing {
+            object.borrow_mut().internal = Some(InternalSlot::PrimitiveValue(primitive));
+            return Value::Undefined;
+        }
+    }
+    primitive
+}
+
+/// The primitive behind `this`, if it is a `type_name` or wraps one
+fn this_primitive(this: &Value, type_name: &str, method: &str) -> Result<Value, String> {
+    let primitive = match this {
+        Value::Object(object) => match &object.borrow().internal {
+            Some(InternalSlot::PrimitiveValue(value)) => value.clone(),
+            _ => Value::Undefined,
+        },
+        value => value.clone(),
+    };
+    if primitive.typeof_() == type_name.to_lowercase() {
+        Ok(primitive)
+    } else {
+        Err(format!("TypeError: {}.prototype.{} requires that 'this' be a {}", type_name, method, type_name))
+    }
+}
+
+/// `String(value)`: ToString, with no argument meaning `""`
+///
+/// Objects are converted with `to_primitive`, so their own `toString` runs.
+pub fn string_constructor(host: &mut dyn Host, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let string = match args.first() {
+        Some(value) => value.to_primitive(PreferredType::String, host)?.to_string(),
+        None => String::new(),
+    };
+    Ok(construct_or_convert(&this, Value::String(string)))
+}
+
+/// `Boolean(value)`: ToBoolean, with no argument meaning `false`
+pub fn boolean_constructor(this: Value, args: &[Value]) -> Result<Value, String> {
+    let boolean = args.first().is_some_and(Value::to_boolean);
+    Ok(construct_or_convert(&this, Value::Boolean(boolean)))
+}
+
+/// `String.prototype.valueOf()` and `String.prototype.toString()`
+pub fn string_value_of(this: Value, _args: &[Value]) -> Result<Value, String> {
+    this_primitive(&this, "String", "valueOf")
+}
+
+/// `Number.prototype.valueOf()`
+pub fn number_value_of(this: Value, _args: &[Value]) -> Result<Value, String> {
+    this_primitive(&this, "Number", "valueOf")
+}
+
+/// `Boolean.prototype.valueOf()`
+pub fn boolean_value_of(this: Value, _args: &[Value]) -> Result<Value, String> {
+    this_primitive(&this, "Boolean", "valueOf")
+}
+
+/// `Boolean.prototype.toString()`
+pub fn boolean_to_string(this: Value, _args: &[Value]) -> Result<Value, String> {
+    let value = this_primitive(&this, "Boolean", "toString")?;
+    Ok(Value::String(value.to_string()))
+}
@@ -0,0 +1,36 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use v8_runtime::array::{array_constructor, is_array, of};
+use v8_runtime::{Object, Value};
+
+fn elements(value: Value) -> Vec<Value> {
+    match value {
+        Value::Array(elements) => elements.borrow().to_vec(),
+        other => panic!("Expected array, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_array_is_array() {
+    assert_eq!(is_array(&[Value::new_array(Vec::new())]).unwrap(), Value::Boolean(true));
+    assert_eq!(is_array(&[Value::Object(Rc::new(RefCell::new(Object::new())))]).unwrap(), Value::Boolean(false));
+    assert_eq!(is_array(&[]).unwrap(), Value::Boolean(false));
+}
+
+#[test]
+fn test_array_of_keeps_a_single_number() {
+    assert_eq!(elements(of(&[Value::Number(7.0)]).unwrap()), vec![Value::Number(7.0)]);
+    assert_eq!(elements(of(&[]).unwrap()), Vec::<Value>::new());
+}
+
+#[test]
+fn test_array_constructor_length_quirk() {
+    assert_eq!(elements(array_constructor(Value::Undefined, &[Value::Number(3.0)]).unwrap()).len(), 3);
+    let items = [Value::Number(1.0), Value::Number(2.0), Value::Number(3.0)];
+    assert_eq!(elements(array_constructor(Value::Undefined, &items).unwrap()), items.to_vec());
+    // A single non-number argument is the only element
+    let single = [Value::String("3".to_string())];
+    assert_eq!(elements(array_constructor(Value::Undefined, &single).unwrap()), single.to_vec());
+    assert!(array_constructor(Value::Undefined, &[Value::Number(-1.0)]).is_err());
+    assert!(array_constructor(Value::Undefined, &[Value::Number(1.5)]).is_err());
+}
@@ -0,0 +1,51 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use v8_runtime::collections::{create_set_constructor, map_get, map_set, set_add, set_constructor, set_has};
+use v8_runtime::iterator::{collect_builtin, iterator_key};
+use v8_runtime::object::{InternalSlot, Object};
+use v8_runtime::Value;
+
+fn new_object() -> Value {
+    Value::Object(Rc::new(RefCell::new(Object::new())))
+}
+
+#[test]
+fn test_set_keeps_unique_values_in_order() {
+    let set = new_object();
+    let initial = Value::new_array(vec![Value::Number(2.0), Value::Number(1.0), Value::Number(2.0)]);
+    set_constructor(set.clone(), &[initial]).unwrap();
+    set_add(set.clone(), &[Value::Number(f64::NAN)]).unwrap();
+    set_add(set.clone(), &[Value::Number(f64::NAN)]).unwrap();
+    
+    assert_eq!(set_has(set.clone(), &[Value::Number(f64::NAN)]).unwrap(), Value::Boolean(true));
+    let elements = collect_builtin(&set).unwrap();
+    assert_eq!(elements.len(), 3);
+    assert_eq!(elements[..2], [Value::Number(2.0), Value::Number(1.0)]);
+    if let Value::Object(object) = &set {
+        assert_eq!(object.borrow().get_property("size"), Some(Value::Number(3.0)));
+        assert!(matches!(object.borrow().internal, Some(InternalSlot::SetData(_))));
+    }
+}
+
+#[test]
+fn test_map_entries_and_iterator_method() {
+    let map = new_object();
+    v8_runtime::collections::map_constructor(map.clone(), &[]).unwrap();
+    map_set(map.clone(), &[Value::String("a".to_string()), Value::Number(1.0)]).unwrap();
+    map_set(map.clone(), &[Value::String("a".to_string()), Value::Number(2.0)]).unwrap();
+    assert_eq!(map_get(map.clone(), &[Value::String("a".to_string())]).unwrap(), Value::Number(2.0));
+    
+    let entries = collect_builtin(&map).unwrap();
+    match &entries[..] {
+        [Value::Array(entry)] => assert_eq!(
+            *entry.borrow(),
+            vec![Value::String("a".to_string()), Value::Number(2.0)]
+        ),
+        other => panic!("Expected one entry, got {:?}", other),
+    }
+    
+    // `Set` called without `new` has no object to initialize
+    assert!(set_constructor(Value::Undefined, &[]).is_err());
+    let set = create_set_constructor();
+    assert!(set.borrow().prototype.borrow().get_property(&iterator_key()).is_some());
+}
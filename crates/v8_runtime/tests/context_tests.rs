@@ -0,0 +1,61 @@
+use v8_runtime::{Runtime, Value};
+
+#[test]
+fn test_contexts_do_not_share_globals() {
+    let mut runtime = Runtime::new();
+    runtime.create_context("a");
+    runtime.create_context("b");
+    
+    runtime
+        .get_context_mut("a")
+        .unwrap()
+        .set_variable("x".to_string(), Value::Number(1.0))
+        .unwrap();
+    
+    assert_eq!(runtime.get_context("a").unwrap().get_variable("x"), Some(Value::Number(1.0)));
+    assert!(!runtime.get_context("b").unwrap().has_variable("x"));
+    assert_eq!(runtime.get_context("b").unwrap().get_variable("x"), None);
+}
+
+#[test]
+fn test_block_scope_shadows_and_restores() {
+    let mut runtime = Runtime::new();
+    let context = runtime.create_context("main");
+    context.declare_variable("x".to_string(), Value::Number(1.0), true).unwrap();
+    
+    context.push_scope();
+    context.declare_variable("x".to_string(), Value::Number(2.0), true).unwrap();
+    context.declare_variable("y".to_string(), Value::Number(3.0), true).unwrap();
+    assert_eq!(context.get_variable("x"), Some(Value::Number(2.0)));
+    context.pop_scope();
+    
+    assert_eq!(context.get_variable("x"), Some(Value::Number(1.0)));
+    assert!(!context.has_variable("y"));
+}
+
+#[test]
+fn test_assignment_resolves_outer_binding() {
+    let mut runtime = Runtime::new();
+    let context = runtime.create_context("main");
+    context.push_scope();
+    context.declare_variable("count".to_string(), Value::Number(0.0), true).unwrap();
+    context.push_scope();
+    context.set_variable("count".to_string(), Value::Number(5.0)).unwrap();
+    context.set_variable("leaked".to_string(), Value::Boolean(true)).unwrap();
+    context.pop_scope();
+    
+    assert_eq!(context.get_variable("count"), Some(Value::Number(5.0)));
+    assert_eq!(context.global_object.borrow().get_property("leaked"), Some(Value::Boolean(true)));
+}
+
+#[test]
+fn test_const_binding_rejects_assignment() {
+    let mut runtime = Runtime::new();
+    let context = runtime.create_context("main");
+    context.push_scope();
+    context.declare_variable("PI".to_string(), Value::Number(3.14), false).unwrap();
+    
+    assert!(context.set_variable("PI".to_string(), Value::Number(3.0)).is_err());
+    assert!(context.declare_variable("PI".to_string(), Value::Number(3.0), false).is_err());
+    assert_eq!(context.get_variable("PI"), Some(Value::Number(3.14)));
+}
@@ -0,0 +1,51 @@
+use v8_runtime::{Date, Runtime, Value};
+
+/// 2024-02-29T13:45:30.123Z
+const LEAP_DAY_MS: f64 = 1709214330123.0;
+
+#[test]
+fn test_date_from_epoch_ms() {
+    let runtime = Runtime::new();
+    let date = runtime.construct_date(&[Value::Number(LEAP_DAY_MS)]);
+    
+    assert_eq!(date.get_time(), LEAP_DAY_MS);
+    assert_eq!(date.get_full_year(), 2024.0);
+    assert_eq!(date.get_month(), 1.0);
+    assert_eq!(date.get_date(), 29.0);
+    assert_eq!(date.get_hours(), 13.0);
+    assert_eq!(date.get_minutes(), 45.0);
+    assert_eq!(date.get_seconds(), 30.0);
+    assert_eq!(date.to_iso_string().unwrap(), "2024-02-29T13:45:30.123Z");
+}
+
+#[test]
+fn test_date_before_epoch() {
+    let date = Date::from_time(-1.0);
+    assert_eq!(date.get_full_year(), 1969.0);
+    assert_eq!(date.to_iso_string().unwrap(), "1969-12-31T23:59:59.999Z");
+    
+    assert_eq!(Date::from_time(0.0).to_iso_string().unwrap(), "1970-01-01T00:00:00.000Z");
+}
+
+#[test]
+fn test_date_uses_injected_clock() {
+    let mut runtime = Runtime::new();
+    runtime.set_clock(|| LEAP_DAY_MS);
+    
+    assert_eq!(runtime.now(), LEAP_DAY_MS);
+    let date = runtime.construct_date(&[]);
+    assert_eq!(date.get_full_year(), 2024.0);
+    assert_eq!(date.to_iso_string().unwrap(), "2024-02-29T13:45:30.123Z");
+}
+
+#[test]
+fn test_invalid_date() {
+    let runtime = Runtime::new();
+    let date = runtime.construct_date(&[Value::Number(f64::NAN)]);
+    assert!(!date.is_valid());
+    assert!(date.get_full_year().is_nan());
+    assert!(date.get_hours().is_nan());
+    assert!(date.to_iso_string().is_err());
+    
+    assert!(!Date::from_time(8.64e15 + 1.0).is_valid());
+}
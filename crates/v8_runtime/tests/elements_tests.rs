@@ -0,0 +1,36 @@
+use v8_runtime::elements::Elements;
+
+#[test]
+fn test_far_writes_are_sparse() {
+    let mut elements = Elements::from(vec![1, 2]);
+    elements.set(4, 5);
+    elements.set(u32::MAX as usize - 1, 9);
+    assert_eq!(elements.len(), u32::MAX as usize);
+    assert_eq!(elements.get(4), Some(&5));
+    assert_eq!(elements.get(u32::MAX as usize - 1), Some(&9));
+    assert_eq!(elements.indices().collect::<Vec<_>>(), vec![0, 1, 4, u32::MAX as usize - 1]);
+    assert_eq!(elements.values().copied().collect::<Vec<_>>(), vec![1, 2, 5, 9]);
+}
+
+#[test]
+fn test_length_grows_with_holes_and_truncates() {
+    let mut elements = Elements::from(vec![1, 2, 3]);
+    elements.set_len(u32::MAX as usize);
+    assert_eq!(elements.len(), u32::MAX as usize);
+    assert!(!elements.has(3));
+    elements.set(1_000_000, 7);
+    elements.set_len(2);
+    assert_eq!(elements, vec![1, 2]);
+    assert_eq!(elements.get(1_000_000), None);
+}
+
+#[test]
+fn test_remove_leaves_a_hole() {
+    let mut elements = Elements::from(vec![1, 2, 3]);
+    assert_eq!(elements.remove(1), Some(2));
+    assert_eq!(elements.len(), 3);
+    assert!(!elements.has(1));
+    assert_eq!(elements.to_vec(), vec![1, 0, 3]);
+    assert_eq!(elements.pop(), Some(3));
+    assert_eq!(elements.len(), 2);
+}
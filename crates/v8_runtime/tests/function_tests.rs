@@ -0,0 +1,18 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use v8_runtime::{Function, Value};
+
+fn concat(args: &[Value]) -> Result<Value, String> {
+    Ok(Value::String(args.iter().map(|arg| arg.to_string()).collect()))
+}
+
+#[test]
+fn test_bound_native_function_prepends_arguments() {
+    let target = Rc::new(RefCell::new(Function::native("concat", concat)));
+    let bound = Function::bound(target, Value::Undefined, vec![Value::String("a".to_string())]);
+    assert_eq!(bound.get_name(), "bound concat");
+    assert_eq!(
+        bound.call(Value::Null, &[Value::Number(1.0), Value::Boolean(true)]).unwrap(),
+        Value::String("a1true".to_string())
+    );
+}
@@ -0,0 +1,99 @@
+use v8_runtime::global::{
+    decode_uri, decode_uri_component, encode_uri, encode_uri_component, escape, parse_float,
+    parse_int, unescape,
+};
+use v8_runtime::Value;
+
+fn number(result: Result<Value, String>) -> f64 {
+    match result {
+        Ok(Value::Number(n)) => n,
+        other => panic!("Expected number, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_int_trailing_garbage() {
+    assert_eq!(number(parse_int(&[Value::String("42px".to_string())])), 42.0);
+}
+
+#[test]
+fn test_parse_int_hex_prefix() {
+    assert_eq!(number(parse_int(&[Value::String("0xFF".to_string())])), 255.0);
+    assert_eq!(number(parse_int(&[Value::String("0x1F".to_string()), Value::Number(16.0)])), 31.0);
+    assert_eq!(number(parse_int(&[Value::String("0x1F".to_string()), Value::Number(10.0)])), 0.0);
+}
+
+#[test]
+fn test_parse_int_radix() {
+    assert_eq!(number(parse_int(&[Value::String("z".to_string()), Value::Number(36.0)])), 35.0);
+    assert!(number(parse_int(&[Value::String("10".to_string()), Value::Number(1.0)])).is_nan());
+    assert!(number(parse_int(&[Value::String("10".to_string()), Value::Number(37.0)])).is_nan());
+}
+
+#[test]
+fn test_parse_int_sign_and_whitespace() {
+    assert_eq!(number(parse_int(&[Value::String("  -10  ".to_string())])), -10.0);
+    assert_eq!(number(parse_int(&[Value::String("3.99".to_string())])), 3.0);
+    assert!(number(parse_int(&[Value::String("abc".to_string())])).is_nan());
+}
+
+#[test]
+fn test_parse_float_trailing_garbage() {
+    assert_eq!(number(parse_float(&[Value::String("3.14abc".to_string())])), 3.14);
+    assert_eq!(number(parse_float(&[Value::String("1e3x".to_string())])), 1000.0);
+    assert_eq!(number(parse_float(&[Value::String("2e".to_string())])), 2.0);
+}
+
+#[test]
+fn test_parse_float_infinity() {
+    assert_eq!(number(parse_float(&[Value::String("Infinity".to_string())])), f64::INFINITY);
+    assert_eq!(number(parse_float(&[Value::String("-Infinityx".to_string())])), f64::NEG_INFINITY);
+    assert!(number(parse_float(&[Value::String(".".to_string())])).is_nan());
+}
+
+fn string(result: Result<Value, String>) -> String {
+    match result {
+        Ok(Value::String(s)) => s,
+        other => panic!("Expected string, got {:?}", other),
+    }
+}
+
+fn arg(s: &str) -> [Value; 1] {
+    [Value::String(s.to_string())]
+}
+
+#[test]
+fn test_encode_space() {
+    assert_eq!(string(encode_uri_component(&arg("a b"))), "a%20b");
+    assert_eq!(string(encode_uri(&arg("a b"))), "a%20b");
+}
+
+#[test]
+fn test_encode_non_ascii() {
+    assert_eq!(string(encode_uri_component(&arg("é"))), "%C3%A9");
+    assert_eq!(string(encode_uri_component(&arg("€"))), "%E2%82%AC");
+    assert_eq!(string(decode_uri_component(&arg("%E2%82%AC"))), "€");
+}
+
+#[test]
+fn test_reserved_characters() {
+    assert_eq!(string(encode_uri(&arg("http://x.com/?a=1&b=2#h"))), "http://x.com/?a=1&b=2#h");
+    assert_eq!(string(encode_uri_component(&arg("a=1&b"))), "a%3D1%26b");
+    assert_eq!(string(decode_uri(&arg("%3F%20"))), "%3F ");
+    assert_eq!(string(decode_uri_component(&arg("%3F%20"))), "? ");
+}
+
+#[test]
+fn test_decode_malformed() {
+    assert!(decode_uri_component(&arg("%")).is_err());
+    assert!(decode_uri_component(&arg("%zz")).is_err());
+    assert!(decode_uri(&arg("%E2%82")).is_err());
+    assert!(decode_uri(&arg("%C3%28")).is_err());
+}
+
+#[test]
+fn test_escape_unescape() {
+    assert_eq!(string(escape(&arg("a b@é€"))), "a%20b@%E9%u20AC");
+    assert_eq!(string(unescape(&arg("a%20b@%E9%u20AC"))), "a b@é€");
+    assert_eq!(string(unescape(&arg("%zz%u12"))), "%zz%u12");
+}
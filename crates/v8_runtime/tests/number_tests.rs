@@ -0,0 +1,44 @@
+use v8_runtime::global;
+use v8_runtime::number::{create_number_constructor, is_integer, is_nan, is_safe_integer, MAX_SAFE_INTEGER};
+use v8_runtime::Value;
+
+fn string(s: &str) -> Value {
+    Value::String(s.to_string())
+}
+
+#[test]
+fn test_number_is_nan_does_not_coerce() {
+    assert_eq!(is_nan(&[Value::Number(f64::NAN)]).unwrap(), Value::Boolean(true));
+    assert_eq!(is_nan(&[string("x")]).unwrap(), Value::Boolean(false));
+    assert_eq!(is_nan(&[Value::Undefined]).unwrap(), Value::Boolean(false));
+    assert_eq!(global::is_nan(&[string("x")]).unwrap(), Value::Boolean(true));
+    assert_eq!(global::is_nan(&[Value::Undefined]).unwrap(), Value::Boolean(true));
+    assert_eq!(global::is_nan(&[string("12")]).unwrap(), Value::Boolean(false));
+}
+
+#[test]
+fn test_number_integer_predicates() {
+    assert_eq!(is_integer(&[Value::Number(5.0)]).unwrap(), Value::Boolean(true));
+    assert_eq!(is_integer(&[Value::Number(5.5)]).unwrap(), Value::Boolean(false));
+    assert_eq!(is_integer(&[string("5")]).unwrap(), Value::Boolean(false));
+    assert_eq!(is_integer(&[Value::Number(f64::INFINITY)]).unwrap(), Value::Boolean(false));
+    assert_eq!(is_safe_integer(&[Value::Number(MAX_SAFE_INTEGER)]).unwrap(), Value::Boolean(true));
+    assert_eq!(is_safe_integer(&[Value::Number(MAX_SAFE_INTEGER + 1.0)]).unwrap(), Value::Boolean(false));
+}
+
+#[test]
+fn test_number_constants() {
+    let constructor = create_number_constructor();
+    let constructor = constructor.borrow();
+    let statics = constructor.properties.borrow();
+    let constant = |name: &str| match statics.get_property(name) {
+        Some(Value::Number(n)) => n,
+        other => panic!("Expected number for {}, got {:?}", name, other),
+    };
+    assert_eq!(constant("MAX_SAFE_INTEGER"), 9007199254740991.0);
+    assert_eq!(constant("MIN_SAFE_INTEGER"), -9007199254740991.0);
+    assert_eq!(constant("EPSILON"), 2f64.powi(-52));
+    assert_eq!(constant("MIN_VALUE"), 5e-324);
+    assert_eq!(constant("NEGATIVE_INFINITY"), f64::NEG_INFINITY);
+    assert!(constant("NaN").is_nan());
+}
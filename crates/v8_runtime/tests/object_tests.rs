@@ -0,0 +1,342 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use v8_runtime::function::{Host, NativeMethod};
+use v8_runtime::object_constructor::{
+    assign, create, define_properties, entries, freeze, get_own_property_names, get_prototype_of, has_own,
+    is_frozen, keys, set_prototype_of, values,
+};
+use v8_runtime::runtime::Microtask;
+use v8_runtime::object::PropertyDescriptor;
+use v8_runtime::{Function, Object, Value};
+
+fn object(properties: &[(&str, f64)]) -> Value {
+    let mut object = Object::new();
+    for (key, value) in properties {
+        object.set_property(key.to_string(), Value::Number(*value));
+    }
+    Value::Object(Rc::new(RefCell::new(object)))
+}
+
+fn strings(items: &[&str]) -> Vec<Value> {
+    items.iter().map(|s| Value::String(s.to_string())).collect()
+}
+
+fn elements(value: Value) -> Vec<Value> {
+    match value {
+        Value::Array(elements) => elements.borrow().to_vec(),
+        other => panic!("Expected array, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_object_keys_in_insertion_order() {
+    let obj = object(&[("b", 1.0), ("a", 2.0), ("c", 3.0)]);
+    assert_eq!(elements(keys(&[obj.clone()]).unwrap()), strings(&["b", "a", "c"]));
+    assert_eq!(
+        elements(values(&[obj.clone()]).unwrap()),
+        vec![Value::Number(1.0), Value::Number(2.0), Value::Number(3.0)]
+    );
+    let entries = elements(entries(&[obj]).unwrap());
+    assert_eq!(
+        elements(entries[0].clone()),
+        vec![Value::String("b".to_string()), Value::Number(1.0)]
+    );
+    assert!(keys(&[Value::Null]).unwrap_err().starts_with("TypeError"));
+}
+
+#[test]
+fn test_integer_keys_enumerate_first_in_ascending_order() {
+    let obj = object(&[("b", 1.0), ("2", 2.0), ("a", 3.0), ("1", 4.0)]);
+    assert_eq!(elements(keys(&[obj.clone()]).unwrap()), strings(&["1", "2", "b", "a"]));
+    assert_eq!(elements(get_own_property_names(&[obj.clone()]).unwrap()), strings(&["1", "2", "b", "a"]));
+
+    // Only canonical array indices count as integer keys; symbols go last
+    let Value::Object(inner) = &obj else { unreachable!() };
+    let symbol = Value::Symbol("tag".to_string()).to_property_key();
+    inner.borrow_mut().set_property(symbol.clone(), Value::Null);
+    inner.borrow_mut().set_property("01".to_string(), Value::Null);
+    inner.borrow_mut().set_property("4294967295".to_string(), Value::Null);
+    inner.borrow_mut().set_property("0".to_string(), Value::Null);
+    assert_eq!(
+        inner.borrow().get_own_property_names(),
+        vec!["0", "1", "2", "b", "a", "01", "4294967295", symbol.as_str()]
+    );
+}
+
+#[test]
+fn test_get_own_property_names_includes_non_enumerable() {
+    let obj = object(&[("a", 1.0)]);
+    let Value::Object(inner) = &obj else { unreachable!() };
+    inner
+        .borrow_mut()
+        .define_property("hidden".to_string(), PropertyDescriptor::data_descriptor(Value::Null, true, false, true));
+    inner.borrow_mut().set_property(Value::Symbol("tag".to_string()).to_property_key(), Value::Null);
+    assert_eq!(elements(keys(&[obj.clone()]).unwrap()), strings(&["a", "Symbol(tag)"]));
+    assert_eq!(elements(get_own_property_names(&[obj]).unwrap()), strings(&["a", "hidden"]));
+
+    let array = Value::new_array(vec![Value::Number(1.0)]);
+    assert_eq!(elements(get_own_property_names(&[array]).unwrap()), strings(&["0", "length"]));
+    assert!(get_own_property_names(&[Value::Undefined]).unwrap_err().starts_with("TypeError"));
+}
+
+fn descriptor(fields: &[(&str, Value)]) -> Value {
+    let mut object = Object::new();
+    for (key, value) in fields {
+        object.set_property(key.to_string(), value.clone());
+    }
+    Value::Object(Rc::new(RefCell::new(object)))
+}
+
+#[test]
+fn test_define_properties() {
+    let obj = object(&[("a", 1.0)]);
+    let mut properties = Object::new();
+    properties.set_property("b".to_string(), descriptor(&[("value", Value::Number(2.0))]));
+    properties.set_property(
+        "a".to_string(),
+        descriptor(&[("enumerable", Value::Boolean(false))]),
+    );
+    let properties = Value::Object(Rc::new(RefCell::new(properties)));
+    define_properties(&[obj.clone(), properties]).unwrap();
+
+    let Value::Object(inner) = &obj else { unreachable!() };
+    // Missing fields default to false on a new property...
+    let b = inner.borrow().properties["b"].clone();
+    assert_eq!(b.value, Some(Value::Number(2.0)));
+    assert_eq!((b.writable, b.enumerable, b.configurable), (Some(false), Some(false), Some(false)));
+    // ...and keep their value on an existing one
+    let a = inner.borrow().properties["a"].clone();
+    assert_eq!(a.value, Some(Value::Number(1.0)));
+    assert_eq!((a.writable, a.enumerable), (Some(true), Some(false)));
+    assert!(elements(keys(&[obj.clone()]).unwrap()).is_empty());
+    assert_eq!(elements(get_own_property_names(&[obj.clone()]).unwrap()), strings(&["a", "b"]));
+
+    // b is not configurable now
+    let mut redefine = Object::new();
+    redefine.set_property("b".to_string(), descriptor(&[("value", Value::Number(3.0))]));
+    let redefine = Value::Object(Rc::new(RefCell::new(redefine)));
+    let error = define_properties(&[obj.clone(), redefine]).unwrap_err();
+    assert_eq!(error, "TypeError: Cannot redefine property: b");
+
+    let mut invalid = Object::new();
+    invalid.set_property(
+        "c".to_string(),
+        descriptor(&[("value", Value::Number(1.0)), ("get", Value::Number(2.0))]),
+    );
+    let invalid = Value::Object(Rc::new(RefCell::new(invalid)));
+    assert!(define_properties(&[obj.clone(), invalid]).unwrap_err().starts_with("TypeError"));
+    let mut not_object = Object::new();
+    not_object.set_property("d".to_string(), Value::Number(1.0));
+    let not_object = Value::Object(Rc::new(RefCell::new(not_object)));
+    assert!(define_properties(&[obj.clone(), not_object]).unwrap_err().starts_with("TypeError"));
+    assert!(!inner.borrow().has_own_property("d"));
+    assert!(define_properties(&[Value::Number(1.0), object(&[])]).unwrap_err().starts_with("TypeError"));
+}
+
+#[test]
+fn test_object_assign_merges_sources() {
+    let target = object(&[("a", 1.0), ("b", 2.0)]);
+    let result = assign(&[target.clone(), object(&[("b", 3.0), ("c", 4.0)]), Value::Null]).unwrap();
+    assert_eq!(elements(keys(&[result]).unwrap()), strings(&["a", "b", "c"]));
+    if let Value::Object(target) = target {
+        assert_eq!(target.borrow().get_property("b"), Some(Value::Number(3.0)));
+        assert_eq!(target.borrow().get_property("c"), Some(Value::Number(4.0)));
+    }
+}
+
+#[test]
+fn test_frozen_object_rejects_writes() {
+    let obj = object(&[("a", 1.0)]);
+    freeze(&[obj.clone()]).unwrap();
+    assert_eq!(is_frozen(&[obj.clone()]).unwrap(), Value::Boolean(true));
+
+    if let Value::Object(inner) = &obj {
+        let mut inner = inner.borrow_mut();
+        assert!(!inner.set_property("b".to_string(), Value::Number(2.0)));
+        assert!(!inner.set_property("a".to_string(), Value::Number(5.0)));
+        assert!(!inner.delete_property("a"));
+        assert_eq!(inner.get_property("a"), Some(Value::Number(1.0)));
+        assert_eq!(inner.get_property("b"), None);
+    }
+    assert!(assign(&[obj, object(&[("b", 2.0)])]).unwrap_err().starts_with("TypeError"));
+}
+
+#[test]
+fn test_object_create_with_prototype() {
+    let proto = object(&[("greeting", 1.0)]);
+    match create(&[proto]).unwrap() {
+        Value::Object(obj) => {
+            assert_eq!(obj.borrow().get_property("greeting"), Some(Value::Number(1.0)));
+            assert!(obj.borrow().get_own_property_names().is_empty());
+        }
+        other => panic!("Expected object, got {:?}", other),
+    }
+    assert!(create(&[Value::Number(1.0)]).is_err());
+}
+
+#[test]
+fn test_get_and_set_prototype() {
+    let proto = object(&[("greeting", 1.0)]);
+    let obj = create(&[proto.clone()]).unwrap();
+    let Value::Object(inner) = &obj else { panic!("Expected object") };
+    let Value::Object(proto_inner) = &proto else { unreachable!() };
+    assert!(matches!(get_prototype_of(&[obj.clone()]).unwrap(), Value::Object(p) if Rc::ptr_eq(&p, proto_inner)));
+
+    // The new prototype is used for inherited lookups
+    let other = object(&[("greeting", 2.0)]);
+    set_prototype_of(&[obj.clone(), other.clone()]).unwrap();
+    assert_eq!(inner.borrow().get_property("greeting"), Some(Value::Number(2.0)));
+    set_prototype_of(&[obj.clone(), Value::Null]).unwrap();
+    assert_eq!(get_prototype_of(&[obj.clone()]).unwrap(), Value::Null);
+    assert_eq!(inner.borrow().get_property("greeting"), None);
+
+    // A prototype that leads back to the object is a cycle
+    set_prototype_of(&[obj.clone(), proto.clone()]).unwrap();
+    let error = set_prototype_of(&[proto.clone(), obj.clone()]).unwrap_err();
+    assert!(error.starts_with("TypeError: Cyclic"), "{}", error);
+    assert!(set_prototype_of(&[obj.clone(), obj.clone()]).is_err());
+    assert_eq!(get_prototype_of(&[proto.clone()]).unwrap(), Value::Null);
+
+    assert!(set_prototype_of(&[obj.clone(), Value::Number(1.0)]).is_err());
+    assert!(get_prototype_of(&[Value::Undefined]).is_err());
+}
+
+#[test]
+fn test_in_and_has_own_property_differ_on_inherited_keys() {
+    let proto = object(&[("toString", 1.0)]);
+    let obj = create(&[proto.clone()]).unwrap();
+    let Value::Object(inner) = &obj else { panic!("Expected object") };
+    inner.borrow_mut().set_property("a".to_string(), Value::Number(2.0));
+
+    // "toString" in obj
+    assert!(inner.borrow().has_property("toString"));
+    // obj.hasOwnProperty("toString")
+    assert!(!inner.borrow().has_own_property("toString"));
+    assert!(inner.borrow().has_own_property("a"));
+    let key = |name: &str| Value::String(name.to_string());
+    assert_eq!(has_own(&[obj.clone(), key("toString")]).unwrap(), Value::Boolean(false));
+    assert_eq!(has_own(&[obj.clone(), key("a")]).unwrap(), Value::Boolean(true));
+    assert!(has_own(&[Value::Null, key("a")]).unwrap_err().starts_with("TypeError"));
+
+    // Deleting an inherited property succeeds without touching the prototype
+    assert!(inner.borrow_mut().delete_property("toString"));
+    assert!(inner.borrow().has_property("toString"));
+    assert!(inner.borrow_mut().delete_property("a"));
+    assert!(!inner.borrow().has_property("a"));
+
+    // Non-configurable own properties stay, even when the prototype is frozen
+    inner.borrow_mut().set_property("b".to_string(), Value::Number(3.0));
+    inner.borrow_mut().freeze();
+    assert!(!inner.borrow_mut().delete_property("b"));
+    freeze(&[proto]).unwrap();
+    assert!(inner.borrow_mut().delete_property("toString"));
+}
+
+#[test]
+fn test_array_values_share_elements() {
+    let a = Value::new_array(vec![Value::Number(1.0)]);
+    let b = a.clone();
+    if let Value::Array(elements) = &b {
+        elements.borrow_mut().push(Value::Number(2.0));
+    }
+    assert_eq!(elements(a.clone()), vec![Value::Number(1.0), Value::Number(2.0)]);
+    assert_eq!(a, b);
+    assert_ne!(a, Value::new_array(vec![Value::Number(1.0), Value::Number(2.0)]));
+}
+
+/// Host that can only call native methods, enough to run accessors
+struct NativeHost;
+
+impl Host for NativeHost {
+    fn call(&mut self, function: &Value, this: Value, args: &[Value]) -> Result<Value, Value> {
+        match function {
+            Value::Function(function) => function.borrow().call(this, args).map_err(Value::from),
+            other => Err(format!("TypeError: {} is not a function", other).into()),
+        }
+    }
+
+    fn construct(&mut self, function: &Value, _args: &[Value]) -> Result<Value, Value> {
+        Err(format!("TypeError: {} is not a constructor", function).into())
+    }
+
+    fn enqueue_microtask(&mut self, _task: Microtask) {}
+}
+
+fn accessor(name: &str, method: NativeMethod) -> Option<Value> {
+    Some(Value::Function(Rc::new(RefCell::new(Function::native_method(name, method, 0)))))
+}
+
+fn number_property(object: &Value, name: &str) -> Value {
+    match object {
+        Value::Object(object) => object.borrow().get_property(name).unwrap_or(Value::Undefined),
+        other => panic!("Expected object, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_getter_computes_value() {
+    fn area(this: Value, _args: &[Value]) -> Result<Value, String> {
+        let width = number_property(&this, "width").to_number();
+        let height = number_property(&this, "height").to_number();
+        Ok(Value::Number(width * height))
+    }
+
+    let rect = object(&[("width", 3.0), ("height", 4.0)]);
+    let Value::Object(target) = &rect else { unreachable!() };
+    target.borrow_mut().define_accessor("area".to_string(), accessor("area", area), None);
+
+    assert_eq!(Object::get(target, "area", rect.clone(), &mut NativeHost), Ok(Some(Value::Number(12.0))));
+    // Without a host the accessor has no value
+    assert_eq!(target.borrow().get_property("area"), None);
+
+    // Inherited getters run with the inheriting object as `this`
+    let square = Rc::new(RefCell::new(Object::with_prototype(target.clone())));
+    square.borrow_mut().set_property("width".to_string(), Value::Number(5.0));
+    square.borrow_mut().set_property("height".to_string(), Value::Number(5.0));
+    let receiver = Value::Object(square.clone());
+    assert_eq!(Object::get(&square, "area", receiver, &mut NativeHost), Ok(Some(Value::Number(25.0))));
+}
+
+#[test]
+fn test_proto_accessor() {
+    let proto = object(&[("a", 1.0)]);
+    let obj = object(&[]);
+    let Value::Object(target) = &obj else { unreachable!() };
+    assert_eq!(Object::get(target, "__proto__", obj.clone(), &mut NativeHost), Ok(Some(Value::Null)));
+
+    let written = Object::set(target, "__proto__".to_string(), proto.clone(), obj.clone(), &mut NativeHost);
+    assert_eq!(written, Ok(true));
+    assert_eq!(target.borrow().get_property("a"), Some(Value::Number(1.0)));
+    assert!(!target.borrow().has_own_property("__proto__"));
+
+    // Primitives are ignored, cycles throw
+    assert_eq!(Object::set(target, "__proto__".to_string(), Value::Number(1.0), obj.clone(), &mut NativeHost), Ok(true));
+    let Value::Object(proto_target) = &proto else { unreachable!() };
+    assert!(Object::set(proto_target, "__proto__".to_string(), obj.clone(), proto.clone(), &mut NativeHost).is_err());
+}
+
+#[test]
+fn test_setter_receives_assigned_value() {
+    fn set_celsius(this: Value, args: &[Value]) -> Result<Value, String> {
+        let Value::Object(this) = this else { unreachable!() };
+        let fahrenheit = args[0].to_number() * 9.0 / 5.0 + 32.0;
+        this.borrow_mut().set_property("fahrenheit".to_string(), Value::Number(fahrenheit));
+        Ok(Value::Undefined)
+    }
+
+    let temperature = object(&[]);
+    let Value::Object(target) = &temperature else { unreachable!() };
+    target.borrow_mut().define_accessor("celsius".to_string(), None, accessor("celsius", set_celsius));
+
+    let written = Object::set(target, "celsius".to_string(), Value::Number(100.0), temperature.clone(), &mut NativeHost);
+    assert_eq!(written, Ok(true));
+    assert_eq!(number_property(&temperature, "fahrenheit"), Value::Number(212.0));
+    // The setter did not turn the accessor into a data property
+    assert!(target.borrow().properties["celsius"].is_accessor_descriptor());
+    assert!(!target.borrow_mut().set_property("celsius".to_string(), Value::Number(0.0)));
+
+    // A getter-only accessor rejects writes
+    target.borrow_mut().define_accessor("kelvin".to_string(), accessor("kelvin", set_celsius), None);
+    let written = Object::set(target, "kelvin".to_string(), Value::Number(1.0), temperature.clone(), &mut NativeHost);
+    assert_eq!(written, Ok(false));
+}
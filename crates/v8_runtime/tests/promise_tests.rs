@@ -0,0 +1,107 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use v8_runtime::function::{FunctionType, Host};
+use v8_runtime::promise::{self, PromiseState};
+use v8_runtime::runtime::Microtask;
+use v8_runtime::{Function, Runtime, Value};
+
+/// Host that can only call native functions, queueing on the runtime
+struct TestHost<'a> {
+    runtime: &'a Runtime,
+}
+
+impl Host for TestHost<'_> {
+    fn call(&mut self, function: &Value, this: Value, args: &[Value]) -> Result<Value, Value> {
+        match function {
+            Value::Function(function) => {
+                let function = function.borrow().clone();
+                match function.function_type {
+                    FunctionType::HostMethod(method) => method(self, this, args),
+                    _ => function.call(this, args).map_err(Value::from),
+                }
+            }
+            other => Err(format!("TypeError: {} is not a function", other).into()),
+        }
+    }
+
+    fn construct(&mut self, function: &Value, _args: &[Value]) -> Result<Value, Value> {
+        Err(format!("TypeError: {} is not a constructor", function).into())
+    }
+
+    fn enqueue_microtask(&mut self, task: Microtask) {
+        self.runtime.enqueue_microtask(task);
+    }
+}
+
+fn method(object: &Value, name: &str) -> Value {
+    match object {
+        Value::Function(function) => function.borrow().properties.borrow().get_property(name),
+        Value::Object(object) => object.borrow().get_property(name),
+        _ => None,
+    }
+    .unwrap_or(Value::Undefined)
+}
+
+fn native(name: &str, function: fn(&[Value]) -> Result<Value, String>) -> Value {
+    Value::Function(Rc::new(RefCell::new(Function::native(name, function))))
+}
+
+#[test]
+fn test_then_runs_after_microtasks() {
+    let runtime = Runtime::new();
+    let mut host = TestHost { runtime: &runtime };
+    let constructor = Value::Function(promise::create_promise_constructor());
+
+    let resolved = host.call(&method(&constructor, "resolve"), constructor.clone(), &[Value::Number(21.0)]).unwrap();
+    assert_eq!(promise::promise_state(&resolved), Some(PromiseState::Fulfilled(Value::Number(21.0))));
+
+    let double = native("double", |args| Ok(Value::Number(args[0].to_number() * 2.0)));
+    let derived = host.call(&method(&resolved, "then"), resolved.clone(), &[double]).unwrap();
+    // Reactions never run synchronously
+    assert_eq!(promise::promise_state(&derived), Some(PromiseState::Pending));
+
+    runtime.run_microtasks(&mut host).unwrap();
+    assert_eq!(promise::promise_state(&derived), Some(PromiseState::Fulfilled(Value::Number(42.0))));
+}
+
+#[test]
+fn test_rejection_skips_then_and_reaches_catch() {
+    let runtime = Runtime::new();
+    let mut host = TestHost { runtime: &runtime };
+    let constructor = Value::Function(promise::create_promise_constructor());
+
+    let rejected = host.call(&method(&constructor, "reject"), constructor.clone(), &[Value::Number(1.0)]).unwrap();
+    let fail = native("fail", |_| Err("unreachable".to_string()));
+    let skipped = host.call(&method(&rejected, "then"), rejected.clone(), &[fail]).unwrap();
+    let recover = native("recover", |args| Ok(Value::Number(args[0].to_number() + 1.0)));
+    let caught = host.call(&method(&skipped, "catch"), skipped.clone(), &[recover]).unwrap();
+
+    runtime.run_microtasks(&mut host).unwrap();
+    assert_eq!(promise::promise_state(&skipped), Some(PromiseState::Rejected(Value::Number(1.0))));
+    assert_eq!(promise::promise_state(&caught), Some(PromiseState::Fulfilled(Value::Number(2.0))));
+}
+
+#[test]
+fn test_promise_all() {
+    let runtime = Runtime::new();
+    let mut host = TestHost { runtime: &runtime };
+    let constructor = Value::Function(promise::create_promise_constructor());
+
+    let resolve = method(&constructor, "resolve");
+    let first = host.call(&resolve, constructor.clone(), &[Value::Number(1.0)]).unwrap();
+    let second = host.call(&resolve, constructor.clone(), &[Value::Number(2.0)]).unwrap();
+    let all = method(&constructor, "all");
+    let aggregate = host.call(&all, constructor.clone(), &[Value::new_array(vec![first, second])]).unwrap();
+    assert_eq!(promise::promise_state(&aggregate), Some(PromiseState::Pending));
+
+    runtime.run_microtasks(&mut host).unwrap();
+    match promise::promise_state(&aggregate) {
+        Some(PromiseState::Fulfilled(Value::Array(values))) => {
+            assert_eq!(*values.borrow(), vec![Value::Number(1.0), Value::Number(2.0)])
+        }
+        other => panic!("Expected fulfilled array, got {:?}", other),
+    }
+
+    let empty = host.call(&all, constructor.clone(), &[Value::new_array(Vec::new())]).unwrap();
+    assert!(matches!(promise::promise_state(&empty), Some(PromiseState::Fulfilled(Value::Array(_)))));
+}
@@ -0,0 +1,63 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use v8_runtime::reflect::{delete_property, get, get_prototype_of, has, own_keys, set};
+use v8_runtime::{Object, Value};
+
+fn object(properties: &[(&str, f64)]) -> Rc<RefCell<Object>> {
+    let mut object = Object::new();
+    for (key, value) in properties {
+        object.set_property(key.to_string(), Value::Number(*value));
+    }
+    Rc::new(RefCell::new(object))
+}
+
+fn key(name: &str) -> Value {
+    Value::String(name.to_string())
+}
+
+#[test]
+fn test_reflect_has_and_get() {
+    let target = Value::Object(object(&[("a", 1.0)]));
+    assert_eq!(has(&[target.clone(), key("a")]).unwrap(), Value::Boolean(true));
+    assert_eq!(has(&[target.clone(), key("b")]).unwrap(), Value::Boolean(false));
+    assert_eq!(get(&[target.clone(), key("a")]).unwrap(), Value::Number(1.0));
+    assert_eq!(get(&[target, key("b")]).unwrap(), Value::Undefined);
+
+    let array = Value::new_array(vec![Value::Number(7.0)]);
+    assert_eq!(has(&[array.clone(), Value::Number(0.0)]).unwrap(), Value::Boolean(true));
+    assert_eq!(get(&[array, key("length")]).unwrap(), Value::Number(1.0));
+
+    assert!(has(&[Value::Number(1.0), key("a")]).unwrap_err().starts_with("TypeError"));
+}
+
+#[test]
+fn test_reflect_own_keys() {
+    let target = object(&[("b", 1.0), ("a", 2.0)]);
+    match own_keys(&[Value::Object(target)]).unwrap() {
+        Value::Array(keys) => assert_eq!(*keys.borrow(), vec![key("b"), key("a")]),
+        other => panic!("Expected array, got {:?}", other),
+    }
+    match own_keys(&[Value::new_array(vec![Value::Null])]).unwrap() {
+        Value::Array(keys) => assert_eq!(*keys.borrow(), vec![key("0"), key("length")]),
+        other => panic!("Expected array, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_reflect_set_and_delete_report_success() {
+    let target = object(&[("a", 1.0)]);
+    let value = Value::Object(target.clone());
+    assert_eq!(set(&[value.clone(), key("b"), Value::Number(2.0)]).unwrap(), Value::Boolean(true));
+    assert_eq!(target.borrow().get_property("b"), Some(Value::Number(2.0)));
+    assert_eq!(delete_property(&[value.clone(), key("b")]).unwrap(), Value::Boolean(true));
+    // Deleting a missing property succeeds
+    assert_eq!(delete_property(&[value.clone(), key("b")]).unwrap(), Value::Boolean(true));
+
+    target.borrow_mut().freeze();
+    assert_eq!(set(&[value.clone(), key("a"), Value::Number(3.0)]).unwrap(), Value::Boolean(false));
+    assert_eq!(delete_property(&[value.clone(), key("a")]).unwrap(), Value::Boolean(false));
+    assert_eq!(target.borrow().get_property("a"), Some(Value::Number(1.0)));
+
+    let child = Rc::new(RefCell::new(Object::with_prototype(target.clone())));
+    assert_eq!(get_prototype_of(&[Value::Object(child)]).unwrap(), value);
+}
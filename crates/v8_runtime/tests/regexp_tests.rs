@@ -0,0 +1,69 @@
+use v8_runtime::regexp::{exec, string_replace, string_split, RegExp};
+use v8_runtime::Value;
+
+fn matches(pattern: &str, flags: &str, input: &str) -> Option<(usize, usize)> {
+    let regexp = RegExp::new(pattern, flags).unwrap();
+    let chars: Vec<char> = input.chars().collect();
+    regexp.find_from(&chars, 0).map(|m| (m.start, m.end))
+}
+
+#[test]
+fn test_regexp_matching() {
+    assert_eq!(matches(r"\d+", "", "a123b"), Some((1, 4)));
+    assert_eq!(matches("abc", "i", "xABC"), Some((1, 4)));
+    assert_eq!(matches("^b", "", "a\nb"), None);
+    assert_eq!(matches("^b", "m", "a\nb"), Some((2, 3)));
+    assert_eq!(matches("a.c", "", "a\nc"), None);
+    assert_eq!(matches("a.c", "s", "a\nc"), Some((0, 3)));
+    assert_eq!(matches("a+?", "", "aaa"), Some((0, 1)));
+    assert_eq!(matches("a{2,3}", "", "aaaa"), Some((0, 3)));
+    assert_eq!(matches(r"(a|ab)c", "", "abc"), Some((0, 3)));
+    assert_eq!(matches(r"(\w)\1", "", "abccd"), Some((2, 4)));
+    assert_eq!(matches(r"\bfoo\b", "", "a foo b"), Some((2, 5)));
+    assert_eq!(matches(r"x(?=y)", "", "xzxy"), Some((2, 3)));
+    assert_eq!(matches(r"[^a-c]+", "", "abcdef"), Some((3, 6)));
+    assert_eq!(matches(r"(a*)*b", "", "aaac"), None);
+}
+
+#[test]
+fn test_regexp_syntax_errors() {
+    assert!(RegExp::new("(a", "").unwrap_err().starts_with("SyntaxError"));
+    assert!(RegExp::new("*a", "").is_err());
+    assert!(RegExp::new("[z-a]", "").is_err());
+    assert!(RegExp::new("a", "gg").is_err());
+}
+
+#[test]
+fn test_exec_global_advances_last_index() {
+    let regexp = Value::new_regexp(RegExp::new(r"(\d)(\d)?", "g").unwrap());
+    let input = [Value::String("1 23".to_string())];
+
+    let Value::Object(first) = exec(regexp.clone(), &input).unwrap() else { panic!("Expected a match") };
+    assert_eq!(first.borrow().get_property("0"), Some(Value::String("1".to_string())));
+    assert_eq!(first.borrow().get_property("2"), Some(Value::Undefined));
+
+    let Value::Object(second) = exec(regexp.clone(), &input).unwrap() else { panic!("Expected a match") };
+    assert_eq!(second.borrow().get_property("index"), Some(Value::Number(2.0)));
+    assert_eq!(second.borrow().get_property("2"), Some(Value::String("3".to_string())));
+
+    assert_eq!(exec(regexp.clone(), &input).unwrap(), Value::Null);
+    let Value::RegExp(regexp) = regexp else { unreachable!() };
+    assert_eq!(regexp.borrow().last_index, 0);
+}
+
+#[test]
+fn test_replace_and_split() {
+    let global = Value::new_regexp(RegExp::new(r"(\w+)@(\w+)", "g").unwrap());
+    let replaced = string_replace(
+        Value::String("a@b, c@d".to_string()),
+        &[global, Value::String("$2@$1 [$&] $$".to_string())],
+    );
+    assert_eq!(replaced.unwrap(), Value::String("b@a [a@b] $, d@c [c@d] $".to_string()));
+
+    let separator = Value::new_regexp(RegExp::new(r"\s*(,)\s*", "").unwrap());
+    let Value::Array(parts) = string_split(Value::String("a , b,c".to_string()), &[separator]).unwrap() else {
+        panic!("Expected an array")
+    };
+    let parts: Vec<String> = parts.borrow().to_vec().iter().map(|part| part.to_string()).collect();
+    assert_eq!(parts, ["a", ",", "b", ",", "c"]);
+}
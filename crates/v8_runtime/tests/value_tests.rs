@@ -0,0 +1,118 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use v8_runtime::regexp::RegExp;
+use v8_runtime::{Function, Object, Value};
+
+fn identity(args: &[Value]) -> Result<Value, String> {
+    Ok(args.first().cloned().unwrap_or(Value::Undefined))
+}
+
+#[test]
+fn test_typeof_every_value_kind() {
+    let cases = [
+        (Value::Undefined, "undefined"),
+        (Value::Null, "object"),
+        (Value::Boolean(false), "boolean"),
+        (Value::Number(f64::NAN), "number"),
+        (Value::BigInt("1".to_string()), "bigint"),
+        (Value::String(String::new()), "string"),
+        (Value::Symbol("s".to_string()), "symbol"),
+        (Value::Function(Rc::new(RefCell::new(Function::native("f", identity)))), "function"),
+        (Value::Object(Rc::new(RefCell::new(Object::new()))), "object"),
+        (Value::new_array(Vec::new()), "object"),
+        (Value::RegExp(Rc::new(RefCell::new(RegExp::new("a", "").unwrap()))), "object"),
+    ];
+    for (value, expected) in cases {
+        assert_eq!(value.typeof_(), expected, "typeof {:?}", value);
+    }
+}
+
+fn object(properties: &[(&str, Value)]) -> Value {
+    let mut object = Object::new();
+    for (name, value) in properties {
+        object.set_property(name.to_string(), value.clone());
+    }
+    Value::Object(Rc::new(RefCell::new(object)))
+}
+
+fn property(value: &Value, name: &str) -> Value {
+    match value {
+        Value::Object(object) => object.borrow().get_property(name).unwrap_or(Value::Undefined),
+        other => panic!("Expected object, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_deep_clone_is_independent() {
+    let inner = object(&[("n", Value::Number(1.0))]);
+    let original = object(&[("inner", inner.clone()), ("list", Value::new_array(vec![Value::Number(2.0)]))]);
+    let clone = original.deep_clone();
+    assert!(clone.deep_equals(&original));
+    assert!(!clone.strict_equals(&original));
+
+    let Value::Object(cloned_inner) = property(&clone, "inner") else { panic!("Expected object") };
+    cloned_inner.borrow_mut().set_property("n".to_string(), Value::Number(5.0));
+    let Value::Array(list) = property(&clone, "list") else { panic!("Expected array") };
+    list.borrow_mut().push(Value::Null);
+
+    assert_eq!(property(&inner, "n"), Value::Number(1.0));
+    let Value::Array(list) = property(&original, "list") else { panic!("Expected array") };
+    assert_eq!(list.borrow().len(), 1);
+    assert!(!clone.deep_equals(&original));
+}
+
+#[test]
+fn test_deep_clone_keeps_cycles() {
+    let Value::Object(node) = object(&[("name", Value::String("a".to_string()))]) else { unreachable!() };
+    node.borrow_mut().set_property("self".to_string(), Value::Object(node.clone()));
+    let clone = Value::Object(node.clone()).deep_clone();
+    let Value::Object(cloned) = &clone else { panic!("Expected object") };
+    assert!(!Rc::ptr_eq(cloned, &node));
+    assert!(property(&clone, "self").strict_equals(&clone));
+}
+
+#[test]
+fn test_deep_equals_compares_structure() {
+    let build = || object(&[("a", Value::new_array(vec![Value::Number(f64::NAN), Value::Null])), ("b", object(&[]))]);
+    assert!(build().deep_equals(&build()));
+    assert!(!build().strict_equals(&build()));
+    assert!(!build().deep_equals(&object(&[("a", Value::new_array(Vec::new())), ("b", object(&[]))])));
+    assert!(!object(&[("a", Value::Null)]).deep_equals(&object(&[("b", Value::Null)])));
+    assert!(!Value::new_array(Vec::new()).deep_equals(&object(&[])));
+
+    // Two separately built cycles of the same shape
+    let cycle = |label: &str| {
+        let value = object(&[("label", Value::String(label.to_string()))]);
+        let Value::Object(node) = &value else { unreachable!() };
+        node.borrow_mut().set_property("next".to_string(), value.clone());
+        value
+    };
+    assert!(cycle("x").deep_equals(&cycle("x")));
+    assert!(!cycle("x").deep_equals(&cycle("y")));
+}
+
+#[test]
+fn test_serde_json_round_trip() {
+    let json = serde_json::json!({"name": "box", "size": {"w": 2, "h": 1.5}, "tags": ["a", null, true]});
+    let value = Value::from_serde_json(&json);
+    assert_eq!(property(&property(&value, "size"), "h"), Value::Number(1.5));
+    assert_eq!(value.to_serde_json(), Ok(json));
+}
+
+#[test]
+fn test_serde_json_drops_values_like_json_stringify() {
+    let function = Value::Function(Rc::new(RefCell::new(Function::native("f", identity))));
+    let list = Value::new_array(vec![function.clone(), Value::Undefined, Value::Number(f64::INFINITY)]);
+    let value = object(&[("f", function.clone()), ("u", Value::Undefined), ("list", list), ("n", Value::Number(1.0))]);
+    assert_eq!(value.to_serde_json(), Ok(serde_json::json!({"list": [null, null, null], "n": 1})));
+    assert_eq!(function.to_serde_json(), Ok(serde_json::Value::Null));
+    assert!(Value::BigInt("1".to_string()).to_serde_json().unwrap_err().starts_with("TypeError"));
+
+    // Shared references are fine, cycles are not
+    let shared = object(&[]);
+    assert!(Value::new_array(vec![shared.clone(), shared]).to_serde_json().is_ok());
+    let Value::Object(node) = object(&[]) else { unreachable!() };
+    node.borrow_mut().set_property("self".to_string(), Value::Object(node.clone()));
+    let error = Value::Object(node).to_serde_json().unwrap_err();
+    assert!(error.contains("circular structure"), "{}", error);
+}
@@ -0,0 +1,145 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use v8_runtime::function::{FunctionType, Host};
+use v8_runtime::runtime::Microtask;
+use v8_runtime::weak::{finalization_registry_constructor, register, unregister, weak_ref_constructor, weak_ref_deref};
+use v8_runtime::{Function, Object, Runtime, Value};
+
+/// Host that can only call native functions and tracks registries on the runtime
+struct TestHost<'a> {
+    runtime: &'a Runtime,
+}
+
+impl Host for TestHost<'_> {
+    fn call(&mut self, function: &Value, this: Value, args: &[Value]) -> Result<Value, Value> {
+        match function {
+            Value::Function(function) => {
+                let function = function.borrow().clone();
+                match function.function_type {
+                    FunctionType::HostMethod(method) => method(self, this, args),
+                    _ => function.call(this, args).map_err(Value::from),
+                }
+            }
+            other => Err(format!("TypeError: {} is not a function", other).into()),
+        }
+    }
+
+    fn construct(&mut self, function: &Value, _args: &[Value]) -> Result<Value, Value> {
+        Err(format!("TypeError: {} is not a constructor", function).into())
+    }
+
+    fn enqueue_microtask(&mut self, task: Microtask) {
+        self.runtime.enqueue_microtask(task);
+    }
+
+    fn track_finalization_registry(&mut self, registry: &Rc<RefCell<Object>>) {
+        self.runtime.finalization_registries().borrow_mut().push(Rc::downgrade(registry));
+    }
+}
+
+fn new_object() -> Value {
+    Value::Object(Rc::new(RefCell::new(Object::new())))
+}
+
+fn native(name: &str, function: fn(&[Value]) -> Result<Value, String>) -> Value {
+    Value::Function(Rc::new(RefCell::new(Function::native(name, function))))
+}
+
+#[test]
+fn test_deref_returns_undefined_after_target_is_collected() {
+    let target = new_object();
+    let weak = new_object();
+    weak_ref_constructor(weak.clone(), std::slice::from_ref(&target)).unwrap();
+    assert!(weak_ref_deref(weak.clone(), &[]).unwrap().strict_equals(&target));
+
+    // The WeakRef does not keep its target alive
+    drop(target);
+    assert_eq!(weak_ref_deref(weak, &[]).unwrap(), Value::Undefined);
+}
+
+#[test]
+fn test_weak_ref_requires_an_object_target() {
+    let err = weak_ref_constructor(new_object(), &[Value::Number(1.0)]).unwrap_err();
+    assert!(err.starts_with("TypeError"), "{}", err);
+    let err = weak_ref_deref(new_object(), &[]).unwrap_err();
+    assert!(err.starts_with("TypeError"), "{}", err);
+}
+
+#[test]
+fn test_collected_target_queues_cleanup_callback() {
+    let runtime = Runtime::new();
+    let mut host = TestHost { runtime: &runtime };
+    let registry = new_object();
+    let cleanup = native("cleanup", |args| Ok(args[0].clone()));
+    finalization_registry_constructor(&mut host, registry.clone(), std::slice::from_ref(&cleanup)).unwrap();
+
+    let target = new_object();
+    register(registry.clone(), &[target.clone(), Value::String("held".to_string())]).unwrap();
+    assert_eq!(runtime.collect_garbage(), 0);
+
+    drop(target);
+    assert_eq!(runtime.collect_garbage(), 1);
+    let queued = runtime.microtask_queue().borrow_mut().pop_front();
+    match queued {
+        Some(Microtask::FinalizationCleanup { callback, held }) => {
+            assert!(callback.strict_equals(&cleanup));
+            assert_eq!(held, Value::String("held".to_string()));
+        }
+        other => panic!("Expected a cleanup job, got {:?}", other),
+    }
+    // Each target is cleaned up once
+    assert_eq!(runtime.collect_garbage(), 0);
+}
+
+#[test]
+fn test_unregister_removes_cells_with_token() {
+    let runtime = Runtime::new();
+    let mut host = TestHost { runtime: &runtime };
+    let registry = new_object();
+    finalization_registry_constructor(&mut host, registry.clone(), &[native("cleanup", |_| Ok(Value::Undefined))]).unwrap();
+
+    let (first, second, token) = (new_object(), new_object(), new_object());
+    register(registry.clone(), &[first.clone(), Value::Number(1.0), token.clone()]).unwrap();
+    register(registry.clone(), &[second.clone(), Value::Number(2.0), token.clone()]).unwrap();
+    assert_eq!(unregister(registry.clone(), std::slice::from_ref(&token)).unwrap(), Value::Boolean(true));
+    assert_eq!(unregister(registry.clone(), &[token]).unwrap(), Value::Boolean(false));
+
+    drop((first, second));
+    assert_eq!(runtime.collect_garbage(), 0);
+}
+
+#[test]
+fn test_register_rejects_invalid_arguments() {
+    let runtime = Runtime::new();
+    let mut host = TestHost { runtime: &runtime };
+    let registry = new_object();
+    let err = finalization_registry_constructor(&mut host, registry.clone(), &[Value::Number(1.0)]).unwrap_err();
+    assert!(err.to_string().starts_with("TypeError"), "{}", err);
+    finalization_registry_constructor(&mut host, registry.clone(), &[native("cleanup", |_| Ok(Value::Undefined))]).unwrap();
+
+    let target = new_object();
+    for args in [
+        vec![Value::Number(1.0)],                                   // primitive target
+        vec![target.clone(), target.clone()],                       // held value is the target
+        vec![target.clone(), Value::Undefined, Value::Number(1.0)], // primitive token
+    ] {
+        let err = register(registry.clone(), &args).unwrap_err();
+        assert!(err.starts_with("TypeError"), "{}", err);
+    }
+    assert!(unregister(registry, &[Value::Undefined]).unwrap_err().starts_with("TypeError"));
+}
+
+#[test]
+fn test_collected_registry_runs_no_callbacks() {
+    let runtime = Runtime::new();
+    let mut host = TestHost { runtime: &runtime };
+    let registry = new_object();
+    finalization_registry_constructor(&mut host, registry.clone(), &[native("cleanup", |_| Ok(Value::Undefined))]).unwrap();
+    let target = new_object();
+    register(registry.clone(), &[target.clone(), Value::Undefined]).unwrap();
+
+    drop(registry);
+    drop(target);
+    assert_eq!(runtime.collect_garbage(), 0);
+    assert!(runtime.finalization_registries().borrow().is_empty());
+}
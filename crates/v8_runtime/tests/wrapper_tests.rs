@@ -0,0 +1,44 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use v8_runtime::function::Host;
+use v8_runtime::object::Object;
+use v8_runtime::runtime::Microtask;
+use v8_runtime::wrapper::{create_string_constructor, string_constructor, string_value_of};
+use v8_runtime::Value;
+
+/// Host that can only call native methods, enough for `String`
+struct NativeHost;
+
+impl Host for NativeHost {
+    fn call(&mut self, function: &Value, this: Value, args: &[Value]) -> Result<Value, Value> {
+        match function {
+            Value::Function(function) => function.borrow().call(this, args).map_err(Value::from),
+            other => Err(format!("TypeError: {} is not a function", other).into()),
+        }
+    }
+
+    fn construct(&mut self, function: &Value, _args: &[Value]) -> Result<Value, Value> {
+        Err(format!("TypeError: {} is not a constructor", function).into())
+    }
+
+    fn enqueue_microtask(&mut self, _task: Microtask) {}
+}
+
+#[test]
+fn test_string_called_converts_and_constructed_wraps() {
+    assert_eq!(string_constructor(&mut NativeHost, Value::Undefined, &[Value::Number(1.5)]).unwrap(), Value::String("1.5".to_string()));
+    assert_eq!(string_constructor(&mut NativeHost, Value::Undefined, &[]).unwrap(), Value::String(String::new()));
+
+    // `new String("a")`: `this` inherits from String.prototype
+    let constructor = create_string_constructor();
+    let prototype = constructor.borrow().prototype.clone();
+    let this = Value::Object(Rc::new(RefCell::new(Object::with_prototype(prototype))));
+    assert_eq!(string_constructor(&mut NativeHost, this.clone(), &[Value::String("a".to_string())]).unwrap(), Value::Undefined);
+    assert_eq!(string_value_of(this.clone(), &[]).unwrap(), Value::String("a".to_string()));
+    assert_eq!(this.to_string(), "a");
+
+    // Any other object receiver is just a conversion
+    let plain = Value::Object(Rc::new(RefCell::new(Object::new())));
+    assert_eq!(string_constructor(&mut NativeHost, plain.clone(), &[Value::Boolean(true)]).unwrap(), Value::String("true".to_string()));
+    assert!(string_value_of(plain, &[]).is_err());
+}
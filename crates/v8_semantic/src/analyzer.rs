@@ -1,6 +1,7 @@
 use crate::{SemanticError, SemanticResult, Type, Scope};
+use crate::constant::{try_eval_constant, Value};
 use crate::scope::ScopeType;
-use v8_ast::Node;
+use v8_ast::{Node, Position, Span};
 use std::collections::HashMap;
 
 /// Main semantic analyzer
@@ -14,8 +15,52 @@ pub struct SemanticAnalyzer {
     /// Collected errors
     errors: Vec<SemanticError>,
     
+    /// Collected warnings, which do not fail the analysis
+    warnings: Vec<SemanticError>,
+    
     /// Whether we're in strict mode
     strict_mode: bool,
+    
+    /// Labels enclosing the statement being checked, with whether each labels a loop
+    labels: Vec<(String, bool)>,
+    
+    /// Whether `super()` has been called yet, inside a derived-class constructor
+    super_called: Option<bool>,
+    
+    /// The `this` of each enclosing non-arrow function, innermost last
+    this_bindings: Vec<ThisBinding>,
+    
+    /// Arrow functions entered since the innermost non-arrow function
+    arrows: Vec<Option<Span>>,
+    
+    /// Uses of `this` inside arrow functions, with the `this` they see
+    lexical_this: Vec<LexicalThis>,
+}
+
+/// The `this` that a use of `this` refers to
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThisBinding {
+    /// The `this` of a function or method, by name when it has one
+    Function(Option<String>),
+    /// The global `this`, outside any function
+    Global,
+}
+
+/// A use of `this` inside an arrow function, which has no `this` of its
+/// own and sees the one of the code around it
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexicalThis {
+    /// Span of the innermost arrow function around the use
+    pub arrow: Option<Span>,
+    pub binding: ThisBinding,
+}
+
+/// Where `super` and `new.target` may appear at a point of the program
+#[derive(Debug, Clone, Copy, Default)]
+struct SuperContext {
+    in_function: bool,            // `new.target`
+    in_method: bool,              // `super.x` and `super[x]`
+    in_derived_constructor: bool, // `super()`
 }
 
 impl SemanticAnalyzer {
@@ -25,7 +70,13 @@ impl SemanticAnalyzer {
             scope_stack: Vec::new(),
             type_env: HashMap::new(),
             errors: Vec::new(),
+            warnings: Vec::new(),
             strict_mode: false,
+            labels: Vec::new(),
+            super_called: None,
+            this_bindings: Vec::new(),
+            arrows: Vec::new(),
+            lexical_this: Vec::new(),
         };
         
         // Push global scope
@@ -44,6 +95,17 @@ impl SemanticAnalyzer {
         Ok(())
     }
     
+    /// Warnings found by the last analysis, such as unreachable code
+    pub fn warnings(&self) -> &[SemanticError] {
+        &self.warnings
+    }
+    
+    /// Uses of `this` inside arrow functions found by the last analysis,
+    /// resolved to the enclosing non-arrow function or the global `this`
+    pub fn lexical_this(&self) -> &[LexicalThis] {
+        &self.lexical_this
+    }
+    
     /// Visit a node and perform semantic analysis
     fn visit_node(&mut self, node: &Node) -> SemanticResult<Type> {
         match node {
@@ -54,18 +116,20 @@ impl SemanticAnalyzer {
             Node::BinaryExpression(expr) => self.visit_binary_expression(expr),
             Node::UnaryExpression(expr) => self.visit_unary_expression(expr),
             Node::Identifier(id) => self.visit_identifier(id),
-            Node::Number(_) => Ok(Type::Number),
+            Node::Number(_) | Node::NumericLiteral(_) => Ok(Type::Number),
             Node::String(_) => Ok(Type::String),
             Node::Boolean(_) => Ok(Type::Boolean),
             Node::Null => Ok(Type::Null),
             Node::Undefined => Ok(Type::Undefined),
-            Node::This => self.visit_this(),
+            Node::This(_) => self.visit_this(node),
             Node::CallExpression(call) => self.visit_call_expression(call),
             Node::AssignmentExpression(assign) => self.visit_assignment_expression(assign),
             Node::IfStatement(if_stmt) => self.visit_if_statement(if_stmt),
             Node::WhileStatement(while_stmt) => self.visit_while_statement(while_stmt),
+            Node::SwitchStatement(switch) => self.visit_switch_statement(switch),
             Node::ReturnStatement(return_stmt) => self.visit_return_statement(return_stmt),
             Node::BlockStatement(block) => self.visit_block_statement(block),
+            Node::LabeledStatement(labeled) => self.visit_node(&labeled.body),
             Node::ArrayLiteral(array) => self.visit_array_literal(array),
             Node::ObjectLiteral(obj) => self.visit_object_literal(obj),
             Node::Property(prop) => self.visit_property(prop),
@@ -73,18 +137,226 @@ impl SemanticAnalyzer {
             Node::LogicalExpression(logical) => self.visit_logical_expression(logical),
             Node::ConditionalExpression(conditional) => self.visit_conditional_expression(conditional),
             Node::ArrowFunctionExpression(arrow) => self.visit_arrow_function_expression(arrow),
+            Node::FunctionExpression(func) => {
+                let name = match func.id.as_deref() {
+                    Some(Node::Identifier(name)) => Some(name.clone()),
+                    _ => None,
+                };
+                self.visit_function_expression(func, name)
+            }
+            Node::TemplateLiteral(template) => self.visit_template_literal(template),
+            Node::TaggedTemplateExpression(tagged) => {
+                self.visit_node(&tagged.tag)?;
+                self.visit_node(&tagged.quasi)?;
+                Ok(Type::Any) // Whatever the tag function returns
+            }
+            Node::ParenthesizedExpression(expr) => self.visit_node(&expr.expression),
+            Node::SequenceExpression(expr) => {
+                let mut last = Type::Undefined;
+                for expression in &expr.expressions {
+                    last = self.visit_node(expression)?;
+                }
+                Ok(last)
+            }
             _ => Ok(Type::Any), // Default for unimplemented nodes
         }
     }
     
     /// Visit program node
     fn visit_program(&mut self, program: &v8_ast::Program) -> SemanticResult<Type> {
+        if has_use_strict_directive(&program.body) {
+            self.strict_mode = true;
+        }
+        self.check_reachability(&program.body);
         for statement in &program.body {
+            self.check_labels(statement);
+            self.check_super(statement, SuperContext::default());
+            self.check_conditions(statement);
             self.visit_node(statement)?;
         }
         Ok(Type::Undefined)
     }
     
+    /// Check that every labeled `break` and `continue` targets an enclosing
+    /// label, and that `continue` targets a loop
+    fn check_labels(&mut self, node: &Node) {
+        match node {
+            Node::LabeledStatement(stmt) => {
+                let Node::Identifier(label) = &*stmt.label else { return };
+                self.labels.push((label.clone(), is_loop(&stmt.body)));
+                self.check_labels(&stmt.body);
+                self.labels.pop();
+            }
+            Node::BreakStatement(stmt) => {
+                if let Some(Node::Identifier(label)) = stmt.label.as_deref() {
+                    if !self.labels.iter().any(|(name, _)| name == label) {
+                        self.errors.push(SemanticError::UndefinedLabel {
+                            label: label.clone(),
+                            position: stmt.span.as_ref().map(|s| s.start),
+                        });
+                    }
+                }
+            }
+            Node::ContinueStatement(stmt) => {
+                if let Some(Node::Identifier(label)) = stmt.label.as_deref() {
+                    let position = stmt.span.as_ref().map(|s| s.start);
+                    match self.labels.iter().rev().find(|(name, _)| name == label) {
+                        Some((_, true)) => {}
+                        Some((_, false)) => self.errors.push(SemanticError::IllegalContinueTarget {
+                            label: label.clone(),
+                            position,
+                        }),
+                        None => self.errors.push(SemanticError::UndefinedLabel {
+                            label: label.clone(),
+                            position,
+                        }),
+                    }
+                }
+            }
+            // Labels do not cross function boundaries
+            Node::FunctionDeclaration(_) | Node::FunctionExpression(_) | Node::ArrowFunctionExpression(_) => {
+                let outer = std::mem::take(&mut self.labels);
+                for child in node.children() {
+                    self.check_labels(child);
+                }
+                self.labels = outer;
+            }
+            _ => {
+                for child in node.children() {
+                    self.check_labels(child);
+                }
+            }
+        }
+    }
+    
+    /// Warn about an assignment used directly as the test of an `if`, a
+    /// loop or a `?:`, which is usually a mistyped `==`
+    ///
+    /// Wrapping the assignment in parentheses marks it as intended.
+    fn check_conditions(&mut self, node: &Node) {
+        let test = match node {
+            Node::IfStatement(stmt) => Some(&*stmt.test),
+            Node::WhileStatement(stmt) => Some(&*stmt.test),
+            Node::DoWhileStatement(stmt) => Some(&*stmt.test),
+            Node::ForStatement(stmt) => stmt.test.as_deref(),
+            Node::ConditionalExpression(expr) => Some(&*expr.test),
+            _ => None,
+        };
+        if let Some(Node::AssignmentExpression(assign)) = test {
+            self.warnings.push(SemanticError::AssignmentInCondition {
+                position: assign.span.as_ref().map(|s| s.start),
+            });
+        }
+        for child in node.children() {
+            self.check_conditions(child);
+        }
+    }
+    
+    /// Check that `super` and `new.target` only appear where they are
+    /// allowed, and that a derived constructor calls `super()` before it
+    /// uses `this`
+    fn check_super(&mut self, node: &Node, context: SuperContext) {
+        match node {
+            Node::ClassDeclaration(v8_ast::ClassDeclaration { super_class, body, .. })
+            | Node::ClassExpression(v8_ast::ClassExpression { super_class, body, .. }) => {
+                if let Some(super_class) = super_class {
+                    self.check_super(super_class, context);
+                }
+                let Node::BlockStatement(body) = body.as_ref() else { return };
+                for member in &body.body {
+                    match member {
+                        Node::Property(prop) => {
+                            let constructor = !prop.computed
+                                && matches!(prop.key.as_ref(), Node::Identifier(name) if name == "constructor");
+                            self.check_method(prop, context, constructor && super_class.is_some());
+                        }
+                        other => self.check_super(other, context),
+                    }
+                }
+            }
+            Node::ObjectLiteral(obj) => {
+                for property in &obj.properties {
+                    match property {
+                        Node::Property(prop) if prop.method || prop.kind != "init" => {
+                            self.check_method(prop, context, false);
+                        }
+                        other => self.check_super(other, context),
+                    }
+                }
+            }
+            Node::FunctionDeclaration(_) | Node::FunctionExpression(_) => {
+                let function = SuperContext { in_function: true, ..SuperContext::default() };
+                self.check_function_super(node, function, None);
+            }
+            // Arrow functions see the enclosing `super` and `new.target`, but
+            // may run after `super()`
+            Node::ArrowFunctionExpression(_) => self.check_function_super(node, context, None),
+            Node::CallExpression(call) if matches!(call.callee.as_ref(), Node::Super(_)) => {
+                if !context.in_derived_constructor {
+                    self.errors.push(SemanticError::UnexpectedSuper { position: node_start(&call.callee) });
+                }
+                for argument in &call.arguments {
+                    self.check_super(argument, context);
+                }
+                if self.super_called.is_some() {
+                    self.super_called = Some(true);
+                }
+            }
+            Node::MemberExpression(member) if matches!(member.object.as_ref(), Node::Super(_)) => {
+                if !context.in_method {
+                    self.errors.push(SemanticError::UnexpectedSuper { position: node_start(&member.object) });
+                }
+                if member.computed {
+                    self.check_super(&member.property, context);
+                }
+            }
+            Node::Super(_) => {
+                self.errors.push(SemanticError::UnexpectedSuper { position: node_start(node) });
+            }
+            Node::MetaProperty(_) => {
+                if !context.in_function {
+                    self.errors.push(SemanticError::UnexpectedNewTarget { position: node_start(node) });
+                }
+            }
+            Node::This(_) => {
+                if self.super_called == Some(false) {
+                    self.errors.push(SemanticError::ThisBeforeSuper { position: node_start(node) });
+                }
+            }
+            _ => {
+                for child in node.children() {
+                    self.check_super(child, context);
+                }
+            }
+        }
+    }
+    
+    /// Check an object or class member; methods and accessors may use `super.x`
+    fn check_method(&mut self, prop: &v8_ast::Property, context: SuperContext, derived_constructor: bool) {
+        if prop.computed {
+            self.check_super(&prop.key, context);
+        }
+        if !matches!(prop.value.as_ref(), Node::FunctionExpression(_)) {
+            self.check_super(&prop.value, context);
+            return;
+        }
+        let method = SuperContext {
+            in_function: true,
+            in_method: true,
+            in_derived_constructor: derived_constructor,
+        };
+        self.check_function_super(&prop.value, method, derived_constructor.then_some(false));
+    }
+    
+    /// Check the parameters and body of a function with its own `this` tracking
+    fn check_function_super(&mut self, function: &Node, context: SuperContext, super_called: Option<bool>) {
+        let outer = std::mem::replace(&mut self.super_called, super_called);
+        for child in function.children() {
+            self.check_super(child, context);
+        }
+        self.super_called = outer;
+    }
+    
     /// Visit variable declaration
     fn visit_variable_declaration(&mut self, decl: &v8_ast::VariableDeclaration) -> SemanticResult<Type> {
         let is_const = decl.kind == "const";
@@ -148,24 +420,18 @@ impl SemanticAnalyzer {
         self.scope_stack.push(function_scope);
         
         // Declare parameters in function scope
-        for param in &func.params {
-            if let Node::Identifier(param_name) = param {
-                let current_scope = self.scope_stack.last_mut().unwrap();
-                current_scope.declare_variable(
-                    param_name,
-                    Type::Any, // TODO: Infer parameter types
-                    true, // Parameters are mutable
-                    1, // TODO: Get actual line number
-                );
-                current_scope.initialize_variable(param_name);
-            }
-        }
+        let outer_strict = self.enter_function_body(&func.body);
+        let outer_arrows = self.enter_this_binding(ThisBinding::Function(Some(func_name.clone())));
+        self.declare_parameters(&func.params, false, func.span.as_ref().map(|s| s.start));
         
         // Analyze function body
-        let return_type = self.visit_node(&func.body)?;
+        let return_type = self.visit_node(&func.body);
         
         // Pop function scope
+        self.leave_this_binding(outer_arrows);
+        self.strict_mode = outer_strict;
         self.scope_stack.pop();
+        let return_type = return_type?;
         
         // Declare function in current scope
         let current_scope = self.scope_stack.last_mut().unwrap();
@@ -183,6 +449,22 @@ impl SemanticAnalyzer {
         })
     }
     
+    /// Warn about the first statement of a list that follows a statement
+    /// that always jumps away
+    ///
+    /// Function declarations are hoisted, so they are never unreachable.
+    fn check_reachability(&mut self, statements: &[Node]) {
+        let Some(exit) = statements.iter().position(always_exits) else { return };
+        let unreachable = statements[exit + 1..]
+            .iter()
+            .find(|statement| !matches!(statement, Node::FunctionDeclaration(_)));
+        if let Some(statement) = unreachable {
+            self.warnings.push(SemanticError::UnreachableCode {
+                span: statement.span().cloned(),
+            });
+        }
+    }
+    
     /// Visit expression statement
     fn visit_expression_statement(&mut self, stmt: &v8_ast::ExpressionStatement) -> SemanticResult<Type> {
         self.visit_node(&stmt.expression)
@@ -193,11 +475,21 @@ impl SemanticAnalyzer {
         let left_type = self.visit_node(&expr.left)?;
         let right_type = self.visit_node(&expr.right)?;
         
+        if matches!(expr.operator.as_str(), "/" | "%")
+            && try_eval_constant(&expr.right).is_some_and(|divisor| divisor.to_number() == 0.0)
+        {
+            self.warnings.push(SemanticError::DivisionByZero {
+                position: expr.span.as_ref().map(|s| s.start),
+            });
+        }
+        
         // Check type compatibility for the operation
         match expr.operator.as_str() {
             "+" => {
                 // String concatenation or number addition
-                if left_type.is_compatible_with(&Type::String) || right_type.is_compatible_with(&Type::String) {
+                if matches!(left_type, Type::Any) || matches!(right_type, Type::Any) {
+                    Ok(Type::Any) // Depends on the runtime types, e.g. untyped parameters
+                } else if left_type.is_compatible_with(&Type::String) || right_type.is_compatible_with(&Type::String) {
                     Ok(Type::String) // String concatenation
                 } else if left_type.is_compatible_with(&Type::Number) && right_type.is_compatible_with(&Type::Number) {
                     Ok(Type::Number) // Number addition
@@ -296,14 +588,22 @@ impl SemanticAnalyzer {
     }
     
     /// Visit 'this' expression
-    fn visit_this(&mut self) -> SemanticResult<Type> {
-        let current_scope = self.scope_stack.last().unwrap();
+    fn visit_this(&mut self, node: &Node) -> SemanticResult<Type> {
+        // An arrow function sees the `this` of the code around it
+        if let Some(arrow) = self.arrows.last().cloned() {
+            let binding = self.this_bindings.last().cloned().unwrap_or(ThisBinding::Global);
+            if binding == ThisBinding::Global {
+                self.warnings.push(SemanticError::ThisInGlobalScope {
+                    position: node_start(node).or(arrow.as_ref().map(|span| span.start)),
+                });
+            }
+            self.lexical_this.push(LexicalThis { arrow, binding });
+            return Ok(Type::Object);
+        }
         
-        // 'this' is valid in function scopes, but not in global scope
-        if current_scope.is_global_scope() {
-            self.errors.push(SemanticError::InvalidThisUsage {
-                position: None, // TODO: Get actual position
-            });
+        // At the top level 'this' is the global 'this'
+        if self.scope_stack.last().unwrap().is_global_scope() {
+            self.warnings.push(SemanticError::ThisInGlobalScope { position: node_start(node) });
         }
         
         Ok(Type::Object)
@@ -341,9 +641,12 @@ impl SemanticAnalyzer {
                 self.visit_node(arg)?;
             }
             
-            // Check if callee is a function type
+            // Check if callee is a function type; a callee of unknown type,
+            // such as a method read from an object, may be one
             if let Type::Function { return_type, .. } = callee_type {
                 Ok(*return_type)
+            } else if callee_type == Type::Any {
+                Ok(Type::Any)
             } else {
                 self.errors.push(SemanticError::TypeMismatch {
                     expected: "function".to_string(),
@@ -385,6 +688,17 @@ impl SemanticAnalyzer {
     fn visit_if_statement(&mut self, if_stmt: &v8_ast::IfStatement) -> SemanticResult<Type> {
         let condition_type = self.visit_node(&if_stmt.test)?;
         
+        // A constant condition leaves one branch dead
+        if let Some(condition) = try_eval_constant(&if_stmt.test).map(|value| value.to_boolean()) {
+            let dead = if condition { if_stmt.alternate.as_deref() } else { Some(&*if_stmt.consequent) };
+            if let Some(dead) = dead {
+                self.warnings.push(SemanticError::DeadBranch {
+                    condition,
+                    span: dead.span().cloned(),
+                });
+            }
+        }
+        
         if !condition_type.is_compatible_with(&Type::Boolean) {
             self.errors.push(SemanticError::TypeMismatch {
                 expected: "boolean".to_string(),
@@ -434,6 +748,55 @@ impl SemanticAnalyzer {
         Ok(Type::Undefined)
     }
     
+    /// Visit switch statement
+    ///
+    /// Case labels that fold to constants are compared with strict
+    /// equality; a repeated label or a second default clause is an error.
+    fn visit_switch_statement(&mut self, switch: &v8_ast::SwitchStatement) -> SemanticResult<Type> {
+        self.visit_node(&switch.discriminant)?;
+        
+        let mut labels: Vec<Value> = Vec::new();
+        let mut has_default = false;
+        
+        // All clauses share one block scope
+        let current_scope = self.scope_stack.last().unwrap().clone();
+        let block_scope = Scope::new_child(current_scope, ScopeType::Block);
+        self.scope_stack.push(block_scope);
+        
+        for case in &switch.cases {
+            let position = case.span.as_ref().map(|s| s.start);
+            match &case.test {
+                Some(test) => {
+                    self.visit_node(test)?;
+                    if let Some(label) = try_eval_constant(test) {
+                        if labels.contains(&label) {
+                            self.errors.push(SemanticError::DuplicateCase {
+                                label: label.to_string(),
+                                position,
+                            });
+                        } else {
+                            labels.push(label);
+                        }
+                    }
+                }
+                None => {
+                    if has_default {
+                        self.errors.push(SemanticError::MultipleDefaults { position });
+                    }
+                    has_default = true;
+                }
+            }
+            self.check_reachability(&case.consequent);
+            for statement in &case.consequent {
+                self.visit_node(statement)?;
+            }
+        }
+        
+        self.scope_stack.pop();
+        
+        Ok(Type::Undefined)
+    }
+    
     /// Visit return statement
     fn visit_return_statement(&mut self, return_stmt: &v8_ast::ReturnStatement) -> SemanticResult<Type> {
         if let Some(argument) = &return_stmt.argument {
@@ -452,6 +815,7 @@ impl SemanticAnalyzer {
         
         let mut last_type = Type::Undefined;
         
+        self.check_reachability(&block.body);
         for statement in &block.body {
             last_type = self.visit_node(statement)?;
         }
@@ -482,6 +846,14 @@ impl SemanticAnalyzer {
         }
     }
     
+    /// Visit template literal
+    fn visit_template_literal(&mut self, template: &v8_ast::TemplateLiteral) -> SemanticResult<Type> {
+        for expression in &template.expressions {
+            self.visit_node(expression)?;
+        }
+        Ok(Type::String)
+    }
+    
     /// Visit object literal
     fn visit_object_literal(&mut self, obj: &v8_ast::ObjectLiteral) -> SemanticResult<Type> {
         for property in &obj.properties {
@@ -499,7 +871,12 @@ impl SemanticAnalyzer {
             _ => self.visit_node(&prop.key),
         }?;
         
-        let value_type = self.visit_node(&prop.value)?;
+        let value_type = match (&*prop.key, &*prop.value) {
+            (Node::Identifier(key), Node::FunctionExpression(func)) if !prop.computed => {
+                self.visit_function_expression(func, Some(key.clone()))?
+            }
+            _ => self.visit_node(&prop.value)?,
+        };
         
         Ok(value_type)
     }
@@ -560,28 +937,200 @@ impl SemanticAnalyzer {
         self.scope_stack.push(function_scope);
         
         // Declare parameters in function scope
-        for param in &arrow.params {
-            if let Node::Identifier(param_name) = param {
-                let current_scope = self.scope_stack.last_mut().unwrap();
-                current_scope.declare_variable(
-                    param_name,
-                    Type::Any, // TODO: Infer parameter types
-                    true, // Parameters are mutable
-                    1, // TODO: Get actual line number
-                );
-                current_scope.initialize_variable(param_name);
-            }
-        }
+        let outer_strict = self.enter_function_body(&arrow.body);
+        self.arrows.push(arrow.span.clone());
+        self.declare_parameters(&arrow.params, true, arrow.span.as_ref().map(|s| s.start));
+        
+        // Analyze function body
+        let return_type = self.visit_node(&arrow.body);
+        
+        // Pop function scope
+        self.arrows.pop();
+        self.strict_mode = outer_strict;
+        self.scope_stack.pop();
+        let return_type = return_type?;
+        
+        Ok(Type::Function {
+            params: vec![], // TODO: Get actual parameter types
+            return_type: Box::new(return_type),
+        })
+    }
+    
+    /// Visit function expression (also used for object literal methods,
+    /// named after their key)
+    fn visit_function_expression(&mut self, func: &v8_ast::FunctionExpression, name: Option<String>) -> SemanticResult<Type> {
+        // Create function scope
+        let current_scope = self.scope_stack.last().unwrap().clone();
+        let function_scope = Scope::new_child(current_scope, ScopeType::Function);
+        self.scope_stack.push(function_scope);
+        
+        // Declare parameters in function scope
+        let outer_strict = self.enter_function_body(&func.body);
+        let outer_arrows = self.enter_this_binding(ThisBinding::Function(name));
+        self.declare_parameters(&func.params, false, func.span.as_ref().map(|s| s.start));
         
         // Analyze function body
-        let return_type = self.visit_node(&arrow.body)?;
+        let return_type = self.visit_node(&func.body);
         
         // Pop function scope
+        self.leave_this_binding(outer_arrows);
+        self.strict_mode = outer_strict;
         self.scope_stack.pop();
+        let return_type = return_type?;
         
         Ok(Type::Function {
             params: vec![], // TODO: Get actual parameter types
             return_type: Box::new(return_type),
         })
     }
-} 
\ No newline at end of file
+    
+    /// Enter a non-arrow function, which binds its own `this`, returning
+    /// the enclosing arrow functions so they can be restored
+    fn enter_this_binding(&mut self, binding: ThisBinding) -> Vec<Option<Span>> {
+        self.this_bindings.push(binding);
+        std::mem::take(&mut self.arrows)
+    }
+    
+    /// Leave the function entered by `enter_this_binding`
+    fn leave_this_binding(&mut self, outer_arrows: Vec<Option<Span>>) {
+        self.this_bindings.pop();
+        self.arrows = outer_arrows;
+    }
+    
+    /// Switch to strict mode if a function body starts with a "use strict"
+    /// directive, returning the previous mode so it can be restored
+    fn enter_function_body(&mut self, body: &Node) -> bool {
+        let outer_strict = self.strict_mode;
+        if let Node::BlockStatement(block) = body {
+            if has_use_strict_directive(&block.body) {
+                self.strict_mode = true;
+            }
+        }
+        outer_strict
+    }
+    
+    /// Check a parameter list and declare its bound names in the current scope
+    ///
+    /// Duplicates are rejected in strict mode, in arrow functions and in
+    /// non-simple lists (defaults, rest or destructuring); strict mode also
+    /// rejects parameters named `eval` or `arguments`.
+    fn declare_parameters(&mut self, params: &[Node], arrow: bool, position: Option<Position>) {
+        let simple = params.iter().all(|param| matches!(param, Node::Identifier(_)));
+        let unique_required = self.strict_mode || arrow || !simple;
+        
+        let mut names = Vec::new();
+        for param in params {
+            collect_bound_names(param, &mut names);
+        }
+        
+        for (index, name) in names.iter().enumerate() {
+            if self.strict_mode && (name == "eval" || name == "arguments") {
+                self.errors.push(SemanticError::StrictModeViolation {
+                    message: format!("'{}' cannot be used as a parameter name", name),
+                    position,
+                });
+            }
+            if unique_required && names[..index].contains(name) {
+                self.errors.push(SemanticError::DuplicateParameter {
+                    name: name.clone(),
+                    position,
+                });
+            }
+            
+            let current_scope = self.scope_stack.last_mut().unwrap();
+            current_scope.declare_variable(
+                name,
+                Type::Any, // TODO: Infer parameter types
+                true, // Parameters are mutable
+                1, // TODO: Get actual line number
+            );
+            current_scope.initialize_variable(name);
+        }
+    }
+}
+
+/// Whether a statement never completes normally: every path through it
+/// ends in `return`, `throw`, `break` or `continue`
+fn always_exits(node: &Node) -> bool {
+    match node {
+        Node::ReturnStatement(_) | Node::ThrowStatement(_) | Node::BreakStatement(_) | Node::ContinueStatement(_) => true,
+        Node::BlockStatement(block) => block.body.iter().any(always_exits),
+        Node::IfStatement(stmt) => {
+            always_exits(&stmt.consequent) && stmt.alternate.as_deref().is_some_and(always_exits)
+        }
+        // `break label` inside the body resumes after the labeled statement
+        Node::LabeledStatement(stmt) => match &*stmt.label {
+            Node::Identifier(label) => always_exits(&stmt.body) && !breaks_to(&stmt.body, label),
+            _ => false,
+        },
+        Node::TryStatement(stmt) => {
+            stmt.finalizer.as_deref().is_some_and(always_exits)
+                || (always_exits(&stmt.block) && stmt.handler.as_deref().is_none_or(always_exits))
+        }
+        Node::CatchClause(clause) => always_exits(&clause.body),
+        _ => false,
+    }
+}
+
+/// Start of a node's span, if it has one
+fn node_start(node: &Node) -> Option<Position> {
+    node.span().map(|span| span.start)
+}
+
+/// Whether a labeled statement is a loop, looking through nested labels
+fn is_loop(node: &Node) -> bool {
+    match node {
+        Node::ForStatement(_) | Node::ForOfStatement(_) | Node::ForInStatement(_) | Node::WhileStatement(_) | Node::DoWhileStatement(_) => true,
+        Node::LabeledStatement(stmt) => is_loop(&stmt.body),
+        _ => false,
+    }
+}
+
+/// Whether `node` contains a `break label` for the given label
+fn breaks_to(node: &Node, label: &str) -> bool {
+    match node {
+        Node::BreakStatement(stmt) => matches!(stmt.label.as_deref(), Some(Node::Identifier(name)) if name == label),
+        // Labels do not cross function boundaries
+        Node::FunctionDeclaration(_) | Node::FunctionExpression(_) | Node::ArrowFunctionExpression(_) => false,
+        Node::ExpressionStatement(_) | Node::VariableDeclaration(_) => false,
+        other => other.children().into_iter().any(|child| breaks_to(child, label)),
+    }
+}
+
+/// Whether a statement list starts with a "use strict" directive
+fn has_use_strict_directive(body: &[Node]) -> bool {
+    for statement in body {
+        match statement {
+            Node::ExpressionStatement(stmt) => match &*stmt.expression {
+                Node::String(directive) if directive == "use strict" => return true,
+                Node::String(_) => continue, // Other directives in the prologue
+                _ => return false,
+            },
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// Collect the names bound by a parameter or binding pattern
+fn collect_bound_names(node: &Node, names: &mut Vec<String>) {
+    match node {
+        Node::Identifier(name) => names.push(name.clone()),
+        Node::AssignmentPattern(pattern) => collect_bound_names(&pattern.left, names),
+        Node::RestElement(rest) => collect_bound_names(&rest.argument, names),
+        Node::ArrayPattern(pattern) => {
+            for element in pattern.elements.iter().flatten() {
+                collect_bound_names(element, names);
+            }
+        }
+        Node::ObjectPattern(pattern) => {
+            for property in &pattern.properties {
+                match property {
+                    Node::Property(prop) => collect_bound_names(&prop.value, names),
+                    other => collect_bound_names(other, names),
+                }
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,207 @@
+//! Compile-time evaluation of constant expressions
+//!
+//! An expression built only from literals and operators has the same
+//! value every time it runs; the analyzer folds such expressions to warn
+//! about dead branches and division by zero and to compare case labels.
+
+use v8_ast::Node;
+
+/// Value of a constant expression
+///
+/// `PartialEq` follows strict equality: `NaN` never matches and `0`
+/// matches `-0`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    String(String),
+    Boolean(bool),
+    Null,
+    Undefined,
+}
+
+impl Value {
+    /// ToBoolean
+    pub fn to_boolean(&self) -> bool {
+        match self {
+            Value::Number(n) => *n != 0.0 && !n.is_nan(),
+            Value::String(s) => !s.is_empty(),
+            Value::Boolean(b) => *b,
+            Value::Null | Value::Undefined => false,
+        }
+    }
+
+    /// ToNumber
+    pub fn to_number(&self) -> f64 {
+        match self {
+            Value::Number(n) => *n,
+            Value::String(s) => string_to_number(s),
+            Value::Boolean(b) => if *b { 1.0 } else { 0.0 },
+            Value::Null => 0.0,
+            Value::Undefined => f64::NAN,
+        }
+    }
+
+    /// ToString, as `+` concatenates it
+    pub fn to_js_string(&self) -> String {
+        match self {
+            Value::Number(n) if n.is_nan() => "NaN".to_string(),
+            Value::Number(n) if n.is_infinite() => {
+                if *n > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+            }
+            Value::Number(n) => format!("{}", n),
+            Value::String(s) => s.clone(),
+            Value::Boolean(b) => b.to_string(),
+            Value::Null => "null".to_string(),
+            Value::Undefined => "undefined".to_string(),
+        }
+    }
+
+    /// Result of `typeof`
+    pub fn type_of(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Boolean(_) => "boolean",
+            Value::Null => "object",
+            Value::Undefined => "undefined",
+        }
+    }
+
+    /// `==` on primitives
+    fn loose_equals(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Null | Value::Undefined, Value::Null | Value::Undefined) => true,
+            (Value::Null | Value::Undefined, _) | (_, Value::Null | Value::Undefined) => false,
+            (Value::String(a), Value::String(b)) => a == b,
+            (a, b) => a.to_number() == b.to_number(),
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::String(s) => write!(f, "\"{}\"", s),
+            other => write!(f, "{}", other.to_js_string()),
+        }
+    }
+}
+
+/// Value of `node` if it is a constant expression
+///
+/// Literals combined with arithmetic, comparison, logical, `typeof` and
+/// conditional operators fold; anything that reads a variable, calls a
+/// function or creates an object does not.
+pub fn try_eval_constant(node: &Node) -> Option<Value> {
+    match node {
+        Node::Number(n) => Some(Value::Number(*n)),
+        Node::NumericLiteral(lit) => Some(Value::Number(lit.value)),
+        Node::String(s) => Some(Value::String(s.clone())),
+        Node::Boolean(b) => Some(Value::Boolean(*b)),
+        Node::Null => Some(Value::Null),
+        Node::Undefined => Some(Value::Undefined),
+        Node::ParenthesizedExpression(expr) => try_eval_constant(&expr.expression),
+        Node::UnaryExpression(expr) => {
+            let operand = try_eval_constant(&expr.argument)?;
+            match expr.operator.as_str() {
+                "-" => Some(Value::Number(-operand.to_number())),
+                "+" => Some(Value::Number(operand.to_number())),
+                "!" => Some(Value::Boolean(!operand.to_boolean())),
+                "typeof" => Some(Value::String(operand.type_of().to_string())),
+                "void" => Some(Value::Undefined),
+                _ => None,
+            }
+        }
+        Node::BinaryExpression(expr) => {
+            let left = try_eval_constant(&expr.left)?;
+            let right = try_eval_constant(&expr.right)?;
+            eval_binary(&expr.operator, left, right)
+        }
+        Node::LogicalExpression(expr) => {
+            let left = try_eval_constant(&expr.left)?;
+            let right = try_eval_constant(&expr.right)?;
+            let take_left = match expr.operator.as_str() {
+                "&&" => !left.to_boolean(),
+                "||" => left.to_boolean(),
+                "??" => !matches!(left, Value::Null | Value::Undefined),
+                _ => return None,
+            };
+            Some(if take_left { left } else { right })
+        }
+        Node::ConditionalExpression(expr) => {
+            let test = try_eval_constant(&expr.test)?;
+            let consequent = try_eval_constant(&expr.consequent)?;
+            let alternate = try_eval_constant(&expr.alternate)?;
+            Some(if test.to_boolean() { consequent } else { alternate })
+        }
+        _ => None,
+    }
+}
+
+fn eval_binary(operator: &str, left: Value, right: Value) -> Option<Value> {
+    let value = match operator {
+        "+" => match (&left, &right) {
+            (Value::String(_), _) | (_, Value::String(_)) => {
+                Value::String(left.to_js_string() + &right.to_js_string())
+            }
+            _ => Value::Number(left.to_number() + right.to_number()),
+        },
+        "-" => Value::Number(left.to_number() - right.to_number()),
+        "*" => Value::Number(left.to_number() * right.to_number()),
+        "/" => Value::Number(left.to_number() / right.to_number()),
+        "%" => Value::Number(left.to_number() % right.to_number()),
+        "**" => Value::Number(left.to_number().powf(right.to_number())),
+        "===" => Value::Boolean(left == right),
+        "!==" => Value::Boolean(left != right),
+        "==" => Value::Boolean(left.loose_equals(&right)),
+        "!=" => Value::Boolean(!left.loose_equals(&right)),
+        "<" | ">" | "<=" | ">=" => {
+            let ordering = match (&left, &right) {
+                (Value::String(a), Value::String(b)) => Some(a.encode_utf16().cmp(b.encode_utf16())),
+                _ => left.to_number().partial_cmp(&right.to_number()),
+            };
+            Value::Boolean(ordering.is_some_and(|ordering| match operator {
+                "<" => ordering.is_lt(),
+                ">" => ordering.is_gt(),
+                "<=" => ordering.is_le(),
+                _ => ordering.is_ge(),
+            }))
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// StringToNumber: decimal, `Infinity` or `0x`/`0o`/`0b` integers
+fn string_to_number(s: &str) -> f64 {
+    let trimmed = s.trim();
+    if trimmed.is_empty() {
+        return 0.0;
+    }
+    let (sign, unsigned) = match trimmed.strip_prefix('-') {
+        Some(rest) => (-1.0, rest),
+        None => (1.0, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+    if unsigned == "Infinity" {
+        return sign * f64::INFINITY;
+    }
+    let radix = match unsigned.get(..2) {
+        Some("0x" | "0X") => Some(16),
+        Some("0o" | "0O") => Some(8),
+        Some("0b" | "0B") => Some(2),
+        _ => None,
+    };
+    if let Some(radix) = radix {
+        // Prefixed integers take no sign
+        return match (sign > 0.0 && trimmed == unsigned, u64::from_str_radix(&unsigned[2..], radix)) {
+            (true, Ok(n)) => n as f64,
+            _ => f64::NAN,
+        };
+    }
+    // Rust also accepts "inf" and "nan", which JavaScript does not
+    if unsigned.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-')) {
+        unsigned.parse::<f64>().map_or(f64::NAN, |n| sign * n)
+    } else {
+        f64::NAN
+    }
+}
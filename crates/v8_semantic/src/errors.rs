@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use v8_ast::Position;
+use v8_ast::{Position, Span};
 
 /// Semantic analysis errors
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,6 +54,84 @@ pub enum SemanticError {
         position: Option<Position>,
     },
     
+    /// Parameter name bound more than once in the same parameter list
+    DuplicateParameter {
+        name: String,
+        position: Option<Position>,
+    },
+    
+    /// Construct forbidden in strict mode code
+    StrictModeViolation {
+        message: String,
+        position: Option<Position>,
+    },
+    
+    /// Switch statement with two case labels of the same constant value
+    DuplicateCase {
+        label: String,
+        position: Option<Position>,
+    },
+    
+    /// Switch statement with more than one default clause
+    MultipleDefaults {
+        position: Option<Position>,
+    },
+    
+    /// `break` or `continue` naming a label that does not enclose it
+    UndefinedLabel {
+        label: String,
+        position: Option<Position>,
+    },
+    
+    /// `continue` naming a label that is not on a loop
+    IllegalContinueTarget {
+        label: String,
+        position: Option<Position>,
+    },
+    
+    /// `super` outside a method, or `super()` outside a derived-class constructor
+    UnexpectedSuper {
+        position: Option<Position>,
+    },
+    
+    /// `new.target` outside a function
+    UnexpectedNewTarget {
+        position: Option<Position>,
+    },
+    
+    /// `this` in a derived-class constructor before `super()` is called
+    ThisBeforeSuper {
+        position: Option<Position>,
+    },
+    
+    /// Statement that can never run, after an unconditional jump (warning)
+    UnreachableCode {
+        span: Option<Span>,
+    },
+    
+    /// Branch of an `if` whose condition is a constant (warning)
+    DeadBranch {
+        condition: bool,
+        span: Option<Span>,
+    },
+    
+    /// `/` or `%` by a constant zero (warning)
+    DivisionByZero {
+        position: Option<Position>,
+    },
+    
+    /// Assignment used directly as a condition, likely meant as a
+    /// comparison (warning)
+    AssignmentInCondition {
+        position: Option<Position>,
+    },
+    
+    /// `this` outside any function, or in an arrow function outside any
+    /// other function, where it is the global `this` (warning)
+    ThisInGlobalScope {
+        position: Option<Position>,
+    },
+    
     /// Invalid operation on type
     InvalidOperation {
         operation: String,
@@ -122,6 +200,104 @@ impl std::fmt::Display for SemanticError {
                 }
                 Ok(())
             }
+            SemanticError::DuplicateParameter { name, position } => {
+                write!(f, "Duplicate parameter name '{}' not allowed in this context", name)?;
+                if let Some(pos) = position {
+                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                }
+                Ok(())
+            }
+            SemanticError::StrictModeViolation { message, position } => {
+                write!(f, "Strict mode violation: {}", message)?;
+                if let Some(pos) = position {
+                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                }
+                Ok(())
+            }
+            SemanticError::DuplicateCase { label, position } => {
+                write!(f, "Duplicate case label '{}' in switch statement", label)?;
+                if let Some(pos) = position {
+                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                }
+                Ok(())
+            }
+            SemanticError::MultipleDefaults { position } => {
+                write!(f, "More than one default clause in switch statement")?;
+                if let Some(pos) = position {
+                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                }
+                Ok(())
+            }
+            SemanticError::UndefinedLabel { label, position } => {
+                write!(f, "Undefined label '{}'", label)?;
+                if let Some(pos) = position {
+                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                }
+                Ok(())
+            }
+            SemanticError::IllegalContinueTarget { label, position } => {
+                write!(f, "Illegal continue statement: '{}' does not denote an iteration statement", label)?;
+                if let Some(pos) = position {
+                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                }
+                Ok(())
+            }
+            SemanticError::UnexpectedSuper { position } => {
+                write!(f, "'super' keyword unexpected here")?;
+                if let Some(pos) = position {
+                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                }
+                Ok(())
+            }
+            SemanticError::UnexpectedNewTarget { position } => {
+                write!(f, "new.target expression is not allowed here")?;
+                if let Some(pos) = position {
+                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                }
+                Ok(())
+            }
+            SemanticError::ThisBeforeSuper { position } => {
+                write!(f, "Must call super constructor before accessing 'this' in a derived class constructor")?;
+                if let Some(pos) = position {
+                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                }
+                Ok(())
+            }
+            SemanticError::UnreachableCode { span } => {
+                write!(f, "Unreachable code")?;
+                if let Some(span) = span {
+                    write!(f, " at line {}, column {}", span.start.line, span.start.column)?;
+                }
+                Ok(())
+            }
+            SemanticError::DeadBranch { condition, span } => {
+                write!(f, "Branch is never taken: the condition is always {}", condition)?;
+                if let Some(span) = span {
+                    write!(f, " at line {}, column {}", span.start.line, span.start.column)?;
+                }
+                Ok(())
+            }
+            SemanticError::DivisionByZero { position } => {
+                write!(f, "Division by zero")?;
+                if let Some(pos) = position {
+                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                }
+                Ok(())
+            }
+            SemanticError::AssignmentInCondition { position } => {
+                write!(f, "Assignment used as a condition; wrap it in parentheses if it is intended")?;
+                if let Some(pos) = position {
+                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                }
+                Ok(())
+            }
+            SemanticError::ThisInGlobalScope { position } => {
+                write!(f, "'this' at the top level refers to the global 'this'")?;
+                if let Some(pos) = position {
+                    write!(f, " at line {}, column {}", pos.line, pos.column)?;
+                }
+                Ok(())
+            }
             SemanticError::InvalidOperation { operation, type_name, position } => {
                 write!(f, "Invalid operation '{}' on type '{}'", operation, type_name)?;
                 if let Some(pos) = position {
@@ -4,11 +4,13 @@
 //! including type checking, scope analysis, and error detection.
 
 pub mod analyzer;
+pub mod constant;
 pub mod scope;
 pub mod types;
 pub mod errors;
 
-pub use analyzer::SemanticAnalyzer;
+pub use analyzer::{LexicalThis, SemanticAnalyzer, ThisBinding};
+pub use constant::try_eval_constant;
 pub use errors::SemanticError;
 pub use scope::Scope;
 pub use types::Type;
@@ -0,0 +1,44 @@
+use v8_ast::Node;
+use v8_parser::Parser;
+use v8_semantic::constant::Value;
+use v8_semantic::try_eval_constant;
+
+fn eval(source: &str) -> Option<Value> {
+    let mut parser = Parser::new(source);
+    let Node::Program(program) = parser.parse().unwrap() else { panic!("Expected Program") };
+    let Node::ExpressionStatement(stmt) = &program.body[0] else { panic!("Expected ExpressionStatement") };
+    try_eval_constant(&stmt.expression)
+}
+
+#[test]
+fn test_fold_arithmetic() {
+    assert_eq!(eval("2 + 3"), Some(Value::Number(5.0)));
+    assert_eq!(eval("(1 + 2) * 4 - 10 / 5"), Some(Value::Number(10.0)));
+    assert_eq!(eval("7 % 4"), Some(Value::Number(3.0)));
+    assert_eq!(eval("-(2 + 3)"), Some(Value::Number(-5.0)));
+    assert_eq!(eval("1 / 0"), Some(Value::Number(f64::INFINITY)));
+    assert_eq!(eval("'6' * '7'"), Some(Value::Number(42.0)));
+}
+
+#[test]
+fn test_fold_strings_and_logic() {
+    assert_eq!(eval("\"a\" + \"b\""), Some(Value::String("ab".to_string())));
+    assert_eq!(eval("'n' + 1 + 2"), Some(Value::String("n12".to_string())));
+    assert_eq!(eval("1 + 2 + 'n'"), Some(Value::String("3n".to_string())));
+    assert_eq!(eval("true && false"), Some(Value::Boolean(false)));
+    assert_eq!(eval("0 || 'x'"), Some(Value::String("x".to_string())));
+    assert_eq!(eval("null ?? 4"), Some(Value::Number(4.0)));
+    assert_eq!(eval("typeof null"), Some(Value::String("object".to_string())));
+    assert_eq!(eval("'b' > 'a' ? 1 : 2"), Some(Value::Number(1.0)));
+    assert_eq!(eval("null == undefined"), Some(Value::Boolean(true)));
+    assert_eq!(eval("'1' === 1"), Some(Value::Boolean(false)));
+}
+
+#[test]
+fn test_identifiers_are_not_constant() {
+    assert_eq!(eval("x"), None);
+    assert_eq!(eval("1 + x"), None);
+    assert_eq!(eval("true && x"), None);
+    assert_eq!(eval("f(1)"), None);
+    assert_eq!(eval("[1][0]"), None);
+}
@@ -1,5 +1,9 @@
-use v8_semantic::{analyze, SemanticError};
+use v8_semantic::{analyze, SemanticAnalyzer, SemanticError, ThisBinding};
 use v8_parser::Parser;
+use v8_ast::{
+    ArrowFunctionExpression, AssignmentPattern, BlockStatement, ExpressionStatement, FunctionDeclaration,
+    FunctionExpression, Node, ObjectLiteral, Program, Property, ReturnStatement, ThisExpression,
+};
 
 #[test]
 fn test_undeclared_variable() {
@@ -55,4 +59,347 @@ fn test_duplicate_declaration() {
     } else {
         panic!("Expected DuplicateDeclaration error");
     }
-} 
\ No newline at end of file
+}
+
+#[test]
+fn test_duplicate_parameter_in_strict_mode() {
+    let mut parser = Parser::new("'use strict'; function f(a, a) { return a; }");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    
+    if let Err(SemanticError::DuplicateParameter { name, .. }) = result {
+        assert_eq!(name, "a");
+    } else {
+        panic!("Expected DuplicateParameter error");
+    }
+    
+    let mut parser = Parser::new("function f(eval) { 'use strict'; return eval; }");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(analyze(&ast), Err(SemanticError::StrictModeViolation { .. })));
+}
+
+#[test]
+fn test_duplicate_parameter_with_default_in_sloppy_mode() {
+    // function f(a, a = 1) {}
+    let default_param = Node::AssignmentPattern(AssignmentPattern {
+        left: Box::new(Node::Identifier("a".to_string())),
+        right: Box::new(Node::Number(1.0)),
+        span: None,
+    });
+    let ast = Node::Program(Program {
+        body: vec![Node::FunctionDeclaration(FunctionDeclaration {
+            id: Some(Box::new(Node::Identifier("f".to_string()))),
+            params: vec![Node::Identifier("a".to_string()), default_param],
+            body: Box::new(Node::BlockStatement(BlockStatement { body: vec![], span: None })),
+            generator: false,
+            r#async: false,
+            span: None,
+        })],
+        source_type: "script".to_string(),
+        span: None,
+    });
+    let result = analyze(&ast);
+    
+    if let Err(SemanticError::DuplicateParameter { name, .. }) = result {
+        assert_eq!(name, "a");
+    } else {
+        panic!("Expected DuplicateParameter error");
+    }
+}
+
+#[test]
+fn test_parameter_lists_allowed() {
+    // Sloppy mode allows duplicates in simple lists
+    let mut parser = Parser::new("function f(a, a) { return a; }");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+    
+    let mut parser = Parser::new("'use strict'; function g(a, b, c) { return a + b + c; }");
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+}
+
+#[test]
+fn test_duplicate_case_label() {
+    let mut parser = Parser::new("let x = 1; switch (x) { case 1: x = 2; break; case 1: x = 3; }");
+    let ast = parser.parse().unwrap();
+    let result = analyze(&ast);
+    
+    if let Err(SemanticError::DuplicateCase { label, .. }) = result {
+        assert_eq!(label, "1");
+    } else {
+        panic!("Expected DuplicateCase error");
+    }
+    
+    // Labels are compared after constant folding
+    let mut parser = Parser::new("let x = 1; switch (x) { case 'ab': break; case 'a' + 'b': break; }");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(analyze(&ast), Err(SemanticError::DuplicateCase { .. })));
+}
+
+#[test]
+fn test_multiple_default_clauses() {
+    let mut parser = Parser::new("let x = 1; switch (x) { default: break; case 1: break; default: break; }");
+    let ast = parser.parse().unwrap();
+    assert!(matches!(analyze(&ast), Err(SemanticError::MultipleDefaults { .. })));
+}
+
+#[test]
+fn test_switch_with_distinct_cases() {
+    let mut parser = Parser::new(
+        "let x = 1; let y = 2; switch (x) { case 1: break; case '1': break; case y: break; case y: break; default: break; }",
+    );
+    let ast = parser.parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+}
+
+fn unreachable_warnings(source: &str) -> usize {
+    let mut parser = Parser::new(source);
+    let ast = parser.parse().unwrap();
+    let mut analyzer = SemanticAnalyzer::new();
+    assert!(analyzer.analyze(&ast).is_ok());
+    analyzer
+        .warnings()
+        .iter()
+        .filter(|warning| matches!(warning, SemanticError::UnreachableCode { .. }))
+        .count()
+}
+
+#[test]
+fn test_unreachable_after_return() {
+    assert_eq!(unreachable_warnings("function f() { let y = 1; { return y; } y = 2; }"), 1);
+    assert_eq!(unreachable_warnings("function f() { throw 1; let y = 2; y = 3; }"), 1);
+    assert_eq!(unreachable_warnings("let x = 1; switch (x) { case 1: break; x = 2; }"), 1);
+}
+
+#[test]
+fn test_reachable_after_conditional_return() {
+    assert_eq!(unreachable_warnings("function f(x) { if (x > 0) { return 1; } return 2; }"), 0);
+    assert_eq!(unreachable_warnings("function f() { return 1; function g() { return 2; } }"), 0);
+    assert_eq!(unreachable_warnings("let x = 1; a: { if (x > 0) { break a; } throw x; } x = 2;"), 0);
+}
+
+fn warnings(source: &str) -> Vec<SemanticError> {
+    let ast = Parser::new(source).parse().unwrap();
+    let mut analyzer = SemanticAnalyzer::new();
+    assert!(analyzer.analyze(&ast).is_ok());
+    analyzer.warnings().to_vec()
+}
+
+#[test]
+fn test_constant_condition_and_division_by_zero_warnings() {
+    let found = warnings("if (false) { let a = 1; }");
+    assert!(matches!(found.as_slice(), [SemanticError::DeadBranch { condition: false, .. }]));
+    let found = warnings("if (1 + 1 === 2) { } else { let b = 2; }");
+    assert!(matches!(found.as_slice(), [SemanticError::DeadBranch { condition: true, .. }]));
+    assert!(warnings("if (true) { }").is_empty());
+    assert!(warnings("let x = true; if (x) { } else { }").is_empty());
+
+    let found = warnings("let x = 4; let y = x / 0; let z = x % (1 - 1);");
+    assert_eq!(found.iter().filter(|w| matches!(w, SemanticError::DivisionByZero { .. })).count(), 2);
+    assert!(warnings("let x = 4; let y = x / 2;").is_empty());
+}
+
+fn assignment_warnings(source: &str, preserve_parens: bool) -> usize {
+    let mut parser = Parser::new(source);
+    parser.set_preserve_parens(preserve_parens);
+    let ast = parser.parse().unwrap();
+    let mut analyzer = SemanticAnalyzer::new();
+    let _ = analyzer.analyze(&ast);
+    analyzer
+        .warnings()
+        .iter()
+        .filter(|warning| matches!(warning, SemanticError::AssignmentInCondition { .. }))
+        .count()
+}
+
+#[test]
+fn test_assignment_in_condition_warns() {
+    assert_eq!(assignment_warnings("let a = 1; let b = 2; if (a = b) { }", false), 1);
+    assert_eq!(assignment_warnings("let a = 1; let b = 2; while (a = b) { }", false), 1);
+    assert_eq!(assignment_warnings("let a = 1; let b = 2; let c = (a = b) ? 1 : 2;", false), 1);
+    assert_eq!(assignment_warnings("let a = 1; let b = 2; for (; a = b;) { }", false), 1);
+    assert_eq!(assignment_warnings("let a = 1; function f(b) { do { } while (a = b); }", false), 1);
+    assert_eq!(assignment_warnings("let a = 1; let b = 2; if (a == b) { }", false), 0);
+}
+
+#[test]
+fn test_parenthesized_assignment_in_condition_is_intended() {
+    assert_eq!(assignment_warnings("let a = 1; let b = 2; if ((a = b)) { }", true), 0);
+    assert_eq!(assignment_warnings("let a = 1; let b = 2; while ((a = b)) { }", true), 0);
+    assert_eq!(assignment_warnings("let a = 1; let b = 2; if (a = b) { }", true), 1);
+}
+
+fn arrow(body: Node) -> Node {
+    Node::ArrowFunctionExpression(ArrowFunctionExpression {
+        params: vec![],
+        body: Box::new(body),
+        expression: true,
+        r#async: false,
+        span: None,
+    })
+}
+
+fn returning(argument: Node) -> Box<Node> {
+    let statement = Node::ReturnStatement(ReturnStatement { argument: Some(Box::new(argument)), span: None });
+    Box::new(Node::BlockStatement(BlockStatement { body: vec![statement], span: None }))
+}
+
+fn analyze_statement(statement: Node) -> SemanticAnalyzer {
+    let ast = Node::Program(Program { body: vec![statement], source_type: "script".to_string(), span: None });
+    let mut analyzer = SemanticAnalyzer::new();
+    assert!(analyzer.analyze(&ast).is_ok());
+    analyzer
+}
+
+fn this() -> Node {
+    Node::This(ThisExpression { span: None })
+}
+
+fn expression_statement(expression: Node) -> Node {
+    Node::ExpressionStatement(ExpressionStatement { expression: Box::new(expression), span: None })
+}
+
+#[test]
+fn test_this_in_top_level_arrow_warns() {
+    // () => this;
+    let analyzer = analyze_statement(expression_statement(arrow(this())));
+    assert!(matches!(analyzer.warnings(), [SemanticError::ThisInGlobalScope { .. }]));
+    assert_eq!(analyzer.lexical_this()[0].binding, ThisBinding::Global);
+    // () => () => this;
+    let analyzer = analyze_statement(expression_statement(arrow(arrow(this()))));
+    assert!(matches!(analyzer.warnings(), [SemanticError::ThisInGlobalScope { .. }]));
+    // function f() { return () => this; }
+    let function = Node::FunctionDeclaration(FunctionDeclaration {
+        id: Some(Box::new(Node::Identifier("f".to_string()))),
+        params: vec![],
+        body: returning(arrow(this())),
+        generator: false,
+        r#async: false,
+        span: None,
+    });
+    let analyzer = analyze_statement(function);
+    assert!(analyzer.warnings().is_empty());
+    assert_eq!(analyzer.lexical_this()[0].binding, ThisBinding::Function(Some("f".to_string())));
+}
+
+#[test]
+fn test_this_in_arrow_resolves_to_enclosing_method() {
+    // ({ m() { return () => () => this; } });
+    let method = Node::FunctionExpression(FunctionExpression {
+        id: None,
+        params: vec![],
+        body: returning(arrow(arrow(this()))),
+        generator: false,
+        r#async: false,
+        span: None,
+    });
+    let object = Node::ObjectLiteral(ObjectLiteral {
+        properties: vec![Node::Property(Property {
+            key: Box::new(Node::Identifier("m".to_string())),
+            value: Box::new(method),
+            kind: "init".to_string(),
+            computed: false,
+            method: true,
+            shorthand: false,
+            span: None,
+        })],
+        span: None,
+    });
+    let analyzer = analyze_statement(expression_statement(object));
+    let uses = analyzer.lexical_this();
+    assert_eq!(uses.len(), 1);
+    assert_eq!(uses[0].binding, ThisBinding::Function(Some("m".to_string())));
+    assert!(analyzer.warnings().is_empty());
+}
+
+#[test]
+fn test_labeled_break_and_continue() {
+    let source = "outer: while (true) { inner: { break inner; } for (;;) { continue outer; } break outer; }";
+    let ast = Parser::new(source).parse().unwrap();
+    assert!(analyze(&ast).is_ok());
+}
+
+#[test]
+fn test_break_to_undefined_label() {
+    let ast = Parser::new("a: { } while (true) { break a; }").parse().unwrap();
+    match analyze(&ast) {
+        Err(SemanticError::UndefinedLabel { label, .. }) => assert_eq!(label, "a"),
+        other => panic!("Expected UndefinedLabel error, got {:?}", other),
+    }
+
+    // A label outside a function is not visible inside it
+    let ast = Parser::new("a: while (true) { let f = function () { break a; }; }").parse().unwrap();
+    assert!(matches!(analyze(&ast), Err(SemanticError::UndefinedLabel { .. })));
+}
+
+#[test]
+fn test_continue_to_non_loop_label() {
+    let ast = Parser::new("a: { while (true) { continue a; } }").parse().unwrap();
+    match analyze(&ast) {
+        Err(SemanticError::IllegalContinueTarget { label, .. }) => assert_eq!(label, "a"),
+        other => panic!("Expected IllegalContinueTarget error, got {:?}", other),
+    }
+}
+
+fn first_error(source: &str) -> Result<(), SemanticError> {
+    analyze(&Parser::new(source).parse().unwrap())
+}
+
+#[test]
+fn test_super_outside_method() {
+    assert!(matches!(first_error("super.x;"), Err(SemanticError::UnexpectedSuper { .. })));
+    assert!(matches!(
+        first_error("function f() { return super.x; }"),
+        Err(SemanticError::UnexpectedSuper { .. })
+    ));
+    // super() needs a derived-class constructor
+    assert!(matches!(
+        first_error("class A { constructor() { super(); } }"),
+        Err(SemanticError::UnexpectedSuper { .. })
+    ));
+    assert!(matches!(
+        first_error("class A extends Object { m() { super(); } }"),
+        Err(SemanticError::UnexpectedSuper { .. })
+    ));
+}
+
+#[test]
+fn test_super_in_methods() {
+    assert!(first_error("class A { m() { return super.x; } }").is_ok());
+    assert!(first_error("let o = { m() { return super.m(); } };").is_ok());
+    assert!(first_error("class A extends Object { constructor() { super(); this.x = super.y; } }").is_ok());
+}
+
+#[test]
+fn test_this_before_super_in_derived_constructor() {
+    match first_error("class A extends Object { constructor() { this.x = 1; super(); } }") {
+        Err(SemanticError::ThisBeforeSuper { .. }) => {}
+        other => panic!("Expected ThisBeforeSuper error, got {:?}", other),
+    }
+    // Base constructors and nested functions have their own `this`
+    assert!(first_error("class A { constructor() { this.x = 1; } }").is_ok());
+    assert!(first_error("class A extends Object { constructor() { let f = function () { return this; }; super(); } }").is_ok());
+}
+
+#[test]
+fn test_new_target_outside_function() {
+    match first_error("new.target;") {
+        Err(SemanticError::UnexpectedNewTarget { position }) => assert!(position.is_some()),
+        other => panic!("Expected UnexpectedNewTarget error, got {:?}", other),
+    }
+    assert!(first_error("function F() { return new.target; }").is_ok());
+    assert!(first_error("class A { m() { return new.target; } }").is_ok());
+}
+
+#[test]
+fn test_undeclared_variable_in_template() {
+    for source in ["`value: ${missing}`;", "let a = 1; `${a} and ${a + missing}`;", "missing`tag`;", "let tag = 1; tag`${missing}`;"] {
+        match first_error(source) {
+            Err(SemanticError::UndeclaredVariable { name, .. }) => assert_eq!(name, "missing", "{}", source),
+            other => panic!("Expected UndeclaredVariable for {:?}, got {:?}", source, other),
+        }
+    }
+    assert!(first_error("let a = 1; let s = `${a} + ${a * 2}`;").is_ok());
+    assert!(first_error("let tag = (strings) => strings; let a = 1; tag`x ${a}`;").is_ok());
+}
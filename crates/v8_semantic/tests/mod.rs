@@ -5,9 +5,11 @@ mod error_tests;
 mod scope_tests;
 mod type_tests;
 mod advanced_tests;
+mod constant_tests;
 
 pub use basic_tests::*;
 pub use error_tests::*;
 pub use scope_tests::*;
 pub use type_tests::*;
-pub use advanced_tests::*; 
\ No newline at end of file
+pub use advanced_tests::*;
+pub use constant_tests::*; 
\ No newline at end of file
@@ -1,4 +1,4 @@
-use v8_semantic::{analyze, SemanticError};
+use v8_semantic::{analyze, SemanticAnalyzer, SemanticError};
 use v8_parser::Parser;
 
 #[test]
@@ -54,12 +54,13 @@ fn test_this_in_function() {
 fn test_this_in_global() {
     let mut parser = Parser::new("let x = this;");
     let ast = parser.parse().unwrap();
-    let result = analyze(&ast);
-    assert!(result.is_err());
+    let mut analyzer = SemanticAnalyzer::new();
+    assert!(analyzer.analyze(&ast).is_ok());
     
-    if let Err(SemanticError::InvalidThisUsage { .. }) = result {
-        // Expected error
-    } else {
-        panic!("Expected InvalidThisUsage error");
+    match analyzer.warnings() {
+        [SemanticError::ThisInGlobalScope { position: Some(position) }] => {
+            assert_eq!((position.line, position.column), (1, 9));
+        }
+        warnings => panic!("Expected a ThisInGlobalScope warning, got {:?}", warnings),
     }
-} 
\ No newline at end of file
+} 
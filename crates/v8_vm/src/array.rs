@@ -0,0 +1,264 @@
+//! Array.prototype methods for the V8-Rust VM
+//!
+//! The methods that take a callback call it through
+//! `Executor::call_function` with the element, its index and the array.
+//! Indices are visited up to the length the array had when the method was
+//! called; elements removed by a callback are not visited.
+
+use crate::executor::Executor;
+use crate::heap::{HandleId, HeapEntry, NativeMethod};
+use crate::value::Value;
+use std::cmp::Ordering;
+use v8_runtime::elements::Elements;
+
+/// Métodos de `Array.prototype`, pelos nomes com que são acessados
+pub const METHODS: &[(&str, NativeMethod)] = &[
+    ("forEach", for_each),
+    ("map", map),
+    ("filter", filter),
+    ("reduce", reduce),
+    ("some", some),
+    ("every", every),
+    ("find", find),
+    ("findIndex", find_index),
+    ("sort", sort),
+];
+
+/// Handle do array `this`; TypeError se não for um array
+fn this_array(executor: &mut Executor, this: &Value, name: &str) -> Result<HandleId, Value> {
+    match this {
+        Value::Array(handle) => Ok(*handle),
+        other => Err(executor.native_error(format!(
+            "TypeError: Array.prototype.{} called on {}",
+            name,
+            other.to_string()
+        ))),
+    }
+}
+
+/// Primeiro argumento, que precisa ser uma função
+pub(crate) fn callback(executor: &mut Executor, args: &[Value]) -> Result<Value, Value> {
+    match args.first() {
+        Some(function @ Value::Function(_)) => Ok(function.clone()),
+        other => {
+            let other = other.cloned().unwrap_or(Value::Undefined);
+            Err(executor.native_error(format!("TypeError: {} is not a function", other.to_string())))
+        }
+    }
+}
+
+fn length(executor: &Executor, handle: HandleId) -> usize {
+    match executor.heap.get(handle) {
+        Some(HeapEntry::Array(elements)) => elements.len(),
+        _ => 0,
+    }
+}
+
+/// Chama o callback para cada elemento até `visit` devolver false
+///
+/// `visit` receives the index, the element and what the callback returned.
+/// The second argument is the `this` of the callback.
+fn each(
+    executor: &mut Executor,
+    handle: HandleId,
+    args: &[Value],
+    mut visit: impl FnMut(&mut Executor, usize, Value, Value) -> bool,
+) -> Result<(), Value> {
+    let callback = callback(executor, args)?;
+    let this_arg = args.get(1).cloned().unwrap_or(Value::Undefined);
+    for idx in 0..length(executor, handle) {
+        // Buracos são pulados
+        let Some(element) = executor.heap.get_array_element(handle, idx).cloned() else {
+            continue;
+        };
+        let call_args = [element.clone(), Value::Number(idx as f64), Value::Array(handle)];
+        let result = executor.call_function(&callback, this_arg.clone(), &call_args)?;
+        if !visit(executor, idx, element, result) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Cria o array do resultado e o mantém na pilha enquanto `fill` roda
+///
+/// The callbacks may trigger a collection, which must not free the results
+/// gathered so far.
+fn with_result(
+    executor: &mut Executor,
+    elements: Elements<Value>,
+    fill: impl FnOnce(&mut Executor, HandleId) -> Result<(), Value>,
+) -> Result<Value, Value> {
+    let result = executor.heap.alloc_entry(HeapEntry::Array(elements));
+    executor.stack.push(Value::Array(result));
+    let filled = fill(executor, result);
+    executor.stack.pop();
+    filled.map(|()| Value::Array(result))
+}
+
+/// `Array.prototype.forEach(callback, thisArg)`
+pub fn for_each(executor: &mut Executor, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handle = this_array(executor, &this, "forEach")?;
+    each(executor, handle, args, |_, _, _, _| true)?;
+    Ok(Value::Undefined)
+}
+
+/// `Array.prototype.map(callback, thisArg)`
+pub fn map(executor: &mut Executor, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handle = this_array(executor, &this, "map")?;
+    let length = length(executor, handle);
+    with_result(executor, Elements::with_length(length), |executor, result| {
+        each(executor, handle, args, |executor, idx, _, mapped| {
+            executor.heap.set_array_element(result, idx, mapped);
+            true
+        })
+    })
+}
+
+/// `Array.prototype.filter(callback, thisArg)`
+pub fn filter(executor: &mut Executor, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handle = this_array(executor, &this, "filter")?;
+    with_result(executor, Elements::default(), |executor, result| {
+        each(executor, handle, args, |executor, _, element, keep| {
+            if keep.to_boolean() {
+                executor.heap.push_array_element(result, element);
+            }
+            true
+        })
+    })
+}
+
+/// `Array.prototype.reduce(callback, initialValue)`
+///
+/// Without an initial value the first element is the starting accumulator,
+/// and an empty array is a TypeError.
+pub fn reduce(executor: &mut Executor, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handle = this_array(executor, &this, "reduce")?;
+    let callback = callback(executor, args)?;
+    let length = length(executor, handle);
+    let (mut accumulator, start) = match args.get(1) {
+        Some(initial) => (initial.clone(), 0),
+        None => match executor.heap.get_array_element(handle, 0) {
+            Some(first) => (first.clone(), 1),
+            None => {
+                let message = "TypeError: Reduce of empty array with no initial value".to_string();
+                return Err(executor.native_error(message));
+            }
+        },
+    };
+    for idx in start..length {
+        let Some(element) = executor.heap.get_array_element(handle, idx).cloned() else {
+            continue;
+        };
+        let call_args = [accumulator, element, Value::Number(idx as f64), Value::Array(handle)];
+        accumulator = executor.call_function(&callback, Value::Undefined, &call_args)?;
+    }
+    Ok(accumulator)
+}
+
+/// Primeiro elemento para o qual o callback retorna um valor verdadeiro
+fn find_element(executor: &mut Executor, handle: HandleId, args: &[Value]) -> Result<Option<(usize, Value)>, Value> {
+    let mut found = None;
+    each(executor, handle, args, |_, idx, element, matched| {
+        if matched.to_boolean() {
+            found = Some((idx, element));
+        }
+        found.is_none()
+    })?;
+    Ok(found)
+}
+
+/// `Array.prototype.some(callback, thisArg)`
+pub fn some(executor: &mut Executor, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handle = this_array(executor, &this, "some")?;
+    Ok(Value::Boolean(find_element(executor, handle, args)?.is_some()))
+}
+
+/// `Array.prototype.every(callback, thisArg)`
+pub fn every(executor: &mut Executor, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handle = this_array(executor, &this, "every")?;
+    let mut all = true;
+    each(executor, handle, args, |_, _, _, matched| {
+        all = matched.to_boolean();
+        all
+    })?;
+    Ok(Value::Boolean(all))
+}
+
+/// `Array.prototype.find(callback, thisArg)`
+pub fn find(executor: &mut Executor, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handle = this_array(executor, &this, "find")?;
+    Ok(find_element(executor, handle, args)?.map_or(Value::Undefined, |(_, element)| element))
+}
+
+/// `Array.prototype.findIndex(callback, thisArg)`
+pub fn find_index(executor: &mut Executor, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handle = this_array(executor, &this, "findIndex")?;
+    let idx = find_element(executor, handle, args)?.map_or(-1.0, |(idx, _)| idx as f64);
+    Ok(Value::Number(idx))
+}
+
+/// `Array.prototype.sort(comparator)`: ordena no lugar, de forma estável
+///
+/// Without a comparator elements are compared as strings. Undefined
+/// elements go to the end without being compared.
+pub fn sort(executor: &mut Executor, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handle = this_array(executor, &this, "sort")?;
+    let comparator = match args.first() {
+        None | Some(Value::Undefined) => None,
+        Some(function @ Value::Function(_)) => Some(function.clone()),
+        Some(_) => {
+            let message = "TypeError: The comparison function must be either a function or undefined".to_string();
+            return Err(executor.native_error(message));
+        }
+    };
+    // Buracos vão para o fim, depois dos undefined
+    let (elements, length) = match executor.heap.get(handle) {
+        Some(HeapEntry::Array(elements)) => (elements.values().cloned().collect::<Vec<_>>(), elements.len()),
+        _ => (Vec::new(), 0),
+    };
+    let (defined, undefined): (Vec<Value>, Vec<Value>) =
+        elements.into_iter().partition(|element| *element != Value::Undefined);
+    let mut sorted = merge_sort(defined, &mut |a, b| match &comparator {
+        Some(comparator) => {
+            let order = executor.call_function(comparator, Value::Undefined, &[a.clone(), b.clone()])?;
+            // NaN conta como igual
+            Ok(order.to_number().partial_cmp(&0.0).unwrap_or(Ordering::Equal))
+        }
+        None => Ok(a.to_string().encode_utf16().cmp(b.to_string().encode_utf16())),
+    })?;
+    sorted.extend(undefined);
+    if let Some(HeapEntry::Array(elements)) = executor.heap.get_mut(handle) {
+        *elements = sorted.into();
+        elements.set_len(length);
+    }
+    Ok(this)
+}
+
+/// Merge sort estável; para no primeiro erro do comparador
+///
+/// Unlike `slice::sort_by`, an inconsistent comparator only yields an
+/// unspecified order.
+fn merge_sort(
+    mut items: Vec<Value>,
+    compare: &mut impl FnMut(&Value, &Value) -> Result<Ordering, Value>,
+) -> Result<Vec<Value>, Value> {
+    if items.len() <= 1 {
+        return Ok(items);
+    }
+    let right = items.split_off(items.len() / 2);
+    let mut left = merge_sort(items, compare)?.into_iter().peekable();
+    let mut right = merge_sort(right, compare)?.into_iter().peekable();
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        // Só um elemento da direita estritamente menor passa à frente: iguais mantêm a ordem
+        if compare(a, b)? == Ordering::Greater {
+            merged.extend(right.next());
+        } else {
+            merged.extend(left.next());
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
@@ -5,11 +5,34 @@ use crate::instructions::Instruction;
 #[derive(Debug, Clone)]
 pub struct Bytecode {
     pub instructions: Vec<Instruction>,
+    pub functions: Vec<FunctionInfo>, // Funções instanciadas por CreateFunction, por índice
     // Futuramente: pool de constantes, metadados, etc.
 }
 
 impl Bytecode {
     pub fn new(instructions: Vec<Instruction>) -> Self {
-        Bytecode { instructions }
+        Bytecode { instructions, functions: Vec::new() }
     }
-} 
\ No newline at end of file
+
+    pub fn with_functions(instructions: Vec<Instruction>, functions: Vec<FunctionInfo>) -> Self {
+        Bytecode { instructions, functions }
+    }
+}
+
+/// De onde vem o valor de uma variável capturada quando a função é criada
+#[derive(Debug, Clone, PartialEq)]
+pub enum Capture {
+    Local(usize), // Variável local do código que cria a função
+    Closure,      // Variável que a própria função criadora capturou, com o mesmo nome
+}
+
+/// Função compilada; `CreateFunction` cria um valor chamável a partir dela
+#[derive(Debug, Clone)]
+pub struct FunctionInfo {
+    pub name: String,
+    pub bytecode: Bytecode,
+    pub arg_count: usize,
+    pub local_count: usize,
+    pub captures: Vec<(String, Capture)>, // Lidas com LoadClosureVar(nome)
+    pub is_async: bool, // Chamadas retornam uma promise; `Await` suspende o frame
+}
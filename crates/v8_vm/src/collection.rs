@@ -0,0 +1,253 @@
+//! Set and Map for the V8-Rust VM
+//!
+//! The entries live in the heap entry of the instance (`HeapEntry::Set`,
+//! `HeapEntry::Map`), in insertion order. Keys are compared with
+//! SameValueZero: `NaN` matches itself and `-0` is stored as `0`. The
+//! methods are shared through a prototype created on first use.
+
+use crate::array::callback;
+use crate::executor::Executor;
+use crate::heap::{HandleId, Heap, HeapEntry, NativeMethod};
+use crate::iterator;
+use crate::value::Value;
+
+/// Construtores globais, pelos nomes com que são acessados
+///
+/// The compiler reserves the first global slots for them, in this order.
+pub const CONSTRUCTORS: &[(&str, NativeMethod)] = &[("Set", construct_set), ("Map", construct_map)];
+
+/// Métodos de `Set.prototype`
+pub const SET_METHODS: &[(&str, NativeMethod)] = &[
+    ("add", add),
+    ("has", has),
+    ("delete", delete),
+    ("clear", clear),
+    ("forEach", for_each),
+];
+
+/// Métodos de `Map.prototype`
+pub const MAP_METHODS: &[(&str, NativeMethod)] = &[
+    ("get", get),
+    ("set", set),
+    ("has", has),
+    ("delete", delete),
+    ("clear", clear),
+    ("forEach", for_each),
+];
+
+/// Igualdade das chaves: como `===`, mas `NaN` é igual a si mesmo
+fn same_value_zero(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x == y || (x.is_nan() && y.is_nan()),
+        _ => a == b,
+    }
+}
+
+/// `-0` vira `0`, como na chave guardada pelo Set ou Map
+fn normalize(key: &Value) -> Value {
+    match key {
+        Value::Number(n) if *n == 0.0 => Value::Number(0.0),
+        other => other.clone(),
+    }
+}
+
+/// Número de entradas, se o handle é um Set ou Map (a propriedade `size`)
+pub fn size(heap: &Heap, handle: HandleId) -> Option<usize> {
+    match heap.get(handle) {
+        Some(HeapEntry::Set(values)) => Some(values.len()),
+        Some(HeapEntry::Map(entries)) => Some(entries.len()),
+        _ => None,
+    }
+}
+
+/// Handle do Set ou Map `this`; TypeError se for outro valor
+///
+/// `kind` names the collection the method belongs to; `None` accepts both.
+fn receiver(executor: &mut Executor, this: &Value, kind: Option<&str>, method: &str) -> Result<HandleId, Value> {
+    let found = match this {
+        Value::Object(handle) => match executor.heap.get(*handle) {
+            Some(HeapEntry::Set(_)) => Some((*handle, "Set")),
+            Some(HeapEntry::Map(_)) => Some((*handle, "Map")),
+            _ => None,
+        },
+        _ => None,
+    };
+    match found {
+        Some((handle, found)) if kind.is_none_or(|kind| kind == found) => Ok(handle),
+        _ => Err(executor.native_error(format!(
+            "TypeError: Method {}.prototype.{} called on incompatible receiver {}",
+            kind.unwrap_or("Set"),
+            method,
+            this.to_string()
+        ))),
+    }
+}
+
+fn position(heap: &Heap, handle: HandleId, key: &Value) -> Option<usize> {
+    match heap.get(handle) {
+        Some(HeapEntry::Set(values)) => values.iter().position(|value| same_value_zero(value, key)),
+        Some(HeapEntry::Map(entries)) => entries.iter().position(|(entry, _)| same_value_zero(entry, key)),
+        _ => None,
+    }
+}
+
+fn insert(heap: &mut Heap, handle: HandleId, key: &Value, value: Value) {
+    let existing = position(heap, handle, key);
+    match heap.get_mut(handle) {
+        Some(HeapEntry::Set(values)) if existing.is_none() => values.push(normalize(key)),
+        Some(HeapEntry::Map(entries)) => match existing {
+            Some(idx) => entries[idx].1 = value,
+            None => entries.push((normalize(key), value)),
+        },
+        _ => {}
+    }
+}
+
+/// Cria a instância e a preenche com os elementos do iterável em `args[0]`
+///
+/// The instance stays on the stack while the iterable runs, since `next`
+/// may trigger a collection. For a Map each element must be an object,
+/// read as a `[key, value]` entry.
+fn construct(executor: &mut Executor, entry: HeapEntry, args: &[Value]) -> Result<Value, Value> {
+    let (name, methods) = match entry {
+        HeapEntry::Map(_) => ("Map", MAP_METHODS),
+        _ => ("Set", SET_METHODS),
+    };
+    let handle = executor.heap.alloc_entry(entry);
+    let prototype = executor.builtin_prototype(name, methods);
+    executor.heap.set_prototype(handle, prototype);
+    let instance = Value::Object(handle);
+    let source = match args.first() {
+        None | Some(Value::Null) | Some(Value::Undefined) => return Ok(instance),
+        Some(source) => source,
+    };
+    executor.stack.push(instance.clone());
+    let mut invalid = None;
+    let filled = iterator::for_each_value(executor, source, |executor, element| {
+        if name == "Set" {
+            insert(&mut executor.heap, handle, &element, Value::Undefined);
+            return;
+        }
+        let pair = match &element {
+            Value::Array(pair) => {
+                [0, 1].map(|idx| executor.heap.get_array_element(*pair, idx).cloned().unwrap_or(Value::Undefined))
+            }
+            Value::Object(pair) => ["0", "1"].map(|key| {
+                executor.heap.get_object_property(*pair, key).cloned().unwrap_or(Value::Undefined)
+            }),
+            _ => {
+                invalid.get_or_insert(element);
+                return;
+            }
+        };
+        let [key, value] = pair;
+        insert(&mut executor.heap, handle, &key, value);
+    });
+    executor.stack.pop();
+    filled?;
+    if let Some(element) = invalid {
+        let message = format!("TypeError: Iterator value {} is not an entry object", element.to_string());
+        return Err(executor.native_error(message));
+    }
+    Ok(instance)
+}
+
+/// `new Set(iterable)`
+pub fn construct_set(executor: &mut Executor, _this: Value, args: &[Value]) -> Result<Value, Value> {
+    construct(executor, HeapEntry::Set(Vec::new()), args)
+}
+
+/// `new Map(iterable)`
+pub fn construct_map(executor: &mut Executor, _this: Value, args: &[Value]) -> Result<Value, Value> {
+    construct(executor, HeapEntry::Map(Vec::new()), args)
+}
+
+/// `Set.prototype.add(value)`; devolve o próprio Set
+pub fn add(executor: &mut Executor, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handle = receiver(executor, &this, Some("Set"), "add")?;
+    let value = args.first().cloned().unwrap_or(Value::Undefined);
+    insert(&mut executor.heap, handle, &value, Value::Undefined);
+    Ok(this)
+}
+
+/// `Map.prototype.set(key, value)`; devolve o próprio Map
+pub fn set(executor: &mut Executor, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handle = receiver(executor, &this, Some("Map"), "set")?;
+    let key = args.first().cloned().unwrap_or(Value::Undefined);
+    let value = args.get(1).cloned().unwrap_or(Value::Undefined);
+    insert(&mut executor.heap, handle, &key, value);
+    Ok(this)
+}
+
+/// `Map.prototype.get(key)`; undefined para uma chave ausente
+pub fn get(executor: &mut Executor, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handle = receiver(executor, &this, Some("Map"), "get")?;
+    let key = args.first().cloned().unwrap_or(Value::Undefined);
+    let value = match (position(&executor.heap, handle, &key), executor.heap.get(handle)) {
+        (Some(idx), Some(HeapEntry::Map(entries))) => entries[idx].1.clone(),
+        _ => Value::Undefined,
+    };
+    Ok(value)
+}
+
+/// `Set.prototype.has(value)` e `Map.prototype.has(key)`
+pub fn has(executor: &mut Executor, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handle = receiver(executor, &this, None, "has")?;
+    let key = args.first().cloned().unwrap_or(Value::Undefined);
+    Ok(Value::Boolean(position(&executor.heap, handle, &key).is_some()))
+}
+
+/// `Set.prototype.delete(value)` e `Map.prototype.delete(key)`; devolve se havia a entrada
+pub fn delete(executor: &mut Executor, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handle = receiver(executor, &this, None, "delete")?;
+    let key = args.first().cloned().unwrap_or(Value::Undefined);
+    let Some(idx) = position(&executor.heap, handle, &key) else { return Ok(Value::Boolean(false)) };
+    match executor.heap.get_mut(handle) {
+        Some(HeapEntry::Set(values)) => {
+            values.remove(idx);
+        }
+        Some(HeapEntry::Map(entries)) => {
+            entries.remove(idx);
+        }
+        _ => {}
+    }
+    Ok(Value::Boolean(true))
+}
+
+/// `Set.prototype.clear()` e `Map.prototype.clear()`
+pub fn clear(executor: &mut Executor, this: Value, _args: &[Value]) -> Result<Value, Value> {
+    let handle = receiver(executor, &this, None, "clear")?;
+    match executor.heap.get_mut(handle) {
+        Some(HeapEntry::Set(values)) => values.clear(),
+        Some(HeapEntry::Map(entries)) => entries.clear(),
+        _ => {}
+    }
+    Ok(Value::Undefined)
+}
+
+/// `forEach(callback, thisArg)`: o callback recebe valor, chave e a coleção
+///
+/// Entries are read by position on each step, so ones added by the callback
+/// are visited too. A Set passes each value as its own key.
+pub fn for_each(executor: &mut Executor, this: Value, args: &[Value]) -> Result<Value, Value> {
+    let handle = receiver(executor, &this, None, "forEach")?;
+    let callback = callback(executor, args)?;
+    let this_arg = args.get(1).cloned().unwrap_or(Value::Undefined);
+    let mut idx = 0;
+    loop {
+        let (value, key) = match executor.heap.get(handle) {
+            Some(HeapEntry::Set(values)) => match values.get(idx) {
+                Some(value) => (value.clone(), value.clone()),
+                None => break,
+            },
+            Some(HeapEntry::Map(entries)) => match entries.get(idx) {
+                Some((key, value)) => (value.clone(), key.clone()),
+                None => break,
+            },
+            _ => break,
+        };
+        executor.call_function(&callback, this_arg.clone(), &[value, key, this.clone()])?;
+        idx += 1;
+    }
+    Ok(Value::Undefined)
+}
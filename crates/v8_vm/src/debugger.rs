@@ -0,0 +1,56 @@
+//! Step debugger for the V8-Rust VM
+//!
+//! Breakpoints and stepping apply to the outermost bytecode run by the
+//! `Executor`; calls made from it run to completion (step over).
+
+use crate::value::Value;
+use std::collections::BTreeSet;
+
+/// Why execution paused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseReason {
+    Step,
+    Breakpoint,
+    DebuggerInstruction,
+}
+
+/// State of the VM handed to the debugger callback
+#[derive(Debug)]
+pub struct DebuggerEvent<'a> {
+    pub reason: PauseReason,
+    pub pc: usize,
+    pub stack: &'a [Value],
+    pub locals: &'a [Value],
+}
+
+pub type DebuggerCallback = Box<dyn FnMut(&DebuggerEvent)>;
+
+/// Breakpoints, callback and paused position of an `Executor`
+#[derive(Default)]
+pub struct Debugger {
+    pub breakpoints: BTreeSet<usize>,
+    pub(crate) callback: Option<DebuggerCallback>,
+    pub(crate) paused: Option<(usize, Vec<Value>)>, // pc e locais do frame mais externo
+}
+
+impl Debugger {
+    /// Whether a callback is attached; `Debugger` instructions only pause then
+    pub fn is_attached(&self) -> bool {
+        self.callback.is_some()
+    }
+
+    pub(crate) fn notify(&mut self, reason: PauseReason, pc: usize, stack: &[Value], locals: &[Value]) {
+        if let Some(callback) = self.callback.as_mut() {
+            callback(&DebuggerEvent { reason, pc, stack, locals });
+        }
+    }
+}
+
+/// How `run_frame` may pause
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DebugMode {
+    Off,    // frames aninhados e chamadas de cauda: nunca pausa
+    Run,    // pausa em breakpoints e em `Debugger`
+    Resume, // como Run, exceto na instrução onde estava pausado
+    Step,   // executa uma instrução e pausa na seguinte
+}
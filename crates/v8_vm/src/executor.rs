@@ -1,13 +1,25 @@
 //! Executor for the V8-Rust VM
 
-use crate::bytecode::Bytecode;
-use crate::frame::Frame;
+use crate::array;
+use crate::collection;
+use crate::bytecode::{Bytecode, Capture};
+use crate::debugger::{DebugMode, Debugger, DebuggerEvent, PauseReason};
+use crate::frame::{Frame, StackFrameInfo};
 use crate::heap::HeapEntry;
-use crate::heap::{HandleId, Heap};
+use crate::heap::{value_handle, HandleId, Heap, HeapStats, NativeMethod};
+use crate::inline_cache::{InlineCache, InlineCacheStats};
+use crate::iterator;
+use crate::limits::ResourceLimits;
+use crate::shape::ShapeId;
 use crate::instructions::Instruction;
+use crate::promise::{Continuation, Microtask, PromiseState, Reaction};
 use crate::registers::Registers;
 use crate::stack::Stack;
 use crate::value::Value;
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+use v8_runtime::object;
 
 pub struct Executor {
     pub stack: Stack,
@@ -15,6 +27,24 @@ pub struct Executor {
     pub registers: Registers,
     pub heap: Heap,
     pub globals: Vec<Value>, // Variáveis globais
+    pub exception: Option<(Value, Vec<StackFrameInfo>)>, // Valor lançado e stack trace no ponto do throw
+    pub main_inline_cache: InlineCache, // Inline cache do código fora de funções
+    pub inline_cache_stats: InlineCacheStats,
+    pub debugger: Debugger,
+    pub microtasks: VecDeque<Microtask>, // Funções async prontas para retomar, em ordem
+    limits: ResourceLimits,
+    stack_base: usize,               // Endereço da pilha nativa na chamada mais externa
+    constants: Rc<[Value]>,          // Pool do programa em execução, para `call_function`
+    array_prototype: Option<HandleId>, // Métodos dos arrays, criados no primeiro acesso
+    builtin_prototypes: HashMap<&'static str, HandleId>, // Protótipos de Set e Map, criados no primeiro uso
+}
+
+/// Como `run_frame` terminou
+enum FrameExit {
+    Done,
+    TailCall(Bytecode), // o frame foi reiniciado para a função chamada
+    Paused,             // o estado ficou em `debugger.paused`
+    Await { value: Value, ip: usize, locals: Vec<Value> }, // função async suspensa no `Await` em `ip`
 }
 
 impl Executor {
@@ -25,62 +55,809 @@ impl Executor {
             registers: Registers::new(),
             heap: Heap::new(),
             globals: vec![Value::Undefined; 32], // 32 variáveis globais
+            exception: None,
+            main_inline_cache: InlineCache::new(),
+            inline_cache_stats: InlineCacheStats::default(),
+            debugger: Debugger::default(),
+            microtasks: VecDeque::new(),
+            limits: ResourceLimits::default(),
+            stack_base: 0,
+            constants: Rc::from(Vec::new()),
+            array_prototype: None,
+            builtin_prototypes: HashMap::new(),
+        }
+    }
+
+    /// Limita o heap, a profundidade de chamadas e a pilha nativa que elas usam
+    ///
+    /// Passar de qualquer um dos limites lança um `RangeError` que o script
+    /// pode capturar. O limite do heap conta as entradas vivas depois de uma
+    /// coleta de lixo, feita quando uma alocação passa dele.
+    pub fn set_limits(&mut self, limits: ResourceLimits) {
+        self.limits = limits;
+        self.heap.set_limit(limits.max_heap_entries);
+    }
+
+    pub fn limits(&self) -> ResourceLimits {
+        self.limits
+    }
+
+    /// Pausa na instrução `pc` do bytecode mais externo
+    pub fn set_breakpoint(&mut self, pc: usize) {
+        self.debugger.breakpoints.insert(pc);
+    }
+
+    /// Remove o breakpoint em `pc`; retorna se havia um
+    pub fn clear_breakpoint(&mut self, pc: usize) -> bool {
+        self.debugger.breakpoints.remove(&pc)
+    }
+
+    /// Registra o callback chamado ao parar num breakpoint ou numa instrução `Debugger`
+    pub fn on_debugger_event(&mut self, callback: impl FnMut(&DebuggerEvent) + 'static) {
+        self.debugger.callback = Some(Box::new(callback));
+    }
+
+    /// Instrução em que a execução está pausada, se estiver
+    pub fn paused_at(&self) -> Option<usize> {
+        self.debugger.paused.as_ref().map(|(pc, _)| *pc)
+    }
+
+    /// Locais do frame pausado
+    pub fn paused_locals(&self) -> Option<&[Value]> {
+        self.debugger.paused.as_ref().map(|(_, locals)| locals.as_slice())
+    }
+
+    /// Executa uma instrução e devolve o controle
+    ///
+    /// Continues from the paused position, or from the first instruction
+    /// when not paused. A call runs to completion as a single step.
+    /// Returns the pc of the next instruction, or `None` once the bytecode
+    /// has finished.
+    pub fn execute_step(&mut self, bytecode: &Bytecode, constants: &[Value]) -> Option<usize> {
+        self.continue_from_pause(bytecode, constants, DebugMode::Step)
+    }
+
+    /// Continua a execução pausada até o próximo breakpoint ou o fim
+    pub fn resume(&mut self, bytecode: &Bytecode, constants: &[Value]) -> Option<usize> {
+        self.continue_from_pause(bytecode, constants, DebugMode::Resume)
+    }
+
+    fn continue_from_pause(&mut self, bytecode: &Bytecode, constants: &[Value], mode: DebugMode) -> Option<usize> {
+        self.constants = Rc::from(constants);
+        let (ip, locals, mode) = match self.debugger.paused.take() {
+            Some((ip, locals)) => (ip, locals, mode),
+            None if mode == DebugMode::Step => (0, new_locals(), mode),
+            None => (0, new_locals(), DebugMode::Run),
+        };
+        self.run_from(bytecode, constants, ip, locals, mode);
+        self.paused_at()
+    }
+
+    /// Stack trace atual, do frame mais interno para o mais externo
+    pub fn current_stack_trace(&self) -> Vec<StackFrameInfo> {
+        let mut trace = vec![self.frame_info(&self.frame)];
+        for frame in self.stack.frames.iter().rev() {
+            trace.push(self.frame_info(frame));
+        }
+        trace
+    }
+
+    pub fn get_heap_stats(&self) -> HeapStats {
+        self.heap.stats()
+    }
+
+    /// Libera as entradas do heap inalcançáveis a partir da pilha, dos frames e das globais
+    ///
+    /// The locals of the running frame are only seen once copied into
+    /// `frame.local_values`, as `GcHint` does. Returns the number of entries freed.
+    pub fn collect_garbage(&mut self) -> usize {
+        let mut roots: Vec<&Value> = Vec::new();
+        roots.extend(&self.stack.values);
+        roots.extend(&self.globals);
+        if let Some((_, locals)) = &self.debugger.paused {
+            roots.extend(locals);
+        }
+        for frame in self.stack.frames.iter().chain(std::iter::once(&self.frame)) {
+            roots.extend(&frame.arguments);
+            roots.extend(&frame.local_values);
+            roots.extend(frame.closure_vars.values());
+            roots.extend(&frame.this_value);
+        }
+        if let Some((value, _)) = &self.exception {
+            roots.push(value);
+        }
+        let mut handles: Vec<HandleId> = roots
+            .into_iter()
+            .filter_map(value_handle)
+            .collect();
+        handles.extend(self.stack.frames.iter().chain(std::iter::once(&self.frame)).filter_map(|frame| frame.function_handle));
+        handles.extend(self.microtasks.iter().flat_map(Microtask::handles));
+        handles.extend(self.array_prototype);
+        handles.extend(self.builtin_prototypes.values());
+        self.heap.collect(handles)
+    }
+
+    fn frame_info(&self, frame: &Frame) -> StackFrameInfo {
+        let (function_name, local_names) = match frame.function_handle.and_then(|h| self.heap.get(h)) {
+            Some(HeapEntry::Function { name, local_names, .. }) => {
+                let name = if name.is_empty() { "<anonymous>" } else { name.as_str() };
+                (name.to_string(), local_names.as_slice())
+            }
+            _ => ("<main>".to_string(), &[][..]),
+        };
+        let locals = local_names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                let value = frame.local_values.get(idx).cloned().unwrap_or(Value::Undefined);
+                (name.clone(), value)
+            })
+            .collect();
+        StackFrameInfo {
+            function_name,
+            ip: frame.ip,
+            locals,
+        }
+    }
+
+    /// Registra o valor lançado com o stack trace no ponto do throw
+    fn throw_value(&mut self, value: Value, ip: usize, locals: &[Value]) {
+        self.frame.ip = ip;
+        self.frame.local_values = locals.to_vec();
+        self.exception = Some((value, self.current_stack_trace()));
+    }
+
+    /// Objeto `Error` lançado no lugar do `Err` de uma função nativa
+    ///
+    /// Uma mensagem como "TypeError: x" vira `{name: "TypeError", message: "x"}`;
+    /// sem esse prefixo, o nome é "Error".
+    pub(crate) fn native_error(&mut self, message: String) -> Value {
+        let (name, message) = match message.split_once(": ") {
+            Some((name, rest)) if name.ends_with("Error") && name.chars().all(|c| c.is_ascii_alphanumeric()) => {
+                (name.to_string(), rest.to_string())
+            }
+            _ => ("Error".to_string(), message),
+        };
+        let handle = self.heap.alloc_object();
+        self.heap.set_object_property(handle, "name".to_string(), Value::String(name));
+        self.heap.set_object_property(handle, "message".to_string(), Value::String(message));
+        Value::Object(handle)
+    }
+
+    /// Elementos de arrays e strings; os demais iteráveis passam por `iterator`
+    pub(crate) fn iterate(&self, value: &Value) -> Option<Vec<Value>> {
+        match value {
+            Value::Array(handle) => match self.heap.get(*handle) {
+                Some(HeapEntry::Array(elements)) => Some(elements.to_vec()),
+                _ => None,
+            },
+            Value::String(s) => Some(s.chars().map(|c| Value::String(c.to_string())).collect()),
+            _ => None,
+        }
+    }
+
+    /// Propriedades próprias enumeráveis copiadas por `{...value}`
+    fn own_properties(&self, value: &Value) -> Vec<(String, Value)> {
+        match value {
+            Value::Object(handle) => match self.heap.get(*handle) {
+                Some(HeapEntry::Object(properties)) => {
+                    properties.iter().map(|(key, value)| (key.clone(), value.clone())).collect()
+                }
+                _ => Vec::new(),
+            },
+            // Arrays contribuem com seus índices, exceto os buracos
+            Value::Array(handle) => match self.heap.get(*handle) {
+                Some(HeapEntry::Array(elements)) => elements
+                    .indices()
+                    .map(|idx| (idx.to_string(), elements.get(idx).cloned().unwrap_or(Value::Undefined)))
+                    .collect(),
+                _ => Vec::new(),
+            },
+            Value::String(_) => self
+                .iterate(value)
+                .unwrap_or_default()
+                .into_iter()
+                .enumerate()
+                .map(|(idx, element)| (idx.to_string(), element))
+                .collect(),
+            // null, undefined e demais primitivos não têm propriedades próprias
+            _ => Vec::new(),
+        }
+    }
+
+    /// Chaves percorridas por `for-in`: as do objeto e depois as de cada
+    /// protótipo ainda não vistas, índices inteiros primeiro. O `constructor`
+    /// dos protótipos não é enumerável
+    fn for_in_keys(&self, value: &Value) -> Vec<String> {
+        let mut handle = match value {
+            Value::Object(handle) => Some(*handle),
+            _ => return self.own_properties(value).into_iter().map(|(key, _)| key).collect(),
+        };
+        let mut keys: Vec<String> = Vec::new();
+        let mut inherited = false;
+        while let Some(current) = handle {
+            if let Some(HeapEntry::Object(properties)) = self.heap.get(current) {
+                let (mut indices, strings): (Vec<String>, Vec<String>) = properties
+                    .iter()
+                    .map(|(key, _)| key)
+                    .filter(|key| !(keys.contains(key) || inherited && key == "constructor"))
+                    .partition(|key| object::array_index(key).is_some());
+                indices.sort_by_key(|key| object::array_index(key).unwrap_or(0));
+                keys.extend(indices.into_iter().chain(strings));
+            }
+            handle = self.heap.get_prototype(current);
+            inherited = true;
+        }
+        keys
+    }
+
+    /// Inline cache da função em execução
+    fn inline_cache_mut(&mut self) -> &mut InlineCache {
+        match self.frame.function_handle.and_then(|handle| self.heap.get_mut(handle)) {
+            Some(HeapEntry::Function { inline_cache, .. }) => inline_cache,
+            _ => &mut self.main_inline_cache,
+        }
+    }
+
+    /// Slot em cache no site `ip` para um objeto com este shape, contando acertos e falhas
+    fn cached_slot(&mut self, ip: usize, shape: ShapeId, key: &str) -> Option<usize> {
+        let slot = self.inline_cache_mut().lookup(ip, shape, key);
+        match slot {
+            Some(_) => self.inline_cache_stats.hits += 1,
+            None => self.inline_cache_stats.misses += 1,
+        }
+        slot
+    }
+
+    /// Lê uma propriedade própria no site `ip`, consultando o inline cache
+    fn load_property(&mut self, handle: HandleId, key: &str, ip: usize) -> Option<Value> {
+        let (shape, slot) = match self.heap.get(handle) {
+            Some(HeapEntry::Object(properties)) => (properties.shape(), properties.slot(key)),
+            _ => return None,
+        };
+        let slot = match self.cached_slot(ip, shape, key) {
+            Some(slot) => slot,
+            None => {
+                let slot = slot?;
+                self.inline_cache_mut().update(ip, shape, key, slot);
+                slot
+            }
+        };
+        match self.heap.get(handle) {
+            Some(HeapEntry::Object(properties)) => properties.get_slot(slot).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Escreve uma propriedade no site `ip`, consultando o inline cache
+    fn store_property(&mut self, handle: HandleId, key: String, value: Value, ip: usize) {
+        let (shape, slot) = match self.heap.get(handle) {
+            Some(HeapEntry::Object(properties)) => (properties.shape(), properties.slot(&key)),
+            _ => return,
+        };
+        let slot = match (self.cached_slot(ip, shape, &key), slot) {
+            (Some(slot), _) => Some(slot),
+            (None, Some(slot)) => {
+                self.inline_cache_mut().update(ip, shape, &key, slot);
+                Some(slot)
+            }
+            // Chave nova: o objeto muda de shape e o site não é cacheado
+            (None, None) => None,
+        };
+        if let Some(HeapEntry::Object(properties)) = self.heap.get_mut(handle) {
+            match slot {
+                Some(slot) => properties.set_slot(slot, value),
+                None => {
+                    properties.insert(key, value);
+                }
+            }
+        }
+    }
+
+    /// Cria o valor de função descrito por `functions[index]`, capturando as variáveis
+    /// do código em execução
+    fn create_function(&mut self, bytecode: &Bytecode, index: usize, locals: &[Value]) -> Value {
+        let info = bytecode.functions[index].clone();
+        let handle = self.heap.alloc_function(info.bytecode, info.arg_count, info.local_count);
+        self.heap.set_function_name(handle, info.name);
+        self.heap.set_function_async(handle, info.is_async);
+        for (name, capture) in info.captures {
+            let value = match capture {
+                Capture::Local(slot) => locals.get(slot).cloned(),
+                Capture::Closure => self.frame.closure_vars.get(&name).cloned(),
+            };
+            self.heap.set_closure_var(handle, name, value.unwrap_or(Value::Undefined));
         }
+        Value::Function(handle)
     }
 
+    /// Valor da célula apontada por `value`; `None` se não é uma célula
+    fn read_cell(&self, value: &Value) -> Option<Value> {
+        match value {
+            Value::Object(handle) => self.heap.cell_value(*handle).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Monta a classe: o protótipo recebe os métodos e aponta de volta para o construtor
+    fn create_class(&mut self, constructor: HandleId, methods: Vec<(Value, Value)>) {
+        let prototype = self.heap.alloc_object();
+        for (key, method) in methods {
+            self.heap.set_object_property(prototype, key.to_string(), method);
+        }
+        self.heap.set_object_property(prototype, "constructor".to_string(), Value::Function(constructor));
+        self.heap.set_function_prototype(constructor, prototype);
+    }
+
+    /// Objeto `prototype` de uma função do usuário, criado no primeiro uso
+    ///
+    /// Classes já recebem o protótipo em `create_class`; funções comuns ganham
+    /// um objeto com `constructor` apontando de volta para elas. Funções nativas
+    /// não têm protótipo.
+    fn function_prototype(&mut self, handle: HandleId) -> Option<HandleId> {
+        if !matches!(self.heap.get(handle), Some(HeapEntry::Function { .. })) {
+            return None;
+        }
+        if let Some(prototype) = self.heap.get_function_prototype(handle) {
+            return Some(prototype);
+        }
+        let prototype = self.heap.alloc_object();
+        self.heap.set_object_property(prototype, "constructor".to_string(), Value::Function(handle));
+        self.heap.set_function_prototype(handle, prototype);
+        Some(prototype)
+    }
+
+    /// Lança `RangeError` se mais um frame passaria do limite de profundidade
+    /// ou de pilha nativa
+    ///
+    /// Cada chamada aninha `execute` na pilha nativa: os limites evitam estourá-la.
+    fn call_depth_exceeded(&mut self, ip: usize, locals: &[Value]) -> bool {
+        let here = stack_address();
+        if self.stack.frames.is_empty() {
+            self.stack_base = here;
+        }
+        let too_deep = self.limits.max_call_depth.is_some_and(|depth| self.stack.frames.len() >= depth);
+        let too_much_stack = self.limits.max_stack_size.is_some_and(|size| self.stack_base.abs_diff(here) > size);
+        if !(too_deep || too_much_stack) {
+            return false;
+        }
+        let error = Value::String("RangeError: Maximum call stack size exceeded".to_string());
+        self.throw_value(error, ip, locals);
+        true
+    }
+
+    /// Executa a função do heap num novo frame; o valor retornado fica no topo da pilha
+    fn enter_function(&mut self, handle: HandleId, args: Vec<Value>, this: Option<Value>, ip: usize, locals: &[Value], constants: &[Value]) {
+        if self.call_depth_exceeded(ip, locals) {
+            return;
+        }
+        let (bytecode, closure_vars) = match self.heap.get(handle) {
+            Some(HeapEntry::Function { bytecode, closure_vars, .. }) => (bytecode.clone(), closure_vars.clone()),
+            _ => panic!("Handle de função inválido no heap"),
+        };
+        let mut new_frame = Frame::new();
+        new_frame.return_address = ip + 1;
+        new_frame.arg_count = args.len();
+        new_frame.arguments = args;
+        new_frame.closure_vars = closure_vars;
+        new_frame.function_handle = Some(handle);
+        new_frame.this_value = this;
+        self.frame.ip = ip;
+        self.frame.local_values = locals.to_vec();
+        self.stack.push_frame(self.frame.clone());
+        self.frame = new_frame;
+        self.execute(&bytecode, constants);
+        if let Some(prev_frame) = self.stack.pop_frame() {
+            self.frame = prev_frame;
+        }
+    }
+
+    /// Chama `func` com `this` e os argumentos; para funções nativas chamarem o script
+    ///
+    /// Script functions run in a frame above the current one, with the
+    /// constant pool of the running program. `Err` is the value the call
+    /// threw; it is not left in `exception`.
+    pub fn call_function(&mut self, func: &Value, this: Value, args: &[Value]) -> Result<Value, Value> {
+        let handle = match func {
+            Value::Function(handle) => *handle,
+            other => return Err(self.native_error(format!("TypeError: {} is not a function", other.to_string()))),
+        };
+        match self.heap.get(handle) {
+            Some(HeapEntry::NativeFunction { function, .. }) => {
+                let function = *function;
+                function(args).map_err(|message| self.native_error(message))
+            }
+            Some(HeapEntry::NativeMethod { method, .. }) => {
+                let method = *method;
+                method(self, this, args)
+            }
+            Some(HeapEntry::Function { .. }) => {
+                let height = self.stack.values.len();
+                let constants = self.constants.clone();
+                // `enter_function` salva o frame atual como está
+                let (ip, locals) = (self.frame.ip, std::mem::take(&mut self.frame.local_values));
+                self.enter_function(handle, args.to_vec(), Some(this), ip, &locals, &constants);
+                let result = match self.exception.take() {
+                    Some((error, _)) => Err(error),
+                    None => Ok(self.stack.pop().unwrap_or(Value::Undefined)),
+                };
+                self.stack.values.truncate(height);
+                result
+            }
+            _ => Err(self.native_error(format!("TypeError: {} is not a function", func.to_string()))),
+        }
+    }
+
+    /// Chama o método nativo na instrução `ip`; o resultado fica no topo da pilha
+    ///
+    /// Returns false when the method threw, with the exception recorded.
+    fn call_native_method(&mut self, method: NativeMethod, this: Value, args: &[Value], ip: usize, locals: &[Value]) -> bool {
+        // Os callbacks rodam em frames acima deste, que precisa estar salvo
+        self.frame.ip = ip;
+        self.frame.local_values = locals.to_vec();
+        match method(self, this, args) {
+            Ok(result) => {
+                self.stack.push(result);
+                true
+            }
+            Err(error) => {
+                self.throw_value(error, ip, locals);
+                false
+            }
+        }
+    }
+
+    /// Objeto com os métodos de `Array.prototype`, criado no primeiro uso
+    fn array_prototype(&mut self) -> HandleId {
+        if let Some(prototype) = self.array_prototype {
+            return prototype;
+        }
+        let prototype = self.heap.alloc_object();
+        for (name, method) in array::METHODS {
+            let handle = self.heap.alloc_native_method(name, *method);
+            self.heap.set_object_property(prototype, name.to_string(), Value::Function(handle));
+        }
+        self.array_prototype = Some(prototype);
+        prototype
+    }
+
+    /// Protótipo com `methods` compartilhado pelas instâncias de `name`, criado no primeiro uso
+    pub(crate) fn builtin_prototype(&mut self, name: &'static str, methods: &[(&str, NativeMethod)]) -> HandleId {
+        if let Some(prototype) = self.builtin_prototypes.get(name) {
+            return *prototype;
+        }
+        let prototype = self.heap.alloc_object();
+        for (method_name, method) in methods {
+            let handle = self.heap.alloc_native_method(method_name, *method);
+            self.heap.set_object_property(prototype, method_name.to_string(), Value::Function(handle));
+        }
+        self.builtin_prototypes.insert(name, prototype);
+        prototype
+    }
+
+    /// Executa o bytecode; no frame mais externo, para no primeiro breakpoint
     pub fn execute(&mut self, bytecode: &Bytecode, constants: &[Value]) {
-        let mut ip = 0;
-        let mut locals = vec![Value::Undefined; 16]; // 16 variáveis locais
-        let mut call_stack = Vec::new(); // Stack de chamadas para Return
-        
+        let mode = if self.stack.frames.is_empty() {
+            self.constants = Rc::from(constants);
+            self.debugger.paused = None;
+            self.frame.handlers.clear();
+            DebugMode::Run
+        } else {
+            DebugMode::Off
+        };
+        if mode == DebugMode::Off && self.frame_is_async() {
+            // A função roda até o primeiro await; quem chamou recebe a promise
+            let promise = self.heap.alloc_promise();
+            let height = self.stack.values.len();
+            self.run_async(bytecode, constants, promise, 0, new_locals(), height);
+            self.stack.push(Value::Object(promise));
+            return;
+        }
+        self.run_from(bytecode, constants, 0, new_locals(), mode);
+    }
+
+    /// Estado da promise, se o valor for uma
+    pub fn promise_state(&self, value: &Value) -> Option<&PromiseState> {
+        match value {
+            Value::Object(handle) => self.heap.get_promise_state(*handle),
+            _ => None,
+        }
+    }
+
+    /// Retoma as funções async cujos valores esperados já foram resolvidos
+    ///
+    /// Microtasks queued while draining run in the same call. Returns the
+    /// number of microtasks run.
+    pub fn run_microtasks(&mut self, constants: &[Value]) -> usize {
+        self.constants = Rc::from(constants);
+        let mut count = 0;
+        while let Some(Microtask { continuation, result }) = self.microtasks.pop_front() {
+            // A função retoma num frame acima do frame atual, com a pilha que tinha
+            let Continuation { bytecode, ip, locals, stack, mut frame, promise } = continuation;
+            let height = self.stack.values.len();
+            for (_, handler_height) in &mut frame.handlers {
+                *handler_height += height;
+            }
+            self.stack.push_frame(std::mem::replace(&mut self.frame, frame));
+            self.stack.values.extend(stack);
+            match result {
+                Ok(value) => self.stack.push(value),
+                Err(error) => self.throw_value(error, ip, &locals),
+            }
+            self.run_async(&bytecode, constants, promise, ip + 1, locals, height);
+            if let Some(prev_frame) = self.stack.pop_frame() {
+                self.frame = prev_frame;
+            }
+            count += 1;
+        }
+        count
+    }
+
+    /// Se o frame atual é de uma função async
+    fn frame_is_async(&self) -> bool {
+        matches!(
+            self.frame.function_handle.and_then(|handle| self.heap.get(handle)),
+            Some(HeapEntry::Function { is_async: true, .. })
+        )
+    }
+
+    /// Executa uma função async a partir de `ip` até o fim ou o próximo `Await`
+    ///
+    /// `height` is where the function's operands start on the stack. On
+    /// return or an uncaught throw the promise settles; on `Await` the frame
+    /// is saved in a continuation on the awaited value. Either way the
+    /// function's operands are removed from the stack.
+    fn run_async(&mut self, bytecode: &Bytecode, constants: &[Value], promise: HandleId, ip: usize, locals: Vec<Value>, height: usize) {
+        match self.run_frame(bytecode, constants, ip, locals, DebugMode::Off) {
+            FrameExit::Await { value, ip, locals } => {
+                let mut frame = self.frame.clone();
+                for (_, handler_height) in &mut frame.handlers {
+                    *handler_height -= height;
+                }
+                let stack = self.stack.values.split_off(height);
+                let continuation = Continuation { bytecode: bytecode.clone(), ip, locals, stack, frame, promise };
+                self.await_value(value, continuation);
+            }
+            // O gerador não emite TailCall em funções async
+            FrameExit::TailCall(_) => panic!("TailCall em função async"),
+            FrameExit::Done | FrameExit::Paused => {
+                let result = match self.exception.take() {
+                    Some((error, _)) => Err(error),
+                    None => Ok(self.stack.pop().unwrap_or(Value::Undefined)),
+                };
+                self.stack.values.truncate(height);
+                self.settle_promise(promise, result);
+            }
+        }
+    }
+
+    /// Agenda a continuação para quando o valor esperado estiver resolvido
+    fn await_value(&mut self, value: Value, continuation: Continuation) {
+        let result = match (&value, self.promise_state(&value).cloned()) {
+            (Value::Object(handle), Some(PromiseState::Pending)) => {
+                if let Some(HeapEntry::Promise(awaited)) = self.heap.get_mut(*handle) {
+                    awaited.reactions.push(Reaction::Resume(Box::new(continuation)));
+                }
+                return;
+            }
+            (_, Some(PromiseState::Fulfilled(value))) => Ok(value),
+            (_, Some(PromiseState::Rejected(error))) => Err(error),
+            // Valores que não são promises são esperados como já resolvidos
+            _ => Ok(value),
+        };
+        self.microtasks.push_back(Microtask { continuation, result });
+    }
+
+    /// Resolve ou rejeita a promise, agendando quem a espera
+    ///
+    /// Resolving with another promise adopts its result once it settles.
+    /// A promise that already settled is left as it is.
+    fn settle_promise(&mut self, promise: HandleId, result: Result<Value, Value>) {
+        if let Ok(Value::Object(handle)) = &result {
+            match self.heap.get_mut(*handle) {
+                Some(HeapEntry::Promise(adopted)) if *handle != promise => match adopted.state.clone() {
+                    PromiseState::Pending => {
+                        adopted.reactions.push(Reaction::Adopt(promise));
+                        return;
+                    }
+                    PromiseState::Fulfilled(value) => return self.settle_promise(promise, Ok(value)),
+                    PromiseState::Rejected(error) => return self.settle_promise(promise, Err(error)),
+                },
+                _ => {}
+            }
+        }
+        let reactions = match self.heap.get_mut(promise) {
+            Some(HeapEntry::Promise(settled)) if settled.state == PromiseState::Pending => {
+                settled.state = match &result {
+                    Ok(value) => PromiseState::Fulfilled(value.clone()),
+                    Err(error) => PromiseState::Rejected(error.clone()),
+                };
+                std::mem::take(&mut settled.reactions)
+            }
+            _ => return,
+        };
+        for reaction in reactions {
+            match reaction {
+                Reaction::Resume(continuation) => {
+                    self.microtasks.push_back(Microtask { continuation: *continuation, result: result.clone() });
+                }
+                Reaction::Adopt(adopting) => self.settle_promise(adopting, result.clone()),
+            }
+        }
+    }
+
+    fn run_from(&mut self, bytecode: &Bytecode, constants: &[Value], ip: usize, locals: Vec<Value>, mode: DebugMode) {
+        let mut exit = self.run_frame(bytecode, constants, ip, locals, mode);
+        // Chamadas em posição de cauda reaproveitam este frame em vez de recursar
+        while let FrameExit::TailCall(callee) = exit {
+            exit = self.run_frame(&callee, constants, 0, new_locals(), DebugMode::Off);
+        }
+    }
+
+    /// Executa o bytecode no frame atual a partir de `ip`
+    ///
+    /// Returns `TailCall` with the callee's bytecode when the frame ends in
+    /// a `TailCall`; the frame has then been reset to the callee and
+    /// `execute` runs it without growing the native stack. Unless `mode` is
+    /// `Off`, the frame may pause before an instruction, saving `ip` and
+    /// the locals in `debugger.paused`.
+    fn run_frame(&mut self, bytecode: &Bytecode, constants: &[Value], mut ip: usize, mut locals: Vec<Value>, mode: DebugMode) -> FrameExit {
+        let mut first = true;
+
         while ip < bytecode.instructions.len() {
+            // Uma alocação passou do limite do heap: lança se a coleta não liberar espaço
+            if self.exception.is_none() && self.heap.take_limit_exceeded() {
+                self.frame.local_values = locals.clone();
+                self.collect_garbage();
+                if self.heap.is_over_limit() {
+                    let limit = self.limits.max_heap_entries.unwrap_or_default();
+                    let error = Value::String(format!("RangeError: JavaScript heap out of memory (limit of {} entries)", limit));
+                    self.throw_value(error, ip, &locals);
+                }
+            }
+            // Exceção pendente: desvia para o catch do Try mais interno deste frame, ou sai dele
+            if self.exception.is_some() {
+                match self.frame.handlers.pop() {
+                    Some((catch, height)) => {
+                        let (value, _) = self.exception.take().unwrap();
+                        self.stack.values.truncate(height);
+                        self.stack.push(value);
+                        ip = catch;
+                    }
+                    None => break,
+                }
+            }
+            // Ao retomar, a instrução onde estava pausado é executada sem pausar de novo
+            if mode != DebugMode::Off && (mode == DebugMode::Run || !first) {
+                let reason = if mode == DebugMode::Step {
+                    Some(PauseReason::Step)
+                } else if self.debugger.breakpoints.contains(&ip) {
+                    Some(PauseReason::Breakpoint)
+                } else if bytecode.instructions[ip] == Instruction::Debugger && self.debugger.is_attached() {
+                    Some(PauseReason::DebuggerInstruction)
+                } else {
+                    None
+                };
+                if let Some(reason) = reason {
+                    if reason != PauseReason::Step {
+                        self.debugger.notify(reason, ip, &self.stack.values, &locals);
+                    }
+                    self.debugger.paused = Some((ip, locals));
+                    return FrameExit::Paused;
+                }
+            }
+            first = false;
             match &bytecode.instructions[ip] {
                 Instruction::PushConst(idx) => {
                     let value = constants.get(*idx).cloned().unwrap_or(Value::Undefined);
                     self.stack.push(value);
                 }
                 Instruction::Add => {
+                    // `+` depende dos tipos em tempo de execução; -, *, / são sempre numéricos
                     let b = self.stack.pop().unwrap();
                     let a = self.stack.pop().unwrap();
-                    // Implementar adição para diferentes tipos
-                    match (a.clone(), b.clone()) {
-                        (Value::Number(a), Value::Number(b)) => {
-                            self.stack.push(Value::Number(a + b));
-                        }
-                        _ => {
-                            // Para outros tipos, converter para string e concatenar
-                            let a_str = format!("{:?}", a);
-                            let b_str = format!("{:?}", b);
-                            self.stack.push(Value::String(format!("{}{}", a_str, b_str)));
-                        }
-                    }
+                    self.stack.push(a.add(&b));
                 }
                 Instruction::Sub => {
                     let b = self.stack.pop().unwrap();
                     let a = self.stack.pop().unwrap();
-                    if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                        self.stack.push(Value::Number(a - b));
-                    } else {
-                        self.stack.push(Value::Number(f64::NAN));
-                    }
+                    self.stack.push(Value::Number(a.to_number() - b.to_number()));
                 }
                 Instruction::Mul => {
                     let b = self.stack.pop().unwrap();
                     let a = self.stack.pop().unwrap();
-                    if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                        self.stack.push(Value::Number(a * b));
-                    } else {
-                        self.stack.push(Value::Number(f64::NAN));
-                    }
+                    self.stack.push(Value::Number(a.to_number() * b.to_number()));
                 }
                 Instruction::Div => {
                     let b = self.stack.pop().unwrap();
                     let a = self.stack.pop().unwrap();
-                    if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                        self.stack.push(Value::Number(a / b));
-                    } else {
-                        self.stack.push(Value::Number(f64::NAN));
+                    self.stack.push(Value::Number(a.to_number() / b.to_number()));
+                }
+                Instruction::Mod => {
+                    let b = self.stack.pop().unwrap();
+                    let a = self.stack.pop().unwrap();
+                    self.stack.push(Value::Number(a.to_number() % b.to_number()));
+                }
+                Instruction::Inc => {
+                    let value = self.stack.pop().unwrap();
+                    self.stack.push(Value::Number(value.to_number() + 1.0));
+                }
+                Instruction::Dec => {
+                    let value = self.stack.pop().unwrap();
+                    self.stack.push(Value::Number(value.to_number() - 1.0));
+                }
+                Instruction::Not => {
+                    let value = self.stack.pop().unwrap();
+                    self.stack.push(Value::Boolean(!value.to_boolean()));
+                }
+                Instruction::TypeOf => {
+                    let value = self.stack.pop().unwrap();
+                    self.stack.push(Value::String(value.typeof_().to_string()));
+                }
+                Instruction::InstanceOf => {
+                    let constructor = self.stack.pop().unwrap();
+                    let value = self.stack.pop().unwrap();
+                    let Value::Function(handle) = constructor else {
+                        let error = Value::String("TypeError: Right-hand side of 'instanceof' is not callable".to_string());
+                        self.throw_value(error, ip, &locals);
+                        continue;
+                    };
+                    // Sem Symbol.hasInstance na VM: procura o prototype do construtor na cadeia do objeto
+                    let mut found = false;
+                    if let (Value::Object(object), Some(prototype)) = (value, self.function_prototype(handle)) {
+                        let mut current = self.heap.get_prototype(object);
+                        while let Some(ancestor) = current {
+                            if ancestor == prototype {
+                                found = true;
+                                break;
+                            }
+                            current = self.heap.get_prototype(ancestor);
+                        }
                     }
+                    self.stack.push(Value::Boolean(found));
+                }
+                Instruction::In => {
+                    let target = self.stack.pop().unwrap();
+                    let key = self.stack.pop().unwrap();
+                    // Procura a chave como o GetProperty: no próprio valor e depois nos protótipos
+                    let found = match target {
+                        Value::Object(handle) => {
+                            let key = key.to_string();
+                            self.heap.has_object_property(handle, &key)
+                                || self.heap.get_inherited_property(handle, &key).is_some()
+                        }
+                        Value::Array(handle) => match array_index(&key) {
+                            Some(idx) => self.heap.get_array_element(handle, idx).is_some(),
+                            None => {
+                                let prototype = self.array_prototype();
+                                let key = key.to_string();
+                                key == "length" || self.heap.has_object_property(prototype, &key)
+                            }
+                        },
+                        Value::Function(handle) => {
+                            key.to_string() == "prototype" && self.function_prototype(handle).is_some()
+                        }
+                        other => {
+                            let error = Value::String(format!(
+                                "TypeError: Cannot use 'in' operator to search for '{}' in {}",
+                                key.to_string(),
+                                other.to_string()
+                            ));
+                            self.throw_value(error, ip, &locals);
+                            continue;
+                        }
+                    };
+                    self.stack.push(Value::Boolean(found));
+                }
+                Instruction::StrictEq => {
+                    let b = self.stack.pop().unwrap();
+                    let a = self.stack.pop().unwrap();
+                    self.stack.push(Value::Boolean(a == b));
+                }
+                Instruction::StrictNe => {
+                    let b = self.stack.pop().unwrap();
+                    let a = self.stack.pop().unwrap();
+                    self.stack.push(Value::Boolean(a != b));
                 }
                 Instruction::Eq => {
                     let b = self.stack.pop().unwrap();
@@ -95,38 +872,22 @@ impl Executor {
                 Instruction::Lt => {
                     let b = self.stack.pop().unwrap();
                     let a = self.stack.pop().unwrap();
-                    if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                        self.stack.push(Value::Boolean(a < b));
-                    } else {
-                        self.stack.push(Value::Boolean(false));
-                    }
+                    self.stack.push(Value::Boolean(matches!(a.compare(&b), Some(Ordering::Less))));
                 }
                 Instruction::Gt => {
                     let b = self.stack.pop().unwrap();
                     let a = self.stack.pop().unwrap();
-                    if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                        self.stack.push(Value::Boolean(a > b));
-                    } else {
-                        self.stack.push(Value::Boolean(false));
-                    }
+                    self.stack.push(Value::Boolean(matches!(a.compare(&b), Some(Ordering::Greater))));
                 }
                 Instruction::Le => {
                     let b = self.stack.pop().unwrap();
                     let a = self.stack.pop().unwrap();
-                    if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                        self.stack.push(Value::Boolean(a <= b));
-                    } else {
-                        self.stack.push(Value::Boolean(false));
-                    }
+                    self.stack.push(Value::Boolean(matches!(a.compare(&b), Some(Ordering::Less | Ordering::Equal))));
                 }
                 Instruction::Ge => {
                     let b = self.stack.pop().unwrap();
                     let a = self.stack.pop().unwrap();
-                    if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                        self.stack.push(Value::Boolean(a >= b));
-                    } else {
-                        self.stack.push(Value::Boolean(false));
-                    }
+                    self.stack.push(Value::Boolean(matches!(a.compare(&b), Some(Ordering::Greater | Ordering::Equal))));
                 }
                 Instruction::Jump(target) => {
                     ip = *target;
@@ -134,14 +895,28 @@ impl Executor {
                 }
                 Instruction::JumpIfTrue(target) => {
                     let cond = self.stack.pop().unwrap();
-                    if cond.as_bool().unwrap_or(false) {
+                    if cond.to_boolean() {
                         ip = *target;
                         continue;
                     }
                 }
                 Instruction::JumpIfFalse(target) => {
                     let cond = self.stack.pop().unwrap();
-                    if !cond.as_bool().unwrap_or(false) {
+                    if !cond.to_boolean() {
+                        ip = *target;
+                        continue;
+                    }
+                }
+                Instruction::JumpIfNotNullish(target) => {
+                    let value = self.stack.pop().unwrap();
+                    if !matches!(value, Value::Null | Value::Undefined) {
+                        ip = *target;
+                        continue;
+                    }
+                }
+                Instruction::JumpIfNullish(target) => {
+                    let value = self.stack.pop().unwrap();
+                    if matches!(value, Value::Null | Value::Undefined) {
                         ip = *target;
                         continue;
                     }
@@ -157,8 +932,17 @@ impl Executor {
                     }
                 }
                 Instruction::LoadGlobal(idx) => {
-                    // Implementar acesso a variáveis globais
-                    self.stack.push(self.globals.get(*idx).cloned().unwrap_or(Value::Undefined));
+                    let value = match self.globals.get(*idx) {
+                        // Os construtores nativos ocupam os primeiros slots e são criados no primeiro acesso
+                        Some(Value::Undefined) if *idx < collection::CONSTRUCTORS.len() => {
+                            let (name, constructor) = collection::CONSTRUCTORS[*idx];
+                            let handle = self.heap.alloc_native_constructor(name, constructor);
+                            self.globals[*idx] = Value::Function(handle);
+                            Value::Function(handle)
+                        }
+                        value => value.cloned().unwrap_or(Value::Undefined),
+                    };
+                    self.stack.push(value);
                 }
                 Instruction::StoreGlobal(idx) => {
                     // Implementar armazenamento em variáveis globais
@@ -196,6 +980,33 @@ impl Executor {
                     };
                     
                     if let Value::Function(handle) = func_value {
+                        // Funções nativas retornam direto na pilha, sem criar frame
+                        if let Some(HeapEntry::NativeFunction { function, .. }) = self.heap.get(handle) {
+                            let function = *function;
+                            let start = self.stack.values.len().saturating_sub(*argc);
+                            let args = self.stack.values.split_off(start);
+                            match function(&args) {
+                                Ok(result) => self.stack.push(result),
+                                Err(message) => {
+                                    let error = self.native_error(message);
+                                    self.throw_value(error, ip, &locals);
+                                    continue;
+                                }
+                            }
+                            ip += 1;
+                            continue;
+                        }
+                        if let Some(HeapEntry::NativeMethod { method, .. }) = self.heap.get(handle) {
+                            // Sem receptor, o `this` é undefined
+                            let method = *method;
+                            let start = self.stack.values.len().saturating_sub(*argc);
+                            let args = self.stack.values.split_off(start);
+                            if !self.call_native_method(method, Value::Undefined, &args, ip, &locals) {
+                                continue;
+                            }
+                            ip += 1;
+                            continue;
+                        }
                         // Extrair dados necessários antes de chamar self.execute
                         let (bytecode, closure_vars) = if let Some(HeapEntry::Function { bytecode, closure_vars, .. }) = self.heap.get(handle) {
                             (bytecode.clone(), closure_vars.clone())
@@ -208,9 +1019,11 @@ impl Executor {
                             args.push(self.stack.pop().unwrap());
                         }
                         args.reverse(); // Ordem correta
-                        // Verificar se há um valor de this na stack (opcional)
-                        // Se não há mais valores na stack, this_value será None
-                        let this_value = self.stack.pop();
+                        // Chamada sem receptor: o `this` é undefined e a pilha abaixo é de quem chamou
+                        let this_value = None;
+                        if self.call_depth_exceeded(ip, &locals) {
+                            continue;
+                        }
                         // Criar novo frame
                         let mut new_frame = Frame::new();
                         new_frame.return_address = ip + 1;
@@ -220,6 +1033,8 @@ impl Executor {
                         new_frame.function_handle = Some(handle); // Passar handle da função
                         new_frame.this_value = this_value; // Passar valor de this (pode ser None)
                         // Empilhar o frame atual e usar o novo
+                        self.frame.ip = ip;
+                        self.frame.local_values = locals.clone();
                         self.stack.push_frame(self.frame.clone());
                         self.frame = new_frame;
                         // Executar o bytecode da função
@@ -228,13 +1043,11 @@ impl Executor {
                         if let Some(prev_frame) = self.stack.pop_frame() {
                             self.frame = prev_frame;
                         }
-                        // Restaurar endereço de retorno
-                        if let Some(return_ip) = call_stack.pop() {
-                            ip = return_ip;
+                        // Propagar exceção lançada dentro da função
+                        if self.exception.is_some() {
                             continue;
-                        } else {
-                            break;
                         }
+                        // O valor retornado fica no topo da pilha; o chamador continua
                     } else {
                         panic!("Topo da stack não é uma função ao executar Call");
                     }
@@ -266,6 +1079,9 @@ impl Executor {
                         // Verificar se há um valor de this na stack (opcional)
                         let this_value = self.stack.pop();
                         println!("DEBUG: CallFunction - Argumentos: {:?}, This: {:?}", args, this_value);
+                        if self.call_depth_exceeded(ip, &locals) {
+                            continue;
+                        }
                         
                         // Criar novo frame
                         let mut new_frame = Frame::new();
@@ -277,6 +1093,8 @@ impl Executor {
                         new_frame.this_value = this_value;
                         
                         // Empilhar o frame atual e usar o novo
+                        self.frame.ip = ip;
+                        self.frame.local_values = locals.clone();
                         self.stack.push_frame(self.frame.clone());
                         self.frame = new_frame;
                         
@@ -287,41 +1105,173 @@ impl Executor {
                         if let Some(prev_frame) = self.stack.pop_frame() {
                             self.frame = prev_frame;
                         }
-                        
-                        // Restaurar endereço de retorno
-                        if let Some(return_ip) = call_stack.pop() {
-                            ip = return_ip;
+                        // Propagar exceção lançada dentro da função
+                        if self.exception.is_some() {
                             continue;
-                        } else {
-                            break;
                         }
                     } else {
                         panic!("Handle de função inválido no heap: {:?}", handle);
                     }
                 }
                 Instruction::Return => {
-                    // Recuperar valor de retorno (se houver)
-                    let return_value = self.stack.pop();
-                    
-                    // Restaurar frame anterior
-                    if let Some(prev_frame) = self.stack.pop_frame() {
-                        self.frame = prev_frame;
+                    // O valor retornado fica no topo da pilha; quem chamou restaura o frame anterior
+                    break;
+                }
+                Instruction::CallMethod(argc) => {
+                    let func_value = self.stack.pop().unwrap();
+                    let start = self.stack.values.len().saturating_sub(*argc);
+                    let args = self.stack.values.split_off(start);
+                    let this = self.stack.pop().unwrap();
+                    match func_value {
+                        Value::Function(handle) => match self.heap.get(handle) {
+                            // Funções nativas ignoram o receptor
+                            Some(HeapEntry::NativeFunction { function, .. }) => match function(&args) {
+                                Ok(result) => self.stack.push(result),
+                                Err(message) => {
+                                    let error = self.native_error(message);
+                                    self.throw_value(error, ip, &locals);
+                                    continue;
+                                }
+                            },
+                            Some(HeapEntry::NativeMethod { method, .. }) => {
+                                let method = *method;
+                                if !self.call_native_method(method, this, &args, ip, &locals) {
+                                    continue;
+                                }
+                            }
+                            _ => {
+                                self.enter_function(handle, args, Some(this), ip, &locals, constants);
+                                if self.exception.is_some() {
+                                    continue;
+                                }
+                            }
+                        },
+                        other => {
+                            let error = Value::String(format!("TypeError: {} is not a function", other.to_string()));
+                            self.throw_value(error, ip, &locals);
+                            continue;
+                        }
                     }
-                    
-                    // Empilhar o valor retornado na stack do chamador
-                    if let Some(value) = return_value {
-                        self.stack.push(value);
+                }
+                Instruction::TailCall(argc) => {
+                    let func_value = self.stack.pop().unwrap();
+                    let start = self.stack.values.len().saturating_sub(*argc);
+                    let args = self.stack.values.split_off(start);
+                    match func_value {
+                        Value::Function(handle) => match self.heap.get(handle).cloned() {
+                            // Funções nativas não criam frame: o resultado é o retorno deste frame
+                            Some(HeapEntry::NativeFunction { function, .. }) => match function(&args) {
+                                Ok(result) => {
+                                    self.stack.push(result);
+                                    break;
+                                }
+                                Err(message) => {
+                                    let error = self.native_error(message);
+                                    self.throw_value(error, ip, &locals);
+                                    continue;
+                                }
+                            },
+                            Some(HeapEntry::NativeMethod { method, .. }) => {
+                                if !self.call_native_method(method, Value::Undefined, &args, ip, &locals) {
+                                    continue;
+                                }
+                                break;
+                            }
+                            Some(HeapEntry::Function { bytecode, closure_vars, .. }) => {
+                                // Reinicia o frame atual com a função chamada; o endereço de retorno é mantido
+                                self.frame.arg_count = args.len();
+                                self.frame.arguments = args;
+                                self.frame.closure_vars = closure_vars;
+                                self.frame.function_handle = Some(handle);
+                                self.frame.this_value = None;
+                                self.frame.ip = 0;
+                                self.frame.local_values = Vec::new();
+                                self.frame.handlers.clear();
+                                return FrameExit::TailCall(bytecode);
+                            }
+                            _ => panic!("Handle de função inválido no heap"),
+                        },
+                        other => {
+                            let error = Value::String(format!("TypeError: {} is not a function", other.to_string()));
+                            self.throw_value(error, ip, &locals);
+                            continue;
+                        }
                     }
-                    
-                    // Restaurar endereço de retorno
-                    if let Some(return_ip) = call_stack.pop() {
-                        ip = return_ip;
-                        continue;
-                    } else {
-                        // Se não há call stack, terminar execução
-                        break;
+                }
+                Instruction::CreateFunction(index) => {
+                    let function = self.create_function(bytecode, *index, &locals);
+                    self.stack.push(function);
+                }
+                Instruction::CreateClass(method_count) => {
+                    let mut methods = Vec::with_capacity(*method_count);
+                    for _ in 0..*method_count {
+                        let method = self.stack.pop().unwrap();
+                        let key = self.stack.pop().unwrap();
+                        methods.push((key, method));
+                    }
+                    methods.reverse(); // Ordem de declaração
+                    match self.stack.values.last() {
+                        Some(Value::Function(constructor)) => {
+                            let constructor = *constructor;
+                            self.create_class(constructor, methods);
+                        }
+                        _ => panic!("CreateClass sem construtor na pilha"),
                     }
                 }
+                Instruction::New(argc) => {
+                    let constructor = self.stack.pop().unwrap();
+                    let start = self.stack.values.len().saturating_sub(*argc);
+                    let args = self.stack.values.split_off(start);
+                    match constructor {
+                        Value::Function(handle) => match self.heap.get(handle) {
+                            Some(HeapEntry::NativeFunction { function, .. }) => match function(&args) {
+                                Ok(result) => self.stack.push(result),
+                                Err(message) => {
+                                    let error = self.native_error(message);
+                                    self.throw_value(error, ip, &locals);
+                                    continue;
+                                }
+                            },
+                            Some(HeapEntry::NativeMethod { method, constructor: true, .. }) => {
+                                let method = *method;
+                                if !self.call_native_method(method, Value::Undefined, &args, ip, &locals) {
+                                    continue;
+                                }
+                            }
+                            Some(HeapEntry::NativeMethod { name, .. }) => {
+                                let error = Value::String(format!("TypeError: {} is not a constructor", name));
+                                self.throw_value(error, ip, &locals);
+                                continue;
+                            }
+                            _ => {
+                                let object = self.heap.alloc_object();
+                                if let Some(prototype) = self.function_prototype(handle) {
+                                    self.heap.set_prototype(object, prototype);
+                                }
+                                self.enter_function(handle, args, Some(Value::Object(object)), ip, &locals, constants);
+                                if self.exception.is_some() {
+                                    continue;
+                                }
+                                // Um construtor que retorna um objeto substitui a instância criada
+                                let result = self.stack.pop().unwrap_or(Value::Undefined);
+                                if result.is_primitive() {
+                                    self.stack.push(Value::Object(object));
+                                } else {
+                                    self.stack.push(result);
+                                }
+                            }
+                        },
+                        other => {
+                            let error = Value::String(format!("TypeError: {} is not a constructor", other.to_string()));
+                            self.throw_value(error, ip, &locals);
+                            continue;
+                        }
+                    }
+                }
+                Instruction::PushNull => self.stack.push(Value::Null),
+                Instruction::PushUndefined => self.stack.push(Value::Undefined),
+                Instruction::PushTrue => self.stack.push(Value::Boolean(true)),
+                Instruction::PushFalse => self.stack.push(Value::Boolean(false)),
                 Instruction::Pop => {
                     self.stack.pop();
                 }
@@ -330,39 +1280,289 @@ impl Executor {
                         self.stack.push(top);
                     }
                 }
+                Instruction::GcHint => {
+                    self.frame.local_values = locals.clone();
+                    self.collect_garbage();
+                }
                 Instruction::NewObject => {
                     let handle = self.heap.alloc_object();
                     self.stack.push(Value::Object(handle));
                 }
-                Instruction::NewArray(_size) => {
-                    let handle = self.heap.alloc_array();
+                Instruction::NewArray(size) => {
+                    // Os elementos estão na pilha, o primeiro mais ao fundo: um único split_off, sem reordenar
+                    let start = match self.stack.values.len().checked_sub(*size) {
+                        Some(start) => start,
+                        None => panic!("Stack com menos de {} elementos ao executar NewArray", size),
+                    };
+                    let elements = self.stack.values.split_off(start);
+                    let handle = self.heap.alloc_entry(HeapEntry::Array(elements.into()));
                     self.stack.push(Value::Array(handle));
                 }
+                Instruction::Spread => {
+                    // Espalha o topo da pilha no array/objeto logo abaixo (que permanece na pilha)
+                    let source = self.stack.values.last().cloned().unwrap();
+                    let len = self.stack.values.len();
+                    match self.stack.values.get(len.wrapping_sub(2)).cloned() {
+                        Some(Value::Array(target)) => {
+                            // Um iterador chama `next` no script; a origem fica na pilha até o fim
+                            self.frame.ip = ip;
+                            self.frame.local_values = locals.to_vec();
+                            let spread = iterator::for_each_value(self, &source, |executor, element| {
+                                executor.heap.push_array_element(target, element);
+                            });
+                            self.stack.pop();
+                            if let Err(error) = spread {
+                                self.throw_value(error, ip, &locals);
+                                continue;
+                            }
+                        }
+                        Some(Value::Object(target)) => {
+                            self.stack.pop();
+                            for (key, value) in self.own_properties(&source) {
+                                self.heap.set_object_property(target, key, value);
+                            }
+                        }
+                        _ => panic!("Spread sem array ou objeto de destino na pilha"),
+                    }
+                }
+                Instruction::RestElements(start) => {
+                    // Pula os `start` primeiros valores e junta o resto num array novo
+                    let source = self.stack.values.last().cloned().unwrap();
+                    let rest = self.heap.alloc_array();
+                    self.stack.push(Value::Array(rest));
+                    self.frame.ip = ip;
+                    self.frame.local_values = locals.to_vec();
+                    let mut skipped = 0;
+                    let collected = iterator::for_each_value(self, &source, |executor, element| {
+                        if skipped < *start {
+                            skipped += 1;
+                        } else {
+                            executor.heap.push_array_element(rest, element);
+                        }
+                    });
+                    self.stack.pop();
+                    self.stack.pop();
+                    match collected {
+                        Ok(()) => self.stack.push(Value::Array(rest)),
+                        Err(error) => {
+                            self.throw_value(error, ip, &locals);
+                            continue;
+                        }
+                    }
+                }
+                Instruction::GetIterator => {
+                    let iterable = self.stack.pop().unwrap_or(Value::Undefined);
+                    // `Symbol.iterator` pode ser uma função do script
+                    self.frame.ip = ip;
+                    self.frame.local_values = locals.to_vec();
+                    match iterator::open(self, &iterable) {
+                        Ok(iterator) => self.stack.push(iterator),
+                        Err(error) => {
+                            self.throw_value(error, ip, &locals);
+                            continue;
+                        }
+                    }
+                }
+                Instruction::IteratorNext => {
+                    let index = self.stack.pop().map_or(0.0, |index| index.to_number()) as usize;
+                    let iterator = self.stack.pop().unwrap_or(Value::Undefined);
+                    self.frame.ip = ip;
+                    self.frame.local_values = locals.to_vec();
+                    match iterator::step(self, &iterator, index) {
+                        Ok(Some(value)) => {
+                            self.stack.push(value);
+                            self.stack.push(Value::Boolean(false));
+                        }
+                        Ok(None) => {
+                            self.stack.push(Value::Undefined);
+                            self.stack.push(Value::Boolean(true));
+                        }
+                        Err(error) => {
+                            self.throw_value(error, ip, &locals);
+                            continue;
+                        }
+                    }
+                }
+                Instruction::CloseIterator => {
+                    let iterator = self.stack.pop().unwrap_or(Value::Undefined);
+                    self.frame.ip = ip;
+                    self.frame.local_values = locals.to_vec();
+                    if let Err(error) = iterator::close(self, &iterator) {
+                        self.throw_value(error, ip, &locals);
+                        continue;
+                    }
+                }
+                Instruction::EnumerateKeys => {
+                    let value = self.stack.pop().unwrap_or(Value::Undefined);
+                    let keys = self.for_in_keys(&value).into_iter().map(Value::String).collect::<Vec<_>>().into();
+                    let keys = self.heap.alloc_entry(HeapEntry::Array(keys));
+                    self.stack.push(Value::Array(keys));
+                }
+                Instruction::RestProperties(count) => {
+                    // As chaves já desestruturadas ficam de fora da cópia
+                    let start = self.stack.values.len().saturating_sub(*count);
+                    let excluded: Vec<String> = self.stack.values.split_off(start).iter().map(Value::to_string).collect();
+                    let source = self.stack.pop().unwrap();
+                    if matches!(source, Value::Null | Value::Undefined) {
+                        let error = Value::String(format!("TypeError: Cannot destructure '{0}' as it is {0}.", source.to_string()));
+                        self.throw_value(error, ip, &locals);
+                        continue;
+                    }
+                    let rest = self.heap.alloc_object();
+                    for (key, value) in self.own_properties(&source) {
+                        if !excluded.contains(&key) {
+                            self.heap.set_object_property(rest, key, value);
+                        }
+                    }
+                    self.stack.push(Value::Object(rest));
+                }
+                Instruction::CallWithSpread => {
+                    let func_value = self.stack.pop().unwrap();
+                    let args = match self.stack.pop().unwrap() {
+                        Value::Array(handle) => match self.heap.get(handle) {
+                            Some(HeapEntry::Array(elements)) => elements.to_vec(),
+                            _ => Vec::new(),
+                        },
+                        _ => panic!("CallWithSpread sem array de argumentos na pilha"),
+                    };
+                    match func_value {
+                        Value::Function(handle) => match self.heap.get(handle).cloned() {
+                            Some(HeapEntry::NativeFunction { function, .. }) => match function(&args) {
+                                Ok(result) => self.stack.push(result),
+                                Err(message) => {
+                                    let error = self.native_error(message);
+                                    self.throw_value(error, ip, &locals);
+                                    continue;
+                                }
+                            },
+                            Some(HeapEntry::NativeMethod { method, .. }) => {
+                                if !self.call_native_method(method, Value::Undefined, &args, ip, &locals) {
+                                    continue;
+                                }
+                            }
+                            Some(HeapEntry::Function { bytecode, closure_vars, .. }) => {
+                                if self.call_depth_exceeded(ip, &locals) {
+                                    continue;
+                                }
+                                let mut new_frame = Frame::new();
+                                new_frame.return_address = ip + 1;
+                                new_frame.arg_count = args.len();
+                                new_frame.arguments = args;
+                                new_frame.closure_vars = closure_vars;
+                                new_frame.function_handle = Some(handle);
+                                self.frame.ip = ip;
+                                self.frame.local_values = locals.clone();
+                                self.stack.push_frame(self.frame.clone());
+                                self.frame = new_frame;
+                                self.execute(&bytecode, constants);
+                                if let Some(prev_frame) = self.stack.pop_frame() {
+                                    self.frame = prev_frame;
+                                }
+                                if self.exception.is_some() {
+                                    continue;
+                                }
+                            }
+                            _ => panic!("Handle de função inválido no heap"),
+                        },
+                        other => {
+                            let error = Value::String(format!("TypeError: {} is not a function", other.to_string()));
+                            self.throw_value(error, ip, &locals);
+                            continue;
+                        }
+                    }
+                }
                 Instruction::SetProperty => {
+                    // Objetos e arrays vivem no heap: todas as cópias do handle veem a escrita
                     let value = self.stack.pop().unwrap();
                     let key = self.stack.pop().unwrap();
                     let obj = self.stack.pop().unwrap();
-                    if let (Value::Object(handle), Value::String(key)) = (obj, key) {
-                        self.heap.set_object_property(handle, key, value);
+                    match obj {
+                        Value::Object(handle) => self.store_property(handle, key.to_string(), value, ip),
+                        Value::Array(handle) => match array_index(&key) {
+                            Some(idx) => self.heap.set_array_element(handle, idx, value),
+                            None if key.to_string() == "length" => {
+                                let length = value.to_number();
+                                if !(0.0..=u32::MAX as f64).contains(&length) || length.fract() != 0.0 {
+                                    let error = Value::String("RangeError: Invalid array length".to_string());
+                                    self.throw_value(error, ip, &locals);
+                                    continue;
+                                }
+                                // Crescer só acrescenta buracos, sem alocá-los
+                                if let Some(HeapEntry::Array(elements)) = self.heap.get_mut(handle) {
+                                    elements.set_len(length as usize);
+                                }
+                            }
+                            None => {}
+                        },
+                        _ => {}
                     }
                 }
                 Instruction::GetProperty => {
                     let key = self.stack.pop().unwrap();
                     let obj = self.stack.pop().unwrap();
-                    if let (Value::Object(handle), Value::String(key)) = (obj, key) {
-                        if let Some(val) = self.heap.get_object_property(handle, &key) {
-                            self.stack.push(val.clone());
-                        } else {
-                            self.stack.push(Value::Undefined);
+                    let value = match obj {
+                        Value::Object(handle) => {
+                            let key = key.to_string();
+                            match self.load_property(handle, &key, ip) {
+                                Some(value) => Some(value),
+                                None => match collection::size(&self.heap, handle) {
+                                    Some(size) if key == "size" => Some(Value::Number(size as f64)),
+                                    _ => self.heap.get_inherited_property(handle, &key).cloned(),
+                                },
+                            }
                         }
-                    } else {
-                        self.stack.push(Value::Undefined);
+                        Value::Function(handle) if key.to_string() == "prototype" => {
+                            self.function_prototype(handle).map(Value::Object)
+                        }
+                        Value::Array(handle) => match array_index(&key) {
+                            Some(idx) => self.heap.get_array_element(handle, idx).cloned(),
+                            None if key.to_string() == "length" => match self.heap.get(handle) {
+                                Some(HeapEntry::Array(elements)) => Some(Value::Number(elements.len() as f64)),
+                                _ => None,
+                            },
+                            None => {
+                                let prototype = self.array_prototype();
+                                self.heap.get_object_property(prototype, &key.to_string()).cloned()
+                            }
+                        },
+                        _ => None,
+                    };
+                    self.stack.push(value.unwrap_or(Value::Undefined));
+                }
+                Instruction::DeleteProperty => {
+                    let key = self.stack.pop().unwrap();
+                    let obj = self.stack.pop().unwrap();
+                    match obj {
+                        Value::Object(handle) => {
+                            // Propriedades do heap são sempre configuráveis
+                            self.heap.remove_object_property(handle, &key.to_string());
+                        }
+                        Value::Array(handle) => {
+                            // O elemento vira um buraco (lido como undefined); o length não muda
+                            if let Some(idx) = array_index(&key) {
+                                if let Some(HeapEntry::Array(elements)) = self.heap.get_mut(handle) {
+                                    elements.remove(idx);
+                                }
+                            }
+                        }
+                        _ => {}
                     }
+                    self.stack.push(Value::Boolean(true));
+                }
+                Instruction::Delete => {
+                    // `delete` de algo que não é referência: descarta o valor e retorna true
+                    self.stack.pop();
+                    self.stack.push(Value::Boolean(true));
                 }
                 Instruction::LoadArg(idx) => {
                     let value = self.frame.arguments.get(*idx).cloned().unwrap_or(Value::Undefined);
                     self.stack.push(value);
                 }
+                Instruction::LoadRestArgs(start) => {
+                    let rest = self.frame.arguments.get(*start..).unwrap_or_default().to_vec();
+                    let handle = self.heap.alloc_entry(HeapEntry::Array(rest.into()));
+                    self.stack.push(Value::Array(handle));
+                }
                 Instruction::LoadThisFunction => {
                     // Empilha o handle da função atual
                     if let Some(func_handle) = self.frame.function_handle {
@@ -381,15 +1581,87 @@ impl Executor {
                 }
                 Instruction::LoadClosureVar(name) => {
                     // Empilha uma variável capturada do escopo externo
-                    if let Some(value) = self.frame.closure_vars.get(name) {
-                        self.stack.push(value.clone());
-                    } else {
-                        self.stack.push(Value::Undefined);
+                    let value = self.frame.closure_vars.get(name).cloned().unwrap_or(Value::Undefined);
+                    let value = self.read_cell(&value).unwrap_or(value);
+                    self.stack.push(value);
+                }
+                Instruction::StoreClosureVar(name) => {
+                    let value = self.stack.pop().unwrap_or(Value::Undefined);
+                    match self.frame.closure_vars.get(name) {
+                        Some(Value::Object(cell)) if self.heap.cell_value(*cell).is_some() => {
+                            self.heap.set_cell_value(*cell, value);
+                        }
+                        _ => {
+                            self.frame.closure_vars.insert(name.clone(), value);
+                        }
+                    }
+                }
+                Instruction::NewCell(idx) => {
+                    let cell = self.heap.alloc_cell(Value::Undefined);
+                    if let Some(slot) = locals.get_mut(*idx) {
+                        *slot = Value::Object(cell);
+                    }
+                }
+                Instruction::LoadCell(idx) => {
+                    let value = locals.get(*idx).and_then(|cell| self.read_cell(cell)).unwrap_or(Value::Undefined);
+                    self.stack.push(value);
+                }
+                Instruction::StoreCell(idx) => {
+                    let value = self.stack.pop().unwrap_or(Value::Undefined);
+                    if let Some(Value::Object(cell)) = locals.get(*idx) {
+                        self.heap.set_cell_value(*cell, value);
+                    }
+                }
+                Instruction::Throw => {
+                    let value = self.stack.pop().unwrap_or(Value::Undefined);
+                    self.throw_value(value, ip, &locals);
+                    continue;
+                }
+                Instruction::Await => {
+                    let value = self.stack.pop().unwrap_or(Value::Undefined);
+                    if self.frame_is_async() {
+                        return FrameExit::Await { value, ip, locals };
+                    }
+                    // Fora de funções async o valor segue adiante, sem suspender
+                    self.stack.push(value);
+                }
+                Instruction::Try(catch) => {
+                    self.frame.handlers.push((*catch, self.stack.values.len()));
+                }
+                Instruction::EndTry => {
+                    self.frame.handlers.pop();
+                }
+                Instruction::Debugger => {
+                    // Em frames aninhados não dá para pausar: só avisa o depurador
+                    if mode == DebugMode::Off {
+                        self.debugger.notify(PauseReason::DebuggerInstruction, ip, &self.stack.values, &locals);
                     }
                 }
                 _ => todo!("Instrução não implementada ainda"),
             }
             ip += 1;
         }
+        FrameExit::Done
     }
-} 
+}
+
+/// Endereço do frame nativo atual, para medir a pilha usada pelas chamadas aninhadas
+#[inline(never)]
+fn stack_address() -> usize {
+    let marker = 0u8;
+    std::ptr::addr_of!(marker) as usize
+}
+
+fn new_locals() -> Vec<Value> {
+    vec![Value::Undefined; 16] // 16 variáveis locais
+}
+
+/// Índice de array representado por `key`: um inteiro abaixo de 2^32 - 1,
+/// escrito na forma canônica se for uma string
+fn array_index(key: &Value) -> Option<usize> {
+    match key {
+        Value::Number(n) if (0.0..u32::MAX as f64).contains(n) && n.fract() == 0.0 => Some(*n as usize),
+        Value::String(s) => object::array_index(s).map(|index| index as usize),
+        _ => None,
+    }
+}
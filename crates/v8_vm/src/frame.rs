@@ -14,6 +14,17 @@ pub struct Frame {
     pub closure_vars: HashMap<String, Value>,
     pub function_handle: Option<HandleId>,
     pub this_value: Option<Value>, // Valor de this da função atual
+    pub ip: usize,                 // Instrução atual (atualizada ao chamar/lançar)
+    pub local_values: Vec<Value>,  // Cópia das variáveis locais (atualizada ao chamar/lançar)
+    pub handlers: Vec<(usize, usize)>, // Handlers de `Try` ativos: (destino do catch, altura da pilha)
+}
+
+/// Informação de um frame para stack traces
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackFrameInfo {
+    pub function_name: String,
+    pub ip: usize,
+    pub locals: Vec<(String, Value)>,
 }
 
 impl Frame {
@@ -27,6 +38,9 @@ impl Frame {
             closure_vars: HashMap::new(),
             function_handle: None,
             this_value: None,
+            ip: 0,
+            local_values: Vec::new(),
+            handlers: Vec::new(),
         }
     }
     
@@ -40,6 +54,9 @@ impl Frame {
             closure_vars: HashMap::new(),
             function_handle: None,
             this_value: None,
+            ip: 0,
+            local_values: Vec::new(),
+            handlers: Vec::new(),
         }
     }
 } 
\ No newline at end of file
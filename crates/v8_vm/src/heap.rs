@@ -1,11 +1,16 @@
 //! Heap for the V8-Rust VM
 
 use crate::bytecode::Bytecode;
+use crate::executor::Executor;
+use crate::inline_cache::InlineCache;
+use crate::promise::{Promise, PromiseState};
+use crate::shape::PropertyMap;
 use crate::value::Value;
 use std::collections::HashMap;
 use std::ops::Deref;
+use v8_runtime::elements::Elements;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
 pub struct HandleId(usize);
 
@@ -35,38 +40,175 @@ impl PartialEq<usize> for HandleId {
     }
 }
 
+/// Função nativa (implementada em Rust) chamável pela VM
+pub type NativeFunction = fn(&[Value]) -> Result<Value, String>;
+
+/// Método nativo: recebe o executor, para chamar funções do script, e o `this`
+///
+/// `Err` carries the thrown value, so an exception from a callback
+/// propagates unchanged.
+pub type NativeMethod = fn(&mut Executor, Value, &[Value]) -> Result<Value, Value>;
+
 #[derive(Debug, Clone)]
 pub enum HeapEntry {
-    Object(HashMap<String, Value>),
-    Array(Vec<Value>),
+    Object(PropertyMap),
+    Array(Elements<Value>), // Elementos com buracos; índices distantes ficam esparsos
     Function {
         bytecode: Bytecode, // Bytecode real da função
         arg_count: usize,
         local_count: usize,
         closure_vars: HashMap<String, Value>,
+        name: String,             // Nome da função (para stack traces)
+        local_names: Vec<String>, // Nomes das variáveis locais, por índice
+        inline_cache: InlineCache, // Shapes vistos pelos acessos a propriedades da função
+        prototype: Option<HandleId>, // Objeto `prototype` das instâncias criadas com `new`
+        is_async: bool,              // Chamadas retornam uma promise
+    },
+    NativeFunction {
+        name: String,
+        function: NativeFunction,
+    },
+    NativeMethod {
+        name: String,
+        method: NativeMethod,
+        constructor: bool, // Aceita `new`; o método cria e devolve a instância
     },
     String(String),
+    Promise(Promise),
+    Set(Vec<Value>),          // Valores de um Set, em ordem de inserção
+    Map(Vec<(Value, Value)>), // Pares chave/valor de um Map, em ordem de inserção
+    Cell(Value), // Variável capturada por closures, compartilhada entre o frame que a declarou e elas
+}
+
+/// Quantidade de entradas vivas no heap, por tipo
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeapStats {
+    pub object_count: usize,
+    pub array_count: usize,
+    pub function_count: usize, // Inclui funções nativas
+    pub string_count: usize,
+    pub free_slots: usize, // Slots liberados pela coleta, reaproveitados nas próximas alocações
 }
 
 #[derive(Debug, Default)]
 pub struct Heap {
-    entries: Vec<HeapEntry>,
+    entries: Vec<Option<HeapEntry>>, // None = slot liberado
+    free_list: Vec<usize>,
+    prototypes: HashMap<HandleId, HandleId>, // [[Prototype]] de cada objeto que tem um
+    limit: Option<usize>,                     // Máximo de entradas vivas
+    limit_exceeded: bool,                     // Uma alocação passou do limite desde a última verificação
 }
 
 impl Heap {
     pub fn new() -> Self {
-        Heap { entries: Vec::new() }
+        Heap { entries: Vec::new(), free_list: Vec::new(), prototypes: HashMap::new(), limit: None, limit_exceeded: false }
     }
+    /// A alocação sempre acontece; passar do limite só é registrado, e o
+    /// executor decide se lança o erro (ver `take_limit_exceeded`)
     pub fn alloc_entry(&mut self, entry: HeapEntry) -> HandleId {
-        let idx = self.entries.len();
-        self.entries.push(entry);
-        HandleId(idx)
+        let handle = if let Some(idx) = self.free_list.pop() {
+            self.entries[idx] = Some(entry);
+            HandleId(idx)
+        } else {
+            self.entries.push(Some(entry));
+            HandleId(self.entries.len() - 1)
+        };
+        if self.is_over_limit() {
+            self.limit_exceeded = true;
+        }
+        handle
+    }
+    /// Número de entradas vivas
+    pub fn live_count(&self) -> usize {
+        self.entries.len() - self.free_list.len()
+    }
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+    pub fn set_limit(&mut self, limit: Option<usize>) {
+        self.limit = limit;
+    }
+    /// Se há mais entradas vivas que o limite
+    pub fn is_over_limit(&self) -> bool {
+        self.limit.is_some_and(|limit| self.live_count() > limit)
+    }
+    /// Se alguma alocação passou do limite desde a última chamada
+    pub fn take_limit_exceeded(&mut self) -> bool {
+        std::mem::take(&mut self.limit_exceeded)
+    }
+    /// Libera a entrada; o handle deixa de ser válido e o slot pode ser reaproveitado
+    pub fn free(&mut self, handle: HandleId) {
+        if let Some(slot) = self.entries.get_mut(*handle) {
+            if slot.take().is_some() {
+                self.free_list.push(*handle);
+                self.prototypes.remove(&handle);
+            }
+        }
+    }
+    pub fn stats(&self) -> HeapStats {
+        let mut stats = HeapStats { free_slots: self.free_list.len(), ..HeapStats::default() };
+        for entry in self.entries.iter().flatten() {
+            match entry {
+                HeapEntry::Object(_)
+                | HeapEntry::Promise(_)
+                | HeapEntry::Set(_)
+                | HeapEntry::Map(_)
+                | HeapEntry::Cell(_) => stats.object_count += 1,
+                HeapEntry::Array(_) => stats.array_count += 1,
+                HeapEntry::Function { .. } | HeapEntry::NativeFunction { .. } | HeapEntry::NativeMethod { .. } => {
+                    stats.function_count += 1
+                }
+                HeapEntry::String(_) => stats.string_count += 1,
+            }
+        }
+        stats
+    }
+    /// Coleta mark-and-sweep: libera tudo que não é alcançável a partir das raízes
+    ///
+    /// Returns the number of entries freed.
+    pub fn collect(&mut self, roots: impl IntoIterator<Item = HandleId>) -> usize {
+        let mut marked = vec![false; self.entries.len()];
+        let mut pending: Vec<HandleId> = roots.into_iter().collect();
+        while let Some(handle) = pending.pop() {
+            match marked.get_mut(*handle) {
+                Some(mark) if !*mark => *mark = true,
+                _ => continue,
+            }
+            if let Some(prototype) = self.get_prototype(handle) {
+                pending.push(prototype);
+            }
+            match self.get(handle) {
+                Some(HeapEntry::Object(properties)) => {
+                    pending.extend(properties.iter().filter_map(|(_, value)| value_handle(value)));
+                }
+                Some(HeapEntry::Array(elements)) => pending.extend(elements.iter().flatten().filter_map(value_handle)),
+                Some(HeapEntry::Function { closure_vars, prototype, .. }) => {
+                    pending.extend(closure_vars.values().filter_map(value_handle));
+                    pending.extend(*prototype);
+                }
+                Some(HeapEntry::Promise(promise)) => pending.extend(promise.handles()),
+                Some(HeapEntry::Set(values)) => pending.extend(values.iter().filter_map(value_handle)),
+                Some(HeapEntry::Map(entries)) => {
+                    pending.extend(entries.iter().flat_map(|(key, value)| [key, value]).filter_map(value_handle));
+                }
+                Some(HeapEntry::Cell(value)) => pending.extend(value_handle(value)),
+                _ => {}
+            }
+        }
+        let mut freed = 0;
+        for (idx, mark) in marked.into_iter().enumerate() {
+            if !mark && self.entries[idx].is_some() {
+                self.free(HandleId(idx));
+                freed += 1;
+            }
+        }
+        freed
     }
     pub fn alloc_object(&mut self) -> HandleId {
-        self.alloc_entry(HeapEntry::Object(HashMap::new()))
+        self.alloc_entry(HeapEntry::Object(PropertyMap::new()))
     }
     pub fn alloc_array(&mut self) -> HandleId {
-        self.alloc_entry(HeapEntry::Array(Vec::new()))
+        self.alloc_entry(HeapEntry::Array(Elements::default()))
     }
     pub fn alloc_function(
         &mut self,
@@ -79,8 +221,49 @@ impl Heap {
             arg_count,
             local_count,
             closure_vars: HashMap::new(),
+            name: String::new(),
+            local_names: Vec::new(),
+            inline_cache: InlineCache::new(),
+            prototype: None,
+            is_async: false,
+        })
+    }
+    pub fn alloc_native_function(&mut self, name: &str, function: NativeFunction) -> HandleId {
+        self.alloc_entry(HeapEntry::NativeFunction {
+            name: name.to_string(),
+            function,
+        })
+    }
+    pub fn alloc_native_method(&mut self, name: &str, method: NativeMethod) -> HandleId {
+        self.alloc_entry(HeapEntry::NativeMethod {
+            name: name.to_string(),
+            method,
+            constructor: false,
         })
     }
+    /// Construtor nativo: um método nativo que `new` chama para criar a instância
+    pub fn alloc_native_constructor(&mut self, name: &str, method: NativeMethod) -> HandleId {
+        self.alloc_entry(HeapEntry::NativeMethod {
+            name: name.to_string(),
+            method,
+            constructor: true,
+        })
+    }
+    pub fn alloc_cell(&mut self, value: Value) -> HandleId {
+        self.alloc_entry(HeapEntry::Cell(value))
+    }
+    /// Valor guardado na célula; `None` se o handle não é uma célula
+    pub fn cell_value(&self, handle: HandleId) -> Option<&Value> {
+        match self.get(handle) {
+            Some(HeapEntry::Cell(value)) => Some(value),
+            _ => None,
+        }
+    }
+    pub fn set_cell_value(&mut self, handle: HandleId, value: Value) {
+        if let Some(HeapEntry::Cell(cell)) = self.get_mut(handle) {
+            *cell = value;
+        }
+    }
     pub fn get_function_info(
         &self,
         handle: HandleId,
@@ -90,6 +273,7 @@ impl Heap {
             arg_count,
             local_count,
             closure_vars,
+            ..
         }) = self.get(handle)
         {
             Some((bytecode, arg_count, local_count, closure_vars))
@@ -98,15 +282,67 @@ impl Heap {
         }
     }
     pub fn set_closure_var(&mut self, handle: HandleId, name: String, value: Value) {
-        if let Some(HeapEntry::Function { closure_vars, .. }) = self.entries.get_mut(*handle) {
+        if let Some(HeapEntry::Function { closure_vars, .. }) = self.get_mut(handle) {
             closure_vars.insert(name, value);
         }
     }
+    pub fn set_function_name(&mut self, handle: HandleId, function_name: String) {
+        if let Some(HeapEntry::Function { name, .. }) = self.get_mut(handle) {
+            *name = function_name;
+        }
+    }
+    pub fn set_local_names(&mut self, handle: HandleId, names: Vec<String>) {
+        if let Some(HeapEntry::Function { local_names, .. }) = self.get_mut(handle) {
+            *local_names = names;
+        }
+    }
+    pub fn set_function_prototype(&mut self, handle: HandleId, object: HandleId) {
+        if let Some(HeapEntry::Function { prototype, .. }) = self.get_mut(handle) {
+            *prototype = Some(object);
+        }
+    }
+    pub fn set_function_async(&mut self, handle: HandleId, function_async: bool) {
+        if let Some(HeapEntry::Function { is_async, .. }) = self.get_mut(handle) {
+            *is_async = function_async;
+        }
+    }
+    pub fn alloc_promise(&mut self) -> HandleId {
+        self.alloc_entry(HeapEntry::Promise(Promise::new()))
+    }
+    pub fn get_promise_state(&self, handle: HandleId) -> Option<&PromiseState> {
+        match self.get(handle) {
+            Some(HeapEntry::Promise(promise)) => Some(&promise.state),
+            _ => None,
+        }
+    }
+    pub fn get_function_prototype(&self, handle: HandleId) -> Option<HandleId> {
+        match self.get(handle) {
+            Some(HeapEntry::Function { prototype, .. }) => *prototype,
+            _ => None,
+        }
+    }
+    pub fn set_prototype(&mut self, handle: HandleId, prototype: HandleId) {
+        self.prototypes.insert(handle, prototype);
+    }
+    pub fn get_prototype(&self, handle: HandleId) -> Option<HandleId> {
+        self.prototypes.get(&handle).copied()
+    }
+    /// Procura a propriedade na cadeia de protótipos do objeto, sem incluí-lo
+    pub fn get_inherited_property(&self, handle: HandleId, key: &str) -> Option<&Value> {
+        let mut current = self.get_prototype(handle);
+        while let Some(prototype) = current {
+            if let Some(value) = self.get_object_property(prototype, key) {
+                return Some(value);
+            }
+            current = self.get_prototype(prototype);
+        }
+        None
+    }
     pub fn get(&self, handle: HandleId) -> Option<&HeapEntry> {
-        self.entries.get(*handle)
+        self.entries.get(*handle).and_then(Option::as_ref)
     }
     pub fn get_mut(&mut self, handle: HandleId) -> Option<&mut HeapEntry> {
-        self.entries.get_mut(*handle)
+        self.entries.get_mut(*handle).and_then(Option::as_mut)
     }
     pub fn set_object_property(&mut self, handle: HandleId, key: String, value: Value) {
         if let Some(HeapEntry::Object(obj)) = self.get_mut(handle) {
@@ -134,13 +370,8 @@ impl Heap {
     }
     pub fn set_array_element(&mut self, handle: HandleId, idx: usize, value: Value) {
         if let Some(HeapEntry::Array(arr)) = self.get_mut(handle) {
-            if idx < arr.len() {
-                arr[idx] = value;
-            } else {
-                // Expande o array se necessário
-                arr.resize(idx + 1, Value::Undefined);
-                arr[idx] = value;
-            }
+            // Escrever além do fim deixa buracos, sem alocá-los
+            arr.set(idx, value);
         }
     }
     pub fn remove_object_property(&mut self, handle: HandleId, key: &str) {
@@ -155,4 +386,12 @@ impl Heap {
             false
         }
     }
-} 
+}
+
+/// Handle do heap referenciado pelo valor, se houver
+pub(crate) fn value_handle(value: &Value) -> Option<HandleId> {
+    match value {
+        Value::Object(handle) | Value::Array(handle) | Value::Function(handle) => Some(*handle),
+        _ => None,
+    }
+}
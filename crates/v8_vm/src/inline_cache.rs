@@ -0,0 +1,51 @@
+//! Inline caches for property access
+//!
+//! Each `GetProperty`/`SetProperty` site remembers the shape of the last
+//! object it accessed, the key and the slot the key lived in. When the next
+//! object at that site has the same shape and the key is the same, the slot
+//! is used directly instead of looking the key up.
+
+use crate::shape::ShapeId;
+
+/// Entrada monomórfica: o último shape visto no site
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    shape: ShapeId,
+    key: String,
+    slot: usize,
+}
+
+/// Caches dos sites de acesso a propriedades de uma função, por índice de instrução
+#[derive(Debug, Clone, Default)]
+pub struct InlineCache {
+    sites: Vec<Option<CacheEntry>>,
+}
+
+impl InlineCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Slot em cache para o site, se o shape e a chave forem os mesmos da última vez
+    pub fn lookup(&self, ip: usize, shape: ShapeId, key: &str) -> Option<usize> {
+        match self.sites.get(ip) {
+            Some(Some(entry)) if entry.shape == shape && entry.key == key => Some(entry.slot),
+            _ => None,
+        }
+    }
+
+    /// Registra o shape e o slot vistos no site, substituindo a entrada anterior
+    pub fn update(&mut self, ip: usize, shape: ShapeId, key: &str, slot: usize) {
+        if self.sites.len() <= ip {
+            self.sites.resize(ip + 1, None);
+        }
+        self.sites[ip] = Some(CacheEntry { shape, key: key.to_string(), slot });
+    }
+}
+
+/// Contadores de acertos e falhas dos inline caches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InlineCacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
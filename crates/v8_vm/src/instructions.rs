@@ -16,26 +16,53 @@ pub enum Instruction {
     LoadGlobal(usize), StoreGlobal(usize),
     LoadLocal(usize), StoreLocal(usize),
     LoadArg(usize), // Nova instrução para acessar argumentos da função
+    LoadRestArgs(usize), // Empilha um array com os argumentos a partir do índice (para `...rest`)
     LoadThisFunction, // Nova instrução para acessar a função atual (útil para recursão)
     LoadThis, // Nova instrução para acessar o valor de this
     LoadClosureVar(String), // Nova instrução para acessar variáveis de closure
+    StoreClosureVar(String), // Desempilha o valor para a variável capturada (na célula compartilhada)
+    NewCell(usize), // Guarda no slot uma célula nova, com undefined, para uma variável capturada por closures
+    LoadCell(usize), StoreCell(usize), // Lê/grava o valor da célula guardada no slot
     // Control flow
     Jump(usize), JumpIfTrue(usize), JumpIfFalse(usize),
+    JumpIfNotNullish(usize), // Salta se o topo não for null/undefined (para `??`)
+    JumpIfNullish(usize), // Salta se o topo for null/undefined (para `?.`)
     // Functions
     Call(usize), Return,
+    CallWithSpread, // Desempilha a função e um array com os argumentos (para `f(...args)`)
+    TailCall(usize), // Como Call, mas em posição de cauda: reaproveita o frame atual
+    CallMethod(usize), // Como Call, com o receptor abaixo dos argumentos; ele vira o `this`
+    CreateFunction(usize), // Empilha uma nova função a partir de `functions[índice]` do bytecode
     // Objects/Arrays
-    NewObject, NewArray(usize), SetProperty, GetProperty,
+    NewObject, // Empilha um objeto vazio; as propriedades vêm depois com Dup, chave, valor, SetProperty
+    NewArray(usize), // Desempilha n elementos (o primeiro empilhado vira o índice 0); empilha o array
+    SetProperty, GetProperty,
+    DeleteProperty, // Desempilha chave e objeto; remove a propriedade própria e empilha o resultado
+    RestElements(usize), // Desempilha um iterável; empilha um array com os elementos a partir do índice (para `[a, ...rest]`)
+    RestProperties(usize), // Desempilha n chaves e um objeto; empilha um objeto com as demais propriedades próprias (para `{a, ...rest}`)
+    GetIterator, // Desempilha um iterável; empilha o iterador percorrido por IteratorNext (para `for-of`)
+    IteratorNext, // Desempilha índice e iterador; empilha o próximo valor e se a iteração acabou
+    CloseIterator, // Desempilha o iterador e chama seu `return`, ao sair de um for-of antes do fim
+    EnumerateKeys, // Desempilha um valor; empilha um array com as chaves percorridas por um for-in
     // Special
-    TypeOf, InstanceOf, In, Delete, New,
+    TypeOf, InstanceOf, In, Delete,
+    New(usize), // Desempilha o construtor e os argumentos; empilha o objeto criado
     // Classes/Prototypes
     NewClass, GetPrototype, SetPrototype,
+    CreateClass(usize), // Desempilha o construtor e n pares (nome, método); empilha o construtor
     // Async/Generators
     Await, Yield,
     // Exception handling
-    Throw, Try(usize, usize), Catch, Finally,
+    Throw, Catch, Finally,
+    Try(usize), // Até o EndTry, uma exceção desvia para o endereço com o valor lançado no topo da pilha
+    EndTry, // Fim do bloco protegido: remove o handler do Try mais recente
     // Modern JS
     Spread, Destructure, OptionalChain, NullishCoalesce,
     // Literals
     PushNull, PushUndefined, PushTrue, PushFalse, PushSymbol(usize), PushBigInt(usize),
     CallFunction(usize, usize), // (handle, argc) - chama função por handle direto
+    // Memory
+    GcHint, // Sugere uma coleta de lixo; libera o que não é alcançável
+    // Debugging
+    Debugger, // Pausa se houver um depurador registrado (ver `Executor::on_debugger_event`)
 } 
\ No newline at end of file
@@ -0,0 +1,163 @@
+//! Iterator protocol for the V8-Rust VM
+//!
+//! Arrays, strings, Sets and Maps are iterated directly. An object is iterable when it
+//! has a method under `ITERATOR_KEY`, which returns the iterator, or when it
+//! is itself an iterator with a `next` method. Each `next()` result is an
+//! object whose `done` ends the iteration and whose `value` is the element.
+
+use crate::executor::Executor;
+use crate::heap::{HandleId, HeapEntry};
+use crate::value::Value;
+
+/// Chave da propriedade `Symbol.iterator`, a mesma do runtime
+pub const ITERATOR_KEY: &str = "Symbol(Symbol.iterator)";
+
+/// Propriedade própria ou herdada do objeto
+fn lookup(executor: &Executor, handle: HandleId, key: &str) -> Option<Value> {
+    executor
+        .heap
+        .get_object_property(handle, key)
+        .or_else(|| executor.heap.get_inherited_property(handle, key))
+        .cloned()
+}
+
+/// Iterador de um objeto iterável; None se o objeto não for iterável
+pub fn get_iterator(executor: &mut Executor, value: &Value) -> Result<Option<Value>, Value> {
+    let Value::Object(handle) = value else { return Ok(None) };
+    if let Some(method @ Value::Function(_)) = lookup(executor, *handle, ITERATOR_KEY) {
+        return match executor.call_function(&method, value.clone(), &[])? {
+            iterator @ Value::Object(_) => Ok(Some(iterator)),
+            _ => {
+                let message = "TypeError: Result of the Symbol.iterator method is not an object".to_string();
+                Err(executor.native_error(message))
+            }
+        };
+    }
+    match lookup(executor, *handle, "next") {
+        Some(Value::Function(_)) => Ok(Some(value.clone())),
+        _ => Ok(None),
+    }
+}
+
+/// Chama `next()` uma vez; `None` quando o resultado tem `done`
+fn next_value(executor: &mut Executor, handle: HandleId) -> Result<Option<Value>, Value> {
+    let next = lookup(executor, handle, "next").unwrap_or(Value::Undefined);
+    let step = match executor.call_function(&next, Value::Object(handle), &[])? {
+        Value::Object(step) => step,
+        other => {
+            let message = format!("TypeError: Iterator result {} is not an object", other.to_string());
+            return Err(executor.native_error(message));
+        }
+    };
+    if lookup(executor, step, "done").is_some_and(|done| done.to_boolean()) {
+        return Ok(None);
+    }
+    Ok(Some(lookup(executor, step, "value").unwrap_or(Value::Undefined)))
+}
+
+/// Chama `next()` até `done`, passando cada valor a `visit`
+///
+/// The iterator stays on the stack while it runs, so a collection triggered
+/// by `next` does not free it.
+fn drain(executor: &mut Executor, iterator: Value, visit: &mut impl FnMut(&mut Executor, Value)) -> Result<(), Value> {
+    let Value::Object(handle) = iterator else { return Ok(()) };
+    executor.stack.push(iterator.clone());
+    let result = loop {
+        match next_value(executor, handle) {
+            Ok(Some(value)) => visit(executor, value),
+            Ok(None) => break Ok(()),
+            Err(error) => break Err(error),
+        }
+    };
+    executor.stack.pop();
+    result
+}
+
+/// Elementos de arrays, strings, Sets e Maps, lidos sem chamar o script
+///
+/// A Set yields its values and a Map a `[key, value]` array per entry.
+pub(crate) fn elements(executor: &mut Executor, value: &Value) -> Option<Vec<Value>> {
+    if let Some(elements) = executor.iterate(value) {
+        return Some(elements);
+    }
+    let Value::Object(handle) = value else { return None };
+    match executor.heap.get(*handle) {
+        Some(HeapEntry::Set(values)) => Some(values.clone()),
+        Some(HeapEntry::Map(entries)) => {
+            let entries = entries.clone();
+            let pairs = entries
+                .into_iter()
+                .map(|(key, value)| Value::Array(executor.heap.alloc_entry(HeapEntry::Array(vec![key, value].into()))))
+                .collect();
+            Some(pairs)
+        }
+        _ => None,
+    }
+}
+
+fn not_iterable(value: &Value) -> Value {
+    Value::String(format!("TypeError: {} is not iterable", value.to_string()))
+}
+
+/// Passa cada valor do iterável a `visit`, em ordem
+///
+/// A value that is not iterable is a TypeError, thrown as a string like the
+/// other errors raised by the instructions.
+pub fn for_each_value(
+    executor: &mut Executor,
+    source: &Value,
+    mut visit: impl FnMut(&mut Executor, Value),
+) -> Result<(), Value> {
+    if let Some(elements) = elements(executor, source) {
+        for element in elements {
+            visit(executor, element);
+        }
+        return Ok(());
+    }
+    match get_iterator(executor, source)? {
+        Some(iterator) => drain(executor, iterator, &mut visit),
+        None => Err(not_iterable(source)),
+    }
+}
+
+/// Iterador de um `for-of`, percorrido com `step`
+///
+/// An array is returned as is and walked by index, so elements added by
+/// the loop body are visited. Strings, Sets and Maps are walked through an
+/// array copied when the loop starts; other iterables through their
+/// iterator object, one `next()` per step.
+pub fn open(executor: &mut Executor, iterable: &Value) -> Result<Value, Value> {
+    if let Value::Array(_) = iterable {
+        return Ok(iterable.clone());
+    }
+    if let Some(elements) = elements(executor, iterable) {
+        return Ok(Value::Array(executor.heap.alloc_entry(HeapEntry::Array(elements.into()))));
+    }
+    get_iterator(executor, iterable)?.ok_or_else(|| not_iterable(iterable))
+}
+
+/// Próximo valor do iterador devolvido por `open`; `None` quando acabou
+///
+/// `index` counts the steps taken, which is the position read from an array.
+pub fn step(executor: &mut Executor, iterator: &Value, index: usize) -> Result<Option<Value>, Value> {
+    match iterator {
+        // Buracos são lidos como undefined
+        Value::Array(handle) => Ok(match executor.heap.get(*handle) {
+            Some(HeapEntry::Array(elements)) if index < elements.len() => {
+                Some(elements.get(index).cloned().unwrap_or(Value::Undefined))
+            }
+            _ => None,
+        }),
+        Value::Object(handle) => next_value(executor, *handle),
+        _ => Ok(None),
+    }
+}
+
+/// Chama `return()` do iterador, se houver, quando o laço termina antes do fim
+pub fn close(executor: &mut Executor, iterator: &Value) -> Result<(), Value> {
+    let Value::Object(handle) = iterator else { return Ok(()) };
+    match lookup(executor, *handle, "return") {
+        Some(method @ Value::Function(_)) => executor.call_function(&method, iterator.clone(), &[]).map(|_| ()),
+        _ => Ok(()),
+    }
+}
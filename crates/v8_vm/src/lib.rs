@@ -3,11 +3,20 @@
 //! This crate provides the bytecode execution engine and
 //! instruction set for the JavaScript engine.
 
+pub mod array;
 pub mod bytecode;
+pub mod collection;
+pub mod debugger;
 pub mod executor;
 pub mod frame;
+pub mod inline_cache;
+pub mod iterator;
+pub mod limits;
+pub mod optimizer;
+pub mod promise;
 pub mod instructions;
 pub mod registers;
+pub mod shape;
 pub mod stack;
 pub mod value;
 pub mod heap;
@@ -16,5 +25,7 @@ pub use bytecode::Bytecode;
 pub use executor::Executor;
 pub use frame::Frame;
 pub use instructions::Instruction;
+pub use limits::ResourceLimits;
+pub use optimizer::Optimizer;
 pub use registers::Registers;
 pub use stack::Stack; 
\ No newline at end of file
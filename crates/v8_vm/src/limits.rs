@@ -0,0 +1,37 @@
+//! Resource limits for running untrusted code on the V8-Rust VM
+//!
+//! Every limit is reported to the script as a `RangeError` it can catch,
+//! rather than exhausting the memory or the native stack of the host.
+
+/// Limits of an executor, applied with `Executor::set_limits`
+///
+/// By default the heap is unbounded and calls are bounded by both depth and
+/// native stack use. Each call that is not in tail position nests native
+/// calls, taking a few KiB of stack when optimized and over 20 KiB in a
+/// debug build, so the stack limit is what stops deep recursion on a small
+/// stack. It leaves room within the 2 MiB stack of a thread spawned with the
+/// default size; hosts running scripts on a larger stack can raise it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceLimits {
+    pub max_heap_entries: Option<usize>, // Live heap entries
+    pub max_call_depth: Option<usize>,   // Nested calls; tail calls do not count
+    pub max_stack_size: Option<usize>,   // Native stack bytes taken by nested calls
+}
+
+impl ResourceLimits {
+    /// Call depth limit of a new executor
+    pub const DEFAULT_MAX_CALL_DEPTH: usize = 1000;
+
+    /// Native stack limit of a new executor, in bytes
+    pub const DEFAULT_MAX_STACK_SIZE: usize = 1536 * 1024;
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            max_heap_entries: None,
+            max_call_depth: Some(Self::DEFAULT_MAX_CALL_DEPTH),
+            max_stack_size: Some(Self::DEFAULT_MAX_STACK_SIZE),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Peephole optimizer for V8-Rust VM bytecode
+//!
+//! The rules rewrite short instruction sequences into cheaper ones with the
+//! same effect on the stack and the locals. A pair is only rewritten when no
+//! jump lands between its two instructions, and jump targets are moved to
+//! follow the instructions they pointed at.
+
+use std::collections::HashSet;
+
+use crate::bytecode::Bytecode;
+use crate::instructions::Instruction;
+use crate::value::Value;
+
+/// Otimizador peephole; aplica as regras até o código não mudar mais
+///
+/// - `StoreLocal(n); LoadLocal(n)` vira `Dup; StoreLocal(n)`
+/// - `LoadLocal(n); Pop` é removido
+/// - `!` de uma constante booleana vira a constante negada
+#[derive(Debug, Default)]
+pub struct Optimizer;
+
+impl Optimizer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Otimiza o bytecode e as funções que ele cria; `constants` é o pool usado na execução
+    pub fn optimize(&self, bytecode: &mut Bytecode, constants: &[Value]) {
+        while let Some(instructions) = peephole(&bytecode.instructions, constants) {
+            bytecode.instructions = instructions;
+        }
+        for function in &mut bytecode.functions {
+            self.optimize(&mut function.bytecode, constants);
+        }
+    }
+}
+
+/// Endereço de destino de uma instrução de salto
+fn target(instruction: &Instruction) -> Option<usize> {
+    match instruction {
+        Instruction::Jump(target)
+        | Instruction::JumpIfTrue(target)
+        | Instruction::JumpIfFalse(target)
+        | Instruction::JumpIfNotNullish(target)
+        | Instruction::JumpIfNullish(target)
+        | Instruction::Try(target) => Some(*target),
+        _ => None,
+    }
+}
+
+fn retarget(instruction: &mut Instruction, new_index: &[usize]) {
+    if let Instruction::Jump(target)
+    | Instruction::JumpIfTrue(target)
+    | Instruction::JumpIfFalse(target)
+    | Instruction::JumpIfNotNullish(target)
+    | Instruction::JumpIfNullish(target)
+    | Instruction::Try(target) = instruction
+    {
+        // Um destino fora do código continua fora dele
+        *target = new_index.get(*target).copied().unwrap_or(new_index[new_index.len() - 1]);
+    }
+}
+
+/// Valor de uma instrução que empilha uma constante booleana
+fn boolean_constant(instruction: &Instruction, constants: &[Value]) -> Option<bool> {
+    match instruction {
+        Instruction::PushTrue => Some(true),
+        Instruction::PushFalse => Some(false),
+        Instruction::PushConst(idx) => match constants.get(*idx) {
+            Some(Value::Boolean(b)) => Some(*b),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Uma passada pelas instruções; `None` se nenhuma regra se aplicou
+fn peephole(instructions: &[Instruction], constants: &[Value]) -> Option<Vec<Instruction>> {
+    let targets: HashSet<usize> = instructions.iter().filter_map(target).collect();
+    let mut output = Vec::with_capacity(instructions.len());
+    // Posição de cada instrução antiga no novo código; a última entrada é o fim do código
+    let mut new_index = Vec::with_capacity(instructions.len() + 1);
+    let mut changed = false;
+    let mut ip = 0;
+    while ip < instructions.len() {
+        let pair = match instructions.get(ip + 1) {
+            Some(next) if !targets.contains(&(ip + 1)) => Some((&instructions[ip], next)),
+            _ => None,
+        };
+        let rewritten = match pair {
+            Some((Instruction::StoreLocal(store), Instruction::LoadLocal(load))) if store == load => {
+                Some(vec![Instruction::Dup, Instruction::StoreLocal(*store)])
+            }
+            Some((Instruction::LoadLocal(_), Instruction::Pop)) => Some(Vec::new()),
+            Some((constant, Instruction::Not)) => boolean_constant(constant, constants)
+                .map(|b| vec![if b { Instruction::PushFalse } else { Instruction::PushTrue }]),
+            _ => None,
+        };
+        match rewritten {
+            Some(replacement) => {
+                // Um salto para o par vai para o que o substitui; nenhum salto cai no meio dele
+                new_index.push(output.len());
+                new_index.push(output.len());
+                output.extend(replacement);
+                changed = true;
+                ip += 2;
+            }
+            None => {
+                new_index.push(output.len());
+                output.push(instructions[ip].clone());
+                ip += 1;
+            }
+        }
+    }
+    if !changed {
+        return None;
+    }
+    new_index.push(output.len());
+    for instruction in &mut output {
+        retarget(instruction, &new_index);
+    }
+    Some(output)
+}
@@ -0,0 +1,106 @@
+//! Promises and microtasks for the V8-Rust VM
+//!
+//! Calling an async function returns a promise. Each `Await` suspends the
+//! function's frame into a `Continuation`; once the awaited value settles,
+//! a `Microtask` resumes it, and `Executor::run_microtasks` drains them.
+
+use crate::bytecode::Bytecode;
+use crate::frame::Frame;
+use crate::heap::{value_handle, HandleId};
+use crate::value::Value;
+
+/// Estado de uma promise
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromiseState {
+    Pending,
+    Fulfilled(Value),
+    Rejected(Value),
+}
+
+/// Quem espera uma promise pendente
+#[derive(Debug, Clone)]
+pub enum Reaction {
+    Resume(Box<Continuation>), // Função async suspensa num `Await`
+    Adopt(HandleId),           // Promise resolvida com esta: assume o mesmo resultado
+}
+
+/// Promise no heap
+#[derive(Debug, Clone)]
+pub struct Promise {
+    pub state: PromiseState,
+    pub reactions: Vec<Reaction>, // Executadas quando a promise é resolvida ou rejeitada
+}
+
+/// Função async suspensa, retomada na instrução seguinte ao `Await`
+#[derive(Debug, Clone)]
+pub struct Continuation {
+    pub bytecode: Bytecode,
+    pub ip: usize,
+    pub locals: Vec<Value>,
+    pub stack: Vec<Value>, // Pilha de operandos da função no ponto do `Await`
+    pub frame: Frame,      // Handlers com alturas relativas ao início de `stack`
+    pub promise: HandleId, // Promise retornada pela chamada
+}
+
+/// Retomada de uma função async com o resultado do valor esperado
+#[derive(Debug, Clone)]
+pub struct Microtask {
+    pub continuation: Continuation,
+    pub result: Result<Value, Value>, // Err: a promise esperada foi rejeitada
+}
+
+impl Promise {
+    pub fn new() -> Self {
+        Promise { state: PromiseState::Pending, reactions: Vec::new() }
+    }
+
+    /// Handles alcançáveis a partir da promise, para a coleta de lixo
+    pub(crate) fn handles(&self) -> Vec<HandleId> {
+        let mut handles: Vec<HandleId> = match &self.state {
+            PromiseState::Fulfilled(value) | PromiseState::Rejected(value) => value_handle(value).into_iter().collect(),
+            PromiseState::Pending => Vec::new(),
+        };
+        for reaction in &self.reactions {
+            match reaction {
+                Reaction::Resume(continuation) => handles.extend(continuation.handles()),
+                Reaction::Adopt(promise) => handles.push(*promise),
+            }
+        }
+        handles
+    }
+}
+
+impl Default for Promise {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Continuation {
+    /// Handles alcançáveis a partir do frame suspenso, para a coleta de lixo
+    pub(crate) fn handles(&self) -> Vec<HandleId> {
+        let frame = &self.frame;
+        let values = self
+            .locals
+            .iter()
+            .chain(&self.stack)
+            .chain(&frame.arguments)
+            .chain(&frame.local_values)
+            .chain(frame.closure_vars.values())
+            .chain(&frame.this_value);
+        values
+            .filter_map(value_handle)
+            .chain(frame.function_handle)
+            .chain(std::iter::once(self.promise))
+            .collect()
+    }
+}
+
+impl Microtask {
+    pub(crate) fn handles(&self) -> Vec<HandleId> {
+        let mut handles = self.continuation.handles();
+        let (Ok(value) | Err(value)) = &self.result;
+        handles.extend(value_handle(value));
+        handles
+    }
+}
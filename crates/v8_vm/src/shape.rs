@@ -0,0 +1,152 @@
+//! Hidden classes (shapes) for heap objects
+//!
+//! Objects that receive the same keys in the same order share a shape,
+//! which maps each key to a slot in the object's value vector. Property
+//! access sites remember the shape and slot they last saw (see
+//! `inline_cache`), so repeated access to same-shaped objects skips the
+//! key lookup.
+
+use crate::value::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Identificador de um shape; iguais se e só se as chaves (e sua ordem) são iguais
+pub type ShapeId = usize;
+
+/// Shape do objeto sem propriedades
+pub const EMPTY_SHAPE: ShapeId = 0;
+
+struct Shape {
+    keys: Vec<String>,                     // Chave de cada slot, em ordem de inserção
+    slots: HashMap<String, usize>,         // Slot de cada chave
+    transitions: HashMap<String, ShapeId>, // Shape obtido ao adicionar cada chave
+}
+
+/// Árvore de transições: cada shape é o anterior mais uma chave
+struct ShapeTable {
+    shapes: Vec<Shape>,
+}
+
+impl ShapeTable {
+    fn new() -> Self {
+        let empty = Shape { keys: Vec::new(), slots: HashMap::new(), transitions: HashMap::new() };
+        ShapeTable { shapes: vec![empty] }
+    }
+
+    fn transition(&mut self, from: ShapeId, key: &str) -> ShapeId {
+        if let Some(&to) = self.shapes[from].transitions.get(key) {
+            return to;
+        }
+        let mut keys = self.shapes[from].keys.clone();
+        let mut slots = self.shapes[from].slots.clone();
+        slots.insert(key.to_string(), keys.len());
+        keys.push(key.to_string());
+        let to = self.shapes.len();
+        self.shapes.push(Shape { keys, slots, transitions: HashMap::new() });
+        self.shapes[from].transitions.insert(key.to_string(), to);
+        to
+    }
+}
+
+thread_local! {
+    // Compartilhada por todos os heaps da thread, para que objetos equivalentes
+    // tenham o mesmo ShapeId
+    static SHAPES: RefCell<ShapeTable> = RefCell::new(ShapeTable::new());
+}
+
+/// Propriedades de um objeto do heap, guardadas em slots segundo o seu shape
+#[derive(Clone)]
+pub struct PropertyMap {
+    shape: ShapeId,
+    values: Vec<Value>,
+}
+
+impl PropertyMap {
+    pub fn new() -> Self {
+        PropertyMap { shape: EMPTY_SHAPE, values: Vec::new() }
+    }
+
+    /// Shape atual; muda sempre que uma chave é adicionada ou removida
+    pub fn shape(&self) -> ShapeId {
+        self.shape
+    }
+
+    /// Slot onde a chave está guardada
+    pub fn slot(&self, key: &str) -> Option<usize> {
+        SHAPES.with(|table| table.borrow().shapes[self.shape].slots.get(key).copied())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.slot(key).and_then(|slot| self.values.get(slot))
+    }
+
+    /// Valor de um slot, sem consultar o shape (usado pelos inline caches)
+    pub fn get_slot(&self, slot: usize) -> Option<&Value> {
+        self.values.get(slot)
+    }
+
+    /// Substitui o valor de um slot existente; o shape não muda
+    pub fn set_slot(&mut self, slot: usize, value: Value) {
+        if let Some(current) = self.values.get_mut(slot) {
+            *current = value;
+        }
+    }
+
+    /// Define a propriedade, devolvendo o valor anterior se a chave já existia
+    pub fn insert(&mut self, key: String, value: Value) -> Option<Value> {
+        if let Some(slot) = self.slot(&key) {
+            return Some(std::mem::replace(&mut self.values[slot], value));
+        }
+        self.shape = SHAPES.with(|table| table.borrow_mut().transition(self.shape, &key));
+        self.values.push(value);
+        None
+    }
+
+    /// Remove a propriedade; o objeto passa ao shape das chaves restantes
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let slot = self.slot(key)?;
+        let keys = self.keys();
+        let mut values = std::mem::take(&mut self.values);
+        let removed = values.remove(slot);
+        self.shape = EMPTY_SHAPE;
+        for (key, value) in keys.into_iter().filter(|k| k != key).zip(values) {
+            self.insert(key, value);
+        }
+        Some(removed)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.slot(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Chaves em ordem de inserção
+    pub fn keys(&self) -> Vec<String> {
+        SHAPES.with(|table| table.borrow().shapes[self.shape].keys.clone())
+    }
+
+    /// Pares (chave, valor) em ordem de inserção
+    pub fn iter(&self) -> impl Iterator<Item = (String, &Value)> {
+        self.keys().into_iter().zip(self.values.iter())
+    }
+}
+
+impl Default for PropertyMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for PropertyMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
@@ -1,8 +1,9 @@
 //! Value type for the V8-Rust VM
 
 use crate::heap::HandleId;
+use std::cmp::Ordering;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub enum Value {
     Number(f64),
     String(String),
@@ -11,6 +12,7 @@ pub enum Value {
     Array(HandleId),
     Function(HandleId),
     Null,
+    #[default]
     Undefined,
 }
 
@@ -49,6 +51,17 @@ impl Value {
             Value::Function(_) => "[function]".to_string(),
         }
     }
+    /// Resultado do operador `typeof` (null é "object" por razões históricas)
+    pub fn typeof_(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Boolean(_) => "boolean",
+            Value::Undefined => "undefined",
+            Value::Function(_) => "function",
+            Value::Null | Value::Object(_) | Value::Array(_) => "object",
+        }
+    }
     pub fn to_boolean(&self) -> bool {
         match self {
             Value::Boolean(b) => *b,
@@ -58,4 +71,22 @@ impl Value {
             _ => true,
         }
     }
-} 
\ No newline at end of file
+    /// Operador `+`: concatena se algum operando for string após ToPrimitive,
+    /// senão soma numericamente. Objetos viram strings em ToPrimitive.
+    pub fn add(&self, other: &Value) -> Value {
+        let is_string = |value: &Value| matches!(value, Value::String(_)) || !value.is_primitive();
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => Value::Number(a + b),
+            (a, b) if is_string(a) || is_string(b) => Value::String(format!("{}{}", a.to_string(), b.to_string())),
+            (a, b) => Value::Number(a.to_number() + b.to_number()),
+        }
+    }
+    /// Comparação relacional abstrata: strings por unidades UTF-16, o resto via ToNumber.
+    /// Retorna None quando algum operando é NaN (toda comparação é falsa).
+    pub fn compare(&self, other: &Value) -> Option<Ordering> {
+        match (self, other) {
+            (Value::String(a), Value::String(b)) => Some(a.encode_utf16().cmp(b.encode_utf16())),
+            _ => self.to_number().partial_cmp(&other.to_number()),
+        }
+    }
+}
@@ -35,7 +35,7 @@ fn test_array_set_get_by_index() {
     heap.set_array_element(arr_handle, 0, Value::Number(10.0));
     heap.set_array_element(arr_handle, 2, Value::String("abc".to_string()));
     assert_eq!(heap.get_array_element(arr_handle, 0), Some(&Value::Number(10.0)));
-    assert_eq!(heap.get_array_element(arr_handle, 1), Some(&Value::Undefined));
+    assert_eq!(heap.get_array_element(arr_handle, 1), None); // Buraco
     assert_eq!(heap.get_array_element(arr_handle, 2), Some(&Value::String("abc".to_string())));
 }
 
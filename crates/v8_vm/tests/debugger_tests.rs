@@ -0,0 +1,98 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use v8_vm::bytecode::Bytecode;
+use v8_vm::debugger::PauseReason;
+use v8_vm::executor::Executor;
+use v8_vm::instructions::Instruction;
+use v8_vm::value::Value;
+
+fn sum_program() -> (Bytecode, Vec<Value>) {
+    let bytecode = Bytecode {
+        instructions: vec![
+            Instruction::PushConst(0), // 3
+            Instruction::PushConst(1), // 2
+            Instruction::Add,
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+        ],
+        functions: Vec::new(),
+    };
+    (bytecode, vec![Value::Number(3.0), Value::Number(2.0)])
+}
+
+#[test]
+fn test_execute_step_runs_one_instruction_at_a_time() {
+    let (bytecode, constants) = sum_program();
+    let mut exec = Executor::new();
+
+    assert_eq!(exec.execute_step(&bytecode, &constants), Some(1));
+    assert_eq!(exec.stack.values, vec![Value::Number(3.0)]);
+    assert_eq!(exec.execute_step(&bytecode, &constants), Some(2));
+    assert_eq!(exec.stack.values, vec![Value::Number(3.0), Value::Number(2.0)]);
+    assert_eq!(exec.execute_step(&bytecode, &constants), Some(3));
+    assert_eq!(exec.stack.values, vec![Value::Number(5.0)]);
+    assert_eq!(exec.execute_step(&bytecode, &constants), Some(4));
+    assert!(exec.stack.values.is_empty());
+    assert_eq!(exec.paused_locals().map(|locals| locals[0].clone()), Some(Value::Number(5.0)));
+
+    // A última instrução termina o programa
+    assert_eq!(exec.execute_step(&bytecode, &constants), None);
+    assert_eq!(exec.paused_at(), None);
+    assert_eq!(exec.stack.values, vec![Value::Number(5.0)]);
+}
+
+#[test]
+fn test_breakpoint_pauses_before_its_instruction() {
+    let (bytecode, constants) = sum_program();
+    let mut exec = Executor::new();
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let seen = events.clone();
+    exec.on_debugger_event(move |event| seen.borrow_mut().push((event.reason, event.pc, event.stack.to_vec())));
+    exec.set_breakpoint(2);
+
+    exec.execute(&bytecode, &constants);
+    assert_eq!(exec.paused_at(), Some(2));
+    assert_eq!(exec.stack.values, vec![Value::Number(3.0), Value::Number(2.0)]);
+    assert_eq!(
+        *events.borrow(),
+        vec![(PauseReason::Breakpoint, 2, vec![Value::Number(3.0), Value::Number(2.0)])]
+    );
+
+    assert_eq!(exec.resume(&bytecode, &constants), None);
+    assert_eq!(exec.stack.values, vec![Value::Number(5.0)]);
+    assert_eq!(events.borrow().len(), 1);
+
+    // Sem breakpoints, a execução vai até o fim
+    assert!(exec.clear_breakpoint(2));
+    exec.stack.values.clear();
+    exec.execute(&bytecode, &constants);
+    assert_eq!(exec.paused_at(), None);
+    assert_eq!(exec.stack.values, vec![Value::Number(5.0)]);
+}
+
+#[test]
+fn test_debugger_instruction_pauses_only_when_attached() {
+    let bytecode = Bytecode {
+        instructions: vec![Instruction::PushConst(0), Instruction::Debugger, Instruction::PushConst(0)],
+        functions: Vec::new(),
+    };
+    let constants = vec![Value::Number(1.0)];
+
+    let mut exec = Executor::new();
+    exec.execute(&bytecode, &constants);
+    assert_eq!(exec.paused_at(), None);
+    assert_eq!(exec.stack.values.len(), 2);
+
+    let mut exec = Executor::new();
+    let pcs = Rc::new(RefCell::new(Vec::new()));
+    let seen = pcs.clone();
+    exec.on_debugger_event(move |event| {
+        assert_eq!(event.reason, PauseReason::DebuggerInstruction);
+        seen.borrow_mut().push(event.pc);
+    });
+    exec.execute(&bytecode, &constants);
+    assert_eq!(exec.paused_at(), Some(1));
+    assert_eq!(exec.resume(&bytecode, &constants), None);
+    assert_eq!(*pcs.borrow(), vec![1]);
+    assert_eq!(exec.stack.values.len(), 2);
+}
@@ -1,6 +1,9 @@
 use v8_vm::executor::Executor;
-use v8_vm::bytecode::Bytecode;
+use v8_vm::heap::HeapEntry;
+use v8_vm::bytecode::{Bytecode, Capture, FunctionInfo};
 use v8_vm::instructions::Instruction;
+use v8_vm::iterator::ITERATOR_KEY;
+use v8_vm::limits::ResourceLimits;
 use v8_vm::value::Value;
 
 #[test]
@@ -12,6 +15,7 @@ fn test_execute_basic_arithmetic() {
             Instruction::PushConst(1),
             Instruction::Add,
         ],
+        functions: Vec::new(),
     };
     let constants = vec![Value::Number(3.0), Value::Number(2.0)];
     exec.execute(&bytecode, &constants);
@@ -31,12 +35,73 @@ fn test_execute_sub_mul_div() {
             Instruction::PushConst(3), // 7
             Instruction::Div,          // 14 / 7 = 2
         ],
+        functions: Vec::new(),
     };
     let constants = vec![Value::Number(10.0), Value::Number(3.0), Value::Number(2.0), Value::Number(7.0)];
     exec.execute(&bytecode, &constants);
     assert_eq!(exec.stack.values, vec![Value::Number(2.0)]);
 }
 
+#[test]
+fn test_add_concatenates_left_to_right() {
+    // "a" + 1 + 2 é ("a" + 1) + 2 = "a12"; 1 + 2 + "a" é "3a"
+    let mut exec = Executor::new();
+    let bytecode = Bytecode {
+        instructions: vec![
+            Instruction::PushConst(0),
+            Instruction::PushConst(1),
+            Instruction::Add,
+            Instruction::PushConst(2),
+            Instruction::Add,
+            Instruction::PushConst(1),
+            Instruction::PushConst(2),
+            Instruction::Add,
+            Instruction::PushConst(0),
+            Instruction::Add,
+        ],
+        functions: Vec::new(),
+    };
+    let constants = vec![Value::String("a".to_string()), Value::Number(1.0), Value::Number(2.0)];
+    exec.execute(&bytecode, &constants);
+    assert_eq!(
+        exec.stack.values,
+        vec![Value::String("a12".to_string()), Value::String("3a".to_string())]
+    );
+}
+
+#[test]
+fn test_arithmetic_operand_conversions() {
+    let mut exec = Executor::new();
+    let bytecode = Bytecode {
+        instructions: vec![
+            Instruction::PushTrue,
+            Instruction::PushConst(0),
+            Instruction::Add, // true + 1 = 2
+            Instruction::PushNull,
+            Instruction::PushConst(0),
+            Instruction::Add, // null + 1 = 1
+            Instruction::PushConst(1),
+            Instruction::PushConst(0),
+            Instruction::Sub, // "5" - 1 = 4 (só + concatena)
+            Instruction::NewObject,
+            Instruction::PushConst(0),
+            Instruction::Add, // {} + 1 = "[object Object]1"
+        ],
+        functions: Vec::new(),
+    };
+    let constants = vec![Value::Number(1.0), Value::String("5".to_string())];
+    exec.execute(&bytecode, &constants);
+    assert_eq!(
+        exec.stack.values,
+        vec![
+            Value::Number(2.0),
+            Value::Number(1.0),
+            Value::Number(4.0),
+            Value::String("[object Object]1".to_string()),
+        ]
+    );
+}
+
 #[test]
 fn test_execute_pop_dup() {
     let mut exec = Executor::new();
@@ -47,6 +112,7 @@ fn test_execute_pop_dup() {
             Instruction::Dup,
             Instruction::Pop,
         ],
+        functions: Vec::new(),
     };
     let constants = vec![Value::Number(42.0), Value::Number(100.0)];
     exec.execute(&bytecode, &constants);
@@ -64,6 +130,7 @@ fn test_execute_load_store_local() {
             Instruction::PushConst(1), // 10
             Instruction::Add,           // 42 + 10 = 52
         ],
+        functions: Vec::new(),
     };
     let constants = vec![Value::Number(42.0), Value::Number(10.0)];
     exec.execute(&bytecode, &constants);
@@ -93,6 +160,7 @@ fn test_execute_jump() {
             Instruction::PushConst(1), // This should be skipped
             Instruction::PushConst(2), // 100
         ],
+        functions: Vec::new(),
     };
     let constants = vec![Value::Number(42.0), Value::Number(999.0), Value::Number(100.0)];
     exec.execute(&bytecode, &constants);
@@ -110,6 +178,7 @@ fn test_execute_jump_if_true() {
             Instruction::PushConst(2), // This should be skipped
             Instruction::PushConst(3), // 100
         ],
+        functions: Vec::new(),
     };
     let constants = vec![Value::Boolean(true), Value::Number(999.0), Value::Number(888.0), Value::Number(100.0)];
     exec.execute(&bytecode, &constants);
@@ -127,6 +196,7 @@ fn test_execute_jump_if_false() {
             Instruction::PushConst(2), // This should be skipped
             Instruction::PushConst(3), // 100
         ],
+        functions: Vec::new(),
     };
     let constants = vec![Value::Boolean(false), Value::Number(999.0), Value::Number(888.0), Value::Number(100.0)];
     exec.execute(&bytecode, &constants);
@@ -144,6 +214,7 @@ fn test_execute_load_store_global() {
             Instruction::PushConst(1), // 10
             Instruction::Add,           // 42 + 10 = 52
         ],
+        functions: Vec::new(),
     };
     let constants = vec![Value::Number(42.0), Value::Number(10.0)];
     exec.execute(&bytecode, &constants);
@@ -159,6 +230,7 @@ fn test_execute_simple_function_call() {
             Instruction::Call(0),      // Call function with 0 args
             Instruction::Return,       // Return from function
         ],
+        functions: Vec::new(),
     };
     let constants = vec![Value::Number(42.0)];
     exec.execute(&bytecode, &constants);
@@ -179,8 +251,921 @@ fn test_execute_conditional_logic() {
             Instruction::PushConst(3), // This should be skipped
             Instruction::PushConst(4), // 100 (result if condition is true)
         ],
+        functions: Vec::new(),
     };
     let constants = vec![Value::Number(10.0), Value::Number(5.0), Value::Number(999.0), Value::Number(888.0), Value::Number(100.0)];
     exec.execute(&bytecode, &constants);
     assert_eq!(exec.stack.values, vec![Value::Number(100.0)]); // apenas o valor final após o jump
-} 
\ No newline at end of file
+} 
+#[test]
+fn test_stack_trace_on_nested_throw() {
+    let mut exec = Executor::new();
+    let inner = exec.heap.alloc_function(
+        Bytecode::new(vec![
+            Instruction::PushConst(0),
+            Instruction::StoreLocal(0),
+            Instruction::PushConst(1),
+            Instruction::Throw,
+        ]),
+        0,
+        1,
+    );
+    exec.heap.set_function_name(inner, "inner".to_string());
+    exec.heap.set_local_names(inner, vec!["x".to_string()]);
+    let outer = exec.heap.alloc_function(
+        Bytecode::new(vec![Instruction::CallFunction(*inner, 0), Instruction::Return]),
+        0,
+        0,
+    );
+    exec.heap.set_function_name(outer, "outer".to_string());
+
+    let bytecode = Bytecode::new(vec![Instruction::CallFunction(*outer, 0)]);
+    let constants = vec![Value::Number(7.0), Value::String("boom".to_string())];
+    exec.execute(&bytecode, &constants);
+
+    let (thrown, trace) = exec.exception.clone().expect("Esperava uma exceção");
+    assert_eq!(thrown, Value::String("boom".to_string()));
+    let names: Vec<&str> = trace.iter().map(|f| f.function_name.as_str()).collect();
+    assert_eq!(names, vec!["inner", "outer", "<main>"]);
+    assert_eq!(trace[0].ip, 3);
+    assert_eq!(trace[0].locals, vec![("x".to_string(), Value::Number(7.0))]);
+    // Após o unwind, o executor volta ao frame principal
+    assert!(exec.stack.frames.is_empty());
+}
+
+#[test]
+fn test_try_catches_value_thrown_in_nested_call() {
+    let mut exec = Executor::new();
+    // function thrower() { throw {code: 42}; }
+    let thrower = exec.heap.alloc_function(
+        Bytecode::new(vec![
+            Instruction::PushConst(2), // lixo na pilha, descartado pelo catch
+            Instruction::NewObject,
+            Instruction::Dup,
+            Instruction::PushConst(0),
+            Instruction::PushConst(1),
+            Instruction::SetProperty,
+            Instruction::Throw,
+        ]),
+        0,
+        0,
+    );
+    // try { thrower(); } catch (e) { e.code }
+    let bytecode = Bytecode::new(vec![
+        Instruction::PushConst(2),
+        Instruction::Try(4),
+        Instruction::CallFunction(*thrower, 0),
+        Instruction::EndTry,
+        Instruction::StoreLocal(0),
+        Instruction::LoadLocal(0),
+        Instruction::PushConst(0),
+        Instruction::GetProperty,
+    ]);
+    let constants = vec![Value::String("code".to_string()), Value::Number(42.0), Value::Number(-1.0)];
+    exec.execute(&bytecode, &constants);
+
+    assert!(exec.exception.is_none());
+    // O catch recebe o objeto lançado, com a pilha de antes do Try
+    assert_eq!(exec.stack.values, vec![Value::Number(-1.0), Value::Number(42.0)]);
+    assert!(exec.stack.frames.is_empty());
+
+    // Sem Try, a exceção escapa com o mesmo objeto
+    let bytecode = Bytecode::new(vec![Instruction::CallFunction(*thrower, 0)]);
+    exec.execute(&bytecode, &constants);
+    match exec.exception.as_ref().map(|(value, _)| value) {
+        Some(Value::Object(handle)) => {
+            assert_eq!(exec.heap.get_object_property(*handle, "code"), Some(&Value::Number(42.0)));
+        }
+        other => panic!("Esperava o objeto lançado, obtido {:?}", other),
+    }
+}
+
+#[test]
+fn test_logical_or_keeps_operand_value() {
+    // 0 || "x"
+    let mut exec = Executor::new();
+    let bytecode = Bytecode::new(vec![
+        Instruction::PushConst(0),
+        Instruction::Dup,
+        Instruction::JumpIfTrue(5),
+        Instruction::Pop,
+        Instruction::PushConst(1),
+    ]);
+    let constants = vec![Value::Number(0.0), Value::String("x".to_string())];
+    exec.execute(&bytecode, &constants);
+    assert_eq!(exec.stack.values, vec![Value::String("x".to_string())]);
+}
+
+#[test]
+fn test_logical_and_keeps_operand_value() {
+    // "a" && "b"
+    let mut exec = Executor::new();
+    let bytecode = Bytecode::new(vec![
+        Instruction::PushConst(0),
+        Instruction::Dup,
+        Instruction::JumpIfFalse(5),
+        Instruction::Pop,
+        Instruction::PushConst(1),
+    ]);
+    let constants = vec![Value::String("a".to_string()), Value::String("b".to_string())];
+    exec.execute(&bytecode, &constants);
+    assert_eq!(exec.stack.values, vec![Value::String("b".to_string())]);
+
+    // "" && "b" curto-circuita com o operando esquerdo
+    let mut exec = Executor::new();
+    let constants = vec![Value::String("".to_string()), Value::String("b".to_string())];
+    exec.execute(&bytecode, &constants);
+    assert_eq!(exec.stack.values, vec![Value::String("".to_string())]);
+}
+
+#[test]
+fn test_nullish_coalesce_keeps_operand_value() {
+    // null ?? 5
+    let mut exec = Executor::new();
+    let bytecode = Bytecode::new(vec![
+        Instruction::PushConst(0),
+        Instruction::Dup,
+        Instruction::JumpIfNotNullish(5),
+        Instruction::Pop,
+        Instruction::PushConst(1),
+    ]);
+    let constants = vec![Value::Null, Value::Number(5.0)];
+    exec.execute(&bytecode, &constants);
+    assert_eq!(exec.stack.values, vec![Value::Number(5.0)]);
+
+    // 0 ?? 5 mantém o 0
+    let mut exec = Executor::new();
+    let constants = vec![Value::Number(0.0), Value::Number(5.0)];
+    exec.execute(&bytecode, &constants);
+    assert_eq!(exec.stack.values, vec![Value::Number(0.0)]);
+}
+
+/// Executa `base?.x.y`, com `base` construído pelas instruções dadas
+fn optional_chain(base: Vec<Instruction>) -> Vec<Value> {
+    let mut instructions = base;
+    let start = instructions.len();
+    instructions.extend([
+        Instruction::Dup,
+        Instruction::JumpIfNullish(start + 7),
+        Instruction::PushConst(0),
+        Instruction::GetProperty,
+        Instruction::PushConst(1),
+        Instruction::GetProperty,
+        Instruction::Jump(start + 9),
+        Instruction::Pop,
+        Instruction::PushUndefined,
+    ]);
+    let constants = vec![Value::String("x".to_string()), Value::String("y".to_string()), Value::Number(5.0)];
+    let mut exec = Executor::new();
+    exec.execute(&Bytecode::new(instructions), &constants);
+    exec.stack.values
+}
+
+#[test]
+fn test_optional_chain_short_circuits() {
+    // null?.x.y não acessa .y: resulta em undefined
+    assert_eq!(optional_chain(vec![Instruction::PushNull]), vec![Value::Undefined]);
+
+    // ({x: {y: 5}})?.x.y
+    let object = vec![
+        Instruction::NewObject,
+        Instruction::Dup,
+        Instruction::PushConst(0),
+        Instruction::NewObject,
+        Instruction::Dup,
+        Instruction::PushConst(1),
+        Instruction::PushConst(2),
+        Instruction::SetProperty,
+        Instruction::SetProperty,
+    ];
+    assert_eq!(optional_chain(object), vec![Value::Number(5.0)]);
+}
+
+fn compare(instruction: Instruction, a: Value, b: Value) -> Value {
+    let mut exec = Executor::new();
+    let bytecode = Bytecode::new(vec![Instruction::PushConst(0), Instruction::PushConst(1), instruction]);
+    exec.execute(&bytecode, &[a, b]);
+    exec.stack.pop().unwrap()
+}
+
+#[test]
+fn test_compare_strings_lexicographically() {
+    let apple = Value::String("apple".to_string());
+    let banana = Value::String("banana".to_string());
+    assert_eq!(compare(Instruction::Lt, apple.clone(), banana.clone()), Value::Boolean(true));
+    assert_eq!(compare(Instruction::Gt, apple.clone(), banana.clone()), Value::Boolean(false));
+    assert_eq!(compare(Instruction::Le, apple.clone(), apple.clone()), Value::Boolean(true));
+    // "10" < "9" como strings
+    assert_eq!(
+        compare(Instruction::Lt, Value::String("10".to_string()), Value::String("9".to_string())),
+        Value::Boolean(true)
+    );
+}
+
+#[test]
+fn test_compare_with_nan_is_false() {
+    for instruction in [Instruction::Lt, Instruction::Gt, Instruction::Le, Instruction::Ge] {
+        assert_eq!(compare(instruction.clone(), Value::Number(f64::NAN), Value::Number(1.0)), Value::Boolean(false));
+        assert_eq!(compare(instruction, Value::Number(1.0), Value::Undefined), Value::Boolean(false));
+    }
+}
+
+#[test]
+fn test_compare_numbers_and_mixed_operands() {
+    assert_eq!(compare(Instruction::Lt, Value::Number(1.0), Value::Number(2.0)), Value::Boolean(true));
+    assert_eq!(compare(Instruction::Ge, Value::Number(2.0), Value::Number(2.0)), Value::Boolean(true));
+    assert_eq!(compare(Instruction::Le, Value::Number(-0.0), Value::Number(0.0)), Value::Boolean(true));
+    assert_eq!(compare(Instruction::Lt, Value::Number(-0.0), Value::Number(0.0)), Value::Boolean(false));
+    // Número contra string converte com ToNumber
+    assert_eq!(compare(Instruction::Lt, Value::Number(9.0), Value::String("10".to_string())), Value::Boolean(true));
+    assert_eq!(compare(Instruction::Gt, Value::Boolean(true), Value::Null), Value::Boolean(true));
+}
+
+fn array_elements(exec: &Executor, value: &Value) -> Vec<Value> {
+    match value {
+        Value::Array(handle) => match exec.heap.get(*handle) {
+            Some(HeapEntry::Array(elements)) => elements.to_vec(),
+            _ => panic!("Array não encontrado no heap"),
+        },
+        other => panic!("Esperado array, obtido {:?}", other),
+    }
+}
+
+fn sum(args: &[Value]) -> Result<Value, String> {
+    Ok(Value::Number(args.iter().map(Value::to_number).sum()))
+}
+
+fn reject(args: &[Value]) -> Result<Value, String> {
+    Err(format!("TypeError: {} is not accepted", args[0].to_string()))
+}
+
+#[test]
+fn test_native_error_is_caught_as_error_object() {
+    let mut exec = Executor::new();
+    let reject = exec.heap.alloc_native_function("reject", reject);
+    exec.globals[0] = Value::Function(reject);
+    // try { reject(1); } catch (e) { e.message }
+    let bytecode = Bytecode::new(vec![
+        Instruction::Try(5),
+        Instruction::PushConst(0),
+        Instruction::LoadGlobal(0),
+        Instruction::Call(1),
+        Instruction::EndTry,
+        Instruction::StoreLocal(0),
+        Instruction::LoadLocal(0),
+        Instruction::PushConst(1),
+        Instruction::GetProperty,
+    ]);
+    let constants = vec![Value::Number(1.0), Value::String("message".to_string())];
+    exec.execute(&bytecode, &constants);
+    assert!(exec.exception.is_none());
+    assert_eq!(exec.stack.values, vec![Value::String("1 is not accepted".to_string())]);
+
+    // Uncaught, the error escapes with its name
+    exec.stack.values.clear();
+    exec.execute(&Bytecode::new(bytecode.instructions[1..4].to_vec()), &constants);
+    match exec.exception.as_ref().map(|(value, _)| value) {
+        Some(Value::Object(handle)) => {
+            assert_eq!(exec.heap.get_object_property(*handle, "name"), Some(&Value::String("TypeError".to_string())));
+        }
+        other => panic!("Expected an Error object, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_rest_elements_collect_remaining_items() {
+    let mut exec = Executor::new();
+    let array = exec.heap.alloc_entry(HeapEntry::Array(vec![Value::Number(1.0), Value::Number(2.0), Value::Number(3.0)].into()));
+    exec.globals[0] = Value::Array(array);
+    // [a, ...rest] = [1, 2, 3]: rest recebe os elementos a partir do índice 1
+    exec.execute(&Bytecode::new(vec![Instruction::LoadGlobal(0), Instruction::RestElements(1)]), &[]);
+    let rest = exec.stack.pop().unwrap();
+    assert_eq!(array_elements(&exec, &rest), vec![Value::Number(2.0), Value::Number(3.0)]);
+    // Um índice além do fim resulta num array vazio
+    exec.execute(&Bytecode::new(vec![Instruction::LoadGlobal(0), Instruction::RestElements(5)]), &[]);
+    let rest = exec.stack.pop().unwrap();
+    assert!(array_elements(&exec, &rest).is_empty());
+}
+
+#[test]
+fn test_rest_properties_skip_destructured_keys() {
+    let mut exec = Executor::new();
+    let object = exec.heap.alloc_object();
+    for (key, value) in [("a", 1.0), ("b", 2.0), ("c", 3.0)] {
+        exec.heap.set_object_property(object, key.to_string(), Value::Number(value));
+    }
+    exec.globals[0] = Value::Object(object);
+    // ({a, ...rest} = {a: 1, b: 2, c: 3})
+    let bytecode = Bytecode::new(vec![Instruction::LoadGlobal(0), Instruction::PushConst(0), Instruction::RestProperties(1)]);
+    exec.execute(&bytecode, &[Value::String("a".to_string())]);
+    let Some(Value::Object(rest)) = exec.stack.pop() else { panic!("Esperava um objeto") };
+    let Some(HeapEntry::Object(properties)) = exec.heap.get(rest) else { panic!("Objeto não encontrado no heap") };
+    assert_eq!(properties.keys(), vec!["b".to_string(), "c".to_string()]);
+    assert_eq!(exec.heap.get_object_property(rest, "b"), Some(&Value::Number(2.0)));
+    assert_eq!(exec.heap.get_object_property(rest, "c"), Some(&Value::Number(3.0)));
+    assert_ne!(rest, object);
+}
+
+#[test]
+fn test_rest_properties_of_null_throws_type_error() {
+    let mut exec = Executor::new();
+    exec.execute(&Bytecode::new(vec![Instruction::PushNull, Instruction::RestProperties(0)]), &[]);
+    let (value, _) = exec.exception.expect("esperava exceção");
+    assert_eq!(value, Value::String("TypeError: Cannot destructure 'null' as it is null.".to_string()));
+}
+
+#[test]
+fn test_array_spread_concatenates_elements() {
+    let mut exec = Executor::new();
+    let a = exec.heap.alloc_entry(HeapEntry::Array(vec![Value::Number(1.0), Value::Number(2.0)].into()));
+    let b = exec.heap.alloc_entry(HeapEntry::Array(vec![Value::Number(4.0)].into()));
+    exec.globals[0] = Value::Array(a);
+    exec.globals[1] = Value::Array(b);
+    // [...a, 3, ...b]
+    let bytecode = Bytecode::new(vec![
+        Instruction::NewArray(0),
+        Instruction::LoadGlobal(0),
+        Instruction::Spread,
+        Instruction::PushConst(0),
+        Instruction::NewArray(1),
+        Instruction::Spread,
+        Instruction::LoadGlobal(1),
+        Instruction::Spread,
+    ]);
+    exec.execute(&bytecode, &[Value::Number(3.0)]);
+    let result = exec.stack.pop().unwrap();
+    assert_eq!(
+        array_elements(&exec, &result),
+        vec![Value::Number(1.0), Value::Number(2.0), Value::Number(3.0), Value::Number(4.0)]
+    );
+    assert!(exec.stack.values.is_empty());
+}
+
+#[test]
+fn test_call_spread_into_native_function() {
+    let mut exec = Executor::new();
+    let sum = exec.heap.alloc_native_function("sum", sum);
+    let args = exec.heap.alloc_entry(HeapEntry::Array(vec![Value::Number(2.0), Value::Number(3.0)].into()));
+    exec.globals[0] = Value::Function(sum);
+    exec.globals[1] = Value::Array(args);
+    // sum(1, ...args)
+    let bytecode = Bytecode::new(vec![
+        Instruction::PushConst(0),
+        Instruction::NewArray(1),
+        Instruction::LoadGlobal(1),
+        Instruction::Spread,
+        Instruction::LoadGlobal(0),
+        Instruction::CallWithSpread,
+    ]);
+    exec.execute(&bytecode, &[Value::Number(1.0)]);
+    assert_eq!(exec.stack.values, vec![Value::Number(6.0)]);
+}
+
+#[test]
+fn test_object_spread_later_properties_win() {
+    let mut exec = Executor::new();
+    let o1 = exec.heap.alloc_object();
+    exec.heap.set_object_property(o1, "a".to_string(), Value::Number(1.0));
+    exec.heap.set_object_property(o1, "b".to_string(), Value::Number(2.0));
+    let o2 = exec.heap.alloc_object();
+    exec.heap.set_object_property(o2, "b".to_string(), Value::Number(3.0));
+    exec.globals[0] = Value::Object(o1);
+    exec.globals[1] = Value::Object(o2);
+    // {a: 0, ...o1, ...o2}: propriedades posteriores sobrescrevem as anteriores
+    let bytecode = Bytecode::new(vec![
+        Instruction::NewObject,
+        Instruction::Dup,
+        Instruction::PushConst(0),
+        Instruction::PushConst(1),
+        Instruction::SetProperty,
+        Instruction::LoadGlobal(0),
+        Instruction::Spread,
+        Instruction::LoadGlobal(1),
+        Instruction::Spread,
+    ]);
+    exec.execute(&bytecode, &[Value::String("a".to_string()), Value::Number(0.0)]);
+    let handle = match exec.stack.pop().unwrap() {
+        Value::Object(handle) => handle,
+        other => panic!("Esperado objeto, obtido {:?}", other),
+    };
+    assert_eq!(exec.heap.get_object_property(handle, "a"), Some(&Value::Number(1.0)));
+    assert_eq!(exec.heap.get_object_property(handle, "b"), Some(&Value::Number(3.0)));
+}
+
+#[test]
+fn test_spread_non_iterable_throws_type_error() {
+    let mut exec = Executor::new();
+    let bytecode = Bytecode::new(vec![
+        Instruction::NewArray(0),
+        Instruction::PushConst(0),
+        Instruction::Spread,
+    ]);
+    exec.execute(&bytecode, &[Value::Number(5.0)]);
+    let (value, _) = exec.exception.expect("esperava exceção");
+    assert_eq!(value, Value::String("TypeError: 5 is not iterable".to_string()));
+}
+
+/// `next()` de um iterador sobre o array em `values`, a partir de `index`
+fn list_next(exec: &mut Executor, this: Value, _args: &[Value]) -> Result<Value, Value> {
+    let Value::Object(iterator) = this else { return Err(Value::String("not an iterator".to_string())) };
+    let index = exec.heap.get_object_property(iterator, "index").map_or(0.0, Value::to_number) as usize;
+    let value = match exec.heap.get_object_property(iterator, "values") {
+        Some(Value::Array(values)) => exec.heap.get_array_element(*values, index).cloned(),
+        _ => None,
+    };
+    exec.heap.set_object_property(iterator, "index".to_string(), Value::Number(index as f64 + 1.0));
+    let step = exec.heap.alloc_object();
+    exec.heap.set_object_property(step, "done".to_string(), Value::Boolean(value.is_none()));
+    exec.heap.set_object_property(step, "value".to_string(), value.unwrap_or(Value::Undefined));
+    Ok(Value::Object(step))
+}
+
+/// Iterador sobre os valores dados
+fn list_iterator(exec: &mut Executor, values: Vec<Value>) -> Value {
+    let values = exec.heap.alloc_entry(HeapEntry::Array(values.into()));
+    let next = exec.heap.alloc_native_method("next", list_next);
+    let iterator = exec.heap.alloc_object();
+    exec.heap.set_object_property(iterator, "values".to_string(), Value::Array(values));
+    exec.heap.set_object_property(iterator, "next".to_string(), Value::Function(next));
+    Value::Object(iterator)
+}
+
+/// `[Symbol.iterator]()` de um conjunto: itera os valores guardados em `entries`
+fn set_values(exec: &mut Executor, this: Value, _args: &[Value]) -> Result<Value, Value> {
+    let entries = match this {
+        Value::Object(set) => exec.heap.get_object_property(set, "entries").cloned(),
+        _ => None,
+    };
+    let values = match entries {
+        Some(Value::Array(entries)) => match exec.heap.get(entries) {
+            Some(HeapEntry::Array(values)) => values.to_vec(),
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    };
+    Ok(list_iterator(exec, values))
+}
+
+#[test]
+fn test_spread_set_through_iterator_protocol() {
+    let mut exec = Executor::new();
+    // new Set([2, 3]), que não é um array: só o protocolo de iteração o percorre
+    let entries = exec.heap.alloc_entry(HeapEntry::Array(vec![Value::Number(2.0), Value::Number(3.0)].into()));
+    let method = exec.heap.alloc_native_method("[Symbol.iterator]", set_values);
+    let set = exec.heap.alloc_object();
+    exec.heap.set_object_property(set, "entries".to_string(), Value::Array(entries));
+    exec.heap.set_object_property(set, ITERATOR_KEY.to_string(), Value::Function(method));
+    exec.globals[0] = Value::Object(set);
+    // [1, ...set, 4]
+    let bytecode = Bytecode::new(vec![
+        Instruction::PushConst(0),
+        Instruction::NewArray(1),
+        Instruction::LoadGlobal(0),
+        Instruction::Spread,
+        Instruction::Dup,
+        Instruction::PushConst(1),
+        Instruction::NewArray(1),
+        Instruction::Spread,
+    ]);
+    exec.execute(&bytecode, &[Value::Number(1.0), Value::Number(4.0)]);
+    assert!(exec.exception.is_none());
+    let array = match exec.stack.values.as_slice() {
+        [Value::Array(array), Value::Array(same)] if array == same => *array,
+        other => panic!("Esperado o array na pilha, obtido {:?}", other),
+    };
+    let expected: Vec<Value> = [1.0, 2.0, 3.0, 4.0].into_iter().map(Value::Number).collect();
+    assert!(matches!(exec.heap.get(array), Some(HeapEntry::Array(elements)) if *elements == expected));
+}
+
+#[test]
+fn test_rest_collects_remaining_iterator_values() {
+    let mut exec = Executor::new();
+    let values = vec![Value::Number(1.0), Value::Number(2.0), Value::Number(3.0)];
+    exec.globals[0] = list_iterator(&mut exec, values);
+    // let [, ...rest] = iterator
+    let bytecode = Bytecode::new(vec![Instruction::LoadGlobal(0), Instruction::RestElements(1)]);
+    exec.execute(&bytecode, &[]);
+    let rest = match exec.stack.values.as_slice() {
+        [Value::Array(rest)] => *rest,
+        other => panic!("Esperado só o array do resto, obtido {:?}", other),
+    };
+    let expected = vec![Value::Number(2.0), Value::Number(3.0)];
+    assert!(matches!(exec.heap.get(rest), Some(HeapEntry::Array(elements)) if *elements == expected));
+}
+
+#[test]
+fn test_spread_iterator_with_non_object_result_throws() {
+    fn bad_next(_: &mut Executor, _: Value, _: &[Value]) -> Result<Value, Value> {
+        Ok(Value::Number(1.0))
+    }
+    let mut exec = Executor::new();
+    let next = exec.heap.alloc_native_method("next", bad_next);
+    let iterator = exec.heap.alloc_object();
+    exec.heap.set_object_property(iterator, "next".to_string(), Value::Function(next));
+    exec.globals[0] = Value::Object(iterator);
+    let bytecode = Bytecode::new(vec![
+        Instruction::NewArray(0),
+        Instruction::LoadGlobal(0),
+        Instruction::Spread,
+    ]);
+    exec.execute(&bytecode, &[]);
+    let (Value::Object(error), _) = exec.exception.expect("esperava exceção") else { panic!("esperava um objeto Error") };
+    assert_eq!(
+        exec.heap.get_object_property(error, "message"),
+        Some(&Value::String("Iterator result 1 is not an object".to_string()))
+    );
+}
+
+#[test]
+fn test_tail_call_reuses_frame() {
+    let mut exec = Executor::new();
+    // function countdown(n) { if (n === 0) return "done"; return countdown(n - 1); }
+    let countdown = exec.heap.alloc_function(
+        Bytecode::new(vec![
+            Instruction::LoadArg(0),
+            Instruction::PushConst(0), // 0
+            Instruction::StrictEq,
+            Instruction::JumpIfFalse(6),
+            Instruction::PushConst(1), // "done"
+            Instruction::Return,
+            Instruction::LoadArg(0),
+            Instruction::PushConst(2), // 1
+            Instruction::Sub,
+            Instruction::LoadThisFunction,
+            Instruction::TailCall(1),
+        ]),
+        1,
+        0,
+    );
+    exec.globals[0] = Value::Function(countdown);
+    // countdown(1000000) === "done"; a recursão comum estouraria a pilha nativa
+    let bytecode = Bytecode::new(vec![
+        Instruction::PushConst(3),
+        Instruction::LoadGlobal(0),
+        Instruction::Call(1),
+        Instruction::PushConst(1),
+        Instruction::StrictEq,
+    ]);
+    let constants = vec![
+        Value::Number(0.0),
+        Value::String("done".to_string()),
+        Value::Number(1.0),
+        Value::Number(1_000_000.0),
+    ];
+    exec.execute(&bytecode, &constants);
+    assert!(exec.exception.is_none());
+    assert_eq!(exec.stack.values, vec![Value::Boolean(true)]);
+    assert!(exec.stack.frames.is_empty());
+}
+
+fn function_info(name: &str, instructions: Vec<Instruction>) -> FunctionInfo {
+    FunctionInfo {
+        name: name.to_string(),
+        bytecode: Bytecode::new(instructions),
+        arg_count: 0,
+        local_count: 0,
+        captures: Vec::new(),
+        is_async: false,
+    }
+}
+
+#[test]
+fn test_create_function_is_callable() {
+    let mut exec = Executor::new();
+    // (function(){ return 7; })()
+    let bytecode = Bytecode::with_functions(
+        vec![Instruction::CreateFunction(0), Instruction::Call(0)],
+        vec![function_info("", vec![Instruction::PushConst(0), Instruction::Return])],
+    );
+    exec.execute(&bytecode, &[Value::Number(7.0)]);
+    assert!(exec.exception.is_none());
+    assert_eq!(exec.stack.values, vec![Value::Number(7.0)]);
+}
+
+#[test]
+fn test_create_function_captures_locals() {
+    let mut exec = Executor::new();
+    // let x = 5; let f = () => x; x = 6; f() — a captura é por valor, na criação
+    let mut arrow = function_info("", vec![Instruction::LoadClosureVar("x".to_string()), Instruction::Return]);
+    arrow.captures = vec![("x".to_string(), Capture::Local(0))];
+    let bytecode = Bytecode::with_functions(
+        vec![
+            Instruction::PushConst(0),
+            Instruction::StoreLocal(0),
+            Instruction::CreateFunction(0),
+            Instruction::StoreLocal(1),
+            Instruction::PushConst(1),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(1),
+            Instruction::Call(0),
+        ],
+        vec![arrow],
+    );
+    exec.execute(&bytecode, &[Value::Number(5.0), Value::Number(6.0)]);
+    assert_eq!(exec.stack.values, vec![Value::Number(5.0)]);
+}
+
+#[test]
+fn test_class_instance_method_is_callable() {
+    let mut exec = Executor::new();
+    // class C { m() { return 7; } } new C().m()
+    let bytecode = Bytecode::with_functions(
+        vec![
+            Instruction::CreateFunction(0),
+            Instruction::PushConst(1),
+            Instruction::CreateFunction(1),
+            Instruction::CreateClass(1),
+            Instruction::New(0),
+            Instruction::PushConst(1),
+            Instruction::GetProperty,
+            Instruction::Call(0),
+        ],
+        vec![
+            function_info("C", vec![Instruction::PushUndefined, Instruction::Return]),
+            function_info("m", vec![Instruction::PushConst(0), Instruction::Return]),
+        ],
+    );
+    exec.execute(&bytecode, &[Value::Number(7.0), Value::String("m".to_string())]);
+    assert!(exec.exception.is_none());
+    assert_eq!(exec.stack.values, vec![Value::Number(7.0)]);
+}
+
+#[test]
+fn test_new_on_non_function_throws_type_error() {
+    let mut exec = Executor::new();
+    exec.execute(&Bytecode::new(vec![Instruction::PushConst(0), Instruction::New(0)]), &[Value::Number(1.0)]);
+    match &exec.exception {
+        Some((Value::String(message), _)) => assert!(message.starts_with("TypeError")),
+        other => panic!("Expected TypeError, got {:?}", other),
+    }
+}
+
+/// function P(x) { this.x = x; return result; }, com `result` empilhado por `returned`
+fn constructor(exec: &mut Executor, returned: Instruction) -> Value {
+    let body = Bytecode::new(vec![
+        Instruction::LoadThis,
+        Instruction::PushConst(1), // "x"
+        Instruction::LoadArg(0),
+        Instruction::SetProperty,
+        returned,
+        Instruction::Return,
+    ]);
+    Value::Function(exec.heap.alloc_function(body, 1, 0))
+}
+
+/// Executa `new P(5)` seguido de `tail`, com P na global 0
+fn run_new(returned: Instruction, tail: Vec<Instruction>) -> Executor {
+    let mut exec = Executor::new();
+    exec.globals[0] = constructor(&mut exec, returned);
+    let mut instructions = vec![Instruction::PushConst(0), Instruction::LoadGlobal(0), Instruction::New(1)];
+    instructions.extend(tail);
+    let constants = ["x", "prototype", "constructor"].map(|key| Value::String(key.to_string()));
+    let constants: Vec<Value> = std::iter::once(Value::Number(5.0)).chain(constants).collect();
+    exec.execute(&Bytecode::new(instructions), &constants);
+    assert!(exec.exception.is_none(), "{:?}", exec.exception);
+    exec
+}
+
+#[test]
+fn test_new_binds_this_to_the_instance() {
+    // new P(5).x
+    let exec = run_new(Instruction::PushUndefined, vec![Instruction::PushConst(1), Instruction::GetProperty]);
+    assert_eq!(exec.stack.values, vec![Value::Number(5.0)]);
+}
+
+#[test]
+fn test_new_links_instance_to_function_prototype() {
+    // new P(5) instanceof P
+    let exec = run_new(Instruction::PushUndefined, vec![Instruction::LoadGlobal(0), Instruction::InstanceOf]);
+    assert_eq!(exec.stack.values, vec![Value::Boolean(true)]);
+    // P.prototype.constructor === P
+    let exec = run_new(
+        Instruction::PushUndefined,
+        vec![
+            Instruction::Pop,
+            Instruction::LoadGlobal(0),
+            Instruction::PushConst(2),
+            Instruction::GetProperty,
+            Instruction::PushConst(3),
+            Instruction::GetProperty,
+            Instruction::LoadGlobal(0),
+            Instruction::StrictEq,
+        ],
+    );
+    assert_eq!(exec.stack.values.last(), Some(&Value::Boolean(true)));
+}
+
+#[test]
+fn test_new_uses_object_returned_by_constructor() {
+    // Um objeto retornado substitui a instância: sem `x` e sem P no protótipo
+    let exec = run_new(Instruction::NewObject, vec![Instruction::PushConst(1), Instruction::GetProperty]);
+    assert_eq!(exec.stack.values, vec![Value::Undefined]);
+    let exec = run_new(Instruction::NewObject, vec![Instruction::LoadGlobal(0), Instruction::InstanceOf]);
+    assert_eq!(exec.stack.values, vec![Value::Boolean(false)]);
+    // Um primitivo retornado é ignorado
+    let exec = run_new(Instruction::PushConst(0), vec![Instruction::PushConst(1), Instruction::GetProperty]);
+    assert_eq!(exec.stack.values, vec![Value::Number(5.0)]);
+}
+
+#[test]
+fn test_heap_stats_count_allocations() {
+    let mut exec = Executor::new();
+    exec.execute(&Bytecode::new(vec![Instruction::NewObject; 100]), &[]);
+    let stats = exec.get_heap_stats();
+    assert_eq!(stats.object_count, 100);
+    assert_eq!(stats.array_count, 0);
+}
+
+#[test]
+fn test_gc_hint_frees_unreachable_entries() {
+    let mut exec = Executor::new();
+    // 10 objetos descartados, um array guardado numa global e um objeto numa local
+    let mut instructions: Vec<Instruction> = (0..10).flat_map(|_| [Instruction::NewObject, Instruction::Pop]).collect();
+    instructions.extend([
+        Instruction::NewObject,
+        Instruction::NewArray(1),
+        Instruction::StoreGlobal(0),
+        Instruction::NewObject,
+        Instruction::StoreLocal(0),
+        Instruction::GcHint,
+    ]);
+    exec.execute(&Bytecode::new(instructions), &[]);
+    let stats = exec.get_heap_stats();
+    assert_eq!(stats.object_count, 2);
+    assert_eq!(stats.array_count, 1);
+    assert_eq!(stats.free_slots, 10);
+    assert_eq!(array_elements(&exec, &exec.globals[0]).len(), 1);
+}
+
+#[test]
+fn test_heap_limit_throws_catchable_range_error() {
+    let mut exec = Executor::new();
+    exec.set_limits(ResourceLimits { max_heap_entries: Some(100), ..ResourceLimits::default() });
+    // try { while (true) pilha.push({}) } catch (e) { g = e }: os objetos ficam vivos na pilha
+    let bytecode = Bytecode::new(vec![
+        Instruction::Try(3),
+        Instruction::NewObject,
+        Instruction::Jump(1),
+        Instruction::StoreGlobal(0),
+    ]);
+    exec.execute(&bytecode, &[]);
+    assert!(exec.exception.is_none(), "{:?}", exec.exception);
+    match exec.globals.first() {
+        Some(Value::String(message)) => assert!(message.starts_with("RangeError: JavaScript heap out of memory"), "{}", message),
+        other => panic!("Esperava RangeError, obtido {:?}", other),
+    }
+    // O catch descartou os objetos: a coleta os libera
+    exec.collect_garbage();
+    assert!(exec.heap.live_count() <= 100);
+}
+
+#[test]
+fn test_heap_limit_counts_live_entries_only() {
+    let mut exec = Executor::new();
+    exec.set_limits(ResourceLimits { max_heap_entries: Some(10), ..ResourceLimits::default() });
+    // 1000 objetos descartados logo após criados: a coleta abre espaço a cada vez que o limite é atingido
+    let instructions: Vec<Instruction> = (0..1000).flat_map(|_| [Instruction::NewObject, Instruction::Pop]).collect();
+    exec.execute(&Bytecode::new(instructions), &[]);
+    assert!(exec.exception.is_none(), "{:?}", exec.exception);
+}
+
+#[test]
+fn test_call_depth_limit_throws_range_error() {
+    let mut exec = Executor::new();
+    exec.set_limits(ResourceLimits { max_call_depth: Some(50), ..ResourceLimits::default() });
+    // function f() { return 1 + f(); }: sem chamada de cauda, cada nível aninha um frame
+    let f = exec.heap.alloc_function(
+        Bytecode::new(vec![
+            Instruction::PushConst(0),
+            Instruction::LoadThisFunction,
+            Instruction::Call(0),
+            Instruction::Add,
+            Instruction::Return,
+        ]),
+        0,
+        0,
+    );
+    exec.globals[0] = Value::Function(f);
+    exec.execute(&Bytecode::new(vec![Instruction::LoadGlobal(0), Instruction::Call(0)]), &[Value::Number(1.0)]);
+    let (value, trace) = exec.exception.clone().expect("esperava exceção");
+    assert_eq!(value, Value::String("RangeError: Maximum call stack size exceeded".to_string()));
+    assert_eq!(trace.len(), 51);
+}
+
+#[test]
+fn test_call_depth_error_is_catchable() {
+    let mut exec = Executor::new();
+    exec.set_limits(ResourceLimits { max_call_depth: Some(50), ..ResourceLimits::default() });
+    let f = exec.heap.alloc_function(
+        Bytecode::new(vec![
+            Instruction::PushConst(0),
+            Instruction::LoadThisFunction,
+            Instruction::Call(0),
+            Instruction::Add,
+            Instruction::Return,
+        ]),
+        0,
+        0,
+    );
+    exec.globals[0] = Value::Function(f);
+    // try { f() } catch (e) { g = e }
+    let bytecode = Bytecode::new(vec![
+        Instruction::Try(3),
+        Instruction::LoadGlobal(0),
+        Instruction::Call(0),
+        Instruction::StoreGlobal(1),
+    ]);
+    exec.execute(&bytecode, &[Value::Number(1.0)]);
+    assert!(exec.exception.is_none(), "{:?}", exec.exception.as_ref().map(|(value, _)| value));
+    assert_eq!(exec.globals[1], Value::String("RangeError: Maximum call stack size exceeded".to_string()));
+    assert!(exec.stack.frames.is_empty());
+}
+
+/// `function f() { return 1 + f(); }` numa global, chamada por `f()`
+fn run_unbounded_recursion(exec: &mut Executor) {
+    let f = exec.heap.alloc_function(
+        Bytecode::new(vec![
+            Instruction::PushConst(0),
+            Instruction::LoadGlobal(0),
+            Instruction::Call(0),
+            Instruction::Add,
+            Instruction::Return,
+        ]),
+        0,
+        0,
+    );
+    exec.globals[0] = Value::Function(f);
+    exec.execute(&Bytecode::new(vec![Instruction::LoadGlobal(0), Instruction::Call(0)]), &[Value::Number(1.0)]);
+}
+
+#[test]
+fn test_default_limits_stop_deep_recursion() {
+    // Sem limites explícitos a recursão lança RangeError em vez de estourar a pilha nativa
+    let mut exec = Executor::new();
+    run_unbounded_recursion(&mut exec);
+    let (value, _) = exec.exception.clone().expect("esperava exceção");
+    assert_eq!(value, Value::String("RangeError: Maximum call stack size exceeded".to_string()));
+
+    // O limite de pilha vale mesmo sem limite de profundidade
+    let mut exec = Executor::new();
+    exec.set_limits(ResourceLimits { max_call_depth: None, ..ResourceLimits::default() });
+    run_unbounded_recursion(&mut exec);
+    let (value, _) = exec.exception.clone().expect("esperava exceção");
+    assert_eq!(value, Value::String("RangeError: Maximum call stack size exceeded".to_string()));
+}
+
+#[test]
+fn test_larger_stack_limit_allows_deeper_calls() {
+    let depth = std::thread::Builder::new()
+        .stack_size(128 << 20)
+        .spawn(|| {
+            let mut exec = Executor::new();
+            exec.set_limits(ResourceLimits { max_stack_size: Some(96 << 20), ..ResourceLimits::default() });
+            run_unbounded_recursion(&mut exec);
+            exec.exception.map(|(_, trace)| trace.len())
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    // Agora quem para a recursão é o limite de profundidade padrão
+    assert_eq!(depth, Some(ResourceLimits::DEFAULT_MAX_CALL_DEPTH + 1));
+}
+
+#[test]
+fn test_call_function_runs_script_function_with_this() {
+    let mut exec = Executor::new();
+    // function (a) { return this + a * 10 }
+    let f = exec.heap.alloc_function(
+        Bytecode::new(vec![
+            Instruction::LoadThis,
+            Instruction::LoadArg(0),
+            Instruction::PushConst(0),
+            Instruction::Mul,
+            Instruction::Add,
+            Instruction::Return,
+        ]),
+        1,
+        0,
+    );
+    exec.execute(&Bytecode::new(Vec::new()), &[Value::Number(10.0)]);
+    let result = exec.call_function(&Value::Function(f), Value::Number(1.0), &[Value::Number(2.0)]);
+    assert_eq!(result, Ok(Value::Number(21.0)));
+    assert!(exec.stack.values.is_empty());
+    assert!(exec.stack.frames.is_empty());
+}
+
+#[test]
+fn test_call_function_returns_thrown_value() {
+    let mut exec = Executor::new();
+    let f = exec.heap.alloc_function(Bytecode::new(vec![Instruction::PushConst(0), Instruction::Throw]), 0, 0);
+    exec.execute(&Bytecode::new(Vec::new()), &[Value::String("boom".to_string())]);
+    let result = exec.call_function(&Value::Function(f), Value::Undefined, &[]);
+    assert_eq!(result, Err(Value::String("boom".to_string())));
+    assert!(exec.exception.is_none());
+
+    // Chamar algo que não é função é um TypeError
+    let Err(Value::Object(error)) = exec.call_function(&Value::Number(1.0), Value::Undefined, &[]) else {
+        panic!("Expected a TypeError object");
+    };
+    assert_eq!(exec.heap.get_object_property(error, "name"), Some(&Value::String("TypeError".to_string())));
+}
@@ -67,4 +67,26 @@ fn test_value_equality() {
     
     assert_eq!(str1, str2);
     assert_ne!(str1, str3);
-} 
\ No newline at end of file
+} 
+#[test]
+fn test_heap_handle_round_trips() {
+    let mut heap = Heap::new();
+    let handle = heap.alloc_object();
+    heap.set_object_property(handle, "x".to_string(), Value::Number(1.0));
+    assert_eq!(heap.get_object_property(handle, "x"), Some(&Value::Number(1.0)));
+    assert!(matches!(heap.get(handle), Some(HeapEntry::Object(_))));
+}
+
+#[test]
+fn test_heap_collect_reuses_freed_slots() {
+    let mut heap = Heap::new();
+    let kept = heap.alloc_array();
+    let inner = heap.alloc_object();
+    let garbage = heap.alloc_object();
+    heap.push_array_element(kept, Value::Object(inner));
+    assert_eq!(heap.collect([kept]), 1);
+    assert!(heap.get(garbage).is_none());
+    assert_eq!(heap.stats().object_count, 1);
+    assert_eq!(heap.alloc_object(), garbage);
+    assert_eq!(heap.stats().free_slots, 0);
+}
@@ -0,0 +1,146 @@
+use v8_vm::bytecode::Bytecode;
+use v8_vm::executor::Executor;
+use v8_vm::heap::HandleId;
+use v8_vm::inline_cache::InlineCacheStats;
+use v8_vm::instructions::Instruction;
+use v8_vm::shape::{PropertyMap, EMPTY_SHAPE};
+use v8_vm::value::Value;
+
+fn object(exec: &mut Executor, properties: &[(&str, f64)]) -> HandleId {
+    let handle = exec.heap.alloc_object();
+    for (key, value) in properties {
+        exec.heap.set_object_property(handle, key.to_string(), Value::Number(*value));
+    }
+    handle
+}
+
+/// `obj[key]`, com obj e key nas constantes 0 e 1
+fn get_property() -> Bytecode {
+    Bytecode {
+        instructions: vec![Instruction::PushConst(0), Instruction::PushConst(1), Instruction::GetProperty],
+        functions: Vec::new(),
+    }
+}
+
+#[test]
+fn test_shapes_follow_key_order() {
+    let mut a = PropertyMap::new();
+    let mut b = PropertyMap::new();
+    assert_eq!(a.shape(), EMPTY_SHAPE);
+    a.insert("x".to_string(), Value::Number(1.0));
+    a.insert("y".to_string(), Value::Number(2.0));
+    b.insert("x".to_string(), Value::Number(3.0));
+    b.insert("y".to_string(), Value::Number(4.0));
+    assert_eq!(a.shape(), b.shape());
+
+    // Sobrescrever uma chave mantém o shape
+    let shape = a.shape();
+    a.insert("x".to_string(), Value::Number(5.0));
+    assert_eq!(a.shape(), shape);
+
+    let mut c = PropertyMap::new();
+    c.insert("y".to_string(), Value::Number(1.0));
+    c.insert("x".to_string(), Value::Number(2.0));
+    assert_ne!(c.shape(), a.shape());
+
+    // Remover uma chave leva ao shape das chaves restantes
+    a.remove("x");
+    let mut d = PropertyMap::new();
+    d.insert("y".to_string(), Value::Number(2.0));
+    assert_eq!(a.shape(), d.shape());
+    assert_eq!(a.get("y"), Some(&Value::Number(2.0)));
+    assert_eq!(a.keys(), vec!["y".to_string()]);
+}
+
+#[test]
+fn test_repeated_access_hits_inline_cache() {
+    // for (let i = 0; i < 1000; i++) sum += o.x;  (o em locals[2])
+    let mut exec = Executor::new();
+    let o = object(&mut exec, &[("a", 1.0), ("x", 2.0)]);
+    let bytecode = Bytecode {
+        instructions: vec![
+            Instruction::PushConst(0),
+            Instruction::StoreLocal(0), // i = 0
+            Instruction::PushConst(0),
+            Instruction::StoreLocal(1), // sum = 0
+            Instruction::LoadLocal(0),  // 4: i < 1000
+            Instruction::PushConst(1),
+            Instruction::Lt,
+            Instruction::JumpIfFalse(19),
+            Instruction::LoadLocal(1),
+            Instruction::PushConst(2),
+            Instruction::PushConst(3),
+            Instruction::GetProperty, // o.x
+            Instruction::Add,
+            Instruction::StoreLocal(1),
+            Instruction::LoadLocal(0),
+            Instruction::PushConst(4),
+            Instruction::Add,
+            Instruction::StoreLocal(0), // i++
+            Instruction::Jump(4),
+            Instruction::LoadLocal(1), // 19
+        ],
+        functions: Vec::new(),
+    };
+    let constants = vec![
+        Value::Number(0.0),
+        Value::Number(1000.0),
+        Value::Object(o),
+        Value::String("x".to_string()),
+        Value::Number(1.0),
+    ];
+    exec.execute(&bytecode, &constants);
+    assert_eq!(exec.stack.values, vec![Value::Number(2000.0)]);
+    // Só o primeiro acesso procura a chave
+    assert_eq!(exec.inline_cache_stats, InlineCacheStats { hits: 999, misses: 1 });
+}
+
+#[test]
+fn test_shape_changes_invalidate_inline_cache() {
+    let mut exec = Executor::new();
+    let first = object(&mut exec, &[("x", 1.0), ("y", 2.0)]);
+    let same_shape = object(&mut exec, &[("x", 3.0), ("y", 4.0)]);
+    let other_shape = object(&mut exec, &[("y", 5.0), ("x", 6.0)]);
+    let key = Value::String("y".to_string());
+
+    let read = |exec: &mut Executor, handle: HandleId| {
+        exec.execute(&get_property(), &[Value::Object(handle), key.clone()]);
+        exec.stack.pop().unwrap()
+    };
+    assert_eq!(read(&mut exec, first), Value::Number(2.0));
+    assert_eq!(read(&mut exec, same_shape), Value::Number(4.0));
+    assert_eq!(exec.inline_cache_stats, InlineCacheStats { hits: 1, misses: 1 });
+
+    // Mesmo site, layout diferente: o slot em cache não pode ser reaproveitado
+    assert_eq!(read(&mut exec, other_shape), Value::Number(5.0));
+    assert_eq!(exec.inline_cache_stats.misses, 2);
+
+    // Apagar uma chave muda o shape e desloca os slots
+    exec.heap.remove_object_property(other_shape, "y");
+    assert_eq!(read(&mut exec, other_shape), Value::Undefined);
+    exec.heap.set_object_property(other_shape, "y".to_string(), Value::Number(7.0));
+    assert_eq!(read(&mut exec, other_shape), Value::Number(7.0));
+}
+
+#[test]
+fn test_set_property_uses_inline_cache() {
+    // obj.x = 9 duas vezes no mesmo site, em objetos do mesmo shape
+    let mut exec = Executor::new();
+    let a = object(&mut exec, &[("x", 1.0)]);
+    let b = object(&mut exec, &[("x", 2.0)]);
+    let bytecode = Bytecode {
+        instructions: vec![
+            Instruction::PushConst(0),
+            Instruction::PushConst(1),
+            Instruction::PushConst(2),
+            Instruction::SetProperty,
+        ],
+        functions: Vec::new(),
+    };
+    let x = Value::String("x".to_string());
+    exec.execute(&bytecode, &[Value::Object(a), x.clone(), Value::Number(9.0)]);
+    exec.execute(&bytecode, &[Value::Object(b), x.clone(), Value::Number(9.0)]);
+    assert_eq!(exec.inline_cache_stats, InlineCacheStats { hits: 1, misses: 1 });
+    assert_eq!(exec.heap.get_object_property(a, "x"), Some(&Value::Number(9.0)));
+    assert_eq!(exec.heap.get_object_property(b, "x"), Some(&Value::Number(9.0)));
+}
@@ -16,6 +16,7 @@ fn test_new_object_and_set_get_property() {
             Instruction::PushConst(0), // empilha chave "foo"
             Instruction::GetProperty, // obj["foo"]
         ],
+        functions: Vec::new(),
     };
     let constants = vec![Value::String("foo".to_string()), Value::Number(123.0)];
     exec.execute(&bytecode, &constants);
@@ -32,9 +33,196 @@ fn test_new_array_and_push_get_element() {
     exec.heap.push_array_element(arr_handle, Value::String("abc".to_string()));
     // Verifica elementos
     if let Some(HeapEntry::Array(arr)) = exec.heap.get(arr_handle) {
-        assert_eq!(arr[0], Value::Number(10.0));
-        assert_eq!(arr[1], Value::String("abc".to_string()));
+        assert_eq!(arr.get(0), Some(&Value::Number(10.0)));
+        assert_eq!(arr.get(1), Some(&Value::String("abc".to_string())));
     } else {
         panic!("Array não encontrado no heap");
     }
-} 
\ No newline at end of file
+} 
+#[test]
+fn test_array_handles_alias_same_elements() {
+    // let a = [1]; let b = a; b[1] = 2; a.length
+    let mut exec = Executor::new();
+    let bytecode = Bytecode {
+        instructions: vec![
+            Instruction::PushConst(0),
+            Instruction::NewArray(1),
+            Instruction::StoreLocal(0), // a
+            Instruction::LoadLocal(0),
+            Instruction::StoreLocal(1), // b = a (copia o handle)
+            Instruction::LoadLocal(1),
+            Instruction::PushConst(0),
+            Instruction::PushConst(1),
+            Instruction::SetProperty, // b[1] = 2
+            Instruction::LoadLocal(0),
+            Instruction::PushConst(2),
+            Instruction::GetProperty, // a.length
+            Instruction::LoadLocal(0),
+            Instruction::PushConst(0),
+            Instruction::GetProperty, // a[1]
+        ],
+        functions: Vec::new(),
+    };
+    let constants = vec![Value::Number(1.0), Value::Number(2.0), Value::String("length".to_string())];
+    exec.execute(&bytecode, &constants);
+    assert_eq!(exec.stack.values, vec![Value::Number(2.0), Value::Number(2.0)]);
+}
+
+#[test]
+fn test_object_handles_alias_same_properties() {
+    // let a = {}; let b = a; b.x = 5; a.x
+    let mut exec = Executor::new();
+    let bytecode = Bytecode {
+        instructions: vec![
+            Instruction::NewObject,
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::StoreLocal(1),
+            Instruction::LoadLocal(1),
+            Instruction::PushConst(0),
+            Instruction::PushConst(1),
+            Instruction::SetProperty,
+            Instruction::LoadLocal(0),
+            Instruction::PushConst(0),
+            Instruction::GetProperty,
+        ],
+        functions: Vec::new(),
+    };
+    let constants = vec![Value::String("x".to_string()), Value::Number(5.0)];
+    exec.execute(&bytecode, &constants);
+    assert_eq!(exec.stack.values.last(), Some(&Value::Number(5.0)));
+}
+
+#[test]
+fn test_delete_property_removes_own_property() {
+    // let o = {x: 5}; delete o.x; o.x
+    let mut exec = Executor::new();
+    let bytecode = Bytecode {
+        instructions: vec![
+            Instruction::NewObject,
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::PushConst(0),
+            Instruction::PushConst(1),
+            Instruction::SetProperty, // o.x = 5
+            Instruction::LoadLocal(0),
+            Instruction::PushConst(0),
+            Instruction::DeleteProperty, // delete o.x
+            Instruction::LoadLocal(0),
+            Instruction::PushConst(0),
+            Instruction::GetProperty, // o.x
+        ],
+        functions: Vec::new(),
+    };
+    let constants = vec![Value::String("x".to_string()), Value::Number(5.0)];
+    exec.execute(&bytecode, &constants);
+    assert_eq!(exec.stack.values, vec![Value::Boolean(true), Value::Undefined]);
+}
+
+#[test]
+fn test_delete_array_element_leaves_hole() {
+    // let a = [1, 2]; delete a[0]; a.length
+    let mut exec = Executor::new();
+    let bytecode = Bytecode {
+        instructions: vec![
+            Instruction::PushConst(0),
+            Instruction::PushConst(1),
+            Instruction::NewArray(2),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::PushConst(2),
+            Instruction::DeleteProperty, // delete a[0]
+            Instruction::LoadLocal(0),
+            Instruction::PushConst(2),
+            Instruction::GetProperty, // a[0]
+            Instruction::LoadLocal(0),
+            Instruction::PushConst(3),
+            Instruction::GetProperty, // a.length
+        ],
+        functions: Vec::new(),
+    };
+    let constants = vec![
+        Value::Number(1.0),
+        Value::Number(2.0),
+        Value::Number(0.0),
+        Value::String("length".to_string()),
+    ];
+    exec.execute(&bytecode, &constants);
+    assert_eq!(
+        exec.stack.values,
+        vec![Value::Boolean(true), Value::Undefined, Value::Number(2.0)]
+    );
+}
+
+#[test]
+fn test_new_array_takes_only_its_elements_in_push_order() {
+    use v8_vm::heap::HeapEntry;
+    let mut exec = Executor::new();
+    let bytecode = Bytecode {
+        instructions: vec![
+            Instruction::PushConst(0), // fica abaixo do array
+            Instruction::PushConst(1),
+            Instruction::PushConst(2),
+            Instruction::PushConst(3),
+            Instruction::NewArray(3),
+        ],
+        functions: Vec::new(),
+    };
+    let constants = vec![
+        Value::String("antes".to_string()),
+        Value::Number(1.0),
+        Value::Number(2.0),
+        Value::Number(3.0),
+    ];
+    exec.execute(&bytecode, &constants);
+    assert_eq!(exec.stack.values.len(), 2);
+    assert_eq!(exec.stack.values[0], Value::String("antes".to_string()));
+    match exec.stack.values.last() {
+        Some(Value::Array(handle)) => match exec.heap.get(*handle) {
+            Some(HeapEntry::Array(elements)) => assert_eq!(
+                elements,
+                &vec![Value::Number(1.0), Value::Number(2.0), Value::Number(3.0)]
+            ),
+            _ => panic!("Array não encontrado no heap"),
+        },
+        other => panic!("Esperava um array, obtive {:?}", other),
+    }
+}
+
+#[test]
+fn test_array_length_assignment_truncates_or_throws() {
+    let mut exec = Executor::new();
+    let program = |length: usize| Bytecode {
+        instructions: vec![
+            Instruction::PushConst(0),
+            Instruction::PushConst(0),
+            Instruction::PushConst(0),
+            Instruction::NewArray(3),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::PushConst(1),
+            Instruction::PushConst(length), // a.length = ...
+            Instruction::SetProperty,
+            Instruction::LoadLocal(0),
+            Instruction::PushConst(1),
+            Instruction::GetProperty,
+        ],
+        functions: Vec::new(),
+    };
+    let constants = vec![
+        Value::Number(7.0),
+        Value::String("length".to_string()),
+        Value::Number(1.0),
+        Value::Number(-1.0),
+    ];
+    exec.execute(&program(2), &constants);
+    assert_eq!(exec.stack.values.last(), Some(&Value::Number(1.0)));
+    assert!(exec.exception.is_none());
+
+    let mut exec = Executor::new();
+    exec.execute(&program(3), &constants);
+    match &exec.exception {
+        Some((Value::String(message), _)) => assert!(message.starts_with("RangeError")),
+        other => panic!("Esperava RangeError, obtive {:?}", other),
+    }
+}
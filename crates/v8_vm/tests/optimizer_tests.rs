@@ -0,0 +1,201 @@
+use v8_vm::bytecode::{Bytecode, FunctionInfo};
+use v8_vm::executor::Executor;
+use v8_vm::instructions::Instruction;
+use v8_vm::optimizer::Optimizer;
+use v8_vm::value::Value;
+
+fn run(bytecode: &Bytecode, constants: &[Value]) -> Vec<Value> {
+    let mut exec = Executor::new();
+    exec.execute(bytecode, constants);
+    exec.stack.values
+}
+
+/// Otimiza `instructions`, confere que o resultado da execução não muda e devolve as novas instruções
+fn optimize(instructions: Vec<Instruction>, constants: &[Value]) -> Vec<Instruction> {
+    let original = Bytecode::new(instructions);
+    let mut bytecode = original.clone();
+    Optimizer::new().optimize(&mut bytecode, constants);
+    assert_eq!(run(&bytecode, constants), run(&original, constants));
+    bytecode.instructions
+}
+
+#[test]
+fn test_store_then_load_becomes_dup_store() {
+    let constants = vec![Value::Number(5.0)];
+    let instructions = optimize(
+        vec![
+            Instruction::PushConst(0),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::StoreLocal(1),
+            Instruction::LoadLocal(0), // Outra variável: fica como está
+            Instruction::LoadLocal(1),
+            Instruction::Add,
+        ],
+        &constants,
+    );
+    assert_eq!(
+        instructions,
+        vec![
+            Instruction::PushConst(0),
+            Instruction::Dup,
+            Instruction::StoreLocal(0),
+            Instruction::StoreLocal(1),
+            Instruction::LoadLocal(0),
+            Instruction::LoadLocal(1),
+            Instruction::Add,
+        ]
+    );
+}
+
+#[test]
+fn test_load_then_pop_is_removed() {
+    let constants = vec![Value::Number(1.0)];
+    let instructions = optimize(
+        vec![
+            Instruction::PushConst(0),
+            Instruction::LoadLocal(3),
+            Instruction::Pop,
+            Instruction::LoadLocal(0),
+            Instruction::LoadLocal(2),
+            Instruction::Pop,
+            Instruction::Pop,
+        ],
+        &constants,
+    );
+    assert_eq!(instructions, vec![Instruction::PushConst(0)]);
+}
+
+#[test]
+fn test_not_of_boolean_constant_is_folded() {
+    let constants = vec![Value::Boolean(true), Value::Number(0.0)];
+    let instructions = optimize(
+        vec![
+            Instruction::PushConst(0),
+            Instruction::Not,
+            Instruction::PushFalse,
+            Instruction::Not,
+            Instruction::PushTrue,
+            Instruction::Not,
+            Instruction::Not,
+            Instruction::PushConst(1), // Não é booleano
+            Instruction::Not,
+        ],
+        &constants,
+    );
+    assert_eq!(
+        instructions,
+        vec![
+            Instruction::PushFalse,
+            Instruction::PushTrue,
+            Instruction::PushTrue, // !!true, dobrado em duas passadas
+            Instruction::PushConst(1),
+            Instruction::Not,
+        ]
+    );
+}
+
+#[test]
+fn test_jump_targets_follow_rewritten_code() {
+    // Conta de 3 até 0; o laço começa no endereço 2
+    let constants = vec![Value::Number(3.0)];
+    let instructions = optimize(
+        vec![
+            Instruction::PushConst(0),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0), // Destino do Jump: o par anterior fica como está
+            Instruction::JumpIfFalse(10),
+            Instruction::LoadLocal(1),
+            Instruction::Pop,
+            Instruction::LoadLocal(0),
+            Instruction::Dec,
+            Instruction::StoreLocal(0),
+            Instruction::Jump(2),
+            Instruction::PushTrue,
+            Instruction::Not,
+            Instruction::LoadLocal(0),
+        ],
+        &constants,
+    );
+    assert_eq!(
+        instructions,
+        vec![
+            Instruction::PushConst(0),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::JumpIfFalse(8),
+            Instruction::LoadLocal(0),
+            Instruction::Dec,
+            Instruction::StoreLocal(0),
+            Instruction::Jump(2),
+            Instruction::PushFalse,
+            Instruction::LoadLocal(0),
+        ]
+    );
+}
+
+#[test]
+fn test_jump_to_removed_pair_lands_after_it() {
+    let constants = vec![Value::Number(1.0), Value::Number(2.0)];
+    let instructions = optimize(
+        vec![
+            Instruction::PushFalse,
+            Instruction::JumpIfFalse(4),
+            Instruction::PushConst(0),
+            Instruction::Pop,
+            Instruction::LoadLocal(0),
+            Instruction::Pop,
+            Instruction::PushConst(1),
+        ],
+        &constants,
+    );
+    assert_eq!(
+        instructions,
+        vec![
+            Instruction::PushFalse,
+            Instruction::JumpIfFalse(4),
+            Instruction::PushConst(0),
+            Instruction::Pop,
+            Instruction::PushConst(1),
+        ]
+    );
+}
+
+#[test]
+fn test_pair_split_by_jump_target_is_kept() {
+    let constants = vec![Value::Number(7.0), Value::Number(8.0)];
+    let original = vec![
+        Instruction::PushConst(0),
+        Instruction::StoreLocal(0),
+        Instruction::PushConst(1),
+        Instruction::PushFalse,
+        Instruction::JumpIfFalse(6),
+        Instruction::StoreLocal(0),
+        Instruction::LoadLocal(0), // Destino do salto, que não passa pelo StoreLocal
+    ];
+    assert_eq!(optimize(original.clone(), &constants), original);
+    assert_eq!(run(&Bytecode::new(original), &constants), vec![Value::Number(8.0), Value::Number(7.0)]);
+}
+
+#[test]
+fn test_function_bodies_are_optimized() {
+    let function = FunctionInfo {
+        name: "f".to_string(),
+        bytecode: Bytecode::new(vec![
+            Instruction::LoadArg(0),
+            Instruction::StoreLocal(0),
+            Instruction::LoadLocal(0),
+            Instruction::Return,
+        ]),
+        arg_count: 1,
+        local_count: 1,
+        captures: Vec::new(),
+        is_async: false,
+    };
+    let mut bytecode = Bytecode::with_functions(vec![Instruction::CreateFunction(0)], vec![function]);
+    Optimizer::new().optimize(&mut bytecode, &[]);
+    assert_eq!(
+        bytecode.functions[0].bytecode.instructions,
+        vec![Instruction::LoadArg(0), Instruction::Dup, Instruction::StoreLocal(0), Instruction::Return]
+    );
+}